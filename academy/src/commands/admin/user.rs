@@ -1,10 +1,30 @@
+use std::collections::BTreeSet;
+
 use academy_config::Config;
-use academy_core_user_contracts::user::{UserCreateCommand, UserService};
+use academy_core_audit_contracts::log::AuditLogService;
+use academy_core_mfa_contracts::disable::MfaDisableService;
+use academy_core_session_contracts::session::SessionService;
+use academy_core_user_contracts::{
+    avatar::UserAvatarService,
+    deletion::UserDeletionService,
+    export::UserExportService,
+    update::UserUpdateService,
+    user::{UserCreateCommand, UserListQuery, UserService},
+};
 use academy_di::Provide;
-use academy_persistence_contracts::{Database as _, Transaction};
-use anyhow::Context;
-use clap::Subcommand;
+use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
+    pagination::{Pagination, PaginationCursor, PaginationSlice, SortDirection},
+    role::Role,
+    user::{UserComposite, UserFilter, UserSort, UserSortBy},
+};
+use academy_persistence_contracts::{user::UserRepository, Database as _, Transaction};
+use academy_shared_contracts::password::PasswordService;
+use anyhow::{anyhow, bail, Context};
+use chrono::{DateTime, Utc};
+use clap::{Args, Subcommand, ValueEnum};
 use tracing::info;
+use uuid::Uuid;
 
 use crate::{
     cache, database, email,
@@ -16,12 +36,45 @@ use crate::{
 
 #[derive(Debug, Subcommand)]
 pub enum AdminUserCommand {
+    /// List user accounts
+    #[command(aliases(["ls", "l"]))]
+    List {
+        #[command(flatten)]
+        filter: UserFilterArgs,
+        /// The maximum number of users to return
+        #[arg(long, default_value_t = 100)]
+        limit: u64,
+        /// The number of users to skip
+        #[arg(long, default_value_t = 0)]
+        offset: u64,
+        /// Continue after the last user of a previous page
+        #[arg(long, conflicts_with = "offset")]
+        cursor: Option<PaginationCursor>,
+        /// The field to sort the users by
+        #[arg(long, value_enum, default_value_t = SortBy::CreatedAt)]
+        sort: SortBy,
+        /// Sort in descending order
+        #[arg(long)]
+        desc: bool,
+        /// Print the users as json
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show a user account
+    #[command(aliases(["s", "get", "g"]))]
+    Show {
+        /// The id, name or email address of the user
+        user: String,
+        /// Print the user as json
+        #[arg(long)]
+        json: bool,
+    },
     /// Create a new user account
     #[command(aliases(["c", "new", "n", "+"]))]
     Create {
-        /// Grant the new user admin privileges
-        #[arg(long)]
-        admin: bool,
+        /// Assign a role to the new user (can be specified multiple times)
+        #[arg(long = "role", value_enum)]
+        roles: Vec<RoleArg>,
         /// Disable the new user account
         #[arg(long)]
         disabled: bool,
@@ -35,21 +88,315 @@ pub enum AdminUserCommand {
         /// The password of the new user
         password: String,
     },
+    /// Update a user account
+    #[command(aliases(["u", "edit", "e"]))]
+    Update {
+        /// The id, name or email address of the user
+        user: String,
+        /// Enable the user account
+        #[arg(long, conflicts_with = "disable")]
+        enable: bool,
+        /// Disable the user account and log out the user
+        #[arg(long)]
+        disable: bool,
+        /// Assign a role to the user (can be specified multiple times)
+        #[arg(long, value_enum)]
+        add_role: Vec<RoleArg>,
+        /// Remove a role from the user (can be specified multiple times)
+        #[arg(long, value_enum)]
+        remove_role: Vec<RoleArg>,
+        /// Mark the email address of the user as verified
+        #[arg(long)]
+        verify_email: bool,
+        /// Set a new password for the user
+        #[arg(long)]
+        password: Option<String>,
+        /// Print the updated user as json
+        #[arg(long)]
+        json: bool,
+    },
+    /// Schedule a user account for deletion after the configured grace period
+    #[command(aliases(["d", "rm", "-"]))]
+    Delete {
+        /// The id, name or email address of the user
+        user: String,
+    },
+    /// Cancel the pending deletion of a user account
+    Restore {
+        /// The id, name or email address of the user
+        user: String,
+    },
+    /// Permanently delete a user account right away
+    Purge {
+        /// The id, name or email address of the user
+        user: String,
+    },
+    /// Log out a user by deleting all of their sessions
+    Logout {
+        /// The id, name or email address of the user
+        user: String,
+    },
+    /// Disable multi-factor authentication for a user
+    ResetMfa {
+        /// The id, name or email address of the user
+        user: String,
+    },
+    /// Export all data stored about a user
+    Export {
+        /// The id, name or email address of the user
+        user: String,
+        /// Send a download link to the user's email address instead of
+        /// printing the export
+        #[arg(long)]
+        email: bool,
+    },
+    /// Show how many accounts still use outdated password hashes
+    PasswordHashes {
+        /// Print the report as json
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct UserFilterArgs {
+    /// Only return users whose name contains the given string
+    #[arg(long)]
+    name: Option<String>,
+    /// Only return users whose email address contains the given string
+    #[arg(long)]
+    email: Option<String>,
+    /// Only return enabled (true) or disabled (false) users
+    #[arg(long)]
+    enabled: Option<bool>,
+    /// Only return users with the given role
+    #[arg(long, value_enum)]
+    role: Option<RoleArg>,
+    /// Only return users with (true) or without (false) MFA enabled
+    #[arg(long)]
+    mfa_enabled: Option<bool>,
+    /// Only return users with a verified (true) or unverified (false) email
+    /// address
+    #[arg(long)]
+    email_verified: Option<bool>,
+    /// Only return users who have (true) or have not (false) subscribed to the
+    /// newsletter
+    #[arg(long)]
+    newsletter: Option<bool>,
+    /// Only return users who can (true) or cannot (false) log in with a
+    /// password
+    #[arg(long)]
+    password_login: Option<bool>,
+    /// Only return users who can (true) or cannot (false) log in via OAuth2
+    #[arg(long)]
+    oauth2_login: Option<bool>,
+    /// Only return users with a link to the given OAuth2 provider
+    #[arg(long)]
+    oauth2_provider: Option<String>,
+    /// Only return users with complete (true) or incomplete (false) invoice
+    /// info
+    #[arg(long)]
+    can_receive_coins: Option<bool>,
+    /// Only return users created at or after the given time (RFC 3339)
+    #[arg(long)]
+    created_after: Option<DateTime<Utc>>,
+    /// Only return users created before the given time (RFC 3339)
+    #[arg(long)]
+    created_before: Option<DateTime<Utc>>,
+    /// Only return users who last logged in at or after the given time (RFC
+    /// 3339)
+    #[arg(long)]
+    last_login_after: Option<DateTime<Utc>>,
+    /// Only return users who last logged in before the given time (RFC 3339)
+    #[arg(long)]
+    last_login_before: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SortBy {
+    CreatedAt,
+    Name,
+    LastLogin,
+}
+
+impl From<SortBy> for UserSortBy {
+    fn from(value: SortBy) -> Self {
+        match value {
+            SortBy::CreatedAt => Self::CreatedAt,
+            SortBy::Name => Self::Name,
+            SortBy::LastLogin => Self::LastLogin,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RoleArg {
+    Superuser,
+    Support,
+    Moderator,
+    Billing,
+}
+
+impl From<RoleArg> for Role {
+    fn from(value: RoleArg) -> Self {
+        match value {
+            RoleArg::Superuser => Self::Superuser,
+            RoleArg::Support => Self::Support,
+            RoleArg::Moderator => Self::Moderator,
+            RoleArg::Billing => Self::Billing,
+        }
+    }
 }
 
 impl AdminUserCommand {
     pub async fn invoke(self, config: Config) -> anyhow::Result<()> {
         match self {
+            AdminUserCommand::List {
+                filter,
+                limit,
+                offset,
+                cursor,
+                sort,
+                desc,
+                json,
+            } => {
+                let sort = UserSort {
+                    by: sort.into(),
+                    direction: match desc {
+                        false => SortDirection::Asc,
+                        true => SortDirection::Desc,
+                    },
+                };
+                list(config, filter, sort, limit, offset, cursor, json).await
+            }
+            AdminUserCommand::Show { user, json } => show(config, user, json).await,
             AdminUserCommand::Create {
-                admin,
+                roles,
                 name,
                 email,
                 password,
                 disabled,
                 verified,
-            } => create(config, name, email, password, admin, !disabled, verified).await,
+            } => {
+                let roles = roles.into_iter().map(Into::into).collect();
+                create(config, name, email, password, roles, !disabled, verified).await
+            }
+            AdminUserCommand::Update {
+                user,
+                enable,
+                disable,
+                add_role,
+                remove_role,
+                verify_email,
+                password,
+                json,
+            } => {
+                let enabled = (enable || disable).then_some(enable);
+                let roles = RolesUpdate {
+                    add: add_role.into_iter().map(Into::into).collect(),
+                    remove: remove_role.into_iter().map(Into::into).collect(),
+                };
+                update(config, user, enabled, roles, verify_email, password, json).await
+            }
+            AdminUserCommand::Delete { user } => delete(config, user).await,
+            AdminUserCommand::Restore { user } => restore(config, user).await,
+            AdminUserCommand::Purge { user } => purge(config, user).await,
+            AdminUserCommand::Logout { user } => logout(config, user).await,
+            AdminUserCommand::ResetMfa { user } => reset_mfa(config, user).await,
+            AdminUserCommand::Export { user, email } => export(config, user, email).await,
+            AdminUserCommand::PasswordHashes { json } => password_hashes(config, json).await,
+        }
+    }
+}
+
+async fn list(
+    config: Config,
+    filter: UserFilterArgs,
+    sort: UserSort,
+    limit: u64,
+    offset: u64,
+    cursor: Option<PaginationCursor>,
+    json: bool,
+) -> anyhow::Result<()> {
+    let limit = limit.try_into()?;
+    let pagination = match cursor {
+        Some(cursor) => Pagination::Cursor {
+            limit,
+            after: Some(cursor),
+        },
+        None => PaginationSlice { limit, offset }.into(),
+    };
+
+    let mut provider = connect(&config).await?;
+
+    let db: Database = provider.provide();
+    let mut txn = db.begin_transaction().await?;
+
+    let user_service: types::User = provider.provide();
+    let result = user_service
+        .list(
+            &mut txn,
+            UserListQuery {
+                pagination,
+                filter: UserFilter {
+                    name: filter.name.map(TryInto::try_into).transpose()?,
+                    email: filter.email.map(TryInto::try_into).transpose()?,
+                    enabled: filter.enabled,
+                    role: filter.role.map(Into::into),
+                    mfa_enabled: filter.mfa_enabled,
+                    email_verified: filter.email_verified,
+                    newsletter: filter.newsletter,
+                    password_login: filter.password_login,
+                    oauth2_login: filter.oauth2_login,
+                    oauth2_provider: filter.oauth2_provider.map(Into::into),
+                    can_receive_coins: filter.can_receive_coins,
+                    created_after: filter.created_after,
+                    created_before: filter.created_before,
+                    last_login_after: filter.last_login_after,
+                    last_login_before: filter.last_login_before,
+                },
+                sort,
+            },
+        )
+        .await
+        .context("Failed to list users")?
+        .context("The user referenced by the cursor no longer exists")?;
+
+    if json {
+        let users = result
+            .user_composites
+            .iter()
+            .map(user_json)
+            .collect::<Vec<_>>();
+        let output = serde_json::json!({
+            "total": result.total,
+            "users": users,
+            "next_cursor": result.next_cursor.map(|x| x.to_string()),
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        print_table(&result.user_composites);
+        println!(
+            "\nShowing {} of {} users",
+            result.user_composites.len(),
+            result.total
+        );
+        if let Some(next_cursor) = result.next_cursor {
+            println!("Next page: --cursor {next_cursor}");
         }
     }
+
+    Ok(())
+}
+
+async fn show(config: Config, user: String, json: bool) -> anyhow::Result<()> {
+    let mut provider = connect(&config).await?;
+
+    let db: Database = provider.provide();
+    let mut txn = db.begin_transaction().await?;
+
+    let user_composite = get_user(&mut provider, &mut txn, &user).await?;
+    print_user(&user_composite, json)
 }
 
 async fn create(
@@ -57,15 +404,11 @@ async fn create(
     name: String,
     email: String,
     password: String,
-    admin: bool,
+    roles: BTreeSet<Role>,
     enabled: bool,
     email_verified: bool,
 ) -> anyhow::Result<()> {
-    let database = database::connect(&config.database).await?;
-    let cache = cache::connect(&config.cache).await?;
-    let email_service = email::connect(&config.email).await?;
-    let config_provider = ConfigProvider::new(&config)?;
-    let mut provider = Provider::new(config_provider, database, cache, email_service);
+    let mut provider = connect(&config).await?;
 
     let db: Database = provider.provide();
     let mut txn = db.begin_transaction().await?;
@@ -79,7 +422,7 @@ async fn create(
                 display_name: name.try_into()?,
                 email: email.parse()?,
                 password: Some(password.try_into()?),
-                admin,
+                roles,
                 enabled,
                 email_verified,
                 oauth2_registration: None,
@@ -94,3 +437,536 @@ async fn create(
 
     Ok(())
 }
+
+struct RolesUpdate {
+    add: BTreeSet<Role>,
+    remove: BTreeSet<Role>,
+}
+
+async fn update(
+    config: Config,
+    user: String,
+    enabled: Option<bool>,
+    roles: RolesUpdate,
+    verify_email: bool,
+    password: Option<String>,
+    json: bool,
+) -> anyhow::Result<()> {
+    let mut provider = connect(&config).await?;
+
+    let db: Database = provider.provide();
+    let mut txn = db.begin_transaction().await?;
+
+    let mut user_composite = get_user(&mut provider, &mut txn, &user).await?;
+    let user_id = user_composite.user.id;
+    let password = password.map(TryInto::try_into).transpose()?;
+
+    let user_update: types::UserUpdate = provider.provide();
+    let mut updated = false;
+
+    if let Some(enabled) = enabled.filter(|&x| x != user_composite.user.enabled) {
+        user_update
+            .update_enabled(&mut txn, user_id, enabled)
+            .await
+            .context("Failed to update enabled status")?;
+        user_composite.user.enabled = enabled;
+        updated = true;
+    }
+
+    let roles = user_composite
+        .user
+        .roles
+        .union(&roles.add)
+        .filter(|role| !roles.remove.contains(role))
+        .copied()
+        .collect::<BTreeSet<_>>();
+    if roles != user_composite.user.roles {
+        user_update
+            .update_roles(&mut txn, user_id, roles.clone())
+            .await
+            .context("Failed to update roles")?;
+        user_composite.user.roles = roles;
+        updated = true;
+    }
+
+    if verify_email && !user_composite.user.email_verified {
+        if user_composite.user.email.is_none() {
+            bail!("The user does not have an email address");
+        }
+        user_update
+            .update_email(&mut txn, user_id, &user_composite.user.email, true)
+            .await
+            .context("Failed to update email verification status")?;
+        user_composite.user.email_verified = true;
+        updated = true;
+    }
+
+    if let Some(password) = password {
+        user_update
+            .update_password(&mut txn, user_id, password)
+            .await
+            .context("Failed to update password")?;
+        user_composite.details.password_login = true;
+        updated = true;
+    }
+
+    if updated {
+        let audit_log: types::AuditLog = provider.provide();
+        audit_log
+            .record(
+                &mut txn,
+                None,
+                user_id,
+                AuditEventKind::UserUpdated,
+                ClientInfo::default(),
+            )
+            .await
+            .context("Failed to record audit event")?;
+    }
+
+    txn.commit().await?;
+
+    if updated {
+        info!("User has been updated");
+    } else {
+        info!("Nothing to update");
+    }
+
+    print_user(&user_composite, json)
+}
+
+async fn delete(config: Config, user: String) -> anyhow::Result<()> {
+    let mut provider = connect(&config).await?;
+
+    let db: Database = provider.provide();
+    let mut txn = db.begin_transaction().await?;
+
+    let user_composite = get_user(&mut provider, &mut txn, &user).await?;
+    let user_id = user_composite.user.id;
+
+    if user_composite.user.deleted_at.is_some() {
+        bail!("The user has already been scheduled for deletion");
+    }
+
+    let user_deletion: types::UserDeletion = provider.provide();
+    user_deletion
+        .schedule(&mut txn, &user_composite)
+        .await
+        .context("Failed to schedule user deletion")?;
+
+    let audit_log: types::AuditLog = provider.provide();
+    audit_log
+        .record(
+            &mut txn,
+            None,
+            user_id,
+            AuditEventKind::UserDeleted,
+            ClientInfo::default(),
+        )
+        .await
+        .context("Failed to record audit event")?;
+
+    txn.commit().await?;
+
+    user_deletion.send_deleted_email(&user_composite).await;
+
+    info!(
+        "User {} has been scheduled for deletion",
+        user_composite.user.name.as_str()
+    );
+
+    Ok(())
+}
+
+async fn restore(config: Config, user: String) -> anyhow::Result<()> {
+    let mut provider = connect(&config).await?;
+
+    let db: Database = provider.provide();
+    let mut txn = db.begin_transaction().await?;
+
+    let user_composite = get_user(&mut provider, &mut txn, &user).await?;
+    let user_id = user_composite.user.id;
+
+    if user_composite.user.deleted_at.is_none() {
+        bail!("The user has not been scheduled for deletion");
+    }
+
+    let user_deletion: types::UserDeletion = provider.provide();
+    user_deletion
+        .restore(&mut txn, user_id)
+        .await
+        .context("Failed to restore user")?;
+
+    let audit_log: types::AuditLog = provider.provide();
+    audit_log
+        .record(
+            &mut txn,
+            None,
+            user_id,
+            AuditEventKind::UserRestored,
+            ClientInfo::default(),
+        )
+        .await
+        .context("Failed to record audit event")?;
+
+    txn.commit().await?;
+
+    info!(
+        "User {} has been restored",
+        user_composite.user.name.as_str()
+    );
+
+    Ok(())
+}
+
+async fn purge(config: Config, user: String) -> anyhow::Result<()> {
+    let mut provider = connect(&config).await?;
+
+    let db: Database = provider.provide();
+    let mut txn = db.begin_transaction().await?;
+
+    let user_composite = get_user(&mut provider, &mut txn, &user).await?;
+    let user_id = user_composite.user.id;
+
+    let session: types::Session = provider.provide();
+    session
+        .delete_by_user(&mut txn, user_id)
+        .await
+        .context("Failed to log out user")?;
+
+    let user_repo: types::UserRepo = provider.provide();
+    user_repo
+        .delete(&mut txn, user_id)
+        .await
+        .context("Failed to delete user from database")?;
+
+    txn.commit().await?;
+
+    if let Some(avatar) = &user_composite.profile.avatar {
+        let user_avatar: types::UserAvatar = provider.provide();
+        user_avatar
+            .remove(avatar)
+            .await
+            .context("Failed to remove avatar")?;
+    }
+
+    info!(
+        "User {} has been deleted permanently",
+        user_composite.user.name.as_str()
+    );
+
+    Ok(())
+}
+
+async fn logout(config: Config, user: String) -> anyhow::Result<()> {
+    let mut provider = connect(&config).await?;
+
+    let db: Database = provider.provide();
+    let mut txn = db.begin_transaction().await?;
+
+    let user_composite = get_user(&mut provider, &mut txn, &user).await?;
+
+    let session: types::Session = provider.provide();
+    session
+        .delete_by_user(&mut txn, user_composite.user.id)
+        .await
+        .context("Failed to log out user")?;
+
+    txn.commit().await?;
+
+    info!(
+        "User {} has been logged out",
+        user_composite.user.name.as_str()
+    );
+
+    Ok(())
+}
+
+async fn reset_mfa(config: Config, user: String) -> anyhow::Result<()> {
+    let mut provider = connect(&config).await?;
+
+    let db: Database = provider.provide();
+    let mut txn = db.begin_transaction().await?;
+
+    let user_composite = get_user(&mut provider, &mut txn, &user).await?;
+    let user_id = user_composite.user.id;
+
+    if !user_composite.details.mfa_enabled {
+        bail!("MFA is not enabled for this user");
+    }
+
+    let mfa_disable: types::MfaDisable = provider.provide();
+    mfa_disable
+        .disable(&mut txn, user_id)
+        .await
+        .context("Failed to disable MFA")?;
+
+    let audit_log: types::AuditLog = provider.provide();
+    audit_log
+        .record(
+            &mut txn,
+            None,
+            user_id,
+            AuditEventKind::MfaDisabled,
+            ClientInfo::default(),
+        )
+        .await
+        .context("Failed to record audit event")?;
+
+    txn.commit().await?;
+
+    info!(
+        "MFA has been disabled for user {}",
+        user_composite.user.name.as_str()
+    );
+
+    Ok(())
+}
+
+async fn export(config: Config, user: String, email: bool) -> anyhow::Result<()> {
+    let mut provider = connect(&config).await?;
+
+    let db: Database = provider.provide();
+    let mut txn = db.begin_transaction().await?;
+
+    let user_composite = get_user(&mut provider, &mut txn, &user).await?;
+    let recipient = user_composite
+        .user
+        .email
+        .clone()
+        .map(|email| email.with_name(user_composite.profile.display_name.clone().into_inner()));
+    let name = user_composite.user.name.clone();
+
+    let user_export: types::UserExport = provider.provide();
+    let export = user_export
+        .export(&mut txn, user_composite)
+        .await
+        .context("Failed to export user")?;
+
+    if !email {
+        println!("{}", serde_json::to_string_pretty(&export)?);
+        return Ok(());
+    }
+
+    let Some(recipient) = recipient else {
+        bail!("The user does not have an email address");
+    };
+
+    user_export
+        .send_download_link(recipient, &export)
+        .await
+        .context("Failed to send download link")?;
+
+    info!("A download link has been sent to user {}", name.as_str());
+
+    Ok(())
+}
+
+async fn password_hashes(config: Config, json: bool) -> anyhow::Result<()> {
+    let mut provider = connect(&config).await?;
+
+    let db: Database = provider.provide();
+    let mut txn = db.begin_transaction().await?;
+
+    let user_repo: types::UserRepo = provider.provide();
+    let password: types::Password = provider.provide();
+    let counts = user_repo
+        .count_password_hashes_by_parameters(&mut txn)
+        .await
+        .context("Failed to count password hashes")?
+        .into_iter()
+        .map(|(parameters, count)| {
+            let legacy = password.needs_rehash(&parameters);
+            (parameters, count, legacy)
+        })
+        .collect::<Vec<_>>();
+
+    let total = counts.iter().map(|&(_, count, _)| count).sum::<u64>();
+    let legacy = counts
+        .iter()
+        .filter(|&&(_, _, legacy)| legacy)
+        .map(|&(_, count, _)| count)
+        .sum::<u64>();
+
+    if json {
+        let hashes = counts
+            .iter()
+            .map(|(parameters, count, legacy)| {
+                serde_json::json!({
+                    "parameters": parameters,
+                    "count": count,
+                    "legacy": legacy,
+                })
+            })
+            .collect::<Vec<_>>();
+        let output = serde_json::json!({
+            "total": total,
+            "legacy": legacy,
+            "hashes": hashes,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        let width = counts
+            .iter()
+            .map(|(parameters, _, _)| parameters.chars().count())
+            .fold("PARAMETERS".len(), usize::max);
+        println!("{:width$}  {:>8}  STATUS", "PARAMETERS", "ACCOUNTS");
+        for (parameters, count, legacy) in &counts {
+            let status = if *legacy { "legacy" } else { "current" };
+            println!("{parameters:width$}  {count:>8}  {status}");
+        }
+        println!("\n{legacy} of {total} accounts use legacy password hashes");
+    }
+
+    Ok(())
+}
+
+async fn connect(config: &Config) -> anyhow::Result<Provider> {
+    let database = database::connect(&config.database).await?;
+    let cache = cache::connect(&config.cache).await?;
+    let email_service = email::connect(&config.email).await?;
+    let config_provider = ConfigProvider::new(config)?;
+    Ok(Provider::new(
+        config_provider,
+        database,
+        cache,
+        email_service,
+    ))
+}
+
+/// Find a user by id, name or email address
+async fn get_user(
+    provider: &mut Provider,
+    txn: &mut <Database as academy_persistence_contracts::Database>::Transaction,
+    user: &str,
+) -> anyhow::Result<UserComposite> {
+    let user_repo: types::UserRepo = provider.provide();
+
+    let user_composite = if let Ok(id) = user.parse::<Uuid>() {
+        user_repo.get_composite(txn, id.into()).await
+    } else if user.contains('@') {
+        user_repo.get_composite_by_email(txn, &user.parse()?).await
+    } else {
+        user_repo
+            .get_composite_by_name(txn, &user.to_owned().try_into()?)
+            .await
+    }
+    .context("Failed to get user from database")?;
+
+    user_composite.ok_or_else(|| anyhow!("User {user} does not exist"))
+}
+
+fn print_user(user_composite: &UserComposite, json: bool) -> anyhow::Result<()> {
+    if json {
+        let output = user_json(user_composite);
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        print_table(std::slice::from_ref(user_composite));
+    }
+
+    Ok(())
+}
+
+fn user_json(user_composite: &UserComposite) -> serde_json::Value {
+    let UserComposite {
+        user,
+        profile,
+        details,
+        ..
+    } = user_composite;
+
+    serde_json::json!({
+        "id": user.id,
+        "name": user.name,
+        "display_name": profile.display_name,
+        "email": user.email,
+        "email_verified": user.email_verified,
+        "created_at": user.created_at,
+        "last_login": user.last_login,
+        "enabled": user.enabled,
+        "roles": user.roles,
+        "newsletter": user.newsletter,
+        "deleted_at": user.deleted_at,
+        "mfa_enabled": details.mfa_enabled,
+        "password_login": details.password_login,
+        "oauth2_login": details.oauth2_login,
+    })
+}
+
+fn print_table(user_composites: &[UserComposite]) {
+    const HEADER: [&str; 7] = [
+        "ID",
+        "NAME",
+        "EMAIL",
+        "FLAGS",
+        "MFA",
+        "CREATED AT",
+        "LAST LOGIN",
+    ];
+
+    let rows = user_composites
+        .iter()
+        .map(|UserComposite { user, details, .. }| {
+            let flags = [
+                (user.enabled, "enabled"),
+                (!user.enabled, "disabled"),
+                (user.email_verified, "verified"),
+                (user.newsletter, "newsletter"),
+                (user.deleted_at.is_some(), "deleted"),
+            ]
+            .into_iter()
+            .filter_map(|(set, flag)| set.then_some(flag))
+            .chain(user.roles.iter().map(|&role| format_role(role)))
+            .collect::<Vec<_>>()
+            .join(",");
+
+            [
+                user.id.to_string(),
+                user.name.to_string(),
+                user.email
+                    .as_ref()
+                    .map(|email| email.as_str().to_owned())
+                    .unwrap_or_default(),
+                flags,
+                if details.mfa_enabled { "yes" } else { "no" }.into(),
+                format_time(user.created_at),
+                user.last_login.map(format_time).unwrap_or_default(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let widths = rows.iter().fold(HEADER.map(str::len), |mut widths, row| {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+        widths
+    });
+
+    let print_row = |row: &[&str]| {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    };
+
+    print_row(&HEADER);
+    for row in &rows {
+        print_row(&row.each_ref().map(String::as_str));
+    }
+}
+
+fn format_role(role: Role) -> &'static str {
+    match role {
+        Role::Superuser => "superuser",
+        Role::Support => "support",
+        Role::Moderator => "moderator",
+        Role::Billing => "billing",
+    }
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S").to_string()
+}
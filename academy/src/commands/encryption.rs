@@ -0,0 +1,48 @@
+use academy_config::Config;
+use academy_shared_impl::encryption::generate_encryption_key;
+use clap::Subcommand;
+
+#[derive(Debug, Subcommand)]
+pub enum EncryptionCommand {
+    /// Generate a new key for encrypting data at rest
+    GenerateKey,
+    /// Generate a new encryption key and print the updated `[encryption]`
+    /// config section
+    ///
+    /// The new key is used to encrypt new data, while the previous keys are
+    /// still used to decrypt existing data. Unlike JWT keys, previous
+    /// encryption keys must be kept until all data encrypted with them has
+    /// been replaced.
+    RotateKeys,
+}
+
+impl EncryptionCommand {
+    pub async fn invoke(self, config: Config) -> anyhow::Result<()> {
+        match self {
+            EncryptionCommand::GenerateKey => generate_key(),
+            EncryptionCommand::RotateKeys => rotate_keys(&config),
+        }
+    }
+}
+
+fn generate_key() -> anyhow::Result<()> {
+    let key = generate_encryption_key()?;
+    println!("{key}");
+
+    Ok(())
+}
+
+fn rotate_keys(config: &Config) -> anyhow::Result<()> {
+    let keys = std::iter::once(generate_encryption_key()?)
+        .chain(config.encryption.keys.iter().cloned())
+        .collect::<Vec<_>>();
+
+    println!("[encryption]");
+    println!("keys = [");
+    for key in &keys {
+        println!("    {key:?},");
+    }
+    println!("]");
+
+    Ok(())
+}
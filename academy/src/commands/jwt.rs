@@ -3,6 +3,7 @@ use std::time::Duration;
 use academy_config::Config;
 use academy_di::Provide;
 use academy_shared_contracts::jwt::JwtService;
+use academy_shared_impl::jwt::{generate_jwt_key, jwt_key_id};
 use anyhow::Context;
 use clap::Subcommand;
 
@@ -18,12 +19,25 @@ pub enum JwtCommand {
         /// The JSON data to sign
         data: String,
     },
+    /// Generate a new key for signing JWTs
+    GenerateKey,
+    /// Generate a new signing key and print the updated `[jwt]` config section
+    ///
+    /// The new key is used to sign new JWTs, while the previous keys are still
+    /// accepted for verification until they are removed from the config.
+    RotateKeys {
+        /// The number of previous keys to keep
+        #[arg(long, default_value = "1")]
+        keep: usize,
+    },
 }
 
 impl JwtCommand {
     pub async fn invoke(self, config: Config) -> anyhow::Result<()> {
         match self {
             JwtCommand::Sign { ttl, data } => sign(&config, &data, Duration::from_secs(ttl)),
+            JwtCommand::GenerateKey => generate_key(),
+            JwtCommand::RotateKeys { keep } => rotate_keys(&config, keep),
         }
     }
 }
@@ -39,3 +53,27 @@ fn sign(config: &Config, data: &str, ttl: Duration) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+fn generate_key() -> anyhow::Result<()> {
+    let key = generate_jwt_key()?;
+    let kid = jwt_key_id(&key)?;
+    println!("key: {key}");
+    println!("kid: {kid}");
+
+    Ok(())
+}
+
+fn rotate_keys(config: &Config, keep: usize) -> anyhow::Result<()> {
+    let keys = std::iter::once(generate_jwt_key()?)
+        .chain(config.jwt.keys.iter().take(keep).cloned())
+        .collect::<Vec<_>>();
+
+    println!("[jwt]");
+    println!("keys = [");
+    for key in &keys {
+        println!("    {key:?}, # {}", jwt_key_id(key)?);
+    }
+    println!("]");
+
+    Ok(())
+}
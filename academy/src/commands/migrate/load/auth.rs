@@ -1,6 +1,7 @@
 use academy_models::{
     mfa::{MfaRecoveryCodeHash, TotpDevice, TotpSecret},
     oauth2::{OAuth2Link, OAuth2UserInfo},
+    role::Role,
     session::{Session, SessionRefreshTokenHash},
     user::{User, UserInvoiceInfo, UserProfile},
     Sha256Hash,
@@ -74,8 +75,9 @@ pub async fn load(db: PostgresDatabase, auth: DbConnection) -> anyhow::Result<()
                 .map(|x| x.and_utc())
                 .filter(|x| x.timestamp() != 0),
             enabled,
-            admin,
+            roles: admin.then_some(Role::Superuser).into_iter().collect(),
             newsletter: newsletter.unwrap_or(false),
+            deleted_at: None,
         };
 
         let profile = UserProfile {
@@ -85,6 +87,8 @@ pub async fn load(db: PostgresDatabase, auth: DbConnection) -> anyhow::Result<()
                 .transpose()?
                 .unwrap_or_default(),
             tags: serde_json::from_str(tags.as_deref().unwrap_or("[]"))?,
+            visibility: Default::default(),
+            avatar: None,
         };
 
         let invoice_info = UserInvoiceInfo {
@@ -112,8 +116,10 @@ pub async fn load(db: PostgresDatabase, auth: DbConnection) -> anyhow::Result<()
             let totp_device = TotpDevice {
                 id: Uuid::new_v4().into(),
                 user_id: user.id,
+                name: "Authenticator".try_into()?,
                 enabled: mfa_enabled.unwrap_or(false),
                 created_at: user.created_at,
+                last_used_at: None,
             };
             let secret = TotpSecret::try_new(
                 base32::decode(base32::Alphabet::Rfc4648 { padding: false }, &mfa_secret)
@@ -156,6 +162,7 @@ pub async fn load(db: PostgresDatabase, auth: DbConnection) -> anyhow::Result<()
             id: id.parse::<Uuid>()?.into(),
             user_id: user_id.parse::<Uuid>()?.into(),
             device_name: Some(device_name.try_into()?),
+            impersonator_id: None,
             created_at: last_update.and_utc(),
             updated_at: last_update.and_utc(),
         };
@@ -1,5 +1,6 @@
 pub mod admin;
 pub mod email;
+pub mod encryption;
 pub mod jwt;
 pub mod migrate;
 pub mod serve;
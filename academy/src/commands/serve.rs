@@ -6,7 +6,9 @@ use academy_persistence_contracts::Database;
 use tracing::info;
 
 use crate::{
-    cache, database, email,
+    cache,
+    commands::tasks::{scheduler, TaskRunner},
+    database, email,
     environment::{types::RestServer, ConfigProvider, Provider},
 };
 
@@ -33,6 +35,13 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
     let email = email::connect(&config.email).await?;
     email.ping().await?;
 
+    let schedule = scheduler::parse(&config.tasks)?;
+    if !schedule.is_empty() {
+        info!("Starting task scheduler");
+        let runner = TaskRunner::new(&config, database.clone(), cache.clone(), email.clone())?;
+        tokio::spawn(scheduler::run(runner, schedule));
+    }
+
     let config_provider = ConfigProvider::new(&config)?;
     let mut provider = Provider::new(config_provider, database, cache, email);
 
@@ -1,40 +1,360 @@
+use std::time::Duration;
+
+use academy_cache_contracts::CacheService;
 use academy_config::Config;
-use academy_persistence_contracts::{session::SessionRepository, Database, Transaction};
-use academy_persistence_postgres::session::PostgresSessionRepository;
+use academy_core_user_contracts::{
+    avatar::UserAvatarService, email_confirmation::UserEmailConfirmationService,
+    export::UserExportService,
+};
+use academy_di::Provide;
+use academy_models::user::UserAvatar;
+use academy_persistence_contracts::{
+    mfa::MfaRepository, session::SessionRepository, user::UserRepository, Database as _,
+    Transaction,
+};
 use anyhow::Context;
 use chrono::Utc;
-use clap::Subcommand;
-use tracing::info;
+use clap::{Args, ValueEnum};
+use tracing::{error, info};
 
-use crate::database;
+use crate::{
+    cache, database, email,
+    environment::{
+        types::{self, Database},
+        ConfigProvider, Provider,
+    },
+};
 
-#[derive(Debug, Subcommand)]
-pub enum TaskCommand {
-    /// Remove expired records from the database.
-    PruneDatabase,
+pub mod scheduler;
+
+#[derive(Debug, Args)]
+pub struct TaskCommand {
+    /// The task to invoke
+    #[arg(value_enum)]
+    task: Task,
+    /// Only report what the task would do without changing anything
+    #[arg(long)]
+    dry_run: bool,
 }
 
 impl TaskCommand {
     pub async fn invoke(self, config: Config) -> anyhow::Result<()> {
-        match self {
-            TaskCommand::PruneDatabase => prune_database(config).await,
-        }
+        let database = database::connect(&config.database).await?;
+        let cache = cache::connect(&config.cache).await?;
+        let email = email::connect(&config.email).await?;
+        let runner = TaskRunner::new(&config, database, cache, email)?;
+
+        let report = runner.run(self.task, self.dry_run).await?;
+        info!("{report}");
+
+        Ok(())
     }
 }
 
-async fn prune_database(config: Config) -> anyhow::Result<()> {
-    let db = database::connect(&config.database).await?;
-    let mut txn = db.begin_transaction().await?;
+/// Maintenance tasks that can be invoked manually or by the scheduler.
+///
+/// Stale OAuth2 registrations do not need a task of their own: they are only
+/// stored in the cache and expire after `oauth2.registration_token_ttl`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Task {
+    /// Remove expired records from the database.
+    PruneDatabase,
+    /// Delete accounts whose email address has not been verified within the
+    /// configured grace period. Accounts that have ever verified an email
+    /// address or have logged in since their registration are kept.
+    PruneUnverifiedUsers,
+    /// Permanently delete accounts whose deletion grace period has expired.
+    PruneDeletedUsers,
+    /// Delete TOTP devices that have been created but never enabled.
+    PruneTotpDevices,
+    /// Remind users with an unverified email address to verify it.
+    SendVerificationReminders,
+    /// Send download links for requested user data exports.
+    SendUserExports,
+}
+
+/// Executes maintenance tasks using its own set of services.
+pub struct TaskRunner {
+    database: Database,
+    cache: types::Cache,
+    user_email_confirmation: types::UserEmailConfirmation,
+    user_export: types::UserExport,
+    user_avatar: types::UserAvatar,
+    session_repo: types::SessionRepo,
+    user_repo: types::UserRepo,
+    mfa_repo: types::MfaRepo,
+    refresh_token_ttl: Duration,
+    unverified_user_ttl: Duration,
+    deletion_grace_period: Duration,
+    verification_reminder_after: Duration,
+    totp_device_setup_ttl: Duration,
+}
+
+impl TaskRunner {
+    pub fn new(
+        config: &Config,
+        database: Database,
+        cache: types::Cache,
+        email: types::Email,
+    ) -> anyhow::Result<Self> {
+        let config_provider = ConfigProvider::new(config)?;
+        let mut provider = Provider::new(config_provider, database, cache, email);
+
+        Ok(Self {
+            database: provider.provide(),
+            cache: provider.provide(),
+            user_email_confirmation: provider.provide(),
+            user_export: provider.provide(),
+            user_avatar: provider.provide(),
+            session_repo: provider.provide(),
+            user_repo: provider.provide(),
+            mfa_repo: provider.provide(),
+            refresh_token_ttl: config.session.refresh_token_ttl.into(),
+            unverified_user_ttl: config.tasks.unverified_user_ttl.into(),
+            deletion_grace_period: config.user.deletion_grace_period.into(),
+            verification_reminder_after: config.tasks.verification_reminder_after.into(),
+            totp_device_setup_ttl: config.tasks.totp_device_setup_ttl.into(),
+        })
+    }
+
+    /// Run the given task and return a short report of what it did.
+    ///
+    /// In dry run mode all changes are rolled back and no emails are sent.
+    pub async fn run(&self, task: Task, dry_run: bool) -> anyhow::Result<String> {
+        let mut txn = self.database.begin_transaction().await?;
+
+        // avatars of deleted users can only be removed after the transaction has been
+        // committed
+        let mut avatars = Vec::new();
+
+        let report = match task {
+            Task::PruneDatabase => self.prune_database(&mut txn, dry_run).await?,
+            Task::PruneUnverifiedUsers => {
+                self.prune_unverified_users(&mut txn, &mut avatars, dry_run)
+                    .await?
+            }
+            Task::PruneDeletedUsers => {
+                self.prune_deleted_users(&mut txn, &mut avatars, dry_run)
+                    .await?
+            }
+            Task::PruneTotpDevices => self.prune_totp_devices(&mut txn, dry_run).await?,
+            Task::SendVerificationReminders => {
+                self.send_verification_reminders(&mut txn, dry_run).await?
+            }
+            Task::SendUserExports => self.send_user_exports(&mut txn, dry_run).await?,
+        };
+
+        if dry_run {
+            txn.rollback().await?;
+        } else {
+            txn.commit().await?;
+            self.remove_avatars(avatars).await;
+        }
+
+        Ok(report)
+    }
+
+    async fn prune_database(&self, txn: &mut Txn, dry_run: bool) -> anyhow::Result<String> {
+        let pruned = self
+            .session_repo
+            .delete_by_updated_at(txn, Utc::now() - self.refresh_token_ttl)
+            .await
+            .context("Failed to prune sessions")?;
 
-    let session_repo = PostgresSessionRepository;
-    let now = Utc::now();
-    let pruned = session_repo
-        .delete_by_updated_at(&mut txn, now - config.session.refresh_token_ttl.0)
-        .await
-        .context("Failed to prune sessions")?;
-    info!("Pruned {pruned} expired sessions.");
+        Ok(format!(
+            "{} {pruned} expired sessions.",
+            verb(dry_run, "Pruned", "Would prune")
+        ))
+    }
+
+    async fn prune_unverified_users(
+        &self,
+        txn: &mut Txn,
+        avatars: &mut Vec<UserAvatar>,
+        dry_run: bool,
+    ) -> anyhow::Result<String> {
+        let created_at = Utc::now() - self.unverified_user_ttl;
+
+        let users = self
+            .user_repo
+            .list_unverified_composites(txn, created_at)
+            .await
+            .context("Failed to get unverified users from database")?;
+
+        let pruned = self
+            .user_repo
+            .delete_unverified(txn, created_at)
+            .await
+            .context("Failed to prune unverified users")?;
+
+        avatars.extend(users.into_iter().filter_map(|x| x.profile.avatar));
+
+        Ok(format!(
+            "{} {pruned} users with an unverified email address.",
+            verb(dry_run, "Deleted", "Would delete")
+        ))
+    }
 
-    txn.commit().await?;
+    async fn prune_deleted_users(
+        &self,
+        txn: &mut Txn,
+        avatars: &mut Vec<UserAvatar>,
+        dry_run: bool,
+    ) -> anyhow::Result<String> {
+        let deleted_at = Utc::now() - self.deletion_grace_period;
 
-    Ok(())
+        let users = self
+            .user_repo
+            .list_deleted_composites(txn, deleted_at)
+            .await
+            .context("Failed to get deleted users from database")?;
+
+        let pruned = self
+            .user_repo
+            .delete_by_deleted_at(txn, deleted_at)
+            .await
+            .context("Failed to prune deleted users")?;
+
+        avatars.extend(users.into_iter().filter_map(|x| x.profile.avatar));
+
+        Ok(format!(
+            "{} {pruned} users whose deletion grace period has expired.",
+            verb(dry_run, "Deleted", "Would delete")
+        ))
+    }
+
+    async fn prune_totp_devices(&self, txn: &mut Txn, dry_run: bool) -> anyhow::Result<String> {
+        let pruned = self
+            .mfa_repo
+            .delete_disabled_totp_devices_by_created_at(
+                txn,
+                Utc::now() - self.totp_device_setup_ttl,
+            )
+            .await
+            .context("Failed to prune totp devices")?;
+
+        Ok(format!(
+            "{} {pruned} totp devices that have never been enabled.",
+            verb(dry_run, "Deleted", "Would delete")
+        ))
+    }
+
+    async fn send_verification_reminders(
+        &self,
+        txn: &mut Txn,
+        dry_run: bool,
+    ) -> anyhow::Result<String> {
+        let users = self
+            .user_repo
+            .list_unverified_composites(txn, Utc::now() - self.verification_reminder_after)
+            .await
+            .context("Failed to get unverified users from database")?;
+
+        let mut sent = 0;
+        for user_composite in users {
+            let Some(email) = user_composite.user.email else {
+                continue;
+            };
+
+            // remember reminded users until their accounts are pruned
+            let cache_key = format!(
+                "verification_reminder:{}",
+                user_composite.user.id.hyphenated()
+            );
+            if self
+                .cache
+                .get::<bool>(&cache_key)
+                .await
+                .context("Failed to get verification reminder from cache")?
+                .is_some()
+            {
+                continue;
+            }
+
+            if !dry_run {
+                self.user_email_confirmation
+                    .request_verification(
+                        email.with_name(user_composite.profile.display_name.into_inner()),
+                    )
+                    .await
+                    .context("Failed to send verification reminder")?;
+                self.cache
+                    .set(&cache_key, &true, Some(self.unverified_user_ttl))
+                    .await
+                    .context("Failed to save verification reminder in cache")?;
+            }
+
+            sent += 1;
+        }
+
+        Ok(format!(
+            "{} {sent} verification reminders.",
+            verb(dry_run, "Sent", "Would send")
+        ))
+    }
+
+    async fn send_user_exports(&self, txn: &mut Txn, dry_run: bool) -> anyhow::Result<String> {
+        let mut sent = 0;
+        while let Some(user_id) = self
+            .user_repo
+            .take_export_request(txn)
+            .await
+            .context("Failed to get export request from database")?
+        {
+            let Some(user_composite) = self
+                .user_repo
+                .get_composite(txn, user_id)
+                .await
+                .context("Failed to get user from database")?
+            else {
+                continue;
+            };
+
+            let Some(email) = user_composite.user.email.clone() else {
+                continue;
+            };
+            let email = email.with_name(user_composite.profile.display_name.clone().into_inner());
+
+            if !dry_run {
+                let export = self
+                    .user_export
+                    .export(txn, user_composite)
+                    .await
+                    .context("Failed to export user")?;
+                self.user_export
+                    .send_download_link(email, &export)
+                    .await
+                    .context("Failed to send user export")?;
+            }
+
+            sent += 1;
+        }
+
+        Ok(format!(
+            "{} {sent} user data exports.",
+            verb(dry_run, "Sent", "Would send")
+        ))
+    }
+
+    /// Remove the given uploaded avatars of deleted users.
+    ///
+    /// The users have already been deleted at this point, so failures are only
+    /// logged.
+    async fn remove_avatars(&self, avatars: Vec<UserAvatar>) {
+        for avatar in avatars {
+            if let Err(err) = self.user_avatar.remove(&avatar).await {
+                error!(avatar_id = %avatar.id.hyphenated(), "Failed to remove avatar: {err:#}");
+            }
+        }
+    }
+}
+
+type Txn = <Database as academy_persistence_contracts::Database>::Transaction;
+
+fn verb(dry_run: bool, done: &'static str, planned: &'static str) -> &'static str {
+    if dry_run {
+        planned
+    } else {
+        done
+    }
 }
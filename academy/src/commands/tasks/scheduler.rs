@@ -0,0 +1,51 @@
+use academy_config::TasksConfig;
+use anyhow::anyhow;
+use chrono::Utc;
+use clap::ValueEnum;
+use cron::Schedule;
+use tracing::{error, info};
+
+use super::{Task, TaskRunner};
+
+/// Parse the configured task schedule.
+pub fn parse(config: &TasksConfig) -> anyhow::Result<Vec<(Task, Schedule)>> {
+    config
+        .schedule
+        .iter()
+        .map(|(name, schedule)| {
+            let task = Task::from_str(name, false)
+                .map_err(|_| anyhow!("Unknown task {name:?} in task schedule"))?;
+            Ok((task, schedule.clone()))
+        })
+        .collect()
+}
+
+/// Run the scheduled tasks forever.
+///
+/// Tasks that are due at the same time are executed sequentially. Failures are
+/// logged and do not affect subsequent runs.
+pub async fn run(runner: TaskRunner, schedule: Vec<(Task, Schedule)>) {
+    loop {
+        let now = Utc::now();
+        let Some(next) = schedule
+            .iter()
+            .filter_map(|(_, schedule)| schedule.after(&now).next())
+            .min()
+        else {
+            return;
+        };
+
+        tokio::time::sleep((next - now).to_std().unwrap_or_default()).await;
+
+        let due = schedule
+            .iter()
+            .filter(|(_, schedule)| schedule.after(&now).next() == Some(next))
+            .map(|&(task, _)| task);
+        for task in due {
+            match runner.run(task, false).await {
+                Ok(report) => info!(?task, "{report}"),
+                Err(err) => error!(?task, "Failed to run scheduled task: {err:?}"),
+            }
+        }
+    }
+}
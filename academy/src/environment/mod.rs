@@ -1,8 +1,8 @@
 use std::{collections::HashMap, sync::Arc};
 
-use academy_api_rest::{RestServerConfig, RestServerRealIpConfig};
+use academy_api_rest::{RestServerConfig, RestServerRateLimitConfig, RestServerRealIpConfig};
 use academy_auth_impl::AuthServiceConfig;
-use academy_config::Config;
+use academy_config::{Config, HttpRateLimitPolicyConfig, OAuth2ProviderConfig};
 use academy_core_contact_impl::ContactFeatureConfig;
 use academy_core_health_impl::HealthFeatureConfig;
 use academy_core_oauth2_impl::OAuth2FeatureConfig;
@@ -13,12 +13,20 @@ use academy_extern_impl::{
     internal::InternalApiServiceConfig, recaptcha::RecaptchaApiServiceConfig,
     vat::VatApiServiceConfig,
 };
-use academy_models::oauth2::OAuth2Provider;
+use academy_models::oauth2::{OAuth2Endpoints, OAuth2Provider, OAuth2ProviderEndpoints};
+use academy_shared_contracts::rate_limit::RateLimitPolicy;
 use academy_shared_impl::{
+    breached_password::BreachedPasswordServiceConfig,
     captcha::{CaptchaServiceConfig, RecaptchaCaptchaServiceConfig},
+    encryption::EncryptionServiceConfig,
     jwt::JwtServiceConfig,
+    password::PasswordServiceConfig,
+    throttle::ThrottleServiceConfig,
     totp::TotpServiceConfig,
+    webauthn::WebauthnServiceConfig,
 };
+use academy_storage_local::LocalStorageConfig;
+use anyhow::bail;
 use types::{Cache, Database, Email};
 
 pub mod types;
@@ -38,11 +46,19 @@ provider! {
             RecaptchaApiServiceConfig,
             VatApiServiceConfig,
 
+            // Storage
+            LocalStorageConfig,
+
             // Shared
+            BreachedPasswordServiceConfig,
             CaptchaServiceConfig,
+            EncryptionServiceConfig,
             JwtServiceConfig,
             OAuth2FeatureConfig,
+            PasswordServiceConfig,
+            ThrottleServiceConfig,
             TotpServiceConfig,
+            WebauthnServiceConfig,
 
             // Auth
             AuthServiceConfig,
@@ -79,11 +95,19 @@ provider! {
         recaptcha_api_service_config: RecaptchaApiServiceConfig,
         vat_api_service_config: VatApiServiceConfig,
 
+        // Storage
+        local_storage_config: LocalStorageConfig,
+
         // Shared
+        breached_password_service_config: BreachedPasswordServiceConfig,
         captcha_service_config: CaptchaServiceConfig,
+        encryption_service_config: EncryptionServiceConfig,
         jwt_service_config: JwtServiceConfig,
         oauth2_service_config: OAuth2FeatureConfig,
+        password_service_config: PasswordServiceConfig,
+        throttle_service_config: ThrottleServiceConfig,
         totp_service_config: TotpServiceConfig,
+        webauthn_service_config: WebauthnServiceConfig,
 
         // Auth
         auth_service_config: AuthServiceConfig,
@@ -108,6 +132,20 @@ impl ConfigProvider {
                 })
             }),
             allowed_origins: Arc::new(config.http.allowed_origins.clone()),
+            rate_limit_config: config.http.rate_limit.as_ref().map(|rate_limit_config| {
+                let policy = |policy: &HttpRateLimitPolicyConfig| RateLimitPolicy {
+                    capacity: policy.capacity,
+                    period: policy.period.into(),
+                };
+                Arc::new(RestServerRateLimitConfig {
+                    default: policy(&rate_limit_config.default),
+                    groups: rate_limit_config
+                        .groups
+                        .iter()
+                        .map(|(group, p)| (group.clone(), policy(p)))
+                        .collect(),
+                })
+            }),
         };
 
         // Extern
@@ -125,7 +163,18 @@ impl ConfigProvider {
         let vat_api_service_config =
             VatApiServiceConfig::new(config.vat.validate_endpoint_override.clone());
 
+        // Storage
+        let local_storage_config = LocalStorageConfig {
+            path: config.storage.path.clone().into(),
+            public_url: config.storage.public_url.clone().into(),
+        };
+
         // Shared
+        let breached_password_service_config = match &config.user.password_breach_list_path {
+            Some(path) => BreachedPasswordServiceConfig::Directory(path.clone().into()),
+            None => BreachedPasswordServiceConfig::Bundled,
+        };
+
         let captcha_service_config = match config.recaptcha.as_ref() {
             Some(recaptcha) => CaptchaServiceConfig::Recaptcha(RecaptchaCaptchaServiceConfig {
                 sitekey: recaptcha.sitekey.clone().into(),
@@ -135,7 +184,15 @@ impl ConfigProvider {
             None => CaptchaServiceConfig::Disabled,
         };
 
-        let jwt_service_config = JwtServiceConfig::new(&config.jwt.secret)?;
+        let jwt_service_config = JwtServiceConfig::new(&config.jwt.keys)?;
+
+        let encryption_service_config = EncryptionServiceConfig::new(&config.encryption.keys)?;
+
+        let password_service_config = PasswordServiceConfig::new(
+            config.password.argon2_memory_cost,
+            config.password.argon2_time_cost,
+            config.password.argon2_parallelism,
+        )?;
 
         let oauth2_service_config = OAuth2FeatureConfig {
             registration_token_ttl: config
@@ -143,39 +200,93 @@ impl ConfigProvider {
                 .as_ref()
                 .map(|oauth2| oauth2.registration_token_ttl.0)
                 .unwrap_or_default(),
+            authorization_ttl: config
+                .oauth2
+                .as_ref()
+                .map(|oauth2| oauth2.authorization_ttl.0)
+                .unwrap_or_default(),
             providers: config
                 .oauth2
                 .iter()
                 .flat_map(|oauth2| oauth2.providers.iter())
                 .map(|(id, provider)| {
-                    (
+                    let endpoints = match provider {
+                        OAuth2ProviderConfig {
+                            issuer_url: Some(issuer_url),
+                            auth_url: None,
+                            token_url: None,
+                            userinfo_url: None,
+                            ..
+                        } => OAuth2ProviderEndpoints::OpenIdConnect {
+                            issuer_url: issuer_url.clone(),
+                        },
+                        OAuth2ProviderConfig {
+                            issuer_url: None,
+                            auth_url: Some(auth_url),
+                            token_url: Some(token_url),
+                            userinfo_url: Some(userinfo_url),
+                            revocation_url,
+                            ..
+                        } => OAuth2ProviderEndpoints::OAuth2(Box::new(OAuth2Endpoints {
+                            auth_url: auth_url.clone(),
+                            token_url: token_url.clone(),
+                            userinfo_url: userinfo_url.clone(),
+                            revocation_url: revocation_url.clone(),
+                        })),
+                        _ => bail!(
+                            "OAuth2 provider {id:?} must either set issuer_url or all of \
+                             auth_url, token_url and userinfo_url"
+                        ),
+                    };
+
+                    Ok((
                         id.clone().into(),
                         OAuth2Provider {
                             name: provider.name.clone().into(),
                             client_id: provider.client_id.clone(),
                             client_secret: Some(provider.client_secret.clone().into()),
-                            auth_url: provider.auth_url.clone(),
-                            token_url: provider.token_url.clone(),
-                            userinfo_url: provider.userinfo_url.clone(),
+                            endpoints,
                             userinfo_id_key: provider.userinfo_id_key.clone(),
                             userinfo_name_key: provider.userinfo_name_key.clone(),
+                            userinfo_email_key: provider.userinfo_email_key.clone(),
+                            userinfo_email_verified_key: provider
+                                .userinfo_email_verified_key
+                                .clone(),
+                            userinfo_display_name_key: provider.userinfo_display_name_key.clone(),
+                            userinfo_avatar_url_key: provider.userinfo_avatar_url_key.clone(),
                             scopes: provider.scopes.clone(),
                         },
-                    )
+                    ))
                 })
-                .collect::<HashMap<_, _>>()
+                .collect::<anyhow::Result<HashMap<_, _>>>()?
                 .into(),
         };
 
+        let throttle_service_config = ThrottleServiceConfig {
+            window: config.session.throttle_window.into(),
+            account_limit: config.session.throttle_account_limit,
+            ip_limit: config.session.throttle_ip_limit,
+            lockout: config.session.throttle_lockout.into(),
+            lockout_max: config.session.throttle_lockout_max.into(),
+        };
+
         let totp_service_config = TotpServiceConfig {
             secret_length: config.totp.secret_length,
         };
 
+        let webauthn_service_config = WebauthnServiceConfig {
+            rp_id: config.webauthn.rp_id.clone().into(),
+            rp_name: config.webauthn.rp_name.clone().into(),
+            origins: config.webauthn.origins.clone().into(),
+            timeout: config.webauthn.timeout.into(),
+        };
+
         // Auth
         let auth_service_config = AuthServiceConfig {
             access_token_ttl: config.session.access_token_ttl.into(),
             refresh_token_ttl: config.session.refresh_token_ttl.into(),
             refresh_token_length: config.session.refresh_token_length,
+            impersonation_session_ttl: config.session.impersonation_session_ttl.into(),
             internal_token_ttl: config.internal.jwt_ttl.into(),
         };
 
@@ -192,6 +303,11 @@ impl ConfigProvider {
 
         let session_feature_config = SessionFeatureConfig {
             login_fails_before_captcha: config.session.login_fails_before_captcha,
+            login_link_code_ttl: config.session.login_link_code_ttl.into(),
+            login_link_redirect_url: config.session.login_link_redirect_url.clone().into(),
+            refresh_token_history: config.session.refresh_token_history,
+            refresh_token_reuse_email: config.session.refresh_token_reuse_email,
+            known_device_ttl: config.session.refresh_token_ttl.into(),
         };
 
         let user_feature_config = UserFeatureConfig {
@@ -206,6 +322,21 @@ impl ConfigProvider {
                 .clone()
                 .into(),
             newsletter_subscription_verification_code_ttl: config.user.newsletter_code_ttl.into(),
+            export_async_threshold: config.user.export_async_threshold,
+            export_redirect_url: config.user.export_redirect_url.clone().into(),
+            export_download_ttl: config.user.export_download_ttl.into(),
+            deletion_grace_period: config.user.deletion_grace_period.into(),
+            restore_redirect_url: config.user.restore_redirect_url.clone().into(),
+            avatar_max_size: config.user.avatar_max_size,
+            avatar_dimensions: config.user.avatar_min_dimension..=config.user.avatar_max_dimension,
+            password_min_length: config.user.password_min_length,
+            password_required_character_classes: config
+                .user
+                .password_required_character_classes
+                .clone()
+                .into(),
+            password_min_strength: config.user.password_min_strength,
+            password_breach_check: config.user.password_breach_check,
         };
 
         Ok(Self {
@@ -219,9 +350,17 @@ impl ConfigProvider {
             recaptcha_api_service_config,
             vat_api_service_config,
 
+            // Storage
+            local_storage_config,
+
             // Shared
+            breached_password_service_config,
+            encryption_service_config,
             jwt_service_config,
+            password_service_config,
+            throttle_service_config,
             totp_service_config,
+            webauthn_service_config,
             captcha_service_config,
             oauth2_service_config,
 
@@ -5,24 +5,29 @@ use academy_auth_impl::{
     refresh_token::AuthRefreshTokenServiceImpl, AuthServiceImpl,
 };
 use academy_cache_valkey::ValkeyCache;
+use academy_core_audit_impl::{log::AuditLogServiceImpl, AuditFeatureServiceImpl};
 use academy_core_config_impl::ConfigFeatureServiceImpl;
 use academy_core_contact_impl::ContactFeatureServiceImpl;
 use academy_core_health_impl::HealthFeatureServiceImpl;
 use academy_core_internal_impl::InternalServiceImpl;
 use academy_core_mfa_impl::{
     authenticate::MfaAuthenticateServiceImpl, disable::MfaDisableServiceImpl,
-    recovery::MfaRecoveryServiceImpl, totp_device::MfaTotpDeviceServiceImpl, MfaFeatureServiceImpl,
+    recovery::MfaRecoveryServiceImpl, totp_device::MfaTotpDeviceServiceImpl,
+    webauthn::MfaWebauthnServiceImpl, MfaFeatureServiceImpl,
 };
 use academy_core_oauth2_impl::{
     link::OAuth2LinkServiceImpl, login::OAuth2LoginServiceImpl,
-    registration::OAuth2RegistrationServiceImpl, OAuth2FeatureServiceImpl,
+    registration::OAuth2RegistrationServiceImpl, token::OAuth2TokenServiceImpl,
+    OAuth2FeatureServiceImpl,
 };
 use academy_core_session_impl::{
-    failed_auth_count::SessionFailedAuthCountServiceImpl, session::SessionServiceImpl,
-    SessionFeatureServiceImpl,
+    failed_auth_count::SessionFailedAuthCountServiceImpl, login_link::SessionLoginLinkServiceImpl,
+    session::SessionServiceImpl, SessionFeatureServiceImpl,
 };
 use academy_core_user_impl::{
-    email_confirmation::UserEmailConfirmationServiceImpl, update::UserUpdateServiceImpl,
+    avatar::UserAvatarServiceImpl, deletion::UserDeletionServiceImpl,
+    email_confirmation::UserEmailConfirmationServiceImpl, export::UserExportServiceImpl,
+    password_policy::UserPasswordPolicyServiceImpl, update::UserUpdateServiceImpl,
     user::UserServiceImpl, UserFeatureServiceImpl,
 };
 use academy_email_impl::{template::TemplateEmailServiceImpl, EmailServiceImpl};
@@ -31,14 +36,18 @@ use academy_extern_impl::{
     recaptcha::RecaptchaApiServiceImpl, vat::VatApiServiceImpl,
 };
 use academy_persistence_postgres::{
-    mfa::PostgresMfaRepository, oauth2::PostgresOAuth2Repository,
+    audit::PostgresAuditRepository, mfa::PostgresMfaRepository, oauth2::PostgresOAuth2Repository,
     session::PostgresSessionRepository, user::PostgresUserRepository, PostgresDatabase,
 };
 use academy_shared_impl::{
-    captcha::CaptchaServiceImpl, hash::HashServiceImpl, id::IdServiceImpl, jwt::JwtServiceImpl,
-    password::PasswordServiceImpl, secret::SecretServiceImpl, time::TimeServiceImpl,
-    totp::TotpServiceImpl,
+    breached_password::BreachedPasswordServiceImpl, captcha::CaptchaServiceImpl,
+    encryption::EncryptionServiceImpl, hash::HashServiceImpl, id::IdServiceImpl,
+    image::ImageServiceImpl, jwt::JwtServiceImpl, password::PasswordServiceImpl,
+    password_strength::PasswordStrengthServiceImpl, rate_limit::RateLimitServiceImpl,
+    secret::SecretServiceImpl, throttle::ThrottleServiceImpl, time::TimeServiceImpl,
+    totp::TotpServiceImpl, webauthn::WebauthnServiceImpl,
 };
+use academy_storage_local::LocalStorage;
 use academy_templates_impl::TemplateServiceImpl;
 
 // API
@@ -50,7 +59,10 @@ pub type RestServer = academy_api_rest::RestServer<
     ContactFeature,
     MfaFeature,
     OAuth2Feature,
+    AuditFeature,
     Internal,
+    RateLimit,
+    AuthAccessToken,
 >;
 
 // Persistence
@@ -59,13 +71,16 @@ pub type Database = PostgresDatabase;
 // Cache
 pub type Cache = ValkeyCache;
 
+// Storage
+pub type Storage = LocalStorage;
+
 // Email
 pub type Email = EmailServiceImpl;
 pub type TemplateEmail = TemplateEmailServiceImpl<Email, Template>;
 
 // Extern
 pub type RecaptchaApi = RecaptchaApiServiceImpl;
-pub type OAuth2Api = OAuth2ApiServiceImpl;
+pub type OAuth2Api = OAuth2ApiServiceImpl<Time>;
 pub type InternalApi = InternalApiServiceImpl<AuthInternal>;
 pub type VatApi = VatApiServiceImpl;
 
@@ -73,20 +88,28 @@ pub type VatApi = VatApiServiceImpl;
 pub type Template = TemplateServiceImpl;
 
 // Shared
+pub type BreachedPassword = BreachedPasswordServiceImpl;
 pub type Captcha = CaptchaServiceImpl<RecaptchaApi>;
+pub type Encryption = EncryptionServiceImpl;
 pub type Hash = HashServiceImpl;
 pub type Id = IdServiceImpl;
+pub type Image = ImageServiceImpl;
 pub type Jwt = JwtServiceImpl<Time>;
 pub type Password = PasswordServiceImpl;
+pub type PasswordStrength = PasswordStrengthServiceImpl;
+pub type RateLimit = RateLimitServiceImpl<Time, Cache>;
 pub type Secret = SecretServiceImpl;
 pub type Time = TimeServiceImpl;
+pub type Throttle = ThrottleServiceImpl<Time, Hash, Cache>;
 pub type Totp = TotpServiceImpl<Secret, Time, Hash, Cache>;
+pub type Webauthn = WebauthnServiceImpl<Secret>;
 
 // Repositories
 pub type SessionRepo = PostgresSessionRepository;
 pub type UserRepo = PostgresUserRepository;
 pub type MfaRepo = PostgresMfaRepository;
 pub type OAuth2Repo = PostgresOAuth2Repository;
+pub type AuditRepo = PostgresAuditRepository;
 
 // Auth
 pub type Auth =
@@ -98,40 +121,76 @@ pub type AuthInternal = AuthInternalServiceImpl<Jwt>;
 // Core
 pub type HealthFeature = HealthFeatureServiceImpl<Time, Database, Cache, Email>;
 
-pub type ConfigFeature = ConfigFeatureServiceImpl<Captcha>;
+pub type ConfigFeature = ConfigFeatureServiceImpl<Captcha, Jwt>;
 
 pub type UserFeature = UserFeatureServiceImpl<
     Database,
     Auth,
     Captcha,
+    Throttle,
     VatApi,
     InternalApi,
     User,
     UserEmailConfirmation,
     UserUpdate,
+    UserExport,
+    UserDeletion,
+    UserAvatar,
+    UserPasswordPolicy,
     Session,
     OAuth2Registration,
     UserRepo,
+    AuditLog,
 >;
 pub type User = UserServiceImpl<Id, Time, Password, UserRepo, OAuth2Link>;
 pub type UserEmailConfirmation =
     UserEmailConfirmationServiceImpl<Auth, Secret, TemplateEmail, Cache, Password, UserRepo>;
-pub type UserUpdate = UserUpdateServiceImpl<Auth, Time, Password, Session, UserRepo>;
+pub type UserUpdate = UserUpdateServiceImpl<Auth, Time, Password, TemplateEmail, Session, UserRepo>;
+pub type UserDeletion =
+    UserDeletionServiceImpl<Time, Secret, TemplateEmail, Cache, Session, UserRepo>;
+pub type UserAvatar = UserAvatarServiceImpl<Id, Image, Storage>;
+pub type UserPasswordPolicy = UserPasswordPolicyServiceImpl<PasswordStrength, BreachedPassword>;
+pub type UserExport = UserExportServiceImpl<
+    Time,
+    Secret,
+    TemplateEmail,
+    Cache,
+    UserRepo,
+    SessionRepo,
+    MfaRepo,
+    OAuth2Repo,
+    AuditRepo,
+>;
 
 pub type SessionFeature = SessionFeatureServiceImpl<
     Database,
     Auth,
     Captcha,
+    Throttle,
     Session,
     SessionFailedAuthCount,
+    SessionLoginLink,
     MfaAuthenticate,
     UserRepo,
     SessionRepo,
+    AuditLog,
+    TemplateEmail,
+>;
+pub type Session = SessionServiceImpl<
+    Id,
+    Time,
+    Hash,
+    Auth,
+    AuthAccessToken,
+    TemplateEmail,
+    Cache,
+    SessionRepo,
+    UserRepo,
 >;
-pub type Session = SessionServiceImpl<Id, Time, Auth, AuthAccessToken, SessionRepo, UserRepo>;
 pub type SessionFailedAuthCount = SessionFailedAuthCountServiceImpl<Hash, Cache>;
+pub type SessionLoginLink = SessionLoginLinkServiceImpl<Secret, TemplateEmail, Cache>;
 
-pub type ContactFeature = ContactFeatureServiceImpl<Captcha, Email>;
+pub type ContactFeature = ContactFeatureServiceImpl<Captcha, Throttle, Email>;
 
 pub type MfaFeature = MfaFeatureServiceImpl<
     Database,
@@ -141,25 +200,36 @@ pub type MfaFeature = MfaFeatureServiceImpl<
     MfaRecovery,
     MfaDisable,
     MfaTotpDevice,
+    MfaWebauthn,
+    TemplateEmail,
+    AuditLog,
 >;
 pub type MfaRecovery = MfaRecoveryServiceImpl<Secret, Hash, MfaRepo>;
-pub type MfaAuthenticate = MfaAuthenticateServiceImpl<Hash, Totp, MfaDisable, MfaRepo>;
+pub type MfaAuthenticate =
+    MfaAuthenticateServiceImpl<Time, Hash, Totp, MfaDisable, MfaWebauthn, MfaRepo>;
 pub type MfaDisable = MfaDisableServiceImpl<MfaRepo>;
 pub type MfaTotpDevice = MfaTotpDeviceServiceImpl<Id, Time, Totp, MfaRepo>;
+pub type MfaWebauthn = MfaWebauthnServiceImpl<Id, Time, Webauthn, Cache, MfaRepo>;
 
 pub type OAuth2Feature = OAuth2FeatureServiceImpl<
     Database,
     Auth,
-    OAuth2Api,
     UserRepo,
     OAuth2Repo,
     OAuth2Link,
     OAuth2Login,
     OAuth2Registration,
+    OAuth2Token,
     Session,
+    TemplateEmail,
+    AuditLog,
 >;
-pub type OAuth2Link = OAuth2LinkServiceImpl<Id, Time, OAuth2Repo>;
-pub type OAuth2Login = OAuth2LoginServiceImpl<OAuth2Api>;
-pub type OAuth2Registration = OAuth2RegistrationServiceImpl<Secret, Cache>;
+pub type OAuth2Link = OAuth2LinkServiceImpl<Id, Time, OAuth2Repo, OAuth2Token>;
+pub type OAuth2Login = OAuth2LoginServiceImpl<OAuth2Api, Secret, Cache>;
+pub type OAuth2Registration = OAuth2RegistrationServiceImpl<Secret, Encryption, Cache>;
+pub type OAuth2Token = OAuth2TokenServiceImpl<Time, Encryption, OAuth2Api, OAuth2Repo>;
+
+pub type AuditFeature = AuditFeatureServiceImpl<Database, Auth, AuditRepo>;
+pub type AuditLog = AuditLogServiceImpl<Id, Time, AuditRepo>;
 
 pub type Internal = InternalServiceImpl<Database, AuthInternal, UserRepo>;
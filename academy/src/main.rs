@@ -1,6 +1,6 @@
 use academy::commands::{
-    admin::AdminCommand, email::EmailCommand, jwt::JwtCommand, migrate::MigrateCommand,
-    serve::serve, tasks::TaskCommand,
+    admin::AdminCommand, email::EmailCommand, encryption::EncryptionCommand, jwt::JwtCommand,
+    migrate::MigrateCommand, serve::serve, tasks::TaskCommand,
 };
 use academy_utils::academy_version;
 use anyhow::Context;
@@ -44,6 +44,7 @@ async fn main() -> anyhow::Result<()> {
         Command::Migrate { command } => command.invoke(config).await?,
         Command::Admin { command } => command.invoke(config).await?,
         Command::Jwt { command } => command.invoke(config).await?,
+        Command::Encryption { command } => command.invoke(config).await?,
         Command::Email { command } => command.invoke(config).await?,
         Command::Task { command } => command.invoke(config).await?,
         Command::CheckConfig { verbose } => {
@@ -85,6 +86,11 @@ enum Command {
         #[command(subcommand)]
         command: JwtCommand,
     },
+    /// Manage keys for encrypting data at rest
+    Encryption {
+        #[command(subcommand)]
+        command: EncryptionCommand,
+    },
     /// Test email deliverability
     #[command(aliases(["e"]))]
     Email {
@@ -94,7 +100,7 @@ enum Command {
     /// Invoke scheduled tasks
     #[command(aliases(["t"]))]
     Task {
-        #[command(subcommand)]
+        #[command(flatten)]
         command: TaskCommand,
     },
     /// Validate configuration
@@ -1,9 +1,10 @@
 use academy_models::auth::{AuthError, AuthenticateError, AuthorizeError};
 use aide::transform::TransformOperation;
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
 };
+use chrono::{DateTime, Utc};
 use schemars::JsonSchema;
 use serde::Serialize;
 
@@ -26,10 +27,11 @@ pub fn auth_error(err: AuthError) -> Response {
             InvalidTokenError.into_response()
         }
         AuthError::Authenticate(AuthenticateError::Other(err)) => internal_server_error(err),
-        AuthError::Authorize(AuthorizeError::Admin) => PermissionDeniedError.into_response(),
+        AuthError::Authorize(AuthorizeError::Permission) => PermissionDeniedError.into_response(),
         AuthError::Authorize(AuthorizeError::EmailVerified) => {
             EmailNotVerifiedError.into_response()
         }
+        AuthError::Authorize(AuthorizeError::Impersonation) => ImpersonationError.into_response(),
     }
 }
 
@@ -38,6 +40,18 @@ pub fn auth_error_docs(op: TransformOperation) -> TransformOperation {
         .with(internal_server_error_docs)
         .add_error::<PermissionDeniedError>()
         .add_error::<EmailNotVerifiedError>()
+        .add_error::<ImpersonationError>()
+}
+
+/// Reject a request because of a rate limit, telling the client to retry
+/// after the given time
+pub fn too_many_requests(until: DateTime<Utc>) -> Response {
+    let retry_after = until.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+    ([(header::RETRY_AFTER, retry_after)], TooManyRequestsError).into_response()
+}
+
+pub fn too_many_requests_docs(op: TransformOperation) -> TransformOperation {
+    op.add_error::<TooManyRequestsError>()
 }
 
 /// A simple error response containing only the error code
@@ -62,6 +76,13 @@ error_code! {
     pub PermissionDeniedError(FORBIDDEN, "Permission denied");
     /// The authenticated user has not verified their email address.
     EmailNotVerifiedError(FORBIDDEN, "Email not verified");
+    /// This action is not allowed in a session that has been created to
+    /// impersonate the user.
+    ImpersonationError(FORBIDDEN, "Not allowed while impersonating");
+
+    /// Too many requests have been sent. Retry after the time given in the
+    /// `Retry-After` header.
+    pub TooManyRequestsError(TOO_MANY_REQUESTS, "Too many requests");
 
     /// reCAPTCHA is enabled but no valid reCAPTCHA response has been provided.
     pub RecaptchaFailedError(PRECONDITION_FAILED, "Recaptcha failed");
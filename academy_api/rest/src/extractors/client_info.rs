@@ -0,0 +1,28 @@
+use std::convert::Infallible;
+
+use academy_models::audit::ClientInfo;
+use aide::OperationInput;
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+
+use super::user_agent::UserAgent;
+use crate::middlewares::client_ip::ClientIp;
+
+/// Extract the IP address and user agent of the client
+pub struct ApiClientInfo(pub ClientInfo);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ApiClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ip_address = parts.extensions.get::<ClientIp>().map(|ip| ip.0);
+        let UserAgent(user_agent) = UserAgent::from_request_parts(parts, state).await?;
+
+        Ok(Self(ClientInfo {
+            ip_address,
+            user_agent,
+        }))
+    }
+}
+
+impl OperationInput for ApiClientInfo {}
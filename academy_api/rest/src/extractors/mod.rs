@@ -1,2 +1,3 @@
 pub mod auth;
+pub mod client_info;
 pub mod user_agent;
@@ -1,8 +1,12 @@
 use std::{
+    collections::HashMap,
     net::{IpAddr, SocketAddr},
     sync::Arc,
 };
 
+use academy_auth_contracts::access_token::AuthAccessTokenService;
+
+use academy_core_audit_contracts::AuditFeatureService;
 use academy_core_config_contracts::ConfigFeatureService;
 use academy_core_contact_contracts::ContactFeatureService;
 use academy_core_health_contracts::HealthFeatureService;
@@ -13,6 +17,7 @@ use academy_core_session_contracts::SessionFeatureService;
 use academy_core_user_contracts::UserFeatureService;
 use academy_di::Build;
 use academy_models::auth::{AccessToken, InternalToken};
+use academy_shared_contracts::rate_limit::{RateLimitPolicy, RateLimitService};
 use academy_utils::{academy_version, Apply};
 use aide::{
     axum::ApiRouter,
@@ -25,6 +30,7 @@ use axum::{
     Extension, Json,
 };
 use extractors::auth::ApiTokenType;
+use middlewares::rate_limit::RateLimiter;
 use regex::bytes::RegexSet;
 use tokio::net::TcpListener;
 use tower_http::cors::{AllowOrigin, Any, CorsLayer};
@@ -39,7 +45,19 @@ mod models;
 mod routes;
 
 #[derive(Debug, Clone, Build)]
-pub struct RestServer<Health, Config, User, Session, Contact, Mfa, OAuth2, Internal> {
+pub struct RestServer<
+    Health,
+    Config,
+    User,
+    Session,
+    Contact,
+    Mfa,
+    OAuth2,
+    Audit,
+    Internal,
+    RateLimit,
+    AuthAccessToken,
+> {
     _config: RestServerConfig,
     health: Health,
     config: Config,
@@ -48,7 +66,10 @@ pub struct RestServer<Health, Config, User, Session, Contact, Mfa, OAuth2, Inter
     contact: Contact,
     mfa: Mfa,
     oauth2: OAuth2,
+    audit: Audit,
     internal: Internal,
+    rate_limit: RateLimit,
+    auth_access_token: AuthAccessToken,
 }
 
 #[derive(Debug, Clone)]
@@ -56,6 +77,7 @@ pub struct RestServerConfig {
     pub addr: SocketAddr,
     pub real_ip_config: Option<Arc<RestServerRealIpConfig>>,
     pub allowed_origins: Arc<RegexSet>,
+    pub rate_limit_config: Option<Arc<RestServerRateLimitConfig>>,
 }
 
 #[derive(Debug, Clone)]
@@ -64,8 +86,46 @@ pub struct RestServerRealIpConfig {
     pub set_from: IpAddr,
 }
 
-impl<Health, Config, User, Session, Contact, Mfa, OAuth2, Internal>
-    RestServer<Health, Config, User, Session, Contact, Mfa, OAuth2, Internal>
+#[derive(Debug, Clone)]
+pub struct RestServerRateLimitConfig {
+    /// Policy for route groups without a specific policy
+    pub default: RateLimitPolicy,
+    /// Policies by route group
+    pub groups: HashMap<String, RateLimitPolicy>,
+}
+
+impl RestServerRateLimitConfig {
+    fn policy(&self, group: &str) -> RateLimitPolicy {
+        self.groups.get(group).copied().unwrap_or(self.default)
+    }
+}
+
+impl<
+        Health,
+        Config,
+        User,
+        Session,
+        Contact,
+        Mfa,
+        OAuth2,
+        Audit,
+        Internal,
+        RateLimit,
+        AuthAccessToken,
+    >
+    RestServer<
+        Health,
+        Config,
+        User,
+        Session,
+        Contact,
+        Mfa,
+        OAuth2,
+        Audit,
+        Internal,
+        RateLimit,
+        AuthAccessToken,
+    >
 where
     Health: HealthFeatureService,
     Config: ConfigFeatureService,
@@ -74,13 +134,17 @@ where
     Contact: ContactFeatureService,
     Mfa: MfaFeatureService,
     OAuth2: OAuth2FeatureService,
+    Audit: AuditFeatureService,
     Internal: InternalService,
+    RateLimit: RateLimitService,
+    AuthAccessToken: AuthAccessTokenService,
 {
     pub async fn serve(self) -> anyhow::Result<()> {
         let RestServerConfig {
             addr,
             ref real_ip_config,
             ref allowed_origins,
+            rate_limit_config: _,
         } = self._config;
         let real_ip_config = real_ip_config.as_ref().map(Arc::clone);
         let allowed_origins = Arc::clone(allowed_origins);
@@ -100,6 +164,7 @@ where
                 routes::session::TAG,
                 routes::mfa::TAG,
                 routes::oauth2::TAG,
+                routes::audit::TAG,
                 routes::internal::TAG,
             ]
             .into_iter()
@@ -133,7 +198,8 @@ where
                     allowed_origins.is_match(origin.as_bytes())
                 },
             ))
-            .allow_headers(Any);
+            .allow_headers(Any)
+            .expose_headers(Any);
 
         let router = self
             .router()
@@ -164,14 +230,24 @@ where
     }
 
     fn router(self) -> ApiRouter<()> {
+        let rate_limiter = self._config.rate_limit_config.map(|config| {
+            Arc::new(RateLimiter {
+                config,
+                rate_limit: self.rate_limit,
+                auth_access_token: self.auth_access_token,
+            })
+        });
+        let rate_limit = |group| middlewares::rate_limit::add(rate_limiter.clone(), group);
+
         ApiRouter::new()
-            .merge(routes::health::router(self.health.into()))
-            .merge(routes::config::router(self.config.into()))
-            .merge(routes::user::router(self.user.into()))
-            .merge(routes::session::router(self.session.into()))
-            .merge(routes::contact::router(self.contact.into()))
-            .merge(routes::mfa::router(self.mfa.into()))
-            .merge(routes::oauth2::router(self.oauth2.into()))
+            .merge(routes::health::router(self.health.into()).apply(rate_limit("health")))
+            .merge(routes::config::router(self.config.into()).apply(rate_limit("config")))
+            .merge(routes::user::router(self.user.into()).apply(rate_limit("user")))
+            .merge(routes::session::router(self.session.into()).apply(rate_limit("session")))
+            .merge(routes::contact::router(self.contact.into()).apply(rate_limit("contact")))
+            .merge(routes::mfa::router(self.mfa.into()).apply(rate_limit("mfa")))
+            .merge(routes::oauth2::router(self.oauth2.into()).apply(rate_limit("oauth2")))
+            .merge(routes::audit::router(self.audit.into()).apply(rate_limit("audit")))
             .merge(routes::internal::router(self.internal.into()))
     }
 }
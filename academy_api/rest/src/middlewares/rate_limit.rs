@@ -0,0 +1,133 @@
+//! Limit the number of requests per client.
+//!
+//! Requests with a valid access token are counted per user, all other requests
+//! per client IP address. Each route group has its own token bucket per client.
+
+use std::{sync::Arc, time::Duration};
+
+use academy_auth_contracts::access_token::AuthAccessTokenService;
+use academy_models::auth::AccessToken;
+use academy_shared_contracts::rate_limit::{RateLimitPolicy, RateLimitService, RateLimitStatus};
+use aide::{axum::ApiRouter, transform::TransformOperation};
+use axum::{
+    extract::Request,
+    http::header,
+    middleware::{from_fn, Next},
+    response::{IntoResponse, Response},
+    RequestPartsExt,
+};
+
+use super::client_ip::ClientIp;
+use crate::{
+    errors::{internal_server_error, too_many_requests_docs, TooManyRequestsError},
+    extractors::auth::ApiToken,
+    RestServerRateLimitConfig,
+};
+
+pub fn add<S, RateLimit, AuthAccessToken>(
+    rate_limiter: Option<Arc<RateLimiter<RateLimit, AuthAccessToken>>>,
+    group: &'static str,
+) -> impl FnOnce(ApiRouter<S>) -> ApiRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+    RateLimit: RateLimitService,
+    AuthAccessToken: AuthAccessTokenService,
+{
+    move |router| {
+        let Some(rate_limiter) = rate_limiter else {
+            return router;
+        };
+
+        router
+            .with_path_items(|mut item| {
+                let path_item = item.inner_mut();
+                for operation in [
+                    &mut path_item.get,
+                    &mut path_item.put,
+                    &mut path_item.post,
+                    &mut path_item.delete,
+                    &mut path_item.options,
+                    &mut path_item.head,
+                    &mut path_item.patch,
+                    &mut path_item.trace,
+                ]
+                .into_iter()
+                .flatten()
+                {
+                    let _ = too_many_requests_docs(TransformOperation::new(operation));
+                }
+                item
+            })
+            .layer(from_fn(move |request: Request, next: Next| {
+                let rate_limiter = Arc::clone(&rate_limiter);
+                async move { rate_limiter.middleware(group, request, next).await }
+            }))
+    }
+}
+
+#[derive(Debug)]
+pub struct RateLimiter<RateLimit, AuthAccessToken> {
+    pub config: Arc<RestServerRateLimitConfig>,
+    pub rate_limit: RateLimit,
+    pub auth_access_token: AuthAccessToken,
+}
+
+impl<RateLimit, AuthAccessToken> RateLimiter<RateLimit, AuthAccessToken>
+where
+    RateLimit: RateLimitService,
+    AuthAccessToken: AuthAccessTokenService,
+{
+    async fn middleware(&self, group: &'static str, request: Request, next: Next) -> Response {
+        let (mut parts, body) = request.into_parts();
+
+        let ApiToken(token) = parts
+            .extract::<ApiToken<AccessToken>>()
+            .await
+            .unwrap_or_else(|x| match x {});
+        let key = match self.auth_access_token.verify(&token) {
+            Some(auth) => format!("{group}:user:{}", auth.user_id.hyphenated()),
+            None => match parts.extensions.get::<ClientIp>() {
+                Some(ClientIp(ip)) => format!("{group}:ip:{ip}"),
+                None => return next.run(Request::from_parts(parts, body)).await,
+            },
+        };
+
+        let policy = self.config.policy(group);
+        let status = match self.rate_limit.acquire(&key, policy).await {
+            Ok(status) => status,
+            Err(err) => return internal_server_error(err),
+        };
+
+        let headers = rate_limit_headers(policy, status);
+        if !status.allowed {
+            return (
+                headers,
+                [(header::RETRY_AFTER, ceil_secs(status.reset).to_string())],
+                TooManyRequestsError,
+            )
+                .into_response();
+        }
+
+        let response = next.run(Request::from_parts(parts, body)).await;
+        (headers, response).into_response()
+    }
+}
+
+fn rate_limit_headers(
+    policy: RateLimitPolicy,
+    status: RateLimitStatus,
+) -> [(&'static str, String); 4] {
+    [
+        ("RateLimit-Limit", policy.capacity.to_string()),
+        ("RateLimit-Remaining", status.remaining.to_string()),
+        ("RateLimit-Reset", ceil_secs(status.reset).to_string()),
+        (
+            "RateLimit-Policy",
+            format!("{};w={}", policy.capacity, ceil_secs(policy.period)),
+        ),
+    ]
+}
+
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
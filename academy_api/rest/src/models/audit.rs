@@ -0,0 +1,60 @@
+use std::net::IpAddr;
+
+use academy_models::{
+    audit::{AuditEvent, AuditEventFilter, AuditEventId, AuditEventKind},
+    user::UserId,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiAuditEvent {
+    /// Audit event ID
+    pub id: AuditEventId,
+    /// ID of the user who performed the action
+    pub actor_id: Option<UserId>,
+    /// ID of the user affected by the action
+    pub user_id: UserId,
+    /// Type of the event
+    pub kind: AuditEventKind,
+    /// IP address of the client which performed the action
+    pub ip_address: Option<IpAddr>,
+    /// User agent of the client which performed the action
+    pub user_agent: Option<String>,
+    /// Timestamp of the event
+    pub created_at: i64,
+}
+
+impl From<AuditEvent> for ApiAuditEvent {
+    fn from(value: AuditEvent) -> Self {
+        Self {
+            id: value.id,
+            actor_id: value.actor_id,
+            user_id: value.user_id,
+            kind: value.kind,
+            ip_address: value.client.ip_address,
+            user_agent: value.client.user_agent,
+            created_at: value.created_at.timestamp(),
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ApiAuditEventFilter {
+    /// Filter by `actor_id`
+    pub actor_id: Option<UserId>,
+    /// Filter by `user_id`
+    pub user_id: Option<UserId>,
+    /// Filter by `kind`
+    pub kind: Option<AuditEventKind>,
+}
+
+impl From<ApiAuditEventFilter> for AuditEventFilter {
+    fn from(value: ApiAuditEventFilter) -> Self {
+        Self {
+            actor_id: value.actor_id,
+            user_id: value.user_id,
+            kind: value.kind,
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use academy_models::auth::JwtPublicKey;
+use academy_utils::serde::base64url;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// JSON Web Key Set (RFC 7517)
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiJwks {
+    pub keys: Vec<ApiJwk>,
+}
+
+/// Public ES256 key used to verify JWTs
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiJwk {
+    /// Key type
+    kty: &'static str,
+    /// Curve
+    crv: &'static str,
+    /// Algorithm
+    alg: &'static str,
+    /// Public key use
+    #[serde(rename = "use")]
+    use_: &'static str,
+    /// Key ID
+    kid: String,
+    /// base64url encoded x coordinate
+    #[serde(with = "base64url")]
+    #[schemars(with = "String")]
+    x: [u8; 32],
+    /// base64url encoded y coordinate
+    #[serde(with = "base64url")]
+    #[schemars(with = "String")]
+    y: [u8; 32],
+}
+
+impl From<Vec<JwtPublicKey>> for ApiJwks {
+    fn from(value: Vec<JwtPublicKey>) -> Self {
+        Self {
+            keys: value.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<JwtPublicKey> for ApiJwk {
+    fn from(value: JwtPublicKey) -> Self {
+        Self {
+            kty: "EC",
+            crv: "P-256",
+            alg: "ES256",
+            use_: "sig",
+            kid: value.kid,
+            x: value.x,
+            y: value.y,
+        }
+    }
+}
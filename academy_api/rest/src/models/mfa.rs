@@ -0,0 +1,290 @@
+use academy_models::{
+    mfa::{
+        MfaRecoveryCode, TotpDevice, TotpDeviceId, TotpDeviceName, TotpSecretBase32, TotpSetup,
+        WebauthnAssertion, WebauthnAuthenticationOptions, WebauthnChallenge, WebauthnCredential,
+        WebauthnCredentialId, WebauthnCredentialName, WebauthnCredentialRawId,
+        WebauthnRegistration, WebauthnRegistrationOptions, WebauthnRegistrationResponse,
+    },
+    user::{UserDisplayName, UserName},
+};
+use academy_utils::serde::base64url;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// COSE algorithm identifiers of the supported public key types (ES256,
+/// EdDSA)
+const SUPPORTED_ALGORITHMS: [i64; 2] = [-7, -8];
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiTotpDevice {
+    /// TOTP device ID
+    pub id: TotpDeviceId,
+    /// Display name
+    pub name: TotpDeviceName,
+    /// Whether the device has been confirmed
+    pub enabled: bool,
+    /// Timestamp of creation
+    pub created_at: i64,
+    /// Timestamp of last successful authentication
+    pub last_used_at: Option<i64>,
+}
+
+impl From<TotpDevice> for ApiTotpDevice {
+    fn from(value: TotpDevice) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            enabled: value.enabled,
+            created_at: value.created_at.timestamp(),
+            last_used_at: value.last_used_at.map(|x| x.timestamp()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiTotpDeviceSetup {
+    /// The newly created device
+    pub device: ApiTotpDevice,
+    /// Base32 encoded TOTP secret for configuring the authenticator
+    pub secret: TotpSecretBase32,
+}
+
+impl From<(TotpDevice, TotpSetup)> for ApiTotpDeviceSetup {
+    fn from((device, setup): (TotpDevice, TotpSetup)) -> Self {
+        Self {
+            device: device.into(),
+            secret: setup.secret,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiWebauthnCredential {
+    /// Credential ID
+    pub id: WebauthnCredentialId,
+    /// Display name
+    pub name: WebauthnCredentialName,
+    /// Timestamp of registration
+    pub created_at: i64,
+    /// Timestamp of last successful authentication
+    pub last_used_at: Option<i64>,
+}
+
+impl From<WebauthnCredential> for ApiWebauthnCredential {
+    fn from(value: WebauthnCredential) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            created_at: value.created_at.timestamp(),
+            last_used_at: value.last_used_at.map(|x| x.timestamp()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiWebauthnRegistration {
+    /// The newly registered credential
+    pub credential: ApiWebauthnCredential,
+    /// MFA recovery code, if MFA has not been enabled before
+    pub recovery_code: Option<MfaRecoveryCode>,
+}
+
+impl From<WebauthnRegistration> for ApiWebauthnRegistration {
+    fn from(value: WebauthnRegistration) -> Self {
+        Self {
+            credential: value.credential.into(),
+            recovery_code: value.recovery_code,
+        }
+    }
+}
+
+/// `PublicKeyCredentialCreationOptions` to be passed to
+/// `navigator.credentials.create()`
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiWebauthnRegistrationOptions {
+    /// base64url encoded challenge
+    challenge: WebauthnChallenge,
+    rp: ApiWebauthnRelyingParty,
+    user: ApiWebauthnUser,
+    pub_key_cred_params: Vec<ApiWebauthnPublicKeyCredentialParameters>,
+    /// Timeout in milliseconds
+    timeout: u128,
+    exclude_credentials: Vec<ApiWebauthnCredentialDescriptor>,
+    authenticator_selection: ApiWebauthnAuthenticatorSelection,
+    attestation: &'static str,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct ApiWebauthnRelyingParty {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct ApiWebauthnUser {
+    /// base64url encoded user handle
+    #[serde(with = "base64url")]
+    #[schemars(with = "String")]
+    id: Vec<u8>,
+    name: UserName,
+    display_name: UserDisplayName,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct ApiWebauthnPublicKeyCredentialParameters {
+    r#type: &'static str,
+    alg: i64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct ApiWebauthnCredentialDescriptor {
+    r#type: &'static str,
+    /// base64url encoded credential id
+    #[serde(with = "base64url")]
+    #[schemars(with = "String")]
+    id: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct ApiWebauthnAuthenticatorSelection {
+    resident_key: &'static str,
+    require_resident_key: bool,
+    user_verification: &'static str,
+}
+
+impl From<WebauthnRegistrationOptions> for ApiWebauthnRegistrationOptions {
+    fn from(value: WebauthnRegistrationOptions) -> Self {
+        Self {
+            challenge: value.challenge,
+            rp: ApiWebauthnRelyingParty {
+                id: value.rp_id,
+                name: value.rp_name,
+            },
+            user: ApiWebauthnUser {
+                id: value.user_id.as_bytes().to_vec(),
+                name: value.user_name,
+                display_name: value.user_display_name,
+            },
+            pub_key_cred_params: SUPPORTED_ALGORITHMS
+                .into_iter()
+                .map(|alg| ApiWebauthnPublicKeyCredentialParameters {
+                    r#type: "public-key",
+                    alg,
+                })
+                .collect(),
+            timeout: value.timeout.as_millis(),
+            exclude_credentials: value
+                .exclude_credentials
+                .into_iter()
+                .map(|id| ApiWebauthnCredentialDescriptor {
+                    r#type: "public-key",
+                    id: id.into_inner(),
+                })
+                .collect(),
+            // credentials must be discoverable, because authentication
+            // challenges are not bound to a specific user
+            authenticator_selection: ApiWebauthnAuthenticatorSelection {
+                resident_key: "required",
+                require_resident_key: true,
+                user_verification: "preferred",
+            },
+            attestation: "none",
+        }
+    }
+}
+
+/// `PublicKeyCredentialRequestOptions` to be passed to
+/// `navigator.credentials.get()`
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiWebauthnAuthenticationOptions {
+    /// base64url encoded challenge
+    challenge: WebauthnChallenge,
+    rp_id: String,
+    /// Timeout in milliseconds
+    timeout: u128,
+    user_verification: &'static str,
+}
+
+impl From<WebauthnAuthenticationOptions> for ApiWebauthnAuthenticationOptions {
+    fn from(value: WebauthnAuthenticationOptions) -> Self {
+        Self {
+            challenge: value.challenge,
+            rp_id: value.rp_id,
+            timeout: value.timeout.as_millis(),
+            user_verification: "preferred",
+        }
+    }
+}
+
+/// JSON encoded `PublicKeyCredential` returned by
+/// `navigator.credentials.create()`
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ApiWebauthnAttestation {
+    response: ApiWebauthnAttestationResponse,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct ApiWebauthnAttestationResponse {
+    /// base64url encoded client data
+    #[serde(rename = "clientDataJSON", with = "base64url")]
+    #[schemars(with = "String")]
+    client_data_json: Vec<u8>,
+    /// base64url encoded attestation object
+    #[serde(with = "base64url")]
+    #[schemars(with = "String")]
+    attestation_object: Vec<u8>,
+}
+
+impl From<ApiWebauthnAttestation> for WebauthnRegistrationResponse {
+    fn from(value: ApiWebauthnAttestation) -> Self {
+        Self {
+            client_data_json: value.response.client_data_json,
+            attestation_object: value.response.attestation_object,
+        }
+    }
+}
+
+/// JSON encoded `PublicKeyCredential` returned by
+/// `navigator.credentials.get()`
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiWebauthnAssertion {
+    /// base64url encoded credential id
+    #[serde(with = "base64url")]
+    #[schemars(with = "String")]
+    raw_id: WebauthnCredentialRawId,
+    response: ApiWebauthnAssertionResponse,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct ApiWebauthnAssertionResponse {
+    /// base64url encoded client data
+    #[serde(rename = "clientDataJSON", with = "base64url")]
+    #[schemars(with = "String")]
+    client_data_json: Vec<u8>,
+    /// base64url encoded authenticator data
+    #[serde(with = "base64url")]
+    #[schemars(with = "String")]
+    authenticator_data: Vec<u8>,
+    /// base64url encoded signature
+    #[serde(with = "base64url")]
+    #[schemars(with = "String")]
+    signature: Vec<u8>,
+}
+
+impl From<ApiWebauthnAssertion> for WebauthnAssertion {
+    fn from(value: ApiWebauthnAssertion) -> Self {
+        Self {
+            raw_id: value.raw_id,
+            client_data_json: value.response.client_data_json,
+            authenticator_data: value.response.authenticator_data,
+            signature: value.response.signature,
+        }
+    }
+}
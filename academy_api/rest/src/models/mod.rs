@@ -1,12 +1,15 @@
 use std::borrow::Cow;
 
-use academy_models::pagination::{PaginationLimit, PaginationSlice};
+use academy_models::pagination::{Pagination, PaginationCursor, PaginationLimit, PaginationSlice};
 use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
 use serde::Deserialize;
 
 use crate::const_schema;
 
+pub mod audit;
+pub mod config;
 pub mod contact;
+pub mod mfa;
 pub mod oauth2;
 pub mod session;
 pub mod user;
@@ -15,6 +18,35 @@ const_schema! {
     pub OkResponse(true);
 }
 
+#[derive(Deserialize, JsonSchema)]
+pub struct ApiPagination {
+    /// The number of items to select.
+    #[serde(default)]
+    pub limit: PaginationLimit,
+    /// The number of items to skip. Ignored if `cursor` is set.
+    #[serde(default)]
+    pub offset: u64,
+    /// Continue after the last item of a previous page by passing its
+    /// `next_cursor`.
+    #[serde(default)]
+    pub cursor: StringOption<PaginationCursor>,
+}
+
+impl From<ApiPagination> for Pagination {
+    fn from(value: ApiPagination) -> Self {
+        match value.cursor {
+            StringOption::Some(cursor) => Self::Cursor {
+                limit: value.limit,
+                after: Some(cursor),
+            },
+            StringOption::None => Self::Offset(PaginationSlice {
+                limit: value.limit,
+                offset: value.offset,
+            }),
+        }
+    }
+}
+
 #[derive(Deserialize, JsonSchema)]
 pub struct ApiPaginationSlice {
     /// The number of items to select.
@@ -35,18 +67,13 @@ impl From<ApiPaginationSlice> for PaginationSlice {
 }
 
 /// [`Option`]-like enum that deserializes the empty string to `None`
+#[derive(Default)]
 pub enum StringOption<T> {
     Some(T),
+    #[default]
     None,
 }
 
-// no `#[derive(Default)]` to avoid the `T: Default` bound
-impl<T> Default for StringOption<T> {
-    fn default() -> Self {
-        Self::None
-    }
-}
-
 impl<'de, T: Deserialize<'de>> Deserialize<'de> for StringOption<T> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -1,9 +1,12 @@
 use academy_models::{
+    email_address::EmailAddress,
     oauth2::{
         OAuth2AuthorizationCode, OAuth2Link, OAuth2LinkId, OAuth2Login, OAuth2ProviderId,
-        OAuth2ProviderName, OAuth2ProviderSummary, OAuth2RemoteUserName,
+        OAuth2ProviderName, OAuth2ProviderSummary, OAuth2RemoteProfile, OAuth2RemoteUserName,
+        OAuth2State,
     },
     url::Url,
+    user::UserDisplayName,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -14,9 +17,6 @@ pub struct ApiOAuth2ProviderSummary {
     pub id: OAuth2ProviderId,
     /// Display name
     pub name: OAuth2ProviderName,
-    /// Remote authorize endpoint URL *without* `state` and `redirect_uri`
-    /// parameters
-    pub authorize_url: Url,
 }
 
 impl From<OAuth2ProviderSummary> for ApiOAuth2ProviderSummary {
@@ -24,7 +24,6 @@ impl From<OAuth2ProviderSummary> for ApiOAuth2ProviderSummary {
         Self {
             id: value.id,
             name: value.name,
-            authorize_url: value.auth_url,
         }
     }
 }
@@ -57,6 +56,8 @@ pub struct ApiOAuth2Login {
     pub code: OAuth2AuthorizationCode,
     /// Redirect URI that was used for this authentication.
     pub redirect_uri: Url,
+    /// `state` parameter returned by the OAuth2 provider
+    pub state: OAuth2State,
 }
 
 impl From<ApiOAuth2Login> for OAuth2Login {
@@ -65,6 +66,32 @@ impl From<ApiOAuth2Login> for OAuth2Login {
             provider_id: value.provider_id,
             code: value.code,
             redirect_uri: value.redirect_uri,
+            state: value.state,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiOAuth2RemoteProfile {
+    /// Email address of the remote user account
+    pub email: Option<EmailAddress>,
+    /// Whether the OAuth2 provider has verified the email address. If the
+    /// same email address is used for registration, the new account does not
+    /// need to be verified.
+    pub email_verified: bool,
+    /// Display name of the remote user account
+    pub display_name: Option<UserDisplayName>,
+    /// URL of the remote user's avatar
+    pub avatar_url: Option<Url>,
+}
+
+impl From<OAuth2RemoteProfile> for ApiOAuth2RemoteProfile {
+    fn from(value: OAuth2RemoteProfile) -> Self {
+        Self {
+            email: value.email,
+            email_verified: value.email_verified,
+            display_name: value.display_name,
+            avatar_url: value.avatar_url,
         }
     }
 }
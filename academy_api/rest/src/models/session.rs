@@ -16,6 +16,9 @@ pub struct ApiSession {
     pub user_id: UserId,
     /// Device Name
     pub device_name: Option<DeviceName>,
+    /// ID of the administrator who created this session to impersonate the
+    /// user
+    pub impersonator_id: Option<UserId>,
     /// Timestamp of last refresh
     pub last_update: i64,
 }
@@ -26,6 +29,7 @@ impl From<Session> for ApiSession {
             id: value.id,
             user_id: value.user_id,
             device_name: value.device_name,
+            impersonator_id: value.impersonator_id,
             last_update: value.updated_at.timestamp(),
         }
     }
@@ -1,13 +1,20 @@
+use std::collections::BTreeSet;
+
 use academy_models::{
     email_address::EmailAddress,
+    oauth2::OAuth2ProviderId,
+    pagination::SortDirection,
+    role::{PermissionSet, Role},
     url::Url,
     user::{
-        UserBio, UserCity, UserComposite, UserCountry, UserDisplayName, UserFilter, UserFirstName,
-        UserId, UserIdOrSelf, UserLastName, UserName, UserPassword, UserStreet, UserTags,
-        UserVatId, UserZipCode,
+        UserAvatar, UserAvatarSize, UserBio, UserCity, UserComposite, UserCountry, UserDisplayName,
+        UserExport, UserFilter, UserFirstName, UserId, UserIdOrSelf, UserLastName, UserName,
+        UserPassword, UserProfileVisibility, UserPublicProfile, UserSort, UserSortBy, UserStreet,
+        UserTags, UserVatId, UserZipCode,
     },
     SearchTerm,
 };
+use chrono::{DateTime, Utc};
 use schemars::{
     gen::SchemaGenerator,
     schema::{Schema, SchemaObject, SubschemaValidation},
@@ -16,6 +23,12 @@ use schemars::{
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
+use super::{
+    audit::ApiAuditEvent,
+    mfa::{ApiTotpDevice, ApiWebauthnCredential},
+    oauth2::ApiOAuth2Link,
+    session::ApiSession,
+};
 use crate::const_schema;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -37,10 +50,17 @@ pub struct ApiUser {
     pub last_login: Option<i64>,
     /// Timestamp of last `name` change
     pub last_name_change: Option<i64>,
+    /// Timestamp of the account deletion request (the account is deleted
+    /// permanently after a grace period unless the deletion is cancelled)
+    pub deleted_at: Option<i64>,
     /// Whether the user account is enabled (disabled users cannot login)
     pub enabled: bool,
-    /// Whether the user is an administrator
+    /// Whether the user has the `superuser` role
     pub admin: bool,
+    /// Roles assigned to the user
+    pub roles: BTreeSet<Role>,
+    /// Permissions granted to the user by their roles
+    pub permissions: PermissionSet,
     /// Whether the user has set a password (if not, login is only possible via
     /// OAuth2)
     pub password: bool,
@@ -50,6 +70,8 @@ pub struct ApiUser {
     pub description: UserBio,
     /// Tags of the user profile
     pub tags: UserTags,
+    /// Fields of the user profile which are visible to other users
+    pub profile_visibility: ApiUserProfileVisibility,
     /// Whether the user is subscribed to the newsletter
     pub newsletter: bool,
     /// Whether the user represents a business instead of a private person
@@ -72,8 +94,11 @@ pub struct ApiUser {
     pub can_buy_coins: bool,
     /// Whether the user can receive coins
     pub can_receive_coins: bool,
-    /// URL of the user's avatar
+    /// URL of the user's avatar (the uploaded avatar in medium size if
+    /// available, otherwise the Gravatar of the user's email address)
     pub avatar_url: Option<Url>,
+    /// The avatar uploaded by the user
+    pub avatar: Option<ApiUserAvatar>,
 }
 
 impl From<UserComposite> for ApiUser {
@@ -88,7 +113,12 @@ impl From<UserComposite> for ApiUser {
             invoice_info,
         } = user_composite;
 
-        let avatar_url = user.email.as_ref().map(get_avatar_url);
+        let avatar_url = match &profile.avatar {
+            Some(avatar) => Some(avatar.url(UserAvatarSize::Medium)),
+            None => user.email.as_ref().map(get_avatar_url),
+        };
+        let admin = user.is_superuser();
+        let permissions = user.permissions();
 
         Self {
             id: user.id,
@@ -98,13 +128,18 @@ impl From<UserComposite> for ApiUser {
             registration: user.created_at.timestamp(),
             last_login: user.last_login.map(|x| x.timestamp()),
             last_name_change: user.last_name_change.map(|x| x.timestamp()),
+            deleted_at: user.deleted_at.map(|x| x.timestamp()),
             enabled: user.enabled,
-            admin: user.admin,
+            admin,
+            permissions,
+            roles: user.roles,
             newsletter: user.newsletter,
 
             display_name: profile.display_name,
             description: profile.bio,
             tags: profile.tags,
+            profile_visibility: profile.visibility.into(),
+            avatar: profile.avatar.map(Into::into),
 
             mfa_enabled: details.mfa_enabled,
             password: details.password_login,
@@ -125,6 +160,126 @@ impl From<UserComposite> for ApiUser {
     }
 }
 
+#[derive(Serialize, JsonSchema)]
+pub struct ApiUserExport {
+    /// The user
+    pub user: ApiUser,
+    /// All sessions of the user
+    pub sessions: Vec<ApiSession>,
+    /// All TOTP devices of the user
+    pub totp_devices: Vec<ApiTotpDevice>,
+    /// All WebAuthn credentials of the user
+    pub webauthn_credentials: Vec<ApiWebauthnCredential>,
+    /// All OAuth2 links of the user
+    pub oauth2_links: Vec<ApiOAuth2Link>,
+    /// All audit events related to the user
+    pub audit_events: Vec<ApiAuditEvent>,
+}
+
+impl From<UserExport> for ApiUserExport {
+    fn from(value: UserExport) -> Self {
+        Self {
+            user: value.user_composite.into(),
+            sessions: value.sessions.into_iter().map(Into::into).collect(),
+            totp_devices: value.totp_devices.into_iter().map(Into::into).collect(),
+            webauthn_credentials: value
+                .webauthn_credentials
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            oauth2_links: value.oauth2_links.into_iter().map(Into::into).collect(),
+            audit_events: value.audit_events.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ApiUserAvatar {
+    /// URL of the avatar image with 64x64 pixels
+    pub small: Url,
+    /// URL of the avatar image with 256x256 pixels
+    pub medium: Url,
+    /// URL of the avatar image with 512x512 pixels
+    pub large: Url,
+}
+
+impl From<UserAvatar> for ApiUserAvatar {
+    fn from(value: UserAvatar) -> Self {
+        Self {
+            small: value.url(UserAvatarSize::Small),
+            medium: value.url(UserAvatarSize::Medium),
+            large: value.url(UserAvatarSize::Large),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ApiUserProfileVisibility {
+    /// Whether the bio of the user profile is public
+    pub description: bool,
+    /// Whether the tags of the user profile are public
+    pub tags: bool,
+    /// Whether the timestamp of creation is public
+    pub registration: bool,
+    /// Whether the timestamp of the last successful login is public
+    pub last_login: bool,
+}
+
+impl From<UserProfileVisibility> for ApiUserProfileVisibility {
+    fn from(value: UserProfileVisibility) -> Self {
+        Self {
+            description: value.bio,
+            tags: value.tags,
+            registration: value.created_at,
+            last_login: value.last_login,
+        }
+    }
+}
+
+impl From<ApiUserProfileVisibility> for UserProfileVisibility {
+    fn from(value: ApiUserProfileVisibility) -> Self {
+        Self {
+            bio: value.description,
+            tags: value.tags,
+            created_at: value.registration,
+            last_login: value.last_login,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ApiUserPublicProfile {
+    /// User ID
+    pub id: UserId,
+    /// Display name (not necessarily unique)
+    pub display_name: UserDisplayName,
+    /// The avatar uploaded by the user
+    pub avatar: Option<ApiUserAvatar>,
+    /// Bio of the user profile (`null` if not public)
+    pub description: Option<UserBio>,
+    /// Tags of the user profile (`null` if not public)
+    pub tags: Option<UserTags>,
+    /// Timestamp of creation (`null` if not public)
+    pub registration: Option<i64>,
+    /// Timestamp of last successful login (`null` if not public or if the
+    /// user has never logged in)
+    pub last_login: Option<i64>,
+}
+
+impl From<UserPublicProfile> for ApiUserPublicProfile {
+    fn from(value: UserPublicProfile) -> Self {
+        Self {
+            id: value.id,
+            display_name: value.display_name,
+            avatar: value.avatar.map(Into::into),
+            description: value.bio,
+            tags: value.tags,
+            registration: value.created_at.map(|x| x.timestamp()),
+            last_login: value.last_login.map(|x| x.timestamp()),
+        }
+    }
+}
+
 #[derive(Deserialize, JsonSchema)]
 pub struct ApiUserFilter {
     /// Filter by `name` and `display_name`
@@ -133,14 +288,38 @@ pub struct ApiUserFilter {
     pub email: Option<SearchTerm>,
     /// Filter by `enabled`
     pub enabled: Option<bool>,
-    /// Filter by `admin`
-    pub admin: Option<bool>,
+    /// Only return users with the given role
+    pub role: Option<Role>,
     /// Filter by `mfa_enabled`
     pub mfa_enabled: Option<bool>,
     /// Filter by `email_verified`
     pub email_verified: Option<bool>,
     /// Filter by `newsletter`
     pub newsletter: Option<bool>,
+    /// Filter by `password_login`
+    pub password_login: Option<bool>,
+    /// Filter by `oauth2_login`
+    pub oauth2_login: Option<bool>,
+    /// Only return users with a link to the given OAuth2 provider
+    pub oauth2_provider: Option<OAuth2ProviderId>,
+    /// Filter by `can_receive_coins`
+    pub can_receive_coins: Option<bool>,
+    /// Only return users created at or after the given timestamp
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    #[schemars(with = "Option<i64>")]
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only return users created before the given timestamp
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    #[schemars(with = "Option<i64>")]
+    pub created_before: Option<DateTime<Utc>>,
+    /// Only return users who last logged in at or after the given timestamp
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    #[schemars(with = "Option<i64>")]
+    pub last_login_after: Option<DateTime<Utc>>,
+    /// Only return users who last logged in before the given timestamp
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    #[schemars(with = "Option<i64>")]
+    pub last_login_before: Option<DateTime<Utc>>,
 }
 
 impl From<ApiUserFilter> for UserFilter {
@@ -149,10 +328,37 @@ impl From<ApiUserFilter> for UserFilter {
             name: value.name,
             email: value.email,
             enabled: value.enabled,
-            admin: value.admin,
+            role: value.role,
             mfa_enabled: value.mfa_enabled,
             email_verified: value.email_verified,
             newsletter: value.newsletter,
+            password_login: value.password_login,
+            oauth2_login: value.oauth2_login,
+            oauth2_provider: value.oauth2_provider,
+            can_receive_coins: value.can_receive_coins,
+            created_after: value.created_after,
+            created_before: value.created_before,
+            last_login_after: value.last_login_after,
+            last_login_before: value.last_login_before,
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ApiUserSort {
+    /// The field to sort the users by
+    #[serde(default)]
+    pub sort_by: UserSortBy,
+    /// The sort direction
+    #[serde(default)]
+    pub sort_direction: SortDirection,
+}
+
+impl From<ApiUserSort> for UserSort {
+    fn from(value: ApiUserSort) -> Self {
+        Self {
+            by: value.sort_by,
+            direction: value.sort_direction,
         }
     }
 }
@@ -253,6 +459,8 @@ fn get_avatar_url(email: &EmailAddress) -> Url {
 
 #[cfg(test)]
 mod tests {
+    use axum::extract::Query;
+
     use super::*;
 
     #[test]
@@ -304,6 +512,47 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn deserialize_api_user_filter() {
+        let uri = "/?name=foo&role=moderator&oauth2_provider=github&can_receive_coins=true&\
+                   created_after=1710423462"
+            .parse()
+            .unwrap();
+        let Query(result) = Query::<ApiUserFilter>::try_from_uri(&uri).unwrap();
+        let result = UserFilter::from(result);
+        assert_eq!(
+            result,
+            UserFilter {
+                name: Some("foo".try_into().unwrap()),
+                role: Some(Role::Moderator),
+                oauth2_provider: Some("github".into()),
+                can_receive_coins: Some(true),
+                created_after: DateTime::from_timestamp(1710423462, 0),
+                ..Default::default()
+            }
+        );
+
+        let uri = "/?created_after=asdf".parse().unwrap();
+        assert!(Query::<ApiUserFilter>::try_from_uri(&uri).is_err());
+    }
+
+    #[test]
+    fn deserialize_api_user_sort() {
+        let uri = "/".parse().unwrap();
+        let Query(result) = Query::<ApiUserSort>::try_from_uri(&uri).unwrap();
+        assert_eq!(UserSort::from(result), UserSort::default());
+
+        let uri = "/?sort_by=last_login&sort_direction=desc".parse().unwrap();
+        let Query(result) = Query::<ApiUserSort>::try_from_uri(&uri).unwrap();
+        assert_eq!(
+            UserSort::from(result),
+            UserSort {
+                by: UserSortBy::LastLogin,
+                direction: SortDirection::Desc
+            }
+        );
+    }
+
     #[test]
     fn get_avatar_url() {
         let result = super::get_avatar_url(&"Test@Example.com".parse().unwrap());
@@ -12,7 +12,7 @@ use axum::{
     Json,
 };
 
-use crate::docs::TransformOperationExt;
+use crate::{docs::TransformOperationExt, models::config::ApiJwks};
 
 pub const TAG: &str = "Config";
 
@@ -22,6 +22,10 @@ pub fn router(service: Arc<impl ConfigFeatureService>) -> ApiRouter<()> {
             "/auth/recaptcha",
             routing::get_with(get_recaptcha_sitekey, get_recaptcha_sitekey_docs),
         )
+        .api_route(
+            "/.well-known/jwks.json",
+            routing::get_with(get_jwks, get_jwks_docs),
+        )
         .with_state(service)
         .with_path_items(|op| op.tag(TAG))
 }
@@ -37,3 +41,16 @@ fn get_recaptcha_sitekey_docs(op: TransformOperation) -> TransformOperation {
             op.example("recaptcha-sitekey")
         })
 }
+
+async fn get_jwks(service: State<Arc<impl ConfigFeatureService>>) -> Response {
+    Json(ApiJwks::from(service.get_jwks())).into_response()
+}
+
+fn get_jwks_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Return the public keys used to verify access and internal tokens.")
+        .description(
+            "The keys are returned as a JSON Web Key Set. JWTs reference the signing key via \
+             the `kid` header.",
+        )
+        .add_response::<ApiJwks>(StatusCode::OK, None)
+}
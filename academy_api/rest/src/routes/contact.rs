@@ -18,7 +18,11 @@ use serde::Deserialize;
 use crate::{
     docs::TransformOperationExt,
     error_code,
-    errors::{internal_server_error, internal_server_error_docs, RecaptchaFailedError},
+    errors::{
+        internal_server_error, internal_server_error_docs, too_many_requests,
+        too_many_requests_docs, RecaptchaFailedError,
+    },
+    extractors::client_info::ApiClientInfo,
     models::{contact::ApiContactMessage, OkResponse, StringOption},
 };
 
@@ -45,17 +49,19 @@ struct SendMessageRequest {
 
 async fn send_message(
     service: State<Arc<impl ContactFeatureService>>,
+    client: ApiClientInfo,
     Json(SendMessageRequest {
         message,
         recaptcha_response,
     }): Json<SendMessageRequest>,
 ) -> Response {
     match service
-        .send_message(message.into(), recaptcha_response.into())
+        .send_message(message.into(), recaptcha_response.into(), client.0)
         .await
     {
         Ok(()) => Json(OkResponse).into_response(),
         Err(ContactSendMessageError::Recaptcha) => RecaptchaFailedError.into_response(),
+        Err(ContactSendMessageError::RateLimit { until }) => too_many_requests(until),
         Err(ContactSendMessageError::Send) => CouldNotSendMessageError.into_response(),
         Err(ContactSendMessageError::Other(err)) => internal_server_error(err),
     }
@@ -67,6 +73,7 @@ fn send_message_docs(op: TransformOperation) -> TransformOperation {
         .add_response::<OkResponse>(StatusCode::OK, "The message has been sent.")
         .add_error::<RecaptchaFailedError>()
         .add_error::<CouldNotSendMessageError>()
+        .with(too_many_requests_docs)
         .with(internal_server_error_docs)
 }
 
@@ -1,9 +1,15 @@
 use std::sync::Arc;
 
 use academy_core_mfa_contracts::{
-    MfaDisableError, MfaEnableError, MfaFeatureService, MfaInitializeError,
+    MfaConfirmTotpDeviceError, MfaCreateTotpDeviceError, MfaDeleteTotpDeviceError,
+    MfaDeleteWebauthnCredentialError, MfaDisableError, MfaEnableError, MfaFeatureService,
+    MfaFinishWebauthnRegistrationError, MfaInitializeError, MfaListTotpDevicesError,
+    MfaListWebauthnCredentialsError, MfaStartWebauthnRegistrationError, MfaUpdateTotpDeviceError,
+};
+use academy_models::mfa::{
+    MfaRecoveryCode, TotpCode, TotpDeviceId, TotpDeviceName, WebauthnCredentialId,
+    WebauthnCredentialName,
 };
-use academy_models::mfa::TotpCode;
 use aide::{
     axum::{routing, ApiRouter},
     transform::TransformOperation,
@@ -22,8 +28,16 @@ use crate::{
     docs::TransformOperationExt,
     error_code,
     errors::{auth_error, auth_error_docs, internal_server_error, internal_server_error_docs},
-    extractors::auth::ApiToken,
-    models::{user::PathUserIdOrSelf, OkResponse},
+    extractors::{auth::ApiToken, client_info::ApiClientInfo},
+    models::{
+        mfa::{
+            ApiTotpDevice, ApiTotpDeviceSetup, ApiWebauthnAttestation,
+            ApiWebauthnAuthenticationOptions, ApiWebauthnCredential, ApiWebauthnRegistration,
+            ApiWebauthnRegistrationOptions,
+        },
+        user::{ApiUserIdOrSelf, PathUserIdOrSelf},
+        OkResponse,
+    },
 };
 
 pub const TAG: &str = "MFA";
@@ -36,6 +50,40 @@ pub fn router(service: Arc<impl MfaFeatureService>) -> ApiRouter<()> {
                 .put_with(enable, enable_docs)
                 .delete_with(disable, disable_docs),
         )
+        .api_route(
+            "/auth/users/:user_id/mfa/devices",
+            routing::get_with(list_totp_devices, list_totp_devices_docs)
+                .post_with(create_totp_device, create_totp_device_docs),
+        )
+        .api_route(
+            "/auth/users/:user_id/mfa/devices/:device_id",
+            routing::put_with(confirm_totp_device, confirm_totp_device_docs)
+                .patch_with(update_totp_device, update_totp_device_docs)
+                .delete_with(delete_totp_device, delete_totp_device_docs),
+        )
+        .api_route(
+            "/auth/users/:user_id/mfa/webauthn",
+            routing::get_with(list_webauthn_credentials, list_webauthn_credentials_docs)
+                .post_with(
+                    start_webauthn_registration,
+                    start_webauthn_registration_docs,
+                )
+                .put_with(
+                    finish_webauthn_registration,
+                    finish_webauthn_registration_docs,
+                ),
+        )
+        .api_route(
+            "/auth/users/:user_id/mfa/webauthn/:credential_id",
+            routing::delete_with(delete_webauthn_credential, delete_webauthn_credential_docs),
+        )
+        .api_route(
+            "/auth/mfa/webauthn/authenticate",
+            routing::post_with(
+                start_webauthn_authentication,
+                start_webauthn_authentication_docs,
+            ),
+        )
         .with_state(service)
         .with_path_items(|op| op.tag(TAG))
 }
@@ -95,13 +143,12 @@ async fn enable(
 fn enable_docs(op: TransformOperation) -> TransformOperation {
     op.summary("Enable MFA for the given user.")
         .description(
-            "Generates and returns a recovery code for disabling MFA in case the user loses \
-             access to their MFA authenticator.\n\nAfter enabling MFA, the user is required to \
-             additionally provide a valid TOTP code when logging in.",
+            "If the user has not enabled MFA before, generates and returns a recovery code for \
+             disabling MFA in case the user loses access to their MFA authenticator. Otherwise \
+             `null` is returned.\n\nAfter enabling MFA, the user is required to additionally \
+             provide a valid TOTP code when logging in.",
         )
-        .add_response_with::<String>(StatusCode::OK, "MFA has been enabled.", |op| {
-            op.example("mfa-recovery-code")
-        })
+        .add_response::<Option<MfaRecoveryCode>>(StatusCode::OK, "MFA has been enabled.")
         .add_error::<MfaAlreadyEnabledError>()
         .add_error::<MfaNotInitializedError>()
         .add_error::<InvalidMfaCodeError>()
@@ -113,9 +160,10 @@ fn enable_docs(op: TransformOperation) -> TransformOperation {
 async fn disable(
     service: State<Arc<impl MfaFeatureService>>,
     token: ApiToken,
+    client: ApiClientInfo,
     Path(PathUserIdOrSelf { user_id }): Path<PathUserIdOrSelf>,
 ) -> Response {
-    match service.disable(&token.0, user_id.into()).await {
+    match service.disable(&token.0, user_id.into(), client.0).await {
         Ok(()) => Json(OkResponse).into_response(),
         Err(MfaDisableError::NotEnabled) => MfaNotEnabledError.into_response(),
         Err(MfaDisableError::NotFound) => UserNotFoundError.into_response(),
@@ -133,6 +181,340 @@ fn disable_docs(op: TransformOperation) -> TransformOperation {
         .with(internal_server_error_docs)
 }
 
+async fn list_totp_devices(
+    service: State<Arc<impl MfaFeatureService>>,
+    token: ApiToken,
+    Path(PathUserIdOrSelf { user_id }): Path<PathUserIdOrSelf>,
+) -> Response {
+    match service.list_totp_devices(&token.0, user_id.into()).await {
+        Ok(devices) => Json(
+            devices
+                .into_iter()
+                .map(Into::into)
+                .collect::<Vec<ApiTotpDevice>>(),
+        )
+        .into_response(),
+        Err(MfaListTotpDevicesError::Auth(err)) => auth_error(err),
+        Err(MfaListTotpDevicesError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn list_totp_devices_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Return all TOTP devices of the given user.")
+        .add_response::<Vec<ApiTotpDevice>>(StatusCode::OK, None)
+        .with(auth_error_docs)
+        .with(internal_server_error_docs)
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct CreateTotpDeviceRequest {
+    /// Display name of the new device
+    name: TotpDeviceName,
+}
+
+async fn create_totp_device(
+    service: State<Arc<impl MfaFeatureService>>,
+    token: ApiToken,
+    Path(PathUserIdOrSelf { user_id }): Path<PathUserIdOrSelf>,
+    Json(CreateTotpDeviceRequest { name }): Json<CreateTotpDeviceRequest>,
+) -> Response {
+    match service
+        .create_totp_device(&token.0, user_id.into(), name)
+        .await
+    {
+        Ok(result) => Json(ApiTotpDeviceSetup::from(result)).into_response(),
+        Err(MfaCreateTotpDeviceError::NotFound) => UserNotFoundError.into_response(),
+        Err(MfaCreateTotpDeviceError::Auth(err)) => auth_error(err),
+        Err(MfaCreateTotpDeviceError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn create_totp_device_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Create a new TOTP device for the given user.")
+        .description(
+            "Generates and returns a new TOTP secret, which should be used to configure the \
+             authenticator. The device needs to be confirmed before it can be used for logging \
+             in.",
+        )
+        .add_response::<ApiTotpDeviceSetup>(StatusCode::OK, "The TOTP device has been created.")
+        .add_error::<UserNotFoundError>()
+        .with(auth_error_docs)
+        .with(internal_server_error_docs)
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct TotpDevicePath {
+    user_id: ApiUserIdOrSelf,
+    device_id: TotpDeviceId,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct ConfirmTotpDeviceRequest {
+    /// TOTP code generated by the authenticator
+    code: TotpCode,
+}
+
+async fn confirm_totp_device(
+    service: State<Arc<impl MfaFeatureService>>,
+    token: ApiToken,
+    Path(TotpDevicePath { user_id, device_id }): Path<TotpDevicePath>,
+    Json(ConfirmTotpDeviceRequest { code }): Json<ConfirmTotpDeviceRequest>,
+) -> Response {
+    match service
+        .confirm_totp_device(&token.0, user_id.into(), device_id, code)
+        .await
+    {
+        Ok(recovery_code) => Json(recovery_code).into_response(),
+        Err(MfaConfirmTotpDeviceError::AlreadyEnabled) => {
+            TotpDeviceAlreadyEnabledError.into_response()
+        }
+        Err(MfaConfirmTotpDeviceError::InvalidCode) => InvalidMfaCodeError.into_response(),
+        Err(MfaConfirmTotpDeviceError::NotFound) => TotpDeviceNotFoundError.into_response(),
+        Err(MfaConfirmTotpDeviceError::Auth(err)) => auth_error(err),
+        Err(MfaConfirmTotpDeviceError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn confirm_totp_device_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Confirm the given TOTP device.")
+        .description(
+            "If the user has not enabled MFA before, MFA is enabled and a recovery code for \
+             disabling MFA is returned. Otherwise `null` is returned.",
+        )
+        .add_response::<Option<MfaRecoveryCode>>(
+            StatusCode::OK,
+            "The TOTP device has been confirmed.",
+        )
+        .add_error::<TotpDeviceAlreadyEnabledError>()
+        .add_error::<InvalidMfaCodeError>()
+        .add_error::<TotpDeviceNotFoundError>()
+        .with(auth_error_docs)
+        .with(internal_server_error_docs)
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct UpdateTotpDeviceRequest {
+    /// New display name of the device
+    name: TotpDeviceName,
+}
+
+async fn update_totp_device(
+    service: State<Arc<impl MfaFeatureService>>,
+    token: ApiToken,
+    Path(TotpDevicePath { user_id, device_id }): Path<TotpDevicePath>,
+    Json(UpdateTotpDeviceRequest { name }): Json<UpdateTotpDeviceRequest>,
+) -> Response {
+    match service
+        .update_totp_device(&token.0, user_id.into(), device_id, name)
+        .await
+    {
+        Ok(device) => Json(ApiTotpDevice::from(device)).into_response(),
+        Err(MfaUpdateTotpDeviceError::NotFound) => TotpDeviceNotFoundError.into_response(),
+        Err(MfaUpdateTotpDeviceError::Auth(err)) => auth_error(err),
+        Err(MfaUpdateTotpDeviceError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn update_totp_device_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Rename the given TOTP device.")
+        .add_response::<ApiTotpDevice>(StatusCode::OK, "The TOTP device has been updated.")
+        .add_error::<TotpDeviceNotFoundError>()
+        .with(auth_error_docs)
+        .with(internal_server_error_docs)
+}
+
+async fn delete_totp_device(
+    service: State<Arc<impl MfaFeatureService>>,
+    token: ApiToken,
+    Path(TotpDevicePath { user_id, device_id }): Path<TotpDevicePath>,
+) -> Response {
+    match service
+        .delete_totp_device(&token.0, user_id.into(), device_id)
+        .await
+    {
+        Ok(()) => Json(OkResponse).into_response(),
+        Err(MfaDeleteTotpDeviceError::NotFound) => TotpDeviceNotFoundError.into_response(),
+        Err(MfaDeleteTotpDeviceError::Auth(err)) => auth_error(err),
+        Err(MfaDeleteTotpDeviceError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn delete_totp_device_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Delete the given TOTP device.")
+        .description(
+            "If this is the last second factor of the user, MFA is disabled and the recovery code \
+             is invalidated.",
+        )
+        .add_response::<OkResponse>(StatusCode::OK, "The TOTP device has been deleted.")
+        .add_error::<TotpDeviceNotFoundError>()
+        .with(auth_error_docs)
+        .with(internal_server_error_docs)
+}
+
+async fn list_webauthn_credentials(
+    service: State<Arc<impl MfaFeatureService>>,
+    token: ApiToken,
+    Path(PathUserIdOrSelf { user_id }): Path<PathUserIdOrSelf>,
+) -> Response {
+    match service
+        .list_webauthn_credentials(&token.0, user_id.into())
+        .await
+    {
+        Ok(credentials) => Json(
+            credentials
+                .into_iter()
+                .map(Into::into)
+                .collect::<Vec<ApiWebauthnCredential>>(),
+        )
+        .into_response(),
+        Err(MfaListWebauthnCredentialsError::Auth(err)) => auth_error(err),
+        Err(MfaListWebauthnCredentialsError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn list_webauthn_credentials_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Return all WebAuthn credentials of the given user.")
+        .add_response::<Vec<ApiWebauthnCredential>>(StatusCode::OK, None)
+        .with(auth_error_docs)
+        .with(internal_server_error_docs)
+}
+
+async fn start_webauthn_registration(
+    service: State<Arc<impl MfaFeatureService>>,
+    token: ApiToken,
+    Path(PathUserIdOrSelf { user_id }): Path<PathUserIdOrSelf>,
+) -> Response {
+    match service
+        .start_webauthn_registration(&token.0, user_id.into())
+        .await
+    {
+        Ok(options) => Json(ApiWebauthnRegistrationOptions::from(options)).into_response(),
+        Err(MfaStartWebauthnRegistrationError::NotFound) => UserNotFoundError.into_response(),
+        Err(MfaStartWebauthnRegistrationError::Auth(err)) => auth_error(err),
+        Err(MfaStartWebauthnRegistrationError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn start_webauthn_registration_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Start the registration of a new WebAuthn credential for the given user.")
+        .description(
+            "Returns the options that should be passed to `navigator.credentials.create()`. The \
+             registration needs to be completed before the options expire.",
+        )
+        .add_response::<ApiWebauthnRegistrationOptions>(StatusCode::OK, None)
+        .add_error::<UserNotFoundError>()
+        .with(auth_error_docs)
+        .with(internal_server_error_docs)
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct FinishWebauthnRegistrationRequest {
+    /// Display name of the new credential
+    name: WebauthnCredentialName,
+    /// The credential returned by `navigator.credentials.create()`
+    credential: ApiWebauthnAttestation,
+}
+
+async fn finish_webauthn_registration(
+    service: State<Arc<impl MfaFeatureService>>,
+    token: ApiToken,
+    Path(PathUserIdOrSelf { user_id }): Path<PathUserIdOrSelf>,
+    Json(FinishWebauthnRegistrationRequest { name, credential }): Json<
+        FinishWebauthnRegistrationRequest,
+    >,
+) -> Response {
+    match service
+        .finish_webauthn_registration(&token.0, user_id.into(), name, credential.into())
+        .await
+    {
+        Ok(registration) => Json(ApiWebauthnRegistration::from(registration)).into_response(),
+        Err(MfaFinishWebauthnRegistrationError::InvalidResponse) => {
+            InvalidWebauthnResponseError.into_response()
+        }
+        Err(MfaFinishWebauthnRegistrationError::AlreadyRegistered) => {
+            WebauthnCredentialAlreadyRegisteredError.into_response()
+        }
+        Err(MfaFinishWebauthnRegistrationError::NotFound) => UserNotFoundError.into_response(),
+        Err(MfaFinishWebauthnRegistrationError::Auth(err)) => auth_error(err),
+        Err(MfaFinishWebauthnRegistrationError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn finish_webauthn_registration_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Complete the registration of a new WebAuthn credential for the given user.")
+        .description(
+            "If the user has not enabled MFA before, MFA is enabled and a recovery code for \
+             disabling MFA is returned.\n\nAfter enabling MFA, the user is required to \
+             additionally provide a valid TOTP code or WebAuthn assertion when logging in.",
+        )
+        .add_response::<ApiWebauthnRegistration>(
+            StatusCode::OK,
+            "The WebAuthn credential has been registered.",
+        )
+        .add_error::<InvalidWebauthnResponseError>()
+        .add_error::<WebauthnCredentialAlreadyRegisteredError>()
+        .add_error::<UserNotFoundError>()
+        .with(auth_error_docs)
+        .with(internal_server_error_docs)
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct DeleteWebauthnCredentialPath {
+    user_id: ApiUserIdOrSelf,
+    credential_id: WebauthnCredentialId,
+}
+
+async fn delete_webauthn_credential(
+    service: State<Arc<impl MfaFeatureService>>,
+    token: ApiToken,
+    Path(DeleteWebauthnCredentialPath {
+        user_id,
+        credential_id,
+    }): Path<DeleteWebauthnCredentialPath>,
+) -> Response {
+    match service
+        .delete_webauthn_credential(&token.0, user_id.into(), credential_id)
+        .await
+    {
+        Ok(()) => Json(OkResponse).into_response(),
+        Err(MfaDeleteWebauthnCredentialError::NotFound) => {
+            WebauthnCredentialNotFoundError.into_response()
+        }
+        Err(MfaDeleteWebauthnCredentialError::Auth(err)) => auth_error(err),
+        Err(MfaDeleteWebauthnCredentialError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn delete_webauthn_credential_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Delete the given WebAuthn credential.")
+        .description(
+            "If this is the last second factor of the user, MFA is disabled and the recovery code \
+             is invalidated.",
+        )
+        .add_response::<OkResponse>(StatusCode::OK, "The WebAuthn credential has been deleted.")
+        .add_error::<WebauthnCredentialNotFoundError>()
+        .with(auth_error_docs)
+        .with(internal_server_error_docs)
+}
+
+async fn start_webauthn_authentication(service: State<Arc<impl MfaFeatureService>>) -> Response {
+    match service.start_webauthn_authentication().await {
+        Ok(options) => Json(ApiWebauthnAuthenticationOptions::from(options)).into_response(),
+        Err(err) => internal_server_error(err),
+    }
+}
+
+fn start_webauthn_authentication_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Start a WebAuthn authentication for creating a new session.")
+        .description(
+            "Returns the options that should be passed to `navigator.credentials.get()`. The \
+             resulting credential can be used as the `webauthn` parameter when creating a new \
+             session.",
+        )
+        .add_response::<ApiWebauthnAuthenticationOptions>(StatusCode::OK, None)
+        .with(internal_server_error_docs)
+}
+
 error_code! {
     /// The user has already enabled MFA.
     MfaAlreadyEnabledError(CONFLICT, "MFA already enabled");
@@ -142,4 +524,14 @@ error_code! {
     pub InvalidMfaCodeError(PRECONDITION_FAILED, "Invalid code");
     /// The user has not enabled MFA.
     MfaNotEnabledError(PRECONDITION_FAILED, "MFA not enabled");
+    /// The TOTP device has already been confirmed.
+    TotpDeviceAlreadyEnabledError(CONFLICT, "TOTP device already enabled");
+    /// The TOTP device does not exist.
+    TotpDeviceNotFoundError(NOT_FOUND, "TOTP device not found");
+    /// The response of the WebAuthn authenticator is invalid or the registration has expired.
+    InvalidWebauthnResponseError(PRECONDITION_FAILED, "Invalid WebAuthn response");
+    /// The WebAuthn credential has already been registered.
+    WebauthnCredentialAlreadyRegisteredError(CONFLICT, "WebAuthn credential already registered");
+    /// The WebAuthn credential does not exist.
+    WebauthnCredentialNotFoundError(NOT_FOUND, "WebAuthn credential not found");
 }
@@ -1,12 +1,13 @@
 use std::sync::Arc;
 
 use academy_core_oauth2_contracts::{
-    OAuth2CreateLinkError, OAuth2CreateSessionError, OAuth2CreateSessionResponse,
-    OAuth2DeleteLinkError, OAuth2FeatureService, OAuth2ListLinksError,
+    OAuth2AuthorizeError, OAuth2CreateLinkError, OAuth2CreateSessionError,
+    OAuth2CreateSessionResponse, OAuth2DeleteLinkError, OAuth2FeatureService, OAuth2ListLinksError,
 };
 use academy_models::{
-    oauth2::{OAuth2LinkId, OAuth2RegistrationToken},
+    oauth2::{OAuth2LinkId, OAuth2ProviderId, OAuth2RegistrationToken},
     session::DeviceName,
+    url::Url,
 };
 use aide::{
     axum::{routing, ApiRouter},
@@ -26,9 +27,9 @@ use crate::{
     docs::TransformOperationExt,
     error_code,
     errors::{auth_error, auth_error_docs, internal_server_error, internal_server_error_docs},
-    extractors::{auth::ApiToken, user_agent::UserAgent},
+    extractors::{auth::ApiToken, client_info::ApiClientInfo, user_agent::UserAgent},
     models::{
-        oauth2::{ApiOAuth2Link, ApiOAuth2Login, ApiOAuth2ProviderSummary},
+        oauth2::{ApiOAuth2Link, ApiOAuth2Login, ApiOAuth2ProviderSummary, ApiOAuth2RemoteProfile},
         session::ApiLogin,
         user::{ApiUserIdOrSelf, PathUserIdOrSelf},
         OkResponse,
@@ -43,6 +44,10 @@ pub fn router(service: Arc<impl OAuth2FeatureService>) -> ApiRouter<()> {
             "/auth/oauth/providers",
             routing::get_with(list_providers, list_providers_docs),
         )
+        .api_route(
+            "/auth/oauth/providers/:provider_id/authorize",
+            routing::post_with(authorize, authorize_docs),
+        )
         .api_route(
             "/auth/oauth/links/:user_id",
             routing::get_with(list_links, list_links_docs).post_with(create_link, create_link_docs),
@@ -75,6 +80,49 @@ fn list_providers_docs(op: TransformOperation) -> TransformOperation {
         .add_response::<Vec<ApiOAuth2ProviderSummary>>(StatusCode::OK, None)
 }
 
+#[derive(Deserialize, JsonSchema)]
+struct AuthorizePath {
+    provider_id: OAuth2ProviderId,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct AuthorizeRequest {
+    /// Redirect URI the OAuth2 provider should redirect the user to after
+    /// authentication
+    redirect_uri: Url,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct AuthorizeResponse {
+    /// Remote authorize endpoint URL including all necessary parameters
+    authorize_url: Url,
+}
+
+async fn authorize(
+    service: State<Arc<impl OAuth2FeatureService>>,
+    Path(AuthorizePath { provider_id }): Path<AuthorizePath>,
+    Json(AuthorizeRequest { redirect_uri }): Json<AuthorizeRequest>,
+) -> Response {
+    match service.authorize(provider_id, redirect_uri).await {
+        Ok(authorize_url) => Json(AuthorizeResponse { authorize_url }).into_response(),
+        Err(OAuth2AuthorizeError::InvalidProvider) => ProviderNotFoundError.into_response(),
+        Err(OAuth2AuthorizeError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn authorize_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Start a new OAuth2 authorization request.")
+        .description(
+            "The user has to be redirected to the returned authorize URL. After the user has \
+             been redirected back, the `code` and `state` parameters can be used to create a \
+             session or an OAuth2 link. Authorization requests expire after a few minutes and \
+             can only be used once.",
+        )
+        .add_response::<AuthorizeResponse>(StatusCode::OK, None)
+        .add_error::<ProviderNotFoundError>()
+        .with(internal_server_error_docs)
+}
+
 async fn list_links(
     service: State<Arc<impl OAuth2FeatureService>>,
     token: ApiToken,
@@ -105,15 +153,17 @@ fn list_links_docs(op: TransformOperation) -> TransformOperation {
 async fn create_link(
     service: State<Arc<impl OAuth2FeatureService>>,
     token: ApiToken,
+    client: ApiClientInfo,
     Path(PathUserIdOrSelf { user_id }): Path<PathUserIdOrSelf>,
     Json(login): Json<ApiOAuth2Login>,
 ) -> Response {
     match service
-        .create_link(&token.0, user_id.into(), login.into())
+        .create_link(&token.0, user_id.into(), login.into(), client.0)
         .await
     {
         Ok(link) => Json(ApiOAuth2Link::from(link)).into_response(),
         Err(OAuth2CreateLinkError::InvalidProvider) => ProviderNotFoundError.into_response(),
+        Err(OAuth2CreateLinkError::InvalidState) => InvalidStateError.into_response(),
         Err(OAuth2CreateLinkError::InvalidCode) => InvalidCodeError.into_response(),
         Err(OAuth2CreateLinkError::RemoteAlreadyLinked) => RemoteAlreadyLinkedError.into_response(),
         Err(OAuth2CreateLinkError::NotFound) => UserNotFoundError.into_response(),
@@ -126,6 +176,7 @@ fn create_link_docs(op: TransformOperation) -> TransformOperation {
     op.summary("Create a new OAuth2 link for the given user.")
         .add_response::<ApiOAuth2Link>(StatusCode::OK, "OAuth2 link has been created.")
         .add_error::<ProviderNotFoundError>()
+        .add_error::<InvalidStateError>()
         .add_error::<InvalidCodeError>()
         .add_error::<RemoteAlreadyLinkedError>()
         .add_error::<UserNotFoundError>()
@@ -142,9 +193,13 @@ struct DeleteLinkPath {
 async fn delete_link(
     service: State<Arc<impl OAuth2FeatureService>>,
     token: ApiToken,
+    client: ApiClientInfo,
     Path(DeleteLinkPath { user_id, link_id }): Path<DeleteLinkPath>,
 ) -> Response {
-    match service.delete_link(&token.0, user_id.into(), link_id).await {
+    match service
+        .delete_link(&token.0, user_id.into(), link_id, client.0)
+        .await
+    {
         Ok(()) => Json(OkResponse).into_response(),
         Err(OAuth2DeleteLinkError::NotFound) => LinkNotFoundError.into_response(),
         Err(OAuth2DeleteLinkError::CannotRemoveLink) => {
@@ -175,17 +230,22 @@ struct CreateSessionLoginResponse {
 #[derive(Serialize, JsonSchema)]
 struct CreateSessionRegistrationTokenResponse {
     register_token: OAuth2RegistrationToken,
+    /// Profile data of the remote user which can be used to pre-fill the
+    /// registration form
+    profile: ApiOAuth2RemoteProfile,
 }
 
 async fn create_session(
     service: State<Arc<impl OAuth2FeatureService>>,
     user_agent: UserAgent,
+    client: ApiClientInfo,
     Json(login): Json<ApiOAuth2Login>,
 ) -> Response {
     match service
         .create_session(
             login.into(),
             user_agent.0.map(DeviceName::from_string_truncated),
+            client.0,
         )
         .await
     {
@@ -193,10 +253,15 @@ async fn create_session(
             login: ApiLogin::from(*login),
         })
         .into_response(),
-        Ok(OAuth2CreateSessionResponse::RegistrationToken(register_token)) => {
-            Json(CreateSessionRegistrationTokenResponse { register_token }).into_response()
+        Ok(OAuth2CreateSessionResponse::Registration { token, profile }) => {
+            Json(CreateSessionRegistrationTokenResponse {
+                register_token: token,
+                profile: profile.into(),
+            })
+            .into_response()
         }
         Err(OAuth2CreateSessionError::InvalidProvider) => ProviderNotFoundError.into_response(),
+        Err(OAuth2CreateSessionError::InvalidState) => InvalidStateError.into_response(),
         Err(OAuth2CreateSessionError::InvalidCode) => InvalidCodeError.into_response(),
         Err(OAuth2CreateSessionError::UserDisabled) => UserDisabledError.into_response(),
         Err(OAuth2CreateSessionError::Other(err)) => internal_server_error(err),
@@ -207,7 +272,8 @@ fn create_session_docs(op: TransformOperation) -> TransformOperation {
     op.summary("Create a session via OAuth2")
         .description(
             "If the remote user is not yet linked to a local user account, a registration token \
-             is returned instead.",
+             is returned instead, together with the remote user's profile data which can be used \
+             to pre-fill the registration form.",
         )
         .add_response::<CreateSessionLoginResponse>(
             StatusCode::OK,
@@ -218,6 +284,7 @@ fn create_session_docs(op: TransformOperation) -> TransformOperation {
             "A registration token has been generated.",
         )
         .add_error::<ProviderNotFoundError>()
+        .add_error::<InvalidStateError>()
         .add_error::<InvalidCodeError>()
         .add_error::<UserDisabledError>()
         .with(internal_server_error_docs)
@@ -226,6 +293,8 @@ fn create_session_docs(op: TransformOperation) -> TransformOperation {
 error_code! {
     /// The OAuth2 provider does not exist.
     ProviderNotFoundError(NOT_FOUND, "Provider not found");
+    /// The authorization request does not exist or has expired.
+    InvalidStateError(UNAUTHORIZED, "Invalid state");
     /// The authorization code is invalid.
     InvalidCodeError(UNAUTHORIZED, "Invalid code");
     /// The remote user has already been linked to another account.
@@ -1,23 +1,25 @@
 use std::sync::Arc;
 
 use academy_core_session_contracts::{
-    SessionCreateCommand, SessionCreateError, SessionDeleteByUserError, SessionDeleteCurrentError,
-    SessionDeleteError, SessionFeatureService, SessionGetCurrentError, SessionImpersonateError,
-    SessionListByUserError, SessionRefreshError,
+    SessionCreateByLoginLinkCommand, SessionCreateByLoginLinkError, SessionCreateCommand,
+    SessionCreateError, SessionDeleteByUserError, SessionDeleteCurrentError, SessionDeleteError,
+    SessionFeatureService, SessionGetCurrentError, SessionImpersonateError, SessionListByUserError,
+    SessionListResult, SessionRefreshError, SessionRequestLoginLinkError,
 };
 use academy_models::{
     auth::RefreshToken,
+    email_address::EmailAddress,
     mfa::{MfaAuthentication, MfaRecoveryCode, TotpCode},
     session::{DeviceName, SessionId},
     user::{UserNameOrEmailAddress, UserPassword},
-    RecaptchaResponse,
+    RecaptchaResponse, VerificationCode,
 };
 use aide::{
     axum::{routing, ApiRouter},
     transform::TransformOperation,
 };
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
@@ -34,18 +36,21 @@ use crate::{
     error_code,
     errors::{
         auth_error, auth_error_docs, internal_server_error, internal_server_error_docs,
-        RecaptchaFailedError,
+        too_many_requests, too_many_requests_docs, RecaptchaFailedError,
     },
-    extractors::{auth::ApiToken, user_agent::UserAgent},
+    extractors::{auth::ApiToken, client_info::ApiClientInfo, user_agent::UserAgent},
     models::{
+        mfa::ApiWebauthnAssertion,
         session::{ApiLogin, ApiSession},
         user::{ApiUserIdOrSelf, PathUserId, PathUserIdOrSelf},
-        OkResponse, StringOption,
+        ApiPagination, OkResponse, StringOption,
     },
 };
 
 pub const TAG: &str = "Session";
 
+const NEXT_CURSOR_HEADER: &str = "X-Next-Cursor";
+
 pub fn router(service: Arc<impl SessionFeatureService>) -> ApiRouter<()> {
     ApiRouter::new()
         .api_route(
@@ -55,6 +60,11 @@ pub fn router(service: Arc<impl SessionFeatureService>) -> ApiRouter<()> {
                 .delete_with(delete_current, delete_current_docs),
         )
         .api_route("/auth/sessions", routing::post_with(create, create_docs))
+        .api_route(
+            "/auth/login_link",
+            routing::post_with(request_login_link, request_login_link_docs)
+                .put_with(create_by_login_link, create_by_login_link_docs),
+        )
         .api_route(
             "/auth/sessions/:user_id",
             routing::get_with(list_by_user, list_by_user_docs)
@@ -91,15 +101,25 @@ async fn list_by_user(
     session_service: State<Arc<impl SessionFeatureService>>,
     token: ApiToken,
     Path(PathUserIdOrSelf { user_id }): Path<PathUserIdOrSelf>,
+    Query(pagination): Query<ApiPagination>,
 ) -> Response {
-    match session_service.list_by_user(&token.0, user_id.into()).await {
-        Ok(sessions) => Json(
-            sessions
-                .into_iter()
-                .map(Into::into)
-                .collect::<Vec<ApiSession>>(),
+    match session_service
+        .list_by_user(&token.0, user_id.into(), pagination.into())
+        .await
+    {
+        Ok(SessionListResult {
+            sessions,
+            next_cursor,
+        }) => (
+            next_cursor.map(|cursor| [(NEXT_CURSOR_HEADER, cursor.to_string())]),
+            Json(
+                sessions
+                    .into_iter()
+                    .map(Into::into)
+                    .collect::<Vec<ApiSession>>(),
+            ),
         )
-        .into_response(),
+            .into_response(),
         Err(SessionListByUserError::Auth(err)) => auth_error(err),
         Err(SessionListByUserError::Other(err)) => internal_server_error(err),
     }
@@ -107,6 +127,10 @@ async fn list_by_user(
 
 fn list_by_user_docs(op: TransformOperation) -> TransformOperation {
     op.summary("Return all sessions of the given user.")
+        .description(
+            "Sessions are ordered by their creation time. If there may be more sessions, the \
+             cursor of the next page is returned in the `X-Next-Cursor` response header.",
+        )
         .add_response::<Vec<ApiSession>>(StatusCode::OK, None)
         .with(auth_error_docs)
         .with(internal_server_error_docs)
@@ -118,17 +142,21 @@ struct CreateRequest {
     password: UserPassword,
     mfa_code: StringOption<TotpCode>,
     recovery_code: StringOption<MfaRecoveryCode>,
+    /// The credential returned by `navigator.credentials.get()`
+    webauthn: Option<ApiWebauthnAssertion>,
     recaptcha_response: StringOption<RecaptchaResponse>,
 }
 
 async fn create(
     session_service: State<Arc<impl SessionFeatureService>>,
     user_agent: UserAgent,
+    client: ApiClientInfo,
     Json(CreateRequest {
         name_or_email,
         password,
         mfa_code,
         recovery_code,
+        webauthn,
         recaptcha_response,
     }): Json<CreateRequest>,
 ) -> Response {
@@ -141,13 +169,16 @@ async fn create(
                 mfa: MfaAuthentication {
                     totp_code: mfa_code.into(),
                     recovery_code: recovery_code.into(),
+                    webauthn: webauthn.map(Into::into),
                 },
             },
             recaptcha_response.into(),
+            client.0,
         )
         .await
     {
         Ok(result) => Json(ApiLogin::from(result)).into_response(),
+        Err(SessionCreateError::RateLimit { until }) => too_many_requests(until),
         Err(SessionCreateError::InvalidCredentials) => InvalidCredentialsError.into_response(),
         Err(SessionCreateError::MfaFailed) => InvalidMfaCodeError.into_response(),
         Err(SessionCreateError::UserDisabled) => UserDisabledError.into_response(),
@@ -159,24 +190,130 @@ async fn create(
 fn create_docs(op: TransformOperation) -> TransformOperation {
     op.summary("Create a new session via username/password authentication.")
         .description(
-            "If the user has MFA enabled, the current TOTP needs to provided. Alternatively, the \
+            "If the user has MFA enabled, the current TOTP or a WebAuthn assertion (see \
+             `POST /auth/mfa/webauthn/authenticate`) needs to provided. Alternatively, the \
              recovery code can be used to disable MFA.\n\nAfter too many failed login attempts, a \
-             valid reCAPTCHA response is required, if reCAPTCHA is enabled.",
+             valid reCAPTCHA response is required, if reCAPTCHA is enabled. If the failed \
+             attempts continue, the account and the client IP address are temporarily locked out.",
         )
         .add_response::<ApiLogin>(StatusCode::OK, "A new session has been created.")
         .add_error::<InvalidCredentialsError>()
         .add_error::<InvalidMfaCodeError>()
         .add_error::<UserDisabledError>()
         .add_error::<RecaptchaFailedError>()
+        .with(too_many_requests_docs)
+        .with(internal_server_error_docs)
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct RequestLoginLinkRequest {
+    email: EmailAddress,
+    recaptcha_response: StringOption<RecaptchaResponse>,
+}
+
+async fn request_login_link(
+    session_service: State<Arc<impl SessionFeatureService>>,
+    Json(RequestLoginLinkRequest {
+        email,
+        recaptcha_response,
+    }): Json<RequestLoginLinkRequest>,
+) -> Response {
+    match session_service
+        .request_login_link(email, recaptcha_response.into())
+        .await
+    {
+        Ok(()) => Json(OkResponse).into_response(),
+        Err(SessionRequestLoginLinkError::Recaptcha) => RecaptchaFailedError.into_response(),
+        Err(SessionRequestLoginLinkError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn request_login_link_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Request a login link email.")
+        .description(
+            "If a user with the given email address exists, a single-use login link is sent to \
+             this address.\n\nAfter too many requests, a valid reCAPTCHA response is required, \
+             if reCAPTCHA is enabled.",
+        )
+        .add_response::<OkResponse>(StatusCode::OK, "The user has been sent a login link email.")
+        .add_error::<RecaptchaFailedError>()
+        .with(internal_server_error_docs)
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct CreateByLoginLinkRequest {
+    code: VerificationCode,
+    mfa_code: StringOption<TotpCode>,
+    recovery_code: StringOption<MfaRecoveryCode>,
+    /// The credential returned by `navigator.credentials.get()`
+    webauthn: Option<ApiWebauthnAssertion>,
+    recaptcha_response: StringOption<RecaptchaResponse>,
+}
+
+async fn create_by_login_link(
+    session_service: State<Arc<impl SessionFeatureService>>,
+    user_agent: UserAgent,
+    client: ApiClientInfo,
+    Json(CreateByLoginLinkRequest {
+        code,
+        mfa_code,
+        recovery_code,
+        webauthn,
+        recaptcha_response,
+    }): Json<CreateByLoginLinkRequest>,
+) -> Response {
+    match session_service
+        .create_session_by_login_link(
+            SessionCreateByLoginLinkCommand {
+                code,
+                device_name: user_agent.0.map(DeviceName::from_string_truncated),
+                mfa: MfaAuthentication {
+                    totp_code: mfa_code.into(),
+                    recovery_code: recovery_code.into(),
+                    webauthn: webauthn.map(Into::into),
+                },
+            },
+            recaptcha_response.into(),
+            client.0,
+        )
+        .await
+    {
+        Ok(result) => Json(ApiLogin::from(result)).into_response(),
+        Err(SessionCreateByLoginLinkError::InvalidCode) => InvalidLoginLinkError.into_response(),
+        Err(SessionCreateByLoginLinkError::MfaFailed) => InvalidMfaCodeError.into_response(),
+        Err(SessionCreateByLoginLinkError::UserDisabled) => UserDisabledError.into_response(),
+        Err(SessionCreateByLoginLinkError::Recaptcha) => RecaptchaFailedError.into_response(),
+        Err(SessionCreateByLoginLinkError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn create_by_login_link_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Create a new session via login link.")
+        .description(
+            "The login link is invalidated after the session has been created. If the user has \
+             MFA enabled, the current TOTP or a WebAuthn assertion (see \
+             `POST /auth/mfa/webauthn/authenticate`) needs to provided. Alternatively, the \
+             recovery code can be used to disable MFA.\n\nAfter too many failed login attempts, \
+             a valid reCAPTCHA response is required, if reCAPTCHA is enabled.",
+        )
+        .add_response::<ApiLogin>(StatusCode::OK, "A new session has been created.")
+        .add_error::<InvalidLoginLinkError>()
+        .add_error::<InvalidMfaCodeError>()
+        .add_error::<UserDisabledError>()
+        .add_error::<RecaptchaFailedError>()
         .with(internal_server_error_docs)
 }
 
 async fn impersonate(
     session_service: State<Arc<impl SessionFeatureService>>,
     token: ApiToken,
+    client: ApiClientInfo,
     Path(PathUserId { user_id }): Path<PathUserId>,
 ) -> Response {
-    match session_service.impersonate(&token.0, user_id).await {
+    match session_service
+        .impersonate(&token.0, user_id, client.0)
+        .await
+    {
         Ok(login) => Json(ApiLogin::from(login)).into_response(),
         Err(SessionImpersonateError::NotFound) => UserNotFoundError.into_response(),
         Err(SessionImpersonateError::Auth(err)) => auth_error(err),
@@ -199,9 +336,13 @@ struct RefreshRequest {
 
 async fn refresh(
     session_service: State<Arc<impl SessionFeatureService>>,
+    client: ApiClientInfo,
     Json(RefreshRequest { refresh_token }): Json<RefreshRequest>,
 ) -> Response {
-    match session_service.refresh_session(&refresh_token).await {
+    match session_service
+        .refresh_session(&refresh_token, client.0)
+        .await
+    {
         Ok(login) => Json(ApiLogin::from(login)).into_response(),
         Err(SessionRefreshError::InvalidRefreshToken) => InvalidRefreshTokenError.into_response(),
         Err(SessionRefreshError::Other(err)) => internal_server_error(err),
@@ -211,7 +352,8 @@ async fn refresh(
 fn refresh_docs(op: TransformOperation) -> TransformOperation {
     op.summary("Refresh session via refresh token")
         .description(
-            "Generates and returns a new access/refresh token pair and invalidates the old tokens.",
+            "Generates and returns a new access/refresh token pair and invalidates the old tokens. \
+             If an already used refresh token is presented again, the whole session is revoked.",
         )
         .add_response::<ApiLogin>(StatusCode::OK, "The session has been refreshed.")
         .add_error::<InvalidRefreshTokenError>()
@@ -298,4 +440,6 @@ error_code! {
     SessionNotFoundError(NOT_FOUND, "Session not found");
     /// The refresh token is invalid or has expired.
     InvalidRefreshTokenError(UNAUTHORIZED, "Invalid refresh token");
+    /// The login link is invalid or has expired.
+    InvalidLoginLinkError(UNAUTHORIZED, "Invalid login link");
 }
@@ -1,29 +1,34 @@
-use std::sync::Arc;
+use std::{collections::BTreeSet, sync::Arc};
 
 use academy_core_user_contracts::{
     user::{UserListQuery, UserListResult},
-    PasswordUpdate, UserCreateError, UserCreateRequest, UserDeleteError, UserFeatureService,
-    UserGetError, UserListError, UserRequestPasswordResetError, UserRequestVerificationEmailError,
-    UserResetPasswordError, UserUpdateError, UserUpdateRequest, UserUpdateUserRequest,
-    UserVerifyEmailError, UserVerifyNewsletterSubscriptionError,
+    PasswordUpdate, UserCreateError, UserCreateRequest, UserDeleteAvatarError, UserDeleteError,
+    UserDownloadExportError, UserExportError, UserExportResult, UserFeatureService, UserGetError,
+    UserGetPublicProfileError, UserListError, UserProfileSearchResult,
+    UserRequestPasswordResetError, UserRequestVerificationEmailError, UserResetPasswordError,
+    UserRestoreError, UserUpdateAvatarError, UserUpdateError, UserUpdateRequest,
+    UserUpdateUserRequest, UserVerifyEmailError, UserVerifyNewsletterSubscriptionError,
 };
 use academy_models::{
     email_address::EmailAddress,
     oauth2::OAuth2RegistrationToken,
+    pagination::PaginationCursor,
+    role::Role,
     session::DeviceName,
     user::{
         UserBio, UserCity, UserCountry, UserDisplayName, UserFirstName, UserInvoiceInfo,
-        UserLastName, UserName, UserPassword, UserProfilePatch, UserStreet, UserTags, UserVatId,
-        UserZipCode,
+        UserLastName, UserName, UserPassword, UserPasswordPolicyViolation, UserProfilePatch,
+        UserStreet, UserTags, UserVatId, UserZipCode,
     },
-    RecaptchaResponse, VerificationCode,
+    RecaptchaResponse, SearchTerm, VerificationCode,
 };
 use aide::{
     axum::{routing, ApiRouter},
     transform::TransformOperation,
 };
 use axum::{
-    extract::{Path, Query, State},
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
@@ -37,30 +42,53 @@ use crate::{
     error_code,
     errors::{
         auth_error, auth_error_docs, internal_server_error, internal_server_error_docs,
-        PermissionDeniedError, RecaptchaFailedError,
+        too_many_requests, too_many_requests_docs, ApiErrorCode, PermissionDeniedError,
+        RecaptchaFailedError,
     },
-    extractors::{auth::ApiToken, user_agent::UserAgent},
+    extractors::{auth::ApiToken, client_info::ApiClientInfo, user_agent::UserAgent},
     models::{
         session::ApiLogin,
-        user::{ApiUser, ApiUserFilter, ApiUserIdOrSelf, ApiUserPasswordOrEmpty, PathUserIdOrSelf},
-        ApiPaginationSlice, OkResponse, StringOption,
+        user::{
+            ApiUser, ApiUserExport, ApiUserFilter, ApiUserIdOrSelf, ApiUserPasswordOrEmpty,
+            ApiUserProfileVisibility, ApiUserPublicProfile, ApiUserSort, PathUserId,
+            PathUserIdOrSelf,
+        },
+        ApiPagination, ApiPaginationSlice, OkResponse, StringOption,
     },
 };
 
 pub const TAG: &str = "User";
 
+/// Maximum size of avatar uploads accepted by the HTTP server (the actual limit
+/// is configured in the user feature)
+const AVATAR_BODY_LIMIT: usize = 16 * 1024 * 1024;
+
 pub fn router(service: Arc<impl UserFeatureService>) -> ApiRouter<()> {
     ApiRouter::new()
         .api_route(
             "/auth/users",
             routing::get_with(list, list_docs).post_with(create, create_docs),
         )
+        .api_route(
+            "/auth/profiles",
+            routing::get_with(search_profiles, search_profiles_docs),
+        )
+        .api_route(
+            "/auth/profiles/:user_id",
+            routing::get_with(get_public_profile, get_public_profile_docs),
+        )
         .api_route(
             "/auth/users/:user_id",
             routing::get_with(get, get_docs)
                 .patch_with(update, update_docs)
                 .delete_with(delete, delete_docs),
         )
+        .api_route(
+            "/auth/users/:user_id/avatar",
+            routing::put_with(update_avatar, update_avatar_docs)
+                .delete_with(delete_avatar, delete_avatar_docs)
+                .layer(DefaultBodyLimit::max(AVATAR_BODY_LIMIT)),
+        )
         .api_route(
             "/auth/users/:user_id/email",
             routing::post_with(request_verification_email, request_verification_email_docs)
@@ -73,6 +101,18 @@ pub fn router(service: Arc<impl UserFeatureService>) -> ApiRouter<()> {
                 verify_newsletter_subscription_docs,
             ),
         )
+        .api_route(
+            "/auth/users/:user_id/export",
+            routing::get_with(export, export_docs),
+        )
+        .api_route(
+            "/auth/user_exports/:code",
+            routing::get_with(download_export, download_export_docs),
+        )
+        .api_route(
+            "/auth/user_restore",
+            routing::put_with(restore, restore_docs),
+        )
         .api_route(
             "/auth/password_reset",
             routing::post_with(request_password_reset, request_password_reset_docs)
@@ -88,19 +128,23 @@ struct ListResult {
     total: u64,
     /// The paginated list of users matching the given query
     users: Vec<ApiUser>,
+    /// The cursor of the next page (`null` if this is the last page)
+    next_cursor: Option<PaginationCursor>,
 }
 
 async fn list(
     user_service: State<Arc<impl UserFeatureService>>,
     token: ApiToken,
-    Query(pagination): Query<ApiPaginationSlice>,
+    Query(pagination): Query<ApiPagination>,
     Query(filter): Query<ApiUserFilter>,
+    Query(sort): Query<ApiUserSort>,
 ) -> Response {
     match user_service
         .list_users(
             &token.0,
             UserListQuery {
                 filter: filter.into(),
+                sort: sort.into(),
                 pagination: pagination.into(),
             },
         )
@@ -109,11 +153,14 @@ async fn list(
         Ok(UserListResult {
             total,
             user_composites: users,
+            next_cursor,
         }) => Json(ListResult {
             total,
             users: users.into_iter().map(Into::into).collect(),
+            next_cursor,
         })
         .into_response(),
+        Err(UserListError::InvalidCursor) => InvalidCursorError.into_response(),
         Err(UserListError::Auth(err)) => auth_error(err),
         Err(UserListError::Other(err)) => internal_server_error(err),
     }
@@ -121,11 +168,80 @@ async fn list(
 
 fn list_docs(op: TransformOperation) -> TransformOperation {
     op.summary("Return all users matching the given query.")
+        .description(
+            "Users are ordered by creation time unless specified otherwise via `sort_by` and \
+             `sort_direction`. Timestamps are given in seconds since the Unix epoch.",
+        )
         .add_response::<ListResult>(StatusCode::OK, None)
+        .add_error::<InvalidCursorError>()
         .with(auth_error_docs)
         .with(internal_server_error_docs)
 }
 
+#[derive(Deserialize, JsonSchema)]
+struct SearchProfilesQuery {
+    /// The full-text search query. Supports quoted phrases, `or` and `-` to
+    /// exclude words.
+    query: SearchTerm,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct SearchProfilesResult {
+    /// The total number of profiles matching the given query
+    total: u64,
+    /// The paginated list of profiles matching the given query, ordered by
+    /// relevance
+    profiles: Vec<ApiUserPublicProfile>,
+}
+
+async fn search_profiles(
+    user_service: State<Arc<impl UserFeatureService>>,
+    Query(SearchProfilesQuery { query }): Query<SearchProfilesQuery>,
+    Query(pagination): Query<ApiPaginationSlice>,
+) -> Response {
+    match user_service.search_profiles(query, pagination.into()).await {
+        Ok(UserProfileSearchResult { total, profiles }) => Json(SearchProfilesResult {
+            total,
+            profiles: profiles.into_iter().map(Into::into).collect(),
+        })
+        .into_response(),
+        Err(err) => internal_server_error(err),
+    }
+}
+
+fn search_profiles_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Search the public profiles of all users.")
+        .description(
+            "Matches in the display name are ranked higher than matches in the tags, which are \
+             ranked higher than matches in the bio. Disabled and deleted users are never \
+             returned.",
+        )
+        .add_response::<SearchProfilesResult>(StatusCode::OK, None)
+        .with(internal_server_error_docs)
+}
+
+async fn get_public_profile(
+    user_service: State<Arc<impl UserFeatureService>>,
+    Path(PathUserId { user_id }): Path<PathUserId>,
+) -> Response {
+    match user_service.get_public_profile(user_id).await {
+        Ok(profile) => Json(ApiUserPublicProfile::from(profile)).into_response(),
+        Err(UserGetPublicProfileError::NotFound) => UserNotFoundError.into_response(),
+        Err(UserGetPublicProfileError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn get_public_profile_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Return the public profile of the given user.")
+        .description(
+            "Only the fields the user has made public via `profile_visibility` are included. \
+             Disabled and deleted users have no public profile.",
+        )
+        .add_response::<ApiUserPublicProfile>(StatusCode::OK, None)
+        .add_error::<UserNotFoundError>()
+        .with(internal_server_error_docs)
+}
+
 async fn get(
     user_service: State<Arc<impl UserFeatureService>>,
     token: ApiToken,
@@ -160,6 +276,7 @@ struct CreateRequest {
 async fn create(
     user_service: State<Arc<impl UserFeatureService>>,
     user_agent: UserAgent,
+    client: ApiClientInfo,
     Json(CreateRequest {
         name,
         display_name,
@@ -180,6 +297,7 @@ async fn create(
             },
             user_agent.0.map(DeviceName::from_string_truncated),
             recaptcha_response.into(),
+            client.0,
         )
         .await
     {
@@ -188,6 +306,7 @@ async fn create(
         Err(UserCreateError::EmailConflict) => EmailAlreadyExistsError.into_response(),
         Err(UserCreateError::Recaptcha) => RecaptchaFailedError.into_response(),
         Err(UserCreateError::NoLoginMethod) => NoLoginMethodError.into_response(),
+        Err(UserCreateError::WeakPassword(violations)) => weak_password_error(violations),
         Err(UserCreateError::InvalidOAuthRegistrationToken) => {
             InvalidOAuthTokenError.into_response()
         }
@@ -204,6 +323,7 @@ fn create_docs(op: TransformOperation) -> TransformOperation {
         .add_error::<EmailAlreadyExistsError>()
         .add_error::<RecaptchaFailedError>()
         .add_error::<NoLoginMethodError>()
+        .with(weak_password_error_docs)
         .add_error::<InvalidOAuthTokenError>()
         .add_error::<RemoteAlreadyLinkedError>()
         .with(internal_server_error_docs)
@@ -217,9 +337,10 @@ struct UpdateRequest {
     email_verified: Option<bool>,
     password: Option<ApiUserPasswordOrEmpty>,
     enabled: Option<bool>,
-    admin: Option<bool>,
+    roles: Option<BTreeSet<Role>>,
     description: StringOption<UserBio>,
     tags: Option<UserTags>,
+    profile_visibility: Option<ApiUserProfileVisibility>,
     newsletter: Option<bool>,
     business: Option<bool>,
     first_name: StringOption<UserFirstName>,
@@ -234,6 +355,7 @@ struct UpdateRequest {
 async fn update(
     user_service: State<Arc<impl UserFeatureService>>,
     token: ApiToken,
+    client: ApiClientInfo,
     Path(PathUserIdOrSelf { user_id }): Path<PathUserIdOrSelf>,
     Json(UpdateRequest {
         name,
@@ -242,9 +364,10 @@ async fn update(
         email_verified,
         password,
         enabled,
-        admin,
+        roles,
         description,
         tags,
+        profile_visibility,
         newsletter,
         business,
         first_name,
@@ -272,13 +395,14 @@ async fn update(
                         })
                         .into(),
                     enabled: enabled.into(),
-                    admin: admin.into(),
+                    roles: roles.into(),
                     newsletter: newsletter.into(),
                 },
                 profile: UserProfilePatch {
                     display_name: Option::from(display_name).into(),
                     bio: Option::from(description).into(),
                     tags: tags.into(),
+                    visibility: profile_visibility.map(Into::into).into(),
                 },
                 invoice_info: UserInvoiceInfo {
                     business,
@@ -291,6 +415,7 @@ async fn update(
                     vat_id: vat_id.into(),
                 },
             },
+            client.0,
         )
         .await
     {
@@ -301,6 +426,7 @@ async fn update(
         Err(UserUpdateError::CannotRemovePassword) => {
             CannotDeleteLastLoginMethodError.into_response()
         }
+        Err(UserUpdateError::WeakPassword(violations)) => weak_password_error(violations),
         Err(
             UserUpdateError::CannotDisableSelf
             | UserUpdateError::CannotDemoteSelf
@@ -320,6 +446,7 @@ fn update_docs(op: TransformOperation) -> TransformOperation {
         .add_error::<UserAlreadyExistsError>()
         .add_error::<EmailAlreadyExistsError>()
         .add_error::<CannotDeleteLastLoginMethodError>()
+        .with(weak_password_error_docs)
         .add_error::<PermissionDeniedError>()
         .add_error::<NoEmailError>()
         .add_error::<InvalidVatIdError>()
@@ -327,12 +454,84 @@ fn update_docs(op: TransformOperation) -> TransformOperation {
         .with(internal_server_error_docs)
 }
 
+async fn update_avatar(
+    user_service: State<Arc<impl UserFeatureService>>,
+    token: ApiToken,
+    client: ApiClientInfo,
+    Path(PathUserIdOrSelf { user_id }): Path<PathUserIdOrSelf>,
+    data: Bytes,
+) -> Response {
+    match user_service
+        .update_avatar(&token.0, user_id.into(), data.into(), client.0)
+        .await
+    {
+        Ok(user) => Json(ApiUser::from(user)).into_response(),
+        Err(UserUpdateAvatarError::NotFound) => UserNotFoundError.into_response(),
+        Err(UserUpdateAvatarError::TooLarge) => AvatarTooLargeError.into_response(),
+        Err(UserUpdateAvatarError::UnsupportedFormat) => {
+            UnsupportedAvatarFormatError.into_response()
+        }
+        Err(UserUpdateAvatarError::InvalidImage) => InvalidAvatarError.into_response(),
+        Err(UserUpdateAvatarError::InvalidDimensions) => {
+            InvalidAvatarDimensionsError.into_response()
+        }
+        Err(UserUpdateAvatarError::Auth(err)) => auth_error(err),
+        Err(UserUpdateAvatarError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn update_avatar_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Upload a new avatar for the given user.")
+        .description(
+            "The request body must contain a PNG, JPEG or WebP image. The image is cropped to a \
+             square and stored in several sizes, which are returned in the `avatar` field of the \
+             user. A previously uploaded avatar is removed.",
+        )
+        .add_response::<ApiUser>(StatusCode::OK, "The avatar has been updated.")
+        .add_error::<UserNotFoundError>()
+        .add_error::<AvatarTooLargeError>()
+        .add_error::<UnsupportedAvatarFormatError>()
+        .add_error::<InvalidAvatarError>()
+        .add_error::<InvalidAvatarDimensionsError>()
+        .with(auth_error_docs)
+        .with(internal_server_error_docs)
+}
+
+async fn delete_avatar(
+    user_service: State<Arc<impl UserFeatureService>>,
+    token: ApiToken,
+    client: ApiClientInfo,
+    Path(PathUserIdOrSelf { user_id }): Path<PathUserIdOrSelf>,
+) -> Response {
+    match user_service
+        .delete_avatar(&token.0, user_id.into(), client.0)
+        .await
+    {
+        Ok(user) => Json(ApiUser::from(user)).into_response(),
+        Err(UserDeleteAvatarError::NotFound) => UserNotFoundError.into_response(),
+        Err(UserDeleteAvatarError::Auth(err)) => auth_error(err),
+        Err(UserDeleteAvatarError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn delete_avatar_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Remove the avatar of the given user.")
+        .add_response::<ApiUser>(StatusCode::OK, "The avatar has been removed.")
+        .add_error::<UserNotFoundError>()
+        .with(auth_error_docs)
+        .with(internal_server_error_docs)
+}
+
 async fn delete(
     user_service: State<Arc<impl UserFeatureService>>,
     token: ApiToken,
+    client: ApiClientInfo,
     Path(PathUserIdOrSelf { user_id }): Path<PathUserIdOrSelf>,
 ) -> Response {
-    match user_service.delete_user(&token.0, user_id.into()).await {
+    match user_service
+        .delete_user(&token.0, user_id.into(), client.0)
+        .await
+    {
         Ok(()) => Json(OkResponse).into_response(),
         Err(UserDeleteError::NotFound) => UserNotFoundError.into_response(),
         Err(UserDeleteError::Auth(err)) => auth_error(err),
@@ -341,8 +540,13 @@ async fn delete(
 }
 
 fn delete_docs(op: TransformOperation) -> TransformOperation {
-    op.summary("Delete the given user.")
-        .add_response::<OkResponse>(StatusCode::OK, "The user has been deleted.")
+    op.summary("Schedule the given user for deletion.")
+        .description(
+            "The user is logged out and can no longer log in. The account is deleted permanently \
+             after a grace period, unless the deletion is cancelled using the link sent to the \
+             user's email address.",
+        )
+        .add_response::<OkResponse>(StatusCode::OK, "The user has been scheduled for deletion.")
         .add_error::<UserNotFoundError>()
         .with(auth_error_docs)
         .with(internal_server_error_docs)
@@ -381,6 +585,61 @@ fn request_verification_email_docs(op: TransformOperation) -> TransformOperation
         .with(internal_server_error_docs)
 }
 
+async fn export(
+    service: State<Arc<impl UserFeatureService>>,
+    token: ApiToken,
+    Path(PathUserIdOrSelf { user_id }): Path<PathUserIdOrSelf>,
+) -> Response {
+    match service.export_user(&token.0, user_id.into()).await {
+        Ok(UserExportResult::Complete(export)) => {
+            Json(ApiUserExport::from(*export)).into_response()
+        }
+        Ok(UserExportResult::Scheduled) => (StatusCode::ACCEPTED, Json(OkResponse)).into_response(),
+        Err(UserExportError::NotFound) => UserNotFoundError.into_response(),
+        Err(UserExportError::Auth(err)) => auth_error(err),
+        Err(UserExportError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn export_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Export all data stored about the given user.")
+        .description(
+            "If the export is too large to be returned directly, it is prepared in the background \
+             and a download link is sent to the user's email address instead.",
+        )
+        .add_response::<ApiUserExport>(StatusCode::OK, "The user's data.")
+        .add_response::<OkResponse>(
+            StatusCode::ACCEPTED,
+            "A download link will be sent to the user via email.",
+        )
+        .add_error::<UserNotFoundError>()
+        .with(auth_error_docs)
+        .with(internal_server_error_docs)
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct DownloadExportPath {
+    code: VerificationCode,
+}
+
+async fn download_export(
+    service: State<Arc<impl UserFeatureService>>,
+    Path(DownloadExportPath { code }): Path<DownloadExportPath>,
+) -> Response {
+    match service.download_user_export(code).await {
+        Ok(export) => Json(ApiUserExport::from(export)).into_response(),
+        Err(UserDownloadExportError::InvalidCode) => InvalidVerificationCodeError.into_response(),
+        Err(UserDownloadExportError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn download_export_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Download a user data export using the code from the download link.")
+        .add_response::<ApiUserExport>(StatusCode::OK, "The user's data.")
+        .add_error::<InvalidVerificationCodeError>()
+        .with(internal_server_error_docs)
+}
+
 #[derive(Deserialize, JsonSchema)]
 struct VerifyEmailRequest {
     code: VerificationCode,
@@ -388,6 +647,7 @@ struct VerifyEmailRequest {
 
 async fn verify_email(
     service: State<Arc<impl UserFeatureService>>,
+    client: ApiClientInfo,
     Path(PathUserIdOrSelf { user_id }): Path<PathUserIdOrSelf>,
     Json(VerifyEmailRequest { code }): Json<VerifyEmailRequest>,
 ) -> Response {
@@ -395,9 +655,10 @@ async fn verify_email(
         return CanOnlyVerifyEmailForSelfError.into_response();
     };
 
-    match service.verify_email(code).await {
+    match service.verify_email(code, client.0).await {
         Ok(()) => Json(OkResponse).into_response(),
         Err(UserVerifyEmailError::InvalidCode) => InvalidVerificationCodeError.into_response(),
+        Err(UserVerifyEmailError::RateLimit { until }) => too_many_requests(until),
         Err(UserVerifyEmailError::Other(err)) => internal_server_error(err),
     }
 }
@@ -409,6 +670,7 @@ fn verify_email_docs(op: TransformOperation) -> TransformOperation {
             "The user's email address has been verified.",
         )
         .add_error::<InvalidVerificationCodeError>()
+        .with(too_many_requests_docs)
         .with(internal_server_error_docs)
 }
 
@@ -461,17 +723,19 @@ struct RequestPasswordResetRequest {
 
 async fn request_password_reset(
     service: State<Arc<impl UserFeatureService>>,
+    client: ApiClientInfo,
     Json(RequestPasswordResetRequest {
         email,
         recaptcha_response,
     }): Json<RequestPasswordResetRequest>,
 ) -> Response {
     match service
-        .request_password_reset(email, recaptcha_response.into())
+        .request_password_reset(email, recaptcha_response.into(), client.0)
         .await
     {
         Ok(()) => Json(OkResponse).into_response(),
         Err(UserRequestPasswordResetError::Recaptcha) => RecaptchaFailedError.into_response(),
+        Err(UserRequestPasswordResetError::RateLimit { until }) => too_many_requests(until),
         Err(UserRequestPasswordResetError::Other(err)) => internal_server_error(err),
     }
 }
@@ -483,6 +747,7 @@ fn request_password_reset_docs(op: TransformOperation) -> TransformOperation {
             "The user has been sent a password reset email.",
         )
         .add_error::<RecaptchaFailedError>()
+        .with(too_many_requests_docs)
         .with(internal_server_error_docs)
 }
 
@@ -495,15 +760,20 @@ struct ResetPasswordRequest {
 
 async fn reset_password(
     service: State<Arc<impl UserFeatureService>>,
+    client: ApiClientInfo,
     Json(ResetPasswordRequest {
         email,
         code,
         password,
     }): Json<ResetPasswordRequest>,
 ) -> Response {
-    match service.reset_password(email, code, password).await {
+    match service
+        .reset_password(email, code, password, client.0)
+        .await
+    {
         Ok(user) => Json(ApiUser::from(user)).into_response(),
         Err(UserResetPasswordError::Failed) => PasswordResetFailedError.into_response(),
+        Err(UserResetPasswordError::WeakPassword(violations)) => weak_password_error(violations),
         Err(UserResetPasswordError::Other(err)) => internal_server_error(err),
     }
 }
@@ -512,6 +782,31 @@ fn reset_password_docs(op: TransformOperation) -> TransformOperation {
     op.summary("Reset a user's password using a password reset verification code.")
         .add_response::<ApiUser>(StatusCode::OK, "The user's password has been changed.")
         .add_error::<PasswordResetFailedError>()
+        .with(weak_password_error_docs)
+        .with(internal_server_error_docs)
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct RestoreRequest {
+    code: VerificationCode,
+}
+
+async fn restore(
+    service: State<Arc<impl UserFeatureService>>,
+    client: ApiClientInfo,
+    Json(RestoreRequest { code }): Json<RestoreRequest>,
+) -> Response {
+    match service.restore_user(code, client.0).await {
+        Ok(()) => Json(OkResponse).into_response(),
+        Err(UserRestoreError::InvalidCode) => InvalidVerificationCodeError.into_response(),
+        Err(UserRestoreError::Other(err)) => internal_server_error(err),
+    }
+}
+
+fn restore_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Cancel a pending user deletion using the code sent via email.")
+        .add_response::<OkResponse>(StatusCode::OK, "The user has been restored.")
+        .add_error::<InvalidVerificationCodeError>()
         .with(internal_server_error_docs)
 }
 
@@ -546,4 +841,44 @@ error_code! {
     InvalidEmailError(BAD_REQUEST, "Invalid email");
     /// Only the email address of the currently authenticated user can be verified.
     CanOnlyVerifyEmailForSelfError(BAD_REQUEST, "Can only verify email for self");
+    /// The avatar image is too large.
+    AvatarTooLargeError(PAYLOAD_TOO_LARGE, "Avatar too large");
+    /// The avatar image is not a PNG, JPEG or WebP image.
+    UnsupportedAvatarFormatError(UNSUPPORTED_MEDIA_TYPE, "Unsupported avatar format");
+    /// The avatar image could not be decoded.
+    InvalidAvatarError(UNPROCESSABLE_ENTITY, "Invalid avatar");
+    /// The width or height of the avatar image is too small or too large.
+    InvalidAvatarDimensionsError(UNPROCESSABLE_ENTITY, "Invalid avatar dimensions");
+    /// The password does not satisfy the password policy. The violated rules
+    /// are listed in `violations`.
+    WeakPasswordError(UNPROCESSABLE_ENTITY, "Weak password");
+    /// The user referenced by the pagination cursor no longer exists. Restart
+    /// from the first page.
+    InvalidCursorError(BAD_REQUEST, "Invalid cursor");
+}
+
+/// Error response for passwords that do not satisfy the password policy
+#[derive(Serialize, JsonSchema)]
+struct ApiWeakPasswordError {
+    #[serde(rename = "detail")]
+    code: WeakPasswordError,
+    violations: Vec<UserPasswordPolicyViolation>,
+}
+
+fn weak_password_error(violations: Vec<UserPasswordPolicyViolation>) -> Response {
+    (
+        WeakPasswordError::STATUS_CODE,
+        Json(ApiWeakPasswordError {
+            code: WeakPasswordError,
+            violations,
+        }),
+    )
+        .into_response()
+}
+
+fn weak_password_error_docs(op: TransformOperation) -> TransformOperation {
+    op.add_response::<ApiWeakPasswordError>(
+        WeakPasswordError::STATUS_CODE,
+        WeakPasswordError::DESCRIPTION,
+    )
 }
@@ -3,7 +3,7 @@ use std::future::Future;
 use academy_models::{
     auth::AccessToken,
     session::{SessionId, SessionRefreshTokenHash},
-    user::User,
+    user::{User, UserId},
 };
 
 use crate::Authentication;
@@ -16,6 +16,7 @@ pub trait AuthAccessTokenService: Send + Sync + 'static {
         user: &User,
         session_id: SessionId,
         refresh_token_hash: SessionRefreshTokenHash,
+        impersonator_id: Option<UserId>,
     ) -> anyhow::Result<AccessToken>;
 
     /// Verify the given access token and return its content if it is valid.
@@ -41,6 +42,7 @@ impl MockAuthAccessTokenService {
         user: User,
         session_id: SessionId,
         refresh_token_hash: SessionRefreshTokenHash,
+        impersonator_id: Option<UserId>,
         result: AccessToken,
     ) -> Self {
         self.expect_issue()
@@ -49,8 +51,9 @@ impl MockAuthAccessTokenService {
                 mockall::predicate::eq(user),
                 mockall::predicate::eq(session_id),
                 mockall::predicate::eq(refresh_token_hash),
+                mockall::predicate::eq(impersonator_id),
             )
-            .return_once(|_, _, _| Ok(result));
+            .return_once(|_, _, _, _| Ok(result));
         self
     }
 
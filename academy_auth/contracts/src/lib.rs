@@ -2,6 +2,7 @@ use std::future::Future;
 
 use academy_models::{
     auth::{AccessToken, AuthError, AuthenticateError, AuthorizeError, RefreshToken},
+    role::{Permission, PermissionSet},
     session::{SessionId, SessionRefreshTokenHash},
     user::{User, UserId, UserPassword},
 };
@@ -20,6 +21,9 @@ pub trait AuthService<Txn: Send + Sync + 'static>: Send + Sync + 'static {
     ) -> impl Future<Output = Result<Authentication, AuthenticateError>> + Send;
 
     /// Authenticates a user using their account password.
+    ///
+    /// If the stored password hash is outdated, it is replaced by a new hash
+    /// using the current algorithm and parameters.
     fn authenticate_by_password(
         &self,
         txn: &mut Txn,
@@ -28,6 +32,9 @@ pub trait AuthService<Txn: Send + Sync + 'static>: Send + Sync + 'static {
     ) -> impl Future<Output = Result<(), AuthenticateByPasswordError>> + Send;
 
     /// Authenticates a user using a refresh token.
+    ///
+    /// Fails with [`AuthenticateByRefreshTokenError::Reused`] if the refresh
+    /// token has already been replaced by a newer one.
     fn authenticate_by_refresh_token(
         &self,
         txn: &mut Txn,
@@ -35,7 +42,15 @@ pub trait AuthService<Txn: Send + Sync + 'static>: Send + Sync + 'static {
     ) -> impl Future<Output = Result<SessionId, AuthenticateByRefreshTokenError>> + Send;
 
     /// Issues an access and refresh token for a given user and session.
-    fn issue_tokens(&self, user: &User, session_id: SessionId) -> anyhow::Result<Tokens>;
+    ///
+    /// `impersonator_id` must be set if the session has been created by an
+    /// administrator to impersonate the user.
+    fn issue_tokens(
+        &self,
+        user: &User,
+        session_id: SessionId,
+        impersonator_id: Option<UserId>,
+    ) -> anyhow::Result<Tokens>;
 
     /// Invalidates all previously issued access tokens of a user.
     fn invalidate_access_tokens(
@@ -57,8 +72,9 @@ pub struct Authentication {
     pub user_id: UserId,
     pub session_id: SessionId,
     pub refresh_token_hash: SessionRefreshTokenHash,
-    pub admin: bool,
+    pub permissions: PermissionSet,
     pub email_verified: bool,
+    pub impersonator_id: Option<UserId>,
 }
 
 #[derive(Debug, Error)]
@@ -75,14 +91,24 @@ pub enum AuthenticateByRefreshTokenError {
     Invalid,
     #[error("The refresh token has expired")]
     Expired(SessionId),
+    #[error("The refresh token has already been used")]
+    Reused(SessionId),
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
 
 impl Authentication {
-    /// Return an error if the authenticated user is not an administrator.
-    pub fn ensure_admin(&self) -> Result<(), AuthorizeError> {
-        self.admin.then_some(()).ok_or(AuthorizeError::Admin)
+    /// Return whether the authenticated user has the given permission.
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions.contains(permission)
+    }
+
+    /// Return an error if the authenticated user does not have the given
+    /// permission.
+    pub fn ensure_permission(&self, permission: Permission) -> Result<(), AuthorizeError> {
+        self.has_permission(permission)
+            .then_some(())
+            .ok_or(AuthorizeError::Permission)
     }
 
     /// Return an error if the authenticated user has not verified their email
@@ -94,11 +120,24 @@ impl Authentication {
     }
 
     /// Return an error if the authenticated user is neither the same as the one
-    /// identified by the given `user_id` nor an administrator.
-    pub fn ensure_self_or_admin(&self, user_id: UserId) -> Result<(), AuthorizeError> {
-        (self.user_id == user_id || self.admin)
+    /// identified by the given `user_id` nor has the given permission.
+    pub fn ensure_self_or_permission(
+        &self,
+        user_id: UserId,
+        permission: Permission,
+    ) -> Result<(), AuthorizeError> {
+        (self.user_id == user_id || self.has_permission(permission))
             .then_some(())
-            .ok_or(AuthorizeError::Admin)
+            .ok_or(AuthorizeError::Permission)
+    }
+
+    /// Return an error if the session has been created by an administrator to
+    /// impersonate the user.
+    pub fn ensure_not_impersonated(&self) -> Result<(), AuthorizeError> {
+        self.impersonator_id
+            .is_none()
+            .then_some(())
+            .ok_or(AuthorizeError::Impersonation)
     }
 }
 
@@ -130,8 +169,9 @@ impl<Txn: Send + Sync + 'static> MockAuthService<Txn> {
                         user_id: user.id,
                         session_id: session.id,
                         refresh_token_hash: SessionRefreshTokenHash::new(Default::default()),
-                        admin: user.admin,
+                        permissions: user.permissions(),
                         email_verified: user.email_verified,
+                        impersonator_id: session.impersonator_id,
                     })
                     .ok_or(AuthenticateError::InvalidToken),
                 ))
@@ -174,14 +214,21 @@ impl<Txn: Send + Sync + 'static> MockAuthService<Txn> {
         self
     }
 
-    pub fn with_issue_tokens(mut self, user: User, session_id: SessionId, tokens: Tokens) -> Self {
+    pub fn with_issue_tokens(
+        mut self,
+        user: User,
+        session_id: SessionId,
+        impersonator_id: Option<UserId>,
+        tokens: Tokens,
+    ) -> Self {
         self.expect_issue_tokens()
             .once()
             .with(
                 mockall::predicate::eq(user),
                 mockall::predicate::eq(session_id),
+                mockall::predicate::eq(impersonator_id),
             )
-            .return_once(|_, _| Ok(tokens));
+            .return_once(|_, _, _| Ok(tokens));
         self
     }
 
@@ -3,6 +3,7 @@ use academy_cache_contracts::CacheService;
 use academy_di::Build;
 use academy_models::{
     auth::AccessToken,
+    role::PermissionSet,
     session::{SessionId, SessionRefreshTokenHash},
     user::{User, UserId},
 };
@@ -32,13 +33,15 @@ where
         user: &User,
         session_id: SessionId,
         refresh_token_hash: SessionRefreshTokenHash,
+        impersonator_id: Option<UserId>,
     ) -> anyhow::Result<AccessToken> {
         let auth = Authentication {
             user_id: user.id,
             session_id,
             refresh_token_hash,
-            admin: user.admin,
+            permissions: user.permissions(),
             email_verified: user.email_verified,
+            impersonator_id,
         };
 
         self.jwt
@@ -88,13 +91,20 @@ struct Token {
     uid: UserId,
     sid: SessionId,
     rt: SessionRefreshTokenHash,
+    /// ID of the administrator impersonating the user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    imp: Option<UserId>,
     data: TokenData,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 struct TokenData {
+    /// Whether the user has all permissions (for services that do not know
+    /// about permissions yet)
     admin: bool,
     email_verified: bool,
+    #[serde(default)]
+    permissions: PermissionSet,
 }
 
 impl From<Token> for Authentication {
@@ -103,8 +113,9 @@ impl From<Token> for Authentication {
             user_id: value.uid,
             session_id: value.sid,
             refresh_token_hash: value.rt,
-            admin: value.data.admin,
+            permissions: value.data.permissions,
             email_verified: value.data.email_verified,
+            impersonator_id: value.imp,
         }
     }
 }
@@ -115,9 +126,11 @@ impl From<Authentication> for Token {
             uid: value.user_id,
             sid: value.session_id,
             rt: value.refresh_token_hash,
+            imp: value.impersonator_id,
             data: TokenData {
-                admin: value.admin,
+                admin: value.permissions == PermissionSet::all(),
                 email_verified: value.email_verified,
+                permissions: value.permissions,
             },
         }
     }
@@ -133,7 +146,10 @@ fn access_token_invalidated_key(refresh_token_hash: SessionRefreshTokenHash) ->
 #[cfg(test)]
 mod tests {
     use academy_cache_contracts::MockCacheService;
-    use academy_demo::{user::FOO, SHA256HASH1, SHA256HASH1_HEX, UUID1};
+    use academy_demo::{
+        user::{ADMIN, FOO},
+        SHA256HASH1, SHA256HASH1_HEX, UUID1,
+    };
     use academy_shared_contracts::jwt::{MockJwtService, VerifyJwtError};
 
     use super::*;
@@ -151,8 +167,9 @@ mod tests {
             user_id: FOO.user.id,
             session_id: UUID1.into(),
             refresh_token_hash: (*SHA256HASH1).into(),
-            admin: FOO.user.admin,
+            permissions: FOO.user.permissions(),
             email_verified: FOO.user.email_verified,
+            impersonator_id: None,
         };
 
         let jwt = MockJwtService::new().with_sign(
@@ -167,12 +184,74 @@ mod tests {
         };
 
         // Act
-        let result = sut.issue(&FOO.user, UUID1.into(), (*SHA256HASH1).into());
+        let result = sut.issue(&FOO.user, UUID1.into(), (*SHA256HASH1).into(), None);
 
         // Assert
         assert_eq!(result.unwrap().into_inner(), expected);
     }
 
+    #[test]
+    fn issue_impersonated() {
+        // Arrange
+        let config = AuthServiceConfig::default();
+
+        let expected = "the access token";
+
+        let auth = Authentication {
+            user_id: FOO.user.id,
+            session_id: UUID1.into(),
+            refresh_token_hash: (*SHA256HASH1).into(),
+            permissions: FOO.user.permissions(),
+            email_verified: FOO.user.email_verified,
+            impersonator_id: Some(ADMIN.user.id),
+        };
+
+        let jwt = MockJwtService::new().with_sign(
+            Token::from(auth),
+            config.access_token_ttl,
+            Ok(AccessToken::new(expected)),
+        );
+
+        let sut = AuthAccessTokenServiceImpl {
+            jwt,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.issue(
+            &FOO.user,
+            UUID1.into(),
+            (*SHA256HASH1).into(),
+            Some(ADMIN.user.id),
+        );
+
+        // Assert
+        assert_eq!(result.unwrap().into_inner(), expected);
+    }
+
+    #[test]
+    fn token_permissions() {
+        for (user, admin) in [(&FOO.user, false), (&ADMIN.user, true)] {
+            // Arrange
+            let auth = Authentication {
+                user_id: user.id,
+                session_id: UUID1.into(),
+                refresh_token_hash: (*SHA256HASH1).into(),
+                permissions: user.permissions(),
+                email_verified: user.email_verified,
+                impersonator_id: None,
+            };
+
+            // Act
+            let token = Token::from(auth);
+
+            // Assert
+            assert_eq!(token.data.admin, admin);
+            assert_eq!(token.data.permissions, user.permissions());
+            assert_eq!(Authentication::from(token), auth);
+        }
+    }
+
     #[test]
     fn verify_ok() {
         // Arrange
@@ -182,8 +261,9 @@ mod tests {
             user_id: FOO.user.id,
             session_id: UUID1.into(),
             refresh_token_hash: (*SHA256HASH1).into(),
-            admin: FOO.user.admin,
+            permissions: FOO.user.permissions(),
             email_verified: FOO.user.email_verified,
+            impersonator_id: None,
         };
 
         let jwt =
@@ -231,8 +311,9 @@ mod tests {
             user_id: FOO.user.id,
             session_id: UUID1.into(),
             refresh_token_hash: (*SHA256HASH1).into(),
-            admin: FOO.user.admin,
+            permissions: FOO.user.permissions(),
             email_verified: FOO.user.email_verified,
+            impersonator_id: None,
         };
 
         let jwt = MockJwtService::new().with_verify(
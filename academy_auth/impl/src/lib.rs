@@ -44,6 +44,9 @@ pub struct AuthServiceConfig {
     pub access_token_ttl: Duration,
     pub refresh_token_ttl: Duration,
     pub refresh_token_length: usize,
+    /// Maximum lifetime of impersonation sessions (which cannot be extended
+    /// by refreshing the session)
+    pub impersonation_session_ttl: Duration,
     pub internal_token_ttl: Duration,
 }
 
@@ -93,8 +96,10 @@ where
             .ok_or(AuthenticateByPasswordError::InvalidCredentials)
             .inspect_err(|_| trace!("no password set"))?;
 
+        let password = password.into_inner();
+
         self.password
-            .verify(password.into_inner().into(), password_hash)
+            .verify(password.clone().into(), password_hash.clone())
             .await
             .map_err(|err| match err {
                 PasswordVerifyError::InvalidPassword => {
@@ -104,7 +109,22 @@ where
                 PasswordVerifyError::Other(err) => {
                     err.context("Failed to verify password against hash").into()
                 }
-            })
+            })?;
+
+        if self.password.needs_rehash(&password_hash) {
+            trace!("rehash password");
+            let password_hash = self
+                .password
+                .hash(password.into())
+                .await
+                .context("Failed to hash password")?;
+            self.user_repo
+                .save_password_hash(txn, user_id, password_hash)
+                .await
+                .context("Failed to save password hash in database")?;
+        }
+
+        Ok(())
     }
 
     #[trace_instrument(skip(self, txn))]
@@ -115,16 +135,35 @@ where
     ) -> Result<SessionId, AuthenticateByRefreshTokenError> {
         let refresh_token_hash = self.auth_refresh_token.hash(refresh_token);
 
-        let session = self
+        let Some(session) = self
             .session_repo
             .get_by_refresh_token_hash(txn, refresh_token_hash)
             .await
             .context("Failed to get session from database")?
-            .ok_or(AuthenticateByRefreshTokenError::Invalid)
-            .inspect_err(|_| trace!("no session"))?;
+        else {
+            return match self
+                .session_repo
+                .get_by_retired_refresh_token_hash(txn, refresh_token_hash)
+                .await
+                .context("Failed to get session from database")?
+            {
+                Some(session) => {
+                    trace!(?session, "refresh token reused");
+                    Err(AuthenticateByRefreshTokenError::Reused(session.id))
+                }
+                None => {
+                    trace!("no session");
+                    Err(AuthenticateByRefreshTokenError::Invalid)
+                }
+            };
+        };
 
         let now = self.time.now();
-        if now >= session.updated_at + self.config.refresh_token_ttl {
+        let expires_at = match session.impersonator_id {
+            Some(_) => session.created_at + self.config.impersonation_session_ttl,
+            None => session.updated_at + self.config.refresh_token_ttl,
+        };
+        if now >= expires_at {
             trace!("session expired");
             return Err(AuthenticateByRefreshTokenError::Expired(session.id));
         }
@@ -133,12 +172,17 @@ where
     }
 
     #[trace_instrument(skip(self))]
-    fn issue_tokens(&self, user: &User, session_id: SessionId) -> anyhow::Result<Tokens> {
+    fn issue_tokens(
+        &self,
+        user: &User,
+        session_id: SessionId,
+        impersonator_id: Option<UserId>,
+    ) -> anyhow::Result<Tokens> {
         let refresh_token = self.auth_refresh_token.issue();
         let refresh_token_hash = self.auth_refresh_token.hash(&refresh_token);
         let access_token = self
             .auth_access_token
-            .issue(user, session_id, refresh_token_hash)
+            .issue(user, session_id, refresh_token_hash, impersonator_id)
             .context("Failed to issue access token")?;
 
         Ok(Tokens {
@@ -14,8 +14,9 @@ async fn ok() {
         user_id: FOO.user.id,
         session_id: UUID1.into(),
         refresh_token_hash: (*SHA256HASH1).into(),
-        admin: FOO.user.admin,
+        permissions: FOO.user.permissions(),
         email_verified: FOO.user.email_verified,
+        impersonator_id: None,
     };
 
     let auth_access_token = MockAuthAccessTokenService::new()
@@ -59,8 +60,9 @@ async fn access_token_invalidated() {
         user_id: FOO.user.id,
         session_id: UUID1.into(),
         refresh_token_hash: (*SHA256HASH1).into(),
-        admin: FOO.user.admin,
+        permissions: FOO.user.permissions(),
         email_verified: FOO.user.email_verified,
+        impersonator_id: None,
     };
 
     let auth_access_token = MockAuthAccessTokenService::new()
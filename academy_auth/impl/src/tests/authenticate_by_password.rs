@@ -14,11 +14,49 @@ async fn ok() {
     let user_repo =
         MockUserRepository::new().with_get_password_hash(FOO.user.id, Some(password_hash.into()));
 
-    let password = MockPasswordService::new().with_verify(
-        FOO_PASSWORD.clone().into_inner(),
-        password_hash.into(),
-        true,
-    );
+    let password = MockPasswordService::new()
+        .with_verify(
+            FOO_PASSWORD.clone().into_inner(),
+            password_hash.into(),
+            true,
+        )
+        .with_needs_rehash(password_hash.into(), false);
+
+    let sut = AuthServiceImpl {
+        user_repo,
+        password,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .authenticate_by_password(&mut (), FOO.user.id, FOO_PASSWORD.clone())
+        .await;
+
+    // Assert
+    result.unwrap();
+}
+
+#[tokio::test]
+async fn ok_rehash() {
+    // Arrange
+    let password_hash = "outdated hash of foo's password";
+
+    let user_repo = MockUserRepository::new()
+        .with_get_password_hash(FOO.user.id, Some(password_hash.into()))
+        .with_save_password_hash(FOO.user.id, "new hash of foo's password".into());
+
+    let password = MockPasswordService::new()
+        .with_verify(
+            FOO_PASSWORD.clone().into_inner(),
+            password_hash.into(),
+            true,
+        )
+        .with_needs_rehash(password_hash.into(), true)
+        .with_hash(
+            FOO_PASSWORD.clone().into_inner(),
+            "new hash of foo's password".into(),
+        );
 
     let sut = AuthServiceImpl {
         user_repo,
@@ -3,7 +3,8 @@ use std::time::Duration;
 use academy_auth_contracts::{
     refresh_token::MockAuthRefreshTokenService, AuthService, AuthenticateByRefreshTokenError,
 };
-use academy_demo::{session::FOO_1, SHA256HASH1};
+use academy_demo::{session::FOO_1, user::ADMIN, SHA256HASH1};
+use academy_models::session::Session;
 use academy_persistence_contracts::session::MockSessionRepository;
 use academy_shared_contracts::time::MockTimeService;
 use academy_utils::assert_matches;
@@ -46,8 +47,9 @@ async fn authenticate_by_refresh_token_invalid() {
     let auth_refresh_token = MockAuthRefreshTokenService::new()
         .with_hash("the refresh token".into(), (*SHA256HASH1).into());
 
-    let session_repo =
-        MockSessionRepository::new().with_get_by_refresh_token_hash((*SHA256HASH1).into(), None);
+    let session_repo = MockSessionRepository::new()
+        .with_get_by_refresh_token_hash((*SHA256HASH1).into(), None)
+        .with_get_by_retired_refresh_token_hash((*SHA256HASH1).into(), None);
 
     let sut = AuthServiceImpl {
         auth_refresh_token,
@@ -93,3 +95,63 @@ async fn authenticate_by_refresh_token_expired() {
     // Assert
     assert_matches!(result, Err(AuthenticateByRefreshTokenError::Expired(x)) if *x == FOO_1.id);
 }
+
+#[tokio::test]
+async fn authenticate_by_refresh_token_impersonation_expired() {
+    // Arrange
+    let config = AuthServiceConfig::default();
+
+    let session = Session {
+        impersonator_id: Some(ADMIN.user.id),
+        ..FOO_1.clone()
+    };
+
+    let auth_refresh_token = MockAuthRefreshTokenService::new()
+        .with_hash("the refresh token".into(), (*SHA256HASH1).into());
+
+    let time = MockTimeService::new()
+        .with_now(session.created_at + config.impersonation_session_ttl + Duration::from_secs(2));
+
+    let session_repo = MockSessionRepository::new()
+        .with_get_by_refresh_token_hash((*SHA256HASH1).into(), Some(session));
+
+    let sut = AuthServiceImpl {
+        auth_refresh_token,
+        time,
+        session_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .authenticate_by_refresh_token(&mut (), &"the refresh token".into())
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(AuthenticateByRefreshTokenError::Expired(x)) if *x == FOO_1.id);
+}
+
+#[tokio::test]
+async fn authenticate_by_refresh_token_reused() {
+    // Arrange
+    let auth_refresh_token = MockAuthRefreshTokenService::new()
+        .with_hash("the refresh token".into(), (*SHA256HASH1).into());
+
+    let session_repo = MockSessionRepository::new()
+        .with_get_by_refresh_token_hash((*SHA256HASH1).into(), None)
+        .with_get_by_retired_refresh_token_hash((*SHA256HASH1).into(), Some(FOO_1.clone()));
+
+    let sut = AuthServiceImpl {
+        auth_refresh_token,
+        session_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .authenticate_by_refresh_token(&mut (), &"the refresh token".into())
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(AuthenticateByRefreshTokenError::Reused(x)) if *x == FOO_1.id);
+}
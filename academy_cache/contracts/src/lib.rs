@@ -10,6 +10,12 @@ pub trait CacheService: Sized + Send + Sync + 'static {
         key: &str,
     ) -> impl Future<Output = anyhow::Result<Option<T>>> + Send;
 
+    /// Read and remove a cache item atomically.
+    fn take<T: DeserializeOwned + Debug + 'static>(
+        &self,
+        key: &str,
+    ) -> impl Future<Output = anyhow::Result<Option<T>>> + Send;
+
     /// Create a new or update an existing cache item.
     ///
     /// If `ttl` is set, the item is automatically removed after this timeout.
@@ -20,6 +26,20 @@ pub trait CacheService: Sized + Send + Sync + 'static {
         ttl: Option<Duration>,
     ) -> impl Future<Output = anyhow::Result<()>> + Send;
 
+    /// Atomically replace a cache item, but only if its current value equals
+    /// `old` (`None` if the item does not exist).
+    ///
+    /// If `ttl` is set, the new item is automatically removed after this
+    /// timeout. Returns `false` if the item has been changed concurrently, in
+    /// which case nothing is written.
+    fn compare_and_swap<T: Serialize + Debug + Sync + 'static>(
+        &self,
+        key: &str,
+        old: &Option<T>,
+        new: &T,
+        ttl: Option<Duration>,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
     /// Remove an existing cache item.
     ///
     /// Does nothing if the cache item does not exist.
@@ -43,6 +63,18 @@ impl MockCacheService {
         self
     }
 
+    pub fn with_take<T: DeserializeOwned + Debug + Send + 'static>(
+        mut self,
+        key: String,
+        result: Option<T>,
+    ) -> Self {
+        self.expect_take()
+            .once()
+            .with(mockall::predicate::eq(key))
+            .return_once(|_| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
     pub fn with_set<T: Debug + PartialEq + Serialize + Send + Sync + 'static>(
         mut self,
         key: String,
@@ -60,6 +92,21 @@ impl MockCacheService {
         self
     }
 
+    pub fn with_compare_and_swap<T: Debug + PartialEq + Serialize + Send + Sync + 'static>(
+        mut self,
+        key: String,
+        old: Option<T>,
+        new: T,
+        ttl: Option<Duration>,
+        result: bool,
+    ) -> Self {
+        self.expect_compare_and_swap()
+            .once()
+            .withf(move |k, o, n, t| *k == key && *o == old && *n == new && *t == ttl)
+            .return_once(move |_, _, _, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
     pub fn with_remove(mut self, key: String) -> Self {
         self.expect_remove()
             .once()
@@ -10,6 +10,23 @@ use bb8_redis::{
 };
 use serde::{de::DeserializeOwned, Serialize};
 
+/// Lua script for [`CacheService::compare_and_swap`]
+///
+/// Arguments: new value, ttl in milliseconds (`0` for none), expected old value
+/// (omitted if the item should not exist)
+const COMPARE_AND_SWAP_SCRIPT: &str = r"
+local current = redis.call('GET', KEYS[1])
+if current ~= (ARGV[3] or false) then
+    return 0
+end
+if ARGV[2] == '0' then
+    redis.call('SET', KEYS[1], ARGV[1])
+else
+    redis.call('SET', KEYS[1], ARGV[1], 'PX', ARGV[2])
+end
+return 1
+";
+
 #[derive(Debug, Clone)]
 pub struct ValkeyCache {
     pool: Pool<RedisConnectionManager>,
@@ -84,6 +101,28 @@ impl CacheService for ValkeyCache {
             .context("Failed to deserialize cached value")
     }
 
+    #[trace_instrument(skip(self))]
+    async fn take<T: DeserializeOwned + Debug + 'static>(
+        &self,
+        key: &str,
+    ) -> anyhow::Result<Option<T>> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .context("Failed to acquire cache connection")?;
+
+        let result = conn
+            .get_del::<_, Option<Vec<u8>>>(key)
+            .await
+            .context("Failed to read and remove value from cache")?;
+
+        result
+            .map(|data| rmp_serde::from_slice(&data))
+            .transpose()
+            .context("Failed to deserialize cached value")
+    }
+
     #[trace_instrument(skip(self))]
     async fn set<T: Serialize + Debug + Sync + 'static>(
         &self,
@@ -100,13 +139,47 @@ impl CacheService for ValkeyCache {
             .context("Failed to acquire cache connection")?;
 
         if let Some(ttl) = ttl {
-            conn.pset_ex(key, value, ttl.as_millis().try_into()?).await
+            conn.pset_ex(key, value, ttl_millis(ttl)?).await
         } else {
             conn.set(key, value).await
         }
         .context("Failed to write value to cache")
     }
 
+    #[trace_instrument(skip(self))]
+    async fn compare_and_swap<T: Serialize + Debug + Sync + 'static>(
+        &self,
+        key: &str,
+        old: &Option<T>,
+        new: &T,
+        ttl: Option<Duration>,
+    ) -> anyhow::Result<bool> {
+        let old = old
+            .as_ref()
+            .map(rmp_serde::to_vec)
+            .transpose()
+            .context("Failed to serialize value")?;
+        let new = rmp_serde::to_vec(new).context("Failed to serialize value")?;
+        let ttl = ttl.map(ttl_millis).transpose()?;
+
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .context("Failed to acquire cache connection")?;
+
+        redis::cmd("EVAL")
+            .arg(COMPARE_AND_SWAP_SCRIPT)
+            .arg(1)
+            .arg(key)
+            .arg(new)
+            .arg(ttl.unwrap_or(0u64))
+            .arg(old)
+            .query_async(&mut *conn)
+            .await
+            .context("Failed to write value to cache")
+    }
+
     #[trace_instrument(skip(self))]
     async fn remove(&self, key: &str) -> anyhow::Result<()> {
         let mut conn = self
@@ -134,3 +207,24 @@ impl CacheService for ValkeyCache {
             .context("Failed to ping cache")
     }
 }
+
+/// Convert the given ttl to milliseconds. Sub-millisecond ttls are rounded up,
+/// because `0` would not expire at all.
+fn ttl_millis(ttl: Duration) -> anyhow::Result<u64> {
+    ttl.as_millis()
+        .max(1)
+        .try_into()
+        .context("Cache ttl is too large")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ttl_millis_rounds_up() {
+        assert_eq!(ttl_millis(Duration::ZERO).unwrap(), 1);
+        assert_eq!(ttl_millis(Duration::from_micros(1)).unwrap(), 1);
+        assert_eq!(ttl_millis(Duration::from_millis(1500)).unwrap(), 1500);
+    }
+}
@@ -85,6 +85,96 @@ async fn remove() {
     assert!(cache.get::<()>("x").await.unwrap().is_none());
 }
 
+#[tokio::test]
+async fn take() {
+    let cache = setup().await;
+
+    assert!(cache.take::<i32>("x").await.unwrap().is_none());
+
+    cache.set("x", &42i32, None).await.unwrap();
+    assert_eq!(cache.take::<i32>("x").await.unwrap().unwrap(), 42);
+    assert!(cache.get::<i32>("x").await.unwrap().is_none());
+    assert!(cache.take::<i32>("x").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn compare_and_swap() {
+    let cache = setup().await;
+
+    assert!(!cache
+        .compare_and_swap("x", &Some(1i32), &2i32, None)
+        .await
+        .unwrap());
+    assert!(cache.get::<i32>("x").await.unwrap().is_none());
+
+    assert!(cache
+        .compare_and_swap("x", &None, &1i32, None)
+        .await
+        .unwrap());
+    assert_eq!(cache.get::<i32>("x").await.unwrap().unwrap(), 1);
+
+    assert!(!cache
+        .compare_and_swap("x", &None, &2i32, None)
+        .await
+        .unwrap());
+    assert!(!cache
+        .compare_and_swap("x", &Some(3i32), &2i32, None)
+        .await
+        .unwrap());
+    assert_eq!(cache.get::<i32>("x").await.unwrap().unwrap(), 1);
+
+    assert!(cache
+        .compare_and_swap("x", &Some(1i32), &2i32, Some(Duration::from_millis(200)))
+        .await
+        .unwrap());
+    assert_eq!(cache.get::<i32>("x").await.unwrap().unwrap(), 2);
+
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert!(cache.get::<i32>("x").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn compare_and_swap_sub_millisecond_ttl() {
+    let cache = setup().await;
+
+    assert!(cache
+        .compare_and_swap("x", &None, &1i32, Some(Duration::from_micros(1)))
+        .await
+        .unwrap());
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(cache.get::<i32>("x").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn compare_and_swap_concurrent() {
+    let cache = setup().await;
+
+    let tasks = (0..20)
+        .map(|_| {
+            let cache = cache.clone();
+            tokio::spawn(async move {
+                loop {
+                    let old = cache.get::<u32>("counter").await.unwrap();
+                    let new = old.unwrap_or(0) + 1;
+                    if cache
+                        .compare_and_swap("counter", &old, &new, None)
+                        .await
+                        .unwrap()
+                    {
+                        break;
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    assert_eq!(cache.get::<u32>("counter").await.unwrap().unwrap(), 20);
+}
+
 #[tokio::test]
 async fn types() {
     let cache = setup().await;
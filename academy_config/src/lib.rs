@@ -1,13 +1,18 @@
 use std::{
     collections::HashMap,
     net::{IpAddr, SocketAddr},
-    path::Path,
+    path::{Path, PathBuf},
+    str::FromStr,
 };
 
 use academy_assets::CONFIG_TOML;
-use academy_models::{email_address::EmailAddressWithName, mfa::TotpSecretLength, url::Url};
+use academy_models::{
+    email_address::EmailAddressWithName, mfa::TotpSecretLength, url::Url,
+    user::UserPasswordCharacterClass,
+};
 use anyhow::Context;
 use config::{File, FileFormat};
+use cron::Schedule;
 use duration::Duration;
 use regex::bytes::RegexSet;
 use serde::{Deserialize, Deserializer};
@@ -58,6 +63,11 @@ fn load_paths(paths: &[impl AsRef<Path>], overrides: &[&str]) -> anyhow::Result<
         .try_deserialize::<Config>()
         .context("Failed to load config")?;
 
+    config
+        .http
+        .rate_limit
+        .take_if(|rate_limit| rate_limit.enable == Some(false));
+
     config
         .recaptcha
         .take_if(|recaptcha| recaptcha.enable == Some(false));
@@ -79,18 +89,23 @@ pub struct Config {
     pub http: HttpConfig,
     pub database: DatabaseConfig,
     pub cache: CacheConfig,
+    pub storage: StorageConfig,
     pub email: EmailConfig,
     pub jwt: JwtConfig,
+    pub encryption: EncryptionConfig,
+    pub password: PasswordConfig,
     pub internal: InternalConfig,
     pub health: HealthConfig,
     pub user: UserConfig,
     pub session: SessionConfig,
     pub totp: TotpConfig,
+    pub webauthn: WebauthnConfig,
     pub contact: ContactConfig,
     pub recaptcha: Option<RecaptchaConfig>,
     pub vat: VatConfig,
     pub sentry: Option<SentryConfig>,
     pub oauth2: Option<OAuth2Config>,
+    pub tasks: TasksConfig,
 }
 
 #[derive(Debug, Deserialize)]
@@ -99,6 +114,7 @@ pub struct HttpConfig {
     pub real_ip: Option<HttpRealIpConfig>,
     #[serde(deserialize_with = "deserialize_regex_set")]
     pub allowed_origins: RegexSet,
+    pub rate_limit: Option<HttpRateLimitConfig>,
 }
 
 fn deserialize_regex_set<'de, D>(deserializer: D) -> Result<RegexSet, D::Error>
@@ -115,6 +131,20 @@ pub struct HttpRealIpConfig {
     pub set_from: IpAddr,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct HttpRateLimitConfig {
+    pub enable: Option<bool>,
+    pub default: HttpRateLimitPolicyConfig,
+    #[serde(default)]
+    pub groups: HashMap<String, HttpRateLimitPolicyConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HttpRateLimitPolicyConfig {
+    pub capacity: u64,
+    pub period: Duration,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct DatabaseConfig {
     pub url: String,
@@ -135,6 +165,12 @@ pub struct CacheConfig {
     pub max_lifetime: Option<Duration>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct StorageConfig {
+    pub path: PathBuf,
+    pub public_url: Url,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct EmailConfig {
     pub smtp_url: String,
@@ -143,7 +179,19 @@ pub struct EmailConfig {
 
 #[derive(Debug, Deserialize)]
 pub struct JwtConfig {
-    pub secret: String,
+    pub keys: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EncryptionConfig {
+    pub keys: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PasswordConfig {
+    pub argon2_memory_cost: u32,
+    pub argon2_time_cost: u32,
+    pub argon2_parallelism: u32,
 }
 
 #[derive(Debug, Deserialize)]
@@ -168,6 +216,19 @@ pub struct UserConfig {
     pub password_reset_redirect_url: String,
     pub newsletter_code_ttl: Duration,
     pub newsletter_redirect_url: String,
+    pub export_async_threshold: u64,
+    pub export_download_ttl: Duration,
+    pub export_redirect_url: String,
+    pub deletion_grace_period: Duration,
+    pub restore_redirect_url: String,
+    pub avatar_max_size: usize,
+    pub avatar_min_dimension: u32,
+    pub avatar_max_dimension: u32,
+    pub password_min_length: usize,
+    pub password_required_character_classes: Vec<UserPasswordCharacterClass>,
+    pub password_min_strength: u8,
+    pub password_breach_check: bool,
+    pub password_breach_list_path: Option<PathBuf>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -175,7 +236,17 @@ pub struct SessionConfig {
     pub access_token_ttl: Duration,
     pub refresh_token_ttl: Duration,
     pub refresh_token_length: usize,
+    pub refresh_token_history: usize,
+    pub refresh_token_reuse_email: bool,
+    pub impersonation_session_ttl: Duration,
     pub login_fails_before_captcha: u64,
+    pub login_link_code_ttl: Duration,
+    pub login_link_redirect_url: String,
+    pub throttle_window: Duration,
+    pub throttle_account_limit: u64,
+    pub throttle_ip_limit: u64,
+    pub throttle_lockout: Duration,
+    pub throttle_lockout_max: Duration,
 }
 
 #[derive(Debug, Deserialize)]
@@ -183,6 +254,14 @@ pub struct TotpConfig {
     pub secret_length: TotpSecretLength,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct WebauthnConfig {
+    pub rp_id: String,
+    pub rp_name: String,
+    pub origins: Vec<String>,
+    pub timeout: Duration,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ContactConfig {
     pub email: EmailAddressWithName,
@@ -212,6 +291,7 @@ pub struct SentryConfig {
 pub struct OAuth2Config {
     pub enable: Option<bool>,
     pub registration_token_ttl: Duration,
+    pub authorization_ttl: Duration,
     pub providers: HashMap<String, OAuth2ProviderConfig>,
 }
 
@@ -221,14 +301,50 @@ pub struct OAuth2ProviderConfig {
     pub name: String,
     pub client_id: String,
     pub client_secret: String,
-    pub auth_url: Url,
-    pub token_url: Url,
-    pub userinfo_url: Url,
+    /// Issuer URL of an OpenID Connect provider (replaces `auth_url`,
+    /// `token_url` and `userinfo_url`)
+    pub issuer_url: Option<Url>,
+    pub auth_url: Option<Url>,
+    pub token_url: Option<Url>,
+    pub userinfo_url: Option<Url>,
+    /// Optional token revocation endpoint (RFC 7009), ignored for OpenID
+    /// Connect providers which advertise it via discovery
+    pub revocation_url: Option<Url>,
     pub userinfo_id_key: String,
     pub userinfo_name_key: String,
+    /// Optional claims used to pre-fill the registration of new users
+    pub userinfo_email_key: Option<String>,
+    /// Claim indicating whether the provider has verified the email address.
+    /// If not set, imported email addresses are never considered verified.
+    pub userinfo_email_verified_key: Option<String>,
+    pub userinfo_display_name_key: Option<String>,
+    pub userinfo_avatar_url_key: Option<String>,
     pub scopes: Vec<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct TasksConfig {
+    pub unverified_user_ttl: Duration,
+    pub verification_reminder_after: Duration,
+    pub totp_device_setup_ttl: Duration,
+    #[serde(default, deserialize_with = "deserialize_schedule")]
+    pub schedule: HashMap<String, Schedule>,
+}
+
+fn deserialize_schedule<'de, D>(deserializer: D) -> Result<HashMap<String, Schedule>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    HashMap::<String, String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(task, expression)| {
+            Schedule::from_str(&expression)
+                .map(|schedule| (task, schedule))
+                .map_err(serde::de::Error::custom)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -0,0 +1,54 @@
+use std::future::Future;
+
+use academy_models::{
+    audit::{AuditEvent, AuditEventFilter},
+    auth::{AccessToken, AuthError},
+    pagination::PaginationSlice,
+    user::UserIdOrSelf,
+};
+use thiserror::Error;
+
+pub mod log;
+
+pub trait AuditFeatureService: Send + Sync + 'static {
+    /// Return all audit events matching the given query.
+    ///
+    /// Requires the `audit_log_read` permission.
+    fn list_events(
+        &self,
+        token: &AccessToken,
+        query: AuditEventListQuery,
+    ) -> impl Future<Output = Result<AuditEventListResult, AuditListEventsError>> + Send;
+
+    /// Return the security history of the given user, i.e. all audit events
+    /// affecting this user.
+    ///
+    /// Requires the `audit_log_read` permission if not used on the authenticated
+    /// user.
+    fn list_events_by_user(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        pagination: PaginationSlice,
+    ) -> impl Future<Output = Result<AuditEventListResult, AuditListEventsError>> + Send;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AuditEventListQuery {
+    pub pagination: PaginationSlice,
+    pub filter: AuditEventFilter,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEventListResult {
+    pub total: u64,
+    pub events: Vec<AuditEvent>,
+}
+
+#[derive(Debug, Error)]
+pub enum AuditListEventsError {
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
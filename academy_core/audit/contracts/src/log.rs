@@ -0,0 +1,45 @@
+use std::future::Future;
+
+use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
+    user::UserId,
+};
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait AuditLogService<Txn: Send + Sync + 'static>: Send + Sync + 'static {
+    /// Record a new audit event.
+    ///
+    /// `actor_id` is the user who performed the action (if authenticated) and
+    /// `user_id` is the user affected by it.
+    fn record(
+        &self,
+        txn: &mut Txn,
+        actor_id: Option<UserId>,
+        user_id: UserId,
+        kind: AuditEventKind,
+        client: ClientInfo,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+}
+
+#[cfg(feature = "mock")]
+impl<Txn: Send + Sync + 'static> MockAuditLogService<Txn> {
+    pub fn with_record(
+        mut self,
+        actor_id: Option<UserId>,
+        user_id: UserId,
+        kind: AuditEventKind,
+        client: ClientInfo,
+    ) -> Self {
+        self.expect_record()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(actor_id),
+                mockall::predicate::eq(user_id),
+                mockall::predicate::eq(kind),
+                mockall::predicate::eq(client),
+            )
+            .return_once(|_, _, _, _, _| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
+}
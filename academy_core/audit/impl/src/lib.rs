@@ -0,0 +1,99 @@
+use academy_auth_contracts::{AuthResultExt, AuthService};
+use academy_core_audit_contracts::{
+    AuditEventListQuery, AuditEventListResult, AuditFeatureService, AuditListEventsError,
+};
+use academy_di::Build;
+use academy_models::{
+    audit::AuditEventFilter, auth::AccessToken, pagination::PaginationSlice, role::Permission,
+    user::UserIdOrSelf,
+};
+use academy_persistence_contracts::{audit::AuditRepository, Database};
+use academy_utils::trace_instrument;
+use anyhow::Context;
+
+pub mod log;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone, Default, Build)]
+pub struct AuditFeatureServiceImpl<Db, Auth, AuditRepo> {
+    db: Db,
+    auth: Auth,
+    audit_repo: AuditRepo,
+}
+
+impl<Db, Auth, AuditRepo> AuditFeatureService for AuditFeatureServiceImpl<Db, Auth, AuditRepo>
+where
+    Db: Database,
+    Auth: AuthService<Db::Transaction>,
+    AuditRepo: AuditRepository<Db::Transaction>,
+{
+    #[trace_instrument(skip(self))]
+    async fn list_events(
+        &self,
+        token: &AccessToken,
+        query: AuditEventListQuery,
+    ) -> Result<AuditEventListResult, AuditListEventsError> {
+        let auth = self.auth.authenticate(token).await.map_auth_err()?;
+        auth.ensure_permission(Permission::AuditLogRead)
+            .map_auth_err()?;
+
+        let mut txn = self.db.begin_transaction().await?;
+
+        self.list(&mut txn, &query.filter, query.pagination)
+            .await
+            .map_err(Into::into)
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn list_events_by_user(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        pagination: PaginationSlice,
+    ) -> Result<AuditEventListResult, AuditListEventsError> {
+        let auth = self.auth.authenticate(token).await.map_auth_err()?;
+        let user_id = user_id.unwrap_or(auth.user_id);
+        auth.ensure_self_or_permission(user_id, Permission::AuditLogRead)
+            .map_auth_err()?;
+
+        let mut txn = self.db.begin_transaction().await?;
+
+        let filter = AuditEventFilter {
+            user_id: Some(user_id),
+            ..Default::default()
+        };
+
+        self.list(&mut txn, &filter, pagination)
+            .await
+            .map_err(Into::into)
+    }
+}
+
+impl<Db, Auth, AuditRepo> AuditFeatureServiceImpl<Db, Auth, AuditRepo>
+where
+    Db: Database,
+    AuditRepo: AuditRepository<Db::Transaction>,
+{
+    async fn list(
+        &self,
+        txn: &mut Db::Transaction,
+        filter: &AuditEventFilter,
+        pagination: PaginationSlice,
+    ) -> anyhow::Result<AuditEventListResult> {
+        let total = self
+            .audit_repo
+            .count(txn, filter)
+            .await
+            .context("Failed to get total number of audit events from database")?;
+
+        let events = self
+            .audit_repo
+            .list(txn, filter, pagination)
+            .await
+            .context("Failed to get audit events from database")?;
+
+        Ok(AuditEventListResult { total, events })
+    }
+}
@@ -0,0 +1,87 @@
+use academy_core_audit_contracts::log::AuditLogService;
+use academy_di::Build;
+use academy_models::{
+    audit::{AuditEvent, AuditEventKind, ClientInfo},
+    user::UserId,
+};
+use academy_persistence_contracts::audit::AuditRepository;
+use academy_shared_contracts::{id::IdService, time::TimeService};
+use academy_utils::trace_instrument;
+use anyhow::Context;
+
+#[derive(Debug, Clone, Build)]
+pub struct AuditLogServiceImpl<Id, Time, AuditRepo> {
+    id: Id,
+    time: Time,
+    audit_repo: AuditRepo,
+}
+
+impl<Txn, Id, Time, AuditRepo> AuditLogService<Txn> for AuditLogServiceImpl<Id, Time, AuditRepo>
+where
+    Txn: Send + Sync + 'static,
+    Id: IdService,
+    Time: TimeService,
+    AuditRepo: AuditRepository<Txn>,
+{
+    #[trace_instrument(skip(self, txn))]
+    async fn record(
+        &self,
+        txn: &mut Txn,
+        actor_id: Option<UserId>,
+        user_id: UserId,
+        kind: AuditEventKind,
+        client: ClientInfo,
+    ) -> anyhow::Result<()> {
+        let event = AuditEvent {
+            id: self.id.generate(),
+            actor_id,
+            user_id,
+            kind,
+            client,
+            created_at: self.time.now(),
+        };
+
+        self.audit_repo
+            .create(txn, &event)
+            .await
+            .context("Failed to create audit event in database")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use academy_demo::audit::FOO_AUDIT_EVENT_2;
+    use academy_persistence_contracts::audit::MockAuditRepository;
+    use academy_shared_contracts::{id::MockIdService, time::MockTimeService};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn ok() {
+        // Arrange
+        let id = MockIdService::new().with_generate(FOO_AUDIT_EVENT_2.id);
+        let time = MockTimeService::new().with_now(FOO_AUDIT_EVENT_2.created_at);
+
+        let audit_repo = MockAuditRepository::new().with_create(FOO_AUDIT_EVENT_2.clone());
+
+        let sut = AuditLogServiceImpl {
+            id,
+            time,
+            audit_repo,
+        };
+
+        // Act
+        let result = sut
+            .record(
+                &mut (),
+                FOO_AUDIT_EVENT_2.actor_id,
+                FOO_AUDIT_EVENT_2.user_id,
+                FOO_AUDIT_EVENT_2.kind,
+                FOO_AUDIT_EVENT_2.client.clone(),
+            )
+            .await;
+
+        // Assert
+        result.unwrap();
+    }
+}
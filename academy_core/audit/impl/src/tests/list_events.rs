@@ -0,0 +1,112 @@
+use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::{
+    AuditEventListQuery, AuditEventListResult, AuditFeatureService, AuditListEventsError,
+};
+use academy_demo::{
+    audit::ALL_AUDIT_EVENTS,
+    session::{ADMIN_1, FOO_1},
+    user::{ADMIN, FOO},
+};
+use academy_models::{
+    audit::{AuditEventFilter, AuditEventKind},
+    auth::{AuthError, AuthenticateError, AuthorizeError},
+    pagination::PaginationSlice,
+};
+use academy_persistence_contracts::{audit::MockAuditRepository, MockDatabase};
+use academy_utils::assert_matches;
+
+use crate::{tests::Sut, AuditFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok() {
+    // Arrange
+    let query = build_query();
+
+    let expected = AuditEventListResult {
+        total: 42,
+        events: ALL_AUDIT_EVENTS.iter().copied().cloned().collect(),
+    };
+
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let audit_repo = MockAuditRepository::new()
+        .with_count(query.filter.clone(), expected.total)
+        .with_list(
+            query.filter.clone(),
+            query.pagination,
+            expected.events.clone(),
+        );
+
+    let sut = AuditFeatureServiceImpl {
+        auth,
+        db,
+        audit_repo,
+    };
+
+    // Act
+    let result = sut.list_events(&"token".into(), query).await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn unauthenticated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(None);
+
+    let sut = AuditFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.list_events(&"token".into(), build_query()).await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(AuditListEventsError::Auth(AuthError::Authenticate(
+            AuthenticateError::InvalidToken
+        )))
+    );
+}
+
+#[tokio::test]
+async fn unauthorized() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let sut = AuditFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.list_events(&"token".into(), build_query()).await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(AuditListEventsError::Auth(AuthError::Authorize(
+            AuthorizeError::Permission
+        )))
+    );
+}
+
+fn build_query() -> AuditEventListQuery {
+    AuditEventListQuery {
+        pagination: PaginationSlice {
+            limit: 42.try_into().unwrap(),
+            offset: 7,
+        },
+        filter: AuditEventFilter {
+            actor_id: Some(ADMIN.user.id),
+            user_id: Some(FOO.user.id),
+            kind: Some(AuditEventKind::UserImpersonated),
+        },
+    }
+}
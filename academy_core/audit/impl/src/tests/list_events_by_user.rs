@@ -0,0 +1,145 @@
+use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::{
+    AuditEventListResult, AuditFeatureService, AuditListEventsError,
+};
+use academy_demo::{
+    audit::ALL_AUDIT_EVENTS,
+    session::{ADMIN_1, BAR_1, FOO_1},
+    user::{ADMIN, BAR, FOO},
+};
+use academy_models::{
+    audit::AuditEventFilter,
+    auth::{AuthError, AuthenticateError, AuthorizeError},
+    pagination::PaginationSlice,
+    user::UserIdOrSelf,
+};
+use academy_persistence_contracts::{audit::MockAuditRepository, MockDatabase};
+use academy_utils::assert_matches;
+
+use crate::{tests::Sut, AuditFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok_self() {
+    // Arrange
+    let expected = AuditEventListResult {
+        total: 2,
+        events: ALL_AUDIT_EVENTS.iter().copied().cloned().collect(),
+    };
+
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let filter = AuditEventFilter {
+        user_id: Some(FOO.user.id),
+        ..Default::default()
+    };
+    let audit_repo = MockAuditRepository::new()
+        .with_count(filter.clone(), expected.total)
+        .with_list(filter, build_pagination(), expected.events.clone());
+
+    let sut = AuditFeatureServiceImpl {
+        auth,
+        db,
+        audit_repo,
+    };
+
+    // Act
+    let result = sut
+        .list_events_by_user(&"token".into(), UserIdOrSelf::Slf, build_pagination())
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn ok_admin() {
+    // Arrange
+    let expected = AuditEventListResult {
+        total: 0,
+        events: vec![],
+    };
+
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let filter = AuditEventFilter {
+        user_id: Some(BAR.user.id),
+        ..Default::default()
+    };
+    let audit_repo = MockAuditRepository::new()
+        .with_count(filter.clone(), expected.total)
+        .with_list(filter, build_pagination(), expected.events.clone());
+
+    let sut = AuditFeatureServiceImpl {
+        auth,
+        db,
+        audit_repo,
+    };
+
+    // Act
+    let result = sut
+        .list_events_by_user(&"token".into(), BAR.user.id.into(), build_pagination())
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn unauthenticated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(None);
+
+    let sut = AuditFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .list_events_by_user(&"token".into(), UserIdOrSelf::Slf, build_pagination())
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(AuditListEventsError::Auth(AuthError::Authenticate(
+            AuthenticateError::InvalidToken
+        )))
+    );
+}
+
+#[tokio::test]
+async fn unauthorized() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((BAR.user.clone(), BAR_1.clone())));
+
+    let sut = AuditFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .list_events_by_user(&"token".into(), FOO.user.id.into(), build_pagination())
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(AuditListEventsError::Auth(AuthError::Authorize(
+            AuthorizeError::Permission
+        )))
+    );
+}
+
+fn build_pagination() -> PaginationSlice {
+    PaginationSlice {
+        limit: 20.try_into().unwrap(),
+        offset: 0,
+    }
+}
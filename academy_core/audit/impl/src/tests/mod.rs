@@ -0,0 +1,13 @@
+use academy_auth_contracts::MockAuthService;
+use academy_persistence_contracts::{audit::MockAuditRepository, MockDatabase, MockTransaction};
+
+use crate::AuditFeatureServiceImpl;
+
+mod list_events;
+mod list_events_by_user;
+
+type Sut = AuditFeatureServiceImpl<
+    MockDatabase,
+    MockAuthService<MockTransaction>,
+    MockAuditRepository<MockTransaction>,
+>;
@@ -1,4 +1,9 @@
+use academy_models::auth::JwtPublicKey;
+
 pub trait ConfigFeatureService: Send + Sync + 'static {
     /// Return the public reCAPTCHA sitekey if reCAPTCHA is enabled.
     fn get_recaptcha_sitekey(&self) -> Option<&str>;
+
+    /// Return the public keys that can be used to verify access tokens.
+    fn get_jwks(&self) -> Vec<JwtPublicKey>;
 }
@@ -1,14 +1,19 @@
 use std::future::Future;
 
-use academy_models::{contact::ContactMessage, RecaptchaResponse};
+use academy_models::{audit::ClientInfo, contact::ContactMessage, RecaptchaResponse};
+use chrono::{DateTime, Utc};
 use thiserror::Error;
 
 pub trait ContactFeatureService: Send + Sync + 'static {
     /// Send a message to the support team.
+    ///
+    /// Messages are counted per author email address and per client IP
+    /// address.
     fn send_message(
         &self,
         message: ContactMessage,
         recaptcha_response: Option<RecaptchaResponse>,
+        client: ClientInfo,
     ) -> impl Future<Output = Result<(), ContactSendMessageError>> + Send;
 }
 
@@ -16,6 +21,8 @@ pub trait ContactFeatureService: Send + Sync + 'static {
 pub enum ContactSendMessageError {
     #[error("Invalid recaptcha response")]
     Recaptcha,
+    #[error("Too many messages. Try again after {until}.")]
+    RateLimit { until: DateTime<Utc> },
     #[error("Failed to send message.")]
     Send,
     #[error(transparent)]
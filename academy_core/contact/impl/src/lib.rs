@@ -4,29 +4,39 @@ use academy_core_contact_contracts::{ContactFeatureService, ContactSendMessageEr
 use academy_di::Build;
 use academy_email_contracts::{ContentType, Email, EmailService};
 use academy_models::{
-    contact::ContactMessage, email_address::EmailAddressWithName, RecaptchaResponse,
+    audit::ClientInfo, contact::ContactMessage, email_address::EmailAddressWithName,
+    RecaptchaResponse,
+};
+use academy_shared_contracts::{
+    captcha::{CaptchaCheckError, CaptchaService},
+    throttle::{ThrottleKey, ThrottleService},
 };
-use academy_shared_contracts::captcha::{CaptchaCheckError, CaptchaService};
 use academy_utils::trace_instrument;
 use anyhow::Context;
 use tracing::{error, trace};
 
 #[derive(Debug, Clone, Build)]
 #[cfg_attr(test, derive(Default))]
-pub struct ContactFeatureServiceImpl<Captcha, Email> {
+pub struct ContactFeatureServiceImpl<Captcha, Throttle, Email> {
     captcha: Captcha,
+    throttle: Throttle,
     email: Email,
     config: ContactFeatureConfig,
 }
 
+/// Action of the [`ThrottleKey`]s used to limit contact messages
+const CONTACT_THROTTLE_ACTION: &str = "contact";
+
 #[derive(Debug, Clone)]
 pub struct ContactFeatureConfig {
     pub email: Arc<EmailAddressWithName>,
 }
 
-impl<Captcha, EmailS> ContactFeatureService for ContactFeatureServiceImpl<Captcha, EmailS>
+impl<Captcha, Throttle, EmailS> ContactFeatureService
+    for ContactFeatureServiceImpl<Captcha, Throttle, EmailS>
 where
     Captcha: CaptchaService,
+    Throttle: ThrottleService,
     EmailS: EmailService,
 {
     #[trace_instrument(skip(self))]
@@ -34,7 +44,29 @@ where
         &self,
         message: ContactMessage,
         recaptcha_response: Option<RecaptchaResponse>,
+        client: ClientInfo,
     ) -> Result<(), ContactSendMessageError> {
+        trace!("check throttle");
+        let throttle_keys = [ThrottleKey::account(
+            CONTACT_THROTTLE_ACTION,
+            message.author.email.as_str(),
+        )]
+        .into_iter()
+        .chain(
+            client
+                .ip_address
+                .map(|ip| ThrottleKey::ip(CONTACT_THROTTLE_ACTION, ip)),
+        )
+        .collect::<Vec<_>>();
+        if let Some(until) = self
+            .throttle
+            .record(&throttle_keys)
+            .await
+            .context("Failed to record contact message")?
+        {
+            return Err(ContactSendMessageError::RateLimit { until });
+        }
+
         trace!("check captcha");
         self.captcha
             .check(recaptcha_response.as_deref().map(String::as_str))
@@ -80,23 +112,37 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        time::Duration,
+    };
+
     use academy_email_contracts::MockEmailService;
     use academy_models::contact::ContactMessageAuthor;
-    use academy_shared_contracts::captcha::{CaptchaCheckError, MockCaptchaService};
+    use academy_shared_contracts::{
+        captcha::{CaptchaCheckError, MockCaptchaService},
+        throttle::MockThrottleService,
+    };
     use academy_utils::assert_matches;
+    use chrono::DateTime;
 
     use super::*;
 
-    type Sut = ContactFeatureServiceImpl<MockCaptchaService, MockEmailService>;
+    type Sut = ContactFeatureServiceImpl<MockCaptchaService, MockThrottleService, MockEmailService>;
+
+    const IP: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 42));
 
     #[tokio::test]
     async fn ok() {
         // Arrange
+        let throttle = MockThrottleService::new().with_record(make_throttle_keys(), None);
+
         let captcha = MockCaptchaService::new().with_check(Some("resp"), Ok(()));
 
         let email = MockEmailService::new().with_send(make_email(), true);
 
         let sut = ContactFeatureServiceImpl {
+            throttle,
             captcha,
             email,
             ..Sut::default()
@@ -104,7 +150,11 @@ mod tests {
 
         // Act
         let result = sut
-            .send_message(make_contact_message(), Some("resp".try_into().unwrap()))
+            .send_message(
+                make_contact_message(),
+                Some("resp".try_into().unwrap()),
+                make_client_info(),
+            )
             .await;
 
         // Assert
@@ -114,17 +164,24 @@ mod tests {
     #[tokio::test]
     async fn error_invalid_recaptcha_response() {
         // Arrange
+        let throttle = MockThrottleService::new().with_record(make_throttle_keys(), None);
+
         let captcha =
             MockCaptchaService::new().with_check(Some("resp"), Err(CaptchaCheckError::Failed));
 
         let sut = ContactFeatureServiceImpl {
+            throttle,
             captcha,
             ..Sut::default()
         };
 
         // Act
         let result = sut
-            .send_message(make_contact_message(), Some("resp".try_into().unwrap()))
+            .send_message(
+                make_contact_message(),
+                Some("resp".try_into().unwrap()),
+                make_client_info(),
+            )
             .await;
 
         // Assert
@@ -134,23 +191,49 @@ mod tests {
     #[tokio::test]
     async fn error() {
         // Arrange
+        let throttle = MockThrottleService::new().with_record(make_throttle_keys(), None);
+
         let captcha = MockCaptchaService::new().with_check(None, Ok(()));
 
         let email = MockEmailService::new().with_send(make_email(), false);
 
         let sut = ContactFeatureServiceImpl {
+            throttle,
             captcha,
             email,
             ..Sut::default()
         };
 
         // Act
-        let result = sut.send_message(make_contact_message(), None).await;
+        let result = sut
+            .send_message(make_contact_message(), None, make_client_info())
+            .await;
 
         // Assert
         assert_matches!(result, Err(ContactSendMessageError::Send));
     }
 
+    #[tokio::test]
+    async fn error_rate_limit() {
+        // Arrange
+        let expected = DateTime::from_timestamp(1724949831, 0).unwrap() + Duration::from_secs(60);
+
+        let throttle = MockThrottleService::new().with_record(make_throttle_keys(), Some(expected));
+
+        let sut = ContactFeatureServiceImpl {
+            throttle,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut
+            .send_message(make_contact_message(), None, make_client_info())
+            .await;
+
+        // Assert
+        assert_matches!(result, Err(ContactSendMessageError::RateLimit { until }) if *until == expected);
+    }
+
     impl Default for ContactFeatureConfig {
         fn default() -> Self {
             ContactFeatureConfig {
@@ -170,6 +253,20 @@ mod tests {
         }
     }
 
+    fn make_client_info() -> ClientInfo {
+        ClientInfo {
+            ip_address: Some(IP),
+            user_agent: None,
+        }
+    }
+
+    fn make_throttle_keys() -> Vec<ThrottleKey> {
+        vec![
+            ThrottleKey::account("contact", "max.mustermann@example.de"),
+            ThrottleKey::ip("contact", IP),
+        ]
+    }
+
     fn make_email() -> Email {
         Email {
             recipient: "contact@example.com".parse().unwrap(),
@@ -5,7 +5,7 @@ use academy_models::user::UserId;
 #[cfg_attr(feature = "mock", mockall::automock)]
 pub trait MfaDisableService<Txn: Send + Sync + 'static>: Send + Sync + 'static {
     /// Completely disable MFA for the given user by deleting all TOTP devices
-    /// and invalidating the MFA recovery code.
+    /// and WebAuthn credentials and invalidating the MFA recovery code.
     fn disable(
         &self,
         txn: &mut Txn,
@@ -1,8 +1,14 @@
 use std::future::Future;
 
 use academy_models::{
+    audit::ClientInfo,
     auth::{AccessToken, AuthError},
-    mfa::{MfaRecoveryCode, TotpCode, TotpSetup},
+    mfa::{
+        MfaRecoveryCode, TotpCode, TotpDevice, TotpDeviceId, TotpDeviceName, TotpSetup,
+        WebauthnAuthenticationOptions, WebauthnCredential, WebauthnCredentialId,
+        WebauthnCredentialName, WebauthnRegistration, WebauthnRegistrationOptions,
+        WebauthnRegistrationResponse,
+    },
     user::UserIdOrSelf,
 };
 use thiserror::Error;
@@ -11,37 +17,182 @@ pub mod authenticate;
 pub mod disable;
 pub mod recovery;
 pub mod totp_device;
+pub mod webauthn;
 
 pub trait MfaFeatureService: Send + Sync + 'static {
     /// Create a new disabled TOTP device or reset an existing disabled TOTP
     /// device.
     ///
-    /// Requires admin privileges if not used on the authenticated user.
+    /// Requires the `user_write` permission if not used on the authenticated
+    /// user.
+    ///
+    /// Not allowed in sessions created to impersonate the user.
     fn initialize(
         &self,
         token: &AccessToken,
         user_id: UserIdOrSelf,
     ) -> impl Future<Output = Result<TotpSetup, MfaInitializeError>> + Send;
 
-    /// Enable a previously created disabled TOTP device and generate an MFA
-    /// recovery code.
+    /// Enable a previously created disabled TOTP device.
+    ///
+    /// If the user has not enabled MFA before (e.g. using a WebAuthn
+    /// credential), an MFA recovery code is generated and returned.
     ///
-    /// Requires admin privileges if not used on the authenticated user.
+    /// Requires the `user_write` permission if not used on the authenticated
+    /// user.
+    ///
+    /// Not allowed in sessions created to impersonate the user.
     fn enable(
         &self,
         token: &AccessToken,
         user_id: UserIdOrSelf,
         code: TotpCode,
-    ) -> impl Future<Output = Result<MfaRecoveryCode, MfaEnableError>> + Send;
+    ) -> impl Future<Output = Result<Option<MfaRecoveryCode>, MfaEnableError>> + Send;
 
-    /// Delete all TOTP devices and invalidate the MFA recovery code.
+    /// Delete all TOTP devices and WebAuthn credentials and invalidate the MFA
+    /// recovery code.
     ///
-    /// Requires admin privileges if not used on the authenticated user.
+    /// Requires the `user_write` permission if not used on the authenticated
+    /// user.
+    ///
+    /// Not allowed in sessions created to impersonate the user.
     fn disable(
         &self,
         token: &AccessToken,
         user_id: UserIdOrSelf,
+        client: ClientInfo,
     ) -> impl Future<Output = Result<(), MfaDisableError>> + Send;
+
+    /// Return all TOTP devices of the given user.
+    ///
+    /// Requires the `user_read` permission if not used on the authenticated
+    /// user.
+    fn list_totp_devices(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+    ) -> impl Future<Output = Result<Vec<TotpDevice>, MfaListTotpDevicesError>> + Send;
+
+    /// Create a new disabled TOTP device.
+    ///
+    /// Requires the `user_write` permission if not used on the authenticated
+    /// user.
+    ///
+    /// Not allowed in sessions created to impersonate the user.
+    fn create_totp_device(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        name: TotpDeviceName,
+    ) -> impl Future<Output = Result<(TotpDevice, TotpSetup), MfaCreateTotpDeviceError>> + Send;
+
+    /// Enable a previously created disabled TOTP device.
+    ///
+    /// If the user has not enabled MFA before, an MFA recovery code is
+    /// generated and returned.
+    ///
+    /// Requires the `user_write` permission if not used on the authenticated
+    /// user.
+    ///
+    /// Not allowed in sessions created to impersonate the user.
+    fn confirm_totp_device(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        totp_device_id: TotpDeviceId,
+        code: TotpCode,
+    ) -> impl Future<Output = Result<Option<MfaRecoveryCode>, MfaConfirmTotpDeviceError>> + Send;
+
+    /// Rename a TOTP device.
+    ///
+    /// Requires the `user_write` permission if not used on the authenticated
+    /// user.
+    ///
+    /// Not allowed in sessions created to impersonate the user.
+    fn update_totp_device(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        totp_device_id: TotpDeviceId,
+        name: TotpDeviceName,
+    ) -> impl Future<Output = Result<TotpDevice, MfaUpdateTotpDeviceError>> + Send;
+
+    /// Delete a TOTP device.
+    ///
+    /// If this is the last enabled second factor of the user, MFA is disabled
+    /// completely.
+    ///
+    /// Requires the `user_write` permission if not used on the authenticated
+    /// user.
+    ///
+    /// Not allowed in sessions created to impersonate the user.
+    fn delete_totp_device(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        totp_device_id: TotpDeviceId,
+    ) -> impl Future<Output = Result<(), MfaDeleteTotpDeviceError>> + Send;
+
+    /// Return all WebAuthn credentials of the given user.
+    ///
+    /// Requires the `user_read` permission if not used on the authenticated
+    /// user.
+    fn list_webauthn_credentials(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+    ) -> impl Future<Output = Result<Vec<WebauthnCredential>, MfaListWebauthnCredentialsError>> + Send;
+
+    /// Generate the options for registering a new WebAuthn credential.
+    ///
+    /// Requires the `user_write` permission if not used on the authenticated
+    /// user.
+    ///
+    /// Not allowed in sessions created to impersonate the user.
+    fn start_webauthn_registration(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+    ) -> impl Future<Output = Result<WebauthnRegistrationOptions, MfaStartWebauthnRegistrationError>>
+           + Send;
+
+    /// Verify the response of the authenticator and save the new WebAuthn
+    /// credential.
+    ///
+    /// If MFA has not been enabled before, an MFA recovery code is generated.
+    ///
+    /// Requires the `user_write` permission if not used on the authenticated
+    /// user.
+    ///
+    /// Not allowed in sessions created to impersonate the user.
+    fn finish_webauthn_registration(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        name: WebauthnCredentialName,
+        response: WebauthnRegistrationResponse,
+    ) -> impl Future<Output = Result<WebauthnRegistration, MfaFinishWebauthnRegistrationError>> + Send;
+
+    /// Delete a WebAuthn credential.
+    ///
+    /// Completely disables MFA if this was the last remaining second factor.
+    ///
+    /// Requires the `user_write` permission if not used on the authenticated
+    /// user.
+    ///
+    /// Not allowed in sessions created to impersonate the user.
+    fn delete_webauthn_credential(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        credential_id: WebauthnCredentialId,
+    ) -> impl Future<Output = Result<(), MfaDeleteWebauthnCredentialError>> + Send;
+
+    /// Generate the options for authenticating using a WebAuthn credential
+    /// when creating a new session.
+    fn start_webauthn_authentication(
+        &self,
+    ) -> impl Future<Output = anyhow::Result<WebauthnAuthenticationOptions>> + Send;
 }
 
 #[derive(Debug, Error)]
@@ -83,3 +234,97 @@ pub enum MfaDisableError {
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
+
+#[derive(Debug, Error)]
+pub enum MfaListTotpDevicesError {
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum MfaCreateTotpDeviceError {
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+    #[error("The user does not exist.")]
+    NotFound,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum MfaConfirmTotpDeviceError {
+    #[error("The totp device has already been enabled.")]
+    AlreadyEnabled,
+    #[error("The totp code in incorrect.")]
+    InvalidCode,
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+    #[error("The totp device does not exist.")]
+    NotFound,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum MfaUpdateTotpDeviceError {
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+    #[error("The totp device does not exist.")]
+    NotFound,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum MfaDeleteTotpDeviceError {
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+    #[error("The totp device does not exist.")]
+    NotFound,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum MfaListWebauthnCredentialsError {
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum MfaStartWebauthnRegistrationError {
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+    #[error("The user does not exist.")]
+    NotFound,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum MfaFinishWebauthnRegistrationError {
+    #[error("The registration response is invalid or the challenge has expired.")]
+    InvalidResponse,
+    #[error("The credential has already been registered.")]
+    AlreadyRegistered,
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+    #[error("The user does not exist.")]
+    NotFound,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum MfaDeleteWebauthnCredentialError {
+    #[error("The credential does not exist.")]
+    NotFound,
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
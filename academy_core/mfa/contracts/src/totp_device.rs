@@ -1,7 +1,7 @@
 use std::future::Future;
 
 use academy_models::{
-    mfa::{TotpCode, TotpDevice, TotpDeviceId, TotpSetup},
+    mfa::{TotpCode, TotpDevice, TotpDeviceId, TotpDeviceName, TotpSetup},
     user::UserId,
 };
 use thiserror::Error;
@@ -13,7 +13,8 @@ pub trait MfaTotpDeviceService<Txn: Send + Sync + 'static>: Send + Sync + 'stati
         &self,
         txn: &mut Txn,
         user_id: UserId,
-    ) -> impl Future<Output = anyhow::Result<TotpSetup>> + Send;
+        name: TotpDeviceName,
+    ) -> impl Future<Output = anyhow::Result<(TotpDevice, TotpSetup)>> + Send;
 
     /// Confirm a previously created TOTP device.
     fn confirm(
@@ -41,14 +42,20 @@ pub enum MfaTotpDeviceConfirmError {
 
 #[cfg(feature = "mock")]
 impl<Txn: Send + Sync + 'static> MockMfaTotpDeviceService<Txn> {
-    pub fn with_create(mut self, user_id: UserId, result: TotpSetup) -> Self {
+    pub fn with_create(
+        mut self,
+        user_id: UserId,
+        name: TotpDeviceName,
+        result: (TotpDevice, TotpSetup),
+    ) -> Self {
         self.expect_create()
             .once()
             .with(
                 mockall::predicate::always(),
                 mockall::predicate::eq(user_id),
+                mockall::predicate::eq(name),
             )
-            .return_once(|_, _| Box::pin(std::future::ready(Ok(result))));
+            .return_once(|_, _, _| Box::pin(std::future::ready(Ok(result))));
         self
     }
 
@@ -0,0 +1,125 @@
+use std::future::Future;
+
+use academy_models::{
+    mfa::{
+        WebauthnAssertion, WebauthnAuthenticationOptions, WebauthnCredential,
+        WebauthnCredentialName, WebauthnRegistrationOptions, WebauthnRegistrationResponse,
+    },
+    user::{UserComposite, UserId},
+};
+use thiserror::Error;
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait MfaWebauthnService<Txn: Send + Sync + 'static>: Send + Sync + 'static {
+    /// Generate the options for registering a new WebAuthn credential and
+    /// temporarily remember the challenge.
+    fn start_registration(
+        &self,
+        txn: &mut Txn,
+        user_composite: &UserComposite,
+    ) -> impl Future<Output = anyhow::Result<WebauthnRegistrationOptions>> + Send;
+
+    /// Verify the response to a previously started registration and save the
+    /// new WebAuthn credential.
+    fn finish_registration(
+        &self,
+        txn: &mut Txn,
+        user_id: UserId,
+        name: WebauthnCredentialName,
+        response: WebauthnRegistrationResponse,
+    ) -> impl Future<Output = Result<WebauthnCredential, MfaWebauthnRegisterError>> + Send;
+
+    /// Generate the options for authenticating using a WebAuthn credential
+    /// and temporarily remember the challenge.
+    fn start_authentication(
+        &self,
+    ) -> impl Future<Output = anyhow::Result<WebauthnAuthenticationOptions>> + Send;
+
+    /// Authenticate the given user using a WebAuthn assertion.
+    fn authenticate(
+        &self,
+        txn: &mut Txn,
+        user_id: UserId,
+        assertion: WebauthnAssertion,
+    ) -> impl Future<Output = Result<(), MfaWebauthnAuthenticateError>> + Send;
+}
+
+#[derive(Debug, Error)]
+pub enum MfaWebauthnRegisterError {
+    #[error("The registration response is invalid or the challenge has expired.")]
+    InvalidResponse,
+    #[error("The credential has already been registered.")]
+    AlreadyRegistered,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum MfaWebauthnAuthenticateError {
+    #[error("The user failed to authenticate.")]
+    Failed,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[cfg(feature = "mock")]
+impl<Txn: Send + Sync + 'static> MockMfaWebauthnService<Txn> {
+    pub fn with_start_registration(
+        mut self,
+        user_composite: UserComposite,
+        result: WebauthnRegistrationOptions,
+    ) -> Self {
+        self.expect_start_registration()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(user_composite),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_finish_registration(
+        mut self,
+        user_id: UserId,
+        name: WebauthnCredentialName,
+        response: WebauthnRegistrationResponse,
+        result: Result<WebauthnCredential, MfaWebauthnRegisterError>,
+    ) -> Self {
+        self.expect_finish_registration()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(user_id),
+                mockall::predicate::eq(name),
+                mockall::predicate::eq(response),
+            )
+            .return_once(|_, _, _, _| Box::pin(std::future::ready(result)));
+        self
+    }
+
+    pub fn with_start_authentication(mut self, result: WebauthnAuthenticationOptions) -> Self {
+        self.expect_start_authentication()
+            .once()
+            .with()
+            .return_once(|| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_authenticate(
+        mut self,
+        user_id: UserId,
+        assertion: WebauthnAssertion,
+        result: Result<(), MfaWebauthnAuthenticateError>,
+    ) -> Self {
+        self.expect_authenticate()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(user_id),
+                mockall::predicate::eq(assertion),
+            )
+            .return_once(|_, _, _| Box::pin(std::future::ready(result)));
+        self
+    }
+}
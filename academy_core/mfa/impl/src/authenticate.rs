@@ -1,12 +1,17 @@
 use academy_core_mfa_contracts::{
     authenticate::{MfaAuthenticateError, MfaAuthenticateResult, MfaAuthenticateService},
     disable::MfaDisableService,
+    webauthn::{MfaWebauthnAuthenticateError, MfaWebauthnService},
 };
 use academy_di::Build;
-use academy_models::{mfa::MfaAuthentication, user::UserId};
+use academy_models::{
+    mfa::{MfaAuthentication, TotpDevicePatchRef},
+    user::UserId,
+};
 use academy_persistence_contracts::mfa::MfaRepository;
 use academy_shared_contracts::{
     hash::HashService,
+    time::TimeService,
     totp::{TotpCheckError, TotpService},
 };
 use academy_utils::trace_instrument;
@@ -14,20 +19,24 @@ use anyhow::Context;
 use tracing::trace;
 
 #[derive(Debug, Clone, Build, Default)]
-pub struct MfaAuthenticateServiceImpl<Hash, Totp, MfaDisable, MfaRepo> {
+pub struct MfaAuthenticateServiceImpl<Time, Hash, Totp, MfaDisable, MfaWebauthn, MfaRepo> {
+    time: Time,
     hash: Hash,
     totp: Totp,
     mfa_disable: MfaDisable,
+    mfa_webauthn: MfaWebauthn,
     mfa_repo: MfaRepo,
 }
 
-impl<Txn, Hash, Totp, MfaDisable, MfaRepo> MfaAuthenticateService<Txn>
-    for MfaAuthenticateServiceImpl<Hash, Totp, MfaDisable, MfaRepo>
+impl<Txn, Time, Hash, Totp, MfaDisable, MfaWebauthn, MfaRepo> MfaAuthenticateService<Txn>
+    for MfaAuthenticateServiceImpl<Time, Hash, Totp, MfaDisable, MfaWebauthn, MfaRepo>
 where
     Txn: Send + Sync + 'static,
+    Time: TimeService,
     Hash: HashService,
     Totp: TotpService,
     MfaDisable: MfaDisableService<Txn>,
+    MfaWebauthn: MfaWebauthnService<Txn>,
     MfaRepo: MfaRepository<Txn>,
 {
     #[trace_instrument(skip(self, txn))]
@@ -45,8 +54,17 @@ where
             .context("Failed to get totp secrets from database")?;
 
         if totp_secrets.is_empty() {
-            trace!("no totp secrets");
-            return Ok(MfaAuthenticateResult::Disabled);
+            trace!("no totp secrets, list webauthn credentials");
+            let webauthn_credentials = self
+                .mfa_repo
+                .list_webauthn_credentials_by_user(txn, user_id)
+                .await
+                .context("Failed to get webauthn credentials from database")?;
+
+            if webauthn_credentials.is_empty() {
+                trace!("no webauthn credentials");
+                return Ok(MfaAuthenticateResult::Disabled);
+            }
         }
 
         if let Some(recovery_code) = cmd.recovery_code {
@@ -69,13 +87,41 @@ where
             }
         }
 
+        if let Some(assertion) = cmd.webauthn {
+            trace!("try webauthn assertion");
+
+            match self
+                .mfa_webauthn
+                .authenticate(txn, user_id, assertion)
+                .await
+            {
+                Ok(()) => {
+                    trace!("webauthn assertion is valid");
+                    return Ok(MfaAuthenticateResult::Ok);
+                }
+                Err(MfaWebauthnAuthenticateError::Failed) => (),
+                Err(MfaWebauthnAuthenticateError::Other(err)) => {
+                    return Err(err.context("Failed to check webauthn assertion").into())
+                }
+            }
+        }
+
         if let Some(code) = cmd.totp_code {
             trace!("try totp code");
 
-            for secret in totp_secrets {
+            for (totp_device_id, secret) in totp_secrets {
                 match self.totp.check(&code, secret).await {
                     Ok(()) => {
-                        trace!("totp code matches");
+                        trace!(?totp_device_id, "totp code matches");
+                        self.mfa_repo
+                            .update_totp_device(
+                                txn,
+                                totp_device_id,
+                                TotpDevicePatchRef::new()
+                                    .update_last_used_at(&Some(self.time.now())),
+                            )
+                            .await
+                            .context("Failed to update totp device in database")?;
                         return Ok(MfaAuthenticateResult::Ok);
                     }
                     Err(TotpCheckError::InvalidCode | TotpCheckError::RecentlyUsed) => (),
@@ -94,12 +140,19 @@ where
 
 #[cfg(test)]
 mod tests {
-    use academy_core_mfa_contracts::disable::MockMfaDisableService;
-    use academy_demo::{user::FOO, SHA256HASH1, SHA256HASH2};
-    use academy_models::mfa::TotpSecret;
+    use academy_core_mfa_contracts::{
+        disable::MockMfaDisableService, webauthn::MockMfaWebauthnService,
+    };
+    use academy_demo::{
+        mfa::{ADMIN2_TOTP_1, ADMIN2_WEBAUTHN_1, FOO_TOTP_1},
+        user::{ADMIN2, FOO},
+        SHA256HASH1, SHA256HASH2,
+    };
+    use academy_models::mfa::{TotpDevicePatch, TotpSecret, WebauthnAssertion};
     use academy_persistence_contracts::mfa::MockMfaRepository;
     use academy_shared_contracts::{
         hash::MockHashService,
+        time::MockTimeService,
         totp::{MockTotpService, TotpCheckError},
     };
     use academy_utils::assert_matches;
@@ -107,9 +160,11 @@ mod tests {
     use super::*;
 
     type Sut = MfaAuthenticateServiceImpl<
+        MockTimeService,
         MockHashService,
         MockTotpService,
         MockMfaDisableService<()>,
+        MockMfaWebauthnService<()>,
         MockMfaRepository<()>,
     >;
 
@@ -119,10 +174,12 @@ mod tests {
         let cmd = MfaAuthentication {
             totp_code: None,
             recovery_code: None,
+            webauthn: None,
         };
 
         let mfa_repo = MockMfaRepository::new()
-            .with_list_enabled_totp_device_secrets_by_user(FOO.user.id, vec![]);
+            .with_list_enabled_totp_device_secrets_by_user(FOO.user.id, vec![])
+            .with_list_webauthn_credentials_by_user(FOO.user.id, vec![]);
 
         let sut = MfaAuthenticateServiceImpl {
             mfa_repo,
@@ -142,6 +199,7 @@ mod tests {
         let cmd = MfaAuthentication {
             totp_code: None,
             recovery_code: Some("PJVURV-QRK3YJ-O3U7T6-D50KAC".try_into().unwrap()),
+            webauthn: None,
         };
 
         let secret =
@@ -153,7 +211,10 @@ mod tests {
         let mfa_disable = MockMfaDisableService::new().with_disable(FOO.user.id);
 
         let mfa_repo = MockMfaRepository::new()
-            .with_list_enabled_totp_device_secrets_by_user(FOO.user.id, vec![secret])
+            .with_list_enabled_totp_device_secrets_by_user(
+                FOO.user.id,
+                vec![(FOO_TOTP_1.id, secret)],
+            )
             .with_get_mfa_recovery_code_hash(FOO.user.id, Some((*SHA256HASH1).into()));
 
         let sut = MfaAuthenticateServiceImpl {
@@ -176,6 +237,7 @@ mod tests {
         let cmd = MfaAuthentication {
             totp_code: Some("123456".try_into().unwrap()),
             recovery_code: None,
+            webauthn: None,
         };
 
         let secret =
@@ -187,10 +249,21 @@ mod tests {
             Ok(()),
         );
 
+        let time = MockTimeService::new().with_now(FOO.user.last_login.unwrap());
+
         let mfa_repo = MockMfaRepository::new()
-            .with_list_enabled_totp_device_secrets_by_user(FOO.user.id, vec![secret]);
+            .with_list_enabled_totp_device_secrets_by_user(
+                FOO.user.id,
+                vec![(FOO_TOTP_1.id, secret)],
+            )
+            .with_update_totp_device(
+                FOO_TOTP_1.id,
+                TotpDevicePatch::new().update_last_used_at(FOO.user.last_login),
+                true,
+            );
 
         let sut = MfaAuthenticateServiceImpl {
+            time,
             totp,
             mfa_repo,
             ..Sut::default()
@@ -203,19 +276,116 @@ mod tests {
         assert_eq!(result.unwrap(), MfaAuthenticateResult::Ok);
     }
 
+    #[tokio::test]
+    async fn ok_webauthn() {
+        // Arrange
+        let assertion = webauthn_assertion();
+        let cmd = MfaAuthentication {
+            totp_code: None,
+            recovery_code: None,
+            webauthn: Some(assertion.clone()),
+        };
+
+        let mfa_webauthn =
+            MockMfaWebauthnService::new().with_authenticate(ADMIN2.user.id, assertion, Ok(()));
+
+        let mfa_repo = MockMfaRepository::new()
+            .with_list_enabled_totp_device_secrets_by_user(ADMIN2.user.id, vec![])
+            .with_list_webauthn_credentials_by_user(
+                ADMIN2.user.id,
+                vec![ADMIN2_WEBAUTHN_1.clone()],
+            );
+
+        let sut = MfaAuthenticateServiceImpl {
+            mfa_webauthn,
+            mfa_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.authenticate(&mut (), ADMIN2.user.id, cmd).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), MfaAuthenticateResult::Ok);
+    }
+
+    #[tokio::test]
+    async fn failed_webauthn_only_no_authentication() {
+        // Arrange
+        let cmd = MfaAuthentication::default();
+
+        let mfa_repo = MockMfaRepository::new()
+            .with_list_enabled_totp_device_secrets_by_user(ADMIN2.user.id, vec![])
+            .with_list_webauthn_credentials_by_user(
+                ADMIN2.user.id,
+                vec![ADMIN2_WEBAUTHN_1.clone()],
+            );
+
+        let sut = MfaAuthenticateServiceImpl {
+            mfa_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.authenticate(&mut (), ADMIN2.user.id, cmd).await;
+
+        // Assert
+        assert_matches!(result, Err(MfaAuthenticateError::Failed));
+    }
+
+    #[tokio::test]
+    async fn failed_invalid_webauthn_assertion() {
+        // Arrange
+        let assertion = webauthn_assertion();
+        let cmd = MfaAuthentication {
+            totp_code: None,
+            recovery_code: None,
+            webauthn: Some(assertion.clone()),
+        };
+
+        let secret =
+            TotpSecret::try_new("IZ6GJPVVwQWfRhQTuxwrdBfn".to_owned().into_bytes()).unwrap();
+
+        let mfa_webauthn = MockMfaWebauthnService::new().with_authenticate(
+            ADMIN2.user.id,
+            assertion,
+            Err(MfaWebauthnAuthenticateError::Failed),
+        );
+
+        let mfa_repo = MockMfaRepository::new().with_list_enabled_totp_device_secrets_by_user(
+            ADMIN2.user.id,
+            vec![(ADMIN2_TOTP_1.id, secret)],
+        );
+
+        let sut = MfaAuthenticateServiceImpl {
+            mfa_webauthn,
+            mfa_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.authenticate(&mut (), ADMIN2.user.id, cmd).await;
+
+        // Assert
+        assert_matches!(result, Err(MfaAuthenticateError::Failed));
+    }
+
     #[tokio::test]
     async fn failed_no_authentication() {
         // Arrange
         let cmd = MfaAuthentication {
             totp_code: None,
             recovery_code: None,
+            webauthn: None,
         };
 
         let secret =
             TotpSecret::try_new("IZ6GJPVVwQWfRhQTuxwrdBfn".to_owned().into_bytes()).unwrap();
 
-        let mfa_repo = MockMfaRepository::new()
-            .with_list_enabled_totp_device_secrets_by_user(FOO.user.id, vec![secret]);
+        let mfa_repo = MockMfaRepository::new().with_list_enabled_totp_device_secrets_by_user(
+            FOO.user.id,
+            vec![(FOO_TOTP_1.id, secret)],
+        );
 
         let sut = MfaAuthenticateServiceImpl {
             mfa_repo,
@@ -235,13 +405,17 @@ mod tests {
         let cmd = MfaAuthentication {
             totp_code: None,
             recovery_code: Some("PJVURV-QRK3YJ-O3U7T6-D50KAC".try_into().unwrap()),
+            webauthn: None,
         };
 
         let secret =
             TotpSecret::try_new("IZ6GJPVVwQWfRhQTuxwrdBfn".to_owned().into_bytes()).unwrap();
 
         let mfa_repo = MockMfaRepository::new()
-            .with_list_enabled_totp_device_secrets_by_user(FOO.user.id, vec![secret])
+            .with_list_enabled_totp_device_secrets_by_user(
+                FOO.user.id,
+                vec![(FOO_TOTP_1.id, secret)],
+            )
             .with_get_mfa_recovery_code_hash(FOO.user.id, None);
 
         let sut = MfaAuthenticateServiceImpl {
@@ -262,6 +436,7 @@ mod tests {
         let cmd = MfaAuthentication {
             totp_code: None,
             recovery_code: Some("PJVURV-QRK3YJ-O3U7T6-D50KAC".try_into().unwrap()),
+            webauthn: None,
         };
 
         let secret =
@@ -271,7 +446,10 @@ mod tests {
             MockHashService::new().with_sha256(cmd.recovery_code.clone().unwrap(), *SHA256HASH1);
 
         let mfa_repo = MockMfaRepository::new()
-            .with_list_enabled_totp_device_secrets_by_user(FOO.user.id, vec![secret])
+            .with_list_enabled_totp_device_secrets_by_user(
+                FOO.user.id,
+                vec![(FOO_TOTP_1.id, secret)],
+            )
             .with_get_mfa_recovery_code_hash(FOO.user.id, Some((*SHA256HASH2).into()));
 
         let sut = MfaAuthenticateServiceImpl {
@@ -293,6 +471,7 @@ mod tests {
         let cmd = MfaAuthentication {
             totp_code: Some("123456".try_into().unwrap()),
             recovery_code: None,
+            webauthn: None,
         };
 
         let secret =
@@ -304,8 +483,10 @@ mod tests {
             Err(TotpCheckError::InvalidCode),
         );
 
-        let mfa_repo = MockMfaRepository::new()
-            .with_list_enabled_totp_device_secrets_by_user(FOO.user.id, vec![secret]);
+        let mfa_repo = MockMfaRepository::new().with_list_enabled_totp_device_secrets_by_user(
+            FOO.user.id,
+            vec![(FOO_TOTP_1.id, secret)],
+        );
 
         let sut = MfaAuthenticateServiceImpl {
             totp,
@@ -326,6 +507,7 @@ mod tests {
         let cmd = MfaAuthentication {
             totp_code: Some("123456".try_into().unwrap()),
             recovery_code: None,
+            webauthn: None,
         };
 
         let secret =
@@ -337,8 +519,10 @@ mod tests {
             Err(TotpCheckError::RecentlyUsed),
         );
 
-        let mfa_repo = MockMfaRepository::new()
-            .with_list_enabled_totp_device_secrets_by_user(FOO.user.id, vec![secret]);
+        let mfa_repo = MockMfaRepository::new().with_list_enabled_totp_device_secrets_by_user(
+            FOO.user.id,
+            vec![(FOO_TOTP_1.id, secret)],
+        );
 
         let sut = MfaAuthenticateServiceImpl {
             totp,
@@ -352,4 +536,13 @@ mod tests {
         // Assert
         assert_matches!(result, Err(MfaAuthenticateError::Failed));
     }
+
+    fn webauthn_assertion() -> WebauthnAssertion {
+        WebauthnAssertion {
+            raw_id: ADMIN2_WEBAUTHN_1.raw_id.clone(),
+            client_data_json: b"client data".to_vec(),
+            authenticator_data: b"authenticator data".to_vec(),
+            signature: b"signature".to_vec(),
+        }
+    }
 }
@@ -24,6 +24,12 @@ where
             .await
             .context("Failed to delete totp devices from database")?;
 
+        trace!("delete webauthn credentials");
+        self.mfa_repo
+            .delete_webauthn_credentials_by_user(txn, user_id)
+            .await
+            .context("Failed to delete webauthn credentials from database")?;
+
         trace!("delete recovery code");
         self.mfa_repo
             .delete_mfa_recovery_code_hash(txn, user_id)
@@ -46,6 +52,7 @@ mod tests {
         // Arrange
         let mfa_repo = MockMfaRepository::new()
             .with_delete_totp_devices_by_user(FOO.user.id)
+            .with_delete_webauthn_credentials_by_user(FOO.user.id)
             .with_delete_mfa_recovery_code_hash(FOO.user.id);
 
         let sut = MfaDisableServiceImpl { mfa_repo };
@@ -1,31 +1,49 @@
 use academy_auth_contracts::{AuthResultExt, AuthService};
+use academy_core_audit_contracts::log::AuditLogService;
 use academy_core_mfa_contracts::{
     disable::MfaDisableService,
     recovery::MfaRecoveryService,
     totp_device::{MfaTotpDeviceConfirmError, MfaTotpDeviceService},
-    MfaDisableError, MfaEnableError, MfaFeatureService, MfaInitializeError,
+    webauthn::{MfaWebauthnRegisterError, MfaWebauthnService},
+    MfaConfirmTotpDeviceError, MfaCreateTotpDeviceError, MfaDeleteTotpDeviceError,
+    MfaDeleteWebauthnCredentialError, MfaDisableError, MfaEnableError, MfaFeatureService,
+    MfaFinishWebauthnRegistrationError, MfaInitializeError, MfaListTotpDevicesError,
+    MfaListWebauthnCredentialsError, MfaStartWebauthnRegistrationError, MfaUpdateTotpDeviceError,
 };
 use academy_di::Build;
+use academy_email_contracts::template::TemplateEmailService;
 use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
     auth::AccessToken,
-    mfa::{MfaRecoveryCode, TotpCode, TotpSetup},
+    mfa::{
+        MfaRecoveryCode, TotpCode, TotpDevice, TotpDeviceId, TotpDeviceName, TotpDevicePatch,
+        TotpSetup, WebauthnAuthenticationOptions, WebauthnCredential, WebauthnCredentialId,
+        WebauthnCredentialName, WebauthnRegistration, WebauthnRegistrationOptions,
+        WebauthnRegistrationResponse,
+    },
+    role::Permission,
     user::UserIdOrSelf,
 };
 use academy_persistence_contracts::{
     mfa::MfaRepository, user::UserRepository, Database, Transaction,
 };
-use academy_utils::trace_instrument;
+use academy_templates_contracts::MfaDisabledTemplate;
+use academy_utils::{patch::Patch, trace_instrument};
 use anyhow::Context;
-use tracing::trace;
+use tracing::{error, trace};
 
 pub mod authenticate;
 pub mod disable;
 pub mod recovery;
 pub mod totp_device;
+pub mod webauthn;
 
 #[cfg(test)]
 mod tests;
 
+/// Name of the TOTP device created by [`MfaFeatureService::initialize`]
+const DEFAULT_TOTP_DEVICE_NAME: &str = "Authenticator";
+
 #[derive(Debug, Clone, Build, Default)]
 pub struct MfaFeatureServiceImpl<
     Db,
@@ -35,6 +53,9 @@ pub struct MfaFeatureServiceImpl<
     MfaRecovery,
     MfaDisable,
     MfaTotpDevice,
+    MfaWebauthn,
+    TemplateEmail,
+    AuditLog,
 > {
     db: Db,
     auth: Auth,
@@ -43,10 +64,35 @@ pub struct MfaFeatureServiceImpl<
     mfa_recovery: MfaRecovery,
     mfa_disable: MfaDisable,
     mfa_totp_device: MfaTotpDevice,
+    mfa_webauthn: MfaWebauthn,
+    template_email: TemplateEmail,
+    audit_log: AuditLog,
 }
 
-impl<Db, Auth, UserRepo, MfaRepo, MfaRecovery, MfaDisable, MfaTotpDevice> MfaFeatureService
-    for MfaFeatureServiceImpl<Db, Auth, UserRepo, MfaRepo, MfaRecovery, MfaDisable, MfaTotpDevice>
+impl<
+        Db,
+        Auth,
+        UserRepo,
+        MfaRepo,
+        MfaRecovery,
+        MfaDisable,
+        MfaTotpDevice,
+        MfaWebauthn,
+        TemplateEmail,
+        AuditLog,
+    > MfaFeatureService
+    for MfaFeatureServiceImpl<
+        Db,
+        Auth,
+        UserRepo,
+        MfaRepo,
+        MfaRecovery,
+        MfaDisable,
+        MfaTotpDevice,
+        MfaWebauthn,
+        TemplateEmail,
+        AuditLog,
+    >
 where
     Db: Database,
     Auth: AuthService<Db::Transaction>,
@@ -55,6 +101,9 @@ where
     MfaRecovery: MfaRecoveryService<Db::Transaction>,
     MfaDisable: MfaDisableService<Db::Transaction>,
     MfaTotpDevice: MfaTotpDeviceService<Db::Transaction>,
+    MfaWebauthn: MfaWebauthnService<Db::Transaction>,
+    TemplateEmail: TemplateEmailService,
+    AuditLog: AuditLogService<Db::Transaction>,
 {
     #[trace_instrument(skip(self))]
     async fn initialize(
@@ -64,7 +113,9 @@ where
     ) -> Result<TotpSetup, MfaInitializeError> {
         let auth = self.auth.authenticate(token).await.map_auth_err()?;
         let user_id = user_id.unwrap_or(auth.user_id);
-        auth.ensure_self_or_admin(user_id).map_auth_err()?;
+        auth.ensure_self_or_permission(user_id, Permission::UserWrite)
+            .map_auth_err()?;
+        auth.ensure_not_impersonated().map_auth_err()?;
 
         let mut txn = self.db.begin_transaction().await?;
 
@@ -100,9 +151,11 @@ where
                 })?
         } else {
             trace!("create new device");
+            let name = TotpDeviceName::try_new(DEFAULT_TOTP_DEVICE_NAME).unwrap();
             self.mfa_totp_device
-                .create(&mut txn, user_id)
+                .create(&mut txn, user_id, name)
                 .await
+                .map(|(_, setup)| setup)
                 .context("Failed to create new totp device")?
         };
 
@@ -117,22 +170,22 @@ where
         token: &AccessToken,
         user_id: UserIdOrSelf,
         code: TotpCode,
-    ) -> Result<MfaRecoveryCode, MfaEnableError> {
+    ) -> Result<Option<MfaRecoveryCode>, MfaEnableError> {
         let auth = self.auth.authenticate(token).await.map_auth_err()?;
         let user_id = user_id.unwrap_or(auth.user_id);
-        auth.ensure_self_or_admin(user_id).map_auth_err()?;
+        auth.ensure_self_or_permission(user_id, Permission::UserWrite)
+            .map_auth_err()?;
+        auth.ensure_not_impersonated().map_auth_err()?;
 
         let mut txn = self.db.begin_transaction().await?;
 
-        trace!("check user existence");
-        if !self
+        trace!("get user");
+        let user_composite = self
             .user_repo
-            .exists(&mut txn, user_id)
+            .get_composite(&mut txn, user_id)
             .await
-            .context("Failed to check user existence")?
-        {
-            return Err(MfaEnableError::NotFound);
-        }
+            .context("Failed to get user from database")?
+            .ok_or(MfaEnableError::NotFound)?;
 
         trace!("list totp devices");
         let totp_devices = self
@@ -163,12 +216,17 @@ where
                     .into(),
             })?;
 
-        trace!("setup recovery code");
-        let recovery_code = self
-            .mfa_recovery
-            .setup(&mut txn, user_id)
-            .await
-            .context("Failed to setup recovery code")?;
+        let recovery_code = if !user_composite.details.mfa_enabled {
+            trace!("setup recovery code");
+            let recovery_code = self
+                .mfa_recovery
+                .setup(&mut txn, user_id)
+                .await
+                .context("Failed to setup recovery code")?;
+            Some(recovery_code)
+        } else {
+            None
+        };
 
         txn.commit().await?;
 
@@ -180,10 +238,111 @@ where
         &self,
         token: &AccessToken,
         user_id: UserIdOrSelf,
+        client: ClientInfo,
     ) -> Result<(), MfaDisableError> {
         let auth = self.auth.authenticate(token).await.map_auth_err()?;
         let user_id = user_id.unwrap_or(auth.user_id);
-        auth.ensure_self_or_admin(user_id).map_auth_err()?;
+        auth.ensure_self_or_permission(user_id, Permission::UserWrite)
+            .map_auth_err()?;
+        auth.ensure_not_impersonated().map_auth_err()?;
+
+        let mut txn = self.db.begin_transaction().await?;
+
+        trace!("get user");
+        let user_composite = self
+            .user_repo
+            .get_composite(&mut txn, user_id)
+            .await
+            .context("Failed to get user from database")?
+            .ok_or(MfaDisableError::NotFound)?;
+
+        trace!("list totp devices");
+        let totp_devices = self
+            .mfa_repo
+            .list_totp_devices_by_user(&mut txn, user_id)
+            .await
+            .context("Failed to get totp devices from database")?;
+
+        trace!("list webauthn credentials");
+        let webauthn_credentials = self
+            .mfa_repo
+            .list_webauthn_credentials_by_user(&mut txn, user_id)
+            .await
+            .context("Failed to get webauthn credentials from database")?;
+
+        if totp_devices.iter().all(|x| !x.enabled) && webauthn_credentials.is_empty() {
+            return Err(MfaDisableError::NotEnabled);
+        }
+
+        trace!("disable mfa");
+        self.mfa_disable
+            .disable(&mut txn, user_id)
+            .await
+            .context("Failed to disable mfa")?;
+
+        trace!("record audit event");
+        self.audit_log
+            .record(
+                &mut txn,
+                Some(auth.user_id),
+                user_id,
+                AuditEventKind::MfaDisabled,
+                client,
+            )
+            .await
+            .context("Failed to record audit event")?;
+
+        txn.commit().await?;
+
+        if let Some(email) = user_composite.user.email {
+            trace!("send notification email");
+            if let Err(err) = self
+                .template_email
+                .send_mfa_disabled_email(
+                    email.with_name(user_composite.profile.display_name.into_inner()),
+                    &MfaDisabledTemplate {},
+                )
+                .await
+            {
+                error!("Failed to send mfa disabled email: {err:#}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn list_totp_devices(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+    ) -> Result<Vec<TotpDevice>, MfaListTotpDevicesError> {
+        let auth = self.auth.authenticate(token).await.map_auth_err()?;
+        let user_id = user_id.unwrap_or(auth.user_id);
+        auth.ensure_self_or_permission(user_id, Permission::UserRead)
+            .map_auth_err()?;
+
+        let mut txn = self.db.begin_transaction().await?;
+
+        self.mfa_repo
+            .list_totp_devices_by_user(&mut txn, user_id)
+            .await
+            .context("Failed to get totp devices from database")
+            .map_err(Into::into)
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn create_totp_device(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        name: TotpDeviceName,
+    ) -> Result<(TotpDevice, TotpSetup), MfaCreateTotpDeviceError> {
+        let auth = self.auth.authenticate(token).await.map_auth_err()?;
+        let user_id = user_id.unwrap_or(auth.user_id);
+        auth.ensure_self_or_permission(user_id, Permission::UserWrite)
+            .map_auth_err()?;
+        auth.ensure_not_impersonated().map_auth_err()?;
 
         let mut txn = self.db.begin_transaction().await?;
 
@@ -194,9 +353,138 @@ where
             .await
             .context("Failed to check user existence")?
         {
-            return Err(MfaDisableError::NotFound);
+            return Err(MfaCreateTotpDeviceError::NotFound);
+        }
+
+        trace!("create new device");
+        let result = self
+            .mfa_totp_device
+            .create(&mut txn, user_id, name)
+            .await
+            .context("Failed to create new totp device")?;
+
+        txn.commit().await?;
+
+        Ok(result)
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn confirm_totp_device(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        totp_device_id: TotpDeviceId,
+        code: TotpCode,
+    ) -> Result<Option<MfaRecoveryCode>, MfaConfirmTotpDeviceError> {
+        let auth = self.auth.authenticate(token).await.map_auth_err()?;
+        let user_id = user_id.unwrap_or(auth.user_id);
+        auth.ensure_self_or_permission(user_id, Permission::UserWrite)
+            .map_auth_err()?;
+        auth.ensure_not_impersonated().map_auth_err()?;
+
+        let mut txn = self.db.begin_transaction().await?;
+
+        trace!("get user");
+        let user_composite = self
+            .user_repo
+            .get_composite(&mut txn, user_id)
+            .await
+            .context("Failed to get user from database")?
+            .ok_or(MfaConfirmTotpDeviceError::NotFound)?;
+
+        trace!("get totp device");
+        let totp_device = self
+            .mfa_repo
+            .get_totp_device(&mut txn, totp_device_id)
+            .await
+            .context("Failed to get totp device from database")?
+            .filter(|x| x.user_id == user_id)
+            .ok_or(MfaConfirmTotpDeviceError::NotFound)?;
+
+        if totp_device.enabled {
+            return Err(MfaConfirmTotpDeviceError::AlreadyEnabled);
         }
 
+        self.mfa_totp_device
+            .confirm(&mut txn, totp_device, code)
+            .await
+            .map_err(|err| match err {
+                MfaTotpDeviceConfirmError::InvalidCode => MfaConfirmTotpDeviceError::InvalidCode,
+                MfaTotpDeviceConfirmError::Other(err) => err
+                    .context(format!("Failed to confirm totp device {}", *totp_device_id))
+                    .into(),
+            })?;
+
+        let recovery_code = if !user_composite.details.mfa_enabled {
+            trace!("setup recovery code");
+            let recovery_code = self
+                .mfa_recovery
+                .setup(&mut txn, user_id)
+                .await
+                .context("Failed to setup recovery code")?;
+            Some(recovery_code)
+        } else {
+            None
+        };
+
+        txn.commit().await?;
+
+        Ok(recovery_code)
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn update_totp_device(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        totp_device_id: TotpDeviceId,
+        name: TotpDeviceName,
+    ) -> Result<TotpDevice, MfaUpdateTotpDeviceError> {
+        let auth = self.auth.authenticate(token).await.map_auth_err()?;
+        let user_id = user_id.unwrap_or(auth.user_id);
+        auth.ensure_self_or_permission(user_id, Permission::UserWrite)
+            .map_auth_err()?;
+        auth.ensure_not_impersonated().map_auth_err()?;
+
+        let mut txn = self.db.begin_transaction().await?;
+
+        trace!("get totp device");
+        let totp_device = self
+            .mfa_repo
+            .get_totp_device(&mut txn, totp_device_id)
+            .await
+            .context("Failed to get totp device from database")?
+            .filter(|x| x.user_id == user_id)
+            .ok_or(MfaUpdateTotpDeviceError::NotFound)?;
+
+        let patch = TotpDevicePatch::new().update_name(name);
+
+        trace!("update device");
+        self.mfa_repo
+            .update_totp_device(&mut txn, totp_device_id, patch.as_ref())
+            .await
+            .context("Failed to update totp device in database")?;
+
+        txn.commit().await?;
+
+        Ok(totp_device.update(patch))
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn delete_totp_device(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        totp_device_id: TotpDeviceId,
+    ) -> Result<(), MfaDeleteTotpDeviceError> {
+        let auth = self.auth.authenticate(token).await.map_auth_err()?;
+        let user_id = user_id.unwrap_or(auth.user_id);
+        auth.ensure_self_or_permission(user_id, Permission::UserWrite)
+            .map_auth_err()?;
+        auth.ensure_not_impersonated().map_auth_err()?;
+
+        let mut txn = self.db.begin_transaction().await?;
+
         trace!("list totp devices");
         let totp_devices = self
             .mfa_repo
@@ -204,18 +492,210 @@ where
             .await
             .context("Failed to get totp devices from database")?;
 
-        if totp_devices.iter().all(|x| !x.enabled) {
-            return Err(MfaDisableError::NotEnabled);
+        let totp_device = totp_devices
+            .iter()
+            .find(|x| x.id == totp_device_id)
+            .ok_or(MfaDeleteTotpDeviceError::NotFound)?;
+
+        let last_enabled_totp_device = totp_device.enabled
+            && !totp_devices
+                .iter()
+                .any(|x| x.enabled && x.id != totp_device_id);
+
+        let last_second_factor = last_enabled_totp_device && {
+            trace!("list webauthn credentials");
+            self.mfa_repo
+                .list_webauthn_credentials_by_user(&mut txn, user_id)
+                .await
+                .context("Failed to get webauthn credentials from database")?
+                .is_empty()
+        };
+
+        if last_second_factor {
+            trace!("last second factor, disable mfa");
+            self.mfa_disable
+                .disable(&mut txn, user_id)
+                .await
+                .context("Failed to disable mfa")?;
+        } else {
+            trace!("delete totp device");
+            self.mfa_repo
+                .delete_totp_device(&mut txn, totp_device_id)
+                .await
+                .context("Failed to delete totp device from database")?;
         }
 
-        trace!("disable mfa");
-        self.mfa_disable
-            .disable(&mut txn, user_id)
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn list_webauthn_credentials(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+    ) -> Result<Vec<WebauthnCredential>, MfaListWebauthnCredentialsError> {
+        let auth = self.auth.authenticate(token).await.map_auth_err()?;
+        let user_id = user_id.unwrap_or(auth.user_id);
+        auth.ensure_self_or_permission(user_id, Permission::UserRead)
+            .map_auth_err()?;
+
+        let mut txn = self.db.begin_transaction().await?;
+
+        self.mfa_repo
+            .list_webauthn_credentials_by_user(&mut txn, user_id)
             .await
-            .context("Failed to disable mfa")?;
+            .context("Failed to get webauthn credentials from database")
+            .map_err(Into::into)
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn start_webauthn_registration(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+    ) -> Result<WebauthnRegistrationOptions, MfaStartWebauthnRegistrationError> {
+        let auth = self.auth.authenticate(token).await.map_auth_err()?;
+        let user_id = user_id.unwrap_or(auth.user_id);
+        auth.ensure_self_or_permission(user_id, Permission::UserWrite)
+            .map_auth_err()?;
+        auth.ensure_not_impersonated().map_auth_err()?;
+
+        let mut txn = self.db.begin_transaction().await?;
+
+        trace!("get user");
+        let user_composite = self
+            .user_repo
+            .get_composite(&mut txn, user_id)
+            .await
+            .context("Failed to get user from database")?
+            .ok_or(MfaStartWebauthnRegistrationError::NotFound)?;
+
+        self.mfa_webauthn
+            .start_registration(&mut txn, &user_composite)
+            .await
+            .context("Failed to start webauthn registration")
+            .map_err(Into::into)
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn finish_webauthn_registration(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        name: WebauthnCredentialName,
+        response: WebauthnRegistrationResponse,
+    ) -> Result<WebauthnRegistration, MfaFinishWebauthnRegistrationError> {
+        let auth = self.auth.authenticate(token).await.map_auth_err()?;
+        let user_id = user_id.unwrap_or(auth.user_id);
+        auth.ensure_self_or_permission(user_id, Permission::UserWrite)
+            .map_auth_err()?;
+        auth.ensure_not_impersonated().map_auth_err()?;
+
+        let mut txn = self.db.begin_transaction().await?;
+
+        trace!("get user");
+        let user_composite = self
+            .user_repo
+            .get_composite(&mut txn, user_id)
+            .await
+            .context("Failed to get user from database")?
+            .ok_or(MfaFinishWebauthnRegistrationError::NotFound)?;
+
+        let credential = self
+            .mfa_webauthn
+            .finish_registration(&mut txn, user_id, name, response)
+            .await
+            .map_err(|err| match err {
+                MfaWebauthnRegisterError::InvalidResponse => {
+                    MfaFinishWebauthnRegistrationError::InvalidResponse
+                }
+                MfaWebauthnRegisterError::AlreadyRegistered => {
+                    MfaFinishWebauthnRegistrationError::AlreadyRegistered
+                }
+                MfaWebauthnRegisterError::Other(err) => {
+                    err.context("Failed to finish webauthn registration").into()
+                }
+            })?;
+
+        let recovery_code = if !user_composite.details.mfa_enabled {
+            trace!("setup recovery code");
+            let recovery_code = self
+                .mfa_recovery
+                .setup(&mut txn, user_id)
+                .await
+                .context("Failed to setup recovery code")?;
+            Some(recovery_code)
+        } else {
+            None
+        };
+
+        txn.commit().await?;
+
+        Ok(WebauthnRegistration {
+            credential,
+            recovery_code,
+        })
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn delete_webauthn_credential(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        credential_id: WebauthnCredentialId,
+    ) -> Result<(), MfaDeleteWebauthnCredentialError> {
+        let auth = self.auth.authenticate(token).await.map_auth_err()?;
+        let user_id = user_id.unwrap_or(auth.user_id);
+        auth.ensure_self_or_permission(user_id, Permission::UserWrite)
+            .map_auth_err()?;
+        auth.ensure_not_impersonated().map_auth_err()?;
+
+        let mut txn = self.db.begin_transaction().await?;
+
+        trace!("list webauthn credentials");
+        let webauthn_credentials = self
+            .mfa_repo
+            .list_webauthn_credentials_by_user(&mut txn, user_id)
+            .await
+            .context("Failed to get webauthn credentials from database")?;
+
+        if !webauthn_credentials.iter().any(|x| x.id == credential_id) {
+            return Err(MfaDeleteWebauthnCredentialError::NotFound);
+        }
+
+        trace!("list totp devices");
+        let totp_devices = self
+            .mfa_repo
+            .list_totp_devices_by_user(&mut txn, user_id)
+            .await
+            .context("Failed to get totp devices from database")?;
+
+        if webauthn_credentials.len() == 1 && totp_devices.iter().all(|x| !x.enabled) {
+            trace!("last second factor, disable mfa");
+            self.mfa_disable
+                .disable(&mut txn, user_id)
+                .await
+                .context("Failed to disable mfa")?;
+        } else {
+            trace!("delete webauthn credential");
+            self.mfa_repo
+                .delete_webauthn_credential(&mut txn, credential_id)
+                .await
+                .context("Failed to delete webauthn credential from database")?;
+        }
 
         txn.commit().await?;
 
         Ok(())
     }
+
+    #[trace_instrument(skip(self))]
+    async fn start_webauthn_authentication(&self) -> anyhow::Result<WebauthnAuthenticationOptions> {
+        self.mfa_webauthn
+            .start_authentication()
+            .await
+            .context("Failed to start webauthn authentication")
+    }
 }
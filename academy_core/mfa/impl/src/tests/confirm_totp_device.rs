@@ -0,0 +1,308 @@
+use academy_auth_contracts::MockAuthService;
+use academy_core_mfa_contracts::{
+    recovery::MockMfaRecoveryService,
+    totp_device::{MfaTotpDeviceConfirmError, MockMfaTotpDeviceService},
+    MfaConfirmTotpDeviceError, MfaFeatureService,
+};
+use academy_demo::{
+    mfa::{ADMIN2_TOTP_1, FOO_TOTP_1},
+    session::{ADMIN_1, BAR_1, FOO_1},
+    user::{ADMIN, ADMIN2, BAR, FOO},
+};
+use academy_models::{
+    auth::{AuthError, AuthenticateError, AuthorizeError},
+    mfa::{MfaRecoveryCode, TotpCode},
+    session::Session,
+    user::UserIdOrSelf,
+};
+use academy_persistence_contracts::{
+    mfa::MockMfaRepository, user::MockUserRepository, MockDatabase,
+};
+use academy_utils::{assert_matches, Apply};
+
+use crate::{tests::Sut, MfaFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok_first_factor() {
+    // Arrange
+    let expected = MfaRecoveryCode::try_new("PJVURV-QRK3YJ-O3U7T6-D50KAC").unwrap();
+    let code = TotpCode::try_new("123456").unwrap();
+
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let mfa_repo =
+        MockMfaRepository::new().with_get_totp_device(FOO_TOTP_1.id, Some(FOO_TOTP_1.clone()));
+
+    let mfa_totp_device = MockMfaTotpDeviceService::new().with_confirm(
+        FOO_TOTP_1.clone(),
+        code.clone(),
+        Ok(FOO_TOTP_1.clone().with(|x| x.enabled = true)),
+    );
+
+    let mfa_recovery = MockMfaRecoveryService::new().with_setup(FOO.user.id, expected.clone());
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        mfa_repo,
+        mfa_recovery,
+        mfa_totp_device,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .confirm_totp_device(&"token".into(), UserIdOrSelf::Slf, FOO_TOTP_1.id, code)
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), Some(expected));
+}
+
+#[tokio::test]
+async fn ok_additional_factor() {
+    // Arrange
+    let code = TotpCode::try_new("123456").unwrap();
+
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let user_repo = MockUserRepository::new().with_get_composite(
+        FOO.user.id,
+        Some(FOO.clone().with(|x| x.details.mfa_enabled = true)),
+    );
+
+    let mfa_repo =
+        MockMfaRepository::new().with_get_totp_device(FOO_TOTP_1.id, Some(FOO_TOTP_1.clone()));
+
+    let mfa_totp_device = MockMfaTotpDeviceService::new().with_confirm(
+        FOO_TOTP_1.clone(),
+        code.clone(),
+        Ok(FOO_TOTP_1.clone().with(|x| x.enabled = true)),
+    );
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        mfa_repo,
+        mfa_totp_device,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .confirm_totp_device(&"token".into(), UserIdOrSelf::Slf, FOO_TOTP_1.id, code)
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), None);
+}
+
+#[tokio::test]
+async fn unauthenticated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(None);
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .confirm_totp_device(
+            &"token".into(),
+            FOO.user.id.into(),
+            FOO_TOTP_1.id,
+            TotpCode::try_new("123456").unwrap(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaConfirmTotpDeviceError::Auth(AuthError::Authenticate(
+            AuthenticateError::InvalidToken
+        )))
+    );
+}
+
+#[tokio::test]
+async fn unauthorized() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((BAR.user.clone(), BAR_1.clone())));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .confirm_totp_device(
+            &"token".into(),
+            FOO.user.id.into(),
+            FOO_TOTP_1.id,
+            TotpCode::try_new("123456").unwrap(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaConfirmTotpDeviceError::Auth(AuthError::Authorize(
+            AuthorizeError::Permission
+        )))
+    );
+}
+
+#[tokio::test]
+async fn impersonated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((
+        FOO.user.clone(),
+        Session {
+            impersonator_id: Some(ADMIN.user.id),
+            ..FOO_1.clone()
+        },
+    )));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .confirm_totp_device(
+            &"token".into(),
+            FOO.user.id.into(),
+            FOO_TOTP_1.id,
+            TotpCode::try_new("123456").unwrap(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaConfirmTotpDeviceError::Auth(AuthError::Authorize(
+            AuthorizeError::Impersonation
+        )))
+    );
+}
+
+#[tokio::test]
+async fn device_of_other_user() {
+    // Arrange
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let mfa_repo = MockMfaRepository::new()
+        .with_get_totp_device(ADMIN2_TOTP_1.id, Some(ADMIN2_TOTP_1.clone()));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        mfa_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .confirm_totp_device(
+            &"token".into(),
+            FOO.user.id.into(),
+            ADMIN2_TOTP_1.id,
+            TotpCode::try_new("123456").unwrap(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(MfaConfirmTotpDeviceError::NotFound));
+}
+
+#[tokio::test]
+async fn already_enabled() {
+    // Arrange
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let user_repo =
+        MockUserRepository::new().with_get_composite(ADMIN2.user.id, Some(ADMIN2.clone()));
+
+    let mfa_repo = MockMfaRepository::new()
+        .with_get_totp_device(ADMIN2_TOTP_1.id, Some(ADMIN2_TOTP_1.clone()));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        mfa_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .confirm_totp_device(
+            &"token".into(),
+            ADMIN2.user.id.into(),
+            ADMIN2_TOTP_1.id,
+            TotpCode::try_new("123456").unwrap(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(MfaConfirmTotpDeviceError::AlreadyEnabled));
+}
+
+#[tokio::test]
+async fn invalid_code() {
+    // Arrange
+    let code = TotpCode::try_new("123456").unwrap();
+
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let mfa_repo =
+        MockMfaRepository::new().with_get_totp_device(FOO_TOTP_1.id, Some(FOO_TOTP_1.clone()));
+
+    let mfa_totp_device = MockMfaTotpDeviceService::new().with_confirm(
+        FOO_TOTP_1.clone(),
+        code.clone(),
+        Err(MfaTotpDeviceConfirmError::InvalidCode),
+    );
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        mfa_repo,
+        mfa_totp_device,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .confirm_totp_device(&"token".into(), UserIdOrSelf::Slf, FOO_TOTP_1.id, code)
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(MfaConfirmTotpDeviceError::InvalidCode));
+}
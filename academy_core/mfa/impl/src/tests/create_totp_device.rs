@@ -0,0 +1,176 @@
+use academy_auth_contracts::MockAuthService;
+use academy_core_mfa_contracts::{
+    totp_device::MockMfaTotpDeviceService, MfaCreateTotpDeviceError, MfaFeatureService,
+};
+use academy_demo::{
+    mfa::FOO_TOTP_1,
+    session::{ADMIN_1, BAR_1, FOO_1},
+    user::{ADMIN, BAR, FOO},
+};
+use academy_models::{
+    auth::{AuthError, AuthenticateError, AuthorizeError},
+    mfa::{TotpDeviceName, TotpSetup},
+    session::Session,
+    user::UserIdOrSelf,
+};
+use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
+use academy_utils::assert_matches;
+
+use crate::{tests::Sut, MfaFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok() {
+    // Arrange
+    let name = TotpDeviceName::try_new("Tablet").unwrap();
+    let expected = (
+        FOO_TOTP_1.clone(),
+        TotpSetup {
+            secret: "the totp secret".into(),
+        },
+    );
+
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let user_repo = MockUserRepository::new().with_exists(FOO.user.id, true);
+
+    let mfa_totp_device =
+        MockMfaTotpDeviceService::new().with_create(FOO.user.id, name.clone(), expected.clone());
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        mfa_totp_device,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_totp_device(&"token".into(), UserIdOrSelf::Slf, name)
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn unauthenticated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(None);
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_totp_device(
+            &"token".into(),
+            FOO.user.id.into(),
+            TotpDeviceName::try_new("Tablet").unwrap(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaCreateTotpDeviceError::Auth(AuthError::Authenticate(
+            AuthenticateError::InvalidToken
+        )))
+    );
+}
+
+#[tokio::test]
+async fn unauthorized() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((BAR.user.clone(), BAR_1.clone())));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_totp_device(
+            &"token".into(),
+            FOO.user.id.into(),
+            TotpDeviceName::try_new("Tablet").unwrap(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaCreateTotpDeviceError::Auth(AuthError::Authorize(
+            AuthorizeError::Permission
+        )))
+    );
+}
+
+#[tokio::test]
+async fn impersonated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((
+        FOO.user.clone(),
+        Session {
+            impersonator_id: Some(ADMIN.user.id),
+            ..FOO_1.clone()
+        },
+    )));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_totp_device(
+            &"token".into(),
+            FOO.user.id.into(),
+            TotpDeviceName::try_new("Tablet").unwrap(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaCreateTotpDeviceError::Auth(AuthError::Authorize(
+            AuthorizeError::Impersonation
+        )))
+    );
+}
+
+#[tokio::test]
+async fn user_not_found() {
+    // Arrange
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_exists(FOO.user.id, false);
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_totp_device(
+            &"token".into(),
+            FOO.user.id.into(),
+            TotpDeviceName::try_new("Tablet").unwrap(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(MfaCreateTotpDeviceError::NotFound));
+}
@@ -0,0 +1,185 @@
+use academy_auth_contracts::MockAuthService;
+use academy_core_mfa_contracts::{
+    disable::MockMfaDisableService, MfaDeleteWebauthnCredentialError, MfaFeatureService,
+};
+use academy_demo::{
+    mfa::{ADMIN2_TOTP_1, ADMIN2_WEBAUTHN_1},
+    session::{ADMIN_1, BAR_1},
+    user::{ADMIN, ADMIN2, BAR},
+};
+use academy_models::{
+    auth::{AuthError, AuthenticateError, AuthorizeError},
+    session::Session,
+};
+use academy_persistence_contracts::{mfa::MockMfaRepository, MockDatabase};
+use academy_utils::{assert_matches, Apply};
+
+use crate::{tests::Sut, MfaFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok() {
+    // Arrange
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let mfa_repo = MockMfaRepository::new()
+        .with_list_webauthn_credentials_by_user(ADMIN2.user.id, vec![ADMIN2_WEBAUTHN_1.clone()])
+        .with_list_totp_devices_by_user(ADMIN2.user.id, vec![ADMIN2_TOTP_1.clone()])
+        .with_delete_webauthn_credential(ADMIN2_WEBAUTHN_1.id, true);
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        mfa_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .delete_webauthn_credential(&"token".into(), ADMIN2.user.id.into(), ADMIN2_WEBAUTHN_1.id)
+        .await;
+
+    // Assert
+    result.unwrap();
+}
+
+#[tokio::test]
+async fn ok_last_factor() {
+    // Arrange
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let mfa_repo = MockMfaRepository::new()
+        .with_list_webauthn_credentials_by_user(ADMIN2.user.id, vec![ADMIN2_WEBAUTHN_1.clone()])
+        .with_list_totp_devices_by_user(
+            ADMIN2.user.id,
+            vec![ADMIN2_TOTP_1.clone().with(|x| x.enabled = false)],
+        );
+
+    let mfa_disable = MockMfaDisableService::new().with_disable(ADMIN2.user.id);
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        mfa_repo,
+        mfa_disable,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .delete_webauthn_credential(&"token".into(), ADMIN2.user.id.into(), ADMIN2_WEBAUTHN_1.id)
+        .await;
+
+    // Assert
+    result.unwrap();
+}
+
+#[tokio::test]
+async fn unauthenticated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(None);
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .delete_webauthn_credential(&"token".into(), ADMIN2.user.id.into(), ADMIN2_WEBAUTHN_1.id)
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaDeleteWebauthnCredentialError::Auth(
+            AuthError::Authenticate(AuthenticateError::InvalidToken)
+        ))
+    );
+}
+
+#[tokio::test]
+async fn unauthorized() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((BAR.user.clone(), BAR_1.clone())));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .delete_webauthn_credential(&"token".into(), ADMIN2.user.id.into(), ADMIN2_WEBAUTHN_1.id)
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaDeleteWebauthnCredentialError::Auth(
+            AuthError::Authorize(AuthorizeError::Permission)
+        ))
+    );
+}
+
+#[tokio::test]
+async fn impersonated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((
+        ADMIN2.user.clone(),
+        Session {
+            impersonator_id: Some(ADMIN.user.id),
+            ..ADMIN_1.clone()
+        },
+    )));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .delete_webauthn_credential(&"token".into(), ADMIN2.user.id.into(), ADMIN2_WEBAUTHN_1.id)
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaDeleteWebauthnCredentialError::Auth(
+            AuthError::Authorize(AuthorizeError::Impersonation)
+        ))
+    );
+}
+
+#[tokio::test]
+async fn not_found() {
+    // Arrange
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let mfa_repo =
+        MockMfaRepository::new().with_list_webauthn_credentials_by_user(ADMIN2.user.id, vec![]);
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        mfa_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .delete_webauthn_credential(&"token".into(), ADMIN2.user.id.into(), ADMIN2_WEBAUTHN_1.id)
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(MfaDeleteWebauthnCredentialError::NotFound));
+}
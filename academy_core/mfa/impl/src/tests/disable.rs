@@ -1,20 +1,27 @@
 use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::log::MockAuditLogService;
 use academy_core_mfa_contracts::{
     disable::MockMfaDisableService, MfaDisableError, MfaFeatureService,
 };
 use academy_demo::{
-    mfa::FOO_TOTP_1,
+    audit::FOO_AUDIT_EVENT_1,
+    mfa::{ADMIN2_WEBAUTHN_1, FOO_TOTP_1},
     session::{ADMIN_1, BAR_1, FOO_1},
-    user::{ADMIN, BAR, FOO},
+    user::{ADMIN, ADMIN2, BAR, FOO},
 };
+use academy_email_contracts::template::MockTemplateEmailService;
 use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
     auth::{AuthError, AuthenticateError, AuthorizeError},
+    session::Session,
     user::UserIdOrSelf,
 };
 use academy_persistence_contracts::{
     mfa::MockMfaRepository, user::MockUserRepository, MockDatabase,
 };
+use academy_templates_contracts::MfaDisabledTemplate;
 use academy_utils::{assert_matches, Apply};
+use anyhow::anyhow;
 
 use crate::{tests::Sut, MfaFeatureServiceImpl};
 
@@ -25,26 +32,167 @@ async fn ok() {
 
     let db = MockDatabase::build(true);
 
-    let user_repo = MockUserRepository::new().with_exists(FOO.user.id, true);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
 
-    let mfa_repo = MockMfaRepository::new().with_list_totp_devices_by_user(
+    let mfa_repo = MockMfaRepository::new()
+        .with_list_totp_devices_by_user(
+            FOO.user.id,
+            vec![FOO_TOTP_1.clone().with(|x| x.enabled = true)],
+        )
+        .with_list_webauthn_credentials_by_user(FOO.user.id, vec![]);
+
+    let mfa_disable = MockMfaDisableService::new().with_disable(FOO.user.id);
+
+    let template_email = MockTemplateEmailService::new().with_send_mfa_disabled_email(
+        FOO.user
+            .email
+            .clone()
+            .unwrap()
+            .with_name(FOO.profile.display_name.clone().into_inner()),
+        MfaDisabledTemplate {},
+        true,
+    );
+
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(FOO.user.id),
         FOO.user.id,
-        vec![FOO_TOTP_1.clone().with(|x| x.enabled = true)],
+        AuditEventKind::MfaDisabled,
+        FOO_AUDIT_EVENT_1.client.clone(),
     );
 
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        mfa_repo,
+        mfa_disable,
+        template_email,
+        audit_log,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .disable(
+            &"token".into(),
+            UserIdOrSelf::Slf,
+            FOO_AUDIT_EVENT_1.client.clone(),
+        )
+        .await;
+
+    // Assert
+    result.unwrap();
+}
+
+#[tokio::test]
+async fn ok_webauthn() {
+    // Arrange
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let user_repo =
+        MockUserRepository::new().with_get_composite(ADMIN2.user.id, Some(ADMIN2.clone()));
+
+    let mfa_repo = MockMfaRepository::new()
+        .with_list_totp_devices_by_user(ADMIN2.user.id, vec![])
+        .with_list_webauthn_credentials_by_user(ADMIN2.user.id, vec![ADMIN2_WEBAUTHN_1.clone()]);
+
+    let mfa_disable = MockMfaDisableService::new().with_disable(ADMIN2.user.id);
+
+    let template_email = MockTemplateEmailService::new().with_send_mfa_disabled_email(
+        ADMIN2
+            .user
+            .email
+            .clone()
+            .unwrap()
+            .with_name(ADMIN2.profile.display_name.clone().into_inner()),
+        MfaDisabledTemplate {},
+        true,
+    );
+
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(ADMIN.user.id),
+        ADMIN2.user.id,
+        AuditEventKind::MfaDisabled,
+        ClientInfo::default(),
+    );
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        mfa_repo,
+        mfa_disable,
+        template_email,
+        audit_log,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .disable(
+            &"token".into(),
+            ADMIN2.user.id.into(),
+            ClientInfo::default(),
+        )
+        .await;
+
+    // Assert
+    result.unwrap();
+}
+
+#[tokio::test]
+async fn email_error() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let mfa_repo = MockMfaRepository::new()
+        .with_list_totp_devices_by_user(
+            FOO.user.id,
+            vec![FOO_TOTP_1.clone().with(|x| x.enabled = true)],
+        )
+        .with_list_webauthn_credentials_by_user(FOO.user.id, vec![]);
+
     let mfa_disable = MockMfaDisableService::new().with_disable(FOO.user.id);
 
+    let mut template_email = MockTemplateEmailService::new();
+    template_email
+        .expect_send_mfa_disabled_email()
+        .once()
+        .return_once(|_, _| Box::pin(std::future::ready(Err(anyhow!("smtp error")))));
+
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(FOO.user.id),
+        FOO.user.id,
+        AuditEventKind::MfaDisabled,
+        FOO_AUDIT_EVENT_1.client.clone(),
+    );
+
     let sut = MfaFeatureServiceImpl {
         auth,
         db,
         user_repo,
         mfa_repo,
         mfa_disable,
+        template_email,
+        audit_log,
         ..Sut::default()
     };
 
     // Act
-    let result = sut.disable(&"token".into(), UserIdOrSelf::Slf).await;
+    let result = sut
+        .disable(
+            &"token".into(),
+            UserIdOrSelf::Slf,
+            FOO_AUDIT_EVENT_1.client.clone(),
+        )
+        .await;
 
     // Assert
     result.unwrap();
@@ -61,7 +209,9 @@ async fn unauthenticated() {
     };
 
     // Act
-    let result = sut.disable(&"token".into(), FOO.user.id.into()).await;
+    let result = sut
+        .disable(&"token".into(), FOO.user.id.into(), ClientInfo::default())
+        .await;
 
     // Assert
     assert_matches!(
@@ -83,13 +233,45 @@ async fn unauthorized() {
     };
 
     // Act
-    let result = sut.disable(&"token".into(), FOO.user.id.into()).await;
+    let result = sut
+        .disable(&"token".into(), FOO.user.id.into(), ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaDisableError::Auth(AuthError::Authorize(
+            AuthorizeError::Permission
+        )))
+    );
+}
+
+#[tokio::test]
+async fn impersonated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((
+        FOO.user.clone(),
+        Session {
+            impersonator_id: Some(ADMIN.user.id),
+            ..FOO_1.clone()
+        },
+    )));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .disable(&"token".into(), FOO.user.id.into(), ClientInfo::default())
+        .await;
 
     // Assert
     assert_matches!(
         result,
         Err(MfaDisableError::Auth(AuthError::Authorize(
-            AuthorizeError::Admin
+            AuthorizeError::Impersonation
         )))
     );
 }
@@ -102,7 +284,7 @@ async fn user_not_found() {
 
     let db = MockDatabase::build(false);
 
-    let user_repo = MockUserRepository::new().with_exists(FOO.user.id, false);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, None);
 
     let sut = MfaFeatureServiceImpl {
         auth,
@@ -112,7 +294,9 @@ async fn user_not_found() {
     };
 
     // Act
-    let result = sut.disable(&"token".into(), FOO.user.id.into()).await;
+    let result = sut
+        .disable(&"token".into(), FOO.user.id.into(), ClientInfo::default())
+        .await;
 
     // Assert
     assert_matches!(result, Err(MfaDisableError::NotFound));
@@ -125,9 +309,11 @@ async fn not_initialized() {
 
     let db = MockDatabase::build(false);
 
-    let user_repo = MockUserRepository::new().with_exists(FOO.user.id, true);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
 
-    let mfa_repo = MockMfaRepository::new().with_list_totp_devices_by_user(FOO.user.id, vec![]);
+    let mfa_repo = MockMfaRepository::new()
+        .with_list_totp_devices_by_user(FOO.user.id, vec![])
+        .with_list_webauthn_credentials_by_user(FOO.user.id, vec![]);
 
     let sut = MfaFeatureServiceImpl {
         auth,
@@ -138,7 +324,9 @@ async fn not_initialized() {
     };
 
     // Act
-    let result = sut.disable(&"token".into(), UserIdOrSelf::Slf).await;
+    let result = sut
+        .disable(&"token".into(), UserIdOrSelf::Slf, ClientInfo::default())
+        .await;
 
     // Assert
     assert_matches!(result, Err(MfaDisableError::NotEnabled));
@@ -151,10 +339,11 @@ async fn not_enabled() {
 
     let db = MockDatabase::build(false);
 
-    let user_repo = MockUserRepository::new().with_exists(FOO.user.id, true);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
 
     let mfa_repo = MockMfaRepository::new()
-        .with_list_totp_devices_by_user(FOO.user.id, vec![FOO_TOTP_1.clone()]);
+        .with_list_totp_devices_by_user(FOO.user.id, vec![FOO_TOTP_1.clone()])
+        .with_list_webauthn_credentials_by_user(FOO.user.id, vec![]);
 
     let sut = MfaFeatureServiceImpl {
         auth,
@@ -165,7 +354,9 @@ async fn not_enabled() {
     };
 
     // Act
-    let result = sut.disable(&"token".into(), UserIdOrSelf::Slf).await;
+    let result = sut
+        .disable(&"token".into(), UserIdOrSelf::Slf, ClientInfo::default())
+        .await;
 
     // Assert
     assert_matches!(result, Err(MfaDisableError::NotEnabled));
@@ -12,6 +12,7 @@ use academy_demo::{
 use academy_models::{
     auth::{AuthError, AuthenticateError, AuthorizeError},
     mfa::{MfaRecoveryCode, TotpCode},
+    session::Session,
     user::UserIdOrSelf,
 };
 use academy_persistence_contracts::{
@@ -31,7 +32,7 @@ async fn ok() {
 
     let db = MockDatabase::build(true);
 
-    let user_repo = MockUserRepository::new().with_exists(FOO.user.id, true);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
 
     let mfa_repo = MockMfaRepository::new()
         .with_list_totp_devices_by_user(FOO.user.id, vec![FOO_TOTP_1.clone()]);
@@ -58,7 +59,46 @@ async fn ok() {
     let result = sut.enable(&"token".into(), UserIdOrSelf::Slf, code).await;
 
     // Assert
-    assert_eq!(result.unwrap(), expected);
+    assert_eq!(result.unwrap(), Some(expected));
+}
+
+#[tokio::test]
+async fn ok_additional_factor() {
+    // Arrange
+    let code = TotpCode::try_new("123456").unwrap();
+
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let user_repo = MockUserRepository::new().with_get_composite(
+        FOO.user.id,
+        Some(FOO.clone().with(|x| x.details.mfa_enabled = true)),
+    );
+
+    let mfa_repo = MockMfaRepository::new()
+        .with_list_totp_devices_by_user(FOO.user.id, vec![FOO_TOTP_1.clone()]);
+
+    let mfa_totp_device = MockMfaTotpDeviceService::new().with_confirm(
+        FOO_TOTP_1.clone(),
+        code.clone(),
+        Ok(FOO_TOTP_1.clone().with(|x| x.enabled = true)),
+    );
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        mfa_repo,
+        mfa_totp_device,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.enable(&"token".into(), UserIdOrSelf::Slf, code).await;
+
+    // Assert
+    assert_eq!(result.unwrap(), None);
 }
 
 #[tokio::test]
@@ -104,7 +144,37 @@ async fn unauthorized() {
     assert_matches!(
         result,
         Err(MfaEnableError::Auth(AuthError::Authorize(
-            AuthorizeError::Admin
+            AuthorizeError::Permission
+        )))
+    );
+}
+
+#[tokio::test]
+async fn impersonated() {
+    // Arrange
+    let code = TotpCode::try_new("123456").unwrap();
+
+    let auth = MockAuthService::new().with_authenticate(Some((
+        FOO.user.clone(),
+        Session {
+            impersonator_id: Some(ADMIN.user.id),
+            ..FOO_1.clone()
+        },
+    )));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.enable(&"token".into(), FOO.user.id.into(), code).await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaEnableError::Auth(AuthError::Authorize(
+            AuthorizeError::Impersonation
         )))
     );
 }
@@ -119,7 +189,7 @@ async fn user_not_found() {
 
     let db = MockDatabase::build(false);
 
-    let user_repo = MockUserRepository::new().with_exists(FOO.user.id, false);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, None);
 
     let sut = MfaFeatureServiceImpl {
         auth,
@@ -144,7 +214,7 @@ async fn already_enabled() {
 
     let db = MockDatabase::build(false);
 
-    let user_repo = MockUserRepository::new().with_exists(FOO.user.id, true);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
 
     let mfa_repo = MockMfaRepository::new().with_list_totp_devices_by_user(
         FOO.user.id,
@@ -175,7 +245,7 @@ async fn not_initialized() {
 
     let db = MockDatabase::build(false);
 
-    let user_repo = MockUserRepository::new().with_exists(FOO.user.id, true);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
 
     let mfa_repo = MockMfaRepository::new().with_list_totp_devices_by_user(FOO.user.id, vec![]);
 
@@ -203,7 +273,7 @@ async fn invalid_code() {
 
     let db = MockDatabase::build(false);
 
-    let user_repo = MockUserRepository::new().with_exists(FOO.user.id, true);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
 
     let mfa_repo = MockMfaRepository::new()
         .with_list_totp_devices_by_user(FOO.user.id, vec![FOO_TOTP_1.clone()]);
@@ -0,0 +1,304 @@
+use academy_auth_contracts::MockAuthService;
+use academy_core_mfa_contracts::{
+    recovery::MockMfaRecoveryService,
+    webauthn::{MfaWebauthnRegisterError, MockMfaWebauthnService},
+    MfaFeatureService, MfaFinishWebauthnRegistrationError,
+};
+use academy_demo::{
+    mfa::ADMIN2_WEBAUTHN_1,
+    session::{ADMIN_1, FOO_1},
+    user::{ADMIN, ADMIN2, FOO},
+};
+use academy_models::{
+    auth::{AuthError, AuthenticateError, AuthorizeError},
+    mfa::{
+        MfaRecoveryCode, WebauthnCredential, WebauthnRegistration, WebauthnRegistrationResponse,
+    },
+    session::Session,
+    user::UserIdOrSelf,
+};
+use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
+use academy_utils::assert_matches;
+
+use crate::{tests::Sut, MfaFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok_first_factor() {
+    // Arrange
+    let credential = WebauthnCredential {
+        user_id: FOO.user.id,
+        ..ADMIN2_WEBAUTHN_1.clone()
+    };
+    let recovery_code = MfaRecoveryCode::try_new("PJVURV-QRK3YJ-O3U7T6-D50KAC").unwrap();
+    let expected = WebauthnRegistration {
+        credential: credential.clone(),
+        recovery_code: Some(recovery_code),
+    };
+
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let mfa_webauthn = MockMfaWebauthnService::new().with_finish_registration(
+        FOO.user.id,
+        credential.name.clone(),
+        response(),
+        Ok(credential.clone()),
+    );
+
+    let mfa_recovery = MockMfaRecoveryService::new()
+        .with_setup(FOO.user.id, expected.recovery_code.clone().unwrap());
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        mfa_webauthn,
+        mfa_recovery,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .finish_webauthn_registration(
+            &"token".into(),
+            UserIdOrSelf::Slf,
+            credential.name.clone(),
+            response(),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn ok_mfa_already_enabled() {
+    // Arrange
+    let expected = WebauthnRegistration {
+        credential: ADMIN2_WEBAUTHN_1.clone(),
+        recovery_code: None,
+    };
+
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let user_repo =
+        MockUserRepository::new().with_get_composite(ADMIN2.user.id, Some(ADMIN2.clone()));
+
+    let mfa_webauthn = MockMfaWebauthnService::new().with_finish_registration(
+        ADMIN2.user.id,
+        ADMIN2_WEBAUTHN_1.name.clone(),
+        response(),
+        Ok(ADMIN2_WEBAUTHN_1.clone()),
+    );
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        mfa_webauthn,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .finish_webauthn_registration(
+            &"token".into(),
+            ADMIN2.user.id.into(),
+            ADMIN2_WEBAUTHN_1.name.clone(),
+            response(),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn unauthenticated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(None);
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .finish_webauthn_registration(
+            &"token".into(),
+            FOO.user.id.into(),
+            ADMIN2_WEBAUTHN_1.name.clone(),
+            response(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaFinishWebauthnRegistrationError::Auth(
+            AuthError::Authenticate(AuthenticateError::InvalidToken)
+        ))
+    );
+}
+
+#[tokio::test]
+async fn impersonated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((
+        FOO.user.clone(),
+        Session {
+            impersonator_id: Some(ADMIN.user.id),
+            ..FOO_1.clone()
+        },
+    )));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .finish_webauthn_registration(
+            &"token".into(),
+            FOO.user.id.into(),
+            ADMIN2_WEBAUTHN_1.name.clone(),
+            response(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaFinishWebauthnRegistrationError::Auth(
+            AuthError::Authorize(AuthorizeError::Impersonation)
+        ))
+    );
+}
+
+#[tokio::test]
+async fn user_not_found() {
+    // Arrange
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, None);
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .finish_webauthn_registration(
+            &"token".into(),
+            FOO.user.id.into(),
+            ADMIN2_WEBAUTHN_1.name.clone(),
+            response(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(MfaFinishWebauthnRegistrationError::NotFound));
+}
+
+#[tokio::test]
+async fn invalid_response() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let mfa_webauthn = MockMfaWebauthnService::new().with_finish_registration(
+        FOO.user.id,
+        ADMIN2_WEBAUTHN_1.name.clone(),
+        response(),
+        Err(MfaWebauthnRegisterError::InvalidResponse),
+    );
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        mfa_webauthn,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .finish_webauthn_registration(
+            &"token".into(),
+            UserIdOrSelf::Slf,
+            ADMIN2_WEBAUTHN_1.name.clone(),
+            response(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaFinishWebauthnRegistrationError::InvalidResponse)
+    );
+}
+
+#[tokio::test]
+async fn already_registered() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let mfa_webauthn = MockMfaWebauthnService::new().with_finish_registration(
+        FOO.user.id,
+        ADMIN2_WEBAUTHN_1.name.clone(),
+        response(),
+        Err(MfaWebauthnRegisterError::AlreadyRegistered),
+    );
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        mfa_webauthn,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .finish_webauthn_registration(
+            &"token".into(),
+            UserIdOrSelf::Slf,
+            ADMIN2_WEBAUTHN_1.name.clone(),
+            response(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaFinishWebauthnRegistrationError::AlreadyRegistered)
+    );
+}
+
+fn response() -> WebauthnRegistrationResponse {
+    WebauthnRegistrationResponse {
+        client_data_json: b"client data".to_vec(),
+        attestation_object: b"attestation object".to_vec(),
+    }
+}
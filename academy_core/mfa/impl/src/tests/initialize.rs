@@ -10,6 +10,7 @@ use academy_demo::{
 use academy_models::{
     auth::{AuthError, AuthenticateError, AuthorizeError},
     mfa::TotpSetup,
+    session::Session,
     user::UserIdOrSelf,
 };
 use academy_persistence_contracts::{
@@ -34,8 +35,11 @@ async fn new() {
 
     let mfa_repo = MockMfaRepository::new().with_list_totp_devices_by_user(FOO.user.id, vec![]);
 
-    let mfa_totp_device =
-        MockMfaTotpDeviceService::new().with_create(FOO.user.id, expected.clone());
+    let mfa_totp_device = MockMfaTotpDeviceService::new().with_create(
+        FOO.user.id,
+        "Authenticator".try_into().unwrap(),
+        (FOO_TOTP_1.clone(), expected.clone()),
+    );
 
     let sut = MfaFeatureServiceImpl {
         auth,
@@ -127,7 +131,35 @@ async fn unauthorized() {
     assert_matches!(
         result,
         Err(MfaInitializeError::Auth(AuthError::Authorize(
-            AuthorizeError::Admin
+            AuthorizeError::Permission
+        )))
+    );
+}
+
+#[tokio::test]
+async fn impersonated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((
+        FOO.user.clone(),
+        Session {
+            impersonator_id: Some(ADMIN.user.id),
+            ..FOO_1.clone()
+        },
+    )));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.initialize(&"token".into(), FOO.user.id.into()).await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaInitializeError::Auth(AuthError::Authorize(
+            AuthorizeError::Impersonation
         )))
     );
 }
@@ -0,0 +1,89 @@
+use academy_auth_contracts::MockAuthService;
+use academy_core_mfa_contracts::{MfaFeatureService, MfaListWebauthnCredentialsError};
+use academy_demo::{
+    mfa::ADMIN2_WEBAUTHN_1,
+    session::{ADMIN_1, BAR_1},
+    user::{ADMIN, ADMIN2, BAR, FOO},
+};
+use academy_models::auth::{AuthError, AuthenticateError, AuthorizeError};
+use academy_persistence_contracts::{mfa::MockMfaRepository, MockDatabase};
+use academy_utils::assert_matches;
+
+use crate::{tests::Sut, MfaFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok() {
+    // Arrange
+    let expected = vec![ADMIN2_WEBAUTHN_1.clone()];
+
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let mfa_repo = MockMfaRepository::new()
+        .with_list_webauthn_credentials_by_user(ADMIN2.user.id, expected.clone());
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        mfa_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .list_webauthn_credentials(&"token".into(), ADMIN2.user.id.into())
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn unauthenticated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(None);
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .list_webauthn_credentials(&"token".into(), FOO.user.id.into())
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaListWebauthnCredentialsError::Auth(
+            AuthError::Authenticate(AuthenticateError::InvalidToken)
+        ))
+    );
+}
+
+#[tokio::test]
+async fn unauthorized() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((BAR.user.clone(), BAR_1.clone())));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .list_webauthn_credentials(&"token".into(), FOO.user.id.into())
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaListWebauthnCredentialsError::Auth(AuthError::Authorize(
+            AuthorizeError::Permission
+        )))
+    );
+}
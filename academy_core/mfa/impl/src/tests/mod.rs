@@ -1,17 +1,28 @@
 use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::log::MockAuditLogService;
 use academy_core_mfa_contracts::{
     disable::MockMfaDisableService, recovery::MockMfaRecoveryService,
-    totp_device::MockMfaTotpDeviceService,
+    totp_device::MockMfaTotpDeviceService, webauthn::MockMfaWebauthnService,
 };
+use academy_email_contracts::template::MockTemplateEmailService;
 use academy_persistence_contracts::{
     mfa::MockMfaRepository, user::MockUserRepository, MockDatabase, MockTransaction,
 };
 
 use crate::MfaFeatureServiceImpl;
 
+mod confirm_totp_device;
+mod create_totp_device;
+mod delete_totp_device;
+mod delete_webauthn_credential;
 mod disable;
 mod enable;
+mod finish_webauthn_registration;
 mod initialize;
+mod list_totp_devices;
+mod list_webauthn_credentials;
+mod start_webauthn_registration;
+mod update_totp_device;
 
 type Sut = MfaFeatureServiceImpl<
     MockDatabase,
@@ -21,4 +32,7 @@ type Sut = MfaFeatureServiceImpl<
     MockMfaRecoveryService<MockTransaction>,
     MockMfaDisableService<MockTransaction>,
     MockMfaTotpDeviceService<MockTransaction>,
+    MockMfaWebauthnService<MockTransaction>,
+    MockTemplateEmailService,
+    MockAuditLogService<MockTransaction>,
 >;
@@ -0,0 +1,164 @@
+use std::time::Duration;
+
+use academy_auth_contracts::MockAuthService;
+use academy_core_mfa_contracts::{
+    webauthn::MockMfaWebauthnService, MfaFeatureService, MfaStartWebauthnRegistrationError,
+};
+use academy_demo::{
+    session::{ADMIN_1, BAR_1, FOO_1},
+    user::{ADMIN, BAR, FOO},
+};
+use academy_models::{
+    auth::{AuthError, AuthenticateError, AuthorizeError},
+    mfa::WebauthnRegistrationOptions,
+    session::Session,
+    user::UserIdOrSelf,
+};
+use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
+use academy_utils::assert_matches;
+
+use crate::{tests::Sut, MfaFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok() {
+    // Arrange
+    let expected = WebauthnRegistrationOptions {
+        challenge: "the challenge".into(),
+        rp_id: "bootstrap.academy".into(),
+        rp_name: "Bootstrap Academy".into(),
+        user_id: FOO.user.id,
+        user_name: FOO.user.name.clone(),
+        user_display_name: FOO.profile.display_name.clone(),
+        exclude_credentials: vec![],
+        timeout: Duration::from_secs(300),
+    };
+
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let mfa_webauthn =
+        MockMfaWebauthnService::new().with_start_registration(FOO.clone(), expected.clone());
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        mfa_webauthn,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .start_webauthn_registration(&"token".into(), UserIdOrSelf::Slf)
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn unauthenticated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(None);
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .start_webauthn_registration(&"token".into(), FOO.user.id.into())
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaStartWebauthnRegistrationError::Auth(
+            AuthError::Authenticate(AuthenticateError::InvalidToken)
+        ))
+    );
+}
+
+#[tokio::test]
+async fn unauthorized() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((BAR.user.clone(), BAR_1.clone())));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .start_webauthn_registration(&"token".into(), FOO.user.id.into())
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaStartWebauthnRegistrationError::Auth(
+            AuthError::Authorize(AuthorizeError::Permission)
+        ))
+    );
+}
+
+#[tokio::test]
+async fn impersonated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((
+        FOO.user.clone(),
+        Session {
+            impersonator_id: Some(ADMIN.user.id),
+            ..FOO_1.clone()
+        },
+    )));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .start_webauthn_registration(&"token".into(), FOO.user.id.into())
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaStartWebauthnRegistrationError::Auth(
+            AuthError::Authorize(AuthorizeError::Impersonation)
+        ))
+    );
+}
+
+#[tokio::test]
+async fn user_not_found() {
+    // Arrange
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, None);
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .start_webauthn_registration(&"token".into(), FOO.user.id.into())
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(MfaStartWebauthnRegistrationError::NotFound));
+}
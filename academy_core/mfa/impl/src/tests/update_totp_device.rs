@@ -0,0 +1,146 @@
+use academy_auth_contracts::MockAuthService;
+use academy_core_mfa_contracts::{MfaFeatureService, MfaUpdateTotpDeviceError};
+use academy_demo::{
+    mfa::{ADMIN2_TOTP_1, FOO_TOTP_1},
+    session::{BAR_1, FOO_1},
+    user::{ADMIN, BAR, FOO},
+};
+use academy_models::{
+    auth::{AuthError, AuthorizeError},
+    mfa::{TotpDeviceName, TotpDevicePatch},
+    session::Session,
+    user::UserIdOrSelf,
+};
+use academy_persistence_contracts::{mfa::MockMfaRepository, MockDatabase};
+use academy_utils::{assert_matches, Apply};
+
+use crate::{tests::Sut, MfaFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok() {
+    // Arrange
+    let name = TotpDeviceName::try_new("Tablet").unwrap();
+    let expected = FOO_TOTP_1.clone().with(|x| x.name = name.clone());
+
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let mfa_repo = MockMfaRepository::new()
+        .with_get_totp_device(FOO_TOTP_1.id, Some(FOO_TOTP_1.clone()))
+        .with_update_totp_device(
+            FOO_TOTP_1.id,
+            TotpDevicePatch::new().update_name(name.clone()),
+            true,
+        );
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        mfa_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .update_totp_device(&"token".into(), UserIdOrSelf::Slf, FOO_TOTP_1.id, name)
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn unauthorized() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((BAR.user.clone(), BAR_1.clone())));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .update_totp_device(
+            &"token".into(),
+            FOO.user.id.into(),
+            FOO_TOTP_1.id,
+            TotpDeviceName::try_new("Tablet").unwrap(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaUpdateTotpDeviceError::Auth(AuthError::Authorize(
+            AuthorizeError::Permission
+        )))
+    );
+}
+
+#[tokio::test]
+async fn impersonated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((
+        FOO.user.clone(),
+        Session {
+            impersonator_id: Some(ADMIN.user.id),
+            ..FOO_1.clone()
+        },
+    )));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .update_totp_device(
+            &"token".into(),
+            FOO.user.id.into(),
+            FOO_TOTP_1.id,
+            TotpDeviceName::try_new("Tablet").unwrap(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(MfaUpdateTotpDeviceError::Auth(AuthError::Authorize(
+            AuthorizeError::Impersonation
+        )))
+    );
+}
+
+#[tokio::test]
+async fn not_found() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let mfa_repo = MockMfaRepository::new()
+        .with_get_totp_device(ADMIN2_TOTP_1.id, Some(ADMIN2_TOTP_1.clone()));
+
+    let sut = MfaFeatureServiceImpl {
+        auth,
+        db,
+        mfa_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .update_totp_device(
+            &"token".into(),
+            UserIdOrSelf::Slf,
+            ADMIN2_TOTP_1.id,
+            TotpDeviceName::try_new("Tablet").unwrap(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(MfaUpdateTotpDeviceError::NotFound));
+}
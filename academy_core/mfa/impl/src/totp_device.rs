@@ -1,7 +1,10 @@
 use academy_core_mfa_contracts::totp_device::{MfaTotpDeviceConfirmError, MfaTotpDeviceService};
 use academy_di::Build;
 use academy_models::{
-    mfa::{TotpCode, TotpDevice, TotpDeviceId, TotpDevicePatch, TotpDevicePatchRef, TotpSetup},
+    mfa::{
+        TotpCode, TotpDevice, TotpDeviceId, TotpDeviceName, TotpDevicePatch, TotpDevicePatchRef,
+        TotpSetup,
+    },
     user::UserId,
 };
 use academy_persistence_contracts::mfa::MfaRepository;
@@ -32,14 +35,21 @@ where
     MfaRepo: MfaRepository<Txn>,
 {
     #[trace_instrument(skip(self, txn))]
-    async fn create(&self, txn: &mut Txn, user_id: UserId) -> anyhow::Result<TotpSetup> {
+    async fn create(
+        &self,
+        txn: &mut Txn,
+        user_id: UserId,
+        name: TotpDeviceName,
+    ) -> anyhow::Result<(TotpDevice, TotpSetup)> {
         let (secret, setup) = self.totp.generate_secret();
 
         let totp_device = TotpDevice {
             id: self.id.generate(),
             user_id,
+            name,
             enabled: false,
             created_at: self.time.now(),
+            last_used_at: None,
         };
 
         self.mfa_repo
@@ -47,7 +57,7 @@ where
             .await
             .context("Failed to save totp device in database")?;
 
-        Ok(setup)
+        Ok((totp_device, setup))
     }
 
     #[trace_instrument(skip(self, txn))]
@@ -155,10 +165,12 @@ mod tests {
         };
 
         // Act
-        let result = sut.create(&mut (), FOO.user.id).await;
+        let result = sut
+            .create(&mut (), FOO.user.id, FOO_TOTP_1.name.clone())
+            .await;
 
         // Assert
-        assert_eq!(result.unwrap(), setup);
+        assert_eq!(result.unwrap(), (FOO_TOTP_1.clone(), setup));
     }
 
     #[tokio::test]
@@ -0,0 +1,629 @@
+use academy_cache_contracts::CacheService;
+use academy_core_mfa_contracts::webauthn::{
+    MfaWebauthnAuthenticateError, MfaWebauthnRegisterError, MfaWebauthnService,
+};
+use academy_di::Build;
+use academy_models::{
+    mfa::{
+        WebauthnAssertion, WebauthnAuthenticationOptions, WebauthnChallenge, WebauthnCredential,
+        WebauthnCredentialName, WebauthnCredentialPatchRef, WebauthnRegistrationOptions,
+        WebauthnRegistrationResponse,
+    },
+    user::{UserComposite, UserId},
+};
+use academy_persistence_contracts::mfa::MfaRepository;
+use academy_shared_contracts::{id::IdService, time::TimeService, webauthn::WebauthnService};
+use academy_utils::trace_instrument;
+use anyhow::Context;
+use tracing::trace;
+
+#[derive(Debug, Clone, Build, Default)]
+pub struct MfaWebauthnServiceImpl<Id, Time, Webauthn, Cache, MfaRepo> {
+    id: Id,
+    time: Time,
+    webauthn: Webauthn,
+    cache: Cache,
+    mfa_repo: MfaRepo,
+}
+
+impl<Txn, Id, Time, Webauthn, Cache, MfaRepo> MfaWebauthnService<Txn>
+    for MfaWebauthnServiceImpl<Id, Time, Webauthn, Cache, MfaRepo>
+where
+    Txn: Send + Sync + 'static,
+    Id: IdService,
+    Time: TimeService,
+    Webauthn: WebauthnService,
+    Cache: CacheService,
+    MfaRepo: MfaRepository<Txn>,
+{
+    #[trace_instrument(skip(self, txn))]
+    async fn start_registration(
+        &self,
+        txn: &mut Txn,
+        user_composite: &UserComposite,
+    ) -> anyhow::Result<WebauthnRegistrationOptions> {
+        let user_id = user_composite.user.id;
+
+        trace!("list existing credentials");
+        let credentials = self
+            .mfa_repo
+            .list_webauthn_credentials_by_user(txn, user_id)
+            .await
+            .context("Failed to get webauthn credentials from database")?;
+
+        let options = self.webauthn.start_registration(
+            user_id,
+            user_composite.user.name.clone(),
+            user_composite.profile.display_name.clone(),
+            credentials.into_iter().map(|x| x.raw_id).collect(),
+        );
+
+        self.cache
+            .set(
+                &registration_cache_key(user_id),
+                &options.challenge,
+                Some(options.timeout),
+            )
+            .await
+            .context("Failed to save webauthn registration challenge in cache")?;
+
+        Ok(options)
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn finish_registration(
+        &self,
+        txn: &mut Txn,
+        user_id: UserId,
+        name: WebauthnCredentialName,
+        response: WebauthnRegistrationResponse,
+    ) -> Result<WebauthnCredential, MfaWebauthnRegisterError> {
+        let cache_key = registration_cache_key(user_id);
+
+        // each challenge can only be used once
+        trace!("take challenge");
+        let challenge = self
+            .cache
+            .take::<WebauthnChallenge>(&cache_key)
+            .await
+            .context("Failed to take webauthn registration challenge from cache")?
+            .ok_or(MfaWebauthnRegisterError::InvalidResponse)?;
+
+        trace!("verify response");
+        let (raw_id, key) = self
+            .webauthn
+            .finish_registration(&challenge, &response)
+            .map_err(|err| {
+                trace!(%err, "invalid registration response");
+                MfaWebauthnRegisterError::InvalidResponse
+            })?;
+
+        if self
+            .mfa_repo
+            .get_webauthn_credential_by_raw_id(txn, &raw_id)
+            .await
+            .context("Failed to get webauthn credential from database")?
+            .is_some()
+        {
+            return Err(MfaWebauthnRegisterError::AlreadyRegistered);
+        }
+
+        let credential = WebauthnCredential {
+            id: self.id.generate(),
+            user_id,
+            raw_id,
+            name,
+            created_at: self.time.now(),
+            last_used_at: None,
+        };
+
+        trace!("save credential");
+        self.mfa_repo
+            .create_webauthn_credential(txn, &credential, &key)
+            .await
+            .context("Failed to save webauthn credential in database")?;
+
+        Ok(credential)
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn start_authentication(&self) -> anyhow::Result<WebauthnAuthenticationOptions> {
+        let options = self.webauthn.start_authentication();
+
+        self.cache
+            .set(
+                &authentication_cache_key(&options.challenge),
+                &(),
+                Some(options.timeout),
+            )
+            .await
+            .context("Failed to save webauthn authentication challenge in cache")?;
+
+        Ok(options)
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn authenticate(
+        &self,
+        txn: &mut Txn,
+        user_id: UserId,
+        assertion: WebauthnAssertion,
+    ) -> Result<(), MfaWebauthnAuthenticateError> {
+        let challenge = self
+            .webauthn
+            .assertion_challenge(&assertion)
+            .ok_or(MfaWebauthnAuthenticateError::Failed)?;
+        let cache_key = authentication_cache_key(&challenge);
+
+        // each challenge can only be used once
+        trace!("take challenge");
+        if self
+            .cache
+            .take::<()>(&cache_key)
+            .await
+            .context("Failed to take webauthn authentication challenge from cache")?
+            .is_none()
+        {
+            return Err(MfaWebauthnAuthenticateError::Failed);
+        }
+
+        trace!("get credential");
+        let (credential, key) = self
+            .mfa_repo
+            .get_webauthn_credential_by_raw_id(txn, &assertion.raw_id)
+            .await
+            .context("Failed to get webauthn credential from database")?
+            .filter(|(credential, _)| credential.user_id == user_id)
+            .ok_or(MfaWebauthnAuthenticateError::Failed)?;
+
+        trace!("verify assertion");
+        let sign_count = self
+            .webauthn
+            .finish_authentication(&challenge, &assertion, &key)
+            .map_err(|err| {
+                trace!(%err, "invalid assertion");
+                MfaWebauthnAuthenticateError::Failed
+            })?;
+
+        trace!("update credential");
+        self.mfa_repo
+            .save_webauthn_credential_sign_count(txn, credential.id, sign_count)
+            .await
+            .context("Failed to update webauthn credential sign count in database")?;
+        self.mfa_repo
+            .update_webauthn_credential(
+                txn,
+                credential.id,
+                WebauthnCredentialPatchRef::new().update_last_used_at(&Some(self.time.now())),
+            )
+            .await
+            .context("Failed to update webauthn credential in database")?;
+
+        Ok(())
+    }
+}
+
+fn registration_cache_key(user_id: UserId) -> String {
+    format!("webauthn_registration:{}", user_id.hyphenated())
+}
+
+fn authentication_cache_key(challenge: &WebauthnChallenge) -> String {
+    format!("webauthn_authentication:{}", **challenge)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use academy_cache_contracts::MockCacheService;
+    use academy_demo::{
+        mfa::{ADMIN2_WEBAUTHN_1, WEBAUTHN_CREDENTIAL_KEYS},
+        user::{ADMIN2, FOO},
+    };
+    use academy_models::mfa::WebauthnCredentialPatch;
+    use academy_persistence_contracts::mfa::MockMfaRepository;
+    use academy_shared_contracts::{
+        id::MockIdService,
+        time::MockTimeService,
+        webauthn::{MockWebauthnService, WebauthnVerifyError},
+    };
+    use academy_utils::{assert_matches, Apply};
+
+    use super::*;
+
+    type Sut = MfaWebauthnServiceImpl<
+        MockIdService,
+        MockTimeService,
+        MockWebauthnService,
+        MockCacheService,
+        MockMfaRepository<()>,
+    >;
+
+    #[tokio::test]
+    async fn start_registration() {
+        // Arrange
+        let expected = registration_options();
+
+        let mfa_repo = MockMfaRepository::new().with_list_webauthn_credentials_by_user(
+            ADMIN2.user.id,
+            vec![ADMIN2_WEBAUTHN_1.clone()],
+        );
+
+        let webauthn = MockWebauthnService::new().with_start_registration(
+            ADMIN2.user.id,
+            ADMIN2.user.name.clone(),
+            ADMIN2.profile.display_name.clone(),
+            vec![ADMIN2_WEBAUTHN_1.raw_id.clone()],
+            expected.clone(),
+        );
+
+        let cache = MockCacheService::new().with_set(
+            registration_cache_key(ADMIN2.user.id),
+            expected.challenge.clone(),
+            Some(expected.timeout),
+        );
+
+        let sut = MfaWebauthnServiceImpl {
+            webauthn,
+            cache,
+            mfa_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.start_registration(&mut (), &ADMIN2).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn finish_registration_ok() {
+        // Arrange
+        let expected = ADMIN2_WEBAUTHN_1.clone().with(|x| x.last_used_at = None);
+        let key = WEBAUTHN_CREDENTIAL_KEYS[&ADMIN2_WEBAUTHN_1.id].clone();
+        let challenge = challenge();
+
+        let id = MockIdService::new().with_generate(expected.id);
+        let time = MockTimeService::new().with_now(expected.created_at);
+
+        let cache = MockCacheService::new().with_take(
+            registration_cache_key(ADMIN2.user.id),
+            Some(challenge.clone()),
+        );
+
+        let webauthn = MockWebauthnService::new().with_finish_registration(
+            challenge,
+            registration_response(),
+            Ok((expected.raw_id.clone(), key.clone())),
+        );
+
+        let mfa_repo = MockMfaRepository::new()
+            .with_get_webauthn_credential_by_raw_id(expected.raw_id.clone(), None)
+            .with_create_webauthn_credential(expected.clone(), key);
+
+        let sut = MfaWebauthnServiceImpl {
+            id,
+            time,
+            webauthn,
+            cache,
+            mfa_repo,
+        };
+
+        // Act
+        let result = sut
+            .finish_registration(
+                &mut (),
+                ADMIN2.user.id,
+                expected.name.clone(),
+                registration_response(),
+            )
+            .await;
+
+        // Assert
+        assert_eq!(result.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn finish_registration_no_challenge() {
+        // Arrange
+        let cache = MockCacheService::new()
+            .with_take::<WebauthnChallenge>(registration_cache_key(FOO.user.id), None);
+
+        let sut = MfaWebauthnServiceImpl {
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut
+            .finish_registration(
+                &mut (),
+                FOO.user.id,
+                ADMIN2_WEBAUTHN_1.name.clone(),
+                registration_response(),
+            )
+            .await;
+
+        // Assert
+        assert_matches!(result, Err(MfaWebauthnRegisterError::InvalidResponse));
+    }
+
+    #[tokio::test]
+    async fn finish_registration_invalid_response() {
+        // Arrange
+        let challenge = challenge();
+
+        let cache = MockCacheService::new()
+            .with_take(registration_cache_key(FOO.user.id), Some(challenge.clone()));
+
+        let webauthn = MockWebauthnService::new().with_finish_registration(
+            challenge,
+            registration_response(),
+            Err(WebauthnVerifyError::InvalidSignature),
+        );
+
+        let sut = MfaWebauthnServiceImpl {
+            webauthn,
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut
+            .finish_registration(
+                &mut (),
+                FOO.user.id,
+                ADMIN2_WEBAUTHN_1.name.clone(),
+                registration_response(),
+            )
+            .await;
+
+        // Assert
+        assert_matches!(result, Err(MfaWebauthnRegisterError::InvalidResponse));
+    }
+
+    #[tokio::test]
+    async fn finish_registration_already_registered() {
+        // Arrange
+        let key = WEBAUTHN_CREDENTIAL_KEYS[&ADMIN2_WEBAUTHN_1.id].clone();
+        let challenge = challenge();
+
+        let cache = MockCacheService::new()
+            .with_take(registration_cache_key(FOO.user.id), Some(challenge.clone()));
+
+        let webauthn = MockWebauthnService::new().with_finish_registration(
+            challenge,
+            registration_response(),
+            Ok((ADMIN2_WEBAUTHN_1.raw_id.clone(), key.clone())),
+        );
+
+        let mfa_repo = MockMfaRepository::new().with_get_webauthn_credential_by_raw_id(
+            ADMIN2_WEBAUTHN_1.raw_id.clone(),
+            Some((ADMIN2_WEBAUTHN_1.clone(), key)),
+        );
+
+        let sut = MfaWebauthnServiceImpl {
+            webauthn,
+            cache,
+            mfa_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut
+            .finish_registration(
+                &mut (),
+                FOO.user.id,
+                ADMIN2_WEBAUTHN_1.name.clone(),
+                registration_response(),
+            )
+            .await;
+
+        // Assert
+        assert_matches!(result, Err(MfaWebauthnRegisterError::AlreadyRegistered));
+    }
+
+    #[tokio::test]
+    async fn start_authentication() {
+        // Arrange
+        let expected = WebauthnAuthenticationOptions {
+            challenge: challenge(),
+            rp_id: "bootstrap.academy".into(),
+            timeout: Duration::from_secs(300),
+        };
+
+        let webauthn = MockWebauthnService::new().with_start_authentication(expected.clone());
+
+        let cache = MockCacheService::new().with_set(
+            authentication_cache_key(&expected.challenge),
+            (),
+            Some(expected.timeout),
+        );
+
+        let sut = MfaWebauthnServiceImpl {
+            webauthn,
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.start_authentication().await;
+
+        // Assert
+        assert_eq!(result.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn authenticate_ok() {
+        // Arrange
+        let key = WEBAUTHN_CREDENTIAL_KEYS[&ADMIN2_WEBAUTHN_1.id].clone();
+        let challenge = challenge();
+        let now = ADMIN2_WEBAUTHN_1.last_used_at.unwrap() + Duration::from_secs(3600);
+
+        let time = MockTimeService::new().with_now(now);
+
+        let webauthn = MockWebauthnService::new()
+            .with_assertion_challenge(assertion(), Some(challenge.clone()))
+            .with_finish_authentication(
+                challenge.clone(),
+                assertion(),
+                key.clone(),
+                Ok(key.sign_count + 1),
+            );
+
+        let cache =
+            MockCacheService::new().with_take(authentication_cache_key(&challenge), Some(()));
+
+        let mfa_repo = MockMfaRepository::new()
+            .with_get_webauthn_credential_by_raw_id(
+                ADMIN2_WEBAUTHN_1.raw_id.clone(),
+                Some((ADMIN2_WEBAUTHN_1.clone(), key.clone())),
+            )
+            .with_save_webauthn_credential_sign_count(ADMIN2_WEBAUTHN_1.id, key.sign_count + 1)
+            .with_update_webauthn_credential(
+                ADMIN2_WEBAUTHN_1.id,
+                WebauthnCredentialPatch::new().update_last_used_at(Some(now)),
+                true,
+            );
+
+        let sut = MfaWebauthnServiceImpl {
+            time,
+            webauthn,
+            cache,
+            mfa_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.authenticate(&mut (), ADMIN2.user.id, assertion()).await;
+
+        // Assert
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn authenticate_unknown_challenge() {
+        // Arrange
+        let challenge = challenge();
+
+        let webauthn = MockWebauthnService::new()
+            .with_assertion_challenge(assertion(), Some(challenge.clone()));
+
+        let cache =
+            MockCacheService::new().with_take::<()>(authentication_cache_key(&challenge), None);
+
+        let sut = MfaWebauthnServiceImpl {
+            webauthn,
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.authenticate(&mut (), ADMIN2.user.id, assertion()).await;
+
+        // Assert
+        assert_matches!(result, Err(MfaWebauthnAuthenticateError::Failed));
+    }
+
+    #[tokio::test]
+    async fn authenticate_credential_of_other_user() {
+        // Arrange
+        let key = WEBAUTHN_CREDENTIAL_KEYS[&ADMIN2_WEBAUTHN_1.id].clone();
+        let challenge = challenge();
+
+        let webauthn = MockWebauthnService::new()
+            .with_assertion_challenge(assertion(), Some(challenge.clone()));
+
+        let cache =
+            MockCacheService::new().with_take(authentication_cache_key(&challenge), Some(()));
+
+        let mfa_repo = MockMfaRepository::new().with_get_webauthn_credential_by_raw_id(
+            ADMIN2_WEBAUTHN_1.raw_id.clone(),
+            Some((ADMIN2_WEBAUTHN_1.clone(), key)),
+        );
+
+        let sut = MfaWebauthnServiceImpl {
+            webauthn,
+            cache,
+            mfa_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.authenticate(&mut (), FOO.user.id, assertion()).await;
+
+        // Assert
+        assert_matches!(result, Err(MfaWebauthnAuthenticateError::Failed));
+    }
+
+    #[tokio::test]
+    async fn authenticate_invalid_signature() {
+        // Arrange
+        let key = WEBAUTHN_CREDENTIAL_KEYS[&ADMIN2_WEBAUTHN_1.id].clone();
+        let challenge = challenge();
+
+        let webauthn = MockWebauthnService::new()
+            .with_assertion_challenge(assertion(), Some(challenge.clone()))
+            .with_finish_authentication(
+                challenge.clone(),
+                assertion(),
+                key.clone(),
+                Err(WebauthnVerifyError::InvalidSignature),
+            );
+
+        let cache =
+            MockCacheService::new().with_take(authentication_cache_key(&challenge), Some(()));
+
+        let mfa_repo = MockMfaRepository::new().with_get_webauthn_credential_by_raw_id(
+            ADMIN2_WEBAUTHN_1.raw_id.clone(),
+            Some((ADMIN2_WEBAUTHN_1.clone(), key)),
+        );
+
+        let sut = MfaWebauthnServiceImpl {
+            webauthn,
+            cache,
+            mfa_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.authenticate(&mut (), ADMIN2.user.id, assertion()).await;
+
+        // Assert
+        assert_matches!(result, Err(MfaWebauthnAuthenticateError::Failed));
+    }
+
+    fn challenge() -> WebauthnChallenge {
+        "3q2-7w".into()
+    }
+
+    fn registration_options() -> WebauthnRegistrationOptions {
+        WebauthnRegistrationOptions {
+            challenge: challenge(),
+            rp_id: "bootstrap.academy".into(),
+            rp_name: "Bootstrap Academy".into(),
+            user_id: ADMIN2.user.id,
+            user_name: ADMIN2.user.name.clone(),
+            user_display_name: ADMIN2.profile.display_name.clone(),
+            exclude_credentials: vec![ADMIN2_WEBAUTHN_1.raw_id.clone()],
+            timeout: Duration::from_secs(300),
+        }
+    }
+
+    fn registration_response() -> WebauthnRegistrationResponse {
+        WebauthnRegistrationResponse {
+            client_data_json: b"client data".to_vec(),
+            attestation_object: b"attestation object".to_vec(),
+        }
+    }
+
+    fn assertion() -> WebauthnAssertion {
+        WebauthnAssertion {
+            raw_id: ADMIN2_WEBAUTHN_1.raw_id.clone(),
+            client_data_json: b"client data".to_vec(),
+            authenticator_data: b"authenticator data".to_vec(),
+            signature: b"signature".to_vec(),
+        }
+    }
+}
@@ -1,11 +1,14 @@
 use std::future::Future;
 
 use academy_models::{
+    audit::ClientInfo,
     auth::{AccessToken, AuthError, Login},
     oauth2::{
-        OAuth2Link, OAuth2LinkId, OAuth2Login, OAuth2ProviderSummary, OAuth2RegistrationToken,
+        OAuth2Link, OAuth2LinkId, OAuth2Login, OAuth2ProviderId, OAuth2ProviderSummary,
+        OAuth2RegistrationToken, OAuth2RemoteProfile,
     },
     session::DeviceName,
+    url::Url,
     user::UserIdOrSelf,
 };
 use thiserror::Error;
@@ -13,14 +16,24 @@ use thiserror::Error;
 pub mod link;
 pub mod login;
 pub mod registration;
+pub mod token;
 
 pub trait OAuth2FeatureService: Send + Sync + 'static {
     /// Return all available OAuth2 providers.
     fn list_providers(&self) -> Vec<OAuth2ProviderSummary>;
 
+    /// Start a new authorization request for the given OAuth2 provider and
+    /// return the authorize URL the user has to be redirected to.
+    fn authorize(
+        &self,
+        provider_id: OAuth2ProviderId,
+        redirect_uri: Url,
+    ) -> impl Future<Output = Result<Url, OAuth2AuthorizeError>> + Send;
+
     /// Return all OAuth2 links of the given user.
     ///
-    /// Requires admin privileges if not used on the authenticated user.
+    /// Requires the `user_read` permission if not used on the authenticated
+    /// user.
     fn list_links(
         &self,
         token: &AccessToken,
@@ -29,22 +42,26 @@ pub trait OAuth2FeatureService: Send + Sync + 'static {
 
     /// Create a new OAuth2 for the given user.
     ///
-    /// Requires admin privileges if not used on the authenticated user.
+    /// Requires the `user_write` permission if not used on the authenticated
+    /// user.
     fn create_link(
         &self,
         token: &AccessToken,
         user_id: UserIdOrSelf,
         login: OAuth2Login,
+        client: ClientInfo,
     ) -> impl Future<Output = Result<OAuth2Link, OAuth2CreateLinkError>> + Send;
 
     /// Delete the given OAuth2 link.
     ///
-    /// Requires admin privileges if not used on the authenticated user.
+    /// Requires the `user_write` permission if not used on the authenticated
+    /// user.
     fn delete_link(
         &self,
         token: &AccessToken,
         user_id: UserIdOrSelf,
         link_id: OAuth2LinkId,
+        client: ClientInfo,
     ) -> impl Future<Output = Result<(), OAuth2DeleteLinkError>> + Send;
 
     /// Create a session via OAuth2.
@@ -52,9 +69,18 @@ pub trait OAuth2FeatureService: Send + Sync + 'static {
         &self,
         login: OAuth2Login,
         device_name: Option<DeviceName>,
+        client: ClientInfo,
     ) -> impl Future<Output = Result<OAuth2CreateSessionResponse, OAuth2CreateSessionError>> + Send;
 }
 
+#[derive(Debug, Error)]
+pub enum OAuth2AuthorizeError {
+    #[error("The provider does not exist.")]
+    InvalidProvider,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
 #[derive(Debug, Error)]
 pub enum OAuth2ListLinksError {
     #[error("The user does not exist.")]
@@ -69,6 +95,8 @@ pub enum OAuth2ListLinksError {
 pub enum OAuth2CreateLinkError {
     #[error("The provider does not exist.")]
     InvalidProvider,
+    #[error("The authorization request does not exist or has expired.")]
+    InvalidState,
     #[error("The authorization code is invalid.")]
     InvalidCode,
     #[error("The remote user has already been linked.")]
@@ -98,16 +126,24 @@ pub enum OAuth2DeleteLinkError {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum OAuth2CreateSessionResponse {
     Login(Box<Login>),
-    RegistrationToken(OAuth2RegistrationToken),
+    /// The remote user is not linked to any local user yet. The token can be
+    /// used to register a new user account, which is then linked to the remote
+    /// user. The profile can be used to pre-fill the registration form.
+    Registration {
+        token: OAuth2RegistrationToken,
+        profile: OAuth2RemoteProfile,
+    },
 }
 
 #[derive(Debug, Error)]
 pub enum OAuth2CreateSessionError {
     #[error("The provider does not exist.")]
     InvalidProvider,
+    #[error("The authorization request does not exist or has expired.")]
+    InvalidState,
     #[error("The authorization code is invalid.")]
     InvalidCode,
-    #[error("The user account has been disabled.")]
+    #[error("The user account has been disabled or scheduled for deletion.")]
     UserDisabled,
     #[error(transparent)]
     Other(#[from] anyhow::Error),
@@ -1,20 +1,22 @@
 use std::future::Future;
 
 use academy_models::{
-    oauth2::{OAuth2Link, OAuth2ProviderId, OAuth2UserInfo},
+    oauth2::{OAuth2Link, OAuth2ProviderId, OAuth2Tokens, OAuth2UserInfo},
     user::UserId,
 };
 use thiserror::Error;
 
 #[cfg_attr(feature = "mock", mockall::automock)]
 pub trait OAuth2LinkService<Txn: Send + Sync + 'static>: Send + Sync + 'static {
-    /// Create a new OAuth2 link.
+    /// Create a new OAuth2 link and save the provider tokens of the remote
+    /// user.
     fn create(
         &self,
         txn: &mut Txn,
         user_id: UserId,
         provider_id: OAuth2ProviderId,
         remote_user: OAuth2UserInfo,
+        tokens: OAuth2Tokens,
     ) -> impl Future<Output = Result<OAuth2Link, OAuth2LinkServiceError>> + Send;
 }
 
@@ -33,6 +35,7 @@ impl<Txn: Send + Sync + 'static> MockOAuth2LinkService<Txn> {
         user_id: UserId,
         provider_id: OAuth2ProviderId,
         remote_user: OAuth2UserInfo,
+        tokens: OAuth2Tokens,
         result: Result<OAuth2Link, OAuth2LinkServiceError>,
     ) -> Self {
         self.expect_create()
@@ -42,8 +45,9 @@ impl<Txn: Send + Sync + 'static> MockOAuth2LinkService<Txn> {
                 mockall::predicate::eq(user_id),
                 mockall::predicate::eq(provider_id),
                 mockall::predicate::eq(remote_user),
+                mockall::predicate::eq(tokens),
             )
-            .return_once(|_, _, _, _| Box::pin(std::future::ready(result)));
+            .return_once(|_, _, _, _, _| Box::pin(std::future::ready(result)));
         self
     }
 }
@@ -1,22 +1,47 @@
 use std::future::Future;
 
-use academy_models::oauth2::{OAuth2Login, OAuth2UserInfo};
+use academy_models::{
+    oauth2::{OAuth2Login, OAuth2ProviderId, OAuth2RemoteLogin},
+    url::Url,
+};
 use thiserror::Error;
 
 #[cfg_attr(feature = "mock", mockall::automock)]
 pub trait OAuth2LoginService: Send + Sync + 'static {
+    /// Start a new authorization request for the given provider and return
+    /// the URL the user has to be redirected to.
+    ///
+    /// The `state`, PKCE code verifier and OpenID Connect `nonce` of the
+    /// request are kept in the cache until the authorization is completed by
+    /// [`OAuth2LoginService::login`].
+    fn authorize(
+        &self,
+        provider_id: OAuth2ProviderId,
+        redirect_uri: Url,
+    ) -> impl Future<Output = Result<Url, OAuth2AuthorizeServiceError>> + Send;
+
     /// Resolve the given [`OAuth2Login`] and return the external user's
-    /// [`OAuth2UserInfo`].
+    /// [`OAuth2RemoteLogin`].
     fn login(
         &self,
         login: OAuth2Login,
-    ) -> impl Future<Output = Result<OAuth2UserInfo, OAuth2LoginServiceError>> + Send;
+    ) -> impl Future<Output = Result<OAuth2RemoteLogin, OAuth2LoginServiceError>> + Send;
+}
+
+#[derive(Debug, Error)]
+pub enum OAuth2AuthorizeServiceError {
+    #[error("The provider does not exist.")]
+    InvalidProvider,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
 }
 
 #[derive(Debug, Error)]
 pub enum OAuth2LoginServiceError {
     #[error("The provider does not exist.")]
     InvalidProvider,
+    #[error("The authorization request does not exist or has expired.")]
+    InvalidState,
     #[error("The authorization code is invalid.")]
     InvalidCode,
     #[error(transparent)]
@@ -25,10 +50,26 @@ pub enum OAuth2LoginServiceError {
 
 #[cfg(feature = "mock")]
 impl MockOAuth2LoginService {
+    pub fn with_authorize(
+        mut self,
+        provider_id: OAuth2ProviderId,
+        redirect_uri: Url,
+        result: Result<Url, OAuth2AuthorizeServiceError>,
+    ) -> Self {
+        self.expect_authorize()
+            .once()
+            .with(
+                mockall::predicate::eq(provider_id),
+                mockall::predicate::eq(redirect_uri),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(result)));
+        self
+    }
+
     pub fn with_login(
         mut self,
         login: OAuth2Login,
-        result: Result<OAuth2UserInfo, OAuth2LoginServiceError>,
+        result: Result<OAuth2RemoteLogin, OAuth2LoginServiceError>,
     ) -> Self {
         self.expect_login()
             .once()
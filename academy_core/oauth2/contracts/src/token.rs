@@ -0,0 +1,97 @@
+use std::future::Future;
+
+use academy_models::oauth2::{
+    OAuth2AccessToken, OAuth2Link, OAuth2LinkId, OAuth2ProviderId, OAuth2Tokens,
+};
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait OAuth2TokenService<Txn: Send + Sync + 'static>: Send + Sync + 'static {
+    /// Encrypt and save the provider tokens of the given OAuth2 link,
+    /// replacing any previously saved tokens.
+    fn save(
+        &self,
+        txn: &mut Txn,
+        link_id: OAuth2LinkId,
+        tokens: &OAuth2Tokens,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Return the decrypted provider tokens of the given OAuth2 link.
+    fn get(
+        &self,
+        txn: &mut Txn,
+        link_id: OAuth2LinkId,
+    ) -> impl Future<Output = anyhow::Result<Option<OAuth2Tokens>>> + Send;
+
+    /// Return an access token which can be used to access the provider's API
+    /// on behalf of the remote user of the given OAuth2 link.
+    ///
+    /// Expired access tokens are refreshed automatically. Returns [`None`] if
+    /// no tokens have been saved for this link or if the access token has
+    /// expired and cannot be refreshed anymore.
+    fn get_access_token(
+        &self,
+        txn: &mut Txn,
+        link: &OAuth2Link,
+    ) -> impl Future<Output = anyhow::Result<Option<OAuth2AccessToken>>> + Send;
+
+    /// Revoke the given tokens at the OAuth2 provider.
+    fn revoke(
+        &self,
+        provider_id: &OAuth2ProviderId,
+        tokens: &OAuth2Tokens,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+}
+
+#[cfg(feature = "mock")]
+impl<Txn: Send + Sync + 'static> MockOAuth2TokenService<Txn> {
+    pub fn with_save(mut self, link_id: OAuth2LinkId, tokens: OAuth2Tokens) -> Self {
+        self.expect_save()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(link_id),
+                mockall::predicate::eq(tokens),
+            )
+            .return_once(|_, _, _| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
+
+    pub fn with_get(mut self, link_id: OAuth2LinkId, result: Option<OAuth2Tokens>) -> Self {
+        self.expect_get()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(link_id),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_get_access_token(
+        mut self,
+        link: OAuth2Link,
+        result: Option<OAuth2AccessToken>,
+    ) -> Self {
+        self.expect_get_access_token()
+            .once()
+            .with(mockall::predicate::always(), mockall::predicate::eq(link))
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_revoke(
+        mut self,
+        provider_id: OAuth2ProviderId,
+        tokens: OAuth2Tokens,
+        result: anyhow::Result<()>,
+    ) -> Self {
+        self.expect_revoke()
+            .once()
+            .with(
+                mockall::predicate::eq(provider_id),
+                mockall::predicate::eq(tokens),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(result)));
+        self
+    }
+}
@@ -1,34 +1,42 @@
 use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use academy_auth_contracts::{AuthResultExt, AuthService};
+use academy_core_audit_contracts::log::AuditLogService;
 use academy_core_oauth2_contracts::{
     link::{OAuth2LinkService, OAuth2LinkServiceError},
-    login::{OAuth2LoginService, OAuth2LoginServiceError},
+    login::{OAuth2AuthorizeServiceError, OAuth2LoginService, OAuth2LoginServiceError},
     registration::OAuth2RegistrationService,
-    OAuth2CreateLinkError, OAuth2CreateSessionError, OAuth2CreateSessionResponse,
-    OAuth2DeleteLinkError, OAuth2FeatureService, OAuth2ListLinksError,
+    token::OAuth2TokenService,
+    OAuth2AuthorizeError, OAuth2CreateLinkError, OAuth2CreateSessionError,
+    OAuth2CreateSessionResponse, OAuth2DeleteLinkError, OAuth2FeatureService, OAuth2ListLinksError,
 };
 use academy_core_session_contracts::session::SessionService;
 use academy_di::Build;
-use academy_extern_contracts::oauth2::OAuth2ApiService;
+use academy_email_contracts::template::TemplateEmailService;
 use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
     auth::AccessToken,
     oauth2::{
         OAuth2Link, OAuth2LinkId, OAuth2Login, OAuth2Provider, OAuth2ProviderId,
-        OAuth2ProviderSummary, OAuth2Registration,
+        OAuth2ProviderSummary, OAuth2Registration, OAuth2RemoteLogin,
     },
+    role::Permission,
     session::DeviceName,
+    url::Url,
     user::UserIdOrSelf,
 };
 use academy_persistence_contracts::{
     oauth2::OAuth2Repository, user::UserRepository, Database, Transaction,
 };
+use academy_templates_contracts::OAuth2LinkCreatedTemplate;
 use academy_utils::trace_instrument;
 use anyhow::Context;
+use tracing::{error, warn};
 
 pub mod link;
 pub mod login;
 pub mod registration;
+pub mod token;
 
 #[cfg(test)]
 mod tests;
@@ -38,23 +46,27 @@ mod tests;
 pub struct OAuth2FeatureServiceImpl<
     Db,
     Auth,
-    OAuth2Api,
     UserRepo,
     OAuth2Repo,
     OAuth2Link,
     OAuth2Login,
     OAuth2Registration,
+    OAuth2Token,
     Session,
+    TemplateEmail,
+    AuditLog,
 > {
     db: Db,
     auth: Auth,
-    oauth2_api: OAuth2Api,
     user_repo: UserRepo,
     oauth2_repo: OAuth2Repo,
     oauth2_create_link: OAuth2Link,
     oauth2_login: OAuth2Login,
     oauth2_registration: OAuth2Registration,
+    oauth2_token: OAuth2Token,
     session: Session,
+    template_email: TemplateEmail,
+    audit_log: AuditLog,
     config: OAuth2FeatureConfig,
 }
 
@@ -62,40 +74,47 @@ pub struct OAuth2FeatureServiceImpl<
 pub struct OAuth2FeatureConfig {
     pub providers: Arc<HashMap<OAuth2ProviderId, OAuth2Provider>>,
     pub registration_token_ttl: Duration,
+    pub authorization_ttl: Duration,
 }
 
 impl<
         Db,
         Auth,
-        OAuth2Api,
         UserRepo,
         OAuth2Repo,
         OAuth2LinkS,
         OAuth2LoginS,
         OAuth2RegistrationS,
+        OAuth2TokenS,
         Session,
+        TemplateEmail,
+        AuditLog,
     > OAuth2FeatureService
     for OAuth2FeatureServiceImpl<
         Db,
         Auth,
-        OAuth2Api,
         UserRepo,
         OAuth2Repo,
         OAuth2LinkS,
         OAuth2LoginS,
         OAuth2RegistrationS,
+        OAuth2TokenS,
         Session,
+        TemplateEmail,
+        AuditLog,
     >
 where
     Db: Database,
     Auth: AuthService<Db::Transaction>,
-    OAuth2Api: OAuth2ApiService,
     UserRepo: UserRepository<Db::Transaction>,
     OAuth2Repo: OAuth2Repository<Db::Transaction>,
     OAuth2LinkS: OAuth2LinkService<Db::Transaction>,
     OAuth2LoginS: OAuth2LoginService,
     OAuth2RegistrationS: OAuth2RegistrationService,
+    OAuth2TokenS: OAuth2TokenService<Db::Transaction>,
     Session: SessionService<Db::Transaction>,
+    TemplateEmail: TemplateEmailService,
+    AuditLog: AuditLogService<Db::Transaction>,
 {
     #[trace_instrument(skip(self))]
     fn list_providers(&self) -> Vec<OAuth2ProviderSummary> {
@@ -105,11 +124,29 @@ where
             .map(|(id, provider)| OAuth2ProviderSummary {
                 id: id.clone(),
                 name: provider.name.clone(),
-                auth_url: self.oauth2_api.generate_auth_url(provider),
             })
             .collect()
     }
 
+    #[trace_instrument(skip(self))]
+    async fn authorize(
+        &self,
+        provider_id: OAuth2ProviderId,
+        redirect_uri: Url,
+    ) -> Result<Url, OAuth2AuthorizeError> {
+        self.oauth2_login
+            .authorize(provider_id, redirect_uri)
+            .await
+            .map_err(|err| match err {
+                OAuth2AuthorizeServiceError::InvalidProvider => {
+                    OAuth2AuthorizeError::InvalidProvider
+                }
+                OAuth2AuthorizeServiceError::Other(err) => {
+                    err.context("Failed to start OAuth2 authorization").into()
+                }
+            })
+    }
+
     #[trace_instrument(skip(self))]
     async fn list_links(
         &self,
@@ -118,7 +155,8 @@ where
     ) -> Result<Vec<OAuth2Link>, OAuth2ListLinksError> {
         let auth = self.auth.authenticate(token).await.map_auth_err()?;
         let user_id = user_id.unwrap_or(auth.user_id);
-        auth.ensure_self_or_admin(user_id).map_auth_err()?;
+        auth.ensure_self_or_permission(user_id, Permission::UserRead)
+            .map_auth_err()?;
 
         let mut txn = self.db.begin_transaction().await?;
 
@@ -149,30 +187,33 @@ where
         token: &AccessToken,
         user_id: UserIdOrSelf,
         login: OAuth2Login,
+        client: ClientInfo,
     ) -> Result<OAuth2Link, OAuth2CreateLinkError> {
         let auth = self.auth.authenticate(token).await.map_auth_err()?;
         let user_id = user_id.unwrap_or(auth.user_id);
-        auth.ensure_self_or_admin(user_id).map_auth_err()?;
+        auth.ensure_self_or_permission(user_id, Permission::UserWrite)
+            .map_auth_err()?;
 
         let mut txn = self.db.begin_transaction().await?;
 
-        if !self
+        let user_composite = self
             .user_repo
-            .exists(&mut txn, user_id)
+            .get_composite(&mut txn, user_id)
             .await
-            .context("Failed to check user existence")?
-        {
-            return Err(OAuth2CreateLinkError::NotFound);
-        }
+            .context("Failed to get user from database")?
+            .ok_or(OAuth2CreateLinkError::NotFound)?;
 
         let provider_id = login.provider_id.clone();
 
-        let user_info = self
+        let OAuth2RemoteLogin {
+            user_info, tokens, ..
+        } = self
             .oauth2_login
             .login(login)
             .await
             .map_err(|err| match err {
                 OAuth2LoginServiceError::InvalidProvider => OAuth2CreateLinkError::InvalidProvider,
+                OAuth2LoginServiceError::InvalidState => OAuth2CreateLinkError::InvalidState,
                 OAuth2LoginServiceError::InvalidCode => OAuth2CreateLinkError::InvalidCode,
                 OAuth2LoginServiceError::Other(err) => {
                     err.context("Failed to perform OAuth2 login").into()
@@ -181,7 +222,7 @@ where
 
         let link = self
             .oauth2_create_link
-            .create(&mut txn, user_id, provider_id, user_info)
+            .create(&mut txn, user_id, provider_id, user_info, tokens)
             .await
             .map_err(|err| match err {
                 OAuth2LinkServiceError::RemoteAlreadyLinked => {
@@ -192,8 +233,38 @@ where
                 }
             })?;
 
+        self.audit_log
+            .record(
+                &mut txn,
+                Some(auth.user_id),
+                user_id,
+                AuditEventKind::OAuth2LinkCreated,
+                client,
+            )
+            .await
+            .context("Failed to record audit event")?;
+
         txn.commit().await?;
 
+        if let Some(email) = user_composite.user.email {
+            let provider = self
+                .config
+                .providers
+                .get(&link.provider_id)
+                .map(|provider| provider.name.clone().into_inner())
+                .unwrap_or_else(|| link.provider_id.clone().into_inner());
+            if let Err(err) = self
+                .template_email
+                .send_oauth2_link_created_email(
+                    email.with_name(user_composite.profile.display_name.into_inner()),
+                    &OAuth2LinkCreatedTemplate { provider },
+                )
+                .await
+            {
+                error!("Failed to send oauth2 link created email: {err:#}");
+            }
+        }
+
         Ok(link)
     }
 
@@ -203,10 +274,12 @@ where
         token: &AccessToken,
         user_id: UserIdOrSelf,
         link_id: OAuth2LinkId,
+        client: ClientInfo,
     ) -> Result<(), OAuth2DeleteLinkError> {
         let auth = self.auth.authenticate(token).await.map_auth_err()?;
         let user_id = user_id.unwrap_or(auth.user_id);
-        auth.ensure_self_or_admin(user_id).map_auth_err()?;
+        auth.ensure_self_or_permission(user_id, Permission::UserWrite)
+            .map_auth_err()?;
 
         let mut txn = self.db.begin_transaction().await?;
 
@@ -218,6 +291,12 @@ where
             .filter(|link| link.user_id == user_id)
             .ok_or(OAuth2DeleteLinkError::NotFound)?;
 
+        let tokens = self
+            .oauth2_token
+            .get(&mut txn, link.id)
+            .await
+            .context("Failed to get OAuth2 tokens")?;
+
         self.oauth2_repo
             .delete_link(&mut txn, link.id)
             .await
@@ -235,8 +314,27 @@ where
             return Err(OAuth2DeleteLinkError::CannotRemoveLink);
         }
 
+        self.audit_log
+            .record(
+                &mut txn,
+                Some(auth.user_id),
+                user_id,
+                AuditEventKind::OAuth2LinkDeleted,
+                client,
+            )
+            .await
+            .context("Failed to record audit event")?;
+
         txn.commit().await?;
 
+        // the link has already been deleted, so a failed revocation only
+        // leaves the tokens valid at the provider until they expire
+        if let Some(tokens) = tokens {
+            if let Err(err) = self.oauth2_token.revoke(&link.provider_id, &tokens).await {
+                warn!("Failed to revoke tokens of deleted OAuth2 link: {err:#}");
+            }
+        }
+
         Ok(())
     }
 
@@ -245,9 +343,14 @@ where
         &self,
         login: OAuth2Login,
         device_name: Option<DeviceName>,
+        client: ClientInfo,
     ) -> Result<OAuth2CreateSessionResponse, OAuth2CreateSessionError> {
         let provider_id = login.provider_id.clone();
-        let user_info = self
+        let OAuth2RemoteLogin {
+            user_info,
+            profile,
+            tokens,
+        } = self
             .oauth2_login
             .login(login)
             .await
@@ -255,6 +358,7 @@ where
                 OAuth2LoginServiceError::InvalidProvider => {
                     OAuth2CreateSessionError::InvalidProvider
                 }
+                OAuth2LoginServiceError::InvalidState => OAuth2CreateSessionError::InvalidState,
                 OAuth2LoginServiceError::InvalidCode => OAuth2CreateSessionError::InvalidCode,
                 OAuth2LoginServiceError::Other(err) => {
                     err.context("Failed to perform OAuth2 login").into()
@@ -281,19 +385,36 @@ where
                 .save(&OAuth2Registration {
                     provider_id,
                     remote_user: user_info,
+                    profile: profile.clone(),
+                    tokens,
                 })
                 .await
                 .context("Failed to save OAuth2 registration")?;
 
-            return Ok(OAuth2CreateSessionResponse::RegistrationToken(
-                registration_token,
-            ));
+            return Ok(OAuth2CreateSessionResponse::Registration {
+                token: registration_token,
+                profile,
+            });
         };
 
-        if !user_composite.user.enabled {
+        if !user_composite.user.can_login() {
             return Err(OAuth2CreateSessionError::UserDisabled);
         }
 
+        let link = self
+            .oauth2_repo
+            .get_link_by_remote_user(&mut txn, &provider_id, &user_info.id)
+            .await
+            .context("Failed to get OAuth2 link from database")?
+            .context("OAuth2 link of user does not exist")?;
+        self.oauth2_token
+            .save(&mut txn, link.id, &tokens)
+            .await
+            .context("Failed to save OAuth2 tokens")?;
+
+        // don't notify the user about the very first login after registration
+        let notify = user_composite.user.last_login.is_some();
+
         let login = self
             .session
             .create(&mut txn, user_composite, device_name, true)
@@ -302,6 +423,8 @@ where
 
         txn.commit().await?;
 
+        self.session.remember_device(&login, &client, notify).await;
+
         Ok(OAuth2CreateSessionResponse::Login(login.into()))
     }
 }
@@ -1,27 +1,33 @@
-use academy_core_oauth2_contracts::link::{OAuth2LinkService, OAuth2LinkServiceError};
+use academy_core_oauth2_contracts::{
+    link::{OAuth2LinkService, OAuth2LinkServiceError},
+    token::OAuth2TokenService,
+};
 use academy_di::Build;
 use academy_models::{
-    oauth2::{OAuth2Link, OAuth2ProviderId, OAuth2UserInfo},
+    oauth2::{OAuth2Link, OAuth2ProviderId, OAuth2Tokens, OAuth2UserInfo},
     user::UserId,
 };
 use academy_persistence_contracts::oauth2::{OAuth2RepoError, OAuth2Repository};
 use academy_shared_contracts::{id::IdService, time::TimeService};
 use academy_utils::trace_instrument;
+use anyhow::Context;
 
 #[derive(Debug, Clone, Build)]
-pub struct OAuth2LinkServiceImpl<Id, Time, OAuth2Repo> {
+pub struct OAuth2LinkServiceImpl<Id, Time, OAuth2Repo, OAuth2Token> {
     id: Id,
     time: Time,
     oauth2_repo: OAuth2Repo,
+    oauth2_token: OAuth2Token,
 }
 
-impl<Txn, Id, Time, OAuth2Repo> OAuth2LinkService<Txn>
-    for OAuth2LinkServiceImpl<Id, Time, OAuth2Repo>
+impl<Txn, Id, Time, OAuth2Repo, OAuth2Token> OAuth2LinkService<Txn>
+    for OAuth2LinkServiceImpl<Id, Time, OAuth2Repo, OAuth2Token>
 where
     Txn: Send + Sync + 'static,
     Id: IdService,
     Time: TimeService,
     OAuth2Repo: OAuth2Repository<Txn>,
+    OAuth2Token: OAuth2TokenService<Txn>,
 {
     #[trace_instrument(skip(self, txn))]
     async fn create(
@@ -30,6 +36,7 @@ where
         user_id: UserId,
         provider_id: OAuth2ProviderId,
         remote_user: OAuth2UserInfo,
+        tokens: OAuth2Tokens,
     ) -> Result<OAuth2Link, OAuth2LinkServiceError> {
         let link = OAuth2Link {
             id: self.id.generate(),
@@ -49,14 +56,20 @@ where
                     .into(),
             })?;
 
+        self.oauth2_token
+            .save(txn, link.id, &tokens)
+            .await
+            .context("Failed to save OAuth2 tokens")?;
+
         Ok(link)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use academy_core_oauth2_contracts::token::MockOAuth2TokenService;
     use academy_demo::{
-        oauth2::{FOO_OAUTH2_LINK_1, TEST_OAUTH2_PROVIDER_ID},
+        oauth2::{FOO_OAUTH2_LINK_1, FOO_OAUTH2_TOKENS, TEST_OAUTH2_PROVIDER_ID},
         user::FOO,
     };
     use academy_persistence_contracts::oauth2::{MockOAuth2Repository, OAuth2RepoError};
@@ -74,10 +87,14 @@ mod tests {
         let oauth2_repo =
             MockOAuth2Repository::new().with_create(FOO_OAUTH2_LINK_1.clone(), Ok(()));
 
+        let oauth2_token = MockOAuth2TokenService::new()
+            .with_save(FOO_OAUTH2_LINK_1.id, FOO_OAUTH2_TOKENS.clone());
+
         let sut = OAuth2LinkServiceImpl {
             id,
             time,
             oauth2_repo,
+            oauth2_token,
         };
 
         // Act
@@ -87,6 +104,7 @@ mod tests {
                 FOO.user.id,
                 TEST_OAUTH2_PROVIDER_ID.clone(),
                 FOO_OAUTH2_LINK_1.remote_user.clone(),
+                FOO_OAUTH2_TOKENS.clone(),
             )
             .await;
 
@@ -107,6 +125,7 @@ mod tests {
             id,
             time,
             oauth2_repo,
+            oauth2_token: MockOAuth2TokenService::new(),
         };
 
         // Act
@@ -116,6 +135,7 @@ mod tests {
                 FOO.user.id,
                 TEST_OAUTH2_PROVIDER_ID.clone(),
                 FOO_OAUTH2_LINK_1.remote_user.clone(),
+                FOO_OAUTH2_TOKENS.clone(),
             )
             .await;
 
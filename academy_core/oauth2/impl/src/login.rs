@@ -1,33 +1,109 @@
-use academy_core_oauth2_contracts::login::{OAuth2LoginService, OAuth2LoginServiceError};
+use academy_cache_contracts::CacheService;
+use academy_core_oauth2_contracts::login::{
+    OAuth2AuthorizeServiceError, OAuth2LoginService, OAuth2LoginServiceError,
+};
 use academy_di::Build;
 use academy_extern_contracts::oauth2::{OAuth2ApiService, OAuth2ResolveCodeError};
-use academy_models::oauth2::{OAuth2Login, OAuth2UserInfo};
+use academy_models::{
+    oauth2::{
+        OAuth2Login, OAuth2Nonce, OAuth2PendingAuthorization, OAuth2PkceVerifier, OAuth2ProviderId,
+        OAuth2RemoteLogin, OAuth2State,
+    },
+    url::Url,
+};
+use academy_shared_contracts::secret::SecretService;
 use academy_utils::trace_instrument;
+use anyhow::Context;
 
 use crate::OAuth2FeatureConfig;
 
 #[derive(Debug, Clone, Build)]
 #[cfg_attr(test, derive(Default))]
-pub struct OAuth2LoginServiceImpl<OAuth2Api> {
+pub struct OAuth2LoginServiceImpl<OAuth2Api, Secret, Cache> {
     oauth2_api: OAuth2Api,
+    secret: Secret,
+    cache: Cache,
     config: OAuth2FeatureConfig,
 }
 
-impl<OAuth2Api> OAuth2LoginService for OAuth2LoginServiceImpl<OAuth2Api>
+impl<OAuth2Api, Secret, Cache> OAuth2LoginService
+    for OAuth2LoginServiceImpl<OAuth2Api, Secret, Cache>
 where
     OAuth2Api: OAuth2ApiService,
+    Secret: SecretService,
+    Cache: CacheService,
 {
     #[trace_instrument(skip(self))]
-    async fn login(&self, login: OAuth2Login) -> Result<OAuth2UserInfo, OAuth2LoginServiceError> {
+    async fn authorize(
+        &self,
+        provider_id: OAuth2ProviderId,
+        redirect_uri: Url,
+    ) -> Result<Url, OAuth2AuthorizeServiceError> {
+        let provider = self
+            .config
+            .providers
+            .get(&provider_id)
+            .ok_or(OAuth2AuthorizeServiceError::InvalidProvider)?;
+
+        let state = OAuth2State::try_new(self.secret.generate(OAuth2State::LEN).0).unwrap();
+        let authorization = OAuth2PendingAuthorization {
+            provider_id,
+            redirect_uri,
+            pkce_verifier: OAuth2PkceVerifier::new(self.secret.generate(OAuth2PkceVerifier::LEN).0),
+            nonce: OAuth2Nonce::new(self.secret.generate(OAuth2Nonce::LEN).0),
+        };
+
+        let auth_url = self
+            .oauth2_api
+            .generate_auth_url(provider, &authorization, &state)
+            .await
+            .context("Failed to generate authorize url")?;
+
+        self.cache
+            .set(
+                &oauth2_authorization_cache_key(&state),
+                &authorization,
+                Some(self.config.authorization_ttl),
+            )
+            .await
+            .context("Failed to save OAuth2 authorization in cache")?;
+
+        Ok(auth_url)
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn login(
+        &self,
+        login: OAuth2Login,
+    ) -> Result<OAuth2RemoteLogin, OAuth2LoginServiceError> {
         let provider = self
             .config
             .providers
             .get(&login.provider_id)
             .ok_or(OAuth2LoginServiceError::InvalidProvider)?;
 
-        let user_info = self
+        // each authorization request can only be completed once
+        let cache_key = oauth2_authorization_cache_key(&login.state);
+        let authorization = self
+            .cache
+            .get::<OAuth2PendingAuthorization>(&cache_key)
+            .await
+            .context("Failed to get OAuth2 authorization from cache")?
+            .ok_or(OAuth2LoginServiceError::InvalidState)?;
+        self.cache
+            .remove(&cache_key)
+            .await
+            .context("Failed to remove OAuth2 authorization from cache")?;
+
+        if authorization.provider_id != login.provider_id
+            || authorization.redirect_uri != login.redirect_uri
+        {
+            return Err(OAuth2LoginServiceError::InvalidState);
+        }
+
+        let remote_login = self
             .oauth2_api
-            .resolve_code(provider.clone(), login.code, login.redirect_uri)
+            .resolve_code(provider.clone(), login.code, authorization)
             .await
             .map_err(|err| match err {
                 OAuth2ResolveCodeError::InvalidCode => OAuth2LoginServiceError::InvalidCode,
@@ -36,38 +112,114 @@ where
                 }
             })?;
 
-        Ok(user_info)
+        Ok(remote_login)
     }
 }
 
+fn oauth2_authorization_cache_key(state: &OAuth2State) -> String {
+    format!("oauth2_authorization:{}", **state)
+}
+
 #[cfg(test)]
 mod tests {
-    use academy_demo::oauth2::{FOO_OAUTH2_LINK_1, TEST_OAUTH2_PROVIDER, TEST_OAUTH2_PROVIDER_ID};
+    use academy_cache_contracts::MockCacheService;
+    use academy_demo::oauth2::{
+        FOO_OAUTH2_LINK_1, FOO_OAUTH2_PROFILE, FOO_OAUTH2_TOKENS, TEST_OAUTH2_PROVIDER,
+        TEST_OAUTH2_PROVIDER_ID,
+    };
     use academy_extern_contracts::oauth2::MockOAuth2ApiService;
+    use academy_shared_contracts::secret::MockSecretService;
     use academy_utils::assert_matches;
 
     use super::*;
 
-    type Sut = OAuth2LoginServiceImpl<MockOAuth2ApiService>;
+    type Sut = OAuth2LoginServiceImpl<MockOAuth2ApiService, MockSecretService, MockCacheService>;
 
     #[tokio::test]
-    async fn ok() {
+    async fn authorize_ok() {
         // Arrange
-        let login = OAuth2Login {
-            provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
-            code: "code".try_into().unwrap(),
-            redirect_uri: "http://test/redirect".parse().unwrap(),
+        let config = OAuth2FeatureConfig::default();
+        let state = state();
+        let authorization = authorization();
+        let expected = "http://test/auth?state=the-state".parse::<Url>().unwrap();
+
+        let secret = MockSecretService::new()
+            .with_generate(OAuth2State::LEN, state.clone().into_inner())
+            .with_generate(
+                OAuth2PkceVerifier::LEN,
+                authorization.pkce_verifier.clone().into_inner(),
+            )
+            .with_generate(OAuth2Nonce::LEN, authorization.nonce.clone().into_inner());
+
+        let oauth2_api = MockOAuth2ApiService::new().with_generate_auth_url(
+            TEST_OAUTH2_PROVIDER.clone(),
+            authorization.clone(),
+            state.clone(),
+            expected.clone(),
+        );
+
+        let cache = MockCacheService::new().with_set(
+            format!("oauth2_authorization:{}", *state),
+            authorization.clone(),
+            Some(config.authorization_ttl),
+        );
+
+        let sut = OAuth2LoginServiceImpl {
+            oauth2_api,
+            secret,
+            cache,
+            ..Sut::default()
         };
 
+        // Act
+        let result = sut
+            .authorize(authorization.provider_id, authorization.redirect_uri)
+            .await;
+
+        // Assert
+        assert_eq!(result.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn authorize_invalid_provider() {
+        // Arrange
+        let sut = Sut::default();
+
+        // Act
+        let result = sut
+            .authorize(
+                "invalid-provider".into(),
+                "http://test/redirect".parse().unwrap(),
+            )
+            .await;
+
+        // Assert
+        assert_matches!(result, Err(OAuth2AuthorizeServiceError::InvalidProvider));
+    }
+
+    #[tokio::test]
+    async fn ok() {
+        // Arrange
+        let login = login();
+        let authorization = authorization();
+
+        let cache = MockCacheService::new()
+            .with_get(
+                format!("oauth2_authorization:{}", *login.state),
+                Some(authorization.clone()),
+            )
+            .with_remove(format!("oauth2_authorization:{}", *login.state));
+
         let oauth2_api = MockOAuth2ApiService::new().with_resolve_code(
             TEST_OAUTH2_PROVIDER.clone(),
             login.code.clone(),
-            login.redirect_uri.clone(),
-            Ok(FOO_OAUTH2_LINK_1.remote_user.clone()),
+            authorization,
+            Ok(remote_login()),
         );
 
         let sut = OAuth2LoginServiceImpl {
             oauth2_api,
+            cache,
             ..Sut::default()
         };
 
@@ -75,7 +227,7 @@ mod tests {
         let result = sut.login(login).await;
 
         // Assert
-        assert_eq!(result.unwrap(), FOO_OAUTH2_LINK_1.remote_user);
+        assert_eq!(result.unwrap(), remote_login());
     }
 
     #[tokio::test]
@@ -83,8 +235,7 @@ mod tests {
         // Arrange
         let login = OAuth2Login {
             provider_id: "invalid-provider".into(),
-            code: "code".try_into().unwrap(),
-            redirect_uri: "http://test/redirect".parse().unwrap(),
+            ..login()
         };
 
         let sut = Sut::default();
@@ -97,23 +248,77 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn invalid_code() {
+    async fn invalid_state() {
+        // Arrange
+        let login = login();
+
+        let cache = MockCacheService::new().with_get(
+            format!("oauth2_authorization:{}", *login.state),
+            None::<OAuth2PendingAuthorization>,
+        );
+
+        let sut = OAuth2LoginServiceImpl {
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.login(login).await;
+
+        // Assert
+        assert_matches!(result, Err(OAuth2LoginServiceError::InvalidState));
+    }
+
+    #[tokio::test]
+    async fn redirect_uri_mismatch() {
         // Arrange
         let login = OAuth2Login {
-            provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
-            code: "code".try_into().unwrap(),
-            redirect_uri: "http://test/redirect".parse().unwrap(),
+            redirect_uri: "http://evil/redirect".parse().unwrap(),
+            ..login()
+        };
+
+        let cache = MockCacheService::new()
+            .with_get(
+                format!("oauth2_authorization:{}", *login.state),
+                Some(authorization()),
+            )
+            .with_remove(format!("oauth2_authorization:{}", *login.state));
+
+        let sut = OAuth2LoginServiceImpl {
+            cache,
+            ..Sut::default()
         };
 
+        // Act
+        let result = sut.login(login).await;
+
+        // Assert
+        assert_matches!(result, Err(OAuth2LoginServiceError::InvalidState));
+    }
+
+    #[tokio::test]
+    async fn invalid_code() {
+        // Arrange
+        let login = login();
+        let authorization = authorization();
+
+        let cache = MockCacheService::new()
+            .with_get(
+                format!("oauth2_authorization:{}", *login.state),
+                Some(authorization.clone()),
+            )
+            .with_remove(format!("oauth2_authorization:{}", *login.state));
+
         let oauth2_api = MockOAuth2ApiService::new().with_resolve_code(
             TEST_OAUTH2_PROVIDER.clone(),
             login.code.clone(),
-            login.redirect_uri.clone(),
+            authorization,
             Err(OAuth2ResolveCodeError::InvalidCode),
         );
 
         let sut = OAuth2LoginServiceImpl {
             oauth2_api,
+            cache,
             ..Sut::default()
         };
 
@@ -123,4 +328,35 @@ mod tests {
         // Assert
         assert_matches!(result, Err(OAuth2LoginServiceError::InvalidCode));
     }
+
+    fn state() -> OAuth2State {
+        "Jx7b0nmDRqzs0Os6iXwb8lSX4MdcxKWQ".try_into().unwrap()
+    }
+
+    fn authorization() -> OAuth2PendingAuthorization {
+        OAuth2PendingAuthorization {
+            provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
+            redirect_uri: "http://test/redirect".parse().unwrap(),
+            pkce_verifier: "tHI7MDQ1rFClyoHtKtq1iWhBw3uCTL95wYOzMfOLZg0Hm0kcXyLJ0hvxqBtRXsSd"
+                .into(),
+            nonce: "Gd7Ltb1oIEbgS1c6aASoKYlfzeJNVNQPmNHEUOmRcI5Hd9bf".into(),
+        }
+    }
+
+    fn remote_login() -> OAuth2RemoteLogin {
+        OAuth2RemoteLogin {
+            user_info: FOO_OAUTH2_LINK_1.remote_user.clone(),
+            profile: FOO_OAUTH2_PROFILE.clone(),
+            tokens: FOO_OAUTH2_TOKENS.clone(),
+        }
+    }
+
+    fn login() -> OAuth2Login {
+        OAuth2Login {
+            provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
+            code: "code".try_into().unwrap(),
+            redirect_uri: "http://test/redirect".parse().unwrap(),
+            state: state(),
+        }
+    }
 }
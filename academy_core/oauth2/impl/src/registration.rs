@@ -2,7 +2,7 @@ use academy_cache_contracts::CacheService;
 use academy_core_oauth2_contracts::registration::OAuth2RegistrationService;
 use academy_di::Build;
 use academy_models::oauth2::{OAuth2Registration, OAuth2RegistrationToken};
-use academy_shared_contracts::secret::SecretService;
+use academy_shared_contracts::{encryption::EncryptionService, secret::SecretService};
 use academy_utils::trace_instrument;
 use anyhow::Context;
 
@@ -10,15 +10,18 @@ use crate::OAuth2FeatureConfig;
 
 #[derive(Debug, Clone, Build)]
 #[cfg_attr(test, derive(Default))]
-pub struct OAuth2RegistrationServiceImpl<Secret, Cache> {
+pub struct OAuth2RegistrationServiceImpl<Secret, Encryption, Cache> {
     secret: Secret,
+    encryption: Encryption,
     cache: Cache,
     config: OAuth2FeatureConfig,
 }
 
-impl<Secret, Cache> OAuth2RegistrationService for OAuth2RegistrationServiceImpl<Secret, Cache>
+impl<Secret, Encryption, Cache> OAuth2RegistrationService
+    for OAuth2RegistrationServiceImpl<Secret, Encryption, Cache>
 where
     Secret: SecretService,
+    Encryption: EncryptionService,
     Cache: CacheService,
 {
     #[trace_instrument(skip(self))]
@@ -30,10 +33,19 @@ where
             OAuth2RegistrationToken::try_new(self.secret.generate(OAuth2RegistrationToken::LEN).0)
                 .unwrap();
 
+        // the registration contains the provider tokens of the remote user
+        let registration =
+            serde_json::to_vec(registration).context("Failed to serialize OAuth2 registration")?;
+        let cache_key = oauth2_registration_cache_key(&registration_token);
+        let registration = self
+            .encryption
+            .encrypt(&registration, cache_key.as_bytes())
+            .context("Failed to encrypt OAuth2 registration")?;
+
         self.cache
             .set(
-                &oauth2_registration_cache_key(&registration_token),
-                registration,
+                &cache_key,
+                &registration,
                 Some(self.config.registration_token_ttl),
             )
             .await
@@ -47,10 +59,23 @@ where
         &self,
         registration_token: &OAuth2RegistrationToken,
     ) -> anyhow::Result<Option<OAuth2Registration>> {
-        self.cache
-            .get(&oauth2_registration_cache_key(registration_token))
+        let cache_key = oauth2_registration_cache_key(registration_token);
+        let Some(registration) = self
+            .cache
+            .get::<Vec<u8>>(&cache_key)
             .await
-            .context("Failed to get OAuth2 registration from cache")
+            .context("Failed to get OAuth2 registration from cache")?
+        else {
+            return Ok(None);
+        };
+
+        let registration = self
+            .encryption
+            .decrypt(&registration, cache_key.as_bytes())
+            .context("Failed to decrypt OAuth2 registration")?;
+        serde_json::from_slice(&registration)
+            .map(Some)
+            .context("Failed to deserialize OAuth2 registration")
     }
 
     #[trace_instrument(skip(self))]
@@ -69,12 +94,13 @@ fn oauth2_registration_cache_key(registration_token: &OAuth2RegistrationToken) -
 #[cfg(test)]
 mod tests {
     use academy_cache_contracts::MockCacheService;
-    use academy_demo::oauth2::FOO_OAUTH2_LINK_1;
-    use academy_shared_contracts::secret::MockSecretService;
+    use academy_demo::oauth2::{FOO_OAUTH2_LINK_1, FOO_OAUTH2_PROFILE, FOO_OAUTH2_TOKENS};
+    use academy_shared_contracts::{encryption::MockEncryptionService, secret::MockSecretService};
 
     use super::*;
 
-    type Sut = OAuth2RegistrationServiceImpl<MockSecretService, MockCacheService>;
+    type Sut =
+        OAuth2RegistrationServiceImpl<MockSecretService, MockEncryptionService, MockCacheService>;
 
     #[tokio::test]
     async fn save() {
@@ -86,14 +112,21 @@ mod tests {
         let secret = MockSecretService::new()
             .with_generate(OAuth2RegistrationToken::LEN, expected.clone().into_inner());
 
+        let encryption = MockEncryptionService::new().with_encrypt(
+            serde_json::to_vec(&registration).unwrap(),
+            format!("oauth2_registration:{}", *expected).into(),
+            encrypted_registration(),
+        );
+
         let cache = MockCacheService::new().with_set(
             format!("oauth2_registration:{}", *expected),
-            registration.clone(),
+            encrypted_registration(),
             Some(config.registration_token_ttl),
         );
 
         let sut = OAuth2RegistrationServiceImpl {
             secret,
+            encryption,
             cache,
             ..Sut::default()
         };
@@ -113,10 +146,17 @@ mod tests {
 
         let cache = MockCacheService::new().with_get(
             format!("oauth2_registration:{}", *token),
-            Some(expected.clone()),
+            Some(encrypted_registration()),
+        );
+
+        let encryption = MockEncryptionService::new().with_decrypt(
+            encrypted_registration(),
+            format!("oauth2_registration:{}", *token).into(),
+            serde_json::to_vec(&expected).unwrap(),
         );
 
         let sut = OAuth2RegistrationServiceImpl {
+            encryption,
             cache,
             ..Sut::default()
         };
@@ -133,10 +173,8 @@ mod tests {
         // Arrange
         let token = token();
 
-        let cache = MockCacheService::new().with_get(
-            format!("oauth2_registration:{}", *token),
-            None::<OAuth2Registration>,
-        );
+        let cache = MockCacheService::new()
+            .with_get(format!("oauth2_registration:{}", *token), None::<Vec<u8>>);
 
         let sut = OAuth2RegistrationServiceImpl {
             cache,
@@ -175,10 +213,16 @@ mod tests {
             .unwrap()
     }
 
+    fn encrypted_registration() -> Vec<u8> {
+        b"encrypted registration".into()
+    }
+
     fn registration() -> OAuth2Registration {
         OAuth2Registration {
             provider_id: "test-provider".into(),
             remote_user: FOO_OAUTH2_LINK_1.remote_user.clone(),
+            profile: FOO_OAUTH2_PROFILE.clone(),
+            tokens: FOO_OAUTH2_TOKENS.clone(),
         }
     }
 }
@@ -0,0 +1,61 @@
+use std::str::FromStr;
+
+use academy_core_oauth2_contracts::{
+    login::{MockOAuth2LoginService, OAuth2AuthorizeServiceError},
+    OAuth2AuthorizeError, OAuth2FeatureService,
+};
+use academy_demo::oauth2::TEST_OAUTH2_PROVIDER_ID;
+use academy_models::url::Url;
+use academy_utils::assert_matches;
+
+use super::Sut;
+use crate::OAuth2FeatureServiceImpl;
+
+#[tokio::test]
+async fn ok() {
+    // Arrange
+    let redirect_uri = Url::from_str("http://test/redirect").unwrap();
+    let expected = Url::from_str("http://test/auth?client_id=test-id&state=the-state").unwrap();
+
+    let oauth2_login = MockOAuth2LoginService::new().with_authorize(
+        TEST_OAUTH2_PROVIDER_ID.clone(),
+        redirect_uri.clone(),
+        Ok(expected.clone()),
+    );
+
+    let sut = OAuth2FeatureServiceImpl {
+        oauth2_login,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .authorize(TEST_OAUTH2_PROVIDER_ID.clone(), redirect_uri)
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn invalid_provider() {
+    // Arrange
+    let redirect_uri = Url::from_str("http://test/redirect").unwrap();
+
+    let oauth2_login = MockOAuth2LoginService::new().with_authorize(
+        "invalid-provider".into(),
+        redirect_uri.clone(),
+        Err(OAuth2AuthorizeServiceError::InvalidProvider),
+    );
+
+    let sut = OAuth2FeatureServiceImpl {
+        oauth2_login,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.authorize("invalid-provider".into(), redirect_uri).await;
+
+    // Assert
+    assert_matches!(result, Err(OAuth2AuthorizeError::InvalidProvider));
+}
@@ -1,20 +1,27 @@
 use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::log::MockAuditLogService;
 use academy_core_oauth2_contracts::{
     link::{MockOAuth2LinkService, OAuth2LinkServiceError},
     login::{MockOAuth2LoginService, OAuth2LoginServiceError},
     OAuth2CreateLinkError, OAuth2FeatureService,
 };
 use academy_demo::{
-    oauth2::{FOO_OAUTH2_LINK_1, TEST_OAUTH2_PROVIDER_ID},
+    oauth2::{
+        FOO_OAUTH2_LINK_1, FOO_OAUTH2_PROFILE, FOO_OAUTH2_TOKENS, TEST_OAUTH2_PROVIDER,
+        TEST_OAUTH2_PROVIDER_ID,
+    },
     session::{ADMIN_1, BAR_1, FOO_1},
     user::{ADMIN, BAR, FOO},
 };
+use academy_email_contracts::template::MockTemplateEmailService;
 use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
     auth::{AuthError, AuthenticateError, AuthorizeError},
-    oauth2::OAuth2Login,
+    oauth2::{OAuth2Login, OAuth2RemoteLogin},
     user::UserIdOrSelf,
 };
 use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
+use academy_templates_contracts::OAuth2LinkCreatedTemplate;
 use academy_utils::assert_matches;
 
 use crate::{tests::Sut, OAuth2FeatureServiceImpl};
@@ -26,36 +33,70 @@ async fn ok() {
         provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
         code: "code".try_into().unwrap(),
         redirect_uri: "http://test/redirect".parse().unwrap(),
+        state: "Jx7b0nmDRqzs0Os6iXwb8lSX4MdcxKWQ".try_into().unwrap(),
     };
 
     let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
 
     let db = MockDatabase::build(true);
 
-    let user_repo = MockUserRepository::new().with_exists(FOO.user.id, true);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
 
-    let oauth2_login = MockOAuth2LoginService::new()
-        .with_login(login.clone(), Ok(FOO_OAUTH2_LINK_1.remote_user.clone()));
+    let oauth2_login = MockOAuth2LoginService::new().with_login(
+        login.clone(),
+        Ok(OAuth2RemoteLogin {
+            user_info: FOO_OAUTH2_LINK_1.remote_user.clone(),
+            profile: FOO_OAUTH2_PROFILE.clone(),
+            tokens: FOO_OAUTH2_TOKENS.clone(),
+        }),
+    );
 
     let oauth2_create_link = MockOAuth2LinkService::new().with_create(
         FOO.user.id,
         TEST_OAUTH2_PROVIDER_ID.clone(),
         FOO_OAUTH2_LINK_1.remote_user.clone(),
+        FOO_OAUTH2_TOKENS.clone(),
         Ok(FOO_OAUTH2_LINK_1.clone()),
     );
 
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(FOO.user.id),
+        FOO.user.id,
+        AuditEventKind::OAuth2LinkCreated,
+        ClientInfo::default(),
+    );
+
+    let template_email = MockTemplateEmailService::new().with_send_oauth2_link_created_email(
+        FOO.user
+            .email
+            .clone()
+            .unwrap()
+            .with_name(FOO.profile.display_name.clone().into_inner()),
+        OAuth2LinkCreatedTemplate {
+            provider: TEST_OAUTH2_PROVIDER.name.clone().into_inner(),
+        },
+        true,
+    );
+
     let sut = OAuth2FeatureServiceImpl {
         db,
         auth,
         user_repo,
         oauth2_login,
         oauth2_create_link,
+        template_email,
+        audit_log,
         ..Sut::default()
     };
 
     // Act
     let result = sut
-        .create_link(&"token".into(), UserIdOrSelf::Slf, login)
+        .create_link(
+            &"token".into(),
+            UserIdOrSelf::Slf,
+            login,
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -69,6 +110,7 @@ async fn unauthenticated() {
         provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
         code: "code".try_into().unwrap(),
         redirect_uri: "http://test/redirect".parse().unwrap(),
+        state: "Jx7b0nmDRqzs0Os6iXwb8lSX4MdcxKWQ".try_into().unwrap(),
     };
 
     let auth = MockAuthService::new().with_authenticate(None);
@@ -80,7 +122,12 @@ async fn unauthenticated() {
 
     // Act
     let result = sut
-        .create_link(&"token".into(), FOO.user.id.into(), login)
+        .create_link(
+            &"token".into(),
+            FOO.user.id.into(),
+            login,
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -99,6 +146,7 @@ async fn unauthorized() {
         provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
         code: "code".try_into().unwrap(),
         redirect_uri: "http://test/redirect".parse().unwrap(),
+        state: "Jx7b0nmDRqzs0Os6iXwb8lSX4MdcxKWQ".try_into().unwrap(),
     };
 
     let auth = MockAuthService::new().with_authenticate(Some((BAR.user.clone(), BAR_1.clone())));
@@ -110,14 +158,19 @@ async fn unauthorized() {
 
     // Act
     let result = sut
-        .create_link(&"token".into(), FOO.user.id.into(), login)
+        .create_link(
+            &"token".into(),
+            FOO.user.id.into(),
+            login,
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
     assert_matches!(
         result,
         Err(OAuth2CreateLinkError::Auth(AuthError::Authorize(
-            AuthorizeError::Admin
+            AuthorizeError::Permission
         )))
     );
 }
@@ -129,6 +182,7 @@ async fn not_found() {
         provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
         code: "code".try_into().unwrap(),
         redirect_uri: "http://test/redirect".parse().unwrap(),
+        state: "Jx7b0nmDRqzs0Os6iXwb8lSX4MdcxKWQ".try_into().unwrap(),
     };
 
     let auth =
@@ -136,7 +190,7 @@ async fn not_found() {
 
     let db = MockDatabase::build(false);
 
-    let user_repo = MockUserRepository::new().with_exists(FOO.user.id, false);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, None);
 
     let sut = OAuth2FeatureServiceImpl {
         db,
@@ -147,7 +201,12 @@ async fn not_found() {
 
     // Act
     let result = sut
-        .create_link(&"token".into(), FOO.user.id.into(), login)
+        .create_link(
+            &"token".into(),
+            FOO.user.id.into(),
+            login,
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -161,13 +220,14 @@ async fn invalid_provider() {
         provider_id: "invalid-provider".into(),
         code: "code".try_into().unwrap(),
         redirect_uri: "http://test/redirect".parse().unwrap(),
+        state: "Jx7b0nmDRqzs0Os6iXwb8lSX4MdcxKWQ".try_into().unwrap(),
     };
 
     let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
 
     let db = MockDatabase::build(false);
 
-    let user_repo = MockUserRepository::new().with_exists(FOO.user.id, true);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
 
     let oauth2_login = MockOAuth2LoginService::new()
         .with_login(login.clone(), Err(OAuth2LoginServiceError::InvalidProvider));
@@ -182,7 +242,12 @@ async fn invalid_provider() {
 
     // Act
     let result = sut
-        .create_link(&"token".into(), UserIdOrSelf::Slf, login)
+        .create_link(
+            &"token".into(),
+            UserIdOrSelf::Slf,
+            login,
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -196,13 +261,14 @@ async fn invalid_code() {
         provider_id: "invalid-provider".into(),
         code: "code".try_into().unwrap(),
         redirect_uri: "http://test/redirect".parse().unwrap(),
+        state: "Jx7b0nmDRqzs0Os6iXwb8lSX4MdcxKWQ".try_into().unwrap(),
     };
 
     let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
 
     let db = MockDatabase::build(false);
 
-    let user_repo = MockUserRepository::new().with_exists(FOO.user.id, true);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
 
     let oauth2_login = MockOAuth2LoginService::new()
         .with_login(login.clone(), Err(OAuth2LoginServiceError::InvalidCode));
@@ -217,7 +283,12 @@ async fn invalid_code() {
 
     // Act
     let result = sut
-        .create_link(&"token".into(), UserIdOrSelf::Slf, login)
+        .create_link(
+            &"token".into(),
+            UserIdOrSelf::Slf,
+            login,
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -231,21 +302,29 @@ async fn remote_already_linked() {
         provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
         code: "code".try_into().unwrap(),
         redirect_uri: "http://test/redirect".parse().unwrap(),
+        state: "Jx7b0nmDRqzs0Os6iXwb8lSX4MdcxKWQ".try_into().unwrap(),
     };
 
     let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
 
     let db = MockDatabase::build(false);
 
-    let user_repo = MockUserRepository::new().with_exists(FOO.user.id, true);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
 
-    let oauth2_login = MockOAuth2LoginService::new()
-        .with_login(login.clone(), Ok(FOO_OAUTH2_LINK_1.remote_user.clone()));
+    let oauth2_login = MockOAuth2LoginService::new().with_login(
+        login.clone(),
+        Ok(OAuth2RemoteLogin {
+            user_info: FOO_OAUTH2_LINK_1.remote_user.clone(),
+            profile: FOO_OAUTH2_PROFILE.clone(),
+            tokens: FOO_OAUTH2_TOKENS.clone(),
+        }),
+    );
 
     let oauth2_create_link = MockOAuth2LinkService::new().with_create(
         FOO.user.id,
         TEST_OAUTH2_PROVIDER_ID.clone(),
         FOO_OAUTH2_LINK_1.remote_user.clone(),
+        FOO_OAUTH2_TOKENS.clone(),
         Err(OAuth2LinkServiceError::RemoteAlreadyLinked),
     );
 
@@ -260,7 +339,12 @@ async fn remote_already_linked() {
 
     // Act
     let result = sut
-        .create_link(&"token".into(), UserIdOrSelf::Slf, login)
+        .create_link(
+            &"token".into(),
+            UserIdOrSelf::Slf,
+            login,
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
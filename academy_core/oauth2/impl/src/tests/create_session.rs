@@ -1,19 +1,23 @@
 use academy_core_oauth2_contracts::{
     login::{MockOAuth2LoginService, OAuth2LoginServiceError},
     registration::MockOAuth2RegistrationService,
+    token::MockOAuth2TokenService,
     OAuth2CreateSessionError, OAuth2CreateSessionResponse, OAuth2FeatureService,
 };
 use academy_core_session_contracts::session::MockSessionService;
 use academy_demo::{
-    oauth2::{FOO_OAUTH2_LINK_1, TEST_OAUTH2_PROVIDER_ID},
+    oauth2::{FOO_OAUTH2_LINK_1, FOO_OAUTH2_PROFILE, FOO_OAUTH2_TOKENS, TEST_OAUTH2_PROVIDER_ID},
     session::FOO_1,
     user::FOO,
 };
 use academy_models::{
+    audit::ClientInfo,
     auth::Login,
-    oauth2::{OAuth2Login, OAuth2RegistrationToken},
+    oauth2::{OAuth2Login, OAuth2RegistrationToken, OAuth2RemoteLogin},
+};
+use academy_persistence_contracts::{
+    oauth2::MockOAuth2Repository, user::MockUserRepository, MockDatabase,
 };
-use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
 use academy_utils::{assert_matches, Apply};
 
 use crate::{tests::Sut, OAuth2FeatureServiceImpl, OAuth2Registration};
@@ -25,6 +29,7 @@ async fn ok() {
         provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
         code: "code".try_into().unwrap(),
         redirect_uri: "http://test/redirect".parse().unwrap(),
+        state: "Jx7b0nmDRqzs0Os6iXwb8lSX4MdcxKWQ".try_into().unwrap(),
     };
     let expected = Login {
         user_composite: FOO.clone(),
@@ -35,8 +40,14 @@ async fn ok() {
 
     let db = MockDatabase::build(true);
 
-    let oauth2_login = MockOAuth2LoginService::new()
-        .with_login(login.clone(), Ok(FOO_OAUTH2_LINK_1.remote_user.clone()));
+    let oauth2_login = MockOAuth2LoginService::new().with_login(
+        login.clone(),
+        Ok(OAuth2RemoteLogin {
+            user_info: FOO_OAUTH2_LINK_1.remote_user.clone(),
+            profile: FOO_OAUTH2_PROFILE.clone(),
+            tokens: FOO_OAUTH2_TOKENS.clone(),
+        }),
+    );
 
     let user_repo = MockUserRepository::new()
         .with_get_composite_by_oauth2_provider_id_and_remote_user_id(
@@ -45,18 +56,31 @@ async fn ok() {
             Some(FOO.clone()),
         );
 
-    let session = MockSessionService::new().with_create(FOO.clone(), None, true, expected.clone());
+    let oauth2_repo = MockOAuth2Repository::new().with_get_link_by_remote_user(
+        login.provider_id.clone(),
+        FOO_OAUTH2_LINK_1.remote_user.id.clone(),
+        Some(FOO_OAUTH2_LINK_1.clone()),
+    );
+
+    let oauth2_token =
+        MockOAuth2TokenService::new().with_save(FOO_OAUTH2_LINK_1.id, FOO_OAUTH2_TOKENS.clone());
+
+    let session = MockSessionService::new()
+        .with_create(FOO.clone(), None, true, expected.clone())
+        .with_remember_device(expected.clone(), ClientInfo::default(), true);
 
     let sut = OAuth2FeatureServiceImpl {
         db,
         oauth2_login,
         user_repo,
+        oauth2_repo,
+        oauth2_token,
         session,
         ..Sut::default()
     };
 
     // Act
-    let result = sut.create_session(login, None).await;
+    let result = sut.create_session(login, None, ClientInfo::default()).await;
 
     // Assert
     assert_eq!(
@@ -72,6 +96,7 @@ async fn not_linked() {
         provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
         code: "code".try_into().unwrap(),
         redirect_uri: "http://test/redirect".parse().unwrap(),
+        state: "Jx7b0nmDRqzs0Os6iXwb8lSX4MdcxKWQ".try_into().unwrap(),
     };
     let expected = OAuth2RegistrationToken::try_new(
         "kvyhRRjn83JC223MwAbqhFTW09J8a75VIBMyLaxhiLtSl0Mddhyr7qctXcqKBINC",
@@ -80,8 +105,14 @@ async fn not_linked() {
 
     let db = MockDatabase::build(false);
 
-    let oauth2_login = MockOAuth2LoginService::new()
-        .with_login(login.clone(), Ok(FOO_OAUTH2_LINK_1.remote_user.clone()));
+    let oauth2_login = MockOAuth2LoginService::new().with_login(
+        login.clone(),
+        Ok(OAuth2RemoteLogin {
+            user_info: FOO_OAUTH2_LINK_1.remote_user.clone(),
+            profile: FOO_OAUTH2_PROFILE.clone(),
+            tokens: FOO_OAUTH2_TOKENS.clone(),
+        }),
+    );
 
     let user_repo = MockUserRepository::new()
         .with_get_composite_by_oauth2_provider_id_and_remote_user_id(
@@ -94,6 +125,8 @@ async fn not_linked() {
         OAuth2Registration {
             provider_id: login.provider_id.clone(),
             remote_user: FOO_OAUTH2_LINK_1.remote_user.clone(),
+            profile: FOO_OAUTH2_PROFILE.clone(),
+            tokens: FOO_OAUTH2_TOKENS.clone(),
         },
         expected.clone(),
     );
@@ -107,12 +140,15 @@ async fn not_linked() {
     };
 
     // Act
-    let result = sut.create_session(login, None).await;
+    let result = sut.create_session(login, None, ClientInfo::default()).await;
 
     // Assert
     assert_eq!(
         result.unwrap(),
-        OAuth2CreateSessionResponse::RegistrationToken(expected)
+        OAuth2CreateSessionResponse::Registration {
+            token: expected,
+            profile: FOO_OAUTH2_PROFILE.clone(),
+        }
     );
 }
 
@@ -123,6 +159,7 @@ async fn invalid_provider() {
         provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
         code: "code".try_into().unwrap(),
         redirect_uri: "http://test/redirect".parse().unwrap(),
+        state: "Jx7b0nmDRqzs0Os6iXwb8lSX4MdcxKWQ".try_into().unwrap(),
     };
 
     let oauth2_login = MockOAuth2LoginService::new()
@@ -134,7 +171,7 @@ async fn invalid_provider() {
     };
 
     // Act
-    let result = sut.create_session(login, None).await;
+    let result = sut.create_session(login, None, ClientInfo::default()).await;
 
     // Assert
     assert_matches!(result, Err(OAuth2CreateSessionError::InvalidProvider));
@@ -147,6 +184,7 @@ async fn invalid_code() {
         provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
         code: "code".try_into().unwrap(),
         redirect_uri: "http://test/redirect".parse().unwrap(),
+        state: "Jx7b0nmDRqzs0Os6iXwb8lSX4MdcxKWQ".try_into().unwrap(),
     };
 
     let oauth2_login = MockOAuth2LoginService::new()
@@ -158,12 +196,37 @@ async fn invalid_code() {
     };
 
     // Act
-    let result = sut.create_session(login, None).await;
+    let result = sut.create_session(login, None, ClientInfo::default()).await;
 
     // Assert
     assert_matches!(result, Err(OAuth2CreateSessionError::InvalidCode));
 }
 
+#[tokio::test]
+async fn invalid_state() {
+    // Arrange
+    let login = OAuth2Login {
+        provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
+        code: "code".try_into().unwrap(),
+        redirect_uri: "http://test/redirect".parse().unwrap(),
+        state: "Jx7b0nmDRqzs0Os6iXwb8lSX4MdcxKWQ".try_into().unwrap(),
+    };
+
+    let oauth2_login = MockOAuth2LoginService::new()
+        .with_login(login.clone(), Err(OAuth2LoginServiceError::InvalidState));
+
+    let sut = OAuth2FeatureServiceImpl {
+        oauth2_login,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.create_session(login, None, ClientInfo::default()).await;
+
+    // Assert
+    assert_matches!(result, Err(OAuth2CreateSessionError::InvalidState));
+}
+
 #[tokio::test]
 async fn user_disabled() {
     // Arrange
@@ -171,12 +234,19 @@ async fn user_disabled() {
         provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
         code: "code".try_into().unwrap(),
         redirect_uri: "http://test/redirect".parse().unwrap(),
+        state: "Jx7b0nmDRqzs0Os6iXwb8lSX4MdcxKWQ".try_into().unwrap(),
     };
 
     let db = MockDatabase::build(false);
 
-    let oauth2_login = MockOAuth2LoginService::new()
-        .with_login(login.clone(), Ok(FOO_OAUTH2_LINK_1.remote_user.clone()));
+    let oauth2_login = MockOAuth2LoginService::new().with_login(
+        login.clone(),
+        Ok(OAuth2RemoteLogin {
+            user_info: FOO_OAUTH2_LINK_1.remote_user.clone(),
+            profile: FOO_OAUTH2_PROFILE.clone(),
+            tokens: FOO_OAUTH2_TOKENS.clone(),
+        }),
+    );
 
     let user_repo = MockUserRepository::new()
         .with_get_composite_by_oauth2_provider_id_and_remote_user_id(
@@ -193,7 +263,7 @@ async fn user_disabled() {
     };
 
     // Act
-    let result = sut.create_session(login, None).await;
+    let result = sut.create_session(login, None, ClientInfo::default()).await;
 
     // Assert
     assert_matches!(result, Err(OAuth2CreateSessionError::UserDisabled));
@@ -1,11 +1,17 @@
 use academy_auth_contracts::MockAuthService;
-use academy_core_oauth2_contracts::{OAuth2DeleteLinkError, OAuth2FeatureService};
+use academy_core_audit_contracts::log::MockAuditLogService;
+use academy_core_oauth2_contracts::{
+    token::MockOAuth2TokenService, OAuth2DeleteLinkError, OAuth2FeatureService,
+};
 use academy_demo::{
-    oauth2::FOO_OAUTH2_LINK_1,
+    oauth2::{FOO_OAUTH2_LINK_1, FOO_OAUTH2_TOKENS, TEST_OAUTH2_PROVIDER_ID},
     session::{ADMIN_1, BAR_1, FOO_1},
     user::{ADMIN, BAR, FOO},
 };
-use academy_models::auth::{AuthError, AuthenticateError, AuthorizeError};
+use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
+    auth::{AuthError, AuthenticateError, AuthorizeError},
+};
 use academy_persistence_contracts::{
     oauth2::MockOAuth2Repository, user::MockUserRepository, MockDatabase,
 };
@@ -24,22 +30,99 @@ async fn ok() {
         .with_get_link(FOO_OAUTH2_LINK_1.id, Some(FOO_OAUTH2_LINK_1.clone()))
         .with_delete_link(FOO_OAUTH2_LINK_1.id, true);
 
+    let oauth2_token = MockOAuth2TokenService::new()
+        .with_get(FOO_OAUTH2_LINK_1.id, Some(FOO_OAUTH2_TOKENS.clone()))
+        .with_revoke(
+            TEST_OAUTH2_PROVIDER_ID.clone(),
+            FOO_OAUTH2_TOKENS.clone(),
+            Ok(()),
+        );
+
+    let user_repo = MockUserRepository::new().with_get_composite(
+        FOO.user.id,
+        Some(FOO.clone().with(|u| u.details.oauth2_login = false)),
+    );
+
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(FOO.user.id),
+        FOO.user.id,
+        AuditEventKind::OAuth2LinkDeleted,
+        ClientInfo::default(),
+    );
+
+    let sut = OAuth2FeatureServiceImpl {
+        db,
+        auth,
+        oauth2_repo,
+        oauth2_token,
+        user_repo,
+        audit_log,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .delete_link(
+            &"token".into(),
+            FOO.user.id.into(),
+            FOO_OAUTH2_LINK_1.id,
+            ClientInfo::default(),
+        )
+        .await;
+
+    // Assert
+    result.unwrap();
+}
+
+#[tokio::test]
+async fn ok_revocation_failed() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let oauth2_repo = MockOAuth2Repository::new()
+        .with_get_link(FOO_OAUTH2_LINK_1.id, Some(FOO_OAUTH2_LINK_1.clone()))
+        .with_delete_link(FOO_OAUTH2_LINK_1.id, true);
+
+    let oauth2_token = MockOAuth2TokenService::new()
+        .with_get(FOO_OAUTH2_LINK_1.id, Some(FOO_OAUTH2_TOKENS.clone()))
+        .with_revoke(
+            TEST_OAUTH2_PROVIDER_ID.clone(),
+            FOO_OAUTH2_TOKENS.clone(),
+            Err(anyhow::anyhow!("provider unavailable")),
+        );
+
     let user_repo = MockUserRepository::new().with_get_composite(
         FOO.user.id,
         Some(FOO.clone().with(|u| u.details.oauth2_login = false)),
     );
 
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(FOO.user.id),
+        FOO.user.id,
+        AuditEventKind::OAuth2LinkDeleted,
+        ClientInfo::default(),
+    );
+
     let sut = OAuth2FeatureServiceImpl {
         db,
         auth,
         oauth2_repo,
+        oauth2_token,
         user_repo,
+        audit_log,
         ..Sut::default()
     };
 
     // Act
     let result = sut
-        .delete_link(&"token".into(), FOO.user.id.into(), FOO_OAUTH2_LINK_1.id)
+        .delete_link(
+            &"token".into(),
+            FOO.user.id.into(),
+            FOO_OAUTH2_LINK_1.id,
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -58,7 +141,12 @@ async fn unauthenticated() {
 
     // Act
     let result = sut
-        .delete_link(&"token".into(), FOO.user.id.into(), FOO_OAUTH2_LINK_1.id)
+        .delete_link(
+            &"token".into(),
+            FOO.user.id.into(),
+            FOO_OAUTH2_LINK_1.id,
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -82,14 +170,19 @@ async fn unauthorized() {
 
     // Act
     let result = sut
-        .delete_link(&"token".into(), FOO.user.id.into(), FOO_OAUTH2_LINK_1.id)
+        .delete_link(
+            &"token".into(),
+            FOO.user.id.into(),
+            FOO_OAUTH2_LINK_1.id,
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
     assert_matches!(
         result,
         Err(OAuth2DeleteLinkError::Auth(AuthError::Authorize(
-            AuthorizeError::Admin
+            AuthorizeError::Permission
         )))
     );
 }
@@ -113,7 +206,12 @@ async fn not_found() {
 
     // Act
     let result = sut
-        .delete_link(&"token".into(), FOO.user.id.into(), FOO_OAUTH2_LINK_1.id)
+        .delete_link(
+            &"token".into(),
+            FOO.user.id.into(),
+            FOO_OAUTH2_LINK_1.id,
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -140,7 +238,12 @@ async fn user_id_mismatch() {
 
     // Act
     let result = sut
-        .delete_link(&"token".into(), BAR.user.id.into(), FOO_OAUTH2_LINK_1.id)
+        .delete_link(
+            &"token".into(),
+            BAR.user.id.into(),
+            FOO_OAUTH2_LINK_1.id,
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -158,6 +261,9 @@ async fn last_login_method() {
         .with_get_link(FOO_OAUTH2_LINK_1.id, Some(FOO_OAUTH2_LINK_1.clone()))
         .with_delete_link(FOO_OAUTH2_LINK_1.id, true);
 
+    let oauth2_token = MockOAuth2TokenService::new()
+        .with_get(FOO_OAUTH2_LINK_1.id, Some(FOO_OAUTH2_TOKENS.clone()));
+
     let user_repo = MockUserRepository::new().with_get_composite(
         FOO.user.id,
         Some(FOO.clone().with(|u| {
@@ -170,13 +276,19 @@ async fn last_login_method() {
         db,
         auth,
         oauth2_repo,
+        oauth2_token,
         user_repo,
         ..Sut::default()
     };
 
     // Act
     let result = sut
-        .delete_link(&"token".into(), FOO.user.id.into(), FOO_OAUTH2_LINK_1.id)
+        .delete_link(
+            &"token".into(),
+            FOO.user.id.into(),
+            FOO_OAUTH2_LINK_1.id,
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -50,7 +50,7 @@ async fn ok() {
     let result = sut.list_links(&"token".into(), FOO.user.id.into()).await;
 
     // Assert
-    assert_eq!(result.unwrap(), [FOO_OAUTH2_LINK_1.clone()]);
+    assert_eq!(result.unwrap(), std::slice::from_ref(&*FOO_OAUTH2_LINK_1));
 }
 
 #[tokio::test]
@@ -92,7 +92,7 @@ async fn unauthorized() {
     assert_matches!(
         result,
         Err(OAuth2ListLinksError::Auth(AuthError::Authorize(
-            AuthorizeError::Admin
+            AuthorizeError::Permission
         )))
     );
 }
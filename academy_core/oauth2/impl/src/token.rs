@@ -0,0 +1,483 @@
+use std::time::Duration;
+
+use academy_core_oauth2_contracts::token::OAuth2TokenService;
+use academy_di::Build;
+use academy_extern_contracts::oauth2::{OAuth2RefreshTokenError, OAuth2TokenApiService};
+use academy_models::oauth2::{
+    OAuth2AccessToken, OAuth2EncryptedTokens, OAuth2Link, OAuth2LinkId, OAuth2ProviderId,
+    OAuth2Tokens,
+};
+use academy_persistence_contracts::oauth2::OAuth2Repository;
+use academy_shared_contracts::{encryption::EncryptionService, time::TimeService};
+use academy_utils::trace_instrument;
+use anyhow::Context;
+
+use crate::OAuth2FeatureConfig;
+
+/// Access tokens which expire within this period are refreshed before they
+/// are returned, so they remain valid long enough to be used.
+const EXPIRATION_LEEWAY: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Build)]
+#[cfg_attr(test, derive(Default))]
+pub struct OAuth2TokenServiceImpl<Time, Encryption, OAuth2TokenApi, OAuth2Repo> {
+    time: Time,
+    encryption: Encryption,
+    oauth2_token_api: OAuth2TokenApi,
+    oauth2_repo: OAuth2Repo,
+    config: OAuth2FeatureConfig,
+}
+
+impl<Txn, Time, Encryption, OAuth2TokenApi, OAuth2Repo> OAuth2TokenService<Txn>
+    for OAuth2TokenServiceImpl<Time, Encryption, OAuth2TokenApi, OAuth2Repo>
+where
+    Txn: Send + Sync + 'static,
+    Time: TimeService,
+    Encryption: EncryptionService,
+    OAuth2TokenApi: OAuth2TokenApiService,
+    OAuth2Repo: OAuth2Repository<Txn>,
+{
+    #[trace_instrument(skip(self, txn))]
+    async fn save(
+        &self,
+        txn: &mut Txn,
+        link_id: OAuth2LinkId,
+        tokens: &OAuth2Tokens,
+    ) -> anyhow::Result<()> {
+        let encrypted = OAuth2EncryptedTokens {
+            access_token: self
+                .encryption
+                .encrypt(
+                    tokens.access_token.as_bytes(),
+                    access_token_associated_data(link_id).as_bytes(),
+                )
+                .context("Failed to encrypt access token")?,
+            refresh_token: tokens
+                .refresh_token
+                .as_ref()
+                .map(|refresh_token| {
+                    self.encryption.encrypt(
+                        refresh_token.as_bytes(),
+                        refresh_token_associated_data(link_id).as_bytes(),
+                    )
+                })
+                .transpose()
+                .context("Failed to encrypt refresh token")?,
+            expires_at: tokens.expires_at,
+        };
+
+        self.oauth2_repo
+            .save_link_tokens(txn, link_id, &encrypted)
+            .await
+            .context("Failed to save OAuth2 tokens in database")
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn get(
+        &self,
+        txn: &mut Txn,
+        link_id: OAuth2LinkId,
+    ) -> anyhow::Result<Option<OAuth2Tokens>> {
+        let Some(encrypted) = self
+            .oauth2_repo
+            .get_link_tokens(txn, link_id)
+            .await
+            .context("Failed to get OAuth2 tokens from database")?
+        else {
+            return Ok(None);
+        };
+
+        let decrypt = |ciphertext: &[u8], associated_data: String| {
+            self.encryption
+                .decrypt(ciphertext, associated_data.as_bytes())
+                .and_then(|plaintext| String::from_utf8(plaintext).map_err(Into::into))
+        };
+
+        Ok(Some(OAuth2Tokens {
+            access_token: decrypt(
+                &encrypted.access_token,
+                access_token_associated_data(link_id),
+            )
+            .context("Failed to decrypt access token")?
+            .into(),
+            refresh_token: encrypted
+                .refresh_token
+                .as_deref()
+                .map(|ciphertext| decrypt(ciphertext, refresh_token_associated_data(link_id)))
+                .transpose()
+                .context("Failed to decrypt refresh token")?
+                .map(Into::into),
+            expires_at: encrypted.expires_at,
+        }))
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn get_access_token(
+        &self,
+        txn: &mut Txn,
+        link: &OAuth2Link,
+    ) -> anyhow::Result<Option<OAuth2AccessToken>> {
+        let Some(tokens) = self.get(txn, link.id).await? else {
+            return Ok(None);
+        };
+
+        let expired = tokens
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= self.time.now() + EXPIRATION_LEEWAY);
+        if !expired {
+            return Ok(Some(tokens.access_token));
+        }
+
+        let (Some(provider), Some(refresh_token)) = (
+            self.config.providers.get(&link.provider_id),
+            &tokens.refresh_token,
+        ) else {
+            return Ok(None);
+        };
+
+        let tokens = match self.oauth2_token_api.refresh(provider, refresh_token).await {
+            Ok(tokens) => tokens,
+            // the user has to link the remote account again
+            Err(OAuth2RefreshTokenError::InvalidToken) => return Ok(None),
+            Err(OAuth2RefreshTokenError::Other(err)) => {
+                return Err(err.context("Failed to refresh OAuth2 access token"))
+            }
+        };
+
+        self.save(txn, link.id, &tokens).await?;
+
+        Ok(Some(tokens.access_token))
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn revoke(
+        &self,
+        provider_id: &OAuth2ProviderId,
+        tokens: &OAuth2Tokens,
+    ) -> anyhow::Result<()> {
+        let Some(provider) = self.config.providers.get(provider_id) else {
+            return Ok(());
+        };
+
+        self.oauth2_token_api
+            .revoke(provider, tokens)
+            .await
+            .context("Failed to revoke OAuth2 tokens")
+    }
+}
+
+/// Bind the encrypted access token to its link, so it cannot be moved to a
+/// different link or swapped with the refresh token.
+fn access_token_associated_data(link_id: OAuth2LinkId) -> String {
+    format!("oauth2_access_token:{}", link_id.hyphenated())
+}
+
+fn refresh_token_associated_data(link_id: OAuth2LinkId) -> String {
+    format!("oauth2_refresh_token:{}", link_id.hyphenated())
+}
+
+#[cfg(test)]
+mod tests {
+    use academy_demo::oauth2::{
+        FOO_OAUTH2_LINK_1, FOO_OAUTH2_TOKENS, TEST_OAUTH2_PROVIDER, TEST_OAUTH2_PROVIDER_ID,
+    };
+    use academy_extern_contracts::oauth2::MockOAuth2TokenApiService;
+    use academy_persistence_contracts::oauth2::MockOAuth2Repository;
+    use academy_shared_contracts::{encryption::MockEncryptionService, time::MockTimeService};
+
+    use super::*;
+
+    type Sut = OAuth2TokenServiceImpl<
+        MockTimeService,
+        MockEncryptionService,
+        MockOAuth2TokenApiService,
+        MockOAuth2Repository<()>,
+    >;
+
+    #[tokio::test]
+    async fn save() {
+        // Arrange
+        let tokens = FOO_OAUTH2_TOKENS.clone();
+
+        let encryption = MockEncryptionService::new()
+            .with_encrypt(
+                access_token().into(),
+                access_token_aad(),
+                encrypted_access_token(),
+            )
+            .with_encrypt(
+                refresh_token().into(),
+                refresh_token_aad(),
+                encrypted_refresh_token(),
+            );
+
+        let oauth2_repo = MockOAuth2Repository::new()
+            .with_save_link_tokens(FOO_OAUTH2_LINK_1.id, encrypted_tokens());
+
+        let sut = OAuth2TokenServiceImpl {
+            encryption,
+            oauth2_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.save(&mut (), FOO_OAUTH2_LINK_1.id, &tokens).await;
+
+        // Assert
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn get_some() {
+        // Arrange
+        let oauth2_repo = MockOAuth2Repository::new()
+            .with_get_link_tokens(FOO_OAUTH2_LINK_1.id, Some(encrypted_tokens()));
+
+        let encryption = MockEncryptionService::new()
+            .with_decrypt(
+                encrypted_access_token(),
+                access_token_aad(),
+                access_token().into(),
+            )
+            .with_decrypt(
+                encrypted_refresh_token(),
+                refresh_token_aad(),
+                refresh_token().into(),
+            );
+
+        let sut = OAuth2TokenServiceImpl {
+            encryption,
+            oauth2_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.get(&mut (), FOO_OAUTH2_LINK_1.id).await;
+
+        // Assert
+        assert_eq!(result.unwrap().unwrap(), *FOO_OAUTH2_TOKENS);
+    }
+
+    #[tokio::test]
+    async fn get_none() {
+        // Arrange
+        let oauth2_repo =
+            MockOAuth2Repository::new().with_get_link_tokens(FOO_OAUTH2_LINK_1.id, None);
+
+        let sut = OAuth2TokenServiceImpl {
+            oauth2_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.get(&mut (), FOO_OAUTH2_LINK_1.id).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn get_access_token_valid() {
+        // Arrange
+        let expires_at = FOO_OAUTH2_TOKENS.expires_at.unwrap();
+
+        let time = MockTimeService::new().with_now(expires_at - Duration::from_secs(120));
+
+        let sut = OAuth2TokenServiceImpl {
+            time,
+            ..make_get_sut()
+        };
+
+        // Act
+        let result = sut.get_access_token(&mut (), &FOO_OAUTH2_LINK_1).await;
+
+        // Assert
+        assert_eq!(result.unwrap().unwrap(), FOO_OAUTH2_TOKENS.access_token);
+    }
+
+    #[tokio::test]
+    async fn get_access_token_expired() {
+        // Arrange
+        let expires_at = FOO_OAUTH2_TOKENS.expires_at.unwrap();
+        let refreshed = OAuth2Tokens {
+            access_token: "N5ZuTf3cKq8LbXw1Hs7VyRjD0mGaEe2P".into(),
+            refresh_token: Some("Wq4Rz8JvXn1TbKs6YdMh3FgLc0PaUe9N".into()),
+            expires_at: Some(expires_at + Duration::from_secs(3600)),
+        };
+
+        let time = MockTimeService::new().with_now(expires_at - Duration::from_secs(30));
+
+        let oauth2_token_api = MockOAuth2TokenApiService::new().with_refresh(
+            TEST_OAUTH2_PROVIDER.clone(),
+            refresh_token().into(),
+            Ok(refreshed.clone()),
+        );
+
+        let get_sut = make_get_sut();
+        let encryption = get_sut
+            .encryption
+            .with_encrypt(
+                refreshed.access_token.as_bytes().into(),
+                access_token_aad(),
+                b"new encrypted access token".into(),
+            )
+            .with_encrypt(
+                refreshed.refresh_token.as_ref().unwrap().as_bytes().into(),
+                refresh_token_aad(),
+                b"new encrypted refresh token".into(),
+            );
+        let oauth2_repo = get_sut.oauth2_repo.with_save_link_tokens(
+            FOO_OAUTH2_LINK_1.id,
+            OAuth2EncryptedTokens {
+                access_token: b"new encrypted access token".into(),
+                refresh_token: Some(b"new encrypted refresh token".into()),
+                expires_at: refreshed.expires_at,
+            },
+        );
+
+        let sut = OAuth2TokenServiceImpl {
+            time,
+            encryption,
+            oauth2_token_api,
+            oauth2_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.get_access_token(&mut (), &FOO_OAUTH2_LINK_1).await;
+
+        // Assert
+        assert_eq!(result.unwrap().unwrap(), refreshed.access_token);
+    }
+
+    #[tokio::test]
+    async fn get_access_token_refresh_token_revoked() {
+        // Arrange
+        let expires_at = FOO_OAUTH2_TOKENS.expires_at.unwrap();
+
+        let time = MockTimeService::new().with_now(expires_at);
+
+        let oauth2_token_api = MockOAuth2TokenApiService::new().with_refresh(
+            TEST_OAUTH2_PROVIDER.clone(),
+            refresh_token().into(),
+            Err(OAuth2RefreshTokenError::InvalidToken),
+        );
+
+        let sut = OAuth2TokenServiceImpl {
+            time,
+            oauth2_token_api,
+            ..make_get_sut()
+        };
+
+        // Act
+        let result = sut.get_access_token(&mut (), &FOO_OAUTH2_LINK_1).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn get_access_token_no_tokens() {
+        // Arrange
+        let oauth2_repo =
+            MockOAuth2Repository::new().with_get_link_tokens(FOO_OAUTH2_LINK_1.id, None);
+
+        let sut = OAuth2TokenServiceImpl {
+            oauth2_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.get_access_token(&mut (), &FOO_OAUTH2_LINK_1).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn revoke() {
+        // Arrange
+        let oauth2_token_api = MockOAuth2TokenApiService::new()
+            .with_revoke(TEST_OAUTH2_PROVIDER.clone(), FOO_OAUTH2_TOKENS.clone());
+
+        let sut = OAuth2TokenServiceImpl {
+            oauth2_token_api,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut
+            .revoke(&TEST_OAUTH2_PROVIDER_ID, &FOO_OAUTH2_TOKENS)
+            .await;
+
+        // Assert
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn revoke_unknown_provider() {
+        // Arrange
+        let sut = Sut::default();
+
+        // Act
+        let result = sut.revoke(&"unknown".into(), &FOO_OAUTH2_TOKENS).await;
+
+        // Assert
+        result.unwrap();
+    }
+
+    fn make_get_sut() -> Sut {
+        let oauth2_repo = MockOAuth2Repository::new()
+            .with_get_link_tokens(FOO_OAUTH2_LINK_1.id, Some(encrypted_tokens()));
+
+        let encryption = MockEncryptionService::new()
+            .with_decrypt(
+                encrypted_access_token(),
+                access_token_aad(),
+                access_token().into(),
+            )
+            .with_decrypt(
+                encrypted_refresh_token(),
+                refresh_token_aad(),
+                refresh_token().into(),
+            );
+
+        OAuth2TokenServiceImpl {
+            encryption,
+            oauth2_repo,
+            ..Sut::default()
+        }
+    }
+
+    fn access_token() -> &'static str {
+        FOO_OAUTH2_TOKENS.access_token.as_str()
+    }
+
+    fn refresh_token() -> &'static str {
+        FOO_OAUTH2_TOKENS.refresh_token.as_ref().unwrap().as_str()
+    }
+
+    fn access_token_aad() -> Vec<u8> {
+        format!("oauth2_access_token:{}", FOO_OAUTH2_LINK_1.id.hyphenated()).into()
+    }
+
+    fn refresh_token_aad() -> Vec<u8> {
+        format!("oauth2_refresh_token:{}", FOO_OAUTH2_LINK_1.id.hyphenated()).into()
+    }
+
+    fn encrypted_access_token() -> Vec<u8> {
+        b"encrypted access token".into()
+    }
+
+    fn encrypted_refresh_token() -> Vec<u8> {
+        b"encrypted refresh token".into()
+    }
+
+    fn encrypted_tokens() -> OAuth2EncryptedTokens {
+        OAuth2EncryptedTokens {
+            access_token: encrypted_access_token(),
+            refresh_token: Some(encrypted_refresh_token()),
+            expires_at: FOO_OAUTH2_TOKENS.expires_at,
+        }
+    }
+}
@@ -1,15 +1,20 @@
 use std::future::Future;
 
 use academy_models::{
+    audit::ClientInfo,
     auth::{AccessToken, AuthError, Login, RefreshToken},
+    email_address::EmailAddress,
     mfa::MfaAuthentication,
+    pagination::{Pagination, PaginationCursor},
     session::{DeviceName, Session, SessionId},
     user::{UserId, UserIdOrSelf, UserNameOrEmailAddress, UserPassword},
-    RecaptchaResponse,
+    RecaptchaResponse, VerificationCode,
 };
+use chrono::{DateTime, Utc};
 use thiserror::Error;
 
 pub mod failed_auth_count;
+pub mod login_link;
 pub mod session;
 
 pub trait SessionFeatureService: Send + Sync + 'static {
@@ -19,45 +24,80 @@ pub trait SessionFeatureService: Send + Sync + 'static {
         token: &AccessToken,
     ) -> impl Future<Output = Result<Session, SessionGetCurrentError>> + Send;
 
-    /// Return all sessions of the given user.
+    /// Return a page of the sessions of the given user, ordered by their
+    /// creation time.
     ///
-    /// Requires admin privileges if not used on the authenticated user.
+    /// Requires the `user_read` permission if not used on the authenticated
+    /// user.
     fn list_by_user(
         &self,
         token: &AccessToken,
         user_id: UserIdOrSelf,
-    ) -> impl Future<Output = Result<Vec<Session>, SessionListByUserError>> + Send;
+        pagination: Pagination,
+    ) -> impl Future<Output = Result<SessionListResult, SessionListByUserError>> + Send;
 
     /// Create a new session by authenticating via username/password and MFA (if
     /// enabled).
+    ///
+    /// Failed attempts are counted per account and per client IP address.
+    /// Exceeding the configured limit temporarily locks out further attempts.
     fn create_session(
         &self,
         cmd: SessionCreateCommand,
         recaptcha_response: Option<RecaptchaResponse>,
+        client: ClientInfo,
     ) -> impl Future<Output = Result<Login, SessionCreateError>> + Send;
 
+    /// Send a single-use login link to the given email address, if it belongs
+    /// to a user.
+    fn request_login_link(
+        &self,
+        email: EmailAddress,
+        recaptcha_response: Option<RecaptchaResponse>,
+    ) -> impl Future<Output = Result<(), SessionRequestLoginLinkError>> + Send;
+
+    /// Create a new session by redeeming a login link and authenticating via
+    /// MFA (if enabled).
+    fn create_session_by_login_link(
+        &self,
+        cmd: SessionCreateByLoginLinkCommand,
+        recaptcha_response: Option<RecaptchaResponse>,
+        client: ClientInfo,
+    ) -> impl Future<Output = Result<Login, SessionCreateByLoginLinkError>> + Send;
+
     /// Impersonate a user by creating a new session for them.
     ///
-    /// Requires admin privileges.
+    /// The session records the impersonating administrator and expires after
+    /// a short time. Sensitive actions (e.g. changing the password or deleting
+    /// the account) are not allowed in this session.
+    ///
+    /// Requires the `user_impersonate` permission (and cannot be used while
+    /// impersonating another user).
     fn impersonate(
         &self,
         token: &AccessToken,
         user_id: UserId,
+        client: ClientInfo,
     ) -> impl Future<Output = Result<Login, SessionImpersonateError>> + Send;
 
     /// Refresh a session using a refresh token.
     ///
     /// This will generate a new access and refresh token pair and invalidate
     /// the previous one.
+    ///
+    /// If a previously used refresh token is presented again, the whole session
+    /// is revoked, as the token has most likely been stolen.
     fn refresh_session(
         &self,
         refresh_token: &RefreshToken,
+        client: ClientInfo,
     ) -> impl Future<Output = Result<Login, SessionRefreshError>> + Send;
 
     /// Delete the given session and invalidate the access and refresh tokens
     /// associated with it.
     ///
-    /// Requires admin privileges if not used on the authenticated user.
+    /// Requires the `user_write` permission if not used on the authenticated
+    /// user.
     fn delete_session(
         &self,
         token: &AccessToken,
@@ -75,7 +115,8 @@ pub trait SessionFeatureService: Send + Sync + 'static {
     /// Delete all sessions of the given user and invalidate all access and
     /// refresh tokens associated with them.
     ///
-    /// Requires admin privileges if not used on the authenticated user.
+    /// Requires the `user_write` permission if not used on the authenticated
+    /// user.
     fn delete_by_user(
         &self,
         token: &AccessToken,
@@ -99,6 +140,13 @@ pub enum SessionGetCurrentError {
     Other(#[from] anyhow::Error),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionListResult {
+    pub sessions: Vec<Session>,
+    /// The cursor of the next page (`None` if this is the last page)
+    pub next_cursor: Option<PaginationCursor>,
+}
+
 #[derive(Debug, Error)]
 pub enum SessionListByUserError {
     #[error(transparent)]
@@ -113,7 +161,38 @@ pub enum SessionCreateError {
     InvalidCredentials,
     #[error("The user has mfa enabled but no valid authentication was provided.")]
     MfaFailed,
-    #[error("The user account has been disabled.")]
+    #[error("The user account has been disabled or scheduled for deletion.")]
+    UserDisabled,
+    #[error("Invalid recaptcha response")]
+    Recaptcha,
+    #[error("Too many failed login attempts. Try again after {until}.")]
+    RateLimit { until: DateTime<Utc> },
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionCreateByLoginLinkCommand {
+    pub code: VerificationCode,
+    pub mfa: MfaAuthentication,
+    pub device_name: Option<DeviceName>,
+}
+
+#[derive(Debug, Error)]
+pub enum SessionRequestLoginLinkError {
+    #[error("Invalid recaptcha response")]
+    Recaptcha,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum SessionCreateByLoginLinkError {
+    #[error("The login link is invalid or has expired.")]
+    InvalidCode,
+    #[error("The user has mfa enabled but no valid authentication was provided.")]
+    MfaFailed,
+    #[error("The user account has been disabled or scheduled for deletion.")]
     UserDisabled,
     #[error("Invalid recaptcha response")]
     Recaptcha,
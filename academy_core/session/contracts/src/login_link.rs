@@ -0,0 +1,59 @@
+use std::future::Future;
+
+use academy_models::{email_address::EmailAddressWithName, user::UserId, VerificationCode};
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait SessionLoginLinkService: Send + Sync + 'static {
+    /// Send an email containing a single-use login link to the given user.
+    fn request(
+        &self,
+        user_id: UserId,
+        email: EmailAddressWithName,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Return the id of the user the given login link has been sent to, if
+    /// the link is valid and has not yet expired. The link remains valid.
+    fn get(
+        &self,
+        code: &VerificationCode,
+    ) -> impl Future<Output = anyhow::Result<Option<UserId>>> + Send;
+
+    /// Invalidate the given login link and return the id of the user it has
+    /// been sent to, if the link was valid and had not yet expired.
+    ///
+    /// Each login link can only be consumed once, even by concurrent requests.
+    fn consume(
+        &self,
+        code: &VerificationCode,
+    ) -> impl Future<Output = anyhow::Result<Option<UserId>>> + Send;
+}
+
+#[cfg(feature = "mock")]
+impl MockSessionLoginLinkService {
+    pub fn with_request(mut self, user_id: UserId, email: EmailAddressWithName) -> Self {
+        self.expect_request()
+            .once()
+            .with(
+                mockall::predicate::eq(user_id),
+                mockall::predicate::eq(email),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
+
+    pub fn with_get(mut self, code: VerificationCode, result: Option<UserId>) -> Self {
+        self.expect_get()
+            .once()
+            .with(mockall::predicate::eq(code))
+            .return_once(move |_| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_consume(mut self, code: VerificationCode, result: Option<UserId>) -> Self {
+        self.expect_consume()
+            .once()
+            .with(mockall::predicate::eq(code))
+            .return_once(move |_| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+}
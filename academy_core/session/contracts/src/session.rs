@@ -1,6 +1,7 @@
 use std::future::Future;
 
 use academy_models::{
+    audit::ClientInfo,
     auth::Login,
     session::{DeviceName, SessionId},
     user::{UserComposite, UserId},
@@ -18,8 +19,38 @@ pub trait SessionService<Txn: Send + Sync + 'static>: Send + Sync + 'static {
         update_last_login: bool,
     ) -> impl Future<Output = anyhow::Result<Login>> + Send;
 
+    /// Remember the device the given session has been created on and notify
+    /// the user via email about logins from unknown devices.
+    ///
+    /// Devices are identified by the user agent and the network prefix of the
+    /// client's IP address. If `notify` is `false` (e.g. directly after
+    /// registration), the device is only remembered.
+    ///
+    /// Must only be called after the session has been committed to the
+    /// database. Failures are logged instead of returned.
+    fn remember_device(
+        &self,
+        login: &Login,
+        client: &ClientInfo,
+        notify: bool,
+    ) -> impl Future<Output = ()> + Send;
+
+    /// Create a new session for the given user on behalf of the given
+    /// administrator.
+    ///
+    /// The session records the impersonator, has a limited lifetime and does
+    /// not update the user's last login timestamp.
+    fn impersonate(
+        &self,
+        txn: &mut Txn,
+        user_composite: UserComposite,
+        impersonator_id: UserId,
+    ) -> impl Future<Output = anyhow::Result<Login>> + Send;
+
     /// Refresh the given session by invalidating the current access/refresh
     /// token pair and generating a new one.
+    ///
+    /// The old refresh token is retired, so that its reuse can be detected.
     fn refresh(
         &self,
         txn: &mut Txn,
@@ -72,6 +103,35 @@ impl<Txn: Send + Sync + 'static> MockSessionService<Txn> {
         self
     }
 
+    pub fn with_remember_device(mut self, login: Login, client: ClientInfo, notify: bool) -> Self {
+        self.expect_remember_device()
+            .once()
+            .with(
+                mockall::predicate::eq(login),
+                mockall::predicate::eq(client),
+                mockall::predicate::eq(notify),
+            )
+            .return_once(|_, _, _| Box::pin(std::future::ready(())));
+        self
+    }
+
+    pub fn with_impersonate(
+        mut self,
+        user_composite: UserComposite,
+        impersonator_id: UserId,
+        result: Login,
+    ) -> Self {
+        self.expect_impersonate()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(user_composite),
+                mockall::predicate::eq(impersonator_id),
+            )
+            .return_once(|_, _, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
     pub fn with_refresh(
         mut self,
         session_id: SessionId,
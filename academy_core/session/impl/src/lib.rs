@@ -1,92 +1,135 @@
+use std::{sync::Arc, time::Duration};
+
 use academy_auth_contracts::{
     AuthResultExt, AuthService, AuthenticateByPasswordError, AuthenticateByRefreshTokenError,
 };
+use academy_core_audit_contracts::log::AuditLogService;
 use academy_core_mfa_contracts::authenticate::{
     MfaAuthenticateError, MfaAuthenticateResult, MfaAuthenticateService,
 };
 use academy_core_session_contracts::{
-    failed_auth_count::SessionFailedAuthCountService, session::SessionService,
+    failed_auth_count::SessionFailedAuthCountService, login_link::SessionLoginLinkService,
+    session::SessionService, SessionCreateByLoginLinkCommand, SessionCreateByLoginLinkError,
     SessionCreateCommand, SessionCreateError, SessionDeleteByUserError, SessionDeleteCurrentError,
     SessionDeleteError, SessionFeatureService, SessionGetCurrentError, SessionImpersonateError,
-    SessionListByUserError, SessionRefreshError,
+    SessionListByUserError, SessionListResult, SessionRefreshError, SessionRequestLoginLinkError,
 };
 use academy_di::Build;
+use academy_email_contracts::template::TemplateEmailService;
 use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
     auth::{AccessToken, Login, RefreshToken},
-    session::{Session, SessionId},
-    user::{UserId, UserIdOrSelf, UserNameOrEmailAddress},
+    email_address::EmailAddress,
+    pagination::Pagination,
+    role::Permission,
+    session::{DeviceName, Session, SessionId},
+    user::{User, UserId, UserIdOrSelf, UserNameOrEmailAddress},
     RecaptchaResponse,
 };
 use academy_persistence_contracts::{
     session::SessionRepository, user::UserRepository, Database, Transaction,
 };
-use academy_shared_contracts::captcha::{CaptchaCheckError, CaptchaService};
+use academy_shared_contracts::{
+    captcha::{CaptchaCheckError, CaptchaService},
+    throttle::{ThrottleKey, ThrottleService},
+};
+use academy_templates_contracts::SessionRevokedTemplate;
 use academy_utils::trace_instrument;
 use anyhow::{anyhow, Context};
+use tracing::trace;
 
 pub mod failed_auth_count;
+pub mod login_link;
 pub mod session;
 
 #[cfg(test)]
 mod tests;
 
+/// Action of the [`ThrottleKey`]s used to limit failed login attempts
+const LOGIN_THROTTLE_ACTION: &str = "login";
+
 #[derive(Debug, Clone, Build)]
 #[cfg_attr(test, derive(Default))]
 pub struct SessionFeatureServiceImpl<
     Db,
     Auth,
     Captcha,
+    Throttle,
     Session,
     SessionFailedAuthCount,
+    SessionLoginLink,
     MfaAuthenticate,
     UserRepo,
     SessionRepo,
+    AuditLog,
+    TemplateEmail,
 > {
     db: Db,
     auth: Auth,
     captcha: Captcha,
+    throttle: Throttle,
     session: Session,
     session_failed_auth_count: SessionFailedAuthCount,
+    session_login_link: SessionLoginLink,
     mfa_authenticate: MfaAuthenticate,
     user_repo: UserRepo,
     session_repo: SessionRepo,
+    audit_log: AuditLog,
+    template_email: TemplateEmail,
     config: SessionFeatureConfig,
 }
 
 #[derive(Debug, Clone)]
 pub struct SessionFeatureConfig {
     pub login_fails_before_captcha: u64,
+    pub login_link_code_ttl: Duration,
+    pub login_link_redirect_url: Arc<String>,
+    pub refresh_token_history: usize,
+    pub refresh_token_reuse_email: bool,
+    pub known_device_ttl: Duration,
 }
 
 impl<
         Db,
         Auth,
         Captcha,
+        Throttle,
         SessionS,
         SessionFailedAuthCount,
+        SessionLoginLink,
         MfaAuthenticate,
         UserRepo,
         SessionRepo,
+        AuditLog,
+        TemplateEmail,
     > SessionFeatureService
     for SessionFeatureServiceImpl<
         Db,
         Auth,
         Captcha,
+        Throttle,
         SessionS,
         SessionFailedAuthCount,
+        SessionLoginLink,
         MfaAuthenticate,
         UserRepo,
         SessionRepo,
+        AuditLog,
+        TemplateEmail,
     >
 where
     Db: Database,
     Auth: AuthService<Db::Transaction>,
     Captcha: CaptchaService,
+    Throttle: ThrottleService,
     SessionS: SessionService<Db::Transaction>,
     SessionFailedAuthCount: SessionFailedAuthCountService,
+    SessionLoginLink: SessionLoginLinkService,
     MfaAuthenticate: MfaAuthenticateService<Db::Transaction>,
     UserRepo: UserRepository<Db::Transaction>,
     SessionRepo: SessionRepository<Db::Transaction>,
+    AuditLog: AuditLogService<Db::Transaction>,
+    TemplateEmail: TemplateEmailService,
 {
     #[trace_instrument(skip(self))]
     async fn get_current_session(
@@ -108,18 +151,27 @@ where
         &self,
         token: &AccessToken,
         user_id: UserIdOrSelf,
-    ) -> Result<Vec<Session>, SessionListByUserError> {
+        pagination: Pagination,
+    ) -> Result<SessionListResult, SessionListByUserError> {
         let auth = self.auth.authenticate(token).await.map_auth_err()?;
         let user_id = user_id.unwrap_or(auth.user_id);
-        auth.ensure_self_or_admin(user_id).map_auth_err()?;
+        auth.ensure_self_or_permission(user_id, Permission::UserRead)
+            .map_auth_err()?;
 
         let mut txn = self.db.begin_transaction().await?;
 
-        self.session_repo
-            .list_by_user(&mut txn, user_id)
+        let sessions = self
+            .session_repo
+            .list_by_user_paginated(&mut txn, user_id, pagination)
             .await
-            .context("Failed to get sessions from database")
-            .map_err(Into::into)
+            .context("Failed to get sessions from database")?;
+
+        let next_cursor = pagination.next_cursor(&sessions);
+
+        Ok(SessionListResult {
+            sessions,
+            next_cursor,
+        })
     }
 
     #[trace_instrument(skip(self))]
@@ -127,7 +179,18 @@ where
         &self,
         cmd: SessionCreateCommand,
         recaptcha_response: Option<RecaptchaResponse>,
+        client: ClientInfo,
     ) -> Result<Login, SessionCreateError> {
+        let throttle_keys = login_throttle_keys([cmd.name_or_email.as_str()], &client);
+        if let Some(until) = self
+            .throttle
+            .check(&throttle_keys)
+            .await
+            .context("Failed to check login throttle")?
+        {
+            return Err(SessionCreateError::RateLimit { until });
+        }
+
         let failed_login_attempts = self
             .session_failed_auth_count
             .get(&cmd.name_or_email)
@@ -158,24 +221,12 @@ where
                     .increment(&cmd.name_or_email)
                     .await
                     .context("Failed to increment failed auth count")?;
-                return Err(SessionCreateError::InvalidCredentials);
-            }
-        };
-
-        let increment_failed_login_attempts = || async {
-            self.session_failed_auth_count
-                .increment(&UserNameOrEmailAddress::Name(
-                    user_composite.user.name.clone(),
-                ))
-                .await
-                .context("Failed to increment failed auth count for name")?;
-            if let Some(email) = user_composite.user.email.clone() {
-                self.session_failed_auth_count
-                    .increment(&UserNameOrEmailAddress::Email(email))
+                self.throttle
+                    .record(&throttle_keys)
                     .await
-                    .context("Failed to increment failed auth count for email")?;
+                    .context("Failed to record failed login attempt")?;
+                return Err(SessionCreateError::InvalidCredentials);
             }
-            anyhow::Ok(())
         };
 
         match self
@@ -185,7 +236,10 @@ where
         {
             Ok(()) => {}
             Err(AuthenticateByPasswordError::InvalidCredentials) => {
-                increment_failed_login_attempts().await?;
+                self.increment_failed_auth_count(&user_composite.user)
+                    .await?;
+                self.record_failed_login(&user_composite.user, &client)
+                    .await?;
                 return Err(SessionCreateError::InvalidCredentials);
             }
             Err(AuthenticateByPasswordError::Other(err)) => {
@@ -204,7 +258,10 @@ where
                 Ok(MfaAuthenticateResult::Ok | MfaAuthenticateResult::Disabled) => (),
                 Ok(MfaAuthenticateResult::Reset) => user_composite.details.mfa_enabled = false,
                 Err(MfaAuthenticateError::Failed) => {
-                    increment_failed_login_attempts().await?;
+                    self.increment_failed_auth_count(&user_composite.user)
+                        .await?;
+                    self.record_failed_login(&user_composite.user, &client)
+                        .await?;
                     return Err(SessionCreateError::MfaFailed);
                 }
                 Err(MfaAuthenticateError::Other(err)) => {
@@ -213,23 +270,171 @@ where
             }
         }
 
+        self.reset_failed_auth_count(&user_composite.user).await?;
+        for id in user_account_ids(&user_composite.user) {
+            self.throttle
+                .reset(&ThrottleKey::account(LOGIN_THROTTLE_ACTION, id))
+                .await
+                .context("Failed to reset login throttle")?;
+        }
+
+        if !user_composite.user.can_login() {
+            return Err(SessionCreateError::UserDisabled);
+        }
+
+        // don't notify the user about the very first login after registration
+        let notify = user_composite.user.last_login.is_some();
+
+        let login = self
+            .session
+            .create(&mut txn, user_composite, cmd.device_name, true)
+            .await
+            .context("Failed to create session")?;
+
+        txn.commit().await?;
+
+        self.session.remember_device(&login, &client, notify).await;
+
+        Ok(login)
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn request_login_link(
+        &self,
+        email: EmailAddress,
+        recaptcha_response: Option<RecaptchaResponse>,
+    ) -> Result<(), SessionRequestLoginLinkError> {
+        let name_or_email = UserNameOrEmailAddress::Email(email.clone());
+
+        let failed_login_attempts = self
+            .session_failed_auth_count
+            .get(&name_or_email)
+            .await
+            .context("Failed to get failed auth count")?;
+
+        if failed_login_attempts >= self.config.login_fails_before_captcha {
+            self.captcha
+                .check(recaptcha_response.as_deref().map(String::as_str))
+                .await
+                .map_err(|err| match err {
+                    CaptchaCheckError::Failed => SessionRequestLoginLinkError::Recaptcha,
+                    CaptchaCheckError::Other(err) => err.context("Failed to check captcha").into(),
+                })?;
+        }
+
+        // requesting a login link counts as a failed login attempt until the user
+        // successfully logs in, so that sending too many emails requires a captcha
         self.session_failed_auth_count
-            .reset(&UserNameOrEmailAddress::Name(
+            .increment(&name_or_email)
+            .await
+            .context("Failed to increment failed auth count")?;
+
+        let mut txn = self.db.begin_transaction().await?;
+
+        if let Some(user_composite) = self
+            .user_repo
+            .get_composite_by_email(&mut txn, &email)
+            .await
+            .context("Failed to get user from database")?
+        {
+            let email = user_composite.user.email.ok_or_else(|| {
+                anyhow!(
+                    "User {} fetched by email {} has no email address",
+                    user_composite.user.id.hyphenated(),
+                    email.as_str()
+                )
+            })?;
+            self.session_login_link
+                .request(
+                    user_composite.user.id,
+                    email.with_name(user_composite.profile.display_name.into_inner()),
+                )
+                .await
+                .context("Failed to request login link email")?;
+        }
+
+        Ok(())
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn create_session_by_login_link(
+        &self,
+        cmd: SessionCreateByLoginLinkCommand,
+        recaptcha_response: Option<RecaptchaResponse>,
+        client: ClientInfo,
+    ) -> Result<Login, SessionCreateByLoginLinkError> {
+        // the login link is only consumed after captcha and MFA have been
+        // checked, so it can be used again if either of them fails
+        let user_id = self
+            .session_login_link
+            .get(&cmd.code)
+            .await
+            .context("Failed to get login link")?
+            .ok_or(SessionCreateByLoginLinkError::InvalidCode)?;
+
+        let mut txn = self.db.begin_transaction().await?;
+
+        let mut user_composite = self
+            .user_repo
+            .get_composite(&mut txn, user_id)
+            .await
+            .context("Failed to get user from database")?
+            .ok_or(SessionCreateByLoginLinkError::InvalidCode)?;
+
+        let failed_login_attempts = self
+            .session_failed_auth_count
+            .get(&UserNameOrEmailAddress::Name(
                 user_composite.user.name.clone(),
             ))
             .await
-            .context("Failed to reset failed auth count for name")?;
-        if let Some(email) = user_composite.user.email.clone() {
-            self.session_failed_auth_count
-                .reset(&UserNameOrEmailAddress::Email(email))
+            .context("Failed to get failed auth count")?;
+
+        if failed_login_attempts >= self.config.login_fails_before_captcha {
+            self.captcha
+                .check(recaptcha_response.as_deref().map(String::as_str))
                 .await
-                .context("Failed to reset failed auth count for email")?;
+                .map_err(|err| match err {
+                    CaptchaCheckError::Failed => SessionCreateByLoginLinkError::Recaptcha,
+                    CaptchaCheckError::Other(err) => err.context("Failed to check captcha").into(),
+                })?;
         }
 
-        if !user_composite.user.enabled {
-            return Err(SessionCreateError::UserDisabled);
+        if user_composite.details.mfa_enabled {
+            match self
+                .mfa_authenticate
+                .authenticate(&mut txn, user_composite.user.id, cmd.mfa)
+                .await
+            {
+                Ok(MfaAuthenticateResult::Ok | MfaAuthenticateResult::Disabled) => (),
+                Ok(MfaAuthenticateResult::Reset) => user_composite.details.mfa_enabled = false,
+                Err(MfaAuthenticateError::Failed) => {
+                    self.increment_failed_auth_count(&user_composite.user)
+                        .await?;
+                    return Err(SessionCreateByLoginLinkError::MfaFailed);
+                }
+                Err(MfaAuthenticateError::Other(err)) => {
+                    return Err(err.context("Failed to perform MFA").into())
+                }
+            }
         }
 
+        self.reset_failed_auth_count(&user_composite.user).await?;
+
+        if !user_composite.user.can_login() {
+            return Err(SessionCreateByLoginLinkError::UserDisabled);
+        }
+
+        // fails if the login link has been used concurrently
+        self.session_login_link
+            .consume(&cmd.code)
+            .await
+            .context("Failed to consume login link")?
+            .filter(|&id| id == user_id)
+            .ok_or(SessionCreateByLoginLinkError::InvalidCode)?;
+
+        // don't notify the user about the very first login after registration
+        let notify = user_composite.user.last_login.is_some();
+
         let login = self
             .session
             .create(&mut txn, user_composite, cmd.device_name, true)
@@ -238,6 +443,8 @@ where
 
         txn.commit().await?;
 
+        self.session.remember_device(&login, &client, notify).await;
+
         Ok(login)
     }
 
@@ -246,9 +453,12 @@ where
         &self,
         token: &AccessToken,
         user_id: UserId,
+        client: ClientInfo,
     ) -> Result<Login, SessionImpersonateError> {
         let auth = self.auth.authenticate(token).await.map_auth_err()?;
-        auth.ensure_admin().map_auth_err()?;
+        auth.ensure_permission(Permission::UserImpersonate)
+            .map_auth_err()?;
+        auth.ensure_not_impersonated().map_auth_err()?;
 
         let mut txn = self.db.begin_transaction().await?;
 
@@ -261,10 +471,21 @@ where
 
         let login = self
             .session
-            .create(&mut txn, user_composite, None, false)
+            .impersonate(&mut txn, user_composite, auth.user_id)
             .await
             .context("Failed to create session")?;
 
+        self.audit_log
+            .record(
+                &mut txn,
+                Some(auth.user_id),
+                user_id,
+                AuditEventKind::UserImpersonated,
+                client,
+            )
+            .await
+            .context("Failed to record audit event")?;
+
         txn.commit().await?;
 
         Ok(login)
@@ -274,6 +495,7 @@ where
     async fn refresh_session(
         &self,
         refresh_token: &RefreshToken,
+        client: ClientInfo,
     ) -> Result<Login, SessionRefreshError> {
         let mut txn = self.db.begin_transaction().await?;
 
@@ -293,6 +515,11 @@ where
                     .context("Failed to delete expired session")?;
                 return Err(SessionRefreshError::InvalidRefreshToken);
             }
+            Err(AuthenticateByRefreshTokenError::Reused(session_id)) => {
+                self.revoke_session(&mut txn, session_id, client).await?;
+                txn.commit().await?;
+                return Err(SessionRefreshError::InvalidRefreshToken);
+            }
             Err(AuthenticateByRefreshTokenError::Other(err)) => {
                 return Err(err
                     .context("Failed to authenticate by refresh token")
@@ -326,7 +553,8 @@ where
     ) -> Result<(), SessionDeleteError> {
         let auth = self.auth.authenticate(token).await.map_auth_err()?;
         let user_id = user_id.unwrap_or(auth.user_id);
-        auth.ensure_self_or_admin(user_id).map_auth_err()?;
+        auth.ensure_self_or_permission(user_id, Permission::UserWrite)
+            .map_auth_err()?;
 
         let mut txn = self.db.begin_transaction().await?;
 
@@ -375,7 +603,8 @@ where
     ) -> Result<(), SessionDeleteByUserError> {
         let auth = self.auth.authenticate(token).await.map_auth_err()?;
         let user_id = user_id.unwrap_or(auth.user_id);
-        auth.ensure_self_or_admin(user_id).map_auth_err()?;
+        auth.ensure_self_or_permission(user_id, Permission::UserWrite)
+            .map_auth_err()?;
 
         let mut txn = self.db.begin_transaction().await?;
 
@@ -389,3 +618,155 @@ where
         Ok(())
     }
 }
+
+impl<
+        Db,
+        Auth,
+        Captcha,
+        Throttle,
+        SessionS,
+        SessionFailedAuthCount,
+        SessionLoginLink,
+        MfaAuthenticate,
+        UserRepo,
+        SessionRepo,
+        AuditLog,
+        TemplateEmail,
+    >
+    SessionFeatureServiceImpl<
+        Db,
+        Auth,
+        Captcha,
+        Throttle,
+        SessionS,
+        SessionFailedAuthCount,
+        SessionLoginLink,
+        MfaAuthenticate,
+        UserRepo,
+        SessionRepo,
+        AuditLog,
+        TemplateEmail,
+    >
+where
+    Db: Database,
+    Throttle: ThrottleService,
+    SessionS: SessionService<Db::Transaction>,
+    SessionFailedAuthCount: SessionFailedAuthCountService,
+    UserRepo: UserRepository<Db::Transaction>,
+    SessionRepo: SessionRepository<Db::Transaction>,
+    AuditLog: AuditLogService<Db::Transaction>,
+    TemplateEmail: TemplateEmailService,
+{
+    /// Revoke a session whose retired refresh token has been used again and
+    /// has therefore most likely been stolen.
+    async fn revoke_session(
+        &self,
+        txn: &mut Db::Transaction,
+        session_id: SessionId,
+        client: ClientInfo,
+    ) -> anyhow::Result<()> {
+        let Some(session) = self
+            .session_repo
+            .get(txn, session_id)
+            .await
+            .context("Failed to get session from database")?
+        else {
+            return Ok(());
+        };
+
+        self.session
+            .delete(txn, session.id)
+            .await
+            .context("Failed to delete session")?;
+
+        self.audit_log
+            .record(
+                txn,
+                None,
+                session.user_id,
+                AuditEventKind::RefreshTokenReused,
+                client,
+            )
+            .await
+            .context("Failed to record audit event")?;
+
+        if self.config.refresh_token_reuse_email {
+            let user_composite = self
+                .user_repo
+                .get_composite(txn, session.user_id)
+                .await
+                .context("Failed to get user from database")?
+                .ok_or_else(|| anyhow!("Failed to get user of session"))?;
+
+            if let Some(email) = user_composite.user.email {
+                trace!("send notification email");
+                self.template_email
+                    .send_session_revoked_email(
+                        email.with_name(user_composite.profile.display_name.into_inner()),
+                        &SessionRevokedTemplate {
+                            device_name: session.device_name.map(DeviceName::into_inner),
+                        },
+                    )
+                    .await
+                    .context("Failed to send session revoked email")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn record_failed_login(&self, user: &User, client: &ClientInfo) -> anyhow::Result<()> {
+        self.throttle
+            .record(&login_throttle_keys(user_account_ids(user), client))
+            .await
+            .context("Failed to record failed login attempt")?;
+        Ok(())
+    }
+
+    async fn increment_failed_auth_count(&self, user: &User) -> anyhow::Result<()> {
+        self.session_failed_auth_count
+            .increment(&UserNameOrEmailAddress::Name(user.name.clone()))
+            .await
+            .context("Failed to increment failed auth count for name")?;
+        if let Some(email) = user.email.clone() {
+            self.session_failed_auth_count
+                .increment(&UserNameOrEmailAddress::Email(email))
+                .await
+                .context("Failed to increment failed auth count for email")?;
+        }
+        Ok(())
+    }
+
+    async fn reset_failed_auth_count(&self, user: &User) -> anyhow::Result<()> {
+        self.session_failed_auth_count
+            .reset(&UserNameOrEmailAddress::Name(user.name.clone()))
+            .await
+            .context("Failed to reset failed auth count for name")?;
+        if let Some(email) = user.email.clone() {
+            self.session_failed_auth_count
+                .reset(&UserNameOrEmailAddress::Email(email))
+                .await
+                .context("Failed to reset failed auth count for email")?;
+        }
+        Ok(())
+    }
+}
+
+fn login_throttle_keys<'a>(
+    account_ids: impl IntoIterator<Item = &'a str>,
+    client: &ClientInfo,
+) -> Vec<ThrottleKey> {
+    account_ids
+        .into_iter()
+        .map(|id| ThrottleKey::account(LOGIN_THROTTLE_ACTION, id))
+        .chain(
+            client
+                .ip_address
+                .map(|ip| ThrottleKey::ip(LOGIN_THROTTLE_ACTION, ip)),
+        )
+        .collect()
+}
+
+fn user_account_ids(user: &User) -> impl Iterator<Item = &str> {
+    std::iter::once(user.name.as_str()).chain(user.email.as_ref().map(EmailAddress::as_str))
+}
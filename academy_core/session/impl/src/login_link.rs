@@ -0,0 +1,192 @@
+use academy_cache_contracts::CacheService;
+use academy_core_session_contracts::login_link::SessionLoginLinkService;
+use academy_di::Build;
+use academy_email_contracts::template::TemplateEmailService;
+use academy_models::{email_address::EmailAddressWithName, user::UserId, VerificationCode};
+use academy_shared_contracts::secret::SecretService;
+use academy_templates_contracts::LoginLinkTemplate;
+use academy_utils::trace_instrument;
+use anyhow::Context;
+
+use crate::SessionFeatureConfig;
+
+#[derive(Debug, Clone, Build)]
+#[cfg_attr(test, derive(Default))]
+pub struct SessionLoginLinkServiceImpl<Secret, TemplateEmail, Cache> {
+    secret: Secret,
+    template_email: TemplateEmail,
+    cache: Cache,
+    config: SessionFeatureConfig,
+}
+
+impl<Secret, TemplateEmail, Cache> SessionLoginLinkService
+    for SessionLoginLinkServiceImpl<Secret, TemplateEmail, Cache>
+where
+    Secret: SecretService,
+    TemplateEmail: TemplateEmailService,
+    Cache: CacheService,
+{
+    #[trace_instrument(skip(self))]
+    async fn request(&self, user_id: UserId, email: EmailAddressWithName) -> anyhow::Result<()> {
+        let code = self.secret.generate_verification_code();
+
+        self.cache
+            .set(
+                &login_link_cache_key(&code),
+                &user_id,
+                Some(self.config.login_link_code_ttl),
+            )
+            .await
+            .context("Failed to save code in cache")?;
+
+        self.template_email
+            .send_login_link_email(
+                email,
+                &LoginLinkTemplate {
+                    code: code.into_inner(),
+                    url: (*self.config.login_link_redirect_url).clone(),
+                },
+            )
+            .await
+            .context("Failed to send email")?;
+
+        Ok(())
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn get(&self, code: &VerificationCode) -> anyhow::Result<Option<UserId>> {
+        self.cache
+            .get(&login_link_cache_key(code))
+            .await
+            .context("Failed to get user id from cache")
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn consume(&self, code: &VerificationCode) -> anyhow::Result<Option<UserId>> {
+        self.cache
+            .take(&login_link_cache_key(code))
+            .await
+            .context("Failed to take user id from cache")
+    }
+}
+
+fn login_link_cache_key(code: &VerificationCode) -> String {
+    format!("login_link:{}", **code)
+}
+
+#[cfg(test)]
+mod tests {
+    use academy_cache_contracts::MockCacheService;
+    use academy_demo::{user::FOO, VERIFICATION_CODE_1};
+    use academy_email_contracts::template::MockTemplateEmailService;
+    use academy_shared_contracts::secret::MockSecretService;
+
+    use super::*;
+
+    type Sut =
+        SessionLoginLinkServiceImpl<MockSecretService, MockTemplateEmailService, MockCacheService>;
+
+    #[tokio::test]
+    async fn request() {
+        // Arrange
+        let config = SessionFeatureConfig::default();
+
+        let recipient = FOO
+            .user
+            .email
+            .clone()
+            .unwrap()
+            .with_name(FOO.profile.display_name.clone().into_inner());
+
+        let secret =
+            MockSecretService::new().with_generate_verification_code(VERIFICATION_CODE_1.clone());
+
+        let template_email = MockTemplateEmailService::new().with_send_login_link_email(
+            recipient.clone(),
+            LoginLinkTemplate {
+                code: VERIFICATION_CODE_1.clone().into_inner(),
+                url: (*config.login_link_redirect_url).clone(),
+            },
+            true,
+        );
+
+        let cache = MockCacheService::new().with_set(
+            format!("login_link:{}", **VERIFICATION_CODE_1),
+            FOO.user.id,
+            Some(config.login_link_code_ttl),
+        );
+
+        let sut = SessionLoginLinkServiceImpl {
+            secret,
+            template_email,
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.request(FOO.user.id, recipient).await;
+
+        // Assert
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn get_ok() {
+        // Arrange
+        let cache = MockCacheService::new().with_get(
+            format!("login_link:{}", **VERIFICATION_CODE_1),
+            Some(FOO.user.id),
+        );
+
+        let sut = SessionLoginLinkServiceImpl {
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.get(&VERIFICATION_CODE_1).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), Some(FOO.user.id));
+    }
+
+    #[tokio::test]
+    async fn consume_ok() {
+        // Arrange
+        let cache = MockCacheService::new().with_take(
+            format!("login_link:{}", **VERIFICATION_CODE_1),
+            Some(FOO.user.id),
+        );
+
+        let sut = SessionLoginLinkServiceImpl {
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.consume(&VERIFICATION_CODE_1).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), Some(FOO.user.id));
+    }
+
+    #[tokio::test]
+    async fn consume_invalid_code() {
+        // Arrange
+        let cache = MockCacheService::new().with_take(
+            format!("login_link:{}", **VERIFICATION_CODE_1),
+            None::<UserId>,
+        );
+
+        let sut = SessionLoginLinkServiceImpl {
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.consume(&VERIFICATION_CODE_1).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), None);
+    }
+}
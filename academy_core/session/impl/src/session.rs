@@ -1,34 +1,72 @@
+use std::net::IpAddr;
+
 use academy_auth_contracts::{access_token::AuthAccessTokenService, AuthService};
+use academy_cache_contracts::CacheService;
 use academy_core_session_contracts::session::{SessionRefreshError, SessionService};
 use academy_di::Build;
+use academy_email_contracts::template::TemplateEmailService;
 use academy_models::{
+    audit::ClientInfo,
     auth::Login,
     session::{DeviceName, Session, SessionId, SessionPatch},
     user::{UserComposite, UserId, UserPatch},
 };
 use academy_persistence_contracts::{session::SessionRepository, user::UserRepository};
-use academy_shared_contracts::{id::IdService, time::TimeService};
+use academy_shared_contracts::{hash::HashService, id::IdService, time::TimeService};
+use academy_templates_contracts::NewSessionTemplate;
 use academy_utils::{patch::Patch, trace_instrument};
 use anyhow::Context;
-
-#[derive(Debug, Clone, Build, Default)]
-pub struct SessionServiceImpl<Id, Time, Auth, AuthAccessToken, SessionRepo, UserRepo> {
+use tracing::error;
+
+use crate::SessionFeatureConfig;
+
+#[derive(Debug, Clone, Build)]
+#[cfg_attr(test, derive(Default))]
+pub struct SessionServiceImpl<
+    Id,
+    Time,
+    Hash,
+    Auth,
+    AuthAccessToken,
+    TemplateEmail,
+    Cache,
+    SessionRepo,
+    UserRepo,
+> {
     id: Id,
     time: Time,
+    hash: Hash,
     auth: Auth,
     auth_access_token: AuthAccessToken,
+    template_email: TemplateEmail,
+    cache: Cache,
     session_repo: SessionRepo,
     user_repo: UserRepo,
+    config: SessionFeatureConfig,
 }
 
-impl<Txn, Id, Time, Auth, AuthAccessToken, SessionRepo, UserRepo> SessionService<Txn>
-    for SessionServiceImpl<Id, Time, Auth, AuthAccessToken, SessionRepo, UserRepo>
+impl<Txn, Id, Time, Hash, Auth, AuthAccessToken, TemplateEmail, Cache, SessionRepo, UserRepo>
+    SessionService<Txn>
+    for SessionServiceImpl<
+        Id,
+        Time,
+        Hash,
+        Auth,
+        AuthAccessToken,
+        TemplateEmail,
+        Cache,
+        SessionRepo,
+        UserRepo,
+    >
 where
     Txn: Send + Sync + 'static,
     Id: IdService,
     Time: TimeService,
+    Hash: HashService,
     Auth: AuthService<Txn>,
     AuthAccessToken: AuthAccessTokenService,
+    TemplateEmail: TemplateEmailService,
+    Cache: CacheService,
     SessionRepo: SessionRepository<Txn>,
     UserRepo: UserRepository<Txn>,
 {
@@ -47,13 +85,14 @@ where
             id,
             user_id: user_composite.user.id,
             device_name,
+            impersonator_id: None,
             created_at: now,
             updated_at: now,
         };
 
         let tokens = self
             .auth
-            .issue_tokens(&user_composite.user, session.id)
+            .issue_tokens(&user_composite.user, session.id, None)
             .context("Failed to issue tokens")?;
 
         self.session_repo
@@ -82,6 +121,54 @@ where
         })
     }
 
+    #[trace_instrument(skip(self, login))]
+    async fn remember_device(&self, login: &Login, client: &ClientInfo, notify: bool) {
+        if let Err(err) = self.try_remember_device(login, client, notify).await {
+            error!("Failed to remember device of new session: {err:#}");
+        }
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn impersonate(
+        &self,
+        txn: &mut Txn,
+        user_composite: UserComposite,
+        impersonator_id: UserId,
+    ) -> anyhow::Result<Login> {
+        let id = self.id.generate();
+        let now = self.time.now();
+
+        let session = Session {
+            id,
+            user_id: user_composite.user.id,
+            device_name: None,
+            impersonator_id: Some(impersonator_id),
+            created_at: now,
+            updated_at: now,
+        };
+
+        let tokens = self
+            .auth
+            .issue_tokens(&user_composite.user, session.id, Some(impersonator_id))
+            .context("Failed to issue tokens")?;
+
+        self.session_repo
+            .create(txn, &session)
+            .await
+            .context("Failed to create session in database")?;
+        self.session_repo
+            .save_refresh_token_hash(txn, session.id, tokens.refresh_token_hash)
+            .await
+            .context("Failed to save session refresh token hash in database")?;
+
+        Ok(Login {
+            user_composite,
+            session,
+            access_token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+        })
+    }
+
     #[trace_instrument(skip(self, txn))]
     async fn refresh(
         &self,
@@ -119,11 +206,24 @@ where
         // issue new token pair
         let tokens = self
             .auth
-            .issue_tokens(&user_composite.user, session_id)
+            .issue_tokens(&user_composite.user, session_id, session.impersonator_id)
             .context("Failed to issue tokens")?;
 
+        // remember old refresh token to detect its reuse
+        let now = self.time.now();
+        self.session_repo
+            .retire_refresh_token_hash(
+                txn,
+                session.id,
+                refresh_token_hash,
+                now,
+                self.config.refresh_token_history,
+            )
+            .await
+            .context("Failed to retire old session refresh token hash in database")?;
+
         // update session
-        let patch = SessionPatch::new().update_updated_at(self.time.now());
+        let patch = SessionPatch::new().update_updated_at(now);
         self.session_repo
             .update(txn, session.id, patch.as_ref())
             .await
@@ -179,6 +279,98 @@ where
     }
 }
 
+impl<Id, Time, Hash, Auth, AuthAccessToken, TemplateEmail, Cache, SessionRepo, UserRepo>
+    SessionServiceImpl<
+        Id,
+        Time,
+        Hash,
+        Auth,
+        AuthAccessToken,
+        TemplateEmail,
+        Cache,
+        SessionRepo,
+        UserRepo,
+    >
+where
+    Hash: HashService,
+    TemplateEmail: TemplateEmailService,
+    Cache: CacheService,
+{
+    async fn try_remember_device(
+        &self,
+        login: &Login,
+        client: &ClientInfo,
+        notify: bool,
+    ) -> anyhow::Result<()> {
+        let user_composite = &login.user_composite;
+
+        let hash = self.hash.sha256(&device_fingerprint(client));
+        let cache_key = format!(
+            "known_device:{}:{}",
+            user_composite.user.id.hyphenated(),
+            hex::encode(hash.0)
+        );
+
+        let known_device = self
+            .cache
+            .get::<()>(&cache_key)
+            .await
+            .context("Failed to get known device from cache")?
+            .is_some();
+
+        self.cache
+            .set(&cache_key, &(), Some(self.config.known_device_ttl))
+            .await
+            .context("Failed to save known device in cache")?;
+
+        if known_device || !notify {
+            return Ok(());
+        }
+
+        let Some(email) = user_composite.user.email.clone() else {
+            return Ok(());
+        };
+
+        self.template_email
+            .send_new_session_email(
+                email.with_name(user_composite.profile.display_name.clone().into_inner()),
+                &NewSessionTemplate {
+                    device_name: login
+                        .session
+                        .device_name
+                        .clone()
+                        .map(DeviceName::into_inner),
+                },
+            )
+            .await
+            .context("Failed to send new session email")?;
+
+        Ok(())
+    }
+}
+
+/// Identify the device of a client by its user agent and the network prefix
+/// of its IP address (`/24` for IPv4 and `/48` for IPv6), so that changing
+/// addresses within the same network are not considered a new device.
+fn device_fingerprint(client: &ClientInfo) -> String {
+    let network = client.ip_address.map(|ip| match ip.to_canonical() {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            format!("{a}.{b}.{c}.0/24")
+        }
+        IpAddr::V6(ip) => {
+            let [a, b, c, ..] = ip.segments();
+            format!("{a:x}:{b:x}:{c:x}::/48")
+        }
+    });
+
+    format!(
+        "{}\n{}",
+        client.user_agent.as_deref().unwrap_or_default(),
+        network.unwrap_or_default()
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
@@ -186,19 +378,31 @@ mod tests {
     use academy_auth_contracts::{
         access_token::MockAuthAccessTokenService, MockAuthService, Tokens,
     };
-    use academy_demo::{session::FOO_1, user::FOO, SHA256HASH1, SHA256HASH2};
+    use academy_cache_contracts::MockCacheService;
+    use academy_demo::{
+        session::FOO_1,
+        user::{ADMIN, FOO},
+        SHA256HASH1, SHA256HASH2,
+    };
+    use academy_email_contracts::template::MockTemplateEmailService;
     use academy_models::user::{User, UserPatch};
     use academy_persistence_contracts::{session::MockSessionRepository, user::MockUserRepository};
-    use academy_shared_contracts::{id::MockIdService, time::MockTimeService};
+    use academy_shared_contracts::{
+        hash::MockHashService, id::MockIdService, time::MockTimeService,
+    };
     use academy_utils::assert_matches;
+    use anyhow::anyhow;
 
     use super::*;
 
     type Sut = SessionServiceImpl<
         MockIdService,
         MockTimeService,
+        MockHashService,
         MockAuthService<()>,
         MockAuthAccessTokenService,
+        MockTemplateEmailService,
+        MockCacheService,
         MockSessionRepository<()>,
         MockUserRepository<()>,
     >;
@@ -224,6 +428,7 @@ mod tests {
                 id: FOO_1.id,
                 user_id: FOO.user.id,
                 device_name: FOO_1.device_name.clone(),
+                impersonator_id: None,
                 created_at: FOO_1.created_at,
                 updated_at: FOO_1.created_at,
             },
@@ -233,8 +438,12 @@ mod tests {
 
         let id = MockIdService::new().with_generate(FOO_1.id);
         let time = MockTimeService::new().with_now(FOO_1.created_at);
-        let auth =
-            MockAuthService::new().with_issue_tokens(FOO.user.clone(), FOO_1.id, tokens.clone());
+        let auth = MockAuthService::new().with_issue_tokens(
+            FOO.user.clone(),
+            FOO_1.id,
+            None,
+            tokens.clone(),
+        );
         let session_repo = MockSessionRepository::new()
             .with_create(expected.session.clone())
             .with_save_refresh_token_hash(FOO_1.id, (*SHA256HASH1).into());
@@ -278,6 +487,7 @@ mod tests {
                 id: FOO_1.id,
                 user_id: FOO.user.id,
                 device_name: FOO_1.device_name.clone(),
+                impersonator_id: None,
                 created_at: FOO_1.created_at,
                 updated_at: FOO_1.created_at,
             },
@@ -287,8 +497,12 @@ mod tests {
 
         let id = MockIdService::new().with_generate(FOO_1.id);
         let time = MockTimeService::new().with_now(FOO_1.created_at);
-        let auth =
-            MockAuthService::new().with_issue_tokens(FOO.user.clone(), FOO_1.id, tokens.clone());
+        let auth = MockAuthService::new().with_issue_tokens(
+            FOO.user.clone(),
+            FOO_1.id,
+            None,
+            tokens.clone(),
+        );
         let session_repo = MockSessionRepository::new()
             .with_create(expected.session.clone())
             .with_save_refresh_token_hash(FOO_1.id, (*SHA256HASH1).into());
@@ -313,6 +527,229 @@ mod tests {
         assert_eq!(result.unwrap(), expected);
     }
 
+    #[tokio::test]
+    async fn remember_device_known() {
+        // Arrange
+        let client = client();
+        let cache_key = format!(
+            "known_device:{}:{}",
+            FOO.user.id.hyphenated(),
+            hex::encode(SHA256HASH1.0)
+        );
+
+        let hash =
+            MockHashService::new().with_sha256("desktop\n192.0.2.0/24".to_owned(), *SHA256HASH1);
+
+        let cache = MockCacheService::new()
+            .with_get(cache_key.clone(), Some(()))
+            .with_set(
+                cache_key,
+                (),
+                Some(SessionFeatureConfig::default().known_device_ttl),
+            );
+
+        let sut = SessionServiceImpl {
+            hash,
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        SessionService::<()>::remember_device(&sut, &login(), &client, true).await;
+    }
+
+    #[tokio::test]
+    async fn remember_device_new() {
+        // Arrange
+        let client = client();
+        let cache_key = format!(
+            "known_device:{}:{}",
+            FOO.user.id.hyphenated(),
+            hex::encode(SHA256HASH1.0)
+        );
+
+        let hash =
+            MockHashService::new().with_sha256("desktop\n192.0.2.0/24".to_owned(), *SHA256HASH1);
+
+        let cache = MockCacheService::new()
+            .with_get(cache_key.clone(), None::<()>)
+            .with_set(
+                cache_key,
+                (),
+                Some(SessionFeatureConfig::default().known_device_ttl),
+            );
+
+        let template_email = MockTemplateEmailService::new().with_send_new_session_email(
+            FOO.user
+                .email
+                .clone()
+                .unwrap()
+                .with_name(FOO.profile.display_name.clone().into_inner()),
+            NewSessionTemplate {
+                device_name: Some("desktop".into()),
+            },
+            true,
+        );
+
+        let sut = SessionServiceImpl {
+            hash,
+            cache,
+            template_email,
+            ..Sut::default()
+        };
+
+        // Act
+        SessionService::<()>::remember_device(&sut, &login(), &client, true).await;
+    }
+
+    #[tokio::test]
+    async fn remember_device_dont_notify() {
+        // Arrange
+        let client = client();
+        let cache_key = format!(
+            "known_device:{}:{}",
+            FOO.user.id.hyphenated(),
+            hex::encode(SHA256HASH1.0)
+        );
+
+        let hash =
+            MockHashService::new().with_sha256("desktop\n192.0.2.0/24".to_owned(), *SHA256HASH1);
+
+        let cache = MockCacheService::new()
+            .with_get(cache_key.clone(), None::<()>)
+            .with_set(
+                cache_key,
+                (),
+                Some(SessionFeatureConfig::default().known_device_ttl),
+            );
+
+        let sut = SessionServiceImpl {
+            hash,
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        SessionService::<()>::remember_device(&sut, &login(), &client, false).await;
+    }
+
+    #[tokio::test]
+    async fn remember_device_email_error() {
+        // Arrange
+        let client = client();
+        let cache_key = format!(
+            "known_device:{}:{}",
+            FOO.user.id.hyphenated(),
+            hex::encode(SHA256HASH1.0)
+        );
+
+        let hash =
+            MockHashService::new().with_sha256("desktop\n192.0.2.0/24".to_owned(), *SHA256HASH1);
+
+        let cache = MockCacheService::new()
+            .with_get(cache_key.clone(), None::<()>)
+            .with_set(
+                cache_key,
+                (),
+                Some(SessionFeatureConfig::default().known_device_ttl),
+            );
+
+        let mut template_email = MockTemplateEmailService::new();
+        template_email
+            .expect_send_new_session_email()
+            .once()
+            .return_once(|_, _| Box::pin(std::future::ready(Err(anyhow!("smtp error")))));
+
+        let sut = SessionServiceImpl {
+            hash,
+            cache,
+            template_email,
+            ..Sut::default()
+        };
+
+        // Act
+        SessionService::<()>::remember_device(&sut, &login(), &client, true).await;
+    }
+
+    #[test]
+    fn device_fingerprint_network_prefix() {
+        let client = |ip: &str| ClientInfo {
+            ip_address: Some(ip.parse().unwrap()),
+            user_agent: Some("desktop".into()),
+        };
+
+        assert_eq!(
+            device_fingerprint(&client("192.0.2.42")),
+            device_fingerprint(&client("192.0.2.7"))
+        );
+        assert_eq!(
+            device_fingerprint(&client("::ffff:192.0.2.7")),
+            "desktop\n192.0.2.0/24"
+        );
+        assert_ne!(
+            device_fingerprint(&client("192.0.2.42")),
+            device_fingerprint(&client("198.51.100.42"))
+        );
+        assert_eq!(
+            device_fingerprint(&client("2001:db8:1234:5678::1")),
+            "desktop\n2001:db8:1234::/48"
+        );
+        assert_eq!(
+            device_fingerprint(&ClientInfo::default()),
+            device_fingerprint(&ClientInfo::default())
+        );
+    }
+
+    #[tokio::test]
+    async fn impersonate() {
+        // Arrange
+        let tokens = Tokens {
+            access_token: "the access token".into(),
+            refresh_token: "the refresh token".into(),
+            refresh_token_hash: (*SHA256HASH1).into(),
+        };
+
+        let expected = Login {
+            user_composite: FOO.clone(),
+            session: Session {
+                id: FOO_1.id,
+                user_id: FOO.user.id,
+                device_name: None,
+                impersonator_id: Some(ADMIN.user.id),
+                created_at: FOO_1.created_at,
+                updated_at: FOO_1.created_at,
+            },
+            access_token: tokens.access_token.clone(),
+            refresh_token: tokens.refresh_token.clone(),
+        };
+
+        let id = MockIdService::new().with_generate(FOO_1.id);
+        let time = MockTimeService::new().with_now(FOO_1.created_at);
+        let auth = MockAuthService::new().with_issue_tokens(
+            FOO.user.clone(),
+            FOO_1.id,
+            Some(ADMIN.user.id),
+            tokens.clone(),
+        );
+        let session_repo = MockSessionRepository::new()
+            .with_create(expected.session.clone())
+            .with_save_refresh_token_hash(FOO_1.id, (*SHA256HASH1).into());
+
+        let sut = SessionServiceImpl {
+            id,
+            time,
+            auth,
+            session_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.impersonate(&mut (), FOO.clone(), ADMIN.user.id).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), expected);
+    }
+
     #[tokio::test]
     async fn refresh_ok() {
         // Arrange
@@ -332,7 +769,8 @@ mod tests {
             refresh_token: tokens.refresh_token.clone(),
         };
 
-        let auth = MockAuthService::new().with_issue_tokens(FOO.user.clone(), FOO_1.id, tokens);
+        let auth =
+            MockAuthService::new().with_issue_tokens(FOO.user.clone(), FOO_1.id, None, tokens);
 
         let auth_access_token =
             MockAuthAccessTokenService::new().with_invalidate((*SHA256HASH1).into());
@@ -349,6 +787,12 @@ mod tests {
                 SessionPatch::new().update_updated_at(expected.session.updated_at),
                 true,
             )
+            .with_retire_refresh_token_hash(
+                FOO_1.id,
+                (*SHA256HASH1).into(),
+                expected.session.updated_at,
+                SessionFeatureConfig::default().refresh_token_history,
+            )
             .with_save_refresh_token_hash(FOO_1.id, (*SHA256HASH2).into());
 
         let sut = SessionServiceImpl {
@@ -485,4 +929,20 @@ mod tests {
         // Assert
         result.unwrap();
     }
+
+    fn client() -> ClientInfo {
+        ClientInfo {
+            ip_address: Some("192.0.2.42".parse().unwrap()),
+            user_agent: Some("desktop".into()),
+        }
+    }
+
+    fn login() -> Login {
+        Login {
+            user_composite: FOO.clone(),
+            session: FOO_1.clone(),
+            access_token: "the access token".into(),
+            refresh_token: "the refresh token".into(),
+        }
+    }
 }
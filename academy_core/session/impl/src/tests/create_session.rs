@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use academy_auth_contracts::MockAuthService;
 use academy_core_mfa_contracts::authenticate::{
     MfaAuthenticateError, MfaAuthenticateResult, MockMfaAuthenticateService,
@@ -7,12 +9,18 @@ use academy_core_session_contracts::{
     SessionCreateCommand, SessionCreateError, SessionFeatureService,
 };
 use academy_demo::{
+    audit::FOO_AUDIT_EVENT_1,
     session::{BAR_1, FOO_1},
     user::{BAR, BAR_PASSWORD, FOO, FOO_PASSWORD},
 };
-use academy_models::{auth::Login, mfa::MfaAuthentication, user::UserNameOrEmailAddress};
+use academy_models::{
+    audit::ClientInfo, auth::Login, mfa::MfaAuthentication, user::UserNameOrEmailAddress,
+};
 use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
-use academy_shared_contracts::captcha::{CaptchaCheckError, MockCaptchaService};
+use academy_shared_contracts::{
+    captcha::{CaptchaCheckError, MockCaptchaService},
+    throttle::{MockThrottleService, ThrottleKey},
+};
 use academy_utils::{assert_matches, Apply};
 
 use crate::{tests::Sut, SessionFeatureServiceImpl};
@@ -52,14 +60,101 @@ async fn ok() {
         true,
     );
 
-    let session = MockSessionService::new().with_create(
-        FOO.clone(),
-        cmd.device_name.clone(),
+    let session = MockSessionService::new()
+        .with_create(FOO.clone(), cmd.device_name.clone(), true, expected.clone())
+        .with_remember_device(expected.clone(), FOO_AUDIT_EVENT_1.client.clone(), true);
+
+    let throttle = MockThrottleService::new()
+        .with_check(
+            vec![
+                ThrottleKey::account("login", &FOO.user.name),
+                ThrottleKey::ip("login", FOO_AUDIT_EVENT_1.client.ip_address.unwrap()),
+            ],
+            None,
+        )
+        .with_reset(ThrottleKey::account("login", &FOO.user.name))
+        .with_reset(ThrottleKey::account(
+            "login",
+            FOO.user.email.as_ref().unwrap().as_str(),
+        ));
+
+    let sut = SessionFeatureServiceImpl {
+        throttle,
+        db,
+        session_failed_auth_count,
+        auth,
+        session,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_session(cmd, None, FOO_AUDIT_EVENT_1.client.clone())
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn ok_first_login() {
+    // Arrange
+    let cmd = SessionCreateCommand {
+        name_or_email: UserNameOrEmailAddress::Name(FOO.user.name.clone()),
+        password: FOO_PASSWORD.clone(),
+        device_name: FOO_1.device_name.clone(),
+        mfa: MfaAuthentication::default(),
+    };
+
+    let user_composite = FOO.clone().with(|u| u.user.last_login = None);
+
+    let expected = Login {
+        user_composite: user_composite.clone(),
+        session: FOO_1.clone(),
+        access_token: "the access token".into(),
+        refresh_token: "some refresh token".into(),
+    };
+
+    let db = MockDatabase::build(true);
+
+    let session_failed_auth_count = MockSessionFailedAuthCountService::new()
+        .with_get(cmd.name_or_email.clone(), 1)
+        .with_reset(UserNameOrEmailAddress::Name(FOO.user.name.clone()))
+        .with_reset(UserNameOrEmailAddress::Email(
+            FOO.user.email.clone().unwrap(),
+        ));
+
+    let user_repo = MockUserRepository::new().with_get_composite_by_name_or_email(
+        cmd.name_or_email.clone(),
+        Some(user_composite.clone()),
+    );
+
+    let auth = MockAuthService::new().with_authenticate_by_password(
+        FOO.user.id,
+        cmd.password.clone(),
         true,
-        expected.clone(),
     );
 
+    let session = MockSessionService::new()
+        .with_create(
+            user_composite,
+            cmd.device_name.clone(),
+            true,
+            expected.clone(),
+        )
+        .with_remember_device(expected.clone(), ClientInfo::default(), false);
+
+    let throttle = MockThrottleService::new()
+        .with_check(vec![ThrottleKey::account("login", &FOO.user.name)], None)
+        .with_reset(ThrottleKey::account("login", &FOO.user.name))
+        .with_reset(ThrottleKey::account(
+            "login",
+            FOO.user.email.as_ref().unwrap().as_str(),
+        ));
+
     let sut = SessionFeatureServiceImpl {
+        throttle,
         db,
         session_failed_auth_count,
         auth,
@@ -69,7 +164,7 @@ async fn ok() {
     };
 
     // Act
-    let result = sut.create_session(cmd, None).await;
+    let result = sut.create_session(cmd, None, ClientInfo::default()).await;
 
     // Assert
     assert_eq!(result.unwrap(), expected);
@@ -85,6 +180,7 @@ async fn ok_mfa() {
         mfa: MfaAuthentication {
             totp_code: Some("123456".try_into().unwrap()),
             recovery_code: None,
+            webauthn: None,
         },
     };
 
@@ -121,14 +217,25 @@ async fn ok_mfa() {
         Ok(MfaAuthenticateResult::Ok),
     );
 
-    let session = MockSessionService::new().with_create(
-        expected.user_composite.clone(),
-        cmd.device_name.clone(),
-        true,
-        expected.clone(),
-    );
+    let session = MockSessionService::new()
+        .with_create(
+            expected.user_composite.clone(),
+            cmd.device_name.clone(),
+            true,
+            expected.clone(),
+        )
+        .with_remember_device(expected.clone(), ClientInfo::default(), true);
+
+    let throttle = MockThrottleService::new()
+        .with_check(vec![ThrottleKey::account("login", &FOO.user.name)], None)
+        .with_reset(ThrottleKey::account("login", &FOO.user.name))
+        .with_reset(ThrottleKey::account(
+            "login",
+            FOO.user.email.as_ref().unwrap().as_str(),
+        ));
 
     let sut = SessionFeatureServiceImpl {
+        throttle,
         db,
         session_failed_auth_count,
         auth,
@@ -139,7 +246,7 @@ async fn ok_mfa() {
     };
 
     // Act
-    let result = sut.create_session(cmd, None).await;
+    let result = sut.create_session(cmd, None, ClientInfo::default()).await;
 
     // Assert
     assert_eq!(result.unwrap(), expected);
@@ -155,6 +262,7 @@ async fn ok_mfa_reset() {
         mfa: MfaAuthentication {
             totp_code: Some("123456".try_into().unwrap()),
             recovery_code: None,
+            webauthn: None,
         },
     };
 
@@ -196,14 +304,25 @@ async fn ok_mfa_reset() {
         Ok(MfaAuthenticateResult::Reset),
     );
 
-    let session = MockSessionService::new().with_create(
-        expected.user_composite.clone(),
-        cmd.device_name.clone(),
-        true,
-        expected.clone(),
-    );
+    let session = MockSessionService::new()
+        .with_create(
+            expected.user_composite.clone(),
+            cmd.device_name.clone(),
+            true,
+            expected.clone(),
+        )
+        .with_remember_device(expected.clone(), ClientInfo::default(), true);
+
+    let throttle = MockThrottleService::new()
+        .with_check(vec![ThrottleKey::account("login", &FOO.user.name)], None)
+        .with_reset(ThrottleKey::account("login", &FOO.user.name))
+        .with_reset(ThrottleKey::account(
+            "login",
+            FOO.user.email.as_ref().unwrap().as_str(),
+        ));
 
     let sut = SessionFeatureServiceImpl {
+        throttle,
         db,
         session_failed_auth_count,
         auth,
@@ -214,7 +333,7 @@ async fn ok_mfa_reset() {
     };
 
     // Act
-    let result = sut.create_session(cmd, None).await;
+    let result = sut.create_session(cmd, None, ClientInfo::default()).await;
 
     // Assert
     assert_eq!(result.unwrap(), expected);
@@ -257,14 +376,20 @@ async fn ok_captcha() {
         true,
     );
 
-    let session = MockSessionService::new().with_create(
-        FOO.clone(),
-        cmd.device_name.clone(),
-        true,
-        expected.clone(),
-    );
+    let session = MockSessionService::new()
+        .with_create(FOO.clone(), cmd.device_name.clone(), true, expected.clone())
+        .with_remember_device(expected.clone(), ClientInfo::default(), true);
+
+    let throttle = MockThrottleService::new()
+        .with_check(vec![ThrottleKey::account("login", &FOO.user.name)], None)
+        .with_reset(ThrottleKey::account("login", &FOO.user.name))
+        .with_reset(ThrottleKey::account(
+            "login",
+            FOO.user.email.as_ref().unwrap().as_str(),
+        ));
 
     let sut = SessionFeatureServiceImpl {
+        throttle,
         db,
         session_failed_auth_count,
         captcha,
@@ -276,7 +401,7 @@ async fn ok_captcha() {
 
     // Act
     let result = sut
-        .create_session(cmd, Some("resp".try_into().unwrap()))
+        .create_session(cmd, Some("resp".try_into().unwrap()), ClientInfo::default())
         .await;
 
     // Assert
@@ -299,7 +424,11 @@ async fn invalid_recaptcha_response() {
     let captcha =
         MockCaptchaService::new().with_check(Some("resp"), Err(CaptchaCheckError::Failed));
 
+    let throttle = MockThrottleService::new()
+        .with_check(vec![ThrottleKey::account("login", &FOO.user.name)], None);
+
     let sut = SessionFeatureServiceImpl {
+        throttle,
         session_failed_auth_count,
         captcha,
         ..Sut::default()
@@ -307,7 +436,7 @@ async fn invalid_recaptcha_response() {
 
     // Act
     let result = sut
-        .create_session(cmd, Some("resp".try_into().unwrap()))
+        .create_session(cmd, Some("resp".try_into().unwrap()), ClientInfo::default())
         .await;
 
     // Assert
@@ -333,7 +462,12 @@ async fn user_not_found() {
     let user_repo = MockUserRepository::new()
         .with_get_composite_by_name_or_email(cmd.name_or_email.clone(), None);
 
+    let throttle = MockThrottleService::new()
+        .with_check(vec![ThrottleKey::account("login", &FOO.user.name)], None)
+        .with_record(vec![ThrottleKey::account("login", &FOO.user.name)], None);
+
     let sut = SessionFeatureServiceImpl {
+        throttle,
         db,
         session_failed_auth_count,
         user_repo,
@@ -341,7 +475,7 @@ async fn user_not_found() {
     };
 
     // Act
-    let result = sut.create_session(cmd, None).await;
+    let result = sut.create_session(cmd, None, ClientInfo::default()).await;
 
     // Assert
     assert_matches!(result, Err(SessionCreateError::InvalidCredentials));
@@ -377,7 +511,18 @@ async fn wrong_password() {
         false,
     );
 
+    let throttle = MockThrottleService::new()
+        .with_check(vec![ThrottleKey::account("login", &FOO.user.name)], None)
+        .with_record(
+            vec![
+                ThrottleKey::account("login", &FOO.user.name),
+                ThrottleKey::account("login", FOO.user.email.as_ref().unwrap().as_str()),
+            ],
+            None,
+        );
+
     let sut = SessionFeatureServiceImpl {
+        throttle,
         db,
         session_failed_auth_count,
         auth,
@@ -386,7 +531,7 @@ async fn wrong_password() {
     };
 
     // Act
-    let result = sut.create_session(cmd, None).await;
+    let result = sut.create_session(cmd, None, ClientInfo::default()).await;
 
     // Assert
     assert_matches!(result, Err(SessionCreateError::InvalidCredentials));
@@ -402,6 +547,7 @@ async fn mfa_failed() {
         mfa: MfaAuthentication {
             totp_code: Some("123456".try_into().unwrap()),
             recovery_code: None,
+            webauthn: None,
         },
     };
 
@@ -431,7 +577,18 @@ async fn mfa_failed() {
         Err(MfaAuthenticateError::Failed),
     );
 
+    let throttle = MockThrottleService::new()
+        .with_check(vec![ThrottleKey::account("login", &FOO.user.name)], None)
+        .with_record(
+            vec![
+                ThrottleKey::account("login", &FOO.user.name),
+                ThrottleKey::account("login", FOO.user.email.as_ref().unwrap().as_str()),
+            ],
+            None,
+        );
+
     let sut = SessionFeatureServiceImpl {
+        throttle,
         db,
         session_failed_auth_count,
         auth,
@@ -441,7 +598,7 @@ async fn mfa_failed() {
     };
 
     // Act
-    let result = sut.create_session(cmd, None).await;
+    let result = sut.create_session(cmd, None, ClientInfo::default()).await;
 
     // Assert
     assert_matches!(result, Err(SessionCreateError::MfaFailed));
@@ -472,7 +629,68 @@ async fn user_disabled() {
         true,
     );
 
+    let throttle = MockThrottleService::new()
+        .with_check(vec![ThrottleKey::account("login", &BAR.user.name)], None)
+        .with_reset(ThrottleKey::account("login", &BAR.user.name));
+
+    let sut = SessionFeatureServiceImpl {
+        throttle,
+        db,
+        session_failed_auth_count,
+        auth,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.create_session(cmd, None, ClientInfo::default()).await;
+
+    // Assert
+    assert_matches!(result, Err(SessionCreateError::UserDisabled));
+}
+
+#[tokio::test]
+async fn user_deleted() {
+    // Arrange
+    let cmd = SessionCreateCommand {
+        name_or_email: UserNameOrEmailAddress::Name(FOO.user.name.clone()),
+        password: FOO_PASSWORD.clone(),
+        device_name: FOO_1.device_name.clone(),
+        mfa: MfaAuthentication::default(),
+    };
+
+    let foo = FOO
+        .clone()
+        .with(|x| x.user.deleted_at = Some(FOO_1.created_at));
+
+    let db = MockDatabase::build(false);
+
+    let session_failed_auth_count = MockSessionFailedAuthCountService::new()
+        .with_get(cmd.name_or_email.clone(), 1)
+        .with_reset(UserNameOrEmailAddress::Name(FOO.user.name.clone()))
+        .with_reset(UserNameOrEmailAddress::Email(
+            FOO.user.email.clone().unwrap(),
+        ));
+
+    let user_repo = MockUserRepository::new()
+        .with_get_composite_by_name_or_email(cmd.name_or_email.clone(), Some(foo));
+
+    let auth = MockAuthService::new().with_authenticate_by_password(
+        FOO.user.id,
+        cmd.password.clone(),
+        true,
+    );
+
+    let throttle = MockThrottleService::new()
+        .with_check(vec![ThrottleKey::account("login", &FOO.user.name)], None)
+        .with_reset(ThrottleKey::account("login", &FOO.user.name))
+        .with_reset(ThrottleKey::account(
+            "login",
+            FOO.user.email.as_ref().unwrap().as_str(),
+        ));
+
     let sut = SessionFeatureServiceImpl {
+        throttle,
         db,
         session_failed_auth_count,
         auth,
@@ -481,8 +699,42 @@ async fn user_disabled() {
     };
 
     // Act
-    let result = sut.create_session(cmd, None).await;
+    let result = sut.create_session(cmd, None, ClientInfo::default()).await;
 
     // Assert
     assert_matches!(result, Err(SessionCreateError::UserDisabled));
 }
+
+#[tokio::test]
+async fn rate_limit() {
+    // Arrange
+    let cmd = SessionCreateCommand {
+        name_or_email: UserNameOrEmailAddress::Name(FOO.user.name.clone()),
+        password: FOO_PASSWORD.clone(),
+        device_name: FOO_1.device_name.clone(),
+        mfa: MfaAuthentication::default(),
+    };
+
+    let expected = FOO_1.created_at + Duration::from_secs(60);
+
+    let throttle = MockThrottleService::new().with_check(
+        vec![
+            ThrottleKey::account("login", &FOO.user.name),
+            ThrottleKey::ip("login", FOO_AUDIT_EVENT_1.client.ip_address.unwrap()),
+        ],
+        Some(expected),
+    );
+
+    let sut = SessionFeatureServiceImpl {
+        throttle,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_session(cmd, None, FOO_AUDIT_EVENT_1.client.clone())
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(SessionCreateError::RateLimit { until }) if *until == expected);
+}
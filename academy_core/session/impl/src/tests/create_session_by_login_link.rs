@@ -0,0 +1,343 @@
+use academy_core_mfa_contracts::authenticate::{
+    MfaAuthenticateError, MfaAuthenticateResult, MockMfaAuthenticateService,
+};
+use academy_core_session_contracts::{
+    failed_auth_count::MockSessionFailedAuthCountService, login_link::MockSessionLoginLinkService,
+    session::MockSessionService, SessionCreateByLoginLinkCommand, SessionCreateByLoginLinkError,
+    SessionFeatureService,
+};
+use academy_demo::{
+    session::{BAR_1, FOO_1},
+    user::{BAR, FOO},
+    VERIFICATION_CODE_1,
+};
+use academy_models::{
+    audit::ClientInfo, auth::Login, mfa::MfaAuthentication, user::UserNameOrEmailAddress,
+};
+use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
+use academy_shared_contracts::captcha::{CaptchaCheckError, MockCaptchaService};
+use academy_utils::{assert_matches, Apply};
+
+use crate::{tests::Sut, SessionFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok() {
+    // Arrange
+    let cmd = SessionCreateByLoginLinkCommand {
+        code: VERIFICATION_CODE_1.clone(),
+        device_name: FOO_1.device_name.clone(),
+        mfa: MfaAuthentication::default(),
+    };
+
+    let expected = Login {
+        user_composite: FOO.clone(),
+        session: FOO_1.clone(),
+        access_token: "the access token".into(),
+        refresh_token: "some refresh token".into(),
+    };
+
+    let db = MockDatabase::build(true);
+
+    let session_login_link = MockSessionLoginLinkService::new()
+        .with_get(cmd.code.clone(), Some(FOO.user.id))
+        .with_consume(cmd.code.clone(), Some(FOO.user.id));
+
+    let session_failed_auth_count = MockSessionFailedAuthCountService::new()
+        .with_get(UserNameOrEmailAddress::Name(FOO.user.name.clone()), 1)
+        .with_reset(UserNameOrEmailAddress::Name(FOO.user.name.clone()))
+        .with_reset(UserNameOrEmailAddress::Email(
+            FOO.user.email.clone().unwrap(),
+        ));
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let session = MockSessionService::new()
+        .with_create(FOO.clone(), cmd.device_name.clone(), true, expected.clone())
+        .with_remember_device(expected.clone(), ClientInfo::default(), true);
+
+    let sut = SessionFeatureServiceImpl {
+        db,
+        session_failed_auth_count,
+        session_login_link,
+        session,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_session_by_login_link(cmd, None, ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn ok_mfa() {
+    // Arrange
+    let cmd = SessionCreateByLoginLinkCommand {
+        code: VERIFICATION_CODE_1.clone(),
+        device_name: FOO_1.device_name.clone(),
+        mfa: MfaAuthentication {
+            totp_code: Some("123456".try_into().unwrap()),
+            recovery_code: None,
+            webauthn: None,
+        },
+    };
+
+    let expected = Login {
+        user_composite: FOO.clone().with(|u| u.details.mfa_enabled = true),
+        session: FOO_1.clone(),
+        access_token: "the access token".into(),
+        refresh_token: "some refresh token".into(),
+    };
+
+    let db = MockDatabase::build(true);
+
+    let session_login_link = MockSessionLoginLinkService::new()
+        .with_get(cmd.code.clone(), Some(FOO.user.id))
+        .with_consume(cmd.code.clone(), Some(FOO.user.id));
+
+    let session_failed_auth_count = MockSessionFailedAuthCountService::new()
+        .with_get(UserNameOrEmailAddress::Name(FOO.user.name.clone()), 1)
+        .with_reset(UserNameOrEmailAddress::Name(FOO.user.name.clone()))
+        .with_reset(UserNameOrEmailAddress::Email(
+            FOO.user.email.clone().unwrap(),
+        ));
+
+    let user_repo = MockUserRepository::new()
+        .with_get_composite(FOO.user.id, Some(expected.user_composite.clone()));
+
+    let mfa_authenticate = MockMfaAuthenticateService::new().with_authenticate(
+        FOO.user.id,
+        cmd.mfa.clone(),
+        Ok(MfaAuthenticateResult::Ok),
+    );
+
+    let session = MockSessionService::new()
+        .with_create(
+            expected.user_composite.clone(),
+            cmd.device_name.clone(),
+            true,
+            expected.clone(),
+        )
+        .with_remember_device(expected.clone(), ClientInfo::default(), true);
+
+    let sut = SessionFeatureServiceImpl {
+        db,
+        session_failed_auth_count,
+        session_login_link,
+        session,
+        mfa_authenticate,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_session_by_login_link(cmd, None, ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn invalid_code() {
+    // Arrange
+    let cmd = SessionCreateByLoginLinkCommand {
+        code: VERIFICATION_CODE_1.clone(),
+        device_name: FOO_1.device_name.clone(),
+        mfa: MfaAuthentication::default(),
+    };
+
+    let session_login_link = MockSessionLoginLinkService::new().with_get(cmd.code.clone(), None);
+
+    let sut = SessionFeatureServiceImpl {
+        session_login_link,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_session_by_login_link(cmd, None, ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(SessionCreateByLoginLinkError::InvalidCode));
+}
+
+#[tokio::test]
+async fn invalid_recaptcha_response() {
+    // Arrange
+    let cmd = SessionCreateByLoginLinkCommand {
+        code: VERIFICATION_CODE_1.clone(),
+        device_name: FOO_1.device_name.clone(),
+        mfa: MfaAuthentication::default(),
+    };
+
+    let db = MockDatabase::build(false);
+
+    // the login link is not consumed and can be used again
+    let session_login_link =
+        MockSessionLoginLinkService::new().with_get(cmd.code.clone(), Some(FOO.user.id));
+
+    let session_failed_auth_count = MockSessionFailedAuthCountService::new()
+        .with_get(UserNameOrEmailAddress::Name(FOO.user.name.clone()), 4);
+
+    let captcha =
+        MockCaptchaService::new().with_check(Some("resp"), Err(CaptchaCheckError::Failed));
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let sut = SessionFeatureServiceImpl {
+        db,
+        session_failed_auth_count,
+        session_login_link,
+        captcha,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_session_by_login_link(cmd, Some("resp".try_into().unwrap()), ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(SessionCreateByLoginLinkError::Recaptcha));
+}
+
+#[tokio::test]
+async fn mfa_failed() {
+    // Arrange
+    let cmd = SessionCreateByLoginLinkCommand {
+        code: VERIFICATION_CODE_1.clone(),
+        device_name: FOO_1.device_name.clone(),
+        mfa: MfaAuthentication {
+            totp_code: Some("123456".try_into().unwrap()),
+            recovery_code: None,
+            webauthn: None,
+        },
+    };
+
+    let db = MockDatabase::build(false);
+
+    // the login link is not consumed and can be used again
+    let session_login_link =
+        MockSessionLoginLinkService::new().with_get(cmd.code.clone(), Some(FOO.user.id));
+
+    let session_failed_auth_count = MockSessionFailedAuthCountService::new()
+        .with_get(UserNameOrEmailAddress::Name(FOO.user.name.clone()), 1)
+        .with_increment(UserNameOrEmailAddress::Name(FOO.user.name.clone()))
+        .with_increment(UserNameOrEmailAddress::Email(
+            FOO.user.email.clone().unwrap(),
+        ));
+
+    let user_repo = MockUserRepository::new().with_get_composite(
+        FOO.user.id,
+        Some(FOO.clone().with(|u| u.details.mfa_enabled = true)),
+    );
+
+    let mfa_authenticate = MockMfaAuthenticateService::new().with_authenticate(
+        FOO.user.id,
+        cmd.mfa.clone(),
+        Err(MfaAuthenticateError::Failed),
+    );
+
+    let sut = SessionFeatureServiceImpl {
+        db,
+        session_failed_auth_count,
+        session_login_link,
+        mfa_authenticate,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_session_by_login_link(cmd, None, ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(SessionCreateByLoginLinkError::MfaFailed));
+}
+
+#[tokio::test]
+async fn user_disabled() {
+    // Arrange
+    let cmd = SessionCreateByLoginLinkCommand {
+        code: VERIFICATION_CODE_1.clone(),
+        device_name: BAR_1.device_name.clone(),
+        mfa: MfaAuthentication::default(),
+    };
+
+    let db = MockDatabase::build(false);
+
+    let session_login_link =
+        MockSessionLoginLinkService::new().with_get(cmd.code.clone(), Some(BAR.user.id));
+
+    let session_failed_auth_count = MockSessionFailedAuthCountService::new()
+        .with_get(UserNameOrEmailAddress::Name(BAR.user.name.clone()), 1)
+        .with_reset(UserNameOrEmailAddress::Name(BAR.user.name.clone()));
+
+    let user_repo = MockUserRepository::new().with_get_composite(BAR.user.id, Some(BAR.clone()));
+
+    let sut = SessionFeatureServiceImpl {
+        db,
+        session_failed_auth_count,
+        session_login_link,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_session_by_login_link(cmd, None, ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(SessionCreateByLoginLinkError::UserDisabled));
+}
+
+#[tokio::test]
+async fn consumed_concurrently() {
+    // Arrange
+    let cmd = SessionCreateByLoginLinkCommand {
+        code: VERIFICATION_CODE_1.clone(),
+        device_name: FOO_1.device_name.clone(),
+        mfa: MfaAuthentication::default(),
+    };
+
+    let db = MockDatabase::build(false);
+
+    let session_login_link = MockSessionLoginLinkService::new()
+        .with_get(cmd.code.clone(), Some(FOO.user.id))
+        .with_consume(cmd.code.clone(), None);
+
+    let session_failed_auth_count = MockSessionFailedAuthCountService::new()
+        .with_get(UserNameOrEmailAddress::Name(FOO.user.name.clone()), 1)
+        .with_reset(UserNameOrEmailAddress::Name(FOO.user.name.clone()))
+        .with_reset(UserNameOrEmailAddress::Email(
+            FOO.user.email.clone().unwrap(),
+        ));
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let sut = SessionFeatureServiceImpl {
+        db,
+        session_failed_auth_count,
+        session_login_link,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_session_by_login_link(cmd, None, ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(SessionCreateByLoginLinkError::InvalidCode));
+}
@@ -110,7 +110,7 @@ async fn unauthorized() {
     assert_matches!(
         result,
         Err(SessionDeleteByUserError::Auth(AuthError::Authorize(
-            AuthorizeError::Admin
+            AuthorizeError::Permission
         )))
     );
 }
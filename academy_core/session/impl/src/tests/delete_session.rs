@@ -143,7 +143,7 @@ async fn unauthorized() {
     assert_matches!(
         result,
         Err(SessionDeleteError::Auth(AuthError::Authorize(
-            AuthorizeError::Admin
+            AuthorizeError::Permission
         )))
     );
 }
@@ -1,12 +1,18 @@
 use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::log::MockAuditLogService;
 use academy_core_session_contracts::{
     session::MockSessionService, SessionFeatureService, SessionImpersonateError,
 };
 use academy_demo::{
+    audit::FOO_AUDIT_EVENT_2,
     session::{ADMIN_1, BAR_1, FOO_1},
     user::{ADMIN, BAR, FOO},
 };
-use academy_models::auth::{AuthError, AuthenticateError, AuthorizeError, Login};
+use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
+    auth::{AuthError, AuthenticateError, AuthorizeError, Login},
+    session::Session,
+};
 use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
 use academy_utils::assert_matches;
 
@@ -17,7 +23,10 @@ async fn ok() {
     // Arrange
     let expected = Login {
         user_composite: FOO.clone(),
-        session: FOO_1.clone(),
+        session: Session {
+            impersonator_id: Some(ADMIN.user.id),
+            ..FOO_1.clone()
+        },
         access_token: "access token".into(),
         refresh_token: "refresh token".into(),
     };
@@ -29,18 +38,33 @@ async fn ok() {
 
     let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
 
-    let session = MockSessionService::new().with_create(FOO.clone(), None, false, expected.clone());
+    let session =
+        MockSessionService::new().with_impersonate(FOO.clone(), ADMIN.user.id, expected.clone());
+
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(ADMIN.user.id),
+        FOO.user.id,
+        AuditEventKind::UserImpersonated,
+        FOO_AUDIT_EVENT_2.client.clone(),
+    );
 
     let sut = SessionFeatureServiceImpl {
         auth,
         db,
         user_repo,
         session,
+        audit_log,
         ..Sut::default()
     };
 
     // Act
-    let result = sut.impersonate(&"token".into(), FOO.user.id).await;
+    let result = sut
+        .impersonate(
+            &"token".into(),
+            FOO.user.id,
+            FOO_AUDIT_EVENT_2.client.clone(),
+        )
+        .await;
 
     // Assert
     assert_eq!(result.unwrap(), expected);
@@ -57,7 +81,9 @@ async fn unauthenticated() {
     };
 
     // Act
-    let result = sut.impersonate(&"token".into(), FOO.user.id).await;
+    let result = sut
+        .impersonate(&"token".into(), FOO.user.id, ClientInfo::default())
+        .await;
 
     // Assert
     assert_matches!(
@@ -79,13 +105,15 @@ async fn unauthorized() {
     };
 
     // Act
-    let result = sut.impersonate(&"token".into(), FOO.user.id).await;
+    let result = sut
+        .impersonate(&"token".into(), FOO.user.id, ClientInfo::default())
+        .await;
 
     // Assert
     assert_matches!(
         result,
         Err(SessionImpersonateError::Auth(AuthError::Authorize(
-            AuthorizeError::Admin
+            AuthorizeError::Permission
         )))
     );
 }
@@ -108,8 +136,40 @@ async fn user_not_found() {
     };
 
     // Act
-    let result = sut.impersonate(&"token".into(), FOO.user.id).await;
+    let result = sut
+        .impersonate(&"token".into(), FOO.user.id, ClientInfo::default())
+        .await;
 
     // Assert
     assert_matches!(result, Err(SessionImpersonateError::NotFound));
 }
+
+#[tokio::test]
+async fn impersonating() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((
+        ADMIN.user.clone(),
+        Session {
+            impersonator_id: Some(ADMIN.user.id),
+            ..ADMIN_1.clone()
+        },
+    )));
+
+    let sut = SessionFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .impersonate(&"token".into(), FOO.user.id, ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(SessionImpersonateError::Auth(AuthError::Authorize(
+            AuthorizeError::Impersonation
+        )))
+    );
+}
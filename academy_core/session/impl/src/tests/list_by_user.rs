@@ -1,11 +1,14 @@
 use academy_auth_contracts::MockAuthService;
-use academy_core_session_contracts::{SessionFeatureService, SessionListByUserError};
+use academy_core_session_contracts::{
+    SessionFeatureService, SessionListByUserError, SessionListResult,
+};
 use academy_demo::{
     session::{ADMIN_1, BAR_1, FOO_1, FOO_2},
     user::{ADMIN, BAR, FOO},
 };
 use academy_models::{
     auth::{AuthError, AuthenticateError, AuthorizeError},
+    pagination::{Pagination, PaginationSlice},
     user::UserIdOrSelf,
 };
 use academy_persistence_contracts::{session::MockSessionRepository, MockDatabase};
@@ -16,14 +19,21 @@ use crate::{tests::Sut, SessionFeatureServiceImpl};
 #[tokio::test]
 async fn ok_self() {
     // Arrange
-    let expected = vec![FOO_1.clone(), FOO_2.clone()];
+    let pagination = Pagination::default();
+    let expected = SessionListResult {
+        sessions: vec![FOO_1.clone(), FOO_2.clone()],
+        next_cursor: None,
+    };
 
     let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
 
     let db = MockDatabase::build(false);
 
-    let session_repo =
-        MockSessionRepository::new().with_list_by_user(FOO.user.id, expected.clone());
+    let session_repo = MockSessionRepository::new().with_list_by_user_paginated(
+        FOO.user.id,
+        pagination,
+        expected.sessions.clone(),
+    );
 
     let sut = SessionFeatureServiceImpl {
         auth,
@@ -33,7 +43,9 @@ async fn ok_self() {
     };
 
     // Act
-    let result = sut.list_by_user(&"token".into(), UserIdOrSelf::Slf).await;
+    let result = sut
+        .list_by_user(&"token".into(), UserIdOrSelf::Slf, pagination)
+        .await;
 
     // Assert
     assert_eq!(result.unwrap(), expected);
@@ -42,15 +54,25 @@ async fn ok_self() {
 #[tokio::test]
 async fn ok_admin() {
     // Arrange
-    let expected = vec![FOO_1.clone(), FOO_2.clone()];
+    let pagination = Pagination::Cursor {
+        limit: 2.try_into().unwrap(),
+        after: Some((&*ADMIN_1).into()),
+    };
+    let expected = SessionListResult {
+        sessions: vec![FOO_1.clone(), FOO_2.clone()],
+        next_cursor: Some((&*FOO_2).into()),
+    };
 
     let auth =
         MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
 
     let db = MockDatabase::build(false);
 
-    let session_repo =
-        MockSessionRepository::new().with_list_by_user(FOO.user.id, expected.clone());
+    let session_repo = MockSessionRepository::new().with_list_by_user_paginated(
+        FOO.user.id,
+        pagination,
+        expected.sessions.clone(),
+    );
 
     let sut = SessionFeatureServiceImpl {
         auth,
@@ -61,7 +83,11 @@ async fn ok_admin() {
 
     // Act
     let result = sut
-        .list_by_user(&"token".into(), UserIdOrSelf::UserId(FOO.user.id))
+        .list_by_user(
+            &"token".into(),
+            UserIdOrSelf::UserId(FOO.user.id),
+            pagination,
+        )
         .await;
 
     // Assert
@@ -79,7 +105,11 @@ async fn unauthenticated() {
 
     // Act
     let result = sut
-        .list_by_user(&"token".into(), UserIdOrSelf::UserId(FOO.user.id))
+        .list_by_user(
+            &"token".into(),
+            UserIdOrSelf::UserId(FOO.user.id),
+            PaginationSlice::default().into(),
+        )
         .await;
 
     // Assert
@@ -102,14 +132,18 @@ async fn unauthorized() {
 
     // Act
     let result = sut
-        .list_by_user(&"token".into(), UserIdOrSelf::UserId(FOO.user.id))
+        .list_by_user(
+            &"token".into(),
+            UserIdOrSelf::UserId(FOO.user.id),
+            PaginationSlice::default().into(),
+        )
         .await;
 
     // Assert
     assert_matches!(
         result,
         Err(SessionListByUserError::Auth(AuthError::Authorize(
-            AuthorizeError::Admin
+            AuthorizeError::Permission
         )))
     );
 }
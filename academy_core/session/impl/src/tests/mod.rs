@@ -1,16 +1,22 @@
+use std::time::Duration;
+
 use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::log::MockAuditLogService;
 use academy_core_mfa_contracts::authenticate::MockMfaAuthenticateService;
 use academy_core_session_contracts::{
-    failed_auth_count::MockSessionFailedAuthCountService, session::MockSessionService,
+    failed_auth_count::MockSessionFailedAuthCountService, login_link::MockSessionLoginLinkService,
+    session::MockSessionService,
 };
+use academy_email_contracts::template::MockTemplateEmailService;
 use academy_persistence_contracts::{
     session::MockSessionRepository, user::MockUserRepository, MockDatabase, MockTransaction,
 };
-use academy_shared_contracts::captcha::MockCaptchaService;
+use academy_shared_contracts::{captcha::MockCaptchaService, throttle::MockThrottleService};
 
 use crate::{SessionFeatureConfig, SessionFeatureServiceImpl};
 
 mod create_session;
+mod create_session_by_login_link;
 mod delete_by_user;
 mod delete_current_session;
 mod delete_session;
@@ -18,22 +24,34 @@ mod get_current_session;
 mod impersonate;
 mod list_by_user;
 mod refresh;
+mod request_login_link;
 
 type Sut = SessionFeatureServiceImpl<
     MockDatabase,
     MockAuthService<MockTransaction>,
     MockCaptchaService,
+    MockThrottleService,
     MockSessionService<MockTransaction>,
     MockSessionFailedAuthCountService,
+    MockSessionLoginLinkService,
     MockMfaAuthenticateService<MockTransaction>,
     MockUserRepository<MockTransaction>,
     MockSessionRepository<MockTransaction>,
+    MockAuditLogService<MockTransaction>,
+    MockTemplateEmailService,
 >;
 
 impl Default for SessionFeatureConfig {
     fn default() -> Self {
         Self {
             login_fails_before_captcha: 3,
+            login_link_code_ttl: Duration::from_secs(15 * 60),
+            login_link_redirect_url: "https://bootstrap.academy/auth/login-link"
+                .to_owned()
+                .into(),
+            refresh_token_history: 5,
+            refresh_token_reuse_email: true,
+            known_device_ttl: Duration::from_secs(30 * 24 * 3600),
         }
     }
 }
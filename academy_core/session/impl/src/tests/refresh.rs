@@ -1,15 +1,24 @@
 use std::time::Duration;
 
 use academy_auth_contracts::{AuthenticateByRefreshTokenError, MockAuthService};
+use academy_core_audit_contracts::log::MockAuditLogService;
 use academy_core_session_contracts::{
     session::MockSessionService, SessionFeatureService, SessionRefreshError,
 };
 use academy_demo::{session::FOO_1, user::FOO};
-use academy_models::{auth::Login, session::Session};
-use academy_persistence_contracts::MockDatabase;
+use academy_email_contracts::template::MockTemplateEmailService;
+use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
+    auth::Login,
+    session::{DeviceName, Session},
+};
+use academy_persistence_contracts::{
+    session::MockSessionRepository, user::MockUserRepository, MockDatabase,
+};
+use academy_templates_contracts::SessionRevokedTemplate;
 use academy_utils::assert_matches;
 
-use crate::{tests::Sut, SessionFeatureServiceImpl};
+use crate::{tests::Sut, SessionFeatureConfig, SessionFeatureServiceImpl};
 
 #[tokio::test]
 async fn ok() {
@@ -39,7 +48,9 @@ async fn ok() {
     };
 
     // Act
-    let result = sut.refresh_session(&"refresh token".into()).await;
+    let result = sut
+        .refresh_session(&"refresh token".into(), ClientInfo::default())
+        .await;
 
     // Assert
     assert_eq!(result.unwrap(), expected);
@@ -62,7 +73,9 @@ async fn invalid_token() {
     };
 
     // Act
-    let result = sut.refresh_session(&"refresh token".into()).await;
+    let result = sut
+        .refresh_session(&"refresh token".into(), ClientInfo::default())
+        .await;
 
     // Assert
     assert_matches!(result, Err(SessionRefreshError::InvalidRefreshToken));
@@ -88,7 +101,9 @@ async fn expired() {
     };
 
     // Act
-    let result = sut.refresh_session(&"refresh token".into()).await;
+    let result = sut
+        .refresh_session(&"refresh token".into(), ClientInfo::default())
+        .await;
 
     // Assert
     assert_matches!(result, Err(SessionRefreshError::InvalidRefreshToken));
@@ -115,7 +130,110 @@ async fn not_found() {
     };
 
     // Act
-    let result = sut.refresh_session(&"refresh token".into()).await;
+    let result = sut
+        .refresh_session(&"refresh token".into(), ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(SessionRefreshError::InvalidRefreshToken));
+}
+
+#[tokio::test]
+async fn reused() {
+    // Arrange
+    let client = ClientInfo {
+        ip_address: Some([1, 2, 3, 4].into()),
+        user_agent: Some("the user agent".into()),
+    };
+
+    let db = MockDatabase::build(true);
+
+    let auth = MockAuthService::new().with_authenticate_by_refresh_token(
+        "refresh token".into(),
+        Err(AuthenticateByRefreshTokenError::Reused(FOO_1.id)),
+    );
+
+    let session = MockSessionService::new().with_delete(FOO_1.id, true);
+
+    let session_repo = MockSessionRepository::new().with_get(FOO_1.id, Some(FOO_1.clone()));
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let audit_log = MockAuditLogService::new().with_record(
+        None,
+        FOO.user.id,
+        AuditEventKind::RefreshTokenReused,
+        client.clone(),
+    );
+
+    let template_email = MockTemplateEmailService::new().with_send_session_revoked_email(
+        FOO.user
+            .email
+            .clone()
+            .unwrap()
+            .with_name(FOO.profile.display_name.clone().into_inner()),
+        SessionRevokedTemplate {
+            device_name: FOO_1.device_name.clone().map(DeviceName::into_inner),
+        },
+        true,
+    );
+
+    let sut = SessionFeatureServiceImpl {
+        db,
+        auth,
+        session,
+        session_repo,
+        user_repo,
+        audit_log,
+        template_email,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.refresh_session(&"refresh token".into(), client).await;
+
+    // Assert
+    assert_matches!(result, Err(SessionRefreshError::InvalidRefreshToken));
+}
+
+#[tokio::test]
+async fn reused_no_email() {
+    // Arrange
+    let db = MockDatabase::build(true);
+
+    let auth = MockAuthService::new().with_authenticate_by_refresh_token(
+        "refresh token".into(),
+        Err(AuthenticateByRefreshTokenError::Reused(FOO_1.id)),
+    );
+
+    let session = MockSessionService::new().with_delete(FOO_1.id, true);
+
+    let session_repo = MockSessionRepository::new().with_get(FOO_1.id, Some(FOO_1.clone()));
+
+    let audit_log = MockAuditLogService::new().with_record(
+        None,
+        FOO.user.id,
+        AuditEventKind::RefreshTokenReused,
+        ClientInfo::default(),
+    );
+
+    let sut = SessionFeatureServiceImpl {
+        db,
+        auth,
+        session,
+        session_repo,
+        audit_log,
+        config: SessionFeatureConfig {
+            refresh_token_reuse_email: false,
+            ..Default::default()
+        },
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .refresh_session(&"refresh token".into(), ClientInfo::default())
+        .await;
 
     // Assert
     assert_matches!(result, Err(SessionRefreshError::InvalidRefreshToken));
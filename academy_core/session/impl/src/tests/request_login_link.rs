@@ -0,0 +1,141 @@
+use academy_core_session_contracts::{
+    failed_auth_count::MockSessionFailedAuthCountService, login_link::MockSessionLoginLinkService,
+    SessionFeatureService, SessionRequestLoginLinkError,
+};
+use academy_demo::user::FOO;
+use academy_models::{email_address::EmailAddress, user::UserNameOrEmailAddress};
+use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
+use academy_shared_contracts::captcha::{CaptchaCheckError, MockCaptchaService};
+use academy_utils::assert_matches;
+
+use crate::{tests::Sut, SessionFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok() {
+    // Arrange
+    let email = FOO.user.email.clone().unwrap();
+
+    let db = MockDatabase::build(false);
+
+    let session_failed_auth_count = MockSessionFailedAuthCountService::new()
+        .with_get(UserNameOrEmailAddress::Email(email.clone()), 0)
+        .with_increment(UserNameOrEmailAddress::Email(email.clone()));
+
+    let user_repo =
+        MockUserRepository::new().with_get_composite_by_email(email.clone(), Some(FOO.clone()));
+
+    let session_login_link = MockSessionLoginLinkService::new().with_request(
+        FOO.user.id,
+        email
+            .clone()
+            .with_name(FOO.profile.display_name.clone().into_inner()),
+    );
+
+    let sut = SessionFeatureServiceImpl {
+        db,
+        session_failed_auth_count,
+        session_login_link,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.request_login_link(email, None).await;
+
+    // Assert
+    result.unwrap();
+}
+
+#[tokio::test]
+async fn ok_captcha() {
+    // Arrange
+    let email = FOO.user.email.clone().unwrap();
+
+    let db = MockDatabase::build(false);
+
+    let session_failed_auth_count = MockSessionFailedAuthCountService::new()
+        .with_get(UserNameOrEmailAddress::Email(email.clone()), 3)
+        .with_increment(UserNameOrEmailAddress::Email(email.clone()));
+
+    let captcha = MockCaptchaService::new().with_check(Some("resp"), Ok(()));
+
+    let user_repo =
+        MockUserRepository::new().with_get_composite_by_email(email.clone(), Some(FOO.clone()));
+
+    let session_login_link = MockSessionLoginLinkService::new().with_request(
+        FOO.user.id,
+        email
+            .clone()
+            .with_name(FOO.profile.display_name.clone().into_inner()),
+    );
+
+    let sut = SessionFeatureServiceImpl {
+        db,
+        session_failed_auth_count,
+        captcha,
+        session_login_link,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .request_login_link(email, Some("resp".try_into().unwrap()))
+        .await;
+
+    // Assert
+    result.unwrap();
+}
+
+#[tokio::test]
+async fn user_not_found() {
+    // Arrange
+    let email = EmailAddress::try_from("nobody@example.com").unwrap();
+
+    let db = MockDatabase::build(false);
+
+    let session_failed_auth_count = MockSessionFailedAuthCountService::new()
+        .with_get(UserNameOrEmailAddress::Email(email.clone()), 0)
+        .with_increment(UserNameOrEmailAddress::Email(email.clone()));
+
+    let user_repo = MockUserRepository::new().with_get_composite_by_email(email.clone(), None);
+
+    let sut = SessionFeatureServiceImpl {
+        db,
+        session_failed_auth_count,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.request_login_link(email, None).await;
+
+    // Assert
+    result.unwrap();
+}
+
+#[tokio::test]
+async fn invalid_recaptcha_response() {
+    // Arrange
+    let email = FOO.user.email.clone().unwrap();
+
+    let session_failed_auth_count = MockSessionFailedAuthCountService::new()
+        .with_get(UserNameOrEmailAddress::Email(email.clone()), 4);
+
+    let captcha =
+        MockCaptchaService::new().with_check(Some("resp"), Err(CaptchaCheckError::Failed));
+
+    let sut = SessionFeatureServiceImpl {
+        session_failed_auth_count,
+        captcha,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .request_login_link(email, Some("resp".try_into().unwrap()))
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(SessionRequestLoginLinkError::Recaptcha));
+}
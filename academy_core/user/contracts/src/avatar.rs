@@ -0,0 +1,55 @@
+use std::future::Future;
+
+use academy_models::user::UserAvatar;
+use thiserror::Error;
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait UserAvatarService: Send + Sync + 'static {
+    /// Validate the given image, crop it to a square, scale it to all
+    /// [`UserAvatarSize`](academy_models::user::UserAvatarSize)s and store the
+    /// results.
+    fn upload(
+        &self,
+        data: Vec<u8>,
+    ) -> impl Future<Output = Result<UserAvatar, UserAvatarUploadError>> + Send;
+
+    /// Remove all images of the given avatar from the storage.
+    fn remove(&self, avatar: &UserAvatar) -> impl Future<Output = anyhow::Result<()>> + Send;
+}
+
+#[derive(Debug, Error)]
+pub enum UserAvatarUploadError {
+    #[error("The image is too large.")]
+    TooLarge,
+    #[error("The image format is not supported.")]
+    UnsupportedFormat,
+    #[error("The image is invalid.")]
+    InvalidImage,
+    #[error("The image dimensions are too small or too large.")]
+    InvalidDimensions,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[cfg(feature = "mock")]
+impl MockUserAvatarService {
+    pub fn with_upload(
+        mut self,
+        data: Vec<u8>,
+        result: Result<UserAvatar, UserAvatarUploadError>,
+    ) -> Self {
+        self.expect_upload()
+            .once()
+            .with(mockall::predicate::eq(data))
+            .return_once(|_| Box::pin(std::future::ready(result)));
+        self
+    }
+
+    pub fn with_remove(mut self, avatar: UserAvatar) -> Self {
+        self.expect_remove()
+            .once()
+            .with(mockall::predicate::eq(avatar))
+            .return_once(|_| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
+}
@@ -0,0 +1,83 @@
+use std::future::Future;
+
+use academy_models::{
+    user::{UserComposite, UserId},
+    VerificationCode,
+};
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait UserDeletionService<Txn: Send + Sync + 'static>: Send + Sync + 'static {
+    /// Mark the given user for deletion and log them out on all devices.
+    ///
+    /// The account is deleted permanently after the configured grace period.
+    fn schedule(
+        &self,
+        txn: &mut Txn,
+        user_composite: &UserComposite,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Send the given user a link to cancel the pending deletion of their
+    /// account, if they have an email address.
+    ///
+    /// Must only be called after the scheduled deletion has been committed to
+    /// the database. Failures are logged instead of returned.
+    fn send_deleted_email(&self, user_composite: &UserComposite)
+        -> impl Future<Output = ()> + Send;
+
+    /// Cancel the pending deletion of the given user.
+    fn restore(
+        &self,
+        txn: &mut Txn,
+        user_id: UserId,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Cancel a pending deletion using the code from the cancellation link.
+    ///
+    /// Returns the id of the restored user or `None` if the code is invalid.
+    fn restore_by_code(
+        &self,
+        txn: &mut Txn,
+        code: &VerificationCode,
+    ) -> impl Future<Output = anyhow::Result<Option<UserId>>> + Send;
+}
+
+#[cfg(feature = "mock")]
+impl<Txn: Send + Sync + 'static> MockUserDeletionService<Txn> {
+    pub fn with_schedule(mut self, user_composite: UserComposite) -> Self {
+        self.expect_schedule()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(user_composite),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
+
+    pub fn with_send_deleted_email(mut self, user_composite: UserComposite) -> Self {
+        self.expect_send_deleted_email()
+            .once()
+            .with(mockall::predicate::eq(user_composite))
+            .return_once(|_| Box::pin(std::future::ready(())));
+        self
+    }
+
+    pub fn with_restore(mut self, user_id: UserId) -> Self {
+        self.expect_restore()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(user_id),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
+
+    pub fn with_restore_by_code(mut self, code: VerificationCode, result: Option<UserId>) -> Self {
+        self.expect_restore_by_code()
+            .once()
+            .with(mockall::predicate::always(), mockall::predicate::eq(code))
+            .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+}
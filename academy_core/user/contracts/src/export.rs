@@ -0,0 +1,106 @@
+use std::future::Future;
+
+use academy_models::{
+    email_address::EmailAddressWithName,
+    user::{UserComposite, UserExport, UserId},
+    VerificationCode,
+};
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait UserExportService<Txn: Send + Sync + 'static>: Send + Sync + 'static {
+    /// Collect all data stored about the given user.
+    fn export(
+        &self,
+        txn: &mut Txn,
+        user_composite: UserComposite,
+    ) -> impl Future<Output = anyhow::Result<UserExport>> + Send;
+
+    /// Return whether the export of the given user is too large to be
+    /// returned directly and should be sent via email instead.
+    fn is_large(
+        &self,
+        txn: &mut Txn,
+        user_id: UserId,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    /// Queue an export of the given user's data, which is later sent to them
+    /// via email.
+    fn request_export(
+        &self,
+        txn: &mut Txn,
+        user_id: UserId,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Store the given export and send a link to download it to the given
+    /// email address.
+    fn send_download_link(
+        &self,
+        email: EmailAddressWithName,
+        export: &UserExport,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Return the export which has been stored for the given download code.
+    fn get_download(
+        &self,
+        code: &VerificationCode,
+    ) -> impl Future<Output = anyhow::Result<Option<UserExport>>> + Send;
+}
+
+#[cfg(feature = "mock")]
+impl<Txn: Send + Sync + 'static> MockUserExportService<Txn> {
+    pub fn with_export(mut self, user_composite: UserComposite, result: UserExport) -> Self {
+        self.expect_export()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(user_composite),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_is_large(mut self, user_id: UserId, result: bool) -> Self {
+        self.expect_is_large()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(user_id),
+            )
+            .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_request_export(mut self, user_id: UserId) -> Self {
+        self.expect_request_export()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(user_id),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
+
+    pub fn with_send_download_link(
+        mut self,
+        email: EmailAddressWithName,
+        export: UserExport,
+    ) -> Self {
+        self.expect_send_download_link()
+            .once()
+            .with(
+                mockall::predicate::eq(email),
+                mockall::predicate::eq(export),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
+
+    pub fn with_get_download(mut self, code: VerificationCode, result: Option<UserExport>) -> Self {
+        self.expect_get_download()
+            .once()
+            .with(mockall::predicate::eq(code))
+            .return_once(|_| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+}
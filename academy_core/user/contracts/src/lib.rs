@@ -1,29 +1,36 @@
-use std::future::Future;
+use std::{collections::BTreeSet, future::Future};
 
 use academy_models::{
+    audit::ClientInfo,
     auth::{AccessToken, AuthError, Login},
     email_address::EmailAddress,
     oauth2::OAuth2RegistrationToken,
+    pagination::PaginationSlice,
+    role::Role,
     session::DeviceName,
     user::{
-        UserComposite, UserDisplayName, UserIdOrSelf, UserInvoiceInfo, UserName, UserPassword,
-        UserProfilePatch,
+        UserComposite, UserDisplayName, UserExport, UserId, UserIdOrSelf, UserInvoiceInfo,
+        UserName, UserPassword, UserPasswordPolicyViolation, UserProfilePatch, UserPublicProfile,
     },
-    RecaptchaResponse, VerificationCode,
+    RecaptchaResponse, SearchTerm, VerificationCode,
 };
 use academy_utils::patch::PatchValue;
 use chrono::{DateTime, Utc};
 use thiserror::Error;
 use user::{UserListQuery, UserListResult};
 
+pub mod avatar;
+pub mod deletion;
 pub mod email_confirmation;
+pub mod export;
+pub mod password_policy;
 pub mod update;
 pub mod user;
 
 pub trait UserFeatureService: Send + Sync + 'static {
     /// Return all users matching the given query.
     ///
-    /// Requires admin privileges.
+    /// Requires the `user_read` permission.
     fn list_users(
         &self,
         token: &AccessToken,
@@ -32,58 +39,116 @@ pub trait UserFeatureService: Send + Sync + 'static {
 
     /// Return the user with the given id.
     ///
-    /// Requires admin privileges if not used on the authenticated user.
+    /// Requires the `user_read` permission if not used on the authenticated
+    /// user.
     fn get_user(
         &self,
         token: &AccessToken,
         user_id: UserIdOrSelf,
     ) -> impl Future<Output = Result<UserComposite, UserGetError>> + Send;
 
+    /// Return the public profile of the given user.
+    ///
+    /// Only the fields the user has chosen to make public are included.
+    /// Disabled users and users pending deletion have no public profile.
+    fn get_public_profile(
+        &self,
+        user_id: UserId,
+    ) -> impl Future<Output = Result<UserPublicProfile, UserGetPublicProfileError>> + Send;
+
+    /// Return the public profiles of all active users matching the given
+    /// full-text search query, ordered by relevance.
+    fn search_profiles(
+        &self,
+        query: SearchTerm,
+        pagination: PaginationSlice,
+    ) -> impl Future<Output = anyhow::Result<UserProfileSearchResult>> + Send;
+
     /// Create a new user and logs them in.
     fn create_user(
         &self,
         request: UserCreateRequest,
         device_name: Option<DeviceName>,
         recaptcha_response: Option<RecaptchaResponse>,
+        client: ClientInfo,
     ) -> impl Future<Output = Result<Login, UserCreateError>> + Send;
 
     /// Update a user.
     ///
     /// - Changing the email address will also set `email_verified` to `false`.
     /// - Disabling a user will also log them out.
-    /// - A user can never change their own admin status.
+    /// - A user can never change their own roles.
     /// - A user can never disable themselves.
     ///
-    /// If the authenticated user is not an administrator:
-    /// - Only the authenticated user itself can be updated.
+    /// Updating other users requires the `user_write` permission, except for
+    /// the profile (`user_profile_write`) and the invoice information
+    /// (`user_invoice_info_write`). Changing `enabled` or `email_verified`
+    /// always requires the `user_write` permission and changing `roles`
+    /// always requires the `role_assign` permission.
+    ///
+    /// If the authenticated user does not have the `user_write` permission:
     /// - Changing the `name` is rate-limited.
     /// - Changing the `newsletter` field from `false` to `true` does not
     ///   immediately update the field's value but rather results in a
     ///   verification email being sent to the user.
-    /// - Changing any of the following fields is not allowed:
-    ///   - `enabled`
-    ///   - `admin`
-    ///   - `email_verified`
+    ///
+    /// In sessions created to impersonate the user, the `email` and `password`
+    /// cannot be changed.
     fn update_user(
         &self,
         token: &AccessToken,
         user_id: UserIdOrSelf,
         request: UserUpdateRequest,
+        client: ClientInfo,
     ) -> impl Future<Output = Result<UserComposite, UserUpdateError>> + Send;
 
-    /// Delete a user.
+    /// Upload a new avatar for a user, replacing the previous one.
+    ///
+    /// The image is cropped to a square and stored in several standard sizes.
     ///
-    /// Requires admin privileges if not used on the authenticated user.
+    /// Requires the `user_profile_write` permission if not used on the authenticated
+    /// user.
+    fn update_avatar(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        data: Vec<u8>,
+        client: ClientInfo,
+    ) -> impl Future<Output = Result<UserComposite, UserUpdateAvatarError>> + Send;
+
+    /// Remove the avatar of a user.
+    ///
+    /// Requires the `user_profile_write` permission if not used on the authenticated
+    /// user.
+    fn delete_avatar(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        client: ClientInfo,
+    ) -> impl Future<Output = Result<UserComposite, UserDeleteAvatarError>> + Send;
+
+    /// Schedule a user for deletion.
+    ///
+    /// The user is logged out immediately and cannot log in again. The account
+    /// is deleted permanently after the configured grace period, unless the
+    /// deletion is cancelled using the link sent to the user's email address.
+    ///
+    /// Requires the `user_write` permission if not used on the authenticated
+    /// user.
+    ///
+    /// Not allowed in sessions created to impersonate the user.
     fn delete_user(
         &self,
         token: &AccessToken,
         user_id: UserIdOrSelf,
+        client: ClientInfo,
     ) -> impl Future<Output = Result<(), UserDeleteError>> + Send;
 
     /// Request an email with a verification code to verify a user's email
     /// address.
     ///
-    /// Requires admin privileges if not used on the authenticated user.
+    /// Requires the `user_verification_email_send` permission if not used on the authenticated
+    /// user.
     fn request_verification_email(
         &self,
         token: &AccessToken,
@@ -91,15 +156,19 @@ pub trait UserFeatureService: Send + Sync + 'static {
     ) -> impl Future<Output = Result<(), UserRequestVerificationEmailError>> + Send;
 
     /// Verify a user's email address using the verification code.
+    ///
+    /// Failed attempts are counted per client IP address.
     fn verify_email(
         &self,
         code: VerificationCode,
+        client: ClientInfo,
     ) -> impl Future<Output = Result<(), UserVerifyEmailError>> + Send;
 
     /// Verifie the newsletter subscription using the verification code sent
     /// via email.
     ///
-    /// Requires admin privileges if not used on the authenticated user.
+    /// Requires the `user_write` permission if not used on the authenticated
+    /// user.
     fn verify_newsletter_subscription(
         &self,
         token: &AccessToken,
@@ -108,10 +177,13 @@ pub trait UserFeatureService: Send + Sync + 'static {
     ) -> impl Future<Output = Result<UserComposite, UserVerifyNewsletterSubscriptionError>> + Send;
 
     /// Request an email with a verification code to reset a user's password.
+    ///
+    /// Requests are counted per email address and per client IP address.
     fn request_password_reset(
         &self,
         email: EmailAddress,
         recaptcha_response: Option<RecaptchaResponse>,
+        client: ClientInfo,
     ) -> impl Future<Output = Result<(), UserRequestPasswordResetError>> + Send;
 
     /// Reset a user's password using the verification code sent via email.
@@ -120,11 +192,41 @@ pub trait UserFeatureService: Send + Sync + 'static {
         email: EmailAddress,
         code: VerificationCode,
         new_password: UserPassword,
+        client: ClientInfo,
     ) -> impl Future<Output = Result<UserComposite, UserResetPasswordError>> + Send;
+
+    /// Cancel a pending user deletion using the code sent via email.
+    fn restore_user(
+        &self,
+        code: VerificationCode,
+        client: ClientInfo,
+    ) -> impl Future<Output = Result<(), UserRestoreError>> + Send;
+
+    /// Export all data stored about a user.
+    ///
+    /// If the export is too large to be returned directly, it is generated in
+    /// the background and a download link is sent to the user's email address
+    /// instead.
+    ///
+    /// Requires the `user_read` permission if not used on the authenticated
+    /// user.
+    fn export_user(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+    ) -> impl Future<Output = Result<UserExportResult, UserExportError>> + Send;
+
+    /// Return a user export using the download code sent via email.
+    fn download_user_export(
+        &self,
+        code: VerificationCode,
+    ) -> impl Future<Output = Result<UserExport, UserDownloadExportError>> + Send;
 }
 
 #[derive(Debug, Error)]
 pub enum UserListError {
+    #[error("The pagination cursor is invalid.")]
+    InvalidCursor,
     #[error(transparent)]
     Auth(#[from] AuthError),
     #[error(transparent)]
@@ -141,6 +243,14 @@ pub enum UserGetError {
     Other(#[from] anyhow::Error),
 }
 
+#[derive(Debug, Error)]
+pub enum UserGetPublicProfileError {
+    #[error("The user does not exist.")]
+    NotFound,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
 #[derive(Debug)]
 pub struct UserCreateRequest {
     pub name: UserName,
@@ -160,6 +270,8 @@ pub enum UserCreateError {
     Recaptcha,
     #[error("No login method has been provided.")]
     NoLoginMethod,
+    #[error("The password does not satisfy the password policy.")]
+    WeakPassword(Vec<UserPasswordPolicyViolation>),
     #[error("The oauth registration token is invalid or has expired.")]
     InvalidOAuthRegistrationToken,
     #[error("The remote user has already been linked.")]
@@ -182,7 +294,7 @@ pub struct UserUpdateUserRequest {
     pub email_verified: PatchValue<bool>,
     pub password: PatchValue<PasswordUpdate>,
     pub enabled: PatchValue<bool>,
-    pub admin: PatchValue<bool>,
+    pub roles: PatchValue<BTreeSet<Role>>,
     pub newsletter: PatchValue<bool>,
 }
 
@@ -207,9 +319,11 @@ pub enum UserUpdateError {
          methods."
     )]
     CannotRemovePassword,
+    #[error("The password does not satisfy the password policy.")]
+    WeakPassword(Vec<UserPasswordPolicyViolation>),
     #[error("The user cannot disable their own account.")]
     CannotDisableSelf,
-    #[error("The user cannot change their own admin status.")]
+    #[error("The user cannot change their own roles.")]
     CannotDemoteSelf,
     #[error("The user cannot change their name until {until}.")]
     NameChangeRateLimit { until: DateTime<Utc> },
@@ -221,6 +335,34 @@ pub enum UserUpdateError {
     Other(#[from] anyhow::Error),
 }
 
+#[derive(Debug, Error)]
+pub enum UserUpdateAvatarError {
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+    #[error("The user does not exist.")]
+    NotFound,
+    #[error("The image is too large.")]
+    TooLarge,
+    #[error("The image format is not supported.")]
+    UnsupportedFormat,
+    #[error("The image is invalid.")]
+    InvalidImage,
+    #[error("The image dimensions are too small or too large.")]
+    InvalidDimensions,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum UserDeleteAvatarError {
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+    #[error("The user does not exist.")]
+    NotFound,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
 #[derive(Debug, Error)]
 pub enum UserDeleteError {
     #[error(transparent)]
@@ -231,6 +373,14 @@ pub enum UserDeleteError {
     Other(#[from] anyhow::Error),
 }
 
+#[derive(Debug, Error)]
+pub enum UserRestoreError {
+    #[error("The restore code is invalid or has expired.")]
+    InvalidCode,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
 #[derive(Debug, Error)]
 pub enum UserRequestVerificationEmailError {
     #[error(transparent)]
@@ -249,6 +399,8 @@ pub enum UserRequestVerificationEmailError {
 pub enum UserVerifyEmailError {
     #[error("The verification code is invalid.")]
     InvalidCode,
+    #[error("Too many failed attempts. Try again after {until}.")]
+    RateLimit { until: DateTime<Utc> },
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -271,6 +423,8 @@ pub enum UserVerifyNewsletterSubscriptionError {
 pub enum UserRequestPasswordResetError {
     #[error("Invalid recaptcha response")]
     Recaptcha,
+    #[error("Too many requests. Try again after {until}.")]
+    RateLimit { until: DateTime<Utc> },
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -279,6 +433,41 @@ pub enum UserRequestPasswordResetError {
 pub enum UserResetPasswordError {
     #[error("The email or verification code is invalid.")]
     Failed,
+    #[error("The password does not satisfy the password policy.")]
+    WeakPassword(Vec<UserPasswordPolicyViolation>),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserProfileSearchResult {
+    /// The total number of profiles matching the query
+    pub total: u64,
+    pub profiles: Vec<UserPublicProfile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserExportResult {
+    /// The export is returned directly.
+    Complete(Box<UserExport>),
+    /// The export is generated in the background and sent via email.
+    Scheduled,
+}
+
+#[derive(Debug, Error)]
+pub enum UserExportError {
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+    #[error("The user does not exist.")]
+    NotFound,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum UserDownloadExportError {
+    #[error("The download code is invalid or has expired.")]
+    InvalidCode,
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
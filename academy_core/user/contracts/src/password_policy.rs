@@ -0,0 +1,34 @@
+use std::future::Future;
+
+use academy_models::user::{UserPassword, UserPasswordPolicyViolation};
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait UserPasswordPolicyService: Send + Sync + 'static {
+    /// Check the given password against the password policy and return all
+    /// rules it violates. Words in `user_inputs` (e.g. the user's name or
+    /// email address) are considered easy to guess.
+    fn check(
+        &self,
+        password: &UserPassword,
+        user_inputs: Vec<String>,
+    ) -> impl Future<Output = anyhow::Result<Vec<UserPasswordPolicyViolation>>> + Send;
+}
+
+#[cfg(feature = "mock")]
+impl MockUserPasswordPolicyService {
+    pub fn with_check(
+        mut self,
+        password: UserPassword,
+        user_inputs: Vec<String>,
+        result: Vec<UserPasswordPolicyViolation>,
+    ) -> Self {
+        self.expect_check()
+            .once()
+            .with(
+                mockall::predicate::eq(password),
+                mockall::predicate::eq(user_inputs),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+}
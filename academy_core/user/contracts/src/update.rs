@@ -1,7 +1,8 @@
-use std::future::Future;
+use std::{collections::BTreeSet, future::Future};
 
 use academy_models::{
     email_address::EmailAddress,
+    role::Role,
     user::{User, UserId, UserInvoiceInfo, UserInvoiceInfoPatch, UserName, UserPassword},
 };
 use chrono::{DateTime, Utc};
@@ -43,12 +44,12 @@ pub trait UserUpdateService<Txn: Send + Sync + 'static>: Send + Sync + 'static {
         enabled: bool,
     ) -> impl Future<Output = anyhow::Result<bool>> + Send;
 
-    /// Update whether a user is an administrator or not.
-    fn update_admin(
+    /// Update the roles of a user.
+    fn update_roles(
         &self,
         txn: &mut Txn,
         user_id: UserId,
-        admin: bool,
+        roles: BTreeSet<Role>,
     ) -> impl Future<Output = anyhow::Result<bool>> + Send;
 
     /// Update a user's invoice information.
@@ -149,13 +150,18 @@ impl<Txn: Send + Sync + 'static> MockUserUpdateService<Txn> {
         self
     }
 
-    pub fn with_update_admin(mut self, user_id: UserId, admin: bool, result: bool) -> Self {
-        self.expect_update_admin()
+    pub fn with_update_roles(
+        mut self,
+        user_id: UserId,
+        roles: BTreeSet<Role>,
+        result: bool,
+    ) -> Self {
+        self.expect_update_roles()
             .once()
             .with(
                 mockall::predicate::always(),
                 mockall::predicate::eq(user_id),
-                mockall::predicate::eq(admin),
+                mockall::predicate::eq(roles),
             )
             .return_once(move |_, _, _| Box::pin(std::future::ready(Ok(result))));
         self
@@ -1,21 +1,24 @@
-use std::future::Future;
+use std::{collections::BTreeSet, future::Future};
 
 use academy_models::{
     email_address::EmailAddress,
     oauth2::OAuth2Registration,
-    pagination::PaginationSlice,
-    user::{UserComposite, UserDisplayName, UserFilter, UserName, UserPassword},
+    pagination::{Pagination, PaginationCursor},
+    role::Role,
+    user::{UserComposite, UserDisplayName, UserFilter, UserName, UserPassword, UserSort},
 };
 use thiserror::Error;
 
 #[cfg_attr(feature = "mock", mockall::automock)]
 pub trait UserService<Txn: Send + Sync + 'static>: Send + Sync + 'static {
     /// Return a paginated and filtered list of all users.
+    ///
+    /// Returns `None` if the pagination cursor is no longer valid.
     fn list(
         &self,
         txn: &mut Txn,
         query: UserListQuery,
-    ) -> impl Future<Output = anyhow::Result<UserListResult>> + Send;
+    ) -> impl Future<Output = anyhow::Result<Option<UserListResult>>> + Send;
 
     /// Create a new user.
     fn create(
@@ -27,14 +30,17 @@ pub trait UserService<Txn: Send + Sync + 'static>: Send + Sync + 'static {
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct UserListQuery {
-    pub pagination: PaginationSlice,
+    pub pagination: Pagination,
     pub filter: UserFilter,
+    pub sort: UserSort,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UserListResult {
     pub total: u64,
     pub user_composites: Vec<UserComposite>,
+    /// The cursor of the next page (`None` if this is the last page)
+    pub next_cursor: Option<PaginationCursor>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -43,7 +49,7 @@ pub struct UserCreateCommand {
     pub display_name: UserDisplayName,
     pub email: EmailAddress,
     pub password: Option<UserPassword>,
-    pub admin: bool,
+    pub roles: BTreeSet<Role>,
     pub enabled: bool,
     pub email_verified: bool,
     pub oauth2_registration: Option<OAuth2Registration>,
@@ -63,7 +69,7 @@ pub enum UserCreateError {
 
 #[cfg(feature = "mock")]
 impl<Txn: Send + Sync + 'static> MockUserService<Txn> {
-    pub fn with_list(mut self, query: UserListQuery, result: UserListResult) -> Self {
+    pub fn with_list(mut self, query: UserListQuery, result: Option<UserListResult>) -> Self {
         self.expect_list()
             .once()
             .with(mockall::predicate::always(), mockall::predicate::eq(query))
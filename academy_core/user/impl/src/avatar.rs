@@ -0,0 +1,214 @@
+use academy_core_user_contracts::avatar::{UserAvatarService, UserAvatarUploadError};
+use academy_di::Build;
+use academy_models::user::{UserAvatar, UserAvatarId, UserAvatarSize};
+use academy_shared_contracts::{
+    id::IdService,
+    image::{ImageCropSquareError, ImageService},
+};
+use academy_storage_contracts::StorageService;
+use academy_utils::trace_instrument;
+use anyhow::Context;
+
+use crate::UserFeatureConfig;
+
+#[derive(Debug, Clone, Build)]
+#[cfg_attr(test, derive(Default))]
+pub struct UserAvatarServiceImpl<Id, Image, Storage> {
+    id: Id,
+    image: Image,
+    storage: Storage,
+    config: UserFeatureConfig,
+}
+
+impl<Id, Image, Storage> UserAvatarService for UserAvatarServiceImpl<Id, Image, Storage>
+where
+    Id: IdService,
+    Image: ImageService,
+    Storage: StorageService,
+{
+    #[trace_instrument(skip(self, data))]
+    async fn upload(&self, data: Vec<u8>) -> Result<UserAvatar, UserAvatarUploadError> {
+        if data.len() > self.config.avatar_max_size {
+            return Err(UserAvatarUploadError::TooLarge);
+        }
+
+        let images = self
+            .image
+            .crop_square(
+                data,
+                self.config.avatar_dimensions.clone(),
+                UserAvatarSize::ALL.map(UserAvatarSize::pixels).into(),
+            )
+            .await
+            .map_err(|err| match err {
+                ImageCropSquareError::UnsupportedFormat => UserAvatarUploadError::UnsupportedFormat,
+                ImageCropSquareError::InvalidImage => UserAvatarUploadError::InvalidImage,
+                ImageCropSquareError::InvalidDimensions => UserAvatarUploadError::InvalidDimensions,
+                ImageCropSquareError::Other(err) => err.context("Failed to process image").into(),
+            })?;
+
+        let id = self.id.generate::<UserAvatarId>();
+
+        for (size, image) in UserAvatarSize::ALL.into_iter().zip(images) {
+            self.storage
+                .put(&UserAvatar::storage_key(id, size), image)
+                .await
+                .context("Failed to store avatar image")?;
+        }
+
+        Ok(UserAvatar {
+            id,
+            url: self.storage.url(&UserAvatar::storage_prefix(id)),
+        })
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn remove(&self, avatar: &UserAvatar) -> anyhow::Result<()> {
+        for size in UserAvatarSize::ALL {
+            self.storage
+                .remove(&UserAvatar::storage_key(avatar.id, size))
+                .await
+                .context("Failed to remove avatar image")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use academy_demo::user::FOO_AVATAR;
+    use academy_shared_contracts::{id::MockIdService, image::MockImageService};
+    use academy_storage_contracts::MockStorageService;
+    use academy_utils::assert_matches;
+
+    use super::*;
+
+    type Sut = UserAvatarServiceImpl<MockIdService, MockImageService, MockStorageService>;
+
+    #[tokio::test]
+    async fn upload_ok() {
+        // Arrange
+        let config = UserFeatureConfig::default();
+        let data = b"avatar".to_vec();
+
+        let image = MockImageService::new().with_crop_square(
+            data.clone(),
+            config.avatar_dimensions.clone(),
+            vec![64, 256, 512],
+            Ok(vec![b"64".into(), b"256".into(), b"512".into()]),
+        );
+
+        let id = MockIdService::new().with_generate(FOO_AVATAR.id);
+
+        let storage = MockStorageService::new()
+            .with_put(
+                format!("avatars/{}/64.webp", FOO_AVATAR.id.hyphenated()),
+                b"64".into(),
+            )
+            .with_put(
+                format!("avatars/{}/256.webp", FOO_AVATAR.id.hyphenated()),
+                b"256".into(),
+            )
+            .with_put(
+                format!("avatars/{}/512.webp", FOO_AVATAR.id.hyphenated()),
+                b"512".into(),
+            )
+            .with_url(
+                format!("avatars/{}/", FOO_AVATAR.id.hyphenated()),
+                FOO_AVATAR.url.clone(),
+            );
+
+        let sut = UserAvatarServiceImpl {
+            id,
+            image,
+            storage,
+            config,
+        };
+
+        // Act
+        let result = sut.upload(data).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), *FOO_AVATAR);
+    }
+
+    #[tokio::test]
+    async fn upload_too_large() {
+        // Arrange
+        let config = UserFeatureConfig::default();
+        let data = vec![0; config.avatar_max_size + 1];
+
+        let sut = UserAvatarServiceImpl {
+            config,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.upload(data).await;
+
+        // Assert
+        assert_matches!(result, Err(UserAvatarUploadError::TooLarge));
+    }
+
+    #[tokio::test]
+    async fn upload_invalid_image() {
+        for (err, expected) in [
+            (
+                ImageCropSquareError::UnsupportedFormat,
+                UserAvatarUploadError::UnsupportedFormat,
+            ),
+            (
+                ImageCropSquareError::InvalidImage,
+                UserAvatarUploadError::InvalidImage,
+            ),
+            (
+                ImageCropSquareError::InvalidDimensions,
+                UserAvatarUploadError::InvalidDimensions,
+            ),
+        ] {
+            // Arrange
+            let config = UserFeatureConfig::default();
+            let data = b"avatar".to_vec();
+
+            let image = MockImageService::new().with_crop_square(
+                data.clone(),
+                config.avatar_dimensions.clone(),
+                vec![64, 256, 512],
+                Err(err),
+            );
+
+            let sut = UserAvatarServiceImpl {
+                image,
+                config,
+                ..Sut::default()
+            };
+
+            // Act
+            let result = sut.upload(data).await;
+
+            // Assert
+            assert_eq!(result.unwrap_err().to_string(), expected.to_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn remove() {
+        // Arrange
+        let storage = MockStorageService::new()
+            .with_remove(format!("avatars/{}/64.webp", FOO_AVATAR.id.hyphenated()))
+            .with_remove(format!("avatars/{}/256.webp", FOO_AVATAR.id.hyphenated()))
+            .with_remove(format!("avatars/{}/512.webp", FOO_AVATAR.id.hyphenated()));
+
+        let sut = UserAvatarServiceImpl {
+            storage,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.remove(&FOO_AVATAR).await;
+
+        // Assert
+        result.unwrap();
+    }
+}
@@ -0,0 +1,353 @@
+use academy_cache_contracts::CacheService;
+use academy_core_session_contracts::session::SessionService;
+use academy_core_user_contracts::deletion::UserDeletionService;
+use academy_di::Build;
+use academy_email_contracts::template::TemplateEmailService;
+use academy_models::{
+    user::{UserComposite, UserId, UserPatchRef},
+    VerificationCode,
+};
+use academy_persistence_contracts::user::UserRepository;
+use academy_shared_contracts::{secret::SecretService, time::TimeService};
+use academy_templates_contracts::UserDeletedTemplate;
+use academy_utils::trace_instrument;
+use anyhow::{anyhow, Context};
+use tracing::error;
+
+use crate::UserFeatureConfig;
+
+#[derive(Debug, Clone, Build)]
+#[cfg_attr(test, derive(Default))]
+pub struct UserDeletionServiceImpl<Time, Secret, TemplateEmail, Cache, Session, UserRepo> {
+    time: Time,
+    secret: Secret,
+    template_email: TemplateEmail,
+    cache: Cache,
+    session: Session,
+    user_repo: UserRepo,
+    config: UserFeatureConfig,
+}
+
+impl<Txn, Time, Secret, TemplateEmail, Cache, Session, UserRepo> UserDeletionService<Txn>
+    for UserDeletionServiceImpl<Time, Secret, TemplateEmail, Cache, Session, UserRepo>
+where
+    Txn: Send + Sync + 'static,
+    Time: TimeService,
+    Secret: SecretService,
+    TemplateEmail: TemplateEmailService,
+    Cache: CacheService,
+    Session: SessionService<Txn>,
+    UserRepo: UserRepository<Txn>,
+{
+    #[trace_instrument(skip(self, txn))]
+    async fn schedule(&self, txn: &mut Txn, user_composite: &UserComposite) -> anyhow::Result<()> {
+        let user_id = user_composite.user.id;
+
+        self.user_repo
+            .update(
+                txn,
+                user_id,
+                UserPatchRef::new().update_deleted_at(&Some(self.time.now())),
+            )
+            .await
+            .map_err(|err| anyhow!(err).context("Failed to update user in database"))?;
+
+        self.session
+            .delete_by_user(txn, user_id)
+            .await
+            .context("Failed to log out user")?;
+
+        Ok(())
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn send_deleted_email(&self, user_composite: &UserComposite) {
+        if let Err(err) = self.try_send_deleted_email(user_composite).await {
+            error!("Failed to send user deleted email: {err:#}");
+        }
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn restore(&self, txn: &mut Txn, user_id: UserId) -> anyhow::Result<()> {
+        self.user_repo
+            .update(txn, user_id, UserPatchRef::new().update_deleted_at(&None))
+            .await
+            .map_err(|err| anyhow!(err).context("Failed to update user in database"))?;
+
+        Ok(())
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn restore_by_code(
+        &self,
+        txn: &mut Txn,
+        code: &VerificationCode,
+    ) -> anyhow::Result<Option<UserId>> {
+        let cache_key = restore_cache_key(code);
+
+        let Some(user_id) = self
+            .cache
+            .get(&cache_key)
+            .await
+            .context("Failed to get user id from cache")?
+        else {
+            return Ok(None);
+        };
+
+        self.restore(txn, user_id).await?;
+
+        self.cache
+            .remove(&cache_key)
+            .await
+            .context("Failed to remove code from cache")?;
+
+        Ok(Some(user_id))
+    }
+}
+
+impl<Time, Secret, TemplateEmail, Cache, Session, UserRepo>
+    UserDeletionServiceImpl<Time, Secret, TemplateEmail, Cache, Session, UserRepo>
+where
+    Secret: SecretService,
+    TemplateEmail: TemplateEmailService,
+    Cache: CacheService,
+{
+    async fn try_send_deleted_email(&self, user_composite: &UserComposite) -> anyhow::Result<()> {
+        let user_id = user_composite.user.id;
+
+        let Some(email) = user_composite.user.email.clone() else {
+            return Ok(());
+        };
+
+        let code = self.secret.generate_verification_code();
+
+        self.cache
+            .set(
+                &restore_cache_key(&code),
+                &user_id,
+                Some(self.config.deletion_grace_period),
+            )
+            .await
+            .context("Failed to save code in cache")?;
+
+        self.template_email
+            .send_user_deleted_email(
+                email.with_name(user_composite.profile.display_name.clone().into_inner()),
+                &UserDeletedTemplate {
+                    code: code.into_inner(),
+                    url: (*self.config.restore_redirect_url).clone(),
+                },
+            )
+            .await
+            .context("Failed to send email")?;
+
+        Ok(())
+    }
+}
+
+fn restore_cache_key(code: &VerificationCode) -> String {
+    format!("user_restore:{}", **code)
+}
+
+#[cfg(test)]
+mod tests {
+    use academy_cache_contracts::MockCacheService;
+    use academy_core_session_contracts::session::MockSessionService;
+    use academy_demo::{
+        user::{BAR, FOO},
+        VERIFICATION_CODE_1,
+    };
+    use academy_email_contracts::template::MockTemplateEmailService;
+    use academy_models::user::UserPatch;
+    use academy_persistence_contracts::user::MockUserRepository;
+    use academy_shared_contracts::{secret::MockSecretService, time::MockTimeService};
+
+    use super::*;
+
+    type Sut = UserDeletionServiceImpl<
+        MockTimeService,
+        MockSecretService,
+        MockTemplateEmailService,
+        MockCacheService,
+        MockSessionService<()>,
+        MockUserRepository<()>,
+    >;
+
+    #[tokio::test]
+    async fn schedule() {
+        // Arrange
+        let now = FOO.user.created_at;
+
+        let time = MockTimeService::new().with_now(now);
+
+        let user_repo = MockUserRepository::new().with_update(
+            FOO.user.id,
+            UserPatch::new().update_deleted_at(Some(now)),
+            Ok(true),
+        );
+
+        let session = MockSessionService::new().with_delete_by_user(FOO.user.id);
+
+        let sut = UserDeletionServiceImpl {
+            time,
+            session,
+            user_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.schedule(&mut (), &FOO).await;
+
+        // Assert
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn send_deleted_email() {
+        // Arrange
+        let config = UserFeatureConfig::default();
+
+        let secret =
+            MockSecretService::new().with_generate_verification_code(VERIFICATION_CODE_1.clone());
+
+        let cache = MockCacheService::new().with_set(
+            format!("user_restore:{}", **VERIFICATION_CODE_1),
+            FOO.user.id,
+            Some(config.deletion_grace_period),
+        );
+
+        let template_email = MockTemplateEmailService::new().with_send_user_deleted_email(
+            FOO.user
+                .email
+                .clone()
+                .unwrap()
+                .with_name(FOO.profile.display_name.clone().into_inner()),
+            UserDeletedTemplate {
+                code: VERIFICATION_CODE_1.clone().into_inner(),
+                url: (*config.restore_redirect_url).clone(),
+            },
+            true,
+        );
+
+        let sut = UserDeletionServiceImpl {
+            secret,
+            template_email,
+            cache,
+            config,
+            ..Sut::default()
+        };
+
+        // Act
+        UserDeletionService::<()>::send_deleted_email(&sut, &FOO).await;
+    }
+
+    #[tokio::test]
+    async fn send_deleted_email_no_email() {
+        // Arrange
+        let sut = Sut::default();
+
+        // Act
+        UserDeletionService::<()>::send_deleted_email(&sut, &BAR).await;
+    }
+
+    #[tokio::test]
+    async fn send_deleted_email_error() {
+        // Arrange
+        let config = UserFeatureConfig::default();
+
+        let secret =
+            MockSecretService::new().with_generate_verification_code(VERIFICATION_CODE_1.clone());
+
+        let cache = MockCacheService::new().with_set(
+            format!("user_restore:{}", **VERIFICATION_CODE_1),
+            FOO.user.id,
+            Some(config.deletion_grace_period),
+        );
+
+        let mut template_email = MockTemplateEmailService::new();
+        template_email
+            .expect_send_user_deleted_email()
+            .once()
+            .return_once(|_, _| Box::pin(std::future::ready(Err(anyhow!("smtp error")))));
+
+        let sut = UserDeletionServiceImpl {
+            secret,
+            template_email,
+            cache,
+            config,
+            ..Sut::default()
+        };
+
+        // Act
+        UserDeletionService::<()>::send_deleted_email(&sut, &FOO).await;
+    }
+
+    #[tokio::test]
+    async fn restore() {
+        // Arrange
+        let user_repo = MockUserRepository::new().with_update(
+            FOO.user.id,
+            UserPatch::new().update_deleted_at(None),
+            Ok(true),
+        );
+
+        let sut = UserDeletionServiceImpl {
+            user_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.restore(&mut (), FOO.user.id).await;
+
+        // Assert
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn restore_by_code_ok() {
+        // Arrange
+        let cache_key = format!("user_restore:{}", **VERIFICATION_CODE_1);
+
+        let cache = MockCacheService::new()
+            .with_get(cache_key.clone(), Some(FOO.user.id))
+            .with_remove(cache_key);
+
+        let user_repo = MockUserRepository::new().with_update(
+            FOO.user.id,
+            UserPatch::new().update_deleted_at(None),
+            Ok(true),
+        );
+
+        let sut = UserDeletionServiceImpl {
+            cache,
+            user_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.restore_by_code(&mut (), &VERIFICATION_CODE_1).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), Some(FOO.user.id));
+    }
+
+    #[tokio::test]
+    async fn restore_by_code_invalid_code() {
+        // Arrange
+        let cache = MockCacheService::new().with_get(
+            format!("user_restore:{}", **VERIFICATION_CODE_1),
+            None::<UserId>,
+        );
+
+        let sut = UserDeletionServiceImpl {
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.restore_by_code(&mut (), &VERIFICATION_CODE_1).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), None);
+    }
+}
@@ -0,0 +1,403 @@
+use academy_cache_contracts::CacheService;
+use academy_core_user_contracts::export::UserExportService;
+use academy_di::Build;
+use academy_email_contracts::template::TemplateEmailService;
+use academy_models::{
+    audit::AuditEventFilter,
+    email_address::EmailAddressWithName,
+    pagination::{PaginationLimit, PaginationSlice},
+    user::{UserComposite, UserExport, UserId},
+    VerificationCode,
+};
+use academy_persistence_contracts::{
+    audit::AuditRepository, mfa::MfaRepository, oauth2::OAuth2Repository,
+    session::SessionRepository, user::UserRepository,
+};
+use academy_shared_contracts::{secret::SecretService, time::TimeService};
+use academy_templates_contracts::UserExportTemplate;
+use academy_utils::trace_instrument;
+use anyhow::Context;
+
+use crate::UserFeatureConfig;
+
+#[derive(Debug, Clone, Build)]
+#[cfg_attr(test, derive(Default))]
+pub struct UserExportServiceImpl<
+    Time,
+    Secret,
+    TemplateEmail,
+    Cache,
+    UserRepo,
+    SessionRepo,
+    MfaRepo,
+    OAuth2Repo,
+    AuditRepo,
+> {
+    time: Time,
+    secret: Secret,
+    template_email: TemplateEmail,
+    cache: Cache,
+    user_repo: UserRepo,
+    session_repo: SessionRepo,
+    mfa_repo: MfaRepo,
+    oauth2_repo: OAuth2Repo,
+    audit_repo: AuditRepo,
+    config: UserFeatureConfig,
+}
+
+impl<
+        Txn,
+        Time,
+        Secret,
+        TemplateEmail,
+        Cache,
+        UserRepo,
+        SessionRepo,
+        MfaRepo,
+        OAuth2Repo,
+        AuditRepo,
+    > UserExportService<Txn>
+    for UserExportServiceImpl<
+        Time,
+        Secret,
+        TemplateEmail,
+        Cache,
+        UserRepo,
+        SessionRepo,
+        MfaRepo,
+        OAuth2Repo,
+        AuditRepo,
+    >
+where
+    Txn: Send + Sync + 'static,
+    Time: TimeService,
+    Secret: SecretService,
+    TemplateEmail: TemplateEmailService,
+    Cache: CacheService,
+    UserRepo: UserRepository<Txn>,
+    SessionRepo: SessionRepository<Txn>,
+    MfaRepo: MfaRepository<Txn>,
+    OAuth2Repo: OAuth2Repository<Txn>,
+    AuditRepo: AuditRepository<Txn>,
+{
+    #[trace_instrument(skip(self, txn))]
+    async fn export(
+        &self,
+        txn: &mut Txn,
+        user_composite: UserComposite,
+    ) -> anyhow::Result<UserExport> {
+        let user_id = user_composite.user.id;
+
+        let sessions = self
+            .session_repo
+            .list_by_user(txn, user_id)
+            .await
+            .context("Failed to get sessions from database")?;
+
+        let totp_devices = self
+            .mfa_repo
+            .list_totp_devices_by_user(txn, user_id)
+            .await
+            .context("Failed to get totp devices from database")?;
+
+        let webauthn_credentials = self
+            .mfa_repo
+            .list_webauthn_credentials_by_user(txn, user_id)
+            .await
+            .context("Failed to get webauthn credentials from database")?;
+
+        let oauth2_links = self
+            .oauth2_repo
+            .list_links_by_user(txn, user_id)
+            .await
+            .context("Failed to get oauth2 links from database")?;
+
+        let filter = AuditEventFilter {
+            user_id: Some(user_id),
+            ..Default::default()
+        };
+        let mut audit_events = Vec::new();
+        loop {
+            let page = self
+                .audit_repo
+                .list(
+                    txn,
+                    &filter,
+                    PaginationSlice {
+                        limit: PaginationLimit::max(),
+                        offset: audit_events.len() as _,
+                    },
+                )
+                .await
+                .context("Failed to get audit events from database")?;
+            let done = (page.len() as u64) < PaginationLimit::MAX;
+            audit_events.extend(page);
+            if done {
+                break;
+            }
+        }
+
+        Ok(UserExport {
+            user_composite,
+            sessions,
+            totp_devices,
+            webauthn_credentials,
+            oauth2_links,
+            audit_events,
+        })
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn is_large(&self, txn: &mut Txn, user_id: UserId) -> anyhow::Result<bool> {
+        let filter = AuditEventFilter {
+            user_id: Some(user_id),
+            ..Default::default()
+        };
+
+        self.audit_repo
+            .count(txn, &filter)
+            .await
+            .context("Failed to count audit events in database")
+            .map(|count| count > self.config.export_async_threshold)
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn request_export(&self, txn: &mut Txn, user_id: UserId) -> anyhow::Result<()> {
+        self.user_repo
+            .create_export_request(txn, user_id, self.time.now())
+            .await
+            .context("Failed to save export request in database")
+    }
+
+    #[trace_instrument(skip(self, export))]
+    async fn send_download_link(
+        &self,
+        email: EmailAddressWithName,
+        export: &UserExport,
+    ) -> anyhow::Result<()> {
+        let code = self.secret.generate_verification_code();
+
+        self.cache
+            .set(
+                &export_cache_key(&code),
+                export,
+                Some(self.config.export_download_ttl),
+            )
+            .await
+            .context("Failed to save export in cache")?;
+
+        self.template_email
+            .send_user_export_email(
+                email,
+                &UserExportTemplate {
+                    code: code.into_inner(),
+                    url: (*self.config.export_redirect_url).clone(),
+                },
+            )
+            .await
+            .context("Failed to send email")?;
+
+        Ok(())
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn get_download(&self, code: &VerificationCode) -> anyhow::Result<Option<UserExport>> {
+        self.cache
+            .get(&export_cache_key(code))
+            .await
+            .context("Failed to get export from cache")
+    }
+}
+
+fn export_cache_key(code: &VerificationCode) -> String {
+    format!("user_export:{}", **code)
+}
+
+#[cfg(test)]
+mod tests {
+    use academy_cache_contracts::MockCacheService;
+    use academy_demo::{
+        audit::FOO_AUDIT_EVENT_1, mfa::FOO_TOTP_1, oauth2::FOO_OAUTH2_LINK_1, session::FOO_1,
+        user::FOO, VERIFICATION_CODE_1,
+    };
+    use academy_email_contracts::template::MockTemplateEmailService;
+    use academy_persistence_contracts::{
+        audit::MockAuditRepository, mfa::MockMfaRepository, oauth2::MockOAuth2Repository,
+        session::MockSessionRepository, user::MockUserRepository,
+    };
+    use academy_shared_contracts::{secret::MockSecretService, time::MockTimeService};
+
+    use super::*;
+
+    type Sut = UserExportServiceImpl<
+        MockTimeService,
+        MockSecretService,
+        MockTemplateEmailService,
+        MockCacheService,
+        MockUserRepository<()>,
+        MockSessionRepository<()>,
+        MockMfaRepository<()>,
+        MockOAuth2Repository<()>,
+        MockAuditRepository<()>,
+    >;
+
+    fn foo_export() -> UserExport {
+        UserExport {
+            user_composite: FOO.clone(),
+            sessions: vec![FOO_1.clone()],
+            totp_devices: vec![FOO_TOTP_1.clone()],
+            webauthn_credentials: vec![],
+            oauth2_links: vec![FOO_OAUTH2_LINK_1.clone()],
+            audit_events: vec![FOO_AUDIT_EVENT_1.clone()],
+        }
+    }
+
+    fn audit_filter() -> AuditEventFilter {
+        AuditEventFilter {
+            user_id: Some(FOO.user.id),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn export() {
+        // Arrange
+        let session_repo =
+            MockSessionRepository::new().with_list_by_user(FOO.user.id, vec![FOO_1.clone()]);
+
+        let mfa_repo = MockMfaRepository::new()
+            .with_list_totp_devices_by_user(FOO.user.id, vec![FOO_TOTP_1.clone()])
+            .with_list_webauthn_credentials_by_user(FOO.user.id, vec![]);
+
+        let oauth2_repo = MockOAuth2Repository::new()
+            .with_list_links_by_user(FOO.user.id, vec![FOO_OAUTH2_LINK_1.clone()]);
+
+        let audit_repo = MockAuditRepository::new().with_list(
+            audit_filter(),
+            PaginationSlice::default(),
+            vec![FOO_AUDIT_EVENT_1.clone()],
+        );
+
+        let sut = UserExportServiceImpl {
+            session_repo,
+            mfa_repo,
+            oauth2_repo,
+            audit_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.export(&mut (), FOO.clone()).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), foo_export());
+    }
+
+    #[tokio::test]
+    async fn is_large() {
+        for (count, expected) in [(0, false), (1000, false), (1001, true)] {
+            // Arrange
+            let audit_repo = MockAuditRepository::new().with_count(audit_filter(), count);
+
+            let sut = UserExportServiceImpl {
+                audit_repo,
+                config: UserFeatureConfig {
+                    export_async_threshold: 1000,
+                    ..Default::default()
+                },
+                ..Sut::default()
+            };
+
+            // Act
+            let result = sut.is_large(&mut (), FOO.user.id).await;
+
+            // Assert
+            assert_eq!(result.unwrap(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn request_export() {
+        // Arrange
+        let time = MockTimeService::new().with_now(FOO.user.created_at);
+
+        let user_repo =
+            MockUserRepository::new().with_create_export_request(FOO.user.id, FOO.user.created_at);
+
+        let sut = UserExportServiceImpl {
+            time,
+            user_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.request_export(&mut (), FOO.user.id).await;
+
+        // Assert
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn send_download_link() {
+        // Arrange
+        let config = UserFeatureConfig::default();
+
+        let recipient = FOO
+            .user
+            .email
+            .clone()
+            .unwrap()
+            .with_name(FOO.profile.display_name.clone().into_inner());
+
+        let secret =
+            MockSecretService::new().with_generate_verification_code(VERIFICATION_CODE_1.clone());
+
+        let cache = MockCacheService::new().with_set(
+            format!("user_export:{}", **VERIFICATION_CODE_1),
+            foo_export(),
+            Some(config.export_download_ttl),
+        );
+
+        let template_email = MockTemplateEmailService::new().with_send_user_export_email(
+            recipient.clone(),
+            UserExportTemplate {
+                code: VERIFICATION_CODE_1.clone().into_inner(),
+                url: (*config.export_redirect_url).clone(),
+            },
+            true,
+        );
+
+        let sut = UserExportServiceImpl {
+            secret,
+            template_email,
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.send_download_link(recipient, &foo_export()).await;
+
+        // Assert
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn get_download() {
+        // Arrange
+        let cache = MockCacheService::new().with_get(
+            format!("user_export:{}", **VERIFICATION_CODE_1),
+            Some(foo_export()),
+        );
+
+        let sut = UserExportServiceImpl {
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.get_download(&VERIFICATION_CODE_1).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), Some(foo_export()));
+    }
+}
@@ -1,71 +1,108 @@
-use std::{sync::Arc, time::Duration};
+use std::{ops::RangeInclusive, sync::Arc, time::Duration};
 
 use academy_auth_contracts::{AuthResultExt, AuthService};
+use academy_core_audit_contracts::log::AuditLogService;
 use academy_core_oauth2_contracts::registration::OAuth2RegistrationService;
 use academy_core_session_contracts::session::SessionService;
 use academy_core_user_contracts::{
+    avatar::{UserAvatarService, UserAvatarUploadError},
+    deletion::UserDeletionService,
     email_confirmation::{
         UserEmailConfirmationResetPasswordError, UserEmailConfirmationService,
         UserEmailConfirmationSubscribeToNewsletterError, UserEmailConfirmationVerifyEmailError,
     },
+    export::UserExportService,
+    password_policy::UserPasswordPolicyService,
     update::{
         UserUpdateEmailError, UserUpdateNameError, UserUpdateNameRateLimitPolicy, UserUpdateService,
     },
     user::{UserCreateCommand, UserListQuery, UserListResult, UserService},
-    PasswordUpdate, UserCreateError, UserCreateRequest, UserDeleteError, UserFeatureService,
-    UserGetError, UserListError, UserRequestPasswordResetError, UserRequestVerificationEmailError,
-    UserResetPasswordError, UserUpdateError, UserUpdateRequest, UserUpdateUserRequest,
-    UserVerifyEmailError, UserVerifyNewsletterSubscriptionError,
+    PasswordUpdate, UserCreateError, UserCreateRequest, UserDeleteAvatarError, UserDeleteError,
+    UserDownloadExportError, UserExportError, UserExportResult, UserFeatureService, UserGetError,
+    UserGetPublicProfileError, UserListError, UserProfileSearchResult,
+    UserRequestPasswordResetError, UserRequestVerificationEmailError, UserResetPasswordError,
+    UserRestoreError, UserUpdateAvatarError, UserUpdateError, UserUpdateRequest,
+    UserUpdateUserRequest, UserVerifyEmailError, UserVerifyNewsletterSubscriptionError,
 };
 use academy_di::Build;
 use academy_extern_contracts::{internal::InternalApiService, vat::VatApiService};
 use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
     auth::{AccessToken, Login},
     email_address::EmailAddress,
+    pagination::PaginationSlice,
+    role::Permission,
     session::DeviceName,
-    user::{UserComposite, UserIdOrSelf, UserInvoiceInfoPatch, UserPassword, UserPatchRef},
-    RecaptchaResponse, VerificationCode,
+    user::{
+        UserComposite, UserExport, UserId, UserIdOrSelf, UserInvoiceInfoPatch, UserPassword,
+        UserPasswordCharacterClass, UserPatchRef, UserPublicProfile,
+    },
+    RecaptchaResponse, SearchTerm, VerificationCode,
 };
 use academy_persistence_contracts::{user::UserRepository, Database, Transaction};
-use academy_shared_contracts::captcha::{CaptchaCheckError, CaptchaService};
+use academy_shared_contracts::{
+    captcha::{CaptchaCheckError, CaptchaService},
+    throttle::{ThrottleKey, ThrottleService},
+};
 use academy_utils::{
     patch::{Patch, PatchValue},
     trace_instrument,
 };
 use anyhow::{anyhow, Context};
 
+pub mod avatar;
+pub mod deletion;
 pub mod email_confirmation;
+pub mod export;
+pub mod password_policy;
 pub mod update;
 pub mod user;
 
 #[cfg(test)]
 mod tests;
 
+/// Action of the [`ThrottleKey`]s used to limit password reset requests
+const PASSWORD_RESET_THROTTLE_ACTION: &str = "password_reset";
+/// Action of the [`ThrottleKey`]s used to limit failed email verifications
+const VERIFY_EMAIL_THROTTLE_ACTION: &str = "verify_email";
+
 #[derive(Debug, Clone, Default, Build)]
 pub struct UserFeatureServiceImpl<
     Db,
     Auth,
     Captcha,
+    Throttle,
     VatApi,
     InternalApi,
     User,
     UserEmailConfirmation,
     UserUpdate,
+    UserExport,
+    UserDeletion,
+    UserAvatar,
+    UserPasswordPolicy,
     Session,
     OAuth2Registration,
     UserRepo,
+    AuditLog,
 > {
     db: Db,
     auth: Auth,
     captcha: Captcha,
+    throttle: Throttle,
     vat_api: VatApi,
     internal_api: InternalApi,
     user: User,
     user_email_confirmation: UserEmailConfirmation,
     user_update: UserUpdate,
+    user_export: UserExport,
+    user_deletion: UserDeletion,
+    user_avatar: UserAvatar,
+    user_password_policy: UserPasswordPolicy,
     session: Session,
     oauth2_registration: OAuth2Registration,
     user_repo: UserRepo,
+    audit_log: AuditLog,
 }
 
 #[derive(Debug, Clone)]
@@ -77,46 +114,75 @@ pub struct UserFeatureConfig {
     pub password_reset_verification_code_ttl: Duration,
     pub newsletter_subscription_redirect_url: Arc<String>,
     pub newsletter_subscription_verification_code_ttl: Duration,
+    pub export_async_threshold: u64,
+    pub export_redirect_url: Arc<String>,
+    pub export_download_ttl: Duration,
+    pub deletion_grace_period: Duration,
+    pub restore_redirect_url: Arc<String>,
+    pub avatar_max_size: usize,
+    pub avatar_dimensions: RangeInclusive<u32>,
+    pub password_min_length: usize,
+    pub password_required_character_classes: Arc<[UserPasswordCharacterClass]>,
+    pub password_min_strength: u8,
+    pub password_breach_check: bool,
 }
 
 impl<
         Db,
         Auth,
         Captcha,
+        Throttle,
         VatApi,
         InternalApi,
         UserS,
         UserEmailConfirmation,
         UserUpdate,
+        UserExportS,
+        UserDeletion,
+        UserAvatar,
+        UserPasswordPolicy,
         Session,
         OAuth2RegistrationS,
         UserRepo,
+        AuditLog,
     > UserFeatureService
     for UserFeatureServiceImpl<
         Db,
         Auth,
         Captcha,
+        Throttle,
         VatApi,
         InternalApi,
         UserS,
         UserEmailConfirmation,
         UserUpdate,
+        UserExportS,
+        UserDeletion,
+        UserAvatar,
+        UserPasswordPolicy,
         Session,
         OAuth2RegistrationS,
         UserRepo,
+        AuditLog,
     >
 where
     Db: Database,
     Auth: AuthService<Db::Transaction>,
     Captcha: CaptchaService,
+    Throttle: ThrottleService,
     VatApi: VatApiService,
     InternalApi: InternalApiService,
     UserS: UserService<Db::Transaction>,
     UserEmailConfirmation: UserEmailConfirmationService<Db::Transaction>,
     UserUpdate: UserUpdateService<Db::Transaction>,
+    UserExportS: UserExportService<Db::Transaction>,
+    UserDeletion: UserDeletionService<Db::Transaction>,
+    UserAvatar: UserAvatarService,
+    UserPasswordPolicy: UserPasswordPolicyService,
     Session: SessionService<Db::Transaction>,
     OAuth2RegistrationS: OAuth2RegistrationService,
     UserRepo: UserRepository<Db::Transaction>,
+    AuditLog: AuditLogService<Db::Transaction>,
 {
     #[trace_instrument(skip(self))]
     async fn list_users(
@@ -125,15 +191,16 @@ where
         query: UserListQuery,
     ) -> Result<UserListResult, UserListError> {
         let auth = self.auth.authenticate(token).await.map_auth_err()?;
-        auth.ensure_admin().map_auth_err()?;
+        auth.ensure_permission(Permission::UserRead)
+            .map_auth_err()?;
 
         let mut txn = self.db.begin_transaction().await.unwrap();
 
         self.user
             .list(&mut txn, query)
             .await
-            .context("Failed to list users")
-            .map_err(Into::into)
+            .context("Failed to list users")?
+            .ok_or(UserListError::InvalidCursor)
     }
 
     #[trace_instrument(skip(self))]
@@ -144,7 +211,8 @@ where
     ) -> Result<UserComposite, UserGetError> {
         let auth = self.auth.authenticate(token).await.map_auth_err()?;
         let user_id = user_id.unwrap_or(auth.user_id);
-        auth.ensure_self_or_admin(user_id).map_auth_err()?;
+        auth.ensure_self_or_permission(user_id, Permission::UserRead)
+            .map_auth_err()?;
 
         let mut txn = self.db.begin_transaction().await.unwrap();
 
@@ -155,17 +223,76 @@ where
             .ok_or(UserGetError::NotFound)
     }
 
+    #[trace_instrument(skip(self))]
+    async fn get_public_profile(
+        &self,
+        user_id: UserId,
+    ) -> Result<UserPublicProfile, UserGetPublicProfileError> {
+        let mut txn = self.db.begin_transaction().await?;
+
+        self.user_repo
+            .get_composite(&mut txn, user_id)
+            .await
+            .context("Failed to get user from database")?
+            .filter(|user_composite| user_composite.user.can_login())
+            .map(Into::into)
+            .ok_or(UserGetPublicProfileError::NotFound)
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn search_profiles(
+        &self,
+        query: SearchTerm,
+        pagination: PaginationSlice,
+    ) -> anyhow::Result<UserProfileSearchResult> {
+        let mut txn = self.db.begin_transaction().await?;
+
+        let total = self
+            .user_repo
+            .count_search_profiles(&mut txn, &query)
+            .await
+            .context("Failed to count matching profiles in database")?;
+
+        let profiles = self
+            .user_repo
+            .search_profiles(&mut txn, &query, pagination)
+            .await
+            .context("Failed to search profiles in database")?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        Ok(UserProfileSearchResult { total, profiles })
+    }
+
     #[trace_instrument(skip(self))]
     async fn create_user(
         &self,
         request: UserCreateRequest,
         device_name: Option<DeviceName>,
         recaptcha_response: Option<RecaptchaResponse>,
+        client: ClientInfo,
     ) -> Result<Login, UserCreateError> {
         if request.password.is_none() && request.oauth2_registration_token.is_none() {
             return Err(UserCreateError::NoLoginMethod);
         }
 
+        if let Some(password) = &request.password {
+            let user_inputs = password_policy_user_inputs(
+                &request.name,
+                &request.display_name,
+                Some(&request.email),
+            );
+            let violations = self
+                .user_password_policy
+                .check(password, user_inputs)
+                .await
+                .context("Failed to check password policy")?;
+            if !violations.is_empty() {
+                return Err(UserCreateError::WeakPassword(violations));
+            }
+        }
+
         self.captcha
             .check(recaptcha_response.as_deref().map(String::as_str))
             .await
@@ -185,6 +312,15 @@ where
             None => None,
         };
 
+        // the email address does not have to be verified again if the OAuth2 provider has
+        // already verified it
+        let email_verified = oauth2_registration.as_ref().is_some_and(|registration| {
+            registration.profile.email_verified
+                && registration.profile.email.as_ref().is_some_and(|email| {
+                    email.as_str().eq_ignore_ascii_case(request.email.as_str())
+                })
+        });
+
         let mut txn = self.db.begin_transaction().await.unwrap();
 
         let cmd = UserCreateCommand {
@@ -192,9 +328,9 @@ where
             display_name: request.display_name,
             email: request.email,
             password: request.password,
-            admin: false,
+            roles: Default::default(),
             enabled: true,
-            email_verified: false,
+            email_verified,
             oauth2_registration,
         };
 
@@ -223,6 +359,9 @@ where
 
         txn.commit().await.unwrap();
 
+        // remember the device, but don't notify the user about their first login
+        self.session.remember_device(&result, &client, false).await;
+
         Ok(result)
     }
 
@@ -239,16 +378,18 @@ where
                     email_verified,
                     password,
                     enabled,
-                    admin,
+                    roles,
                     newsletter,
                 },
             profile: profile_update,
             invoice_info: invoice_info_update,
         }: UserUpdateRequest,
+        client: ClientInfo,
     ) -> Result<UserComposite, UserUpdateError> {
         let auth = self.auth.authenticate(token).await.map_auth_err()?;
         let user_id = user_id.unwrap_or(auth.user_id);
-        auth.ensure_self_or_admin(user_id).map_auth_err()?;
+        auth.ensure_self_or_permission(user_id, Permission::UserRead)
+            .map_auth_err()?;
 
         let mut txn = self.db.begin_transaction().await?;
 
@@ -273,7 +414,7 @@ where
         let email_verified =
             email_verified.minimize(&(user.email_verified && email.is_unchanged()));
         let enabled = enabled.minimize(&user.enabled);
-        let admin = admin.minimize(&user.admin);
+        let roles = roles.minimize(&user.roles);
         let newsletter = newsletter.minimize(&user.newsletter);
 
         let profile_update = profile_update.minimize(&profile);
@@ -291,15 +432,44 @@ where
         .minimize(&invoice_info);
 
         // Validate patch
-        if email_verified.is_update() || enabled.is_update() || admin.is_update() {
-            auth.ensure_admin().map_auth_err()?;
+        if user_id != auth.user_id {
+            if name.is_update()
+                || email.is_update()
+                || password.is_update()
+                || newsletter.is_update()
+            {
+                auth.ensure_permission(Permission::UserWrite)
+                    .map_auth_err()?;
+            }
+            if profile_update.is_update() {
+                auth.ensure_permission(Permission::UserProfileWrite)
+                    .map_auth_err()?;
+            }
+            if invoice_info_update.is_update() {
+                auth.ensure_permission(Permission::UserInvoiceInfoWrite)
+                    .map_auth_err()?;
+            }
+        }
+
+        if email_verified.is_update() || enabled.is_update() {
+            auth.ensure_permission(Permission::UserWrite)
+                .map_auth_err()?;
+        }
+
+        if roles.is_update() {
+            auth.ensure_permission(Permission::RoleAssign)
+                .map_auth_err()?;
+        }
+
+        if email.is_update() || password.is_update() {
+            auth.ensure_not_impersonated().map_auth_err()?;
         }
 
         if enabled == PatchValue::Update(false) && user_id == auth.user_id {
             return Err(UserUpdateError::CannotDisableSelf);
         }
 
-        if admin.is_update() && user_id == auth.user_id {
+        if roles.is_update() && user_id == auth.user_id {
             return Err(UserUpdateError::CannotDemoteSelf);
         }
 
@@ -325,7 +495,7 @@ where
         }
 
         if let PatchValue::Update(name) = name {
-            let rate_limit_policy = if auth.admin {
+            let rate_limit_policy = if auth.has_permission(Permission::UserWrite) {
                 UserUpdateNameRateLimitPolicy::Bypass
             } else {
                 UserUpdateNameRateLimitPolicy::Enforce
@@ -371,12 +541,12 @@ where
             commit = true;
         }
 
-        if let PatchValue::Update(admin) = admin {
+        if let PatchValue::Update(roles) = roles {
             self.user_update
-                .update_admin(&mut txn, user_id, admin)
+                .update_roles(&mut txn, user_id, roles.clone())
                 .await
-                .context("Failed to update admin status")?;
-            user.admin = admin;
+                .context("Failed to update roles")?;
+            user.roles = roles;
             commit = true;
         }
 
@@ -393,6 +563,20 @@ where
                 commit = true;
             }
             PatchValue::Update(PasswordUpdate::Change(password)) => {
+                let user_inputs = password_policy_user_inputs(
+                    &user.name,
+                    &profile.display_name,
+                    user.email.as_ref(),
+                );
+                let violations = self
+                    .user_password_policy
+                    .check(&password, user_inputs)
+                    .await
+                    .context("Failed to check password policy")?;
+                if !violations.is_empty() {
+                    return Err(UserUpdateError::WeakPassword(violations));
+                }
+
                 self.user_update
                     .update_password(&mut txn, user_id, password)
                     .await
@@ -404,7 +588,7 @@ where
         }
 
         if let PatchValue::Update(newsletter) = newsletter {
-            if newsletter && !auth.admin {
+            if newsletter && !auth.has_permission(Permission::UserWrite) {
                 let email = user.email.clone().ok_or(UserUpdateError::NoEmail)?;
                 self.user_email_confirmation
                     .request_newsletter_subscription(
@@ -440,6 +624,17 @@ where
         }
 
         if commit {
+            self.audit_log
+                .record(
+                    &mut txn,
+                    Some(auth.user_id),
+                    user_id,
+                    AuditEventKind::UserUpdated,
+                    client,
+                )
+                .await
+                .context("Failed to record audit event")?;
+
             txn.commit().await?;
         }
 
@@ -460,34 +655,171 @@ where
         Ok(user_composite)
     }
 
+    #[trace_instrument(skip(self, data))]
+    async fn update_avatar(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        data: Vec<u8>,
+        client: ClientInfo,
+    ) -> Result<UserComposite, UserUpdateAvatarError> {
+        let auth = self.auth.authenticate(token).await.map_auth_err()?;
+        let user_id = user_id.unwrap_or(auth.user_id);
+        auth.ensure_self_or_permission(user_id, Permission::UserProfileWrite)
+            .map_auth_err()?;
+
+        let mut txn = self.db.begin_transaction().await?;
+
+        let mut user_composite = self
+            .user_repo
+            .get_composite(&mut txn, user_id)
+            .await
+            .context("Failed to get user from database")?
+            .ok_or(UserUpdateAvatarError::NotFound)?;
+
+        let avatar = self
+            .user_avatar
+            .upload(data)
+            .await
+            .map_err(|err| match err {
+                UserAvatarUploadError::TooLarge => UserUpdateAvatarError::TooLarge,
+                UserAvatarUploadError::UnsupportedFormat => {
+                    UserUpdateAvatarError::UnsupportedFormat
+                }
+                UserAvatarUploadError::InvalidImage => UserUpdateAvatarError::InvalidImage,
+                UserAvatarUploadError::InvalidDimensions => {
+                    UserUpdateAvatarError::InvalidDimensions
+                }
+                UserAvatarUploadError::Other(err) => err.context("Failed to upload avatar").into(),
+            })
+            .map(Some)?;
+
+        self.user_repo
+            .update_avatar(&mut txn, user_id, &avatar)
+            .await
+            .context("Failed to update avatar in database")?;
+
+        self.audit_log
+            .record(
+                &mut txn,
+                Some(auth.user_id),
+                user_id,
+                AuditEventKind::UserUpdated,
+                client,
+            )
+            .await
+            .context("Failed to record audit event")?;
+
+        txn.commit().await?;
+
+        // the previous images are only removed after the new avatar has been saved
+        if let Some(old_avatar) = std::mem::replace(&mut user_composite.profile.avatar, avatar) {
+            self.user_avatar
+                .remove(&old_avatar)
+                .await
+                .context("Failed to remove previous avatar")?;
+        }
+
+        Ok(user_composite)
+    }
+
     #[trace_instrument(skip(self))]
-    async fn delete_user(
+    async fn delete_avatar(
         &self,
         token: &AccessToken,
         user_id: UserIdOrSelf,
-    ) -> Result<(), UserDeleteError> {
+        client: ClientInfo,
+    ) -> Result<UserComposite, UserDeleteAvatarError> {
         let auth = self.auth.authenticate(token).await.map_auth_err()?;
         let user_id = user_id.unwrap_or(auth.user_id);
-        auth.ensure_self_or_admin(user_id).map_auth_err()?;
+        auth.ensure_self_or_permission(user_id, Permission::UserProfileWrite)
+            .map_auth_err()?;
 
         let mut txn = self.db.begin_transaction().await?;
 
-        self.auth
-            .invalidate_access_tokens(&mut txn, user_id)
+        let mut user_composite = self
+            .user_repo
+            .get_composite(&mut txn, user_id)
+            .await
+            .context("Failed to get user from database")?
+            .ok_or(UserDeleteAvatarError::NotFound)?;
+
+        let Some(avatar) = user_composite.profile.avatar.take() else {
+            return Ok(user_composite);
+        };
+
+        self.user_repo
+            .update_avatar(&mut txn, user_id, &None)
+            .await
+            .context("Failed to remove avatar from database")?;
+
+        self.audit_log
+            .record(
+                &mut txn,
+                Some(auth.user_id),
+                user_id,
+                AuditEventKind::UserUpdated,
+                client,
+            )
+            .await
+            .context("Failed to record audit event")?;
+
+        txn.commit().await?;
+
+        self.user_avatar
+            .remove(&avatar)
             .await
-            .context("Failed to invalidate access tokens")?;
+            .context("Failed to remove avatar")?;
+
+        Ok(user_composite)
+    }
 
-        if !self
+    #[trace_instrument(skip(self))]
+    async fn delete_user(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+        client: ClientInfo,
+    ) -> Result<(), UserDeleteError> {
+        let auth = self.auth.authenticate(token).await.map_auth_err()?;
+        let user_id = user_id.unwrap_or(auth.user_id);
+        auth.ensure_self_or_permission(user_id, Permission::UserWrite)
+            .map_auth_err()?;
+        auth.ensure_not_impersonated().map_auth_err()?;
+
+        let mut txn = self.db.begin_transaction().await?;
+
+        let user_composite = self
             .user_repo
-            .delete(&mut txn, user_id)
+            .get_composite(&mut txn, user_id)
             .await
-            .context("Failed to delete user from database")?
-        {
-            return Err(UserDeleteError::NotFound);
+            .context("Failed to get user from database")?
+            .ok_or(UserDeleteError::NotFound)?;
+
+        if user_composite.user.deleted_at.is_some() {
+            return Ok(());
         }
 
+        self.user_deletion
+            .schedule(&mut txn, &user_composite)
+            .await
+            .context("Failed to schedule user deletion")?;
+
+        self.audit_log
+            .record(
+                &mut txn,
+                Some(auth.user_id),
+                user_id,
+                AuditEventKind::UserDeleted,
+                client,
+            )
+            .await
+            .context("Failed to record audit event")?;
+
         txn.commit().await?;
 
+        self.user_deletion.send_deleted_email(&user_composite).await;
+
         Ok(())
     }
 
@@ -499,7 +831,8 @@ where
     ) -> Result<(), UserRequestVerificationEmailError> {
         let auth = self.auth.authenticate(token).await.map_auth_err()?;
         let user_id = user_id.unwrap_or(auth.user_id);
-        auth.ensure_self_or_admin(user_id).map_auth_err()?;
+        auth.ensure_self_or_permission(user_id, Permission::UserVerificationEmailSend)
+            .map_auth_err()?;
 
         let mut txn = self.db.begin_transaction().await?;
 
@@ -528,7 +861,25 @@ where
     }
 
     #[trace_instrument(skip(self))]
-    async fn verify_email(&self, code: VerificationCode) -> Result<(), UserVerifyEmailError> {
+    async fn verify_email(
+        &self,
+        code: VerificationCode,
+        client: ClientInfo,
+    ) -> Result<(), UserVerifyEmailError> {
+        let throttle_keys = client
+            .ip_address
+            .map(|ip| ThrottleKey::ip(VERIFY_EMAIL_THROTTLE_ACTION, ip))
+            .into_iter()
+            .collect::<Vec<_>>();
+        if let Some(until) = self
+            .throttle
+            .check(&throttle_keys)
+            .await
+            .context("Failed to check email verification throttle")?
+        {
+            return Err(UserVerifyEmailError::RateLimit { until });
+        }
+
         let mut txn = self.db.begin_transaction().await?;
 
         match self
@@ -542,6 +893,10 @@ where
             }
             Err(UserEmailConfirmationVerifyEmailError::AlreadyVerified) => Ok(()),
             Err(UserEmailConfirmationVerifyEmailError::InvalidCode) => {
+                self.throttle
+                    .record(&throttle_keys)
+                    .await
+                    .context("Failed to record failed email verification")?;
                 Err(UserVerifyEmailError::InvalidCode)
             }
             Err(UserEmailConfirmationVerifyEmailError::Other(err)) => {
@@ -559,7 +914,8 @@ where
     ) -> Result<UserComposite, UserVerifyNewsletterSubscriptionError> {
         let auth = self.auth.authenticate(token).await.map_auth_err()?;
         let user_id = user_id.unwrap_or(auth.user_id);
-        auth.ensure_self_or_admin(user_id).map_auth_err()?;
+        auth.ensure_self_or_permission(user_id, Permission::UserWrite)
+            .map_auth_err()?;
 
         let mut txn = self.db.begin_transaction().await?;
 
@@ -598,7 +954,28 @@ where
         &self,
         email: EmailAddress,
         recaptcha_response: Option<RecaptchaResponse>,
+        client: ClientInfo,
     ) -> Result<(), UserRequestPasswordResetError> {
+        let throttle_keys = [ThrottleKey::account(
+            PASSWORD_RESET_THROTTLE_ACTION,
+            email.as_str(),
+        )]
+        .into_iter()
+        .chain(
+            client
+                .ip_address
+                .map(|ip| ThrottleKey::ip(PASSWORD_RESET_THROTTLE_ACTION, ip)),
+        )
+        .collect::<Vec<_>>();
+        if let Some(until) = self
+            .throttle
+            .record(&throttle_keys)
+            .await
+            .context("Failed to record password reset request")?
+        {
+            return Err(UserRequestPasswordResetError::RateLimit { until });
+        }
+
         self.captcha
             .check(recaptcha_response.as_deref().map(String::as_str))
             .await
@@ -640,7 +1017,20 @@ where
         email: EmailAddress,
         code: VerificationCode,
         new_password: UserPassword,
+        client: ClientInfo,
     ) -> Result<UserComposite, UserResetPasswordError> {
+        // Only the email address is used as user input here, so the result of
+        // the policy check does not reveal whether a user with this email
+        // address exists.
+        let violations = self
+            .user_password_policy
+            .check(&new_password, vec![email.as_str().into()])
+            .await
+            .context("Failed to check password policy")?;
+        if !violations.is_empty() {
+            return Err(UserResetPasswordError::WeakPassword(violations));
+        }
+
         let mut txn = self.db.begin_transaction().await?;
 
         let user_composite = self
@@ -662,8 +1052,120 @@ where
                 }
             })?;
 
+        self.audit_log
+            .record(
+                &mut txn,
+                None,
+                user_composite.user.id,
+                AuditEventKind::PasswordReset,
+                client,
+            )
+            .await
+            .context("Failed to record audit event")?;
+
         txn.commit().await?;
 
         Ok(user_composite)
     }
+
+    #[trace_instrument(skip(self))]
+    async fn restore_user(
+        &self,
+        code: VerificationCode,
+        client: ClientInfo,
+    ) -> Result<(), UserRestoreError> {
+        let mut txn = self.db.begin_transaction().await?;
+
+        let user_id = self
+            .user_deletion
+            .restore_by_code(&mut txn, &code)
+            .await
+            .context("Failed to restore user")?
+            .ok_or(UserRestoreError::InvalidCode)?;
+
+        self.audit_log
+            .record(
+                &mut txn,
+                None,
+                user_id,
+                AuditEventKind::UserRestored,
+                client,
+            )
+            .await
+            .context("Failed to record audit event")?;
+
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn export_user(
+        &self,
+        token: &AccessToken,
+        user_id: UserIdOrSelf,
+    ) -> Result<UserExportResult, UserExportError> {
+        let auth = self.auth.authenticate(token).await.map_auth_err()?;
+        let user_id = user_id.unwrap_or(auth.user_id);
+        auth.ensure_self_or_permission(user_id, Permission::UserRead)
+            .map_auth_err()?;
+
+        let mut txn = self.db.begin_transaction().await?;
+
+        let user_composite = self
+            .user_repo
+            .get_composite(&mut txn, user_id)
+            .await
+            .context("Failed to get user from database")?
+            .ok_or(UserExportError::NotFound)?;
+
+        // large exports can only be sent via email
+        if user_composite.user.email.is_some()
+            && self
+                .user_export
+                .is_large(&mut txn, user_id)
+                .await
+                .context("Failed to check export size")?
+        {
+            self.user_export
+                .request_export(&mut txn, user_id)
+                .await
+                .context("Failed to request export")?;
+            txn.commit().await?;
+            return Ok(UserExportResult::Scheduled);
+        }
+
+        self.user_export
+            .export(&mut txn, user_composite)
+            .await
+            .context("Failed to export user")
+            .map(|export| UserExportResult::Complete(export.into()))
+            .map_err(Into::into)
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn download_user_export(
+        &self,
+        code: VerificationCode,
+    ) -> Result<UserExport, UserDownloadExportError> {
+        self.user_export
+            .get_download(&code)
+            .await
+            .context("Failed to get export")?
+            .ok_or(UserDownloadExportError::InvalidCode)
+    }
+}
+
+/// Return the user inputs that are considered easy to guess when checking a
+/// password against the password policy.
+fn password_policy_user_inputs(
+    name: &str,
+    display_name: &str,
+    email: Option<&EmailAddress>,
+) -> Vec<String> {
+    [name, display_name]
+        .into_iter()
+        .chain(email.map(EmailAddress::as_str))
+        .map(Into::into)
+        .collect()
 }
@@ -0,0 +1,180 @@
+use academy_core_user_contracts::password_policy::UserPasswordPolicyService;
+use academy_di::Build;
+use academy_models::user::{UserPassword, UserPasswordPolicyViolation};
+use academy_shared_contracts::{
+    breached_password::BreachedPasswordService, password_strength::PasswordStrengthService,
+};
+use academy_utils::trace_instrument;
+use anyhow::Context;
+
+use crate::UserFeatureConfig;
+
+#[derive(Debug, Clone, Build)]
+#[cfg_attr(test, derive(Default))]
+pub struct UserPasswordPolicyServiceImpl<PasswordStrength, BreachedPassword> {
+    password_strength: PasswordStrength,
+    breached_password: BreachedPassword,
+    config: UserFeatureConfig,
+}
+
+impl<PasswordStrength, BreachedPassword> UserPasswordPolicyService
+    for UserPasswordPolicyServiceImpl<PasswordStrength, BreachedPassword>
+where
+    PasswordStrength: PasswordStrengthService,
+    BreachedPassword: BreachedPasswordService,
+{
+    #[trace_instrument(skip(self))]
+    async fn check(
+        &self,
+        password: &UserPassword,
+        user_inputs: Vec<String>,
+    ) -> anyhow::Result<Vec<UserPasswordPolicyViolation>> {
+        let mut violations = Vec::new();
+
+        if password.chars().count() < self.config.password_min_length {
+            violations.push(UserPasswordPolicyViolation::TooShort {
+                min_length: self.config.password_min_length,
+            });
+        }
+
+        for &class in &*self.config.password_required_character_classes {
+            if !password.chars().any(|c| class.matches(c)) {
+                violations.push(UserPasswordPolicyViolation::MissingCharacterClass { class });
+            }
+        }
+
+        if self.config.password_min_strength > 0 {
+            let score = self
+                .password_strength
+                .estimate(password.clone().into_inner().into(), user_inputs);
+            if score < self.config.password_min_strength {
+                violations.push(UserPasswordPolicyViolation::TooWeak {
+                    score,
+                    min_score: self.config.password_min_strength,
+                });
+            }
+        }
+
+        if self.config.password_breach_check
+            && self
+                .breached_password
+                .is_breached(password.clone().into_inner().into())
+                .await
+                .context("Failed to check whether the password has been breached")?
+        {
+            violations.push(UserPasswordPolicyViolation::Breached);
+        }
+
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use academy_models::user::UserPasswordCharacterClass;
+    use academy_shared_contracts::{
+        breached_password::MockBreachedPasswordService,
+        password_strength::MockPasswordStrengthService,
+    };
+
+    use super::*;
+
+    type Sut =
+        UserPasswordPolicyServiceImpl<MockPasswordStrengthService, MockBreachedPasswordService>;
+
+    #[tokio::test]
+    async fn ok() {
+        // Arrange
+        let password = UserPassword::try_new("correct horse battery staple").unwrap();
+        let user_inputs = vec!["foo".to_owned(), "foo@example.com".to_owned()];
+
+        let password_strength = MockPasswordStrengthService::new().with_estimate(
+            password.clone().into_inner(),
+            user_inputs.clone(),
+            4,
+        );
+
+        let breached_password = MockBreachedPasswordService::new()
+            .with_is_breached(password.clone().into_inner(), false);
+
+        let sut = UserPasswordPolicyServiceImpl {
+            password_strength,
+            breached_password,
+            config: UserFeatureConfig {
+                password_required_character_classes: [UserPasswordCharacterClass::Lowercase].into(),
+                ..Default::default()
+            },
+        };
+
+        // Act
+        let result = sut.check(&password, user_inputs).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), []);
+    }
+
+    #[tokio::test]
+    async fn violations() {
+        // Arrange
+        let password = UserPassword::try_new("Pass1").unwrap();
+
+        let password_strength = MockPasswordStrengthService::new().with_estimate(
+            password.clone().into_inner(),
+            vec![],
+            0,
+        );
+
+        let breached_password = MockBreachedPasswordService::new()
+            .with_is_breached(password.clone().into_inner(), true);
+
+        let sut = UserPasswordPolicyServiceImpl {
+            password_strength,
+            breached_password,
+            config: UserFeatureConfig {
+                password_required_character_classes: UserPasswordCharacterClass::ALL.into(),
+                ..Default::default()
+            },
+        };
+
+        // Act
+        let result = sut.check(&password, vec![]).await;
+
+        // Assert
+        assert_eq!(
+            result.unwrap(),
+            [
+                UserPasswordPolicyViolation::TooShort { min_length: 8 },
+                UserPasswordPolicyViolation::MissingCharacterClass {
+                    class: UserPasswordCharacterClass::Symbol
+                },
+                UserPasswordPolicyViolation::TooWeak {
+                    score: 0,
+                    min_score: 2
+                },
+                UserPasswordPolicyViolation::Breached,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn disabled_checks() {
+        // Arrange
+        let password = UserPassword::try_new("a").unwrap();
+
+        let sut = UserPasswordPolicyServiceImpl {
+            config: UserFeatureConfig {
+                password_min_length: 1,
+                password_min_strength: 0,
+                password_breach_check: false,
+                ..Default::default()
+            },
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.check(&password, vec![]).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), []);
+    }
+}
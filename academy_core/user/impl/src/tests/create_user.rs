@@ -1,17 +1,20 @@
 use academy_core_oauth2_contracts::registration::MockOAuth2RegistrationService;
 use academy_core_session_contracts::session::MockSessionService;
 use academy_core_user_contracts::{
+    password_policy::MockUserPasswordPolicyService,
     user::{MockUserService, UserCreateCommand},
     UserCreateError, UserCreateRequest, UserFeatureService,
 };
 use academy_demo::{
-    oauth2::{FOO_OAUTH2_LINK_1, TEST_OAUTH2_PROVIDER_ID},
+    oauth2::{FOO_OAUTH2_LINK_1, FOO_OAUTH2_PROFILE, FOO_OAUTH2_TOKENS, TEST_OAUTH2_PROVIDER_ID},
     session::FOO_1,
     user::FOO,
 };
 use academy_models::{
+    audit::ClientInfo,
     auth::Login,
     oauth2::{OAuth2Registration, OAuth2RegistrationToken},
+    user::{UserPasswordCharacterClass, UserPasswordPolicyViolation},
 };
 use academy_persistence_contracts::MockDatabase;
 use academy_shared_contracts::captcha::{CaptchaCheckError, MockCaptchaService};
@@ -30,6 +33,12 @@ async fn ok() {
         oauth2_registration_token: None,
     };
 
+    let user_password_policy = MockUserPasswordPolicyService::new().with_check(
+        request.password.clone().unwrap(),
+        req_to_user_inputs(&request),
+        vec![],
+    );
+
     let expected = Login {
         user_composite: FOO.clone(),
         session: FOO_1.clone(),
@@ -43,18 +52,21 @@ async fn ok() {
 
     let user = MockUserService::new().with_create(req_to_cmd(&request), Ok(FOO.clone()));
 
-    let session = MockSessionService::new().with_create(
-        FOO.clone(),
-        FOO_1.device_name.clone(),
-        true,
-        expected.clone(),
-    );
+    let session = MockSessionService::new()
+        .with_create(
+            FOO.clone(),
+            FOO_1.device_name.clone(),
+            true,
+            expected.clone(),
+        )
+        .with_remember_device(expected.clone(), ClientInfo::default(), false);
 
     let sut = UserFeatureServiceImpl {
         db,
         captcha,
         user,
         session,
+        user_password_policy,
         ..Sut::default()
     };
 
@@ -64,6 +76,7 @@ async fn ok() {
             request,
             FOO_1.device_name.clone(),
             Some("resp".try_into().unwrap()),
+            ClientInfo::default(),
         )
         .await;
 
@@ -104,19 +117,181 @@ async fn ok_oauth2() {
             Some(OAuth2Registration {
                 provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
                 remote_user: FOO_OAUTH2_LINK_1.remote_user.clone(),
+                profile: Default::default(),
+                tokens: FOO_OAUTH2_TOKENS.clone(),
             }),
         )
         .with_remove(token);
 
     let user = MockUserService::new().with_create(req_to_cmd(&request), Ok(FOO.clone()));
 
-    let session = MockSessionService::new().with_create(
-        FOO.clone(),
-        FOO_1.device_name.clone(),
-        true,
-        expected.clone(),
+    let session = MockSessionService::new()
+        .with_create(
+            FOO.clone(),
+            FOO_1.device_name.clone(),
+            true,
+            expected.clone(),
+        )
+        .with_remember_device(expected.clone(), ClientInfo::default(), false);
+
+    let sut = UserFeatureServiceImpl {
+        db,
+        captcha,
+        user,
+        oauth2_registration,
+        session,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_user(
+            request,
+            FOO_1.device_name.clone(),
+            Some("resp".try_into().unwrap()),
+            ClientInfo::default(),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn ok_oauth2_verified_email() {
+    // Arrange
+    let token = OAuth2RegistrationToken::try_new(
+        "K7oACiokVoyttnGgYxJwCc2VCvDbQI10Bewthc5exlyQly2JZCViycDereak92oB",
+    )
+    .unwrap();
+
+    let request = UserCreateRequest {
+        name: FOO.user.name.clone(),
+        display_name: FOO.profile.display_name.clone(),
+        email: FOO.user.email.clone().unwrap(),
+        password: None,
+        oauth2_registration_token: Some(token.clone()),
+    };
+
+    let registration = OAuth2Registration {
+        provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
+        remote_user: FOO_OAUTH2_LINK_1.remote_user.clone(),
+        profile: FOO_OAUTH2_PROFILE.clone(),
+        tokens: FOO_OAUTH2_TOKENS.clone(),
+    };
+
+    let expected = Login {
+        user_composite: FOO.clone(),
+        session: FOO_1.clone(),
+        access_token: "the access token".into(),
+        refresh_token: "some refresh token".into(),
+    };
+
+    let db = MockDatabase::build(true);
+
+    let captcha = MockCaptchaService::new().with_check(Some("resp"), Ok(()));
+
+    let oauth2_registration = MockOAuth2RegistrationService::new()
+        .with_get(token.clone(), Some(registration.clone()))
+        .with_remove(token);
+
+    let user = MockUserService::new().with_create(
+        UserCreateCommand {
+            email_verified: true,
+            oauth2_registration: Some(registration),
+            ..req_to_cmd(&request)
+        },
+        Ok(FOO.clone()),
+    );
+
+    let session = MockSessionService::new()
+        .with_create(
+            FOO.clone(),
+            FOO_1.device_name.clone(),
+            true,
+            expected.clone(),
+        )
+        .with_remember_device(expected.clone(), ClientInfo::default(), false);
+
+    let sut = UserFeatureServiceImpl {
+        db,
+        captcha,
+        user,
+        oauth2_registration,
+        session,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_user(
+            request,
+            FOO_1.device_name.clone(),
+            Some("resp".try_into().unwrap()),
+            ClientInfo::default(),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn ok_oauth2_different_email() {
+    // Arrange
+    let token = OAuth2RegistrationToken::try_new(
+        "K7oACiokVoyttnGgYxJwCc2VCvDbQI10Bewthc5exlyQly2JZCViycDereak92oB",
+    )
+    .unwrap();
+
+    let request = UserCreateRequest {
+        name: FOO.user.name.clone(),
+        display_name: FOO.profile.display_name.clone(),
+        email: "other@example.com".parse().unwrap(),
+        password: None,
+        oauth2_registration_token: Some(token.clone()),
+    };
+
+    let registration = OAuth2Registration {
+        provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
+        remote_user: FOO_OAUTH2_LINK_1.remote_user.clone(),
+        profile: FOO_OAUTH2_PROFILE.clone(),
+        tokens: FOO_OAUTH2_TOKENS.clone(),
+    };
+
+    let expected = Login {
+        user_composite: FOO.clone(),
+        session: FOO_1.clone(),
+        access_token: "the access token".into(),
+        refresh_token: "some refresh token".into(),
+    };
+
+    let db = MockDatabase::build(true);
+
+    let captcha = MockCaptchaService::new().with_check(Some("resp"), Ok(()));
+
+    let oauth2_registration = MockOAuth2RegistrationService::new()
+        .with_get(token.clone(), Some(registration.clone()))
+        .with_remove(token);
+
+    let user = MockUserService::new().with_create(
+        UserCreateCommand {
+            email_verified: false,
+            oauth2_registration: Some(registration),
+            ..req_to_cmd(&request)
+        },
+        Ok(FOO.clone()),
     );
 
+    let session = MockSessionService::new()
+        .with_create(
+            FOO.clone(),
+            FOO_1.device_name.clone(),
+            true,
+            expected.clone(),
+        )
+        .with_remember_device(expected.clone(), ClientInfo::default(), false);
+
     let sut = UserFeatureServiceImpl {
         db,
         captcha,
@@ -132,6 +307,7 @@ async fn ok_oauth2() {
             request,
             FOO_1.device_name.clone(),
             Some("resp".try_into().unwrap()),
+            ClientInfo::default(),
         )
         .await;
 
@@ -154,7 +330,12 @@ async fn no_login_method() {
 
     // Act
     let result = sut
-        .create_user(request, FOO_1.device_name.clone(), None)
+        .create_user(
+            request,
+            FOO_1.device_name.clone(),
+            None,
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -172,11 +353,18 @@ async fn invalid_recaptcha_response() {
         oauth2_registration_token: None,
     };
 
+    let user_password_policy = MockUserPasswordPolicyService::new().with_check(
+        request.password.clone().unwrap(),
+        req_to_user_inputs(&request),
+        vec![],
+    );
+
     let captcha =
         MockCaptchaService::new().with_check(Some("resp"), Err(CaptchaCheckError::Failed));
 
     let sut = UserFeatureServiceImpl {
         captcha,
+        user_password_policy,
         ..Sut::default()
     };
 
@@ -186,6 +374,7 @@ async fn invalid_recaptcha_response() {
             request,
             FOO_1.device_name.clone(),
             Some("resp".try_into().unwrap()),
+            ClientInfo::default(),
         )
         .await;
 
@@ -193,6 +382,53 @@ async fn invalid_recaptcha_response() {
     assert_matches!(result, Err(UserCreateError::Recaptcha));
 }
 
+#[tokio::test]
+async fn weak_password() {
+    // Arrange
+    let request = UserCreateRequest {
+        name: FOO.user.name.clone(),
+        display_name: FOO.profile.display_name.clone(),
+        email: FOO.user.email.clone().unwrap(),
+        password: Some("foo".try_into().unwrap()),
+        oauth2_registration_token: None,
+    };
+
+    let violations = vec![
+        UserPasswordPolicyViolation::TooShort { min_length: 8 },
+        UserPasswordPolicyViolation::MissingCharacterClass {
+            class: UserPasswordCharacterClass::Digit,
+        },
+        UserPasswordPolicyViolation::TooWeak {
+            score: 0,
+            min_score: 2,
+        },
+    ];
+
+    let user_password_policy = MockUserPasswordPolicyService::new().with_check(
+        request.password.clone().unwrap(),
+        req_to_user_inputs(&request),
+        violations.clone(),
+    );
+
+    let sut = UserFeatureServiceImpl {
+        user_password_policy,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .create_user(
+            request,
+            FOO_1.device_name.clone(),
+            Some("resp".try_into().unwrap()),
+            ClientInfo::default(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(UserCreateError::WeakPassword(v)) if *v == violations);
+}
+
 #[tokio::test]
 async fn name_conflict() {
     // Arrange
@@ -204,6 +440,12 @@ async fn name_conflict() {
         oauth2_registration_token: None,
     };
 
+    let user_password_policy = MockUserPasswordPolicyService::new().with_check(
+        request.password.clone().unwrap(),
+        req_to_user_inputs(&request),
+        vec![],
+    );
+
     let db = MockDatabase::build(false);
 
     let captcha = MockCaptchaService::new().with_check(None, Ok(()));
@@ -217,12 +459,18 @@ async fn name_conflict() {
         db,
         captcha,
         user,
+        user_password_policy,
         ..Sut::default()
     };
 
     // Act
     let result = sut
-        .create_user(request, FOO_1.device_name.clone(), None)
+        .create_user(
+            request,
+            FOO_1.device_name.clone(),
+            None,
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -240,6 +488,12 @@ async fn email_conflict() {
         oauth2_registration_token: None,
     };
 
+    let user_password_policy = MockUserPasswordPolicyService::new().with_check(
+        request.password.clone().unwrap(),
+        req_to_user_inputs(&request),
+        vec![],
+    );
+
     let db = MockDatabase::build(false);
 
     let captcha = MockCaptchaService::new().with_check(None, Ok(()));
@@ -253,12 +507,18 @@ async fn email_conflict() {
         db,
         captcha,
         user,
+        user_password_policy,
         ..Sut::default()
     };
 
     // Act
     let result = sut
-        .create_user(request, FOO_1.device_name.clone(), None)
+        .create_user(
+            request,
+            FOO_1.device_name.clone(),
+            None,
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -296,6 +556,7 @@ async fn oauth2_invalid_registration_token() {
             request,
             FOO_1.device_name.clone(),
             Some("resp".try_into().unwrap()),
+            ClientInfo::default(),
         )
         .await;
 
@@ -331,6 +592,8 @@ async fn oauth2_remote_already_linked() {
         Some(OAuth2Registration {
             provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
             remote_user: FOO_OAUTH2_LINK_1.remote_user.clone(),
+            profile: Default::default(),
+            tokens: FOO_OAUTH2_TOKENS.clone(),
         }),
     );
 
@@ -353,6 +616,7 @@ async fn oauth2_remote_already_linked() {
             request,
             FOO_1.device_name.clone(),
             Some("resp".try_into().unwrap()),
+            ClientInfo::default(),
         )
         .await;
 
@@ -366,7 +630,7 @@ fn req_to_cmd(req: &UserCreateRequest) -> UserCreateCommand {
         display_name: req.display_name.clone(),
         email: req.email.clone(),
         password: req.password.clone(),
-        admin: false,
+        roles: Default::default(),
         enabled: true,
         email_verified: false,
         oauth2_registration: req
@@ -375,6 +639,16 @@ fn req_to_cmd(req: &UserCreateRequest) -> UserCreateCommand {
             .map(|_| OAuth2Registration {
                 provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
                 remote_user: FOO_OAUTH2_LINK_1.remote_user.clone(),
+                profile: Default::default(),
+                tokens: FOO_OAUTH2_TOKENS.clone(),
             }),
     }
 }
+
+fn req_to_user_inputs(req: &UserCreateRequest) -> Vec<String> {
+    vec![
+        req.name.clone().into_inner(),
+        req.display_name.clone().into_inner(),
+        req.email.as_str().into(),
+    ]
+}
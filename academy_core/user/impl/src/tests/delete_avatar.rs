@@ -0,0 +1,200 @@
+use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::log::MockAuditLogService;
+use academy_core_user_contracts::{
+    avatar::MockUserAvatarService, UserDeleteAvatarError, UserFeatureService,
+};
+use academy_demo::{
+    session::{ADMIN_1, BAR_1, FOO_1},
+    user::{ADMIN, BAR, FOO, FOO_AVATAR},
+};
+use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
+    auth::{AuthError, AuthenticateError, AuthorizeError},
+    user::UserIdOrSelf,
+};
+use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
+use academy_utils::{assert_matches, Apply};
+
+use crate::{tests::Sut, UserFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok_self() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let user_repo = MockUserRepository::new()
+        .with_get_composite(FOO.user.id, Some(FOO.clone()))
+        .with_update_avatar(FOO.user.id, None, true);
+
+    let user_avatar = MockUserAvatarService::new().with_remove(FOO_AVATAR.clone());
+
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(FOO.user.id),
+        FOO.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        user_avatar,
+        audit_log,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .delete_avatar(&"token".into(), UserIdOrSelf::Slf, ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap(),
+        FOO.clone().with(|x| x.profile.avatar = None)
+    );
+}
+
+#[tokio::test]
+async fn ok_admin() {
+    // Arrange
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let user_repo = MockUserRepository::new()
+        .with_get_composite(FOO.user.id, Some(FOO.clone()))
+        .with_update_avatar(FOO.user.id, None, true);
+
+    let user_avatar = MockUserAvatarService::new().with_remove(FOO_AVATAR.clone());
+
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(ADMIN.user.id),
+        FOO.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        user_avatar,
+        audit_log,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .delete_avatar(&"token".into(), FOO.user.id.into(), ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap(),
+        FOO.clone().with(|x| x.profile.avatar = None)
+    );
+}
+
+#[tokio::test]
+async fn ok_no_avatar() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((BAR.user.clone(), BAR_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(BAR.user.id, Some(BAR.clone()));
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .delete_avatar(&"token".into(), UserIdOrSelf::Slf, ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), *BAR);
+}
+
+#[tokio::test]
+async fn unauthenticated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(None);
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .delete_avatar(&"token".into(), FOO.user.id.into(), ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(UserDeleteAvatarError::Auth(AuthError::Authenticate(
+            AuthenticateError::InvalidToken
+        )))
+    );
+}
+
+#[tokio::test]
+async fn unauthorized() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((BAR.user.clone(), BAR_1.clone())));
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .delete_avatar(&"token".into(), FOO.user.id.into(), ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(UserDeleteAvatarError::Auth(AuthError::Authorize(
+            AuthorizeError::Permission
+        )))
+    );
+}
+
+#[tokio::test]
+async fn not_found() {
+    // Arrange
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, None);
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .delete_avatar(&"token".into(), FOO.user.id.into(), ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(UserDeleteAvatarError::NotFound));
+}
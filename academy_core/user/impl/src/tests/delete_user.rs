@@ -1,38 +1,56 @@
 use academy_auth_contracts::MockAuthService;
-use academy_core_user_contracts::{UserDeleteError, UserFeatureService};
+use academy_core_audit_contracts::log::MockAuditLogService;
+use academy_core_user_contracts::{
+    deletion::MockUserDeletionService, UserDeleteError, UserFeatureService,
+};
 use academy_demo::{
     session::{ADMIN_1, BAR_1, FOO_1},
     user::{ADMIN, BAR, FOO},
 };
 use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
     auth::{AuthError, AuthenticateError, AuthorizeError},
+    session::Session,
     user::UserIdOrSelf,
 };
 use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
-use academy_utils::assert_matches;
+use academy_utils::{assert_matches, Apply};
 
 use crate::{tests::Sut, UserFeatureServiceImpl};
 
 #[tokio::test]
 async fn ok_self() {
     // Arrange
-    let auth = MockAuthService::new()
-        .with_authenticate(Some((FOO.user.clone(), FOO_1.clone())))
-        .with_invalidate_access_tokens(FOO.user.id);
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
 
     let db = MockDatabase::build(true);
 
-    let user_repo = MockUserRepository::new().with_delete(FOO.user.id, true);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let user_deletion = MockUserDeletionService::new()
+        .with_schedule(FOO.clone())
+        .with_send_deleted_email(FOO.clone());
+
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(FOO.user.id),
+        FOO.user.id,
+        AuditEventKind::UserDeleted,
+        ClientInfo::default(),
+    );
 
     let sut = UserFeatureServiceImpl {
         auth,
         db,
         user_repo,
+        user_deletion,
+        audit_log,
         ..Sut::default()
     };
 
     // Act
-    let result = sut.delete_user(&"token".into(), UserIdOrSelf::Slf).await;
+    let result = sut
+        .delete_user(&"token".into(), UserIdOrSelf::Slf, ClientInfo::default())
+        .await;
 
     // Assert
     result.unwrap();
@@ -41,13 +59,54 @@ async fn ok_self() {
 #[tokio::test]
 async fn ok_admin() {
     // Arrange
-    let auth = MockAuthService::new()
-        .with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())))
-        .with_invalidate_access_tokens(FOO.user.id);
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
 
     let db = MockDatabase::build(true);
 
-    let user_repo = MockUserRepository::new().with_delete(FOO.user.id, true);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let user_deletion = MockUserDeletionService::new()
+        .with_schedule(FOO.clone())
+        .with_send_deleted_email(FOO.clone());
+
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(ADMIN.user.id),
+        FOO.user.id,
+        AuditEventKind::UserDeleted,
+        ClientInfo::default(),
+    );
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        user_deletion,
+        audit_log,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .delete_user(&"token".into(), FOO.user.id.into(), ClientInfo::default())
+        .await;
+
+    // Assert
+    result.unwrap();
+}
+
+#[tokio::test]
+async fn ok_already_deleted() {
+    // Arrange
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let foo = FOO
+        .clone()
+        .with(|x| x.user.deleted_at = Some(FOO.user.created_at));
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(foo));
 
     let sut = UserFeatureServiceImpl {
         auth,
@@ -57,7 +116,9 @@ async fn ok_admin() {
     };
 
     // Act
-    let result = sut.delete_user(&"token".into(), FOO.user.id.into()).await;
+    let result = sut
+        .delete_user(&"token".into(), FOO.user.id.into(), ClientInfo::default())
+        .await;
 
     // Assert
     result.unwrap();
@@ -74,7 +135,9 @@ async fn unauthenticated() {
     };
 
     // Act
-    let result = sut.delete_user(&"token".into(), FOO.user.id.into()).await;
+    let result = sut
+        .delete_user(&"token".into(), FOO.user.id.into(), ClientInfo::default())
+        .await;
 
     // Assert
     assert_matches!(
@@ -96,13 +159,45 @@ async fn unauthorized() {
     };
 
     // Act
-    let result = sut.delete_user(&"token".into(), FOO.user.id.into()).await;
+    let result = sut
+        .delete_user(&"token".into(), FOO.user.id.into(), ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(UserDeleteError::Auth(AuthError::Authorize(
+            AuthorizeError::Permission
+        )))
+    );
+}
+
+#[tokio::test]
+async fn impersonated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((
+        FOO.user.clone(),
+        Session {
+            impersonator_id: Some(ADMIN.user.id),
+            ..FOO_1.clone()
+        },
+    )));
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .delete_user(&"token".into(), UserIdOrSelf::Slf, ClientInfo::default())
+        .await;
 
     // Assert
     assert_matches!(
         result,
         Err(UserDeleteError::Auth(AuthError::Authorize(
-            AuthorizeError::Admin
+            AuthorizeError::Impersonation
         )))
     );
 }
@@ -110,13 +205,12 @@ async fn unauthorized() {
 #[tokio::test]
 async fn not_found() {
     // Arrange
-    let auth = MockAuthService::new()
-        .with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())))
-        .with_invalidate_access_tokens(FOO.user.id);
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
 
     let db = MockDatabase::build(false);
 
-    let user_repo = MockUserRepository::new().with_delete(FOO.user.id, false);
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, None);
 
     let sut = UserFeatureServiceImpl {
         auth,
@@ -126,7 +220,9 @@ async fn not_found() {
     };
 
     // Act
-    let result = sut.delete_user(&"token".into(), FOO.user.id.into()).await;
+    let result = sut
+        .delete_user(&"token".into(), FOO.user.id.into(), ClientInfo::default())
+        .await;
 
     // Assert
     assert_matches!(result, Err(UserDeleteError::NotFound));
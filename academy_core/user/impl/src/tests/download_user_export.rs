@@ -0,0 +1,53 @@
+use academy_core_user_contracts::{
+    export::MockUserExportService, UserDownloadExportError, UserFeatureService,
+};
+use academy_demo::{user::FOO, VERIFICATION_CODE_1};
+use academy_models::user::UserExport;
+use academy_utils::assert_matches;
+
+use crate::{tests::Sut, UserFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok() {
+    // Arrange
+    let expected = UserExport {
+        user_composite: FOO.clone(),
+        sessions: vec![],
+        totp_devices: vec![],
+        webauthn_credentials: vec![],
+        oauth2_links: vec![],
+        audit_events: vec![],
+    };
+
+    let user_export = MockUserExportService::new()
+        .with_get_download(VERIFICATION_CODE_1.clone(), Some(expected.clone()));
+
+    let sut = UserFeatureServiceImpl {
+        user_export,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.download_user_export(VERIFICATION_CODE_1.clone()).await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn invalid_code() {
+    // Arrange
+    let user_export =
+        MockUserExportService::new().with_get_download(VERIFICATION_CODE_1.clone(), None);
+
+    let sut = UserFeatureServiceImpl {
+        user_export,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.download_user_export(VERIFICATION_CODE_1.clone()).await;
+
+    // Assert
+    assert_matches!(result, Err(UserDownloadExportError::InvalidCode));
+}
@@ -0,0 +1,214 @@
+use academy_auth_contracts::MockAuthService;
+use academy_core_user_contracts::{
+    export::MockUserExportService, UserExportError, UserExportResult, UserFeatureService,
+};
+use academy_demo::{
+    session::{ADMIN_1, BAR_1, FOO_1},
+    user::{ADMIN, BAR, FOO},
+};
+use academy_models::{
+    auth::{AuthError, AuthenticateError, AuthorizeError},
+    user::{UserComposite, UserExport, UserIdOrSelf},
+};
+use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
+use academy_utils::{assert_matches, Apply};
+
+use crate::{tests::Sut, UserFeatureServiceImpl};
+
+fn export(user_composite: UserComposite) -> UserExport {
+    UserExport {
+        user_composite,
+        sessions: vec![],
+        totp_devices: vec![],
+        webauthn_credentials: vec![],
+        oauth2_links: vec![],
+        audit_events: vec![],
+    }
+}
+
+#[tokio::test]
+async fn ok_self() {
+    // Arrange
+    let db = MockDatabase::build(false);
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let user_export = MockUserExportService::new()
+        .with_is_large(FOO.user.id, false)
+        .with_export(FOO.clone(), export(FOO.clone()));
+
+    let sut = UserFeatureServiceImpl {
+        db,
+        auth,
+        user_repo,
+        user_export,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.export_user(&"token".into(), UserIdOrSelf::Slf).await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap(),
+        UserExportResult::Complete(export(FOO.clone()).into())
+    );
+}
+
+#[tokio::test]
+async fn ok_admin() {
+    // Arrange
+    let db = MockDatabase::build(false);
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let user_export = MockUserExportService::new()
+        .with_is_large(FOO.user.id, false)
+        .with_export(FOO.clone(), export(FOO.clone()));
+
+    let sut = UserFeatureServiceImpl {
+        db,
+        auth,
+        user_repo,
+        user_export,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.export_user(&"token".into(), FOO.user.id.into()).await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap(),
+        UserExportResult::Complete(export(FOO.clone()).into())
+    );
+}
+
+#[tokio::test]
+async fn ok_large() {
+    // Arrange
+    let db = MockDatabase::build(true);
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let user_export = MockUserExportService::new()
+        .with_is_large(FOO.user.id, true)
+        .with_request_export(FOO.user.id);
+
+    let sut = UserFeatureServiceImpl {
+        db,
+        auth,
+        user_repo,
+        user_export,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.export_user(&"token".into(), UserIdOrSelf::Slf).await;
+
+    // Assert
+    assert_eq!(result.unwrap(), UserExportResult::Scheduled);
+}
+
+#[tokio::test]
+async fn ok_no_email() {
+    // Arrange
+    let expected = FOO.clone().with(|u| u.user.email = None);
+
+    let db = MockDatabase::build(false);
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let user_repo =
+        MockUserRepository::new().with_get_composite(FOO.user.id, Some(expected.clone()));
+
+    let user_export =
+        MockUserExportService::new().with_export(expected.clone(), export(expected.clone()));
+
+    let sut = UserFeatureServiceImpl {
+        db,
+        auth,
+        user_repo,
+        user_export,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.export_user(&"token".into(), UserIdOrSelf::Slf).await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap(),
+        UserExportResult::Complete(export(expected).into())
+    );
+}
+
+#[tokio::test]
+async fn unauthenticated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(None);
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.export_user(&"token".into(), FOO.user.id.into()).await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(UserExportError::Auth(AuthError::Authenticate(
+            AuthenticateError::InvalidToken
+        )))
+    );
+}
+
+#[tokio::test]
+async fn unauthorized() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((BAR.user.clone(), BAR_1.clone())));
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.export_user(&"token".into(), FOO.user.id.into()).await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(UserExportError::Auth(AuthError::Authorize(
+            AuthorizeError::Permission
+        )))
+    );
+}
+
+#[tokio::test]
+async fn not_found() {
+    // Arrange
+    let db = MockDatabase::build(false);
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, None);
+
+    let sut = UserFeatureServiceImpl {
+        db,
+        auth,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.export_user(&"token".into(), FOO.user.id.into()).await;
+
+    // Assert
+    assert_matches!(result, Err(UserExportError::NotFound));
+}
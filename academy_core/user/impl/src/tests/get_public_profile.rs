@@ -0,0 +1,152 @@
+use academy_core_user_contracts::{UserFeatureService, UserGetPublicProfileError};
+use academy_demo::user::{BAR, FOO};
+use academy_models::user::{
+    User, UserComposite, UserProfile, UserProfileVisibility, UserPublicProfile,
+};
+use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
+use academy_utils::assert_matches;
+
+use crate::{tests::Sut, UserFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok() {
+    // Arrange
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let sut = UserFeatureServiceImpl {
+        db,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.get_public_profile(FOO.user.id).await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap(),
+        UserPublicProfile {
+            id: FOO.user.id,
+            display_name: FOO.profile.display_name.clone(),
+            avatar: FOO.profile.avatar.clone(),
+            bio: Some(FOO.profile.bio.clone()),
+            tags: Some(FOO.profile.tags.clone()),
+            created_at: Some(FOO.user.created_at),
+            last_login: None,
+        }
+    );
+}
+
+#[tokio::test]
+async fn ok_hidden_fields() {
+    // Arrange
+    let foo = UserComposite {
+        profile: UserProfile {
+            visibility: UserProfileVisibility {
+                bio: false,
+                tags: false,
+                created_at: false,
+                last_login: true,
+            },
+            ..FOO.profile.clone()
+        },
+        ..FOO.clone()
+    };
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(foo));
+
+    let sut = UserFeatureServiceImpl {
+        db,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.get_public_profile(FOO.user.id).await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap(),
+        UserPublicProfile {
+            id: FOO.user.id,
+            display_name: FOO.profile.display_name.clone(),
+            avatar: FOO.profile.avatar.clone(),
+            bio: None,
+            tags: None,
+            created_at: None,
+            last_login: FOO.user.last_login,
+        }
+    );
+}
+
+#[tokio::test]
+async fn not_found() {
+    // Arrange
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, None);
+
+    let sut = UserFeatureServiceImpl {
+        db,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.get_public_profile(FOO.user.id).await;
+
+    // Assert
+    assert_matches!(result, Err(UserGetPublicProfileError::NotFound));
+}
+
+#[tokio::test]
+async fn disabled() {
+    // Arrange
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(BAR.user.id, Some(BAR.clone()));
+
+    let sut = UserFeatureServiceImpl {
+        db,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.get_public_profile(BAR.user.id).await;
+
+    // Assert
+    assert_matches!(result, Err(UserGetPublicProfileError::NotFound));
+}
+
+#[tokio::test]
+async fn pending_deletion() {
+    // Arrange
+    let foo = UserComposite {
+        user: User {
+            deleted_at: Some(FOO.user.created_at),
+            ..FOO.user.clone()
+        },
+        ..FOO.clone()
+    };
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(foo));
+
+    let sut = UserFeatureServiceImpl {
+        db,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.get_public_profile(FOO.user.id).await;
+
+    // Assert
+    assert_matches!(result, Err(UserGetPublicProfileError::NotFound));
+}
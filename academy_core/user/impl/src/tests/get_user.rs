@@ -97,7 +97,7 @@ async fn unauthorized() {
     assert_matches!(
         result,
         Err(UserGetError::Auth(AuthError::Authorize(
-            AuthorizeError::Admin
+            AuthorizeError::Permission
         )))
     );
 }
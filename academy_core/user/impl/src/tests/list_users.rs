@@ -9,8 +9,9 @@ use academy_demo::{
 };
 use academy_models::{
     auth::{AuthError, AuthenticateError, AuthorizeError},
-    pagination::PaginationSlice,
-    user::UserFilter,
+    pagination::{PaginationSlice, SortDirection},
+    role::Role,
+    user::{UserFilter, UserSort, UserSortBy},
 };
 use academy_persistence_contracts::MockDatabase;
 use academy_utils::assert_matches;
@@ -25,13 +26,14 @@ async fn ok() {
     let expected = UserListResult {
         total: 42,
         user_composites: ALL_USERS.iter().copied().cloned().collect(),
+        next_cursor: None,
     };
 
     let db = MockDatabase::build(false);
     let auth =
         MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
 
-    let user = MockUserService::new().with_list(query.clone(), expected.clone());
+    let user = MockUserService::new().with_list(query.clone(), Some(expected.clone()));
 
     let sut = UserFeatureServiceImpl {
         db,
@@ -47,6 +49,31 @@ async fn ok() {
     assert_eq!(result.unwrap(), expected);
 }
 
+#[tokio::test]
+async fn invalid_cursor() {
+    // Arrange
+    let query = build_query();
+
+    let db = MockDatabase::build(false);
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let user = MockUserService::new().with_list(query.clone(), None);
+
+    let sut = UserFeatureServiceImpl {
+        db,
+        auth,
+        user,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.list_users(&"token".into(), query).await;
+
+    // Assert
+    assert_matches!(result, Err(UserListError::InvalidCursor));
+}
+
 #[tokio::test]
 async fn unauthenticated() {
     // Arrange
@@ -90,7 +117,7 @@ async fn unauthorized() {
     assert_matches!(
         result,
         Err(UserListError::Auth(AuthError::Authorize(
-            AuthorizeError::Admin
+            AuthorizeError::Permission
         )))
     );
 }
@@ -100,15 +127,22 @@ fn build_query() -> UserListQuery {
         pagination: PaginationSlice {
             limit: 42.try_into().unwrap(),
             offset: 7,
-        },
+        }
+        .into(),
         filter: UserFilter {
             name: Some("the name".try_into().unwrap()),
             email: Some("the email".try_into().unwrap()),
             enabled: Some(true),
-            admin: Some(false),
+            role: Some(Role::Support),
             mfa_enabled: None,
             email_verified: Some(true),
             newsletter: Some(false),
+            can_receive_coins: Some(true),
+            ..Default::default()
+        },
+        sort: UserSort {
+            by: UserSortBy::Name,
+            direction: SortDirection::Asc,
         },
     }
 }
@@ -1,25 +1,35 @@
 use std::time::Duration;
 
 use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::log::MockAuditLogService;
 use academy_core_oauth2_contracts::registration::MockOAuth2RegistrationService;
 use academy_core_session_contracts::session::MockSessionService;
 use academy_core_user_contracts::{
-    email_confirmation::MockUserEmailConfirmationService, update::MockUserUpdateService,
+    avatar::MockUserAvatarService, deletion::MockUserDeletionService,
+    email_confirmation::MockUserEmailConfirmationService, export::MockUserExportService,
+    password_policy::MockUserPasswordPolicyService, update::MockUserUpdateService,
     user::MockUserService,
 };
 use academy_extern_contracts::{internal::MockInternalApiService, vat::MockVatApiService};
 use academy_persistence_contracts::{user::MockUserRepository, MockDatabase, MockTransaction};
-use academy_shared_contracts::captcha::MockCaptchaService;
+use academy_shared_contracts::{captcha::MockCaptchaService, throttle::MockThrottleService};
 
 use crate::{UserFeatureConfig, UserFeatureServiceImpl};
 
 mod create_user;
+mod delete_avatar;
 mod delete_user;
+mod download_user_export;
+mod export_user;
+mod get_public_profile;
 mod get_user;
 mod list_users;
 mod request_password_reset;
 mod request_verification_email;
 mod reset_password;
+mod restore_user;
+mod search_profiles;
+mod update_avatar;
 mod update_user;
 mod verify_email;
 mod verify_newsletter_subscription;
@@ -28,14 +38,20 @@ type Sut = UserFeatureServiceImpl<
     MockDatabase,
     MockAuthService<MockTransaction>,
     MockCaptchaService,
+    MockThrottleService,
     MockVatApiService,
     MockInternalApiService,
     MockUserService<MockTransaction>,
     MockUserEmailConfirmationService<MockTransaction>,
     MockUserUpdateService<MockTransaction>,
+    MockUserExportService<MockTransaction>,
+    MockUserDeletionService<MockTransaction>,
+    MockUserAvatarService,
+    MockUserPasswordPolicyService,
     MockSessionService<MockTransaction>,
     MockOAuth2RegistrationService,
     MockUserRepository<MockTransaction>,
+    MockAuditLogService<MockTransaction>,
 >;
 
 impl Default for UserFeatureConfig {
@@ -54,6 +70,19 @@ impl Default for UserFeatureConfig {
                 .to_owned()
                 .into(),
             newsletter_subscription_verification_code_ttl: Duration::from_secs(3600),
+            export_async_threshold: 1000,
+            export_redirect_url: "https://bootstrap.academy/account/export".to_owned().into(),
+            export_download_ttl: Duration::from_secs(7 * 24 * 3600),
+            deletion_grace_period: Duration::from_secs(30 * 24 * 3600),
+            restore_redirect_url: "https://bootstrap.academy/account/restore"
+                .to_owned()
+                .into(),
+            avatar_max_size: 5 * 1024 * 1024,
+            avatar_dimensions: 128..=4096,
+            password_min_length: 8,
+            password_required_character_classes: [].into(),
+            password_min_strength: 2,
+            password_breach_check: true,
         }
     }
 }
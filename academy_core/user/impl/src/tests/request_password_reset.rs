@@ -1,10 +1,15 @@
+use std::time::Duration;
+
 use academy_core_user_contracts::{
     email_confirmation::MockUserEmailConfirmationService, UserFeatureService,
     UserRequestPasswordResetError,
 };
-use academy_demo::user::FOO;
+use academy_demo::{audit::FOO_AUDIT_EVENT_1, user::FOO};
 use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
-use academy_shared_contracts::captcha::{CaptchaCheckError, MockCaptchaService};
+use academy_shared_contracts::{
+    captcha::{CaptchaCheckError, MockCaptchaService},
+    throttle::{MockThrottleService, ThrottleKey},
+};
 use academy_utils::assert_matches;
 
 use crate::{tests::Sut, UserFeatureServiceImpl};
@@ -12,6 +17,17 @@ use crate::{tests::Sut, UserFeatureServiceImpl};
 #[tokio::test]
 async fn ok() {
     // Arrange
+    let throttle = MockThrottleService::new().with_record(
+        vec![
+            ThrottleKey::account("password_reset", FOO.user.email.as_ref().unwrap().as_str()),
+            ThrottleKey::ip(
+                "password_reset",
+                FOO_AUDIT_EVENT_1.client.ip_address.unwrap(),
+            ),
+        ],
+        None,
+    );
+
     let db = MockDatabase::build(false);
 
     let captcha = MockCaptchaService::new().with_check(Some("resp"), Ok(()));
@@ -30,6 +46,7 @@ async fn ok() {
         );
 
     let sut = UserFeatureServiceImpl {
+        throttle,
         db,
         captcha,
         user_repo,
@@ -42,6 +59,7 @@ async fn ok() {
         .request_password_reset(
             FOO.user.email.clone().unwrap(),
             Some("resp".try_into().unwrap()),
+            FOO_AUDIT_EVENT_1.client.clone(),
         )
         .await;
 
@@ -52,10 +70,22 @@ async fn ok() {
 #[tokio::test]
 async fn invalid_captcha_response() {
     // Arrange
+    let throttle = MockThrottleService::new().with_record(
+        vec![
+            ThrottleKey::account("password_reset", FOO.user.email.as_ref().unwrap().as_str()),
+            ThrottleKey::ip(
+                "password_reset",
+                FOO_AUDIT_EVENT_1.client.ip_address.unwrap(),
+            ),
+        ],
+        None,
+    );
+
     let captcha =
         MockCaptchaService::new().with_check(Some("resp"), Err(CaptchaCheckError::Failed));
 
     let sut = UserFeatureServiceImpl {
+        throttle,
         captcha,
         ..Sut::default()
     };
@@ -65,6 +95,7 @@ async fn invalid_captcha_response() {
         .request_password_reset(
             FOO.user.email.clone().unwrap(),
             Some("resp".try_into().unwrap()),
+            FOO_AUDIT_EVENT_1.client.clone(),
         )
         .await;
 
@@ -75,6 +106,17 @@ async fn invalid_captcha_response() {
 #[tokio::test]
 async fn user_not_found() {
     // Arrange
+    let throttle = MockThrottleService::new().with_record(
+        vec![
+            ThrottleKey::account("password_reset", FOO.user.email.as_ref().unwrap().as_str()),
+            ThrottleKey::ip(
+                "password_reset",
+                FOO_AUDIT_EVENT_1.client.ip_address.unwrap(),
+            ),
+        ],
+        None,
+    );
+
     let db = MockDatabase::build(false);
 
     let captcha = MockCaptchaService::new().with_check(None, Ok(()));
@@ -83,6 +125,7 @@ async fn user_not_found() {
         .with_get_composite_by_email(FOO.user.email.clone().unwrap(), None);
 
     let sut = UserFeatureServiceImpl {
+        throttle,
         db,
         captcha,
         user_repo,
@@ -91,9 +134,50 @@ async fn user_not_found() {
 
     // Act
     let result = sut
-        .request_password_reset(FOO.user.email.clone().unwrap(), None)
+        .request_password_reset(
+            FOO.user.email.clone().unwrap(),
+            None,
+            FOO_AUDIT_EVENT_1.client.clone(),
+        )
         .await;
 
     // Assert
     result.unwrap();
 }
+
+#[tokio::test]
+async fn rate_limit() {
+    // Arrange
+    let expected = FOO.user.created_at + Duration::from_secs(60);
+
+    let throttle = MockThrottleService::new().with_record(
+        vec![
+            ThrottleKey::account("password_reset", FOO.user.email.as_ref().unwrap().as_str()),
+            ThrottleKey::ip(
+                "password_reset",
+                FOO_AUDIT_EVENT_1.client.ip_address.unwrap(),
+            ),
+        ],
+        Some(expected),
+    );
+
+    let sut = UserFeatureServiceImpl {
+        throttle,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .request_password_reset(
+            FOO.user.email.clone().unwrap(),
+            None,
+            FOO_AUDIT_EVENT_1.client.clone(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(UserRequestPasswordResetError::RateLimit { until }) if *until == expected
+    );
+}
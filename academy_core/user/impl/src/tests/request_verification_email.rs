@@ -136,7 +136,7 @@ async fn unauthorized() {
     assert_matches!(
         result,
         Err(UserRequestVerificationEmailError::Auth(
-            AuthError::Authorize(AuthorizeError::Admin)
+            AuthError::Authorize(AuthorizeError::Permission)
         ))
     );
 }
@@ -1,13 +1,19 @@
+use academy_core_audit_contracts::log::MockAuditLogService;
 use academy_core_user_contracts::{
     email_confirmation::{
         MockUserEmailConfirmationService, UserEmailConfirmationResetPasswordError,
     },
+    password_policy::MockUserPasswordPolicyService,
     UserFeatureService, UserResetPasswordError,
 };
 use academy_demo::{
     user::{FOO, FOO_PASSWORD},
     VERIFICATION_CODE_1,
 };
+use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
+    user::UserPasswordPolicyViolation,
+};
 use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
 use academy_utils::assert_matches;
 
@@ -16,6 +22,12 @@ use crate::{tests::Sut, UserFeatureServiceImpl};
 #[tokio::test]
 async fn ok() {
     // Arrange
+    let user_password_policy = MockUserPasswordPolicyService::new().with_check(
+        FOO_PASSWORD.clone(),
+        vec![FOO.user.email.clone().unwrap().as_str().into()],
+        vec![],
+    );
+
     let db = MockDatabase::build(true);
 
     let user_repo = MockUserRepository::new()
@@ -28,10 +40,19 @@ async fn ok() {
         Ok(()),
     );
 
+    let audit_log = MockAuditLogService::new().with_record(
+        None,
+        FOO.user.id,
+        AuditEventKind::PasswordReset,
+        ClientInfo::default(),
+    );
+
     let sut = UserFeatureServiceImpl {
         db,
         user_repo,
         user_email_confirmation,
+        audit_log,
+        user_password_policy,
         ..Sut::default()
     };
 
@@ -41,6 +62,7 @@ async fn ok() {
             FOO.user.email.clone().unwrap(),
             VERIFICATION_CODE_1.clone(),
             FOO_PASSWORD.clone(),
+            ClientInfo::default(),
         )
         .await;
 
@@ -51,6 +73,12 @@ async fn ok() {
 #[tokio::test]
 async fn user_not_found() {
     // Arrange
+    let user_password_policy = MockUserPasswordPolicyService::new().with_check(
+        FOO_PASSWORD.clone(),
+        vec![FOO.user.email.clone().unwrap().as_str().into()],
+        vec![],
+    );
+
     let db = MockDatabase::build(false);
 
     let user_repo = MockUserRepository::new()
@@ -59,6 +87,7 @@ async fn user_not_found() {
     let sut = UserFeatureServiceImpl {
         db,
         user_repo,
+        user_password_policy,
         ..Sut::default()
     };
 
@@ -68,6 +97,7 @@ async fn user_not_found() {
             FOO.user.email.clone().unwrap(),
             VERIFICATION_CODE_1.clone(),
             FOO_PASSWORD.clone(),
+            ClientInfo::default(),
         )
         .await;
 
@@ -78,6 +108,12 @@ async fn user_not_found() {
 #[tokio::test]
 async fn invalid_code() {
     // Arrange
+    let user_password_policy = MockUserPasswordPolicyService::new().with_check(
+        FOO_PASSWORD.clone(),
+        vec![FOO.user.email.clone().unwrap().as_str().into()],
+        vec![],
+    );
+
     let db = MockDatabase::build(false);
 
     let user_repo = MockUserRepository::new()
@@ -94,6 +130,7 @@ async fn invalid_code() {
         db,
         user_repo,
         user_email_confirmation,
+        user_password_policy,
         ..Sut::default()
     };
 
@@ -103,9 +140,43 @@ async fn invalid_code() {
             FOO.user.email.clone().unwrap(),
             VERIFICATION_CODE_1.clone(),
             FOO_PASSWORD.clone(),
+            ClientInfo::default(),
         )
         .await;
 
     // Act
     assert_matches!(result, Err(UserResetPasswordError::Failed));
 }
+
+#[tokio::test]
+async fn weak_password() {
+    // Arrange
+    let violations = vec![UserPasswordPolicyViolation::TooWeak {
+        score: 1,
+        min_score: 2,
+    }];
+
+    let user_password_policy = MockUserPasswordPolicyService::new().with_check(
+        FOO_PASSWORD.clone(),
+        vec![FOO.user.email.clone().unwrap().as_str().into()],
+        violations.clone(),
+    );
+
+    let sut = UserFeatureServiceImpl {
+        user_password_policy,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .reset_password(
+            FOO.user.email.clone().unwrap(),
+            VERIFICATION_CODE_1.clone(),
+            FOO_PASSWORD.clone(),
+            ClientInfo::default(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(UserResetPasswordError::WeakPassword(v)) if *v == violations);
+}
@@ -0,0 +1,64 @@
+use academy_core_audit_contracts::log::MockAuditLogService;
+use academy_core_user_contracts::{
+    deletion::MockUserDeletionService, UserFeatureService, UserRestoreError,
+};
+use academy_demo::{user::FOO, VERIFICATION_CODE_1};
+use academy_models::audit::{AuditEventKind, ClientInfo};
+use academy_persistence_contracts::MockDatabase;
+use academy_utils::assert_matches;
+
+use crate::{tests::Sut, UserFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok() {
+    // Arrange
+    let db = MockDatabase::build(true);
+
+    let user_deletion = MockUserDeletionService::new()
+        .with_restore_by_code(VERIFICATION_CODE_1.clone(), Some(FOO.user.id));
+
+    let audit_log = MockAuditLogService::new().with_record(
+        None,
+        FOO.user.id,
+        AuditEventKind::UserRestored,
+        ClientInfo::default(),
+    );
+
+    let sut = UserFeatureServiceImpl {
+        db,
+        user_deletion,
+        audit_log,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .restore_user(VERIFICATION_CODE_1.clone(), ClientInfo::default())
+        .await;
+
+    // Assert
+    result.unwrap();
+}
+
+#[tokio::test]
+async fn invalid_code() {
+    // Arrange
+    let db = MockDatabase::build(false);
+
+    let user_deletion =
+        MockUserDeletionService::new().with_restore_by_code(VERIFICATION_CODE_1.clone(), None);
+
+    let sut = UserFeatureServiceImpl {
+        db,
+        user_deletion,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .restore_user(VERIFICATION_CODE_1.clone(), ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(UserRestoreError::InvalidCode));
+}
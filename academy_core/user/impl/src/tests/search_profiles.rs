@@ -0,0 +1,79 @@
+use academy_core_user_contracts::{UserFeatureService, UserProfileSearchResult};
+use academy_demo::user::{ADMIN, FOO};
+use academy_models::{
+    pagination::PaginationSlice,
+    user::{UserComposite, UserProfile, UserProfileVisibility, UserPublicProfile},
+};
+use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
+
+use crate::{tests::Sut, UserFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok() {
+    // Arrange
+    let query = "foo".try_into().unwrap();
+    let pagination = PaginationSlice {
+        limit: 2.try_into().unwrap(),
+        offset: 3,
+    };
+
+    let admin = UserComposite {
+        profile: UserProfile {
+            visibility: UserProfileVisibility {
+                bio: false,
+                tags: true,
+                created_at: false,
+                last_login: true,
+            },
+            ..ADMIN.profile.clone()
+        },
+        ..ADMIN.clone()
+    };
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new()
+        .with_count_search_profiles("foo".try_into().unwrap(), 5)
+        .with_search_profiles(
+            "foo".try_into().unwrap(),
+            pagination,
+            vec![FOO.clone(), admin],
+        );
+
+    let sut = UserFeatureServiceImpl {
+        db,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut.search_profiles(query, pagination).await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap(),
+        UserProfileSearchResult {
+            total: 5,
+            profiles: vec![
+                UserPublicProfile {
+                    id: FOO.user.id,
+                    display_name: FOO.profile.display_name.clone(),
+                    avatar: FOO.profile.avatar.clone(),
+                    bio: Some(FOO.profile.bio.clone()),
+                    tags: Some(FOO.profile.tags.clone()),
+                    created_at: Some(FOO.user.created_at),
+                    last_login: None,
+                },
+                UserPublicProfile {
+                    id: ADMIN.user.id,
+                    display_name: ADMIN.profile.display_name.clone(),
+                    avatar: ADMIN.profile.avatar.clone(),
+                    bio: None,
+                    tags: Some(ADMIN.profile.tags.clone()),
+                    created_at: None,
+                    last_login: ADMIN.user.last_login,
+                },
+            ],
+        }
+    );
+}
@@ -0,0 +1,275 @@
+use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::log::MockAuditLogService;
+use academy_core_user_contracts::{
+    avatar::{MockUserAvatarService, UserAvatarUploadError},
+    UserFeatureService, UserUpdateAvatarError,
+};
+use academy_demo::{
+    session::{ADMIN_1, BAR_1, FOO_1},
+    user::{ADMIN, BAR, FOO, FOO_AVATAR},
+};
+use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
+    auth::{AuthError, AuthenticateError, AuthorizeError},
+    user::{UserAvatar, UserIdOrSelf},
+};
+use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
+use academy_utils::{assert_matches, Apply};
+use uuid::uuid;
+
+use crate::{tests::Sut, UserFeatureServiceImpl};
+
+#[tokio::test]
+async fn ok_self() {
+    // Arrange
+    let data = b"avatar".to_vec();
+    let avatar = new_avatar();
+
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let user_repo = MockUserRepository::new()
+        .with_get_composite(FOO.user.id, Some(FOO.clone()))
+        .with_update_avatar(FOO.user.id, Some(avatar.clone()), true);
+
+    let user_avatar = MockUserAvatarService::new()
+        .with_upload(data.clone(), Ok(avatar.clone()))
+        .with_remove(FOO_AVATAR.clone());
+
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(FOO.user.id),
+        FOO.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        user_avatar,
+        audit_log,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .update_avatar(
+            &"token".into(),
+            UserIdOrSelf::Slf,
+            data,
+            ClientInfo::default(),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap(),
+        FOO.clone().with(|x| x.profile.avatar = Some(avatar))
+    );
+}
+
+#[tokio::test]
+async fn ok_admin() {
+    // Arrange
+    let data = b"avatar".to_vec();
+    let avatar = new_avatar();
+
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let user_repo = MockUserRepository::new()
+        .with_get_composite(BAR.user.id, Some(BAR.clone()))
+        .with_update_avatar(BAR.user.id, Some(avatar.clone()), true);
+
+    let user_avatar = MockUserAvatarService::new().with_upload(data.clone(), Ok(avatar.clone()));
+
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(ADMIN.user.id),
+        BAR.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        user_avatar,
+        audit_log,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .update_avatar(
+            &"token".into(),
+            BAR.user.id.into(),
+            data,
+            ClientInfo::default(),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap(),
+        BAR.clone().with(|x| x.profile.avatar = Some(avatar))
+    );
+}
+
+#[tokio::test]
+async fn invalid_image() {
+    for (err, expected) in [
+        (
+            UserAvatarUploadError::TooLarge,
+            UserUpdateAvatarError::TooLarge,
+        ),
+        (
+            UserAvatarUploadError::UnsupportedFormat,
+            UserUpdateAvatarError::UnsupportedFormat,
+        ),
+        (
+            UserAvatarUploadError::InvalidImage,
+            UserUpdateAvatarError::InvalidImage,
+        ),
+        (
+            UserAvatarUploadError::InvalidDimensions,
+            UserUpdateAvatarError::InvalidDimensions,
+        ),
+    ] {
+        // Arrange
+        let data = b"avatar".to_vec();
+
+        let auth =
+            MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+        let db = MockDatabase::build(false);
+
+        let user_repo =
+            MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+        let user_avatar = MockUserAvatarService::new().with_upload(data.clone(), Err(err));
+
+        let sut = UserFeatureServiceImpl {
+            auth,
+            db,
+            user_repo,
+            user_avatar,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut
+            .update_avatar(
+                &"token".into(),
+                UserIdOrSelf::Slf,
+                data,
+                ClientInfo::default(),
+            )
+            .await;
+
+        // Assert
+        assert_eq!(result.unwrap_err().to_string(), expected.to_string());
+    }
+}
+
+#[tokio::test]
+async fn unauthenticated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(None);
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .update_avatar(
+            &"token".into(),
+            FOO.user.id.into(),
+            b"avatar".to_vec(),
+            ClientInfo::default(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(UserUpdateAvatarError::Auth(AuthError::Authenticate(
+            AuthenticateError::InvalidToken
+        )))
+    );
+}
+
+#[tokio::test]
+async fn unauthorized() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((BAR.user.clone(), BAR_1.clone())));
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .update_avatar(
+            &"token".into(),
+            FOO.user.id.into(),
+            b"avatar".to_vec(),
+            ClientInfo::default(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(UserUpdateAvatarError::Auth(AuthError::Authorize(
+            AuthorizeError::Permission
+        )))
+    );
+}
+
+#[tokio::test]
+async fn not_found() {
+    // Arrange
+    let auth =
+        MockAuthService::new().with_authenticate(Some((ADMIN.user.clone(), ADMIN_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, None);
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .update_avatar(
+            &"token".into(),
+            FOO.user.id.into(),
+            b"avatar".to_vec(),
+            ClientInfo::default(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(UserUpdateAvatarError::NotFound));
+}
+
+fn new_avatar() -> UserAvatar {
+    UserAvatar {
+        id: uuid!("a3b0f1d2-6c4e-4f8a-9b7d-2e5c8f1a4d60").into(),
+        url: "https://example.com/storage/avatars/a3b0f1d2-6c4e-4f8a-9b7d-2e5c8f1a4d60/"
+            .parse()
+            .unwrap(),
+    }
+}
@@ -1,4 +1,5 @@
 use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::log::MockAuditLogService;
 use academy_core_user_contracts::{
     update::{MockUserUpdateService, UserUpdateEmailError},
     UserFeatureService, UserUpdateError, UserUpdateRequest, UserUpdateUserRequest,
@@ -7,7 +8,12 @@ use academy_demo::{
     session::{ADMIN_1, FOO_1},
     user::{ADMIN, FOO},
 };
-use academy_models::user::{User, UserComposite, UserIdOrSelf};
+use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
+    auth::{AuthError, AuthorizeError},
+    session::Session,
+    user::{User, UserComposite, UserIdOrSelf},
+};
 use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
 use academy_utils::assert_matches;
 
@@ -38,11 +44,19 @@ async fn update_email_self() {
         Ok(true),
     );
 
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(FOO.user.id),
+        FOO.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
     let sut = UserFeatureServiceImpl {
         auth,
         db,
         user_update,
         user_repo,
+        audit_log,
         ..Sut::default()
     };
 
@@ -58,6 +72,7 @@ async fn update_email_self() {
                 },
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
@@ -91,11 +106,19 @@ async fn update_email_admin() {
         Ok(true),
     );
 
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(ADMIN.user.id),
+        FOO.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
     let sut = UserFeatureServiceImpl {
         auth,
         db,
         user_update,
         user_repo,
+        audit_log,
         ..Sut::default()
     };
 
@@ -111,6 +134,7 @@ async fn update_email_admin() {
                 },
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
@@ -144,11 +168,19 @@ async fn update_email_admin_verified() {
         Ok(true),
     );
 
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(ADMIN.user.id),
+        FOO.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
     let sut = UserFeatureServiceImpl {
         auth,
         db,
         user_update,
         user_repo,
+        audit_log,
         ..Sut::default()
     };
 
@@ -165,6 +197,7 @@ async fn update_email_admin_verified() {
                 },
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
@@ -198,11 +231,19 @@ async fn update_email_admin_unverified() {
         Ok(true),
     );
 
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(ADMIN.user.id),
+        FOO.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
     let sut = UserFeatureServiceImpl {
         auth,
         db,
         user_update,
         user_repo,
+        audit_log,
         ..Sut::default()
     };
 
@@ -219,6 +260,7 @@ async fn update_email_admin_unverified() {
                 },
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
@@ -254,11 +296,19 @@ async fn update_set_email_verified() {
         Ok(true),
     );
 
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(ADMIN.user.id),
+        FOO.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
     let sut = UserFeatureServiceImpl {
         auth,
         db,
         user_update,
         user_repo,
+        audit_log,
         ..Sut::default()
     };
 
@@ -274,6 +324,7 @@ async fn update_set_email_verified() {
                 },
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
@@ -306,11 +357,19 @@ async fn update_set_email_unverified() {
         Ok(true),
     );
 
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(ADMIN.user.id),
+        FOO.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
     let sut = UserFeatureServiceImpl {
         auth,
         db,
         user_update,
         user_repo,
+        audit_log,
         ..Sut::default()
     };
 
@@ -326,6 +385,7 @@ async fn update_set_email_unverified() {
                 },
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
@@ -369,9 +429,57 @@ async fn update_email_conflict() {
                 },
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
     // Assert
     assert_matches!(result, Err(UserUpdateError::EmailConflict));
 }
+
+#[tokio::test]
+async fn update_email_impersonated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((
+        FOO.user.clone(),
+        Session {
+            impersonator_id: Some(ADMIN.user.id),
+            ..FOO_1.clone()
+        },
+    )));
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .update_user(
+            &"token".into(),
+            UserIdOrSelf::Slf,
+            UserUpdateRequest {
+                user: UserUpdateUserRequest {
+                    email: ADMIN.user.email.clone().unwrap().into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ClientInfo::default(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(UserUpdateError::Auth(AuthError::Authorize(
+            AuthorizeError::Impersonation
+        )))
+    );
+}
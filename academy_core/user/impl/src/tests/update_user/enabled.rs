@@ -1,4 +1,5 @@
 use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::log::MockAuditLogService;
 use academy_core_user_contracts::{
     update::MockUserUpdateService, UserFeatureService, UserUpdateError, UserUpdateRequest,
     UserUpdateUserRequest,
@@ -7,7 +8,10 @@ use academy_demo::{
     session::ADMIN_1,
     user::{ADMIN, BAR, FOO},
 };
-use academy_models::user::{User, UserComposite, UserIdOrSelf};
+use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
+    user::{User, UserComposite, UserIdOrSelf},
+};
 use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
 use academy_utils::assert_matches;
 
@@ -36,11 +40,19 @@ async fn update_enabled() {
         let user_update =
             MockUserUpdateService::new().with_update_enabled(user_composite.user.id, enabled, true);
 
+        let audit_log = MockAuditLogService::new().with_record(
+            Some(ADMIN.user.id),
+            user_composite.user.id,
+            AuditEventKind::UserUpdated,
+            ClientInfo::default(),
+        );
+
         let sut = UserFeatureServiceImpl {
             auth,
             db,
             user_update,
             user_repo,
+            audit_log,
             ..Sut::default()
         };
 
@@ -56,6 +68,7 @@ async fn update_enabled() {
                     },
                     ..Default::default()
                 },
+                ClientInfo::default(),
             )
             .await;
 
@@ -94,6 +107,7 @@ async fn disable_self() {
                 },
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
@@ -1,4 +1,5 @@
 use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::log::MockAuditLogService;
 use academy_core_user_contracts::{
     update::MockUserUpdateService, UserFeatureService, UserUpdateError, UserUpdateRequest,
 };
@@ -7,7 +8,10 @@ use academy_demo::{
     user::{BAR, FOO},
 };
 use academy_extern_contracts::{internal::MockInternalApiService, vat::MockVatApiService};
-use academy_models::user::{UserComposite, UserIdOrSelf, UserInvoiceInfo};
+use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
+    user::{UserComposite, UserIdOrSelf, UserInvoiceInfo},
+};
 use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
 use academy_utils::{assert_matches, patch::Patch, Apply};
 
@@ -43,11 +47,19 @@ async fn ok() {
         expected.invoice_info.clone(),
     );
 
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(BAR.user.id),
+        BAR.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
     let sut = UserFeatureServiceImpl {
         auth,
         db,
         user_repo,
         user_update,
+        audit_log,
         ..Sut::default()
     };
 
@@ -60,6 +72,7 @@ async fn ok() {
                 invoice_info: expected.invoice_info.clone(),
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
@@ -96,6 +109,13 @@ async fn ok_release_coins() {
 
     let internal_api = MockInternalApiService::new().with_release_coins(BAR.user.id);
 
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(BAR.user.id),
+        BAR.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
     let sut = UserFeatureServiceImpl {
         auth,
         db,
@@ -103,6 +123,7 @@ async fn ok_release_coins() {
         user_update,
         vat_api,
         internal_api,
+        audit_log,
         ..Sut::default()
     };
 
@@ -115,6 +136,7 @@ async fn ok_release_coins() {
                 invoice_info: expected.invoice_info.clone(),
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
@@ -159,6 +181,7 @@ async fn invalid_vat_id() {
                 invoice_info: expected.invoice_info.clone(),
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
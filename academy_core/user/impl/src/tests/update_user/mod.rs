@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use academy_auth_contracts::MockAuthService;
 use academy_core_user_contracts::{
     UserFeatureService, UserUpdateError, UserUpdateRequest, UserUpdateUserRequest,
@@ -6,13 +8,17 @@ use academy_demo::{
     session::{ADMIN_1, BAR_1, FOO_1},
     user::{ADMIN, BAR, FOO},
 };
-use academy_models::auth::{AuthError, AuthenticateError, AuthorizeError};
+use academy_models::{
+    audit::ClientInfo,
+    auth::{AuthError, AuthenticateError, AuthorizeError},
+    role::Role,
+    user::{User, UserInvoiceInfo},
+};
 use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
 use academy_utils::assert_matches;
 
 use crate::{tests::Sut, UserFeatureServiceImpl};
 
-mod admin;
 mod email;
 mod enabled;
 mod invoice_info;
@@ -21,6 +27,7 @@ mod newsletter;
 mod no_op;
 mod password;
 mod profile;
+mod roles;
 
 #[tokio::test]
 async fn unauthenticated() {
@@ -34,7 +41,12 @@ async fn unauthenticated() {
 
     // Act
     let result = sut
-        .update_user(&"token".into(), FOO.user.id.into(), Default::default())
+        .update_user(
+            &"token".into(),
+            FOO.user.id.into(),
+            Default::default(),
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -58,14 +70,19 @@ async fn unauthorized() {
 
     // Act
     let result = sut
-        .update_user(&"token".into(), FOO.user.id.into(), Default::default())
+        .update_user(
+            &"token".into(),
+            FOO.user.id.into(),
+            Default::default(),
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
     assert_matches!(
         result,
         Err(UserUpdateError::Auth(AuthError::Authorize(
-            AuthorizeError::Admin
+            AuthorizeError::Permission
         )))
     );
 }
@@ -89,7 +106,7 @@ async fn unauthorized_admin() {
         },
         UserUpdateRequest {
             user: UserUpdateUserRequest {
-                admin: true.into(),
+                roles: BTreeSet::from([Role::Superuser]).into(),
                 ..Default::default()
             },
             ..Default::default()
@@ -117,14 +134,82 @@ async fn unauthorized_admin() {
 
         // Act
         let result = sut
-            .update_user(&"token".into(), FOO.user.id.into(), request)
+            .update_user(
+                &"token".into(),
+                FOO.user.id.into(),
+                request,
+                ClientInfo::default(),
+            )
+            .await;
+
+        // Assert
+        assert_matches!(
+            result,
+            Err(UserUpdateError::Auth(AuthError::Authorize(
+                AuthorizeError::Permission
+            )))
+        );
+    }
+}
+
+#[tokio::test]
+async fn unauthorized_role() {
+    let moderator = User {
+        roles: BTreeSet::from([Role::Moderator]),
+        ..BAR.user.clone()
+    };
+
+    let requests = [
+        UserUpdateRequest {
+            user: UserUpdateUserRequest {
+                name: BAR.user.name.clone().into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        UserUpdateRequest {
+            invoice_info: UserInvoiceInfo {
+                city: Some("the city".try_into().unwrap()),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    ];
+
+    for request in requests {
+        eprintln!("request = {request:?}");
+
+        // Arrange
+        let auth =
+            MockAuthService::new().with_authenticate(Some((moderator.clone(), BAR_1.clone())));
+
+        let db = MockDatabase::build(false);
+
+        let user_repo =
+            MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+        let sut = UserFeatureServiceImpl {
+            auth,
+            db,
+            user_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut
+            .update_user(
+                &"token".into(),
+                FOO.user.id.into(),
+                request,
+                ClientInfo::default(),
+            )
             .await;
 
         // Assert
         assert_matches!(
             result,
             Err(UserUpdateError::Auth(AuthError::Authorize(
-                AuthorizeError::Admin
+                AuthorizeError::Permission
             )))
         );
     }
@@ -149,7 +234,12 @@ async fn not_found() {
 
     // Act
     let result = sut
-        .update_user(&"token".into(), FOO.user.id.into(), Default::default())
+        .update_user(
+            &"token".into(),
+            FOO.user.id.into(),
+            Default::default(),
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::log::MockAuditLogService;
 use academy_core_user_contracts::{
     update::{MockUserUpdateService, UserUpdateNameError, UserUpdateNameRateLimitPolicy},
     UserFeatureService, UserUpdateError, UserUpdateRequest, UserUpdateUserRequest,
@@ -9,7 +10,10 @@ use academy_demo::{
     session::{ADMIN_1, FOO_1},
     user::{ADMIN, BAR, FOO},
 };
-use academy_models::user::{User, UserComposite, UserIdOrSelf};
+use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
+    user::{User, UserComposite, UserIdOrSelf},
+};
 use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
 use academy_utils::assert_matches;
 
@@ -40,11 +44,19 @@ async fn update_name_self() {
         Ok(expected.user.clone()),
     );
 
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(FOO.user.id),
+        FOO.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
     let sut = UserFeatureServiceImpl {
         auth,
         db,
         user_update,
         user_repo,
+        audit_log,
         ..Sut::default()
     };
 
@@ -60,6 +72,7 @@ async fn update_name_self() {
                 },
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
@@ -93,11 +106,19 @@ async fn update_name_admin_no_rate_limit() {
         Ok(expected.user.clone()),
     );
 
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(ADMIN.user.id),
+        FOO.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
     let sut = UserFeatureServiceImpl {
         auth,
         db,
         user_update,
         user_repo,
+        audit_log,
         ..Sut::default()
     };
 
@@ -113,6 +134,7 @@ async fn update_name_admin_no_rate_limit() {
                 },
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
@@ -158,6 +180,7 @@ async fn update_name_self_rate_limit() {
                 },
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
@@ -201,6 +224,7 @@ async fn update_name_conflict() {
                 },
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
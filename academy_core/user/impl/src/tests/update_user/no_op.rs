@@ -4,7 +4,7 @@ use academy_demo::{
     session::{ADMIN_1, FOO_1},
     user::{ADMIN, FOO},
 };
-use academy_models::user::UserIdOrSelf;
+use academy_models::{audit::ClientInfo, user::UserIdOrSelf};
 use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
 
 use crate::{tests::Sut, UserFeatureServiceImpl};
@@ -27,7 +27,12 @@ async fn no_op_self() {
 
     // Act
     let result = sut
-        .update_user(&"token".into(), UserIdOrSelf::Slf, Default::default())
+        .update_user(
+            &"token".into(),
+            UserIdOrSelf::Slf,
+            Default::default(),
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -53,7 +58,12 @@ async fn no_op_admin() {
 
     // Act
     let result = sut
-        .update_user(&"token".into(), FOO.user.id.into(), Default::default())
+        .update_user(
+            &"token".into(),
+            FOO.user.id.into(),
+            Default::default(),
+            ClientInfo::default(),
+        )
         .await;
 
     // Assert
@@ -1,10 +1,19 @@
 use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::log::MockAuditLogService;
 use academy_core_user_contracts::{
-    update::MockUserUpdateService, PasswordUpdate, UserFeatureService, UserUpdateError,
-    UserUpdateRequest, UserUpdateUserRequest,
+    password_policy::MockUserPasswordPolicyService, update::MockUserUpdateService, PasswordUpdate,
+    UserFeatureService, UserUpdateError, UserUpdateRequest, UserUpdateUserRequest,
+};
+use academy_demo::{
+    session::FOO_1,
+    user::{ADMIN, FOO},
+};
+use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
+    auth::{AuthError, AuthorizeError},
+    session::Session,
+    user::{UserIdOrSelf, UserPassword, UserPasswordPolicyViolation},
 };
-use academy_demo::{session::FOO_1, user::FOO};
-use academy_models::user::{UserIdOrSelf, UserPassword};
 use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
 use academy_utils::{assert_matches, patch::PatchValue, Apply};
 
@@ -21,14 +30,29 @@ async fn update_password() {
 
     let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
 
+    let user_password_policy = MockUserPasswordPolicyService::new().with_check(
+        new_password.clone(),
+        user_inputs(),
+        vec![],
+    );
+
     let user_update =
         MockUserUpdateService::new().with_update_password(FOO.user.id, new_password.clone());
 
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(FOO.user.id),
+        FOO.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
     let sut = UserFeatureServiceImpl {
         auth,
         db,
         user_update,
         user_repo,
+        user_password_policy,
+        audit_log,
         ..Sut::default()
     };
 
@@ -44,6 +68,7 @@ async fn update_password() {
                 },
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
@@ -51,6 +76,55 @@ async fn update_password() {
     assert_eq!(result.unwrap(), *FOO);
 }
 
+#[tokio::test]
+async fn update_password_weak() {
+    // Arrange
+    let new_password = UserPassword::try_new("password").unwrap();
+
+    let auth = MockAuthService::new().with_authenticate(Some((FOO.user.clone(), FOO_1.clone())));
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let user_password_policy = MockUserPasswordPolicyService::new().with_check(
+        new_password.clone(),
+        user_inputs(),
+        vec![UserPasswordPolicyViolation::Breached],
+    );
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        user_password_policy,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .update_user(
+            &"token".into(),
+            UserIdOrSelf::Slf,
+            UserUpdateRequest {
+                user: UserUpdateUserRequest {
+                    password: PatchValue::Update(PasswordUpdate::Change(new_password)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ClientInfo::default(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(UserUpdateError::WeakPassword(violations))
+            if *violations == [UserPasswordPolicyViolation::Breached]
+    );
+}
+
 #[tokio::test]
 async fn remove_password_oauth() {
     // Arrange
@@ -62,10 +136,18 @@ async fn remove_password_oauth() {
         .with_get_composite(FOO.user.id, Some(FOO.clone()))
         .with_remove_password_hash(FOO.user.id, true);
 
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(FOO.user.id),
+        FOO.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
     let sut = UserFeatureServiceImpl {
         auth,
         db,
         user_repo,
+        audit_log,
         ..Sut::default()
     };
 
@@ -81,6 +163,7 @@ async fn remove_password_oauth() {
                 },
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
@@ -122,9 +205,67 @@ async fn remove_password_no_oauth() {
                 },
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
     // Assert
     assert_matches!(result, Err(UserUpdateError::CannotRemovePassword));
 }
+
+fn user_inputs() -> Vec<String> {
+    vec![
+        FOO.user.name.clone().into_inner(),
+        FOO.profile.display_name.clone().into_inner(),
+        FOO.user.email.clone().unwrap().as_str().into(),
+    ]
+}
+
+#[tokio::test]
+async fn update_password_impersonated() {
+    // Arrange
+    let auth = MockAuthService::new().with_authenticate(Some((
+        FOO.user.clone(),
+        Session {
+            impersonator_id: Some(ADMIN.user.id),
+            ..FOO_1.clone()
+        },
+    )));
+
+    let db = MockDatabase::build(false);
+
+    let user_repo = MockUserRepository::new().with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .update_user(
+            &"token".into(),
+            UserIdOrSelf::Slf,
+            UserUpdateRequest {
+                user: UserUpdateUserRequest {
+                    password: PatchValue::Update(PasswordUpdate::Change(
+                        UserPassword::try_new("the new password").unwrap(),
+                    )),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ClientInfo::default(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(
+        result,
+        Err(UserUpdateError::Auth(AuthError::Authorize(
+            AuthorizeError::Impersonation
+        )))
+    );
+}
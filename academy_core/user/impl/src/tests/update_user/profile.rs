@@ -1,10 +1,19 @@
+use std::collections::BTreeSet;
+
 use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::log::MockAuditLogService;
 use academy_core_user_contracts::{UserFeatureService, UserUpdateRequest};
 use academy_demo::{
-    session::FOO_1,
+    session::{BAR_1, FOO_1},
     user::{BAR, FOO},
 };
-use academy_models::user::{UserComposite, UserIdOrSelf};
+use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
+    role::Role,
+    user::{
+        User, UserComposite, UserIdOrSelf, UserProfile, UserProfilePatch, UserProfileVisibility,
+    },
+};
 use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
 use academy_utils::patch::Patch;
 
@@ -14,7 +23,16 @@ use crate::{tests::Sut, UserFeatureServiceImpl};
 async fn update_profile() {
     // Arrange
     let expected = UserComposite {
-        profile: BAR.profile.clone(),
+        profile: UserProfile {
+            visibility: UserProfileVisibility {
+                bio: false,
+                tags: true,
+                created_at: false,
+                last_login: true,
+            },
+            avatar: FOO.profile.avatar.clone(),
+            ..BAR.profile.clone()
+        },
         ..FOO.clone()
     };
 
@@ -26,10 +44,18 @@ async fn update_profile() {
         .with_get_composite(FOO.user.id, Some(FOO.clone()))
         .with_update_profile(FOO.user.id, expected.profile.clone().into_patch(), true);
 
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(FOO.user.id),
+        FOO.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
     let sut = UserFeatureServiceImpl {
         auth,
         db,
         user_repo,
+        audit_log,
         ..Sut::default()
     };
 
@@ -42,6 +68,7 @@ async fn update_profile() {
                 profile: expected.profile.clone().into_patch(),
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
@@ -74,9 +101,70 @@ async fn update_profile_no_changes() {
                 profile: FOO.profile.clone().into_patch(),
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
     // Assert
     assert_eq!(result.unwrap(), *FOO);
 }
+
+#[tokio::test]
+async fn update_profile_moderator() {
+    // Arrange
+    let moderator = User {
+        roles: BTreeSet::from([Role::Moderator]),
+        ..BAR.user.clone()
+    };
+
+    let expected = UserComposite {
+        profile: UserProfile {
+            bio: "".try_into().unwrap(),
+            ..FOO.profile.clone()
+        },
+        ..FOO.clone()
+    };
+
+    let auth = MockAuthService::new().with_authenticate(Some((moderator, BAR_1.clone())));
+
+    let db = MockDatabase::build(true);
+
+    let user_repo = MockUserRepository::new()
+        .with_get_composite(FOO.user.id, Some(FOO.clone()))
+        .with_update_profile(
+            FOO.user.id,
+            UserProfilePatch::new().update_bio(expected.profile.bio.clone()),
+            true,
+        );
+
+    let audit_log = MockAuditLogService::new().with_record(
+        Some(BAR.user.id),
+        FOO.user.id,
+        AuditEventKind::UserUpdated,
+        ClientInfo::default(),
+    );
+
+    let sut = UserFeatureServiceImpl {
+        auth,
+        db,
+        user_repo,
+        audit_log,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .update_user(
+            &"token".into(),
+            FOO.user.id.into(),
+            UserUpdateRequest {
+                profile: UserProfilePatch::new().update_bio(expected.profile.bio.clone()),
+                ..Default::default()
+            },
+            ClientInfo::default(),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap(), expected);
+}
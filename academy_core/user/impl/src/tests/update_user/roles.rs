@@ -1,4 +1,7 @@
+use std::collections::BTreeSet;
+
 use academy_auth_contracts::MockAuthService;
+use academy_core_audit_contracts::log::MockAuditLogService;
 use academy_core_user_contracts::{
     update::MockUserUpdateService, UserFeatureService, UserUpdateError, UserUpdateRequest,
     UserUpdateUserRequest,
@@ -8,14 +11,18 @@ use academy_demo::{
     user::{ADMIN, FOO},
     UUID1,
 };
-use academy_models::user::{User, UserComposite, UserIdOrSelf};
+use academy_models::{
+    audit::{AuditEventKind, ClientInfo},
+    role::Role,
+    user::{User, UserComposite, UserIdOrSelf},
+};
 use academy_persistence_contracts::{user::MockUserRepository, MockDatabase};
 use academy_utils::assert_matches;
 
 use crate::{tests::Sut, UserFeatureServiceImpl};
 
 #[tokio::test]
-async fn update_admin() {
+async fn update_roles() {
     let admin2 = UserComposite {
         user: User {
             id: UUID1.into(),
@@ -24,11 +31,14 @@ async fn update_admin() {
         ..ADMIN.clone()
     };
 
-    for (admin, user_composite) in [(false, &admin2), (true, &*FOO)] {
+    for (roles, user_composite) in [
+        (BTreeSet::new(), &admin2),
+        (BTreeSet::from([Role::Support, Role::Billing]), &*FOO),
+    ] {
         // Arrange
         let expected = UserComposite {
             user: User {
-                admin,
+                roles: roles.clone(),
                 ..user_composite.user.clone()
             },
             ..user_composite.clone()
@@ -42,14 +52,25 @@ async fn update_admin() {
         let user_repo = MockUserRepository::new()
             .with_get_composite(user_composite.user.id, Some(user_composite.clone()));
 
-        let user_update =
-            MockUserUpdateService::new().with_update_admin(user_composite.user.id, admin, true);
+        let user_update = MockUserUpdateService::new().with_update_roles(
+            user_composite.user.id,
+            roles.clone(),
+            true,
+        );
+
+        let audit_log = MockAuditLogService::new().with_record(
+            Some(ADMIN.user.id),
+            user_composite.user.id,
+            AuditEventKind::UserUpdated,
+            ClientInfo::default(),
+        );
 
         let sut = UserFeatureServiceImpl {
             auth,
             db,
             user_update,
             user_repo,
+            audit_log,
             ..Sut::default()
         };
 
@@ -60,11 +81,12 @@ async fn update_admin() {
                 UserIdOrSelf::UserId(user_composite.user.id),
                 UserUpdateRequest {
                     user: UserUpdateUserRequest {
-                        admin: admin.into(),
+                        roles: roles.into(),
                         ..Default::default()
                     },
                     ..Default::default()
                 },
+                ClientInfo::default(),
             )
             .await;
 
@@ -98,11 +120,12 @@ async fn demote_self() {
             UserIdOrSelf::Slf,
             UserUpdateRequest {
                 user: UserUpdateUserRequest {
-                    admin: false.into(),
+                    roles: BTreeSet::new().into(),
                     ..Default::default()
                 },
                 ..Default::default()
             },
+            ClientInfo::default(),
         )
         .await;
 
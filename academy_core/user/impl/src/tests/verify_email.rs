@@ -1,9 +1,12 @@
+use std::time::Duration;
+
 use academy_core_user_contracts::{
     email_confirmation::{MockUserEmailConfirmationService, UserEmailConfirmationVerifyEmailError},
     UserFeatureService, UserVerifyEmailError,
 };
-use academy_demo::{user::FOO, VERIFICATION_CODE_1};
+use academy_demo::{audit::FOO_AUDIT_EVENT_1, user::FOO, VERIFICATION_CODE_1};
 use academy_persistence_contracts::MockDatabase;
+use academy_shared_contracts::throttle::{MockThrottleService, ThrottleKey};
 use academy_utils::assert_matches;
 
 use crate::{tests::Sut, UserFeatureServiceImpl};
@@ -11,6 +14,14 @@ use crate::{tests::Sut, UserFeatureServiceImpl};
 #[tokio::test]
 async fn ok() {
     // Arrange
+    let throttle = MockThrottleService::new().with_check(
+        vec![ThrottleKey::ip(
+            "verify_email",
+            FOO_AUDIT_EVENT_1.client.ip_address.unwrap(),
+        )],
+        None,
+    );
+
     let db = MockDatabase::build(true);
 
     let user_email_confirmation = MockUserEmailConfirmationService::new()
@@ -18,12 +29,18 @@ async fn ok() {
 
     let sut = UserFeatureServiceImpl {
         db,
+        throttle,
         user_email_confirmation,
         ..Sut::default()
     };
 
     // Act
-    let result = sut.verify_email(VERIFICATION_CODE_1.clone()).await;
+    let result = sut
+        .verify_email(
+            VERIFICATION_CODE_1.clone(),
+            FOO_AUDIT_EVENT_1.client.clone(),
+        )
+        .await;
 
     // Assert
     result.unwrap();
@@ -32,6 +49,22 @@ async fn ok() {
 #[tokio::test]
 async fn invalid_code() {
     // Arrange
+    let throttle = MockThrottleService::new()
+        .with_check(
+            vec![ThrottleKey::ip(
+                "verify_email",
+                FOO_AUDIT_EVENT_1.client.ip_address.unwrap(),
+            )],
+            None,
+        )
+        .with_record(
+            vec![ThrottleKey::ip(
+                "verify_email",
+                FOO_AUDIT_EVENT_1.client.ip_address.unwrap(),
+            )],
+            None,
+        );
+
     let db = MockDatabase::build(false);
 
     let user_email_confirmation = MockUserEmailConfirmationService::new().with_verify_email(
@@ -41,12 +74,18 @@ async fn invalid_code() {
 
     let sut = UserFeatureServiceImpl {
         db,
+        throttle,
         user_email_confirmation,
         ..Sut::default()
     };
 
     // Act
-    let result = sut.verify_email(VERIFICATION_CODE_1.clone()).await;
+    let result = sut
+        .verify_email(
+            VERIFICATION_CODE_1.clone(),
+            FOO_AUDIT_EVENT_1.client.clone(),
+        )
+        .await;
 
     // Assert
     assert_matches!(result, Err(UserVerifyEmailError::InvalidCode));
@@ -55,6 +94,14 @@ async fn invalid_code() {
 #[tokio::test]
 async fn already_verified() {
     // Arrange
+    let throttle = MockThrottleService::new().with_check(
+        vec![ThrottleKey::ip(
+            "verify_email",
+            FOO_AUDIT_EVENT_1.client.ip_address.unwrap(),
+        )],
+        None,
+    );
+
     let db = MockDatabase::build(false);
 
     let user_email_confirmation = MockUserEmailConfirmationService::new().with_verify_email(
@@ -64,13 +111,49 @@ async fn already_verified() {
 
     let sut = UserFeatureServiceImpl {
         db,
+        throttle,
         user_email_confirmation,
         ..Sut::default()
     };
 
     // Act
-    let result = sut.verify_email(VERIFICATION_CODE_1.clone()).await;
+    let result = sut
+        .verify_email(
+            VERIFICATION_CODE_1.clone(),
+            FOO_AUDIT_EVENT_1.client.clone(),
+        )
+        .await;
 
     // Assert
     result.unwrap();
 }
+
+#[tokio::test]
+async fn rate_limit() {
+    // Arrange
+    let expected = FOO.user.created_at + Duration::from_secs(60);
+
+    let throttle = MockThrottleService::new().with_check(
+        vec![ThrottleKey::ip(
+            "verify_email",
+            FOO_AUDIT_EVENT_1.client.ip_address.unwrap(),
+        )],
+        Some(expected),
+    );
+
+    let sut = UserFeatureServiceImpl {
+        throttle,
+        ..Sut::default()
+    };
+
+    // Act
+    let result = sut
+        .verify_email(
+            VERIFICATION_CODE_1.clone(),
+            FOO_AUDIT_EVENT_1.client.clone(),
+        )
+        .await;
+
+    // Assert
+    assert_matches!(result, Err(UserVerifyEmailError::RateLimit { until }) if *until == expected);
+}
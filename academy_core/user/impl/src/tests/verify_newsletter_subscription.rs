@@ -106,7 +106,7 @@ async fn unauthorized() {
     assert_matches!(
         result,
         Err(UserVerifyNewsletterSubscriptionError::Auth(
-            AuthError::Authorize(AuthorizeError::Admin)
+            AuthError::Authorize(AuthorizeError::Permission)
         ))
     );
 }
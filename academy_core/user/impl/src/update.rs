@@ -1,18 +1,23 @@
+use std::collections::BTreeSet;
+
 use academy_auth_contracts::AuthService;
 use academy_core_session_contracts::session::SessionService;
 use academy_core_user_contracts::update::{
     UserUpdateEmailError, UserUpdateNameError, UserUpdateNameRateLimitPolicy, UserUpdateService,
 };
 use academy_di::Build;
+use academy_email_contracts::template::TemplateEmailService;
 use academy_models::{
     email_address::EmailAddress,
+    role::Role,
     user::{
-        User, UserId, UserInvoiceInfo, UserInvoiceInfoPatch, UserName, UserPassword, UserPatch,
-        UserPatchRef,
+        User, UserComposite, UserId, UserInvoiceInfo, UserInvoiceInfoPatch, UserName, UserPassword,
+        UserPatch, UserPatchRef,
     },
 };
 use academy_persistence_contracts::user::{UserRepoError, UserRepository};
 use academy_shared_contracts::{password::PasswordService, time::TimeService};
+use academy_templates_contracts::{EmailChangedTemplate, PasswordChangedTemplate};
 use academy_utils::{
     patch::{Patch, PatchValue},
     trace_instrument,
@@ -23,22 +28,24 @@ use crate::UserFeatureConfig;
 
 #[derive(Debug, Clone, Build)]
 #[cfg_attr(test, derive(Default))]
-pub struct UserUpdateServiceImpl<Auth, Time, Password, Session, UserRepo> {
+pub struct UserUpdateServiceImpl<Auth, Time, Password, TemplateEmail, Session, UserRepo> {
     auth: Auth,
     time: Time,
     password: Password,
+    template_email: TemplateEmail,
     session: Session,
     user_repo: UserRepo,
     config: UserFeatureConfig,
 }
 
-impl<Txn, Auth, Time, Password, Session, UserRepo> UserUpdateService<Txn>
-    for UserUpdateServiceImpl<Auth, Time, Password, Session, UserRepo>
+impl<Txn, Auth, Time, Password, TemplateEmail, Session, UserRepo> UserUpdateService<Txn>
+    for UserUpdateServiceImpl<Auth, Time, Password, TemplateEmail, Session, UserRepo>
 where
     Txn: Send + Sync + 'static,
     Auth: AuthService<Txn>,
     Time: TimeService,
     Password: PasswordService,
+    TemplateEmail: TemplateEmailService,
     Session: SessionService<Txn>,
     UserRepo: UserRepository<Txn>,
 {
@@ -92,6 +99,15 @@ where
         email: &Option<EmailAddress>,
         email_verified: bool,
     ) -> Result<bool, UserUpdateEmailError> {
+        let Some(user_composite) = self
+            .user_repo
+            .get_composite(txn, user_id)
+            .await
+            .context("Failed to get user from database")?
+        else {
+            return Ok(false);
+        };
+
         let result = self
             .user_repo
             .update(
@@ -118,6 +134,24 @@ where
                 .context("Failed to invalidate access tokens")?;
         }
 
+        if result && user_composite.user.email != *email {
+            let display_name = user_composite.profile.display_name.into_inner();
+            let template = EmailChangedTemplate {
+                new_email: email.as_ref().map(|email| email.as_str().into()),
+            };
+
+            // notify both the old and the new email address
+            for recipient in [user_composite.user.email, email.clone()]
+                .into_iter()
+                .flatten()
+            {
+                self.template_email
+                    .send_email_changed_email(recipient.with_name(display_name.clone()), &template)
+                    .await
+                    .context("Failed to send email changed email")?;
+            }
+        }
+
         Ok(result)
     }
 
@@ -139,6 +173,28 @@ where
             .await
             .context("Failed to save password hash in database")?;
 
+        let user_composite = self
+            .user_repo
+            .get_composite(txn, user_id)
+            .await
+            .context("Failed to get user from database")?;
+        if let Some(UserComposite {
+            user: User {
+                email: Some(email), ..
+            },
+            profile,
+            ..
+        }) = user_composite
+        {
+            self.template_email
+                .send_password_changed_email(
+                    email.with_name(profile.display_name.into_inner()),
+                    &PasswordChangedTemplate {},
+                )
+                .await
+                .context("Failed to send password changed email")?;
+        }
+
         Ok(())
     }
 
@@ -163,21 +219,21 @@ where
     }
 
     #[trace_instrument(skip(self, txn))]
-    async fn update_admin(
+    async fn update_roles(
         &self,
         txn: &mut Txn,
         user_id: UserId,
-        admin: bool,
+        roles: BTreeSet<Role>,
     ) -> anyhow::Result<bool> {
-        // access tokens contain the `admin` field, so we need to invalidate
-        // them when changing this value
+        // access tokens contain the permissions granted by the roles, so we
+        // need to invalidate them when changing this value
         self.auth
             .invalidate_access_tokens(txn, user_id)
             .await
             .context("Failed to invalidate access tokens")?;
 
         self.user_repo
-            .update(txn, user_id, UserPatchRef::new().update_admin(&admin))
+            .update(txn, user_id, UserPatchRef::new().update_roles(&roles))
             .await
             .context("Failed to update user in database")
     }
@@ -210,6 +266,7 @@ mod tests {
     use academy_auth_contracts::MockAuthService;
     use academy_core_session_contracts::session::MockSessionService;
     use academy_demo::user::{ADMIN, BAR, FOO};
+    use academy_email_contracts::template::MockTemplateEmailService;
     use academy_models::user::UserPatch;
     use academy_persistence_contracts::user::MockUserRepository;
     use academy_shared_contracts::{password::MockPasswordService, time::MockTimeService};
@@ -221,6 +278,7 @@ mod tests {
         MockAuthService<()>,
         MockTimeService,
         MockPasswordService,
+        MockTemplateEmailService,
         MockSessionService<()>,
         MockUserRepository<()>,
     >;
@@ -377,16 +435,43 @@ mod tests {
             // Arrange
             let auth = MockAuthService::new().with_invalidate_access_tokens(FOO.user.id);
 
-            let user_repo = MockUserRepository::new().with_update(
-                FOO.user.id,
-                UserPatch::new()
-                    .update_email(Some(ADMIN.user.email.clone().unwrap()))
-                    .update_email_verified(verified),
-                Ok(true),
-            );
+            let user_repo = MockUserRepository::new()
+                .with_get_composite(FOO.user.id, Some(FOO.clone()))
+                .with_update(
+                    FOO.user.id,
+                    UserPatch::new()
+                        .update_email(Some(ADMIN.user.email.clone().unwrap()))
+                        .update_email_verified(verified),
+                    Ok(true),
+                );
+
+            let template = EmailChangedTemplate {
+                new_email: Some(ADMIN.user.email.clone().unwrap().as_str().into()),
+            };
+            let template_email = MockTemplateEmailService::new()
+                .with_send_email_changed_email(
+                    FOO.user
+                        .email
+                        .clone()
+                        .unwrap()
+                        .with_name(FOO.profile.display_name.clone().into_inner()),
+                    template.clone(),
+                    true,
+                )
+                .with_send_email_changed_email(
+                    ADMIN
+                        .user
+                        .email
+                        .clone()
+                        .unwrap()
+                        .with_name(FOO.profile.display_name.clone().into_inner()),
+                    template,
+                    true,
+                );
 
             let sut = UserUpdateServiceImpl {
                 auth,
+                template_email,
                 user_repo,
                 ..Sut::default()
             };
@@ -406,18 +491,50 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn update_email_unchanged() {
+        // Arrange
+        let auth = MockAuthService::new().with_invalidate_access_tokens(FOO.user.id);
+
+        let user_repo = MockUserRepository::new()
+            .with_get_composite(FOO.user.id, Some(FOO.clone()))
+            .with_update(
+                FOO.user.id,
+                UserPatch::new()
+                    .update_email(FOO.user.email.clone())
+                    .update_email_verified(false),
+                Ok(true),
+            );
+
+        let sut = UserUpdateServiceImpl {
+            auth,
+            user_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut
+            .update_email(&mut (), FOO.user.id, &FOO.user.email, false)
+            .await;
+
+        // Assert
+        assert!(result.unwrap());
+    }
+
     #[tokio::test]
     async fn update_email_conflict() {
         // Arrange
         let auth = MockAuthService::new();
 
-        let user_repo = MockUserRepository::new().with_update(
-            FOO.user.id,
-            UserPatch::new()
-                .update_email(Some(ADMIN.user.email.clone().unwrap()))
-                .update_email_verified(false),
-            Err(UserRepoError::EmailConflict),
-        );
+        let user_repo = MockUserRepository::new()
+            .with_get_composite(FOO.user.id, Some(FOO.clone()))
+            .with_update(
+                FOO.user.id,
+                UserPatch::new()
+                    .update_email(Some(ADMIN.user.email.clone().unwrap()))
+                    .update_email_verified(false),
+                Err(UserRepoError::EmailConflict),
+            );
 
         let sut = UserUpdateServiceImpl {
             auth,
@@ -445,11 +562,23 @@ mod tests {
         let password =
             MockPasswordService::new().with_hash("new password".into(), "the hash".into());
 
-        let user_repo =
-            MockUserRepository::new().with_save_password_hash(FOO.user.id, "the hash".into());
+        let user_repo = MockUserRepository::new()
+            .with_save_password_hash(FOO.user.id, "the hash".into())
+            .with_get_composite(FOO.user.id, Some(FOO.clone()));
+
+        let template_email = MockTemplateEmailService::new().with_send_password_changed_email(
+            FOO.user
+                .email
+                .clone()
+                .unwrap()
+                .with_name(FOO.profile.display_name.clone().into_inner()),
+            PasswordChangedTemplate {},
+            true,
+        );
 
         let sut = UserUpdateServiceImpl {
             password,
+            template_email,
             user_repo,
             ..Sut::default()
         };
@@ -509,13 +638,15 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn update_admin_promote() {
+    async fn update_roles_promote() {
         // Arrange
+        let roles = BTreeSet::from([Role::Support, Role::Billing]);
+
         let auth = MockAuthService::new().with_invalidate_access_tokens(FOO.user.id);
 
         let user_repo = MockUserRepository::new().with_update(
             FOO.user.id,
-            UserPatch::new().update_admin(true),
+            UserPatch::new().update_roles(roles.clone()),
             Ok(true),
         );
 
@@ -526,20 +657,20 @@ mod tests {
         };
 
         // Act
-        let result = sut.update_admin(&mut (), FOO.user.id, true).await;
+        let result = sut.update_roles(&mut (), FOO.user.id, roles).await;
 
         // Assert
         assert!(result.unwrap());
     }
 
     #[tokio::test]
-    async fn update_admin_demote() {
+    async fn update_roles_demote() {
         // Arrange
-        let auth = MockAuthService::new().with_invalidate_access_tokens(FOO.user.id);
+        let auth = MockAuthService::new().with_invalidate_access_tokens(ADMIN.user.id);
 
         let user_repo = MockUserRepository::new().with_update(
-            FOO.user.id,
-            UserPatch::new().update_admin(false),
+            ADMIN.user.id,
+            UserPatch::new().update_roles(BTreeSet::new()),
             Ok(true),
         );
 
@@ -550,7 +681,9 @@ mod tests {
         };
 
         // Act
-        let result = sut.update_admin(&mut (), FOO.user.id, false).await;
+        let result = sut
+            .update_roles(&mut (), ADMIN.user.id, BTreeSet::new())
+            .await;
 
         // Assert
         assert!(result.unwrap());
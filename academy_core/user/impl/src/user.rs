@@ -29,23 +29,33 @@ where
     OAuth2Link: OAuth2LinkService<Txn>,
 {
     #[trace_instrument(skip(self, txn))]
-    async fn list(&self, txn: &mut Txn, query: UserListQuery) -> anyhow::Result<UserListResult> {
+    async fn list(
+        &self,
+        txn: &mut Txn,
+        query: UserListQuery,
+    ) -> anyhow::Result<Option<UserListResult>> {
         let total = self
             .user_repo
             .count(txn, &query.filter)
             .await
             .context("Failed to get total number of users from database")?;
 
-        let user_composites = self
+        let Some(user_composites) = self
             .user_repo
-            .list_composites(txn, &query.filter, query.pagination)
+            .list_composites(txn, &query.filter, query.sort, query.pagination)
             .await
-            .context("Failed to get users from database")?;
+            .context("Failed to get users from database")?
+        else {
+            return Ok(None);
+        };
 
-        Ok(UserListResult {
+        let next_cursor = query.pagination.next_cursor(&user_composites);
+
+        Ok(Some(UserListResult {
             total,
             user_composites,
-        })
+            next_cursor,
+        }))
     }
 
     #[trace_instrument(skip(self, txn))]
@@ -57,7 +67,7 @@ where
             display_name,
             email,
             password,
-            admin,
+            roles,
             enabled,
             email_verified,
             oauth2_registration,
@@ -82,14 +92,17 @@ where
             last_login: None,
             last_name_change: None,
             enabled,
-            admin,
+            roles,
             newsletter: false,
+            deleted_at: None,
         };
 
         let profile = UserProfile {
             display_name,
             bio: Default::default(),
             tags: Default::default(),
+            visibility: Default::default(),
+            avatar: None,
         };
 
         let details = UserDetails {
@@ -125,6 +138,7 @@ where
                     user.id,
                     oauth2_registration.provider_id,
                     oauth2_registration.remote_user,
+                    oauth2_registration.tokens,
                 )
                 .await
                 .map_err(|err| match err {
@@ -152,13 +166,14 @@ where
 mod tests {
     use academy_core_oauth2_contracts::link::MockOAuth2LinkService;
     use academy_demo::{
-        oauth2::{FOO_OAUTH2_LINK_1, TEST_OAUTH2_PROVIDER_ID},
+        oauth2::{FOO_OAUTH2_LINK_1, FOO_OAUTH2_TOKENS, TEST_OAUTH2_PROVIDER_ID},
         user::{ALL_USERS, FOO},
     };
     use academy_models::{
         oauth2::OAuth2Registration,
-        pagination::PaginationSlice,
-        user::{UserFilter, UserPassword},
+        pagination::{Pagination, PaginationCursor, SortDirection},
+        role::Role,
+        user::{UserFilter, UserPassword, UserSort, UserSortBy},
     };
     use academy_persistence_contracts::user::MockUserRepository;
     use academy_shared_contracts::{
@@ -180,25 +195,40 @@ mod tests {
     async fn list() {
         // Arrange
         let query = UserListQuery {
-            pagination: PaginationSlice {
-                limit: 42.try_into().unwrap(),
-                offset: 7,
+            pagination: Pagination::Cursor {
+                limit: 4.try_into().unwrap(),
+                after: Some(PaginationCursor {
+                    created_at: FOO.user.created_at,
+                    id: *FOO.user.id,
+                }),
             },
             filter: UserFilter {
                 name: Some("the name".try_into().unwrap()),
                 email: Some("the email".try_into().unwrap()),
                 enabled: Some(true),
-                admin: Some(false),
+                role: Some(Role::Support),
                 mfa_enabled: None,
                 email_verified: Some(true),
                 newsletter: Some(false),
+                oauth2_provider: Some(TEST_OAUTH2_PROVIDER_ID.clone()),
+                last_login_after: Some(FOO.user.created_at),
+                ..Default::default()
+            },
+            sort: UserSort {
+                by: UserSortBy::LastLogin,
+                direction: SortDirection::Desc,
             },
         };
         let expected = ALL_USERS.iter().copied().cloned().collect::<Vec<_>>();
 
         let user_repo = MockUserRepository::new()
             .with_count(query.filter.clone(), 17)
-            .with_list_composites(query.filter.clone(), query.pagination, expected.clone());
+            .with_list_composites(
+                query.filter.clone(),
+                query.sort,
+                query.pagination,
+                Some(expected.clone()),
+            );
 
         let sut = UserServiceImpl {
             user_repo,
@@ -209,8 +239,31 @@ mod tests {
         let result = sut.list(&mut (), query).await;
 
         // Assert
-        let result = result.unwrap();
+        let result = result.unwrap().unwrap();
+        assert_eq!(result.total, 17);
         assert_eq!(result.user_composites, expected);
+        assert_eq!(result.next_cursor, Some(ALL_USERS[3].into()));
+    }
+
+    #[tokio::test]
+    async fn list_invalid_cursor() {
+        // Arrange
+        let query = UserListQuery::default();
+
+        let user_repo = MockUserRepository::new()
+            .with_count(query.filter.clone(), 17)
+            .with_list_composites(query.filter.clone(), query.sort, query.pagination, None);
+
+        let sut = UserServiceImpl {
+            user_repo,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.list(&mut (), query).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), None);
     }
 
     #[tokio::test]
@@ -249,7 +302,7 @@ mod tests {
             display_name: FOO.profile.display_name.clone(),
             email: FOO.user.email.clone().unwrap(),
             password: Some(user_password),
-            admin: false,
+            roles: Default::default(),
             enabled: true,
             email_verified: false,
             oauth2_registration: None,
@@ -280,6 +333,7 @@ mod tests {
             FOO.user.id,
             TEST_OAUTH2_PROVIDER_ID.clone(),
             FOO_OAUTH2_LINK_1.remote_user.clone(),
+            FOO_OAUTH2_TOKENS.clone(),
             Ok(FOO_OAUTH2_LINK_1.clone()),
         );
 
@@ -296,12 +350,14 @@ mod tests {
             display_name: FOO.profile.display_name.clone(),
             email: FOO.user.email.clone().unwrap(),
             password: None,
-            admin: false,
+            roles: Default::default(),
             enabled: true,
             email_verified: false,
             oauth2_registration: Some(OAuth2Registration {
                 provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
                 remote_user: FOO_OAUTH2_LINK_1.remote_user.clone(),
+                profile: Default::default(),
+                tokens: FOO_OAUTH2_TOKENS.clone(),
             }),
         };
 
@@ -346,7 +402,7 @@ mod tests {
             display_name: FOO.profile.display_name.clone(),
             email: FOO.user.email.clone().unwrap(),
             password: Some(user_password),
-            admin: false,
+            roles: Default::default(),
             enabled: true,
             email_verified: false,
             oauth2_registration: None,
@@ -393,7 +449,7 @@ mod tests {
             display_name: FOO.profile.display_name.clone(),
             email: FOO.user.email.clone().unwrap(),
             password: Some(user_password),
-            admin: false,
+            roles: Default::default(),
             enabled: true,
             email_verified: false,
             oauth2_registration: None,
@@ -424,6 +480,7 @@ mod tests {
             FOO.user.id,
             TEST_OAUTH2_PROVIDER_ID.clone(),
             FOO_OAUTH2_LINK_1.remote_user.clone(),
+            FOO_OAUTH2_TOKENS.clone(),
             Err(OAuth2LinkServiceError::RemoteAlreadyLinked),
         );
 
@@ -440,12 +497,14 @@ mod tests {
             display_name: FOO.profile.display_name.clone(),
             email: FOO.user.email.clone().unwrap(),
             password: None,
-            admin: false,
+            roles: Default::default(),
             enabled: true,
             email_verified: false,
             oauth2_registration: Some(OAuth2Registration {
                 provider_id: TEST_OAUTH2_PROVIDER_ID.clone(),
                 remote_user: FOO_OAUTH2_LINK_1.remote_user.clone(),
+                profile: Default::default(),
+                tokens: FOO_OAUTH2_TOKENS.clone(),
             }),
         };
 
@@ -467,13 +526,16 @@ mod tests {
                 last_login: None,
                 last_name_change: None,
                 enabled: true,
-                admin: false,
+                roles: Default::default(),
                 newsletter: false,
+                deleted_at: None,
             },
             profile: UserProfile {
                 display_name: FOO.profile.display_name.clone(),
                 bio: Default::default(),
                 tags: Default::default(),
+                visibility: Default::default(),
+                avatar: None,
             },
             details: UserDetails {
                 mfa_enabled: false,
@@ -0,0 +1,44 @@
+use std::{sync::LazyLock, time::Duration};
+
+use academy_models::audit::{AuditEvent, AuditEventKind, ClientInfo};
+use academy_persistence_contracts::audit::AuditRepository;
+use uuid::uuid;
+
+use crate::user::{ADMIN, FOO};
+
+pub static ALL_AUDIT_EVENTS: LazyLock<Vec<&AuditEvent>> =
+    LazyLock::new(|| vec![&FOO_AUDIT_EVENT_1, &FOO_AUDIT_EVENT_2]);
+
+pub static FOO_AUDIT_EVENT_1: LazyLock<AuditEvent> = LazyLock::new(|| AuditEvent {
+    id: uuid!("8b3ac2f1-1f0b-4c55-9a0b-49b8f0b5d9e3").into(),
+    actor_id: Some(FOO.user.id),
+    user_id: FOO.user.id,
+    kind: AuditEventKind::UserUpdated,
+    client: ClientInfo {
+        ip_address: Some([192, 0, 2, 42].into()),
+        user_agent: Some("Mozilla/5.0 (X11; Linux x86_64; rv:132.0) Firefox/132.0".into()),
+    },
+    created_at: FOO.user.created_at + Duration::from_secs(3600),
+});
+
+pub static FOO_AUDIT_EVENT_2: LazyLock<AuditEvent> = LazyLock::new(|| AuditEvent {
+    id: uuid!("0d4c7b1e-52a4-4f0e-8d2e-6f6a3c9b7a10").into(),
+    actor_id: Some(ADMIN.user.id),
+    user_id: FOO.user.id,
+    kind: AuditEventKind::UserImpersonated,
+    client: ClientInfo {
+        ip_address: Some("2001:db8::1".parse().unwrap()),
+        user_agent: None,
+    },
+    created_at: FOO.user.created_at + Duration::from_secs(7200),
+});
+
+pub async fn create<Txn: Send + Sync + 'static>(
+    txn: &mut Txn,
+    repo: impl AuditRepository<Txn>,
+) -> anyhow::Result<()> {
+    for &event in &*ALL_AUDIT_EVENTS {
+        repo.create(txn, event).await?;
+    }
+    Ok(())
+}
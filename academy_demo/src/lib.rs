@@ -2,11 +2,13 @@ use std::sync::LazyLock;
 
 use academy_models::{Sha256Hash, VerificationCode};
 use academy_persistence_contracts::{
-    mfa::MfaRepository, oauth2::OAuth2Repository, session::SessionRepository, user::UserRepository,
+    audit::AuditRepository, mfa::MfaRepository, oauth2::OAuth2Repository,
+    session::SessionRepository, user::UserRepository,
 };
 use anyhow::Context;
 use uuid::{uuid, Uuid};
 
+pub mod audit;
 pub mod mfa;
 pub mod oauth2;
 pub mod session;
@@ -38,6 +40,7 @@ pub async fn create<Txn: Send + Sync + 'static>(
     session: impl SessionRepository<Txn>,
     mfa: impl MfaRepository<Txn>,
     oauth2: impl OAuth2Repository<Txn>,
+    audit: impl AuditRepository<Txn>,
 ) -> anyhow::Result<()> {
     macro_rules! create {
         ($($ident:ident),* $(,)?) => { $(
@@ -47,7 +50,7 @@ pub async fn create<Txn: Send + Sync + 'static>(
         )*};
     }
 
-    create!(user, session, mfa, oauth2);
+    create!(user, session, mfa, oauth2, audit);
 
     Ok(())
 }
@@ -1,6 +1,9 @@
 use std::{collections::HashMap, sync::LazyLock, time::Duration};
 
-use academy_models::mfa::{TotpDevice, TotpDeviceId, TotpSecret};
+use academy_models::mfa::{
+    TotpDevice, TotpDeviceId, TotpSecret, WebauthnCredential, WebauthnCredentialId,
+    WebauthnCredentialKey,
+};
 use academy_persistence_contracts::mfa::MfaRepository;
 use uuid::uuid;
 
@@ -12,15 +15,19 @@ pub static ALL_TOTP_DEVICES: LazyLock<Vec<&TotpDevice>> =
 pub static ADMIN2_TOTP_1: LazyLock<TotpDevice> = LazyLock::new(|| TotpDevice {
     id: uuid!("75a9def2-688f-4211-9fc7-750eb89600cd").into(),
     user_id: ADMIN2.user.id,
+    name: "Phone".try_into().unwrap(),
     enabled: true,
     created_at: ADMIN2.user.created_at + Duration::from_secs(600),
+    last_used_at: Some(ADMIN2.user.created_at + Duration::from_secs(2 * 24 * 3600)),
 });
 
 pub static FOO_TOTP_1: LazyLock<TotpDevice> = LazyLock::new(|| TotpDevice {
     id: uuid!("24532ed6-9126-4b8a-b0b3-c6979ff0549e").into(),
     user_id: FOO.user.id,
+    name: "Authenticator".try_into().unwrap(),
     enabled: false,
     created_at: FOO.user.created_at + Duration::from_secs(2 * 24 * 3600),
+    last_used_at: None,
 });
 
 pub static TOTP_SECRETS: LazyLock<HashMap<TotpDeviceId, TotpSecret>> = LazyLock::new(|| {
@@ -32,6 +39,43 @@ pub static TOTP_SECRETS: LazyLock<HashMap<TotpDeviceId, TotpSecret>> = LazyLock:
     .into()
 });
 
+pub static ALL_WEBAUTHN_CREDENTIALS: LazyLock<Vec<&WebauthnCredential>> =
+    LazyLock::new(|| vec![&ADMIN2_WEBAUTHN_1]);
+
+pub static ADMIN2_WEBAUTHN_1: LazyLock<WebauthnCredential> = LazyLock::new(|| WebauthnCredential {
+    id: uuid!("c5a5c9a3-5b8f-4a0e-8f7c-2f0e3d7a3b61").into(),
+    user_id: ADMIN2.user.id,
+    raw_id: hex::decode("8a1c0f3e5d7b9a2c4e6f8091a3b5c7d9")
+        .unwrap()
+        .try_into()
+        .unwrap(),
+    name: "YubiKey".try_into().unwrap(),
+    created_at: ADMIN2.user.created_at + Duration::from_secs(1200),
+    last_used_at: Some(ADMIN2.user.created_at + Duration::from_secs(3 * 24 * 3600)),
+});
+
+pub static WEBAUTHN_CREDENTIAL_KEYS: LazyLock<
+    HashMap<WebauthnCredentialId, WebauthnCredentialKey>,
+> = LazyLock::new(|| {
+    [(
+        ADMIN2_WEBAUTHN_1.id,
+        // ES256 public key (COSE encoded)
+        "a50102032620012158205667ae39bb75bb17d1b6556cfe6c0c9fe78638d310facfcf9f0170503e296a4\
+             2225820113b3e3f8ec229ef3b9b43837b4f1d88e5c4800b1b38754f4538e8a1bec9101d",
+        17,
+    )]
+    .map(|(id, public_key, sign_count)| {
+        (
+            id,
+            WebauthnCredentialKey {
+                public_key: hex::decode(public_key).unwrap().into(),
+                sign_count,
+            },
+        )
+    })
+    .into()
+});
+
 fn decode_secret(secret: &str) -> TotpSecret {
     base32::decode(base32::Alphabet::Rfc4648 { padding: false }, secret)
         .unwrap()
@@ -47,5 +91,9 @@ pub async fn create<Txn: Send + Sync + 'static>(
         repo.create_totp_device(txn, totp_device, &TOTP_SECRETS[&totp_device.id])
             .await?;
     }
+    for &credential in &*ALL_WEBAUTHN_CREDENTIALS {
+        repo.create_webauthn_credential(txn, credential, &WEBAUTHN_CREDENTIAL_KEYS[&credential.id])
+            .await?;
+    }
     Ok(())
 }
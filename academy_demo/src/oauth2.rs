@@ -1,6 +1,9 @@
 use std::{sync::LazyLock, time::Duration};
 
-use academy_models::oauth2::{OAuth2Link, OAuth2Provider, OAuth2ProviderId, OAuth2UserInfo};
+use academy_models::oauth2::{
+    OAuth2Endpoints, OAuth2Link, OAuth2Provider, OAuth2ProviderEndpoints, OAuth2ProviderId,
+    OAuth2RemoteProfile, OAuth2Tokens, OAuth2UserInfo,
+};
 use academy_persistence_contracts::oauth2::OAuth2Repository;
 use uuid::uuid;
 
@@ -13,11 +16,18 @@ pub static TEST_OAUTH2_PROVIDER: LazyLock<OAuth2Provider> = LazyLock::new(|| OAu
     name: "Test Provider".into(),
     client_id: "test-id".into(),
     client_secret: Some("test-secret".into()),
-    auth_url: "http://test/auth".parse().unwrap(),
-    token_url: "http://test/token".parse().unwrap(),
-    userinfo_url: "http://test/user".parse().unwrap(),
+    endpoints: OAuth2ProviderEndpoints::OAuth2(Box::new(OAuth2Endpoints {
+        auth_url: "http://test/auth".parse().unwrap(),
+        token_url: "http://test/token".parse().unwrap(),
+        userinfo_url: "http://test/user".parse().unwrap(),
+        revocation_url: Some("http://test/revoke".parse().unwrap()),
+    })),
     userinfo_id_key: "id".into(),
     userinfo_name_key: "name".into(),
+    userinfo_email_key: Some("email".into()),
+    userinfo_email_verified_key: Some("email_verified".into()),
+    userinfo_display_name_key: Some("display_name".into()),
+    userinfo_avatar_url_key: Some("avatar_url".into()),
     scopes: ["foo", "bar", "baz"].map(Into::into).into(),
 });
 
@@ -35,6 +45,20 @@ pub static FOO_OAUTH2_LINK_1: LazyLock<OAuth2Link> = LazyLock::new(|| OAuth2Link
     },
 });
 
+pub static FOO_OAUTH2_PROFILE: LazyLock<OAuth2RemoteProfile> =
+    LazyLock::new(|| OAuth2RemoteProfile {
+        email: FOO.user.email.clone(),
+        email_verified: true,
+        display_name: Some(FOO.profile.display_name.clone()),
+        avatar_url: Some("http://test/avatars/28374.png".parse().unwrap()),
+    });
+
+pub static FOO_OAUTH2_TOKENS: LazyLock<OAuth2Tokens> = LazyLock::new(|| OAuth2Tokens {
+    access_token: "YSk3hTLbZ7BzqGM6xUTGYGbhFz2v8QNu".into(),
+    refresh_token: Some("LvHMpdCz4y3k9NqX5aE0sJrWfTtB2uGc".into()),
+    expires_at: Some(FOO_OAUTH2_LINK_1.created_at + Duration::from_secs(3600)),
+});
+
 pub async fn create<Txn: Send + Sync + 'static>(
     txn: &mut Txn,
     repo: impl OAuth2Repository<Txn>,
@@ -12,6 +12,7 @@ pub static ADMIN_1: LazyLock<Session> = LazyLock::new(|| Session {
     id: uuid!("1943a975-8895-428d-9fb1-f8d450f29dae").into(),
     user_id: ADMIN.user.id,
     device_name: Some("laptop".try_into().unwrap()),
+    impersonator_id: None,
     created_at: ADMIN.user.created_at,
     updated_at: ADMIN.user.created_at + Duration::from_secs(1337),
 });
@@ -20,6 +21,7 @@ pub static FOO_1: LazyLock<Session> = LazyLock::new(|| Session {
     id: uuid!("b2b772de-4fc6-4651-9684-c71e70b9197b").into(),
     user_id: FOO.user.id,
     device_name: Some("desktop".try_into().unwrap()),
+    impersonator_id: None,
     created_at: FOO.user.created_at + Duration::from_secs(42),
     updated_at: FOO.user.created_at + Duration::from_secs(1337),
 });
@@ -28,6 +30,7 @@ pub static FOO_2: LazyLock<Session> = LazyLock::new(|| Session {
     id: uuid!("eb0fe09a-552e-40c1-a912-e77ec9ca8b36").into(),
     user_id: FOO.user.id,
     device_name: None,
+    impersonator_id: None,
     created_at: FOO.user.created_at,
     updated_at: FOO.user.created_at + Duration::from_secs(17),
 });
@@ -36,6 +39,7 @@ pub static BAR_1: LazyLock<Session> = LazyLock::new(|| Session {
     id: uuid!("2dbe3650-aad6-412a-9207-68a444697909").into(),
     user_id: BAR.user.id,
     device_name: None,
+    impersonator_id: None,
     created_at: BAR.user.created_at,
     updated_at: BAR.user.created_at + Duration::from_secs(23),
 });
@@ -1,7 +1,10 @@
-use std::sync::LazyLock;
+use std::{collections::BTreeSet, sync::LazyLock};
 
-use academy_models::user::{
-    User, UserComposite, UserDetails, UserInvoiceInfo, UserPassword, UserProfile,
+use academy_models::{
+    role::Role,
+    user::{
+        User, UserAvatar, UserComposite, UserDetails, UserInvoiceInfo, UserPassword, UserProfile,
+    },
 };
 use academy_persistence_contracts::user::UserRepository;
 use argon2::{
@@ -24,13 +27,16 @@ pub static ADMIN: LazyLock<UserComposite> = LazyLock::new(|| UserComposite {
         last_login: Some(Utc.with_ymd_and_hms(2024, 4, 7, 10, 23, 0).unwrap()),
         last_name_change: None,
         enabled: true,
-        admin: true,
+        roles: BTreeSet::from([Role::Superuser]),
         newsletter: false,
+        deleted_at: None,
     },
     profile: UserProfile {
         display_name: "Administrator".try_into().unwrap(),
         bio: Default::default(),
         tags: Default::default(),
+        visibility: Default::default(),
+        avatar: None,
     },
     details: UserDetails {
         mfa_enabled: false,
@@ -53,13 +59,16 @@ pub static ADMIN2: LazyLock<UserComposite> = LazyLock::new(|| UserComposite {
         last_login: Some(Utc.with_ymd_and_hms(2024, 4, 7, 10, 23, 0).unwrap()),
         last_name_change: None,
         enabled: true,
-        admin: true,
+        roles: BTreeSet::from([Role::Superuser]),
         newsletter: true,
+        deleted_at: None,
     },
     profile: UserProfile {
         display_name: "Administrator2".try_into().unwrap(),
         bio: Default::default(),
         tags: Default::default(),
+        visibility: Default::default(),
+        avatar: None,
     },
     details: UserDetails {
         mfa_enabled: true,
@@ -82,8 +91,9 @@ pub static FOO: LazyLock<UserComposite> = LazyLock::new(|| UserComposite {
         last_login: Some(Utc.with_ymd_and_hms(2024, 3, 15, 13, 37, 0).unwrap()),
         last_name_change: Some(Utc.with_ymd_and_hms(2024, 3, 14, 13, 50, 0).unwrap()),
         enabled: true,
-        admin: false,
+        roles: BTreeSet::new(),
         newsletter: true,
+        deleted_at: None,
     },
     profile: UserProfile {
         display_name: "Foo 42".try_into().unwrap(),
@@ -95,6 +105,8 @@ pub static FOO: LazyLock<UserComposite> = LazyLock::new(|| UserComposite {
             .unwrap()
             .try_into()
             .unwrap(),
+        visibility: Default::default(),
+        avatar: Some(FOO_AVATAR.clone()),
     },
     details: UserDetails {
         mfa_enabled: false,
@@ -116,6 +128,13 @@ pub static FOO: LazyLock<UserComposite> = LazyLock::new(|| UserComposite {
 pub static FOO_PASSWORD: LazyLock<UserPassword> =
     LazyLock::new(|| "foo password".try_into().unwrap());
 
+pub static FOO_AVATAR: LazyLock<UserAvatar> = LazyLock::new(|| UserAvatar {
+    id: uuid!("5f0d6e53-2b8e-4c1a-9d27-8a4c3e6b1f90").into(),
+    url: "https://example.com/storage/avatars/5f0d6e53-2b8e-4c1a-9d27-8a4c3e6b1f90/"
+        .parse()
+        .unwrap(),
+});
+
 pub static BAR: LazyLock<UserComposite> = LazyLock::new(|| UserComposite {
     user: User {
         id: uuid!("94d0e3ca-bf16-486b-a172-b87f4bcbd039").into(),
@@ -126,8 +145,9 @@ pub static BAR: LazyLock<UserComposite> = LazyLock::new(|| UserComposite {
         last_login: None,
         last_name_change: None,
         enabled: false,
-        admin: false,
+        roles: BTreeSet::new(),
         newsletter: false,
+        deleted_at: None,
     },
     profile: UserProfile {
         display_name: "Bar".try_into().unwrap(),
@@ -139,6 +159,8 @@ pub static BAR: LazyLock<UserComposite> = LazyLock::new(|| UserComposite {
             .unwrap()
             .try_into()
             .unwrap(),
+        visibility: Default::default(),
+        avatar: None,
     },
     details: UserDetails {
         mfa_enabled: false,
@@ -2,7 +2,10 @@ use std::future::Future;
 
 use academy_models::email_address::EmailAddressWithName;
 use academy_templates_contracts::{
-    ResetPasswordTemplate, SubscribeNewsletterTemplate, VerifyEmailTemplate,
+    EmailChangedTemplate, LoginLinkTemplate, MfaDisabledTemplate, NewSessionTemplate,
+    OAuth2LinkCreatedTemplate, PasswordChangedTemplate, ResetPasswordTemplate,
+    SessionRevokedTemplate, SubscribeNewsletterTemplate, UserDeletedTemplate, UserExportTemplate,
+    VerifyEmailTemplate,
 };
 
 #[cfg_attr(feature = "mock", mockall::automock)]
@@ -24,6 +27,60 @@ pub trait TemplateEmailService: Send + Sync + 'static {
         recipient: EmailAddressWithName,
         data: &VerifyEmailTemplate,
     ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    fn send_login_link_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &LoginLinkTemplate,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    fn send_new_session_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &NewSessionTemplate,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    fn send_password_changed_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &PasswordChangedTemplate,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    fn send_mfa_disabled_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &MfaDisabledTemplate,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    fn send_email_changed_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &EmailChangedTemplate,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    fn send_oauth2_link_created_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &OAuth2LinkCreatedTemplate,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    fn send_user_export_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &UserExportTemplate,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    fn send_user_deleted_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &UserDeletedTemplate,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    fn send_session_revoked_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &SessionRevokedTemplate,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
 }
 
 #[cfg(feature = "mock")]
@@ -75,4 +132,148 @@ impl MockTemplateEmailService {
             .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
         self
     }
+
+    pub fn with_send_login_link_email(
+        mut self,
+        recipient: EmailAddressWithName,
+        data: LoginLinkTemplate,
+        result: bool,
+    ) -> Self {
+        self.expect_send_login_link_email()
+            .once()
+            .with(
+                mockall::predicate::eq(recipient),
+                mockall::predicate::eq(data),
+            )
+            .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_send_new_session_email(
+        mut self,
+        recipient: EmailAddressWithName,
+        data: NewSessionTemplate,
+        result: bool,
+    ) -> Self {
+        self.expect_send_new_session_email()
+            .once()
+            .with(
+                mockall::predicate::eq(recipient),
+                mockall::predicate::eq(data),
+            )
+            .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_send_password_changed_email(
+        mut self,
+        recipient: EmailAddressWithName,
+        data: PasswordChangedTemplate,
+        result: bool,
+    ) -> Self {
+        self.expect_send_password_changed_email()
+            .once()
+            .with(
+                mockall::predicate::eq(recipient),
+                mockall::predicate::eq(data),
+            )
+            .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_send_mfa_disabled_email(
+        mut self,
+        recipient: EmailAddressWithName,
+        data: MfaDisabledTemplate,
+        result: bool,
+    ) -> Self {
+        self.expect_send_mfa_disabled_email()
+            .once()
+            .with(
+                mockall::predicate::eq(recipient),
+                mockall::predicate::eq(data),
+            )
+            .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_send_email_changed_email(
+        mut self,
+        recipient: EmailAddressWithName,
+        data: EmailChangedTemplate,
+        result: bool,
+    ) -> Self {
+        self.expect_send_email_changed_email()
+            .once()
+            .with(
+                mockall::predicate::eq(recipient),
+                mockall::predicate::eq(data),
+            )
+            .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_send_oauth2_link_created_email(
+        mut self,
+        recipient: EmailAddressWithName,
+        data: OAuth2LinkCreatedTemplate,
+        result: bool,
+    ) -> Self {
+        self.expect_send_oauth2_link_created_email()
+            .once()
+            .with(
+                mockall::predicate::eq(recipient),
+                mockall::predicate::eq(data),
+            )
+            .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_send_user_export_email(
+        mut self,
+        recipient: EmailAddressWithName,
+        data: UserExportTemplate,
+        result: bool,
+    ) -> Self {
+        self.expect_send_user_export_email()
+            .once()
+            .with(
+                mockall::predicate::eq(recipient),
+                mockall::predicate::eq(data),
+            )
+            .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_send_user_deleted_email(
+        mut self,
+        recipient: EmailAddressWithName,
+        data: UserDeletedTemplate,
+        result: bool,
+    ) -> Self {
+        self.expect_send_user_deleted_email()
+            .once()
+            .with(
+                mockall::predicate::eq(recipient),
+                mockall::predicate::eq(data),
+            )
+            .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_send_session_revoked_email(
+        mut self,
+        recipient: EmailAddressWithName,
+        data: SessionRevokedTemplate,
+        result: bool,
+    ) -> Self {
+        self.expect_send_session_revoked_email()
+            .once()
+            .with(
+                mockall::predicate::eq(recipient),
+                mockall::predicate::eq(data),
+            )
+            .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
 }
@@ -2,8 +2,10 @@ use academy_di::Build;
 use academy_email_contracts::{template::TemplateEmailService, ContentType, Email, EmailService};
 use academy_models::email_address::EmailAddressWithName;
 use academy_templates_contracts::{
-    ResetPasswordTemplate, SubscribeNewsletterTemplate, Template, TemplateService,
-    VerifyEmailTemplate,
+    EmailChangedTemplate, LoginLinkTemplate, MfaDisabledTemplate, NewSessionTemplate,
+    OAuth2LinkCreatedTemplate, PasswordChangedTemplate, ResetPasswordTemplate,
+    SessionRevokedTemplate, SubscribeNewsletterTemplate, Template, TemplateService,
+    UserDeletedTemplate, UserExportTemplate, VerifyEmailTemplate,
 };
 use academy_utils::trace_instrument;
 
@@ -47,6 +49,104 @@ where
         self.send_email(recipient, data, "Willkommen bei der Bootstrap Academy!")
             .await
     }
+
+    #[trace_instrument(skip(self))]
+    async fn send_login_link_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &LoginLinkTemplate,
+    ) -> anyhow::Result<bool> {
+        self.send_email(recipient, data, "Anmelden - Bootstrap Academy")
+            .await
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn send_new_session_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &NewSessionTemplate,
+    ) -> anyhow::Result<bool> {
+        self.send_email(recipient, data, "Neue Anmeldung - Bootstrap Academy")
+            .await
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn send_password_changed_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &PasswordChangedTemplate,
+    ) -> anyhow::Result<bool> {
+        self.send_email(recipient, data, "Passwort geändert - Bootstrap Academy")
+            .await
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn send_mfa_disabled_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &MfaDisabledTemplate,
+    ) -> anyhow::Result<bool> {
+        self.send_email(
+            recipient,
+            data,
+            "Zwei-Faktor-Authentifizierung deaktiviert - Bootstrap Academy",
+        )
+        .await
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn send_email_changed_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &EmailChangedTemplate,
+    ) -> anyhow::Result<bool> {
+        self.send_email(
+            recipient,
+            data,
+            "E-Mail-Adresse geändert - Bootstrap Academy",
+        )
+        .await
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn send_oauth2_link_created_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &OAuth2LinkCreatedTemplate,
+    ) -> anyhow::Result<bool> {
+        self.send_email(recipient, data, "Account verknüpft - Bootstrap Academy")
+            .await
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn send_user_export_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &UserExportTemplate,
+    ) -> anyhow::Result<bool> {
+        self.send_email(recipient, data, "Datenexport - Bootstrap Academy")
+            .await
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn send_user_deleted_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &UserDeletedTemplate,
+    ) -> anyhow::Result<bool> {
+        self.send_email(recipient, data, "Account gelöscht - Bootstrap Academy")
+            .await
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn send_session_revoked_email(
+        &self,
+        recipient: EmailAddressWithName,
+        data: &SessionRevokedTemplate,
+    ) -> anyhow::Result<bool> {
+        self.send_email(recipient, data, "Sitzung beendet - Bootstrap Academy")
+            .await
+    }
 }
 
 impl<EmailS, TemplateS> TemplateEmailServiceImpl<EmailS, TemplateS>
@@ -1,7 +1,10 @@
 use std::future::Future;
 
 use academy_models::{
-    oauth2::{OAuth2AuthorizationCode, OAuth2Provider, OAuth2UserInfo},
+    oauth2::{
+        OAuth2AuthorizationCode, OAuth2PendingAuthorization, OAuth2Provider, OAuth2RefreshToken,
+        OAuth2RemoteLogin, OAuth2State, OAuth2Tokens,
+    },
     url::Url,
 };
 use thiserror::Error;
@@ -9,16 +12,31 @@ use thiserror::Error;
 #[cfg_attr(feature = "mock", mockall::automock)]
 pub trait OAuth2ApiService: Send + Sync + 'static {
     /// Build the authorize URL for the given OAuth2 provider.
-    fn generate_auth_url(&self, provider: &OAuth2Provider) -> Url;
+    ///
+    /// The URL includes the redirect URI, the `state` parameter and the PKCE
+    /// code challenge (plus the `nonce` for OpenID Connect providers), so it
+    /// can be used by the client as is.
+    fn generate_auth_url(
+        &self,
+        provider: &OAuth2Provider,
+        authorization: &OAuth2PendingAuthorization,
+        state: &OAuth2State,
+    ) -> impl Future<Output = anyhow::Result<Url>> + Send;
 
     /// Try to resolve an authorization code and return the remote user
     /// information in case of success.
+    ///
+    /// The profile of the remote user is extracted from the claims configured
+    /// for the provider. Missing or invalid profile claims are ignored.
+    ///
+    /// For OpenID Connect providers the returned ID token is verified using
+    /// the provider's JWKS.
     fn resolve_code(
         &self,
         provider: OAuth2Provider,
         code: OAuth2AuthorizationCode,
-        redirect_url: Url,
-    ) -> impl Future<Output = Result<OAuth2UserInfo, OAuth2ResolveCodeError>> + Send;
+        authorization: OAuth2PendingAuthorization,
+    ) -> impl Future<Output = Result<OAuth2RemoteLogin, OAuth2ResolveCodeError>> + Send;
 }
 
 #[derive(Debug, Error)]
@@ -29,13 +47,54 @@ pub enum OAuth2ResolveCodeError {
     Other(#[from] anyhow::Error),
 }
 
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait OAuth2TokenApiService: Send + Sync + 'static {
+    /// Use the given refresh token to obtain new tokens from the OAuth2
+    /// provider.
+    ///
+    /// If the provider does not issue a new refresh token, the given one is
+    /// kept.
+    fn refresh(
+        &self,
+        provider: &OAuth2Provider,
+        refresh_token: &OAuth2RefreshToken,
+    ) -> impl Future<Output = Result<OAuth2Tokens, OAuth2RefreshTokenError>> + Send;
+
+    /// Revoke the given tokens at the OAuth2 provider.
+    ///
+    /// Does nothing if the provider does not support token revocation.
+    fn revoke(
+        &self,
+        provider: &OAuth2Provider,
+        tokens: &OAuth2Tokens,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+}
+
+#[derive(Debug, Error)]
+pub enum OAuth2RefreshTokenError {
+    #[error("The refresh token is invalid or has been revoked.")]
+    InvalidToken,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
 #[cfg(feature = "mock")]
 impl MockOAuth2ApiService {
-    pub fn with_generate_auth_url(mut self, provider: OAuth2Provider, result: Url) -> Self {
+    pub fn with_generate_auth_url(
+        mut self,
+        provider: OAuth2Provider,
+        authorization: OAuth2PendingAuthorization,
+        state: OAuth2State,
+        result: Url,
+    ) -> Self {
         self.expect_generate_auth_url()
             .once()
-            .with(mockall::predicate::eq(provider))
-            .return_once(|_| result);
+            .with(
+                mockall::predicate::eq(provider),
+                mockall::predicate::eq(authorization),
+                mockall::predicate::eq(state),
+            )
+            .return_once(|_, _, _| Box::pin(std::future::ready(Ok(result))));
         self
     }
 
@@ -43,17 +102,47 @@ impl MockOAuth2ApiService {
         mut self,
         provider: OAuth2Provider,
         code: OAuth2AuthorizationCode,
-        redirect_url: Url,
-        result: Result<OAuth2UserInfo, OAuth2ResolveCodeError>,
+        authorization: OAuth2PendingAuthorization,
+        result: Result<OAuth2RemoteLogin, OAuth2ResolveCodeError>,
     ) -> Self {
         self.expect_resolve_code()
             .once()
             .with(
                 mockall::predicate::eq(provider),
                 mockall::predicate::eq(code),
-                mockall::predicate::eq(redirect_url),
+                mockall::predicate::eq(authorization),
             )
             .return_once(|_, _, _| Box::pin(std::future::ready(result)));
         self
     }
 }
+
+#[cfg(feature = "mock")]
+impl MockOAuth2TokenApiService {
+    pub fn with_refresh(
+        mut self,
+        provider: OAuth2Provider,
+        refresh_token: OAuth2RefreshToken,
+        result: Result<OAuth2Tokens, OAuth2RefreshTokenError>,
+    ) -> Self {
+        self.expect_refresh()
+            .once()
+            .with(
+                mockall::predicate::eq(provider),
+                mockall::predicate::eq(refresh_token),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(result)));
+        self
+    }
+
+    pub fn with_revoke(mut self, provider: OAuth2Provider, tokens: OAuth2Tokens) -> Self {
+        self.expect_revoke()
+            .once()
+            .with(
+                mockall::predicate::eq(provider),
+                mockall::predicate::eq(tokens),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
+}
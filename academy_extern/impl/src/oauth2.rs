@@ -1,45 +1,157 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
 
 use academy_di::Build;
-use academy_extern_contracts::oauth2::{OAuth2ApiService, OAuth2ResolveCodeError};
+use academy_extern_contracts::oauth2::{
+    OAuth2ApiService, OAuth2RefreshTokenError, OAuth2ResolveCodeError, OAuth2TokenApiService,
+};
 use academy_models::{
-    oauth2::{OAuth2AuthorizationCode, OAuth2Provider, OAuth2UserInfo},
+    oauth2::{
+        OAuth2AuthorizationCode, OAuth2Nonce, OAuth2PendingAuthorization, OAuth2Provider,
+        OAuth2ProviderEndpoints, OAuth2RefreshToken, OAuth2RemoteLogin, OAuth2RemoteProfile,
+        OAuth2State, OAuth2Tokens, OAuth2UserInfo,
+    },
     url::Url,
 };
+use academy_shared_contracts::time::TimeService;
 use academy_utils::{trace_instrument, Apply};
-use anyhow::{anyhow, Context};
+use anyhow::{anyhow, bail, ensure, Context};
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use chrono::TimeDelta;
 use oauth2::{
-    basic::BasicClient, AuthUrl, AuthorizationCode, ClientId, ClientSecret, RedirectUrl,
-    RequestTokenError, TokenResponse, TokenUrl,
+    basic::{
+        BasicErrorResponse, BasicRevocationErrorResponse, BasicTokenIntrospectionResponse,
+        BasicTokenType,
+    },
+    AccessToken, AuthUrl, AuthorizationCode, Client, ClientId, ClientSecret, ExtraTokenFields,
+    PkceCodeChallenge, PkceCodeVerifier, RedirectUrl, RefreshToken, RequestTokenError,
+    RevocationUrl, StandardRevocableToken, StandardTokenResponse, TokenResponse, TokenUrl,
+};
+use ring::signature::{
+    RsaPublicKeyComponents, UnparsedPublicKey, ECDSA_P256_SHA256_FIXED, RSA_PKCS1_2048_8192_SHA256,
 };
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use tracing::trace;
 
 use crate::http::{HttpClient, USER_AGENT};
 
 #[derive(Debug, Clone, Build, Default)]
-pub struct OAuth2ApiServiceImpl {
+pub struct OAuth2ApiServiceImpl<Time> {
+    time: Time,
     #[di(default)]
     http: HttpClient,
+    #[di(default)]
+    oidc_cache: OidcCache,
+}
+
+/// Discovered OpenID Connect providers, indexed by their issuer url
+#[derive(Debug, Clone, Default)]
+struct OidcCache(Arc<RwLock<HashMap<String, Arc<OidcProvider>>>>);
+
+#[derive(Debug)]
+struct OidcProvider {
+    metadata: OidcProviderMetadata,
+    jwks: RwLock<Vec<Jwk>>,
+}
+
+/// Subset of the OpenID Connect provider metadata as specified in
+/// [OpenID Connect Discovery 1.0](https://openid.net/specs/openid-connect-discovery-1_0.html#ProviderMetadata)
+#[derive(Debug, Deserialize)]
+struct OidcProviderMetadata {
+    issuer: String,
+    authorization_endpoint: Url,
+    token_endpoint: Url,
+    userinfo_endpoint: Option<Url>,
+    jwks_uri: Url,
+    revocation_endpoint: Option<Url>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Jwks {
+    keys: Vec<Jwk>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Jwk {
+    kty: String,
+    kid: Option<String>,
+    crv: Option<String>,
+    n: Option<String>,
+    e: Option<String>,
+    x: Option<String>,
+    y: Option<String>,
+}
+
+struct Endpoints {
+    auth_url: Url,
+    token_url: Url,
+    userinfo_url: Option<Url>,
+    revocation_url: Option<Url>,
+    oidc: Option<Arc<OidcProvider>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IdTokenFields {
+    id_token: Option<String>,
 }
 
-impl OAuth2ApiService for OAuth2ApiServiceImpl {
+impl ExtraTokenFields for IdTokenFields {}
+
+type OAuth2Client = Client<
+    BasicErrorResponse,
+    StandardTokenResponse<IdTokenFields, BasicTokenType>,
+    BasicTokenType,
+    BasicTokenIntrospectionResponse,
+    StandardRevocableToken,
+    BasicRevocationErrorResponse,
+>;
+
+impl<Time> OAuth2ApiService for OAuth2ApiServiceImpl<Time>
+where
+    Time: TimeService,
+{
     #[trace_instrument(skip(self))]
-    fn generate_auth_url(&self, provider: &OAuth2Provider) -> Url {
-        let mut url = provider.auth_url.clone();
+    async fn generate_auth_url(
+        &self,
+        provider: &OAuth2Provider,
+        authorization: &OAuth2PendingAuthorization,
+        state: &OAuth2State,
+    ) -> anyhow::Result<Url> {
+        let endpoints = self.endpoints(provider).await?;
+        let oidc = endpoints.oidc.is_some();
+
+        let mut scopes = provider
+            .scopes
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        if oidc && !scopes.contains(&"openid") {
+            scopes.insert(0, "openid");
+        }
+
+        let code_challenge = PkceCodeChallenge::from_code_verifier_sha256(&PkceCodeVerifier::new(
+            authorization.pkce_verifier.clone().into_inner(),
+        ));
+
+        let mut url = endpoints.auth_url;
         url.query_pairs_mut()
             .append_pair("response_type", "code")
             .append_pair("client_id", &provider.client_id)
-            .apply_if(!provider.scopes.is_empty(), |q| {
-                let mut it = provider.scopes.iter();
-                let mut scopes = it.next().unwrap().clone();
-                for scope in it {
-                    scopes.push(' ');
-                    scopes.push_str(scope);
-                }
-                q.append_pair("scope", &scopes)
+            .append_pair("redirect_uri", authorization.redirect_uri.as_str())
+            .append_pair("state", state.as_str())
+            .append_pair("code_challenge", code_challenge.as_str())
+            .append_pair("code_challenge_method", code_challenge.method().as_str())
+            .apply_if(!scopes.is_empty(), |q| {
+                q.append_pair("scope", &scopes.join(" "))
+            })
+            .apply_if(oidc, |q| {
+                q.append_pair("nonce", authorization.nonce.as_str())
             })
             .finish();
-        url
+        Ok(url)
     }
 
     #[trace_instrument(skip(self))]
@@ -47,21 +159,22 @@ impl OAuth2ApiService for OAuth2ApiServiceImpl {
         &self,
         provider: OAuth2Provider,
         code: OAuth2AuthorizationCode,
-        redirect_url: Url,
-    ) -> Result<OAuth2UserInfo, OAuth2ResolveCodeError> {
-        let client = BasicClient::new(
-            ClientId::new(provider.client_id),
-            provider
-                .client_secret
-                .map(|x| ClientSecret::new(x.into_inner())),
-            AuthUrl::from_url(provider.auth_url.0),
-            Some(TokenUrl::from_url(provider.token_url.0)),
-        )
-        .set_redirect_uri(RedirectUrl::from_url(redirect_url.0));
+        authorization: OAuth2PendingAuthorization,
+    ) -> Result<OAuth2RemoteLogin, OAuth2ResolveCodeError> {
+        let endpoints = self
+            .endpoints(&provider)
+            .await
+            .context("Failed to get OAuth2 provider endpoints")?;
+
+        let client = make_client(&provider, &endpoints)
+            .set_redirect_uri(RedirectUrl::from_url(authorization.redirect_uri.0.clone()));
 
         // exchange the authorization code for an access token
         let response = client
             .exchange_code(AuthorizationCode::new(code.into_inner()))
+            .set_pkce_verifier(PkceCodeVerifier::new(
+                authorization.pkce_verifier.clone().into_inner(),
+            ))
             .request_async(http_client)
             .await
             .map_err(|err| match err {
@@ -73,50 +186,480 @@ impl OAuth2ApiService for OAuth2ApiServiceImpl {
                     .into(),
             })?;
 
-        let access_token = response.access_token().secret();
-        trace!(
-            access_token,
-            "exchanged authorization code for access token"
+        let tokens = self.make_tokens(&response, None);
+
+        self.fetch_user_info(&provider, &endpoints, &authorization, &response, tokens)
+            .await
+            .map_err(Into::into)
+    }
+}
+
+impl<Time> OAuth2TokenApiService for OAuth2ApiServiceImpl<Time>
+where
+    Time: TimeService,
+{
+    #[trace_instrument(skip(self))]
+    async fn refresh(
+        &self,
+        provider: &OAuth2Provider,
+        refresh_token: &OAuth2RefreshToken,
+    ) -> Result<OAuth2Tokens, OAuth2RefreshTokenError> {
+        let endpoints = self
+            .endpoints(provider)
+            .await
+            .context("Failed to get OAuth2 provider endpoints")?;
+
+        let response = make_client(provider, &endpoints)
+            .exchange_refresh_token(&RefreshToken::new(refresh_token.clone().into_inner()))
+            .request_async(http_client)
+            .await
+            .map_err(|err| match err {
+                RequestTokenError::ServerResponse(_) | RequestTokenError::Parse(_, _) => {
+                    OAuth2RefreshTokenError::InvalidToken
+                }
+                err => anyhow!(err)
+                    .context("Failed to refresh access token")
+                    .into(),
+            })?;
+
+        Ok(self.make_tokens(&response, Some(refresh_token)))
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn revoke(&self, provider: &OAuth2Provider, tokens: &OAuth2Tokens) -> anyhow::Result<()> {
+        let endpoints = self
+            .endpoints(provider)
+            .await
+            .context("Failed to get OAuth2 provider endpoints")?;
+
+        let Some(revocation_url) = endpoints.revocation_url.clone() else {
+            return Ok(());
+        };
+
+        // revoking the refresh token also invalidates all access tokens
+        // issued with it (RFC 7009, section 2.1)
+        let token = match &tokens.refresh_token {
+            Some(refresh_token) => StandardRevocableToken::RefreshToken(RefreshToken::new(
+                refresh_token.clone().into_inner(),
+            )),
+            None => StandardRevocableToken::AccessToken(AccessToken::new(
+                tokens.access_token.clone().into_inner(),
+            )),
+        };
+
+        make_client(provider, &endpoints)
+            .set_revocation_uri(RevocationUrl::from_url(revocation_url.0))
+            .revoke_token(token)
+            .context("Failed to build token revocation request")?
+            .request_async(http_client)
+            .await
+            .context("Failed to revoke token")?;
+
+        Ok(())
+    }
+}
+
+impl<Time> OAuth2ApiServiceImpl<Time>
+where
+    Time: TimeService,
+{
+    async fn endpoints(&self, provider: &OAuth2Provider) -> anyhow::Result<Endpoints> {
+        match &provider.endpoints {
+            OAuth2ProviderEndpoints::OAuth2(endpoints) => Ok(Endpoints {
+                auth_url: endpoints.auth_url.clone(),
+                token_url: endpoints.token_url.clone(),
+                userinfo_url: Some(endpoints.userinfo_url.clone()),
+                revocation_url: endpoints.revocation_url.clone(),
+                oidc: None,
+            }),
+            OAuth2ProviderEndpoints::OpenIdConnect { issuer_url } => {
+                let oidc = self.discover(issuer_url).await?;
+                Ok(Endpoints {
+                    auth_url: oidc.metadata.authorization_endpoint.clone(),
+                    token_url: oidc.metadata.token_endpoint.clone(),
+                    userinfo_url: oidc.metadata.userinfo_endpoint.clone(),
+                    revocation_url: oidc.metadata.revocation_endpoint.clone(),
+                    oidc: Some(oidc),
+                })
+            }
+        }
+    }
+
+    async fn discover(&self, issuer_url: &Url) -> anyhow::Result<Arc<OidcProvider>> {
+        let issuer = issuer_url.as_str().trim_end_matches('/');
+
+        let cached = self.oidc_cache.0.read().unwrap().get(issuer).cloned();
+        if let Some(oidc) = cached {
+            return Ok(oidc);
+        }
+
+        let metadata = self
+            .http
+            .get(format!("{issuer}/.well-known/openid-configuration"))
+            .send()
+            .await
+            .context("Failed to send OpenID Connect discovery request")?
+            .error_for_status()
+            .context("OpenID Connect discovery request returned an error")?
+            .json::<OidcProviderMetadata>()
+            .await
+            .context("Failed to deserialize OpenID Connect provider metadata")?;
+        trace!(?metadata, "discovered openid connect provider");
+
+        ensure!(
+            metadata.issuer.trim_end_matches('/') == issuer,
+            "Issuer {:?} of the discovered provider metadata does not match the configured issuer \
+             {issuer:?}",
+            metadata.issuer
         );
 
-        // use the access token to fetch the remote user's id and name
-        let userinfo = self
+        let jwks = self.fetch_jwks(&metadata.jwks_uri).await?;
+
+        let oidc = Arc::new(OidcProvider {
+            metadata,
+            jwks: RwLock::new(jwks),
+        });
+        self.oidc_cache
+            .0
+            .write()
+            .unwrap()
+            .insert(issuer.into(), Arc::clone(&oidc));
+
+        Ok(oidc)
+    }
+
+    async fn fetch_jwks(&self, jwks_uri: &Url) -> anyhow::Result<Vec<Jwk>> {
+        let jwks = self
             .http
-            .get(provider.userinfo_url.0)
-            .bearer_auth(access_token)
+            .get(jwks_uri.0.clone())
             .send()
             .await
-            .context("Failed to send request to fetch userinfo")?
+            .context("Failed to send request to fetch JWKS")?
             .error_for_status()
-            .context("Fetch userinfo request returned an error")?
-            .json::<HashMap<String, serde_json::Value>>()
+            .context("Fetch JWKS request returned an error")?
+            .json::<Jwks>()
             .await
-            .context("Failed to deserialize userinfo")?;
-        trace!(?userinfo, "fetched userinfo");
-
-        let id = match userinfo.get(&provider.userinfo_id_key) {
-            Some(serde_json::Value::Number(id)) => Ok(id.to_string()),
-            Some(serde_json::Value::String(id)) => Ok(id.to_owned()),
-            Some(x) => Err(anyhow!("Invalid user id: {x}")),
-            None => Err(anyhow!("User id missing")),
+            .context("Failed to deserialize JWKS")?;
+        trace!(?jwks, "fetched jwks");
+        Ok(jwks.keys)
+    }
+
+    /// Extract the tokens from a token response. If the response does not
+    /// contain a new refresh token, the `previous_refresh_token` is kept.
+    fn make_tokens(
+        &self,
+        response: &StandardTokenResponse<IdTokenFields, BasicTokenType>,
+        previous_refresh_token: Option<&OAuth2RefreshToken>,
+    ) -> OAuth2Tokens {
+        OAuth2Tokens {
+            access_token: response.access_token().secret().clone().into(),
+            refresh_token: response
+                .refresh_token()
+                .map(|refresh_token| refresh_token.secret().clone().into())
+                .or_else(|| previous_refresh_token.cloned()),
+            expires_at: response
+                .expires_in()
+                .and_then(|expires_in| TimeDelta::from_std(expires_in).ok())
+                .map(|expires_in| self.time.now() + expires_in),
+        }
+    }
+
+    async fn fetch_user_info(
+        &self,
+        provider: &OAuth2Provider,
+        endpoints: &Endpoints,
+        authorization: &OAuth2PendingAuthorization,
+        response: &StandardTokenResponse<IdTokenFields, BasicTokenType>,
+        tokens: OAuth2Tokens,
+    ) -> anyhow::Result<OAuth2RemoteLogin> {
+        let access_token = response.access_token().secret();
+        trace!(
+            access_token,
+            "exchanged authorization code for access token"
+        );
+
+        let mut claims = serde_json::Map::new();
+
+        // verify the ID token of OpenID Connect providers
+        if let Some(oidc) = &endpoints.oidc {
+            let id_token = response
+                .extra_fields()
+                .id_token
+                .as_deref()
+                .context("Token response does not contain an ID token")?;
+            claims = self
+                .verify_id_token(oidc, &provider.client_id, &authorization.nonce, id_token)
+                .await
+                .context("Failed to verify ID token")?;
+            trace!(?claims, "verified id token");
+        }
+
+        // use the access token to fetch the remote user's id and name
+        if let Some(userinfo_url) = &endpoints.userinfo_url {
+            let userinfo = self
+                .http
+                .get(userinfo_url.0.clone())
+                .bearer_auth(access_token)
+                .send()
+                .await
+                .context("Failed to send request to fetch userinfo")?
+                .error_for_status()
+                .context("Fetch userinfo request returned an error")?
+                .json::<serde_json::Map<String, Value>>()
+                .await
+                .context("Failed to deserialize userinfo")?;
+            trace!(?userinfo, "fetched userinfo");
+
+            ensure!(
+                endpoints.oidc.is_none() || userinfo.get("sub") == claims.get("sub"),
+                "Subject of userinfo does not match the subject of the ID token"
+            );
+
+            claims.extend(userinfo);
+        }
+
+        parse_remote_login(provider, &claims, tokens)
+    }
+
+    async fn verify_id_token(
+        &self,
+        oidc: &OidcProvider,
+        client_id: &str,
+        nonce: &OAuth2Nonce,
+        id_token: &str,
+    ) -> anyhow::Result<serde_json::Map<String, Value>> {
+        let id_token = IdToken::parse(id_token)?;
+        let kid = id_token.header.kid.as_deref();
+
+        let cached = find_key(&oidc.jwks.read().unwrap(), kid).cloned();
+        let key = match cached {
+            Some(key) => key,
+            None => {
+                // the provider may have rotated its keys
+                let jwks = self.fetch_jwks(&oidc.metadata.jwks_uri).await?;
+                let key = find_key(&jwks, kid).cloned();
+                *oidc.jwks.write().unwrap() = jwks;
+                key.with_context(|| format!("Unknown key id {kid:?}"))?
+            }
+        };
+
+        id_token.verify_signature(&key)?;
+        id_token.validate_claims(
+            &oidc.metadata.issuer,
+            client_id,
+            nonce,
+            self.time.now().timestamp(),
+        )?;
+
+        Ok(id_token.claims)
+    }
+}
+
+fn parse_remote_login(
+    provider: &OAuth2Provider,
+    claims: &serde_json::Map<String, Value>,
+    tokens: OAuth2Tokens,
+) -> anyhow::Result<OAuth2RemoteLogin> {
+    let id = match claims.get(&provider.userinfo_id_key) {
+        Some(Value::Number(id)) => Ok(id.to_string()),
+        Some(Value::String(id)) => Ok(id.to_owned()),
+        Some(x) => Err(anyhow!("Invalid user id: {x}")),
+        None => Err(anyhow!("User id missing")),
+    }
+    .context("Failed to get user id from userinfo")?
+    .try_into()
+    .map_err(|id| anyhow!("Failed to deserialize remote user id {id:?}"))?;
+
+    let name = match claims.get(&provider.userinfo_name_key) {
+        Some(Value::String(name)) => Ok(name.clone()),
+        Some(x) => Err(anyhow!("Invalid username: {x}")),
+        None => Err(anyhow!("Username missing")),
+    }
+    .context("Failed to get username from userinfo")?
+    .try_into()
+    .map_err(|name| anyhow!("Failed to deserialize remote user name {name:?}"))?;
+
+    // the profile is only used to pre-fill the registration, so missing or
+    // invalid claims are ignored instead of failing the login
+    let get_str = |key: &Option<String>| match key.as_ref().and_then(|key| claims.get(key)) {
+        Some(Value::String(value)) if !value.is_empty() => Some(value.as_str()),
+        _ => None,
+    };
+
+    let email = get_str(&provider.userinfo_email_key).and_then(|email| email.parse().ok());
+    let email_verified = email.is_some()
+        && match provider
+            .userinfo_email_verified_key
+            .as_ref()
+            .and_then(|key| claims.get(key))
+        {
+            Some(Value::Bool(verified)) => *verified,
+            // some providers return boolean claims as strings
+            Some(Value::String(verified)) => verified == "true",
+            _ => false,
+        };
+    let display_name = get_str(&provider.userinfo_display_name_key)
+        .and_then(|display_name| display_name.trim().try_into().ok());
+    let avatar_url = get_str(&provider.userinfo_avatar_url_key)
+        .and_then(|avatar_url| avatar_url.parse::<Url>().ok())
+        .filter(|avatar_url| avatar_url.scheme() == "https" || avatar_url.scheme() == "http");
+
+    Ok(OAuth2RemoteLogin {
+        user_info: OAuth2UserInfo { id, name },
+        profile: OAuth2RemoteProfile {
+            email,
+            email_verified,
+            display_name,
+            avatar_url,
+        },
+        tokens,
+    })
+}
+
+fn find_key<'a>(jwks: &'a [Jwk], kid: Option<&str>) -> Option<&'a Jwk> {
+    jwks.iter()
+        .find(|jwk| kid.is_none() || jwk.kid.as_deref() == kid)
+}
+
+struct IdToken {
+    header: IdTokenHeader,
+    signing_input: String,
+    signature: Vec<u8>,
+    claims: serde_json::Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct IdTokenHeader {
+    alg: String,
+    kid: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    iss: String,
+    aud: IdTokenAudience,
+    azp: Option<String>,
+    exp: i64,
+    nonce: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IdTokenAudience {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl IdTokenAudience {
+    fn contains(&self, client_id: &str) -> bool {
+        match self {
+            Self::Single(aud) => aud == client_id,
+            Self::Multiple(aud) => aud.iter().any(|aud| aud == client_id),
         }
-        .context("Failed to get user id from userinfo")?
-        .try_into()
-        .map_err(|id| anyhow!("Failed to deserialize remote user id {id:?}"))?;
-
-        let name = match userinfo.get(&provider.userinfo_name_key) {
-            Some(serde_json::Value::String(name)) => Ok(name.clone()),
-            Some(x) => Err(anyhow!("Invalid username: {x}")),
-            None => Err(anyhow!("Username missing")),
+    }
+}
+
+impl IdToken {
+    fn parse(id_token: &str) -> anyhow::Result<Self> {
+        let mut parts = id_token.split('.');
+        let (Some(header), Some(claims), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            bail!("Invalid ID token format");
+        };
+
+        let decode = |part: &str| {
+            BASE64_URL_SAFE_NO_PAD
+                .decode(part)
+                .context("Failed to decode ID token")
+        };
+
+        Ok(Self {
+            header: serde_json::from_slice(&decode(header)?)
+                .context("Failed to deserialize ID token header")?,
+            signing_input: format!("{header}.{claims}"),
+            signature: decode(signature)?,
+            claims: serde_json::from_slice(&decode(claims)?)
+                .context("Failed to deserialize ID token claims")?,
+        })
+    }
+
+    fn verify_signature(&self, key: &Jwk) -> anyhow::Result<()> {
+        let param = |value: &Option<String>, name: &str| {
+            BASE64_URL_SAFE_NO_PAD
+                .decode(
+                    value
+                        .as_deref()
+                        .with_context(|| format!("JWK parameter {name:?} is missing"))?,
+                )
+                .with_context(|| format!("Failed to decode JWK parameter {name:?}"))
+        };
+
+        let message = self.signing_input.as_bytes();
+        let result = match (self.header.alg.as_str(), key.kty.as_str()) {
+            ("RS256", "RSA") => RsaPublicKeyComponents {
+                n: param(&key.n, "n")?,
+                e: param(&key.e, "e")?,
+            }
+            .verify(&RSA_PKCS1_2048_8192_SHA256, message, &self.signature),
+            ("ES256", "EC") if key.crv.as_deref() == Some("P-256") => {
+                // uncompressed point: 0x04 || x || y
+                let mut point = vec![0x04];
+                point.extend(param(&key.x, "x")?);
+                point.extend(param(&key.y, "y")?);
+                UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, point)
+                    .verify(message, &self.signature)
+            }
+            (alg, kty) => bail!("Unsupported ID token algorithm {alg:?} for key type {kty:?}"),
+        };
+
+        result.map_err(|_| anyhow!("Invalid ID token signature"))
+    }
+
+    fn validate_claims(
+        &self,
+        issuer: &str,
+        client_id: &str,
+        nonce: &OAuth2Nonce,
+        now: i64,
+    ) -> anyhow::Result<()> {
+        let claims = serde_json::from_value::<IdTokenClaims>(Value::Object(self.claims.clone()))
+            .context("Failed to deserialize ID token claims")?;
+
+        ensure!(
+            claims.iss == issuer,
+            "Invalid ID token issuer {:?}",
+            claims.iss
+        );
+        ensure!(
+            claims.aud.contains(client_id),
+            "ID token has not been issued for this client"
+        );
+        if let Some(azp) = &claims.azp {
+            ensure!(azp == client_id, "Invalid authorized party {azp:?}");
         }
-        .context("Failed to get username from userinfo")?
-        .try_into()
-        .map_err(|name| anyhow!("Failed to deserialize remote user name {name:?}"))?;
+        ensure!(now < claims.exp, "ID token has expired");
+        ensure!(
+            claims.nonce.as_deref() == Some(nonce.as_str()),
+            "Invalid ID token nonce"
+        );
 
-        Ok(OAuth2UserInfo { id, name })
+        Ok(())
     }
 }
 
+fn make_client(provider: &OAuth2Provider, endpoints: &Endpoints) -> OAuth2Client {
+    OAuth2Client::new(
+        ClientId::new(provider.client_id.clone()),
+        provider
+            .client_secret
+            .clone()
+            .map(|x| ClientSecret::new(x.into_inner())),
+        AuthUrl::from_url(endpoints.auth_url.0.clone()),
+        Some(TokenUrl::from_url(endpoints.token_url.0.clone())),
+    )
+}
+
 async fn http_client(
     mut request: oauth2::HttpRequest,
 ) -> Result<oauth2::HttpResponse, oauth2::reqwest::AsyncHttpClientError> {
@@ -129,53 +672,393 @@ async fn http_client(
 
 #[cfg(test)]
 mod tests {
+    use academy_models::oauth2::OAuth2Endpoints;
+    use academy_shared_contracts::time::MockTimeService;
+    use chrono::{TimeZone, Utc};
+    use ring::{
+        rand::SystemRandom,
+        signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING},
+    };
+    use serde_json::json;
+
     use super::*;
 
-    #[test]
-    fn generate_auth_url_with_scopes() {
+    type Sut = OAuth2ApiServiceImpl<MockTimeService>;
+
+    #[tokio::test]
+    async fn generate_auth_url_with_scopes() {
         // Arrange
         let provider = make_provider();
 
-        let sut = OAuth2ApiServiceImpl::default();
+        let sut = Sut::default();
 
         // Act
-        let result = sut.generate_auth_url(&provider);
+        let result = sut
+            .generate_auth_url(&provider, &make_authorization(), &make_state())
+            .await;
 
         // Assert
-        assert_eq!(result.as_str(), "https://oauth2.provider/auth?response_type=code&client_id=the-client-id&scope=foo+bar+baz");
+        assert_eq!(
+            result.unwrap().as_str(),
+            "https://oauth2.provider/auth?response_type=code&client_id=the-client-id&redirect_uri=https%3A%2F%2Fthe-frontend%2Fcallback&state=0VtfWpsqnWBHOYEHzu1tzIgqNTxnFzyQ&code_challenge=E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM&code_challenge_method=S256&scope=foo+bar+baz"
+        );
     }
 
-    #[test]
-    fn generate_auth_url_without_scopes() {
+    #[tokio::test]
+    async fn generate_auth_url_without_scopes() {
         // Arrange
         let provider = OAuth2Provider {
             scopes: Vec::new(),
             ..make_provider()
         };
 
-        let sut = OAuth2ApiServiceImpl::default();
+        let sut = Sut::default();
+
+        // Act
+        let result = sut
+            .generate_auth_url(&provider, &make_authorization(), &make_state())
+            .await;
+
+        // Assert
+        assert_eq!(
+            result.unwrap().as_str(),
+            "https://oauth2.provider/auth?response_type=code&client_id=the-client-id&redirect_uri=https%3A%2F%2Fthe-frontend%2Fcallback&state=0VtfWpsqnWBHOYEHzu1tzIgqNTxnFzyQ&code_challenge=E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM&code_challenge_method=S256"
+        );
+    }
+
+    #[test]
+    fn verify_id_token_ok() {
+        // Arrange
+        let key = TestKey::generate();
+        let id_token = key.sign(&make_claims());
+
+        // Act
+        let result = IdToken::parse(&id_token).and_then(|id_token| {
+            id_token.verify_signature(&key.jwk)?;
+            id_token.validate_claims(ISSUER, "the-client-id", &make_nonce(), NOW)?;
+            Ok(id_token.claims)
+        });
+
+        // Assert
+        assert_eq!(result.unwrap().get("sub").unwrap(), "the-user-id");
+    }
+
+    #[test]
+    fn verify_id_token_invalid_signature() {
+        // Arrange
+        let key = TestKey::generate();
+        let other_key = TestKey::generate();
+        let id_token = other_key.sign(&make_claims());
+
+        // Act
+        let result = IdToken::parse(&id_token)
+            .unwrap()
+            .verify_signature(&key.jwk);
+
+        // Assert
+        result.unwrap_err();
+    }
+
+    #[test]
+    fn verify_id_token_invalid_claims() {
+        for (key, value, now) in [
+            ("iss", json!("https://other.provider"), NOW),
+            ("aud", json!("other-client-id"), NOW),
+            ("aud", json!(["other-client-id"]), NOW),
+            ("azp", json!("other-client-id"), NOW),
+            ("nonce", json!("other-nonce"), NOW),
+            ("nonce", Value::Null, NOW),
+            ("exp", json!(NOW + 600), NOW + 600),
+        ] {
+            // Arrange
+            let test_key = TestKey::generate();
+            let mut claims = make_claims();
+            claims.insert(key.into(), value);
+            let id_token = IdToken::parse(&test_key.sign(&claims)).unwrap();
+
+            // Act
+            let result = id_token.validate_claims(ISSUER, "the-client-id", &make_nonce(), now);
+
+            // Assert
+            result.unwrap_err();
+        }
+    }
+
+    #[test]
+    fn verify_id_token_multiple_audiences() {
+        // Arrange
+        let key = TestKey::generate();
+        let mut claims = make_claims();
+        claims.insert("aud".into(), json!(["other-client-id", "the-client-id"]));
+        claims.insert("azp".into(), json!("the-client-id"));
+        let id_token = IdToken::parse(&key.sign(&claims)).unwrap();
+
+        // Act
+        let result = id_token.validate_claims(ISSUER, "the-client-id", &make_nonce(), NOW);
+
+        // Assert
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn verify_id_token_time() {
+        // Arrange
+        let key = TestKey::generate();
+        let id_token = key.sign(&make_claims());
+
+        let oidc = OidcProvider {
+            metadata: OidcProviderMetadata {
+                issuer: ISSUER.into(),
+                authorization_endpoint: "https://oidc.provider/auth".parse().unwrap(),
+                token_endpoint: "https://oidc.provider/token".parse().unwrap(),
+                userinfo_endpoint: None,
+                jwks_uri: "https://oidc.provider/jwks".parse().unwrap(),
+                revocation_endpoint: None,
+            },
+            jwks: RwLock::new(vec![key.jwk]),
+        };
+
+        let time = MockTimeService::new()
+            .with_now(Utc.timestamp_opt(NOW, 0).unwrap())
+            .with_now(Utc.timestamp_opt(NOW + 600, 0).unwrap());
+
+        let sut = OAuth2ApiServiceImpl {
+            time,
+            ..Sut::default()
+        };
+
+        // Act
+        let valid = sut
+            .verify_id_token(&oidc, "the-client-id", &make_nonce(), &id_token)
+            .await;
+        let expired = sut
+            .verify_id_token(&oidc, "the-client-id", &make_nonce(), &id_token)
+            .await;
+
+        // Assert
+        valid.unwrap();
+        expired.unwrap_err();
+    }
+
+    #[test]
+    fn parse_remote_login_ok() {
+        // Arrange
+        let provider = make_provider();
+        let claims = json!({
+            "id": 1337,
+            "name": "foo",
+            "email": "foo@example.com",
+            "email_verified": true,
+            "display_name": "Foo Bar",
+            "avatar_url": "https://cdn.oauth2.provider/avatars/1337.png",
+        });
 
         // Act
-        let result = sut.generate_auth_url(&provider);
+        let result = parse_remote_login(&provider, claims.as_object().unwrap(), make_tokens());
 
         // Assert
         assert_eq!(
-            result.as_str(),
-            "https://oauth2.provider/auth?response_type=code&client_id=the-client-id"
+            result.unwrap(),
+            OAuth2RemoteLogin {
+                user_info: OAuth2UserInfo {
+                    id: "1337".try_into().unwrap(),
+                    name: "foo".try_into().unwrap(),
+                },
+                profile: OAuth2RemoteProfile {
+                    email: Some("foo@example.com".parse().unwrap()),
+                    email_verified: true,
+                    display_name: Some("Foo Bar".try_into().unwrap()),
+                    avatar_url: Some(
+                        "https://cdn.oauth2.provider/avatars/1337.png"
+                            .parse()
+                            .unwrap()
+                    ),
+                },
+                tokens: make_tokens(),
+            }
         );
     }
 
+    #[test]
+    fn parse_remote_login_string_email_verified() {
+        // Arrange
+        let provider = make_provider();
+        let claims = json!({
+            "id": "1337",
+            "name": "foo",
+            "email": "foo@example.com",
+            "email_verified": "true",
+        });
+
+        // Act
+        let result = parse_remote_login(&provider, claims.as_object().unwrap(), make_tokens());
+
+        // Assert
+        let profile = result.unwrap().profile;
+        assert_eq!(profile.email, Some("foo@example.com".parse().unwrap()));
+        assert!(profile.email_verified);
+    }
+
+    #[test]
+    fn parse_remote_login_email_verified_key_not_configured() {
+        // Arrange
+        let provider = OAuth2Provider {
+            userinfo_email_verified_key: None,
+            ..make_provider()
+        };
+        let claims = json!({
+            "id": "1337",
+            "name": "foo",
+            "email": "foo@example.com",
+            "email_verified": true,
+        });
+
+        // Act
+        let result = parse_remote_login(&provider, claims.as_object().unwrap(), make_tokens());
+
+        // Assert
+        let profile = result.unwrap().profile;
+        assert_eq!(profile.email, Some("foo@example.com".parse().unwrap()));
+        assert!(!profile.email_verified);
+    }
+
+    #[test]
+    fn parse_remote_login_invalid_profile() {
+        // Arrange
+        let provider = make_provider();
+        let claims = json!({
+            "id": "1337",
+            "name": "foo",
+            "email": "not an email address",
+            "email_verified": true,
+            "display_name": "x".repeat(65),
+            "avatar_url": "javascript:alert(1)",
+        });
+
+        // Act
+        let result = parse_remote_login(&provider, claims.as_object().unwrap(), make_tokens());
+
+        // Assert
+        assert_eq!(result.unwrap().profile, OAuth2RemoteProfile::default());
+    }
+
+    #[test]
+    fn parse_remote_login_missing_id() {
+        // Arrange
+        let provider = make_provider();
+        let claims = json!({"name": "foo"});
+
+        // Act
+        let result = parse_remote_login(&provider, claims.as_object().unwrap(), make_tokens());
+
+        // Assert
+        result.unwrap_err();
+    }
+
+    const ISSUER: &str = "https://oidc.provider";
+    const NOW: i64 = 1_700_000_000;
+
+    struct TestKey {
+        key_pair: EcdsaKeyPair,
+        jwk: Jwk,
+    }
+
+    impl TestKey {
+        fn generate() -> Self {
+            let rng = SystemRandom::new();
+            let pkcs8 =
+                EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng).unwrap();
+            let key_pair =
+                EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref(), &rng)
+                    .unwrap();
+            let point = key_pair.public_key().as_ref();
+            let jwk = Jwk {
+                kty: "EC".into(),
+                kid: Some("the-key-id".into()),
+                crv: Some("P-256".into()),
+                n: None,
+                e: None,
+                x: Some(BASE64_URL_SAFE_NO_PAD.encode(&point[1..33])),
+                y: Some(BASE64_URL_SAFE_NO_PAD.encode(&point[33..65])),
+            };
+            Self { key_pair, jwk }
+        }
+
+        fn sign(&self, claims: &serde_json::Map<String, Value>) -> String {
+            let header = BASE64_URL_SAFE_NO_PAD
+                .encode(json!({"alg": "ES256", "kid": "the-key-id"}).to_string());
+            let claims = BASE64_URL_SAFE_NO_PAD.encode(Value::Object(claims.clone()).to_string());
+            let signature = self
+                .key_pair
+                .sign(
+                    &SystemRandom::new(),
+                    format!("{header}.{claims}").as_bytes(),
+                )
+                .unwrap();
+            format!(
+                "{header}.{claims}.{}",
+                BASE64_URL_SAFE_NO_PAD.encode(signature)
+            )
+        }
+    }
+
+    fn make_claims() -> serde_json::Map<String, Value> {
+        let Value::Object(claims) = json!({
+            "iss": ISSUER,
+            "sub": "the-user-id",
+            "aud": "the-client-id",
+            "exp": NOW + 300,
+            "iat": NOW,
+            "nonce": "the-nonce",
+        }) else {
+            unreachable!()
+        };
+        claims
+    }
+
     fn make_provider() -> OAuth2Provider {
         OAuth2Provider {
             name: "test".into(),
             client_id: "the-client-id".into(),
             client_secret: None,
-            auth_url: "https://oauth2.provider/auth".parse().unwrap(),
-            token_url: "http://test".parse().unwrap(),
-            userinfo_url: "http://test".parse().unwrap(),
-            userinfo_id_key: String::new(),
-            userinfo_name_key: String::new(),
+            endpoints: OAuth2ProviderEndpoints::OAuth2(Box::new(OAuth2Endpoints {
+                auth_url: "https://oauth2.provider/auth".parse().unwrap(),
+                token_url: "http://test".parse().unwrap(),
+                userinfo_url: "http://test".parse().unwrap(),
+                revocation_url: None,
+            })),
+            userinfo_id_key: "id".into(),
+            userinfo_name_key: "name".into(),
+            userinfo_email_key: Some("email".into()),
+            userinfo_email_verified_key: Some("email_verified".into()),
+            userinfo_display_name_key: Some("display_name".into()),
+            userinfo_avatar_url_key: Some("avatar_url".into()),
             scopes: ["foo", "bar", "baz"].map(Into::into).into(),
         }
     }
+
+    fn make_authorization() -> OAuth2PendingAuthorization {
+        OAuth2PendingAuthorization {
+            provider_id: "test".into(),
+            redirect_uri: "https://the-frontend/callback".parse().unwrap(),
+            pkce_verifier: "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".into(),
+            nonce: make_nonce(),
+        }
+    }
+
+    fn make_tokens() -> OAuth2Tokens {
+        OAuth2Tokens {
+            access_token: "the-access-token".into(),
+            refresh_token: Some("the-refresh-token".into()),
+            expires_at: None,
+        }
+    }
+
+    fn make_state() -> OAuth2State {
+        "0VtfWpsqnWBHOYEHzu1tzIgqNTxnFzyQ".try_into().unwrap()
+    }
+
+    fn make_nonce() -> OAuth2Nonce {
+        "the-nonce".into()
+    }
 }
@@ -1,30 +1,71 @@
 use std::{collections::HashMap, str::FromStr};
 
-use academy_extern_contracts::oauth2::{OAuth2ApiService, OAuth2ResolveCodeError};
+use academy_extern_contracts::oauth2::{
+    OAuth2ApiService, OAuth2RefreshTokenError, OAuth2ResolveCodeError, OAuth2TokenApiService,
+};
 use academy_extern_impl::oauth2::OAuth2ApiServiceImpl;
 use academy_models::{
-    oauth2::{OAuth2Provider, OAuth2UserInfo},
+    oauth2::{
+        OAuth2Endpoints, OAuth2PendingAuthorization, OAuth2Provider, OAuth2ProviderEndpoints,
+        OAuth2RemoteLogin, OAuth2RemoteProfile, OAuth2UserInfo,
+    },
     url::Url,
 };
+use academy_shared_impl::time::TimeServiceImpl;
 use academy_utils::assert_matches;
 
 #[tokio::test]
 async fn oauth2() {
-    let provider = get_provider();
+    let base_url = Url::from_str("http://localhost:8002").unwrap();
+    let provider = OAuth2Provider {
+        // email addresses of this provider are never considered verified
+        userinfo_email_verified_key: None,
+        ..get_provider(OAuth2ProviderEndpoints::OAuth2(Box::new(OAuth2Endpoints {
+            auth_url: base_url.join("oauth2/authorize").unwrap().into(),
+            token_url: base_url.join("oauth2/token").unwrap().into(),
+            userinfo_url: base_url.join("user").unwrap().into(),
+            revocation_url: Some(base_url.join("oauth2/revoke").unwrap().into()),
+        })))
+    };
+
+    test_provider(provider, "userid123", "theremoteusername", false).await;
+}
+
+#[tokio::test]
+async fn openid_connect() {
+    let provider = get_provider(OAuth2ProviderEndpoints::OpenIdConnect {
+        issuer_url: "http://127.0.0.1:8002".parse().unwrap(),
+    });
 
+    test_provider(provider, "userid456", "theoidcusername", true).await;
+}
+
+async fn test_provider(provider: OAuth2Provider, id: &str, name: &str, email_verified: bool) {
     let client = reqwest::Client::builder()
         .redirect(reqwest::redirect::Policy::none())
         .build()
         .unwrap();
 
-    let mut url = provider.auth_url.clone();
-    url.query_pairs_mut()
-        .append_pair("response_type", "code")
-        .append_pair("client_id", &provider.client_id)
-        .append_pair("state", "thestate")
-        .append_pair("redirect_uri", redirect_url().as_str())
-        .finish();
-    let form = HashMap::from([("id", "userid123"), ("name", "theremoteusername")]);
+    let sut = OAuth2ApiServiceImpl::<TimeServiceImpl>::default();
+
+    let authorization = get_authorization();
+    let url = sut
+        .generate_auth_url(
+            &provider,
+            &authorization,
+            &"GwVDAIwhlkDLTgxYxCqPaRbRcJafMqKB".try_into().unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let form = HashMap::from([
+        ("id", id),
+        ("name", name),
+        ("email", "remote@example.com"),
+        ("email_verified", "true"),
+        ("display_name", "Remote User"),
+        ("avatar_url", "https://example.com/avatar.png"),
+    ]);
     let response = client
         .post(url.0)
         .form(&form)
@@ -43,48 +84,78 @@ async fn oauth2() {
         .unwrap();
     let code = url.query_pairs().find(|(k, _)| *k == "code").unwrap().1;
     let state = url.query_pairs().find(|(k, _)| *k == "state").unwrap().1;
-    assert_eq!(state, "thestate");
-
-    let sut = OAuth2ApiServiceImpl::default();
+    assert_eq!(state, "GwVDAIwhlkDLTgxYxCqPaRbRcJafMqKB");
 
     let result = sut
         .resolve_code(
             provider.clone(),
             code.as_ref().try_into().unwrap(),
-            redirect_url(),
+            authorization.clone(),
         )
         .await
         .unwrap();
+    let tokens = result.tokens.clone();
+    assert!(tokens.refresh_token.is_some());
+    assert!(tokens.expires_at.is_some());
     assert_eq!(
         result,
-        OAuth2UserInfo {
-            id: "userid123".try_into().unwrap(),
-            name: "theremoteusername".try_into().unwrap()
+        OAuth2RemoteLogin {
+            user_info: OAuth2UserInfo {
+                id: id.try_into().unwrap(),
+                name: name.try_into().unwrap()
+            },
+            profile: OAuth2RemoteProfile {
+                email: Some("remote@example.com".parse().unwrap()),
+                email_verified,
+                display_name: Some("Remote User".try_into().unwrap()),
+                avatar_url: Some("https://example.com/avatar.png".parse().unwrap()),
+            },
+            tokens: tokens.clone(),
         }
     );
 
+    let refresh_token = tokens.refresh_token.unwrap();
+    let refreshed = sut.refresh(&provider, &refresh_token).await.unwrap();
+    assert_ne!(refreshed.access_token, tokens.access_token);
+    assert!(refreshed.expires_at.is_some());
+
+    // refresh tokens are rotated
+    let result = sut.refresh(&provider, &refresh_token).await;
+    assert_matches!(result, Err(OAuth2RefreshTokenError::InvalidToken));
+
+    sut.revoke(&provider, &refreshed).await.unwrap();
     let result = sut
-        .resolve_code(provider, "invalidcode".try_into().unwrap(), redirect_url())
+        .refresh(&provider, refreshed.refresh_token.as_ref().unwrap())
+        .await;
+    assert_matches!(result, Err(OAuth2RefreshTokenError::InvalidToken));
+
+    let result = sut
+        .resolve_code(provider, "invalidcode".try_into().unwrap(), authorization)
         .await;
     assert_matches!(result, Err(OAuth2ResolveCodeError::InvalidCode));
 }
 
-fn get_provider() -> OAuth2Provider {
-    let base_url = Url::from_str("http://localhost:8002").unwrap();
-
+fn get_provider(endpoints: OAuth2ProviderEndpoints) -> OAuth2Provider {
     OAuth2Provider {
         name: "test".into(),
         client_id: "client-id".into(),
         client_secret: Some("client-secret".into()),
-        auth_url: base_url.join("oauth2/authorize").unwrap().into(),
-        token_url: base_url.join("oauth2/token").unwrap().into(),
-        userinfo_url: base_url.join("user").unwrap().into(),
+        endpoints,
         userinfo_id_key: "id".into(),
         userinfo_name_key: "name".into(),
+        userinfo_email_key: Some("email".into()),
+        userinfo_email_verified_key: Some("email_verified".into()),
+        userinfo_display_name_key: Some("display_name".into()),
+        userinfo_avatar_url_key: Some("avatar_url".into()),
         scopes: vec![],
     }
 }
 
-fn redirect_url() -> Url {
-    Url::from_str("http://localhost/oauth2/callback").unwrap()
+fn get_authorization() -> OAuth2PendingAuthorization {
+    OAuth2PendingAuthorization {
+        provider_id: "test".into(),
+        redirect_uri: "http://localhost/oauth2/callback".parse().unwrap(),
+        pkce_verifier: "4tZxsTq0QCTSKddGvY6QgCmHbTn7ORL4R3o3ADlAU2NZmjaXlIyWt5bn3ILvJmdQ".into(),
+        nonce: "jnxLgdP2m7J06G9jmHX9eHaxJtuPfpZK".into(),
+    }
 }
@@ -0,0 +1,60 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{macros::id, user::UserId};
+
+id!(AuditEventId);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub id: AuditEventId,
+    /// The user who performed the action, `None` if it was performed without
+    /// authentication (e.g. a password reset)
+    pub actor_id: Option<UserId>,
+    /// The user affected by the action
+    pub user_id: UserId,
+    pub kind: AuditEventKind,
+    pub client: ClientInfo,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEventKind {
+    /// The user's profile or account settings have been updated
+    UserUpdated,
+    /// The user has been impersonated by an administrator
+    UserImpersonated,
+    /// Multi-factor authentication has been disabled
+    MfaDisabled,
+    /// The password has been reset using a verification code
+    PasswordReset,
+    /// An OAuth2 account has been linked
+    OAuth2LinkCreated,
+    /// An OAuth2 account has been unlinked
+    OAuth2LinkDeleted,
+    /// The deletion of the user account has been requested
+    UserDeleted,
+    /// A pending deletion of the user account has been cancelled
+    UserRestored,
+    /// A session has been revoked because one of its retired refresh tokens
+    /// has been used again
+    RefreshTokenReused,
+}
+
+/// Information about the client which triggered an action
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ClientInfo {
+    pub ip_address: Option<IpAddr>,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AuditEventFilter {
+    pub actor_id: Option<UserId>,
+    pub user_id: Option<UserId>,
+    pub kind: Option<AuditEventKind>,
+}
@@ -28,12 +28,25 @@ pub enum AuthenticateError {
 
 #[derive(Debug, Error)]
 pub enum AuthorizeError {
-    #[error("The user is not an administrator.")]
-    Admin,
+    #[error("The user does not have the required permission.")]
+    Permission,
     #[error("The user's email address is not verified.")]
     EmailVerified,
+    #[error("This action is not allowed while impersonating a user.")]
+    Impersonation,
 }
 
 nutype_string!(AccessToken(sensitive));
 nutype_string!(RefreshToken(sensitive));
 nutype_string!(InternalToken(sensitive));
+
+/// Public part of an ES256 key used to sign JWTs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JwtPublicKey {
+    /// Key id (RFC 7638 JWK thumbprint)
+    pub kid: String,
+    /// X coordinate of the P-256 public key point
+    pub x: [u8; 32],
+    /// Y coordinate of the P-256 public key point
+    pub y: [u8; 32],
+}
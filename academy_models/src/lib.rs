@@ -5,6 +5,7 @@ use regex::Regex;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+pub mod audit;
 pub mod auth;
 pub mod contact;
 pub mod email_address;
@@ -12,6 +13,7 @@ mod macros;
 pub mod mfa;
 pub mod oauth2;
 pub mod pagination;
+pub mod role;
 pub mod session;
 pub mod url;
 pub mod user;
@@ -1,29 +1,37 @@
-use std::sync::LazyLock;
+use std::{sync::LazyLock, time::Duration};
 
 use academy_utils::patch::Patch;
 use chrono::{DateTime, Utc};
 use nutype::nutype;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     hyphenated_code_regex,
     macros::{id, nutype_string, sensitive_debug, sha256hash},
-    user::UserId,
+    user::{UserDisplayName, UserId, UserName},
 };
 
 id!(TotpDeviceId);
 
-#[derive(Debug, Clone, PartialEq, Eq, Patch)]
+#[derive(Debug, Clone, PartialEq, Eq, Patch, Serialize, Deserialize)]
 pub struct TotpDevice {
     #[no_patch]
     pub id: TotpDeviceId,
     #[no_patch]
     pub user_id: UserId,
+    pub name: TotpDeviceName,
     pub enabled: bool,
     #[no_patch]
     pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
 }
 
+nutype_string!(TotpDeviceName(validate(
+    len_char_min = 1,
+    len_char_max = 64
+)));
+
 nutype_string!(TotpCode(validate(regex = TOTP_CODE_REGEX)));
 pub static TOTP_CODE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new("^[0-9]{6}$").unwrap());
 
@@ -76,4 +84,86 @@ sha256hash!(MfaRecoveryCodeHash);
 pub struct MfaAuthentication {
     pub totp_code: Option<TotpCode>,
     pub recovery_code: Option<MfaRecoveryCode>,
+    pub webauthn: Option<WebauthnAssertion>,
+}
+
+id!(WebauthnCredentialId);
+
+#[derive(Debug, Clone, PartialEq, Eq, Patch, Serialize, Deserialize)]
+pub struct WebauthnCredential {
+    #[no_patch]
+    pub id: WebauthnCredentialId,
+    #[no_patch]
+    pub user_id: UserId,
+    /// The credential id chosen by the authenticator
+    #[no_patch]
+    pub raw_id: WebauthnCredentialRawId,
+    pub name: WebauthnCredentialName,
+    #[no_patch]
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+nutype_string!(WebauthnCredentialName(validate(
+    len_char_min = 1,
+    len_char_max = 64
+)));
+
+#[nutype(
+    validate(predicate = |x| !x.is_empty() && x.len() <= 1023),
+    derive(Debug, Clone, PartialEq, Eq, Hash, Deref, TryFrom, Serialize, Deserialize)
+)]
+pub struct WebauthnCredentialRawId(Vec<u8>);
+
+/// COSE encoded public key of a WebAuthn credential
+#[nutype(derive(Debug, Clone, PartialEq, Eq, Deref, From))]
+pub struct WebauthnPublicKey(Vec<u8>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebauthnCredentialKey {
+    pub public_key: WebauthnPublicKey,
+    pub sign_count: u32,
+}
+
+// base64url encoded random challenge
+nutype_string!(WebauthnChallenge);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebauthnRegistrationOptions {
+    pub challenge: WebauthnChallenge,
+    pub rp_id: String,
+    pub rp_name: String,
+    pub user_id: UserId,
+    pub user_name: UserName,
+    pub user_display_name: UserDisplayName,
+    pub exclude_credentials: Vec<WebauthnCredentialRawId>,
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebauthnAuthenticationOptions {
+    pub challenge: WebauthnChallenge,
+    pub rp_id: String,
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebauthnRegistrationResponse {
+    pub client_data_json: Vec<u8>,
+    pub attestation_object: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebauthnRegistration {
+    pub credential: WebauthnCredential,
+    /// The newly generated MFA recovery code if MFA has not been enabled before
+    pub recovery_code: Option<MfaRecoveryCode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebauthnAssertion {
+    pub raw_id: WebauthnCredentialRawId,
+    pub client_data_json: Vec<u8>,
+    pub authenticator_data: Vec<u8>,
+    pub signature: Vec<u8>,
 }
@@ -2,9 +2,10 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    email_address::EmailAddress,
     macros::{id, nutype_string},
     url::Url,
-    user::UserId,
+    user::{UserDisplayName, UserId},
 };
 
 id!(OAuth2LinkId);
@@ -14,22 +15,41 @@ pub struct OAuth2Provider {
     pub name: OAuth2ProviderName,
     pub client_id: String,
     pub client_secret: Option<OAuth2ProviderClientSecret>,
-    pub auth_url: Url,
-    pub token_url: Url,
-    pub userinfo_url: Url,
+    pub endpoints: OAuth2ProviderEndpoints,
     pub userinfo_id_key: String,
     pub userinfo_name_key: String,
+    pub userinfo_email_key: Option<String>,
+    pub userinfo_email_verified_key: Option<String>,
+    pub userinfo_display_name_key: Option<String>,
+    pub userinfo_avatar_url_key: Option<String>,
     pub scopes: Vec<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OAuth2ProviderEndpoints {
+    /// Plain OAuth2 provider with explicitly configured endpoints
+    OAuth2(Box<OAuth2Endpoints>),
+    /// OpenID Connect provider whose endpoints are discovered via
+    /// `{issuer_url}/.well-known/openid-configuration`
+    OpenIdConnect { issuer_url: Url },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuth2Endpoints {
+    pub auth_url: Url,
+    pub token_url: Url,
+    pub userinfo_url: Url,
+    /// Token revocation endpoint as specified in RFC 7009
+    pub revocation_url: Option<Url>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OAuth2ProviderSummary {
     pub id: OAuth2ProviderId,
     pub name: OAuth2ProviderName,
-    pub auth_url: Url,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OAuth2Link {
     pub id: OAuth2LinkId,
     pub user_id: UserId,
@@ -44,11 +64,60 @@ pub struct OAuth2UserInfo {
     pub name: OAuth2RemoteUserName,
 }
 
+/// Optional profile data of the remote user which can be used to pre-fill the
+/// registration of a new local user account
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuth2RemoteProfile {
+    pub email: Option<EmailAddress>,
+    /// Whether the provider has verified that the email address belongs to
+    /// the remote user
+    pub email_verified: bool,
+    pub display_name: Option<UserDisplayName>,
+    pub avatar_url: Option<Url>,
+}
+
+/// Remote user returned by the OAuth2 provider after a successful login
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuth2RemoteLogin {
+    pub user_info: OAuth2UserInfo,
+    pub profile: OAuth2RemoteProfile,
+    pub tokens: OAuth2Tokens,
+}
+
+/// Tokens issued by an OAuth2 provider which can be used to access the
+/// provider's API on behalf of the remote user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuth2Tokens {
+    pub access_token: OAuth2AccessToken,
+    pub refresh_token: Option<OAuth2RefreshToken>,
+    /// Expiration time of the access token, if known
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Encrypted [`OAuth2Tokens`] of an OAuth2 link as stored in the database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuth2EncryptedTokens {
+    pub access_token: Vec<u8>,
+    pub refresh_token: Option<Vec<u8>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OAuth2Login {
     pub provider_id: OAuth2ProviderId,
     pub code: OAuth2AuthorizationCode,
     pub redirect_uri: Url,
+    pub state: OAuth2State,
+}
+
+/// Authorization request that has been started by the client, but has not yet
+/// been completed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuth2PendingAuthorization {
+    pub provider_id: OAuth2ProviderId,
+    pub redirect_uri: Url,
+    pub pkce_verifier: OAuth2PkceVerifier,
+    pub nonce: OAuth2Nonce,
 }
 
 nutype_string!(OAuth2ProviderId);
@@ -60,6 +129,30 @@ nutype_string!(OAuth2AuthorizationCode(
     validate(len_char_max = 256)
 ));
 
+nutype_string!(OAuth2State(
+    sensitive,
+    validate(
+        len_char_min = OAuth2State::LEN,
+        len_char_max = OAuth2State::LEN
+    )
+));
+impl OAuth2State {
+    pub const LEN: usize = 32;
+}
+
+nutype_string!(OAuth2PkceVerifier(sensitive));
+impl OAuth2PkceVerifier {
+    pub const LEN: usize = 64;
+}
+
+nutype_string!(OAuth2Nonce(sensitive));
+impl OAuth2Nonce {
+    pub const LEN: usize = 48;
+}
+
+nutype_string!(OAuth2AccessToken(sensitive));
+nutype_string!(OAuth2RefreshToken(sensitive));
+
 nutype_string!(OAuth2RemoteUserId(validate(len_char_max = 256)));
 nutype_string!(OAuth2RemoteUserName(validate(len_char_max = 256)));
 
@@ -78,4 +171,6 @@ impl OAuth2RegistrationToken {
 pub struct OAuth2Registration {
     pub provider_id: OAuth2ProviderId,
     pub remote_user: OAuth2UserInfo,
+    pub profile: OAuth2RemoteProfile,
+    pub tokens: OAuth2Tokens,
 }
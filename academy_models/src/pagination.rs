@@ -1,4 +1,15 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
 use nutype::nutype;
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Schema, SchemaObject},
+    JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct PaginationSlice {
@@ -26,6 +37,128 @@ impl Default for PaginationLimit {
     }
 }
 
+/// Selects a page of an ordered list, either by skipping a number of items or
+/// by continuing after a [`PaginationCursor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pagination {
+    Offset(PaginationSlice),
+    Cursor {
+        limit: PaginationLimit,
+        /// The cursor of the last item of the previous page (`None` for the
+        /// first page)
+        after: Option<PaginationCursor>,
+    },
+}
+
+impl Pagination {
+    pub fn limit(&self) -> PaginationLimit {
+        match *self {
+            Self::Offset(PaginationSlice { limit, .. }) | Self::Cursor { limit, .. } => limit,
+        }
+    }
+
+    /// Return the cursor of the next page given the items of the current page,
+    /// or `None` if the current page is the last one.
+    pub fn next_cursor<'a, T: 'a>(
+        &self,
+        items: impl IntoIterator<Item = &'a T>,
+    ) -> Option<PaginationCursor>
+    where
+        &'a T: Into<PaginationCursor>,
+    {
+        let mut len = 0;
+        let last = items.into_iter().inspect(|_| len += 1).last()?;
+        (len >= *self.limit()).then(|| last.into())
+    }
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self::Offset(Default::default())
+    }
+}
+
+impl From<PaginationSlice> for Pagination {
+    fn from(value: PaginationSlice) -> Self {
+        Self::Offset(value)
+    }
+}
+
+/// Opaque reference to an item of a paginated list.
+///
+/// Lists are ordered by creation time unless specified otherwise. Ties between
+/// items are broken by their ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PaginationCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl fmt::Display for PaginationCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut bytes = [0; 24];
+        bytes[..8].copy_from_slice(&self.created_at.timestamp_micros().to_be_bytes());
+        bytes[8..].copy_from_slice(self.id.as_bytes());
+        f.write_str(&hex::encode(bytes))
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Invalid pagination cursor")]
+pub struct InvalidPaginationCursorError;
+
+impl FromStr for PaginationCursor {
+    type Err = InvalidPaginationCursorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bytes = [0; 24];
+        hex::decode_to_slice(s, &mut bytes).map_err(|_| InvalidPaginationCursorError)?;
+        let (created_at, id) = bytes.split_at(8);
+        let created_at = i64::from_be_bytes(created_at.try_into().unwrap());
+        Ok(Self {
+            created_at: DateTime::from_timestamp_micros(created_at)
+                .ok_or(InvalidPaginationCursorError)?,
+            id: Uuid::from_slice(id).unwrap(),
+        })
+    }
+}
+
+impl Serialize for PaginationCursor {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PaginationCursor {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl JsonSchema for PaginationCursor {
+    fn schema_name() -> String {
+        "PaginationCursor".into()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -34,4 +167,51 @@ mod tests {
     fn pagination_limit_default() {
         PaginationLimit::default();
     }
+
+    #[test]
+    fn pagination_cursor_roundtrip() {
+        let cursor = PaginationCursor {
+            created_at: DateTime::from_timestamp_micros(1710423462123456).unwrap(),
+            id: Uuid::from_u128(0xa8d95e0f71ae4c49995e695b7c93848c),
+        };
+
+        let encoded = cursor.to_string();
+        assert_eq!(encoded, "0006139efee017c0a8d95e0f71ae4c49995e695b7c93848c");
+        assert_eq!(encoded.parse::<PaginationCursor>().unwrap(), cursor);
+    }
+
+    #[test]
+    fn pagination_cursor_invalid() {
+        for s in ["", "foo", "0006139efee017c0a8d95e0f71ae4c49995e695b7c93848"] {
+            s.parse::<PaginationCursor>().unwrap_err();
+        }
+    }
+
+    #[test]
+    fn next_cursor() {
+        struct Item(u128);
+        impl From<&Item> for PaginationCursor {
+            fn from(value: &Item) -> Self {
+                Self {
+                    created_at: DateTime::UNIX_EPOCH,
+                    id: Uuid::from_u128(value.0),
+                }
+            }
+        }
+
+        let pagination = Pagination::Cursor {
+            limit: 2.try_into().unwrap(),
+            after: None,
+        };
+
+        assert_eq!(pagination.next_cursor::<Item>(&[]), None);
+        assert_eq!(pagination.next_cursor(&[Item(1)]), None);
+        assert_eq!(
+            pagination.next_cursor(&[Item(1), Item(2)]),
+            Some(PaginationCursor {
+                created_at: DateTime::UNIX_EPOCH,
+                id: Uuid::from_u128(2)
+            })
+        );
+    }
 }
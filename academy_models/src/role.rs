@@ -0,0 +1,185 @@
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Role granting a fixed set of [`Permission`]s to a user
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Has all permissions
+    Superuser,
+    /// Can read user accounts and resend verification emails
+    Support,
+    /// Can read user accounts and edit user profiles
+    Moderator,
+    /// Can read user accounts and edit invoice information
+    Billing,
+}
+
+impl Role {
+    pub const ALL: [Self; 4] = [
+        Self::Superuser,
+        Self::Support,
+        Self::Moderator,
+        Self::Billing,
+    ];
+
+    /// Return the permissions granted by this role.
+    pub fn permissions(self) -> PermissionSet {
+        use Permission::*;
+        match self {
+            Self::Superuser => PermissionSet::all(),
+            Self::Support => [UserRead, UserVerificationEmailSend].into_iter().collect(),
+            Self::Moderator => [UserRead, UserProfileWrite].into_iter().collect(),
+            Self::Billing => [UserRead, UserInvoiceInfoWrite].into_iter().collect(),
+        }
+    }
+}
+
+/// Permission to perform a privileged action (usually on accounts of other
+/// users)
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    /// Read user accounts including their sessions, linked OAuth2 accounts and
+    /// MFA devices
+    UserRead,
+    /// Update and delete user accounts
+    UserWrite,
+    /// Update user profiles and avatars
+    UserProfileWrite,
+    /// Update invoice information of users
+    UserInvoiceInfoWrite,
+    /// Send verification emails to users
+    UserVerificationEmailSend,
+    /// Create sessions to impersonate users
+    UserImpersonate,
+    /// Read the audit log
+    AuditLogRead,
+    /// Assign roles to users
+    RoleAssign,
+}
+
+impl Permission {
+    pub const ALL: [Self; 8] = [
+        Self::UserRead,
+        Self::UserWrite,
+        Self::UserProfileWrite,
+        Self::UserInvoiceInfoWrite,
+        Self::UserVerificationEmailSend,
+        Self::UserImpersonate,
+        Self::AuditLogRead,
+        Self::RoleAssign,
+    ];
+
+    fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// Set of [`Permission`]s
+///
+/// Serialized as a list of permissions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PermissionSet(u32);
+
+impl PermissionSet {
+    /// Return the set of all permissions.
+    pub fn all() -> Self {
+        Permission::ALL.into_iter().collect()
+    }
+
+    pub fn contains(self, permission: Permission) -> bool {
+        self.0 & permission.bit() != 0
+    }
+
+    pub fn insert(&mut self, permission: Permission) {
+        self.0 |= permission.bit();
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = Permission> {
+        Permission::ALL
+            .into_iter()
+            .filter(move |&permission| self.contains(permission))
+    }
+}
+
+impl FromIterator<Permission> for PermissionSet {
+    fn from_iter<T: IntoIterator<Item = Permission>>(iter: T) -> Self {
+        let mut out = Self::default();
+        for permission in iter {
+            out.insert(permission);
+        }
+        out
+    }
+}
+
+impl FromIterator<PermissionSet> for PermissionSet {
+    fn from_iter<T: IntoIterator<Item = PermissionSet>>(iter: T) -> Self {
+        Self(iter.into_iter().fold(0, |acc, x| acc | x.0))
+    }
+}
+
+impl Serialize for PermissionSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for PermissionSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<Permission>::deserialize(deserializer).map(|x| x.into_iter().collect())
+    }
+}
+
+impl JsonSchema for PermissionSet {
+    fn schema_name() -> String {
+        "PermissionSet".into()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        Vec::<Permission>::json_schema(gen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn superuser_has_all_permissions() {
+        // Act
+        let result = Role::Superuser.permissions();
+
+        // Assert
+        for permission in Permission::ALL {
+            assert!(result.contains(permission));
+        }
+    }
+
+    #[test]
+    fn permission_set_serde() {
+        // Arrange
+        let set = [Permission::UserRead, Permission::AuditLogRead]
+            .into_iter()
+            .collect::<PermissionSet>();
+
+        // Act
+        let serialized = serde_json::to_value(set).unwrap();
+        let deserialized = serde_json::from_value::<PermissionSet>(serialized.clone()).unwrap();
+
+        // Assert
+        assert_eq!(
+            serialized,
+            serde_json::json!(["user_read", "audit_log_read"])
+        );
+        assert_eq!(deserialized, set);
+    }
+}
@@ -1,25 +1,39 @@
 use academy_utils::patch::Patch;
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     macros::{id, nutype_string, sha256hash},
+    pagination::PaginationCursor,
     user::UserId,
 };
 
 id!(SessionId);
 
-#[derive(Debug, Clone, PartialEq, Eq, Patch)]
+#[derive(Debug, Clone, PartialEq, Eq, Patch, Serialize, Deserialize)]
 pub struct Session {
     #[no_patch]
     pub id: SessionId,
     #[no_patch]
     pub user_id: UserId,
     pub device_name: Option<DeviceName>,
+    /// The administrator who created this session to impersonate the user
+    #[no_patch]
+    pub impersonator_id: Option<UserId>,
     #[no_patch]
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl From<&Session> for PaginationCursor {
+    fn from(value: &Session) -> Self {
+        Self {
+            created_at: value.created_at,
+            id: *value.id,
+        }
+    }
+}
+
 nutype_string!(DeviceName(validate(len_char_max = DeviceName::MAX_LEN)));
 
 impl DeviceName {
@@ -1,4 +1,4 @@
-use std::sync::LazyLock;
+use std::{collections::BTreeSet, sync::LazyLock};
 
 use academy_utils::patch::Patch;
 use chrono::{DateTime, Utc};
@@ -8,8 +8,15 @@ use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    audit::AuditEvent,
     email_address::EmailAddress,
     macros::{id, nutype_string},
+    mfa::{TotpDevice, WebauthnCredential},
+    oauth2::{OAuth2Link, OAuth2ProviderId},
+    pagination::{PaginationCursor, SortDirection},
+    role::{PermissionSet, Role},
+    session::Session,
+    url::Url,
     SearchTerm,
 };
 
@@ -39,7 +46,7 @@ impl UserIdOrSelf {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UserComposite {
     pub user: User,
     pub profile: UserProfile,
@@ -47,7 +54,36 @@ pub struct UserComposite {
     pub invoice_info: UserInvoiceInfo,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Patch)]
+/// The publicly visible part of a user's data
+///
+/// Fields hidden by the user's [`UserProfileVisibility`] are set to `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserPublicProfile {
+    pub id: UserId,
+    pub display_name: UserDisplayName,
+    pub avatar: Option<UserAvatar>,
+    pub bio: Option<UserBio>,
+    pub tags: Option<UserTags>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub last_login: Option<DateTime<Utc>>,
+}
+
+impl From<UserComposite> for UserPublicProfile {
+    fn from(UserComposite { user, profile, .. }: UserComposite) -> Self {
+        let visibility = profile.visibility;
+        Self {
+            id: user.id,
+            display_name: profile.display_name,
+            avatar: profile.avatar,
+            bio: visibility.bio.then_some(profile.bio),
+            tags: visibility.tags.then_some(profile.tags),
+            created_at: visibility.created_at.then_some(user.created_at),
+            last_login: user.last_login.filter(|_| visibility.last_login),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Patch, Serialize, Deserialize)]
 pub struct User {
     #[no_patch]
     pub id: UserId,
@@ -59,25 +95,110 @@ pub struct User {
     pub last_login: Option<DateTime<Utc>>,
     pub last_name_change: Option<DateTime<Utc>>,
     pub enabled: bool,
-    pub admin: bool,
+    pub roles: BTreeSet<Role>,
     pub newsletter: bool,
+    /// Time at which the deletion of the account has been requested. Pending
+    /// deletions can be cancelled until the configured grace period expires.
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Patch)]
+#[derive(Debug, Clone, PartialEq, Eq, Patch, Serialize, Deserialize)]
 pub struct UserProfile {
     pub display_name: UserDisplayName,
     pub bio: UserBio,
     pub tags: UserTags,
+    #[serde(default)]
+    pub visibility: UserProfileVisibility,
+    #[no_patch]
+    #[serde(default)]
+    pub avatar: Option<UserAvatar>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Which fields of a user's profile are visible to other users (the display
+/// name and the avatar are always public)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserProfileVisibility {
+    pub bio: bool,
+    pub tags: bool,
+    pub created_at: bool,
+    pub last_login: bool,
+}
+
+impl Default for UserProfileVisibility {
+    fn default() -> Self {
+        Self {
+            bio: true,
+            tags: true,
+            created_at: true,
+            last_login: false,
+        }
+    }
+}
+
+id!(UserAvatarId);
+
+/// An uploaded profile picture, stored as a square WebP image in each of the
+/// [`UserAvatarSize`]s
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserAvatar {
+    pub id: UserAvatarId,
+    /// Public URL of the storage location that contains the images (see
+    /// [`UserAvatar::storage_prefix`])
+    pub url: Url,
+}
+
+impl UserAvatar {
+    /// Return the storage key prefix shared by all images of the given avatar.
+    pub fn storage_prefix(id: UserAvatarId) -> String {
+        format!("avatars/{}/", id.hyphenated())
+    }
+
+    /// Return the storage key of the image of the given avatar and size.
+    pub fn storage_key(id: UserAvatarId, size: UserAvatarSize) -> String {
+        format!("{}{}", Self::storage_prefix(id), size.file_name())
+    }
+
+    /// Return the public URL of the image with the given size.
+    pub fn url(&self, size: UserAvatarSize) -> Url {
+        self.url
+            .join(&size.file_name())
+            .expect("file names are valid relative URLs")
+            .into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UserAvatarSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl UserAvatarSize {
+    pub const ALL: [Self; 3] = [Self::Small, Self::Medium, Self::Large];
+
+    /// Return the width and height of the image in pixels.
+    pub fn pixels(self) -> u32 {
+        match self {
+            Self::Small => 64,
+            Self::Medium => 256,
+            Self::Large => 512,
+        }
+    }
+
+    fn file_name(self) -> String {
+        format!("{}.webp", self.pixels())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UserDetails {
     pub mfa_enabled: bool,
     pub password_login: bool,
     pub oauth2_login: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Patch, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Patch, Default, Serialize, Deserialize)]
 pub struct UserInvoiceInfo {
     pub business: Option<bool>,
     pub first_name: Option<UserFirstName>,
@@ -89,6 +210,24 @@ pub struct UserInvoiceInfo {
     pub vat_id: Option<UserVatId>,
 }
 
+impl User {
+    /// Return whether the user is allowed to log in, i.e. the account is
+    /// enabled and not pending deletion.
+    pub fn can_login(&self) -> bool {
+        self.enabled && self.deleted_at.is_none()
+    }
+
+    /// Return the permissions granted by the user's roles.
+    pub fn permissions(&self) -> PermissionSet {
+        self.roles.iter().map(|role| role.permissions()).collect()
+    }
+
+    /// Return whether the user has the superuser role.
+    pub fn is_superuser(&self) -> bool {
+        self.roles.contains(&Role::Superuser)
+    }
+}
+
 impl UserComposite {
     pub fn can_receive_coins(&self) -> bool {
         self.user.email_verified
@@ -110,6 +249,15 @@ impl UserComposite {
     }
 }
 
+impl From<&UserComposite> for PaginationCursor {
+    fn from(value: &UserComposite) -> Self {
+        Self {
+            created_at: value.user.created_at,
+            id: *value.user.id,
+        }
+    }
+}
+
 nutype_string!(UserName(validate(regex = USER_NAME_REGEX)));
 nutype_string!(UserDisplayName(validate(
     len_char_min = 1,
@@ -124,6 +272,47 @@ impl UserPassword {
     pub const MAX_LENGTH: usize = 4096;
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UserPasswordCharacterClass {
+    /// Lowercase letters
+    Lowercase,
+    /// Uppercase letters
+    Uppercase,
+    /// Decimal digits
+    Digit,
+    /// Any character that is neither a letter nor a digit
+    Symbol,
+}
+
+impl UserPasswordCharacterClass {
+    pub const ALL: [Self; 4] = [Self::Lowercase, Self::Uppercase, Self::Digit, Self::Symbol];
+
+    pub fn matches(self, c: char) -> bool {
+        match self {
+            Self::Lowercase => c.is_lowercase(),
+            Self::Uppercase => c.is_uppercase(),
+            Self::Digit => c.is_ascii_digit(),
+            Self::Symbol => !c.is_alphanumeric(),
+        }
+    }
+}
+
+/// A rule of the password policy that a password does not satisfy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum UserPasswordPolicyViolation {
+    /// The password is shorter than `min_length` characters.
+    TooShort { min_length: usize },
+    /// The password does not contain any character of the given class.
+    MissingCharacterClass { class: UserPasswordCharacterClass },
+    /// The estimated strength `score` of the password (from 0 to 4) is lower
+    /// than `min_score`.
+    TooWeak { score: u8, min_score: u8 },
+    /// The password has appeared in a known data breach.
+    Breached,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum UserNameOrEmailAddress {
@@ -131,6 +320,15 @@ pub enum UserNameOrEmailAddress {
     Email(EmailAddress),
 }
 
+impl UserNameOrEmailAddress {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Name(name) => name,
+            Self::Email(email) => email.as_str(),
+        }
+    }
+}
+
 nutype_string!(UserBio(
     validate(len_char_max = 1024),
     derive(Default),
@@ -164,15 +362,54 @@ nutype_string!(UserCity(validate(len_char_max = 64)));
 nutype_string!(UserCountry(validate(len_char_max = 64)));
 nutype_string!(UserVatId(validate(len_char_max = 64)));
 
+/// All data stored about a user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserExport {
+    pub user_composite: UserComposite,
+    pub sessions: Vec<Session>,
+    pub totp_devices: Vec<TotpDevice>,
+    pub webauthn_credentials: Vec<WebauthnCredential>,
+    pub oauth2_links: Vec<OAuth2Link>,
+    pub audit_events: Vec<AuditEvent>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct UserFilter {
     pub name: Option<SearchTerm>,
     pub email: Option<SearchTerm>,
     pub enabled: Option<bool>,
-    pub admin: Option<bool>,
+    pub role: Option<Role>,
     pub mfa_enabled: Option<bool>,
     pub email_verified: Option<bool>,
     pub newsletter: Option<bool>,
+    pub password_login: Option<bool>,
+    pub oauth2_login: Option<bool>,
+    /// Only match users with a link to the given OAuth2 provider
+    pub oauth2_provider: Option<OAuth2ProviderId>,
+    /// Match users by [`UserComposite::can_receive_coins`]
+    pub can_receive_coins: Option<bool>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub last_login_after: Option<DateTime<Utc>>,
+    pub last_login_before: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct UserSort {
+    pub by: UserSortBy,
+    pub direction: SortDirection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UserSortBy {
+    #[default]
+    CreatedAt,
+    /// Sort by `name` (case-insensitive)
+    Name,
+    /// Sort by `last_login`, treating users who have never logged in as the
+    /// least recent ones
+    LastLogin,
 }
 
 #[cfg(test)]
@@ -0,0 +1,68 @@
+use std::future::Future;
+
+use academy_models::{
+    audit::{AuditEvent, AuditEventFilter},
+    pagination::PaginationSlice,
+};
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait AuditRepository<Txn: Send + Sync + 'static>: Send + Sync + 'static {
+    /// Return the number of audit events matching the given filter.
+    fn count(
+        &self,
+        txn: &mut Txn,
+        filter: &AuditEventFilter,
+    ) -> impl Future<Output = anyhow::Result<u64>> + Send;
+
+    /// Return all audit events matching the given filter and pagination
+    /// slice, ordered by creation time (newest first).
+    fn list(
+        &self,
+        txn: &mut Txn,
+        filter: &AuditEventFilter,
+        pagination: PaginationSlice,
+    ) -> impl Future<Output = anyhow::Result<Vec<AuditEvent>>> + Send;
+
+    /// Create a new audit event.
+    fn create(
+        &self,
+        txn: &mut Txn,
+        event: &AuditEvent,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+}
+
+#[cfg(feature = "mock")]
+impl<Txn: Send + Sync + 'static> MockAuditRepository<Txn> {
+    pub fn with_count(mut self, filter: AuditEventFilter, result: u64) -> Self {
+        self.expect_count()
+            .once()
+            .with(mockall::predicate::always(), mockall::predicate::eq(filter))
+            .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_list(
+        mut self,
+        filter: AuditEventFilter,
+        pagination: PaginationSlice,
+        result: Vec<AuditEvent>,
+    ) -> Self {
+        self.expect_list()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(filter),
+                mockall::predicate::eq(pagination),
+            )
+            .return_once(|_, _, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_create(mut self, event: AuditEvent) -> Self {
+        self.expect_create()
+            .once()
+            .with(mockall::predicate::always(), mockall::predicate::eq(event))
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
+}
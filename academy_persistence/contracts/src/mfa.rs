@@ -1,9 +1,14 @@
 use std::future::Future;
 
 use academy_models::{
-    mfa::{MfaRecoveryCodeHash, TotpDevice, TotpDeviceId, TotpDevicePatchRef, TotpSecret},
+    mfa::{
+        MfaRecoveryCodeHash, TotpDevice, TotpDeviceId, TotpDevicePatchRef, TotpSecret,
+        WebauthnCredential, WebauthnCredentialId, WebauthnCredentialKey,
+        WebauthnCredentialPatchRef, WebauthnCredentialRawId,
+    },
     user::UserId,
 };
+use chrono::{DateTime, Utc};
 
 #[cfg_attr(feature = "mock", mockall::automock)]
 pub trait MfaRepository<Txn: Send + Sync + 'static>: Send + Sync + 'static {
@@ -14,6 +19,13 @@ pub trait MfaRepository<Txn: Send + Sync + 'static>: Send + Sync + 'static {
         user_id: UserId,
     ) -> impl Future<Output = anyhow::Result<Vec<TotpDevice>>> + Send;
 
+    /// Return the TOTP device with the given id.
+    fn get_totp_device(
+        &self,
+        txn: &mut Txn,
+        totp_device_id: TotpDeviceId,
+    ) -> impl Future<Output = anyhow::Result<Option<TotpDevice>>> + Send;
+
     /// Create a new TOTP device and set the associated secret.
     fn create_totp_device(
         &self,
@@ -30,6 +42,13 @@ pub trait MfaRepository<Txn: Send + Sync + 'static>: Send + Sync + 'static {
         patch: TotpDevicePatchRef<'a>,
     ) -> impl Future<Output = anyhow::Result<bool>> + Send;
 
+    /// Delete the given TOTP device.
+    fn delete_totp_device(
+        &self,
+        txn: &mut Txn,
+        totp_device_id: TotpDeviceId,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
     /// Delete all TOTP devices of the given user.
     fn delete_totp_devices_by_user(
         &self,
@@ -37,12 +56,23 @@ pub trait MfaRepository<Txn: Send + Sync + 'static>: Send + Sync + 'static {
         user_id: UserId,
     ) -> impl Future<Output = anyhow::Result<()>> + Send;
 
-    /// Return the secrets of all enabled TOTP devices of the given user.
+    /// Delete all TOTP devices that are not enabled and have been created
+    /// before `created_at`.
+    ///
+    /// Returns the number of deleted TOTP devices.
+    fn delete_disabled_totp_devices_by_created_at(
+        &self,
+        txn: &mut Txn,
+        created_at: DateTime<Utc>,
+    ) -> impl Future<Output = anyhow::Result<u64>> + Send;
+
+    /// Return the ids and secrets of all enabled TOTP devices of the given
+    /// user.
     fn list_enabled_totp_device_secrets_by_user(
         &self,
         txn: &mut Txn,
         user_id: UserId,
-    ) -> impl Future<Output = anyhow::Result<Vec<TotpSecret>>> + Send;
+    ) -> impl Future<Output = anyhow::Result<Vec<(TotpDeviceId, TotpSecret)>>> + Send;
 
     /// Return the secret of the given TOTP device.
     fn get_totp_device_secret(
@@ -80,6 +110,59 @@ pub trait MfaRepository<Txn: Send + Sync + 'static>: Send + Sync + 'static {
         txn: &mut Txn,
         user_id: UserId,
     ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Return all WebAuthn credentials of the given user.
+    fn list_webauthn_credentials_by_user(
+        &self,
+        txn: &mut Txn,
+        user_id: UserId,
+    ) -> impl Future<Output = anyhow::Result<Vec<WebauthnCredential>>> + Send;
+
+    /// Return the WebAuthn credential with the given authenticator chosen
+    /// credential id together with its public key.
+    fn get_webauthn_credential_by_raw_id(
+        &self,
+        txn: &mut Txn,
+        raw_id: &WebauthnCredentialRawId,
+    ) -> impl Future<Output = anyhow::Result<Option<(WebauthnCredential, WebauthnCredentialKey)>>> + Send;
+
+    /// Create a new WebAuthn credential.
+    fn create_webauthn_credential(
+        &self,
+        txn: &mut Txn,
+        credential: &WebauthnCredential,
+        key: &WebauthnCredentialKey,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Update an existing WebAuthn credential.
+    fn update_webauthn_credential<'a>(
+        &self,
+        txn: &mut Txn,
+        credential_id: WebauthnCredentialId,
+        patch: WebauthnCredentialPatchRef<'a>,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    /// Update the signature counter of the given WebAuthn credential.
+    fn save_webauthn_credential_sign_count(
+        &self,
+        txn: &mut Txn,
+        credential_id: WebauthnCredentialId,
+        sign_count: u32,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Delete the given WebAuthn credential.
+    fn delete_webauthn_credential(
+        &self,
+        txn: &mut Txn,
+        credential_id: WebauthnCredentialId,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    /// Delete all WebAuthn credentials of the given user.
+    fn delete_webauthn_credentials_by_user(
+        &self,
+        txn: &mut Txn,
+        user_id: UserId,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
 }
 
 #[cfg(feature = "mock")]
@@ -99,6 +182,21 @@ impl<Txn: Send + Sync + 'static> MockMfaRepository<Txn> {
         self
     }
 
+    pub fn with_get_totp_device(
+        mut self,
+        totp_device_id: TotpDeviceId,
+        result: Option<TotpDevice>,
+    ) -> Self {
+        self.expect_get_totp_device()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(totp_device_id),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
     pub fn with_create_totp_device(mut self, totp_device: TotpDevice, secret: TotpSecret) -> Self {
         self.expect_create_totp_device()
             .once()
@@ -124,6 +222,17 @@ impl<Txn: Send + Sync + 'static> MockMfaRepository<Txn> {
         self
     }
 
+    pub fn with_delete_totp_device(mut self, totp_device_id: TotpDeviceId, result: bool) -> Self {
+        self.expect_delete_totp_device()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(totp_device_id),
+            )
+            .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
     pub fn with_delete_totp_devices_by_user(mut self, user_id: UserId) -> Self {
         self.expect_delete_totp_devices_by_user()
             .once()
@@ -138,7 +247,7 @@ impl<Txn: Send + Sync + 'static> MockMfaRepository<Txn> {
     pub fn with_list_enabled_totp_device_secrets_by_user(
         mut self,
         user_id: UserId,
-        secrets: Vec<TotpSecret>,
+        secrets: Vec<(TotpDeviceId, TotpSecret)>,
     ) -> Self {
         self.expect_list_enabled_totp_device_secrets_by_user()
             .once()
@@ -222,4 +331,102 @@ impl<Txn: Send + Sync + 'static> MockMfaRepository<Txn> {
             .return_once(|_, _| Box::pin(std::future::ready(Ok(()))));
         self
     }
+
+    pub fn with_list_webauthn_credentials_by_user(
+        mut self,
+        user_id: UserId,
+        result: Vec<WebauthnCredential>,
+    ) -> Self {
+        self.expect_list_webauthn_credentials_by_user()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(user_id),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_get_webauthn_credential_by_raw_id(
+        mut self,
+        raw_id: WebauthnCredentialRawId,
+        result: Option<(WebauthnCredential, WebauthnCredentialKey)>,
+    ) -> Self {
+        self.expect_get_webauthn_credential_by_raw_id()
+            .once()
+            .with(mockall::predicate::always(), mockall::predicate::eq(raw_id))
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_create_webauthn_credential(
+        mut self,
+        credential: WebauthnCredential,
+        key: WebauthnCredentialKey,
+    ) -> Self {
+        self.expect_create_webauthn_credential()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(credential),
+                mockall::predicate::eq(key),
+            )
+            .return_once(|_, _, _| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
+
+    pub fn with_update_webauthn_credential(
+        mut self,
+        credential_id: WebauthnCredentialId,
+        patch: academy_models::mfa::WebauthnCredentialPatch,
+        result: bool,
+    ) -> Self {
+        self.expect_update_webauthn_credential()
+            .once()
+            .withf(move |_, id, p| *id == credential_id && *p == patch.as_ref())
+            .return_once(move |_, _, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_save_webauthn_credential_sign_count(
+        mut self,
+        credential_id: WebauthnCredentialId,
+        sign_count: u32,
+    ) -> Self {
+        self.expect_save_webauthn_credential_sign_count()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(credential_id),
+                mockall::predicate::eq(sign_count),
+            )
+            .return_once(|_, _, _| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
+
+    pub fn with_delete_webauthn_credential(
+        mut self,
+        credential_id: WebauthnCredentialId,
+        result: bool,
+    ) -> Self {
+        self.expect_delete_webauthn_credential()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(credential_id),
+            )
+            .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_delete_webauthn_credentials_by_user(mut self, user_id: UserId) -> Self {
+        self.expect_delete_webauthn_credentials_by_user()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(user_id),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
 }
@@ -1,7 +1,9 @@
 use std::future::Future;
 
 use academy_models::{
-    oauth2::{OAuth2Link, OAuth2LinkId},
+    oauth2::{
+        OAuth2EncryptedTokens, OAuth2Link, OAuth2LinkId, OAuth2ProviderId, OAuth2RemoteUserId,
+    },
     user::UserId,
 };
 use thiserror::Error;
@@ -22,6 +24,14 @@ pub trait OAuth2Repository<Txn: Send + Sync + 'static>: Send + Sync + 'static {
         link_id: OAuth2LinkId,
     ) -> impl Future<Output = anyhow::Result<Option<OAuth2Link>>> + Send;
 
+    /// Return the OAuth2 link to the given remote user.
+    fn get_link_by_remote_user(
+        &self,
+        txn: &mut Txn,
+        provider_id: &OAuth2ProviderId,
+        remote_user_id: &OAuth2RemoteUserId,
+    ) -> impl Future<Output = anyhow::Result<Option<OAuth2Link>>> + Send;
+
     /// Create a new OAuth2 link.
     fn create_link(
         &self,
@@ -35,6 +45,25 @@ pub trait OAuth2Repository<Txn: Send + Sync + 'static>: Send + Sync + 'static {
         txn: &mut Txn,
         link_id: OAuth2LinkId,
     ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    /// Return the encrypted provider tokens of the given OAuth2 link.
+    ///
+    /// The tokens are locked until the end of the transaction, so concurrent
+    /// refreshes of the same tokens are serialized.
+    fn get_link_tokens(
+        &self,
+        txn: &mut Txn,
+        link_id: OAuth2LinkId,
+    ) -> impl Future<Output = anyhow::Result<Option<OAuth2EncryptedTokens>>> + Send;
+
+    /// Create or replace the encrypted provider tokens of the given OAuth2
+    /// link.
+    fn save_link_tokens(
+        &self,
+        txn: &mut Txn,
+        link_id: OAuth2LinkId,
+        tokens: &OAuth2EncryptedTokens,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
 }
 
 #[derive(Debug, Error)]
@@ -69,6 +98,23 @@ impl<Txn: Send + Sync + 'static> MockOAuth2Repository<Txn> {
         self
     }
 
+    pub fn with_get_link_by_remote_user(
+        mut self,
+        provider_id: OAuth2ProviderId,
+        remote_user_id: OAuth2RemoteUserId,
+        result: Option<OAuth2Link>,
+    ) -> Self {
+        self.expect_get_link_by_remote_user()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(provider_id),
+                mockall::predicate::eq(remote_user_id),
+            )
+            .return_once(|_, _, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
     pub fn with_create(mut self, link: OAuth2Link, result: Result<(), OAuth2RepoError>) -> Self {
         self.expect_create_link()
             .once()
@@ -87,4 +133,35 @@ impl<Txn: Send + Sync + 'static> MockOAuth2Repository<Txn> {
             .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
         self
     }
+
+    pub fn with_get_link_tokens(
+        mut self,
+        link_id: OAuth2LinkId,
+        result: Option<OAuth2EncryptedTokens>,
+    ) -> Self {
+        self.expect_get_link_tokens()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(link_id),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_save_link_tokens(
+        mut self,
+        link_id: OAuth2LinkId,
+        tokens: OAuth2EncryptedTokens,
+    ) -> Self {
+        self.expect_save_link_tokens()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(link_id),
+                mockall::predicate::eq(tokens),
+            )
+            .return_once(|_, _, _| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
 }
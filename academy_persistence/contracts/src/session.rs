@@ -1,6 +1,7 @@
 use std::future::Future;
 
 use academy_models::{
+    pagination::Pagination,
     session::{Session, SessionId, SessionPatchRef, SessionRefreshTokenHash},
     user::UserId,
 };
@@ -22,6 +23,13 @@ pub trait SessionRepository<Txn: Send + Sync + 'static>: Send + Sync + 'static {
         refresh_token_hash: SessionRefreshTokenHash,
     ) -> impl Future<Output = anyhow::Result<Option<Session>>> + Send;
 
+    /// Return the session with the given retired refresh token hash.
+    fn get_by_retired_refresh_token_hash(
+        &self,
+        txn: &mut Txn,
+        refresh_token_hash: SessionRefreshTokenHash,
+    ) -> impl Future<Output = anyhow::Result<Option<Session>>> + Send;
+
     /// Return all sessions of a given user.
     fn list_by_user(
         &self,
@@ -29,6 +37,15 @@ pub trait SessionRepository<Txn: Send + Sync + 'static>: Send + Sync + 'static {
         user_id: UserId,
     ) -> impl Future<Output = anyhow::Result<Vec<Session>>> + Send;
 
+    /// Return the sessions of a given user matching the given pagination,
+    /// ordered by their creation time.
+    fn list_by_user_paginated(
+        &self,
+        txn: &mut Txn,
+        user_id: UserId,
+        pagination: Pagination,
+    ) -> impl Future<Output = anyhow::Result<Vec<Session>>> + Send;
+
     /// Create a new session.
     fn create(
         &self,
@@ -88,6 +105,20 @@ pub trait SessionRepository<Txn: Send + Sync + 'static>: Send + Sync + 'static {
         session_id: SessionId,
         refresh_token_hash: SessionRefreshTokenHash,
     ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Remember a refresh token hash of a given session that has been replaced
+    /// by a new one.
+    ///
+    /// Only the `keep` most recently retired refresh token hashes of the
+    /// session are kept, older ones are deleted.
+    fn retire_refresh_token_hash(
+        &self,
+        txn: &mut Txn,
+        session_id: SessionId,
+        refresh_token_hash: SessionRefreshTokenHash,
+        retired_at: DateTime<Utc>,
+        keep: usize,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
 }
 
 #[cfg(feature = "mock")]
@@ -118,6 +149,21 @@ impl<Txn: Send + Sync + 'static> MockSessionRepository<Txn> {
         self
     }
 
+    pub fn with_get_by_retired_refresh_token_hash(
+        mut self,
+        refresh_token_hash: SessionRefreshTokenHash,
+        result: Option<Session>,
+    ) -> Self {
+        self.expect_get_by_retired_refresh_token_hash()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(refresh_token_hash),
+            )
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
     pub fn with_list_by_user(mut self, user_id: UserId, result: Vec<Session>) -> Self {
         self.expect_list_by_user()
             .once()
@@ -129,6 +175,23 @@ impl<Txn: Send + Sync + 'static> MockSessionRepository<Txn> {
         self
     }
 
+    pub fn with_list_by_user_paginated(
+        mut self,
+        user_id: UserId,
+        pagination: Pagination,
+        result: Vec<Session>,
+    ) -> Self {
+        self.expect_list_by_user_paginated()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(user_id),
+                mockall::predicate::eq(pagination),
+            )
+            .return_once(|_, _, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
     pub fn with_create(mut self, session: Session) -> Self {
         self.expect_create()
             .once()
@@ -220,4 +283,24 @@ impl<Txn: Send + Sync + 'static> MockSessionRepository<Txn> {
             .return_once(|_, _, _| Box::pin(std::future::ready(Ok(()))));
         self
     }
+
+    pub fn with_retire_refresh_token_hash(
+        mut self,
+        session_id: SessionId,
+        refresh_token_hash: SessionRefreshTokenHash,
+        retired_at: DateTime<Utc>,
+        keep: usize,
+    ) -> Self {
+        self.expect_retire_refresh_token_hash()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(session_id),
+                mockall::predicate::eq(refresh_token_hash),
+                mockall::predicate::eq(retired_at),
+                mockall::predicate::eq(keep),
+            )
+            .return_once(|_, _, _, _, _| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
 }
@@ -3,12 +3,15 @@ use std::future::Future;
 use academy_models::{
     email_address::EmailAddress,
     oauth2::{OAuth2ProviderId, OAuth2RemoteUserId},
-    pagination::PaginationSlice,
+    pagination::{Pagination, PaginationSlice},
     user::{
-        User, UserComposite, UserFilter, UserId, UserInvoiceInfo, UserInvoiceInfoPatchRef,
-        UserName, UserNameOrEmailAddress, UserPatchRef, UserProfile, UserProfilePatchRef,
+        User, UserAvatar, UserComposite, UserFilter, UserId, UserInvoiceInfo,
+        UserInvoiceInfoPatchRef, UserName, UserNameOrEmailAddress, UserPatchRef, UserProfile,
+        UserProfilePatchRef, UserSort,
     },
+    SearchTerm,
 };
+use chrono::{DateTime, Utc};
 use thiserror::Error;
 
 #[cfg_attr(feature = "mock", mockall::automock)]
@@ -20,12 +23,36 @@ pub trait UserRepository<Txn: Send + Sync + 'static>: Send + Sync + 'static {
         filter: &UserFilter,
     ) -> impl Future<Output = anyhow::Result<u64>> + Send;
 
-    /// Return all user composites matching the given filter and pagination
-    /// slice.
+    /// Return all user composites matching the given filter and pagination,
+    /// in the given order.
+    ///
+    /// Returns `None` if the user referenced by the pagination cursor no
+    /// longer exists and is required to continue the list in the given order.
     fn list_composites(
         &self,
         txn: &mut Txn,
         filter: &UserFilter,
+        sort: UserSort,
+        pagination: Pagination,
+    ) -> impl Future<Output = anyhow::Result<Option<Vec<UserComposite>>>> + Send;
+
+    /// Return the number of enabled and non-deleted users whose profile matches
+    /// the given full-text search query.
+    fn count_search_profiles(
+        &self,
+        txn: &mut Txn,
+        query: &SearchTerm,
+    ) -> impl Future<Output = anyhow::Result<u64>> + Send;
+
+    /// Return the user composites of all enabled and non-deleted users whose
+    /// profile matches the given full-text search query, ordered by relevance.
+    ///
+    /// Matches in the `display_name` are ranked higher than matches in the
+    /// `tags`, which are ranked higher than matches in the `bio`.
+    fn search_profiles(
+        &self,
+        txn: &mut Txn,
+        query: &SearchTerm,
         pagination: PaginationSlice,
     ) -> impl Future<Output = anyhow::Result<Vec<UserComposite>>> + Send;
 
@@ -109,6 +136,14 @@ pub trait UserRepository<Txn: Send + Sync + 'static>: Send + Sync + 'static {
         patch: UserProfilePatchRef<'a>,
     ) -> impl Future<Output = anyhow::Result<bool>> + Send;
 
+    /// Set or remove the avatar of an existing user.
+    fn update_avatar(
+        &self,
+        txn: &mut Txn,
+        user_id: UserId,
+        avatar: &Option<UserAvatar>,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
     /// Update the invoice info of an existing user.
     fn update_invoice_info<'a>(
         &self,
@@ -124,6 +159,42 @@ pub trait UserRepository<Txn: Send + Sync + 'static>: Send + Sync + 'static {
         user_id: UserId,
     ) -> impl Future<Output = anyhow::Result<bool>> + Send;
 
+    /// Return all non-admin users with an unverified email address that have
+    /// been created before `created_at`, have never verified any email
+    /// address and have not logged in since their registration.
+    fn list_unverified_composites(
+        &self,
+        txn: &mut Txn,
+        created_at: DateTime<Utc>,
+    ) -> impl Future<Output = anyhow::Result<Vec<UserComposite>>> + Send;
+
+    /// Delete all users matched by
+    /// [`UserRepository::list_unverified_composites`].
+    ///
+    /// Returns the number of deleted users.
+    fn delete_unverified(
+        &self,
+        txn: &mut Txn,
+        created_at: DateTime<Utc>,
+    ) -> impl Future<Output = anyhow::Result<u64>> + Send;
+
+    /// Return all users whose deletion has been requested before `deleted_at`.
+    fn list_deleted_composites(
+        &self,
+        txn: &mut Txn,
+        deleted_at: DateTime<Utc>,
+    ) -> impl Future<Output = anyhow::Result<Vec<UserComposite>>> + Send;
+
+    /// Permanently delete all users whose deletion has been requested before
+    /// `deleted_at`.
+    ///
+    /// Returns the number of deleted users.
+    fn delete_by_deleted_at(
+        &self,
+        txn: &mut Txn,
+        deleted_at: DateTime<Utc>,
+    ) -> impl Future<Output = anyhow::Result<u64>> + Send;
+
     /// Save or update the password hash for a given user.
     fn save_password_hash(
         &self,
@@ -145,6 +216,34 @@ pub trait UserRepository<Txn: Send + Sync + 'static>: Send + Sync + 'static {
         txn: &mut Txn,
         user_id: UserId,
     ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+
+    /// Return the number of stored password hashes for each set of hash
+    /// parameters, i.e. the PHC string prefix without salt and hash (e.g.
+    /// `$argon2id$v=19$m=19456,t=2,p=1`).
+    fn count_password_hashes_by_parameters(
+        &self,
+        txn: &mut Txn,
+    ) -> impl Future<Output = anyhow::Result<Vec<(String, u64)>>> + Send;
+
+    /// Queue a data export for a given user.
+    ///
+    /// Does nothing if an export has already been requested for this user.
+    fn create_export_request(
+        &self,
+        txn: &mut Txn,
+        user_id: UserId,
+        requested_at: DateTime<Utc>,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Remove the oldest queued data export request and return the id of the
+    /// corresponding user.
+    ///
+    /// Requests which are currently being processed by another transaction are
+    /// skipped.
+    fn take_export_request(
+        &self,
+        txn: &mut Txn,
+    ) -> impl Future<Output = anyhow::Result<Option<UserId>>> + Send;
 }
 
 #[derive(Debug, Error)]
@@ -170,14 +269,41 @@ impl<Txn: Send + Sync + 'static> MockUserRepository<Txn> {
     pub fn with_list_composites(
         mut self,
         filter: UserFilter,
-        pagination: PaginationSlice,
-        result: Vec<UserComposite>,
+        sort: UserSort,
+        pagination: Pagination,
+        result: Option<Vec<UserComposite>>,
     ) -> Self {
         self.expect_list_composites()
             .once()
             .with(
                 mockall::predicate::always(),
                 mockall::predicate::eq(filter),
+                mockall::predicate::eq(sort),
+                mockall::predicate::eq(pagination),
+            )
+            .return_once(|_, _, _, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_count_search_profiles(mut self, query: SearchTerm, result: u64) -> Self {
+        self.expect_count_search_profiles()
+            .once()
+            .with(mockall::predicate::always(), mockall::predicate::eq(query))
+            .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_search_profiles(
+        mut self,
+        query: SearchTerm,
+        pagination: PaginationSlice,
+        result: Vec<UserComposite>,
+    ) -> Self {
+        self.expect_search_profiles()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(query),
                 mockall::predicate::eq(pagination),
             )
             .return_once(|_, _, _| Box::pin(std::future::ready(Ok(result))));
@@ -307,6 +433,19 @@ impl<Txn: Send + Sync + 'static> MockUserRepository<Txn> {
         self
     }
 
+    pub fn with_update_avatar(
+        mut self,
+        user_id: UserId,
+        avatar: Option<UserAvatar>,
+        result: bool,
+    ) -> Self {
+        self.expect_update_avatar()
+            .once()
+            .withf(move |_, id, a| *id == user_id && *a == avatar)
+            .return_once(move |_, _, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
     pub fn with_update_invoice_info(
         mut self,
         user_id: UserId,
@@ -368,4 +507,20 @@ impl<Txn: Send + Sync + 'static> MockUserRepository<Txn> {
             .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
         self
     }
+
+    pub fn with_create_export_request(
+        mut self,
+        user_id: UserId,
+        requested_at: DateTime<Utc>,
+    ) -> Self {
+        self.expect_create_export_request()
+            .once()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(user_id),
+                mockall::predicate::eq(requested_at),
+            )
+            .return_once(|_, _, _| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
 }
@@ -0,0 +1,25 @@
+create table webauthn_credentials (
+    id uuid primary key,
+    user_id uuid not null references users(id) on delete cascade,
+    raw_id bytea unique not null,
+    name text not null,
+    public_key bytea not null,
+    sign_count bigint not null,
+    created_at timestamp with time zone not null,
+    last_used_at timestamp with time zone
+);
+
+create index webauthn_credentials_user_id_idx on webauthn_credentials (user_id);
+
+drop view if exists user_details;
+create view user_details as (
+    select
+        u.id as user_id,
+        (
+            exists (select td.id from totp_devices td where td.user_id=u.id and td.enabled)
+            or exists (select wc.id from webauthn_credentials wc where wc.user_id=u.id)
+        ) as mfa_enabled,
+        (exists (select up.user_id from user_passwords up where up.user_id=u.id)) as password_login,
+        (exists (select ol.user_id from oauth2_links ol where ol.user_id=u.id)) as oauth2_login
+    from users u
+);
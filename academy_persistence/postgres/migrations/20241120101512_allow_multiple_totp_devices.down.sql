@@ -0,0 +1,10 @@
+-- keep only the oldest device of each user
+delete from totp_devices td where exists (
+    select 1 from totp_devices o where o.user_id=td.user_id and (o.created_at, o.id) < (td.created_at, td.id)
+);
+
+alter table totp_devices drop column last_used_at;
+alter table totp_devices drop column name;
+
+drop index totp_devices_user_id_idx;
+alter table totp_devices add constraint totp_devices_user_id_key unique (user_id);
@@ -0,0 +1,6 @@
+alter table totp_devices drop constraint totp_devices_user_id_key;
+create index totp_devices_user_id_idx on totp_devices (user_id);
+
+alter table totp_devices add column name text not null default 'Authenticator';
+alter table totp_devices alter column name drop default;
+alter table totp_devices add column last_used_at timestamp with time zone;
@@ -0,0 +1,13 @@
+create table audit_events (
+    id uuid primary key,
+    actor_id uuid references users(id) on delete set null,
+    user_id uuid not null references users(id) on delete cascade,
+    kind text not null,
+    ip_address inet,
+    user_agent text,
+    created_at timestamp with time zone not null
+);
+
+create index audit_events_actor_id_idx on audit_events (actor_id);
+create index audit_events_user_id_idx on audit_events (user_id);
+create index audit_events_created_at_idx on audit_events (created_at);
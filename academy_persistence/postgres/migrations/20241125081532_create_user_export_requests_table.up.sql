@@ -0,0 +1,6 @@
+create table user_export_requests (
+    user_id uuid primary key references users(id) on delete cascade,
+    requested_at timestamp with time zone not null
+);
+
+create index user_export_requests_requested_at_idx on user_export_requests (requested_at);
@@ -0,0 +1,2 @@
+drop index sessions_user_id_created_at_id_idx;
+drop index users_created_at_id_idx;
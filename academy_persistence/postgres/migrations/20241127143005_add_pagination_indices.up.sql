@@ -0,0 +1,2 @@
+create index users_created_at_id_idx on users (created_at, id);
+create index sessions_user_id_created_at_id_idx on sessions (user_id, created_at, id);
@@ -0,0 +1,2 @@
+ALTER TABLE user_profiles DROP COLUMN search_vector;
+DROP FUNCTION user_profile_search_vector;
@@ -0,0 +1,11 @@
+CREATE FUNCTION user_profile_search_vector(display_name TEXT, bio TEXT, tags TEXT[]) RETURNS TSVECTOR
+    LANGUAGE SQL IMMUTABLE PARALLEL SAFE
+    AS $$
+        SELECT setweight(to_tsvector('simple', display_name), 'A')
+            || setweight(to_tsvector('simple', array_to_string(tags, ' ')), 'B')
+            || setweight(to_tsvector('simple', bio), 'C')
+    $$;
+
+ALTER TABLE user_profiles ADD COLUMN search_vector TSVECTOR NOT NULL
+    GENERATED ALWAYS AS (user_profile_search_vector(display_name, bio, tags)) STORED;
+CREATE INDEX user_profiles_search_vector_idx ON user_profiles USING GIN (search_vector);
@@ -0,0 +1,9 @@
+CREATE TABLE user_profile_visibility (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    bio BOOLEAN NOT NULL,
+    tags BOOLEAN NOT NULL,
+    created_at BOOLEAN NOT NULL,
+    last_login BOOLEAN NOT NULL
+);
+INSERT INTO user_profile_visibility (user_id, bio, tags, created_at, last_login)
+    SELECT id, true, true, true, false FROM users;
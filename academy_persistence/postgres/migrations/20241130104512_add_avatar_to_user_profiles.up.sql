@@ -0,0 +1,3 @@
+alter table user_profiles add column avatar_id uuid;
+alter table user_profiles add column avatar_url text;
+alter table user_profiles add constraint user_profiles_avatar_check check ((avatar_id is null) = (avatar_url is null));
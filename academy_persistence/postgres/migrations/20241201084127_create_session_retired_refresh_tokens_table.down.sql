@@ -0,0 +1 @@
+drop table session_retired_refresh_tokens;
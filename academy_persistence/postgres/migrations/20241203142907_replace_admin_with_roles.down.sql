@@ -0,0 +1,3 @@
+alter table users add column admin boolean not null default false;
+update users set admin=true where 'superuser'=any(roles);
+alter table users drop column roles;
@@ -0,0 +1,3 @@
+alter table users add column roles text[] not null default '{}';
+update users set roles='{superuser}' where admin;
+alter table users drop column admin;
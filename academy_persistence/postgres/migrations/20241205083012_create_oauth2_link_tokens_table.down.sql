@@ -0,0 +1 @@
+drop table oauth2_link_tokens;
@@ -0,0 +1,6 @@
+create table oauth2_link_tokens (
+    link_id uuid primary key references oauth2_links(id) on delete cascade,
+    access_token bytea not null,
+    refresh_token bytea,
+    expires_at timestamp with time zone
+);
@@ -0,0 +1,3 @@
+alter table users add column email_ever_verified boolean not null default false;
+-- users who have already logged in may have verified a previous email address
+update users set email_ever_verified=email_verified or last_login is not null;
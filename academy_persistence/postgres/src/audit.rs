@@ -0,0 +1,151 @@
+use academy_di::Build;
+use academy_models::{
+    audit::{AuditEvent, AuditEventFilter, AuditEventKind, ClientInfo},
+    pagination::PaginationSlice,
+};
+use academy_persistence_contracts::audit::AuditRepository;
+use academy_utils::trace_instrument;
+use anyhow::anyhow;
+use bb8_postgres::tokio_postgres::{types::ToSql, Row};
+use uuid::Uuid;
+
+use crate::{arg_indices, columns, ColumnCounter, PostgresTransaction};
+
+#[derive(Debug, Clone, Build)]
+pub struct PostgresAuditRepository;
+
+columns!(audit_events as "ae": "id", "actor_id", "user_id", "kind", "ip_address", "user_agent", "created_at");
+
+/// Text representations of the audit event kinds as stored in the database
+static KINDS: [(AuditEventKind, &str); 9] = [
+    (AuditEventKind::UserUpdated, "user_updated"),
+    (AuditEventKind::UserImpersonated, "user_impersonated"),
+    (AuditEventKind::MfaDisabled, "mfa_disabled"),
+    (AuditEventKind::PasswordReset, "password_reset"),
+    (AuditEventKind::OAuth2LinkCreated, "oauth2_link_created"),
+    (AuditEventKind::OAuth2LinkDeleted, "oauth2_link_deleted"),
+    (AuditEventKind::UserDeleted, "user_deleted"),
+    (AuditEventKind::UserRestored, "user_restored"),
+    (AuditEventKind::RefreshTokenReused, "refresh_token_reused"),
+];
+
+impl AuditRepository<PostgresTransaction> for PostgresAuditRepository {
+    #[trace_instrument(skip(self, txn))]
+    async fn count(
+        &self,
+        txn: &mut PostgresTransaction,
+        filter: &AuditEventFilter,
+    ) -> anyhow::Result<u64> {
+        let mut query = "select count(*) from audit_events ae where true".to_owned();
+        let mut params: Vec<&(dyn ToSql + Sync)> = Vec::new();
+        make_filter(filter, &mut query, &mut params);
+
+        txn.txn()
+            .query_one(&query, &params)
+            .await
+            .map(|row| row.get::<_, i64>(0) as _)
+            .map_err(Into::into)
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn list(
+        &self,
+        txn: &mut PostgresTransaction,
+        filter: &AuditEventFilter,
+        pagination: PaginationSlice,
+    ) -> anyhow::Result<Vec<AuditEvent>> {
+        let mut query = format!("select {AUDIT_EVENTS_COLS} from audit_events ae where true");
+        let mut params: Vec<&(dyn ToSql + Sync)> = Vec::new();
+        make_filter(filter, &mut query, &mut params);
+        query.push_str(&format!(
+            " order by ae.created_at desc, ae.id asc limit {} offset {}",
+            *pagination.limit, pagination.offset
+        ));
+
+        txn.txn()
+            .query(&query, &params)
+            .await
+            .map_err(Into::into)
+            .and_then(|rows| {
+                rows.into_iter()
+                    .map(|row| decode_audit_event(&row, &mut Default::default()))
+                    .collect()
+            })
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn create(
+        &self,
+        txn: &mut PostgresTransaction,
+        event: &AuditEvent,
+    ) -> anyhow::Result<()> {
+        txn.txn()
+            .execute(
+                &format!(
+                    "insert into audit_events ({AUDIT_EVENTS_COL_NAMES}) values ({})",
+                    arg_indices(1..=AUDIT_EVENTS_CNT)
+                ),
+                &[
+                    &*event.id,
+                    &event.actor_id.as_deref(),
+                    &*event.user_id,
+                    encode_kind(event.kind),
+                    &event.client.ip_address,
+                    &event.client.user_agent,
+                    &event.created_at,
+                ],
+            )
+            .await
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+}
+
+fn make_filter<'a>(
+    filter: &'a AuditEventFilter,
+    query: &mut String,
+    params: &mut Vec<&'a (dyn ToSql + Sync)>,
+) {
+    if let Some(actor_id) = &filter.actor_id {
+        params.push(&**actor_id);
+        query.push_str(&format!(" and actor_id=${}", params.len()));
+    }
+    if let Some(user_id) = &filter.user_id {
+        params.push(&**user_id);
+        query.push_str(&format!(" and user_id=${}", params.len()));
+    }
+    if let Some(kind) = filter.kind {
+        params.push(encode_kind(kind));
+        query.push_str(&format!(" and kind=${}", params.len()));
+    }
+}
+
+fn encode_kind(kind: AuditEventKind) -> &'static &'static str {
+    KINDS
+        .iter()
+        .find(|(k, _)| *k == kind)
+        .map(|(_, s)| s)
+        .unwrap()
+}
+
+fn decode_kind(kind: &str) -> anyhow::Result<AuditEventKind> {
+    KINDS
+        .iter()
+        .find(|(_, s)| *s == kind)
+        .map(|(k, _)| *k)
+        .ok_or_else(|| anyhow!("Invalid audit event kind: {kind}"))
+}
+
+fn decode_audit_event(row: &Row, cnt: &mut ColumnCounter) -> anyhow::Result<AuditEvent> {
+    Ok(AuditEvent {
+        id: row.get::<_, Uuid>(cnt.idx()).into(),
+        actor_id: row.get::<_, Option<Uuid>>(cnt.idx()).map(Into::into),
+        user_id: row.get::<_, Uuid>(cnt.idx()).into(),
+        kind: decode_kind(row.get(cnt.idx()))?,
+        client: ClientInfo {
+            ip_address: row.get(cnt.idx()),
+            user_agent: row.get(cnt.idx()),
+        },
+        created_at: row.get(cnt.idx()),
+    })
+}
@@ -1,17 +1,21 @@
 use std::{collections::HashSet, fmt::Write, time::Duration};
 
-use academy_models::Sha256Hash;
+use academy_models::{
+    pagination::{Pagination, PaginationSlice, SortDirection},
+    Sha256Hash,
+};
 use academy_persistence_contracts::{Database, Transaction};
 use academy_utils::trace_instrument;
 use anyhow::{anyhow, Context};
 use bb8::{Pool, PooledConnection};
 use bb8_postgres::{
-    tokio_postgres::{self, NoTls},
+    tokio_postgres::{self, types::ToSql, NoTls},
     PostgresConnectionManager,
 };
 use ouroboros::self_referencing;
 use tracing::trace;
 
+pub mod audit;
 pub mod mfa;
 pub mod oauth2;
 pub mod session;
@@ -321,6 +325,122 @@ fn arg_indices(indices: impl IntoIterator<Item = usize>) -> String {
     out
 }
 
+/// Key by which the items of a paginated list are sorted. Ties are broken by
+/// the `id` column.
+#[derive(Clone, Copy)]
+enum SortKey<'s> {
+    /// Sort by the `created_at` column.
+    CreatedAt,
+    /// Sort by an arbitrary expression.
+    Expr {
+        /// The table that contains the items.
+        table: &'s str,
+        /// Returns the sort expression for the given table alias.
+        expr: fn(&str) -> String,
+    },
+}
+
+/// Return whether the item referenced by the cursor of the given [`Pagination`]
+/// still exists if it is needed to look up the sort value of the cursor.
+///
+/// Only the `created_at` column is part of the cursor itself, so continuing
+/// after a deleted item is impossible when sorting by an expression.
+async fn cursor_exists(
+    txn: &mut PostgresTransaction,
+    pagination: &Pagination,
+    key: SortKey<'_>,
+) -> anyhow::Result<bool> {
+    let (
+        Pagination::Cursor {
+            after: Some(after), ..
+        },
+        SortKey::Expr { table, .. },
+    ) = (pagination, key)
+    else {
+        return Ok(true);
+    };
+
+    txn.txn()
+        .query_one(
+            &format!("select exists(select 1 from {table} where id=$1)"),
+            &[&after.id],
+        )
+        .await
+        .map(|row| row.get(0))
+        .map_err(Into::into)
+}
+
+/// Append the cursor condition, ordering and limit for the given [`Pagination`]
+/// to a query which ends with a `where` clause.
+///
+/// Items of the table referenced by `alias` are ordered by `key` and `id` in
+/// the given `direction`.
+fn make_pagination<'a>(
+    pagination: &'a Pagination,
+    key: SortKey,
+    direction: SortDirection,
+    alias: &str,
+    query: &mut String,
+    params: &mut Vec<&'a (dyn ToSql + Sync)>,
+) {
+    let (op, dir) = match direction {
+        SortDirection::Asc => (">", "asc"),
+        SortDirection::Desc => ("<", "desc"),
+    };
+
+    let expr = match key {
+        SortKey::CreatedAt => format!("{alias}.created_at"),
+        SortKey::Expr { expr, .. } => expr(alias),
+    };
+
+    let limit = match pagination {
+        Pagination::Offset(PaginationSlice { limit, .. }) => limit,
+        Pagination::Cursor { limit, after } => {
+            if let Some(after) = after {
+                match key {
+                    SortKey::CreatedAt => {
+                        params.push(&after.created_at);
+                        params.push(&after.id);
+                        write!(
+                            query,
+                            " and ({expr}, {alias}.id){op}(${}, ${})",
+                            params.len() - 1,
+                            params.len()
+                        )
+                        .unwrap();
+                    }
+                    SortKey::Expr {
+                        table,
+                        expr: cursor_expr,
+                    } => {
+                        params.push(&after.id);
+                        write!(
+                            query,
+                            " and ({expr}, {alias}.id){op}(select {}, cur.id from {table} cur \
+                             where cur.id=${})",
+                            cursor_expr("cur"),
+                            params.len()
+                        )
+                        .unwrap();
+                    }
+                }
+            }
+            limit
+        }
+    };
+
+    write!(
+        query,
+        " order by {expr} {dir}, {alias}.id {dir} limit {}",
+        **limit
+    )
+    .unwrap();
+
+    if let Pagination::Offset(PaginationSlice { offset, .. }) = pagination {
+        write!(query, " offset {offset}").unwrap();
+    }
+}
+
 #[derive(Debug, Default)]
 struct ColumnCounter(usize);
 impl ColumnCounter {
@@ -2,12 +2,17 @@ use std::fmt::Write;
 
 use academy_di::Build;
 use academy_models::{
-    mfa::{MfaRecoveryCodeHash, TotpDevice, TotpDeviceId, TotpDevicePatchRef, TotpSecret},
+    mfa::{
+        MfaRecoveryCodeHash, TotpDevice, TotpDeviceId, TotpDevicePatchRef, TotpSecret,
+        WebauthnCredential, WebauthnCredentialId, WebauthnCredentialKey,
+        WebauthnCredentialPatchRef, WebauthnCredentialRawId,
+    },
     user::UserId,
 };
 use academy_persistence_contracts::mfa::MfaRepository;
 use academy_utils::{patch::PatchValue, trace_instrument};
 use bb8_postgres::tokio_postgres::{types::ToSql, Row};
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use crate::{arg_indices, columns, decode_sha256hash, ColumnCounter, PostgresTransaction};
@@ -15,7 +20,9 @@ use crate::{arg_indices, columns, decode_sha256hash, ColumnCounter, PostgresTran
 #[derive(Debug, Clone, Build)]
 pub struct PostgresMfaRepository;
 
-columns!(totp_device as "td": "id", "user_id", "enabled", "created_at");
+columns!(totp_device as "td": "id", "user_id", "name", "enabled", "created_at", "last_used_at");
+columns!(webauthn_credential as "wc": "id", "user_id", "raw_id", "name", "created_at", "last_used_at");
+columns!(webauthn_credential_key as "wc": "public_key", "sign_count");
 
 impl MfaRepository<PostgresTransaction> for PostgresMfaRepository {
     #[trace_instrument(skip(self, txn))]
@@ -26,7 +33,10 @@ impl MfaRepository<PostgresTransaction> for PostgresMfaRepository {
     ) -> anyhow::Result<Vec<TotpDevice>> {
         txn.txn()
             .query(
-                &format!("select {TOTP_DEVICE_COLS} from totp_devices td where user_id=$1"),
+                &format!(
+                    "select {TOTP_DEVICE_COLS} from totp_devices td where user_id=$1 order by \
+                     created_at"
+                ),
                 &[&*user_id],
             )
             .await
@@ -38,6 +48,25 @@ impl MfaRepository<PostgresTransaction> for PostgresMfaRepository {
             })
     }
 
+    #[trace_instrument(skip(self, txn))]
+    async fn get_totp_device(
+        &self,
+        txn: &mut PostgresTransaction,
+        totp_device_id: TotpDeviceId,
+    ) -> anyhow::Result<Option<TotpDevice>> {
+        txn.txn()
+            .query_opt(
+                &format!("select {TOTP_DEVICE_COLS} from totp_devices td where id=$1"),
+                &[&*totp_device_id],
+            )
+            .await
+            .map_err(Into::into)
+            .and_then(|row| {
+                row.map(|row| decode_totp_device(&row, &mut Default::default()))
+                    .transpose()
+            })
+    }
+
     #[trace_instrument(skip(self, txn))]
     async fn create_totp_device(
         &self,
@@ -54,8 +83,10 @@ impl MfaRepository<PostgresTransaction> for PostgresMfaRepository {
                 &[
                     &*totp_device.id,
                     &*totp_device.user_id,
+                    &*totp_device.name,
                     &totp_device.enabled,
                     &totp_device.created_at,
+                    &totp_device.last_used_at,
                 ],
             )
             .await?;
@@ -71,15 +102,27 @@ impl MfaRepository<PostgresTransaction> for PostgresMfaRepository {
         &self,
         txn: &mut PostgresTransaction,
         totp_device_id: TotpDeviceId,
-        TotpDevicePatchRef { enabled }: TotpDevicePatchRef<'a>,
+        TotpDevicePatchRef {
+            name,
+            enabled,
+            last_used_at,
+        }: TotpDevicePatchRef<'a>,
     ) -> anyhow::Result<bool> {
         let mut query = "update totp_devices set id=id".to_owned();
         let mut params: Vec<&(dyn ToSql + Sync)> = vec![&*totp_device_id];
 
+        if let PatchValue::Update(name) = name {
+            params.push(&**name);
+            write!(&mut query, ", name=${}", params.len()).unwrap();
+        }
         if let PatchValue::Update(enabled) = enabled {
             params.push(enabled);
             write!(&mut query, ", enabled=${}", params.len()).unwrap();
         }
+        if let PatchValue::Update(last_used_at) = last_used_at {
+            params.push(last_used_at);
+            write!(&mut query, ", last_used_at=${}", params.len()).unwrap();
+        }
 
         query.push_str(" where id=$1");
 
@@ -90,6 +133,19 @@ impl MfaRepository<PostgresTransaction> for PostgresMfaRepository {
             .map_err(Into::into)
     }
 
+    #[trace_instrument(skip(self, txn))]
+    async fn delete_totp_device(
+        &self,
+        txn: &mut PostgresTransaction,
+        totp_device_id: TotpDeviceId,
+    ) -> anyhow::Result<bool> {
+        txn.txn()
+            .execute("delete from totp_devices where id=$1", &[&*totp_device_id])
+            .await
+            .map(|n| n != 0)
+            .map_err(Into::into)
+    }
+
     #[trace_instrument(skip(self, txn))]
     async fn delete_totp_devices_by_user(
         &self,
@@ -103,23 +159,43 @@ impl MfaRepository<PostgresTransaction> for PostgresMfaRepository {
             .map_err(Into::into)
     }
 
+    #[trace_instrument(skip(self, txn))]
+    async fn delete_disabled_totp_devices_by_created_at(
+        &self,
+        txn: &mut PostgresTransaction,
+        created_at: DateTime<Utc>,
+    ) -> anyhow::Result<u64> {
+        txn.txn()
+            .execute(
+                "delete from totp_devices where not enabled and created_at<$1",
+                &[&created_at],
+            )
+            .await
+            .map_err(Into::into)
+    }
+
     #[trace_instrument(skip(self, txn))]
     async fn list_enabled_totp_device_secrets_by_user(
         &self,
         txn: &mut PostgresTransaction,
         user_id: UserId,
-    ) -> anyhow::Result<Vec<TotpSecret>> {
+    ) -> anyhow::Result<Vec<(TotpDeviceId, TotpSecret)>> {
         txn.txn()
             .query(
-                "select secret from totp_device_secrets inner join totp_devices using(id) where \
-                 user_id=$1 and enabled",
+                "select id, secret from totp_device_secrets inner join totp_devices using(id) \
+                 where user_id=$1 and enabled order by created_at",
                 &[&*user_id],
             )
             .await
             .map_err(Into::into)
             .and_then(|rows| {
                 rows.into_iter()
-                    .map(|row| decode_totp_device_secret(row.get(0)))
+                    .map(|row| {
+                        Ok((
+                            row.get::<_, Uuid>(0).into(),
+                            decode_totp_device_secret(row.get(1))?,
+                        ))
+                    })
                     .collect()
             })
     }
@@ -210,17 +286,201 @@ impl MfaRepository<PostgresTransaction> for PostgresMfaRepository {
             .map(|_| ())
             .map_err(Into::into)
     }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn list_webauthn_credentials_by_user(
+        &self,
+        txn: &mut PostgresTransaction,
+        user_id: UserId,
+    ) -> anyhow::Result<Vec<WebauthnCredential>> {
+        txn.txn()
+            .query(
+                &format!(
+                    "select {WEBAUTHN_CREDENTIAL_COLS} from webauthn_credentials wc where \
+                     user_id=$1 order by created_at"
+                ),
+                &[&*user_id],
+            )
+            .await
+            .map_err(Into::into)
+            .and_then(|rows| {
+                rows.into_iter()
+                    .map(|row| decode_webauthn_credential(&row, &mut Default::default()))
+                    .collect()
+            })
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn get_webauthn_credential_by_raw_id(
+        &self,
+        txn: &mut PostgresTransaction,
+        raw_id: &WebauthnCredentialRawId,
+    ) -> anyhow::Result<Option<(WebauthnCredential, WebauthnCredentialKey)>> {
+        txn.txn()
+            .query_opt(
+                &format!(
+                    "select {WEBAUTHN_CREDENTIAL_COLS}, {WEBAUTHN_CREDENTIAL_KEY_COLS} from \
+                     webauthn_credentials wc where raw_id=$1"
+                ),
+                &[&**raw_id],
+            )
+            .await
+            .map_err(Into::into)
+            .and_then(|row| {
+                row.map(|row| {
+                    let mut cnt = ColumnCounter::default();
+                    Ok((
+                        decode_webauthn_credential(&row, &mut cnt)?,
+                        decode_webauthn_credential_key(&row, &mut cnt)?,
+                    ))
+                })
+                .transpose()
+            })
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn create_webauthn_credential(
+        &self,
+        txn: &mut PostgresTransaction,
+        credential: &WebauthnCredential,
+        key: &WebauthnCredentialKey,
+    ) -> anyhow::Result<()> {
+        txn.txn()
+            .execute(
+                &format!(
+                    "insert into webauthn_credentials ({WEBAUTHN_CREDENTIAL_COL_NAMES}, \
+                     {WEBAUTHN_CREDENTIAL_KEY_COL_NAMES}) values ({})",
+                    arg_indices(1..=WEBAUTHN_CREDENTIAL_CNT + WEBAUTHN_CREDENTIAL_KEY_CNT)
+                ),
+                &[
+                    &*credential.id,
+                    &*credential.user_id,
+                    &*credential.raw_id,
+                    &*credential.name,
+                    &credential.created_at,
+                    &credential.last_used_at,
+                    &*key.public_key,
+                    &(key.sign_count as i64),
+                ],
+            )
+            .await
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn update_webauthn_credential<'a>(
+        &self,
+        txn: &mut PostgresTransaction,
+        credential_id: WebauthnCredentialId,
+        WebauthnCredentialPatchRef { name, last_used_at }: WebauthnCredentialPatchRef<'a>,
+    ) -> anyhow::Result<bool> {
+        let mut query = "update webauthn_credentials set id=id".to_owned();
+        let mut params: Vec<&(dyn ToSql + Sync)> = vec![&*credential_id];
+
+        if let PatchValue::Update(name) = name {
+            params.push(&**name);
+            write!(&mut query, ", name=${}", params.len()).unwrap();
+        }
+        if let PatchValue::Update(last_used_at) = last_used_at {
+            params.push(last_used_at);
+            write!(&mut query, ", last_used_at=${}", params.len()).unwrap();
+        }
+
+        query.push_str(" where id=$1");
+
+        txn.txn()
+            .execute(&query, &params)
+            .await
+            .map(|n| n != 0)
+            .map_err(Into::into)
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn save_webauthn_credential_sign_count(
+        &self,
+        txn: &mut PostgresTransaction,
+        credential_id: WebauthnCredentialId,
+        sign_count: u32,
+    ) -> anyhow::Result<()> {
+        txn.txn()
+            .execute(
+                "update webauthn_credentials set sign_count=$2 where id=$1",
+                &[&*credential_id, &(sign_count as i64)],
+            )
+            .await
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn delete_webauthn_credential(
+        &self,
+        txn: &mut PostgresTransaction,
+        credential_id: WebauthnCredentialId,
+    ) -> anyhow::Result<bool> {
+        txn.txn()
+            .execute(
+                "delete from webauthn_credentials where id=$1",
+                &[&*credential_id],
+            )
+            .await
+            .map(|n| n != 0)
+            .map_err(Into::into)
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn delete_webauthn_credentials_by_user(
+        &self,
+        txn: &mut PostgresTransaction,
+        user_id: UserId,
+    ) -> anyhow::Result<()> {
+        txn.txn()
+            .execute(
+                "delete from webauthn_credentials where user_id=$1",
+                &[&*user_id],
+            )
+            .await
+            .map(|_| ())
+            .map_err(Into::into)
+    }
 }
 
 fn decode_totp_device(row: &Row, cnt: &mut ColumnCounter) -> anyhow::Result<TotpDevice> {
     Ok(TotpDevice {
         id: row.get::<_, Uuid>(cnt.idx()).into(),
         user_id: row.get::<_, Uuid>(cnt.idx()).into(),
+        name: row.get::<_, String>(cnt.idx()).try_into()?,
         enabled: row.get(cnt.idx()),
         created_at: row.get(cnt.idx()),
+        last_used_at: row.get(cnt.idx()),
     })
 }
 
 fn decode_totp_device_secret(data: Vec<u8>) -> anyhow::Result<TotpSecret> {
     data.try_into().map_err(Into::into)
 }
+
+fn decode_webauthn_credential(
+    row: &Row,
+    cnt: &mut ColumnCounter,
+) -> anyhow::Result<WebauthnCredential> {
+    Ok(WebauthnCredential {
+        id: row.get::<_, Uuid>(cnt.idx()).into(),
+        user_id: row.get::<_, Uuid>(cnt.idx()).into(),
+        raw_id: row.get::<_, Vec<u8>>(cnt.idx()).try_into()?,
+        name: row.get::<_, String>(cnt.idx()).try_into()?,
+        created_at: row.get(cnt.idx()),
+        last_used_at: row.get(cnt.idx()),
+    })
+}
+
+fn decode_webauthn_credential_key(
+    row: &Row,
+    cnt: &mut ColumnCounter,
+) -> anyhow::Result<WebauthnCredentialKey> {
+    Ok(WebauthnCredentialKey {
+        public_key: row.get::<_, Vec<u8>>(cnt.idx()).into(),
+        sign_count: row.get::<_, i64>(cnt.idx()).try_into()?,
+    })
+}
@@ -1,6 +1,9 @@
 use academy_di::Build;
 use academy_models::{
-    oauth2::{OAuth2Link, OAuth2LinkId, OAuth2UserInfo},
+    oauth2::{
+        OAuth2EncryptedTokens, OAuth2Link, OAuth2LinkId, OAuth2ProviderId, OAuth2RemoteUserId,
+        OAuth2UserInfo,
+    },
     user::UserId,
 };
 use academy_persistence_contracts::oauth2::{OAuth2RepoError, OAuth2Repository};
@@ -14,6 +17,7 @@ use crate::{arg_indices, columns, ColumnCounter, PostgresTransaction};
 pub struct PostgresOAuth2Repository;
 
 columns!(oauth2_links as "ol": "id", "user_id", "provider_id", "created_at", "remote_user_id", "remote_user_name");
+columns!(oauth2_link_tokens as "olt": "link_id", "access_token", "refresh_token", "expires_at");
 
 impl OAuth2Repository<PostgresTransaction> for PostgresOAuth2Repository {
     #[trace_instrument(skip(self, txn))]
@@ -55,6 +59,29 @@ impl OAuth2Repository<PostgresTransaction> for PostgresOAuth2Repository {
             })
     }
 
+    #[trace_instrument(skip(self, txn))]
+    async fn get_link_by_remote_user(
+        &self,
+        txn: &mut PostgresTransaction,
+        provider_id: &OAuth2ProviderId,
+        remote_user_id: &OAuth2RemoteUserId,
+    ) -> anyhow::Result<Option<OAuth2Link>> {
+        txn.txn()
+            .query_opt(
+                &format!(
+                    "select {OAUTH2_LINKS_COLS} from oauth2_links ol where provider_id=$1 and \
+                     remote_user_id=$2"
+                ),
+                &[&**provider_id, &**remote_user_id],
+            )
+            .await
+            .map_err(Into::into)
+            .and_then(|row| {
+                row.map(|row| decode_oauth2_link(&row, &mut Default::default()))
+                    .transpose()
+            })
+    }
+
     #[trace_instrument(skip(self, txn))]
     async fn create_link(
         &self,
@@ -93,6 +120,52 @@ impl OAuth2Repository<PostgresTransaction> for PostgresOAuth2Repository {
             .map(|n| n != 0)
             .map_err(Into::into)
     }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn get_link_tokens(
+        &self,
+        txn: &mut PostgresTransaction,
+        link_id: OAuth2LinkId,
+    ) -> anyhow::Result<Option<OAuth2EncryptedTokens>> {
+        txn.txn()
+            .query_opt(
+                &format!(
+                    "select {OAUTH2_LINK_TOKENS_COLS} from oauth2_link_tokens olt where \
+                     link_id=$1 for update"
+                ),
+                &[&*link_id],
+            )
+            .await
+            .map(|row| row.map(|row| decode_oauth2_link_tokens(&row, &mut Default::default())))
+            .map_err(Into::into)
+    }
+
+    #[trace_instrument(skip(self, txn, tokens))]
+    async fn save_link_tokens(
+        &self,
+        txn: &mut PostgresTransaction,
+        link_id: OAuth2LinkId,
+        tokens: &OAuth2EncryptedTokens,
+    ) -> anyhow::Result<()> {
+        txn.txn()
+            .execute(
+                &format!(
+                    "insert into oauth2_link_tokens ({OAUTH2_LINK_TOKENS_COL_NAMES}) values ({}) \
+                     on conflict (link_id) do update set access_token=$2, refresh_token=$3, \
+                     expires_at=$4",
+                    arg_indices(1..=OAUTH2_LINK_TOKENS_CNT)
+                ),
+                &[
+                    &*link_id,
+                    &tokens.access_token,
+                    &tokens.refresh_token,
+                    &tokens.expires_at,
+                ],
+            )
+            .await
+            .map(|_| ())
+            .map_err(Into::into)
+    }
 }
 
 fn decode_oauth2_link(row: &Row, cnt: &mut ColumnCounter) -> anyhow::Result<OAuth2Link> {
@@ -108,6 +181,15 @@ fn decode_oauth2_link(row: &Row, cnt: &mut ColumnCounter) -> anyhow::Result<OAut
     })
 }
 
+fn decode_oauth2_link_tokens(row: &Row, cnt: &mut ColumnCounter) -> OAuth2EncryptedTokens {
+    cnt.idx(); // link_id
+    OAuth2EncryptedTokens {
+        access_token: row.get(cnt.idx()),
+        refresh_token: row.get(cnt.idx()),
+        expires_at: row.get(cnt.idx()),
+    }
+}
+
 fn map_oauth2_repo_error(err: tokio_postgres::Error) -> OAuth2RepoError {
     match err.as_db_error() {
         Some(err) if err.constraint() == Some("oauth2_links_provider_id_remote_user_id_idx") => {
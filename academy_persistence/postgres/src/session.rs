@@ -2,6 +2,7 @@ use std::fmt::Write;
 
 use academy_di::Build;
 use academy_models::{
+    pagination::{Pagination, SortDirection},
     session::{Session, SessionId, SessionPatchRef, SessionRefreshTokenHash},
     user::UserId,
 };
@@ -11,12 +12,15 @@ use bb8_postgres::tokio_postgres::{types::ToSql, Row};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-use crate::{arg_indices, columns, decode_sha256hash, ColumnCounter, PostgresTransaction};
+use crate::{
+    arg_indices, columns, decode_sha256hash, make_pagination, ColumnCounter, PostgresTransaction,
+    SortKey,
+};
 
 #[derive(Debug, Clone, Build)]
 pub struct PostgresSessionRepository;
 
-columns!(session as "s": "id", "user_id", "device_name", "created_at", "updated_at");
+columns!(session as "s": "id", "user_id", "device_name", "impersonator_id", "created_at", "updated_at");
 
 impl SessionRepository<PostgresTransaction> for PostgresSessionRepository {
     #[trace_instrument(skip(self, txn))]
@@ -60,6 +64,29 @@ impl SessionRepository<PostgresTransaction> for PostgresSessionRepository {
             })
     }
 
+    #[trace_instrument(skip(self, txn))]
+    async fn get_by_retired_refresh_token_hash(
+        &self,
+        txn: &mut PostgresTransaction,
+        refresh_token_hash: SessionRefreshTokenHash,
+    ) -> anyhow::Result<Option<Session>> {
+        txn.txn()
+            .query_opt(
+                &format!(
+                    "select {SESSION_COLS} from sessions s inner join \
+                     session_retired_refresh_tokens rt on s.id=rt.session_id where \
+                     rt.refresh_token_hash=$1"
+                ),
+                &[&refresh_token_hash.0.as_slice()],
+            )
+            .await
+            .map_err(Into::into)
+            .and_then(|row| {
+                row.map(|row| decode_session(&row, &mut Default::default()))
+                    .transpose()
+            })
+    }
+
     #[trace_instrument(skip(self, txn))]
     async fn list_by_user(
         &self,
@@ -80,6 +107,35 @@ impl SessionRepository<PostgresTransaction> for PostgresSessionRepository {
             })
     }
 
+    #[trace_instrument(skip(self, txn))]
+    async fn list_by_user_paginated(
+        &self,
+        txn: &mut PostgresTransaction,
+        user_id: UserId,
+        pagination: Pagination,
+    ) -> anyhow::Result<Vec<Session>> {
+        let mut query = format!("select {SESSION_COLS} from sessions s where user_id=$1");
+        let mut params: Vec<&(dyn ToSql + Sync)> = vec![&*user_id];
+        make_pagination(
+            &pagination,
+            SortKey::CreatedAt,
+            SortDirection::Asc,
+            "s",
+            &mut query,
+            &mut params,
+        );
+
+        txn.txn()
+            .query(&query, &params)
+            .await
+            .map_err(Into::into)
+            .and_then(|rows| {
+                rows.into_iter()
+                    .map(|row| decode_session(&row, &mut Default::default()))
+                    .collect()
+            })
+    }
+
     #[trace_instrument(skip(self, txn))]
     async fn create(&self, txn: &mut PostgresTransaction, session: &Session) -> anyhow::Result<()> {
         txn.txn()
@@ -92,6 +148,7 @@ impl SessionRepository<PostgresTransaction> for PostgresSessionRepository {
                     &*session.id,
                     &*session.user_id,
                     &session.device_name.as_deref(),
+                    &session.impersonator_id.as_deref(),
                     &session.created_at,
                     &session.updated_at,
                 ],
@@ -229,6 +286,36 @@ impl SessionRepository<PostgresTransaction> for PostgresSessionRepository {
             .map(|_| ())
             .map_err(Into::into)
     }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn retire_refresh_token_hash(
+        &self,
+        txn: &mut PostgresTransaction,
+        session_id: SessionId,
+        refresh_token_hash: SessionRefreshTokenHash,
+        retired_at: DateTime<Utc>,
+        keep: usize,
+    ) -> anyhow::Result<()> {
+        txn.txn()
+            .execute(
+                "insert into session_retired_refresh_tokens (refresh_token_hash, session_id, \
+                 retired_at) values ($1, $2, $3) on conflict (refresh_token_hash) do nothing",
+                &[&refresh_token_hash.0.as_slice(), &*session_id, &retired_at],
+            )
+            .await?;
+
+        txn.txn()
+            .execute(
+                "delete from session_retired_refresh_tokens where session_id=$1 and \
+                 refresh_token_hash not in (select refresh_token_hash from \
+                 session_retired_refresh_tokens where session_id=$1 order by retired_at desc \
+                 limit $2)",
+                &[&*session_id, &(keep as i64)],
+            )
+            .await
+            .map(|_| ())
+            .map_err(Into::into)
+    }
 }
 
 fn decode_session(row: &Row, cnt: &mut ColumnCounter) -> anyhow::Result<Session> {
@@ -239,6 +326,7 @@ fn decode_session(row: &Row, cnt: &mut ColumnCounter) -> anyhow::Result<Session>
             .get::<_, Option<String>>(cnt.idx())
             .map(TryInto::try_into)
             .transpose()?,
+        impersonator_id: row.get::<_, Option<Uuid>>(cnt.idx()).map(Into::into),
         created_at: row.get(cnt.idx()),
         updated_at: row.get(cnt.idx()),
     })
@@ -1,34 +1,84 @@
-use std::fmt::Write;
+use std::{collections::BTreeSet, fmt::Write};
 
 use academy_di::Build;
 use academy_models::{
     email_address::EmailAddress,
     oauth2::{OAuth2ProviderId, OAuth2RemoteUserId},
-    pagination::PaginationSlice,
+    pagination::{Pagination, PaginationSlice},
+    role::Role,
     user::{
-        User, UserComposite, UserDetails, UserFilter, UserId, UserInvoiceInfo,
+        User, UserAvatar, UserComposite, UserDetails, UserFilter, UserId, UserInvoiceInfo,
         UserInvoiceInfoPatchRef, UserName, UserPatchRef, UserProfile, UserProfilePatchRef,
+        UserProfileVisibility, UserSort, UserSortBy,
     },
+    SearchTerm,
 };
 use academy_persistence_contracts::user::{UserRepoError, UserRepository};
 use academy_utils::{patch::PatchValue, trace_instrument};
+use anyhow::anyhow;
 use bb8_postgres::tokio_postgres::{self, types::ToSql, Row};
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-use crate::{arg_indices, columns, ColumnCounter, PostgresTransaction};
+use crate::{
+    arg_indices, columns, cursor_exists, make_pagination, ColumnCounter, PostgresTransaction,
+    SortKey,
+};
 
 #[derive(Debug, Clone, Copy, Default, Build)]
 pub struct PostgresUserRepository;
 
-columns!(user as "u": "id", "name", "email", "email_verified", "created_at", "last_login", "last_name_change", "enabled", "admin", "newsletter");
-columns!(profile as "p": "user_id", "display_name", "bio", "tags");
+columns!(user as "u": "id", "name", "email", "email_verified", "created_at", "last_login", "last_name_change", "enabled", "roles", "newsletter", "deleted_at");
+columns!(profile as "p": "user_id", "display_name", "bio", "tags", "avatar_id", "avatar_url");
+columns!(profile_visibility as "v": "user_id", "bio", "tags", "created_at", "last_login");
 columns!(details as "d": "user_id", "mfa_enabled", "password_login", "oauth2_login");
 columns!(invoice_info as "i": "user_id", "business", "first_name", "last_name", "street", "zip_code", "city", "country", "vat_id");
 
-const JOIN_PROFILE: &str = "inner join user_profiles p on u.id=p.user_id";
+/// Text representations of the roles as stored in the database
+static ROLES: [(Role, &str); 4] = [
+    (Role::Superuser, "superuser"),
+    (Role::Support, "support"),
+    (Role::Moderator, "moderator"),
+    (Role::Billing, "billing"),
+];
+
+const JOIN_PROFILE: &str = "inner join user_profiles p on u.id=p.user_id inner join \
+                            user_profile_visibility v on u.id=v.user_id";
 const JOIN_DETAILS: &str = "inner join user_details d on u.id=d.user_id";
 const JOIN_INVOICE_INFO: &str = "inner join user_invoice_info i on u.id=i.user_id";
 
+/// Matches enabled and non-deleted users whose profile matches the full-text
+/// search query `q`.
+///
+/// The index on `p.search_vector` is used to find candidates matching the
+/// non-negated terms of `q`. Hidden profile fields are then excluded from the
+/// actual match, so they cannot be probed via negated terms either.
+const PROFILE_SEARCH: &str = "u.enabled and u.deleted_at is null and querytree(q)<>'T' and \
+                              p.search_vector @@ querytree(q)::tsquery and \
+                              user_profile_search_vector(p.display_name, case when v.bio then \
+                              p.bio else '' end, case when v.tags then p.tags else '{}' end) @@ q";
+
+/// Search vector of the visible profile fields, see [`PROFILE_SEARCH`]
+const VISIBLE_SEARCH_VECTOR: &str = "user_profile_search_vector(p.display_name, case when v.bio \
+                                     then p.bio else '' end, case when v.tags then p.tags else \
+                                     '{}' end)";
+
+/// Matches users with complete invoice info, see
+/// [`UserComposite::can_receive_coins`]
+const CAN_RECEIVE_COINS: &str = "u.email_verified and i.business is not null and i.first_name is \
+                                 not null and i.last_name is not null and i.street is not null \
+                                 and i.zip_code is not null and i.city is not null and \
+                                 i.country is not null and (not i.business or i.vat_id is not \
+                                 null)";
+
+/// Matches users without any roles that have been created before `$1`, have
+/// never verified any email address and have never logged in again after
+/// registration (which itself sets `last_login`)
+const UNVERIFIED: &str = "u.email is not null and not u.email_verified and not \
+                          u.email_ever_verified and (u.last_login is null or \
+                          u.last_login<u.created_at+interval '1 minute') and u.roles='{}' and \
+                          u.created_at<$1";
+
 impl UserRepository<PostgresTransaction> for PostgresUserRepository {
     #[trace_instrument(skip(self, txn))]
     async fn count(
@@ -40,9 +90,15 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
         if filter.name.is_some() {
             query.push_str(JOIN_PROFILE)
         }
-        if filter.mfa_enabled.is_some() {
+        if filter.mfa_enabled.is_some()
+            || filter.password_login.is_some()
+            || filter.oauth2_login.is_some()
+        {
             query.push_str(JOIN_DETAILS)
         }
+        if filter.can_receive_coins.is_some() {
+            query.push_str(JOIN_INVOICE_INFO)
+        }
         query.push_str(" where true");
 
         let mut params: Vec<&(dyn ToSql + Sync)> = Vec::new();
@@ -60,18 +116,38 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
         &self,
         txn: &mut PostgresTransaction,
         filter: &UserFilter,
-        pagination: PaginationSlice,
-    ) -> anyhow::Result<Vec<UserComposite>> {
+        sort: UserSort,
+        pagination: Pagination,
+    ) -> anyhow::Result<Option<Vec<UserComposite>>> {
         let mut query = format!(
-            "select {USER_COLS}, {PROFILE_COLS}, {DETAILS_COLS}, {INVOICE_INFO_COLS} from users u \
-             {JOIN_PROFILE} {JOIN_DETAILS} {JOIN_INVOICE_INFO} where true"
+            "select {USER_COLS}, {PROFILE_COLS}, {PROFILE_VISIBILITY_COLS}, {DETAILS_COLS}, \
+             {INVOICE_INFO_COLS} from users u {JOIN_PROFILE} {JOIN_DETAILS} {JOIN_INVOICE_INFO} \
+             where true"
         );
         let mut params: Vec<&(dyn ToSql + Sync)> = Vec::new();
         make_filter(filter, &mut query, &mut params);
-        query.push_str(&format!(
-            " order by u.created_at asc limit {} offset {}",
-            *pagination.limit, pagination.offset
-        ));
+        let key = match sort.by {
+            UserSortBy::CreatedAt => SortKey::CreatedAt,
+            UserSortBy::Name => SortKey::Expr {
+                table: "users",
+                expr: |alias| format!("lower({alias}.name)"),
+            },
+            UserSortBy::LastLogin => SortKey::Expr {
+                table: "users",
+                expr: |alias| format!("coalesce({alias}.last_login, '-infinity')"),
+            },
+        };
+        if !cursor_exists(txn, &pagination, key).await? {
+            return Ok(None);
+        }
+        make_pagination(
+            &pagination,
+            key,
+            sort.direction,
+            "u",
+            &mut query,
+            &mut params,
+        );
 
         txn.txn()
             .query(&query, &params)
@@ -82,6 +158,54 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
                     .map(|row| decode_composite(&row, &mut Default::default()))
                     .collect()
             })
+            .map(Some)
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn count_search_profiles(
+        &self,
+        txn: &mut PostgresTransaction,
+        query: &SearchTerm,
+    ) -> anyhow::Result<u64> {
+        txn.txn()
+            .query_one(
+                &format!(
+                    "select count(*) from users u {JOIN_PROFILE}, \
+                     websearch_to_tsquery('simple', $1) q where {PROFILE_SEARCH}"
+                ),
+                &[&**query],
+            )
+            .await
+            .map(|row| row.get::<_, i64>(0) as _)
+            .map_err(Into::into)
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn search_profiles(
+        &self,
+        txn: &mut PostgresTransaction,
+        query: &SearchTerm,
+        pagination: PaginationSlice,
+    ) -> anyhow::Result<Vec<UserComposite>> {
+        txn.txn()
+            .query(
+                &format!(
+                    "select {USER_COLS}, {PROFILE_COLS}, {PROFILE_VISIBILITY_COLS}, \
+                     {DETAILS_COLS}, {INVOICE_INFO_COLS} from users u {JOIN_PROFILE} \
+                     {JOIN_DETAILS} {JOIN_INVOICE_INFO}, websearch_to_tsquery('simple', $1) q \
+                     where {PROFILE_SEARCH} order by ts_rank({VISIBLE_SEARCH_VECTOR}, q) desc, \
+                     u.id asc limit {} offset {}",
+                    *pagination.limit, pagination.offset
+                ),
+                &[&**query],
+            )
+            .await
+            .map_err(Into::into)
+            .and_then(|rows| {
+                rows.into_iter()
+                    .map(|row| decode_composite(&row, &mut Default::default()))
+                    .collect()
+            })
     }
 
     #[trace_instrument(skip(self, txn))]
@@ -102,8 +226,9 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
         txn.txn()
             .query_opt(
                 &format!(
-                    "select {USER_COLS}, {PROFILE_COLS}, {DETAILS_COLS}, {INVOICE_INFO_COLS} from \
-                     users u {JOIN_PROFILE} {JOIN_DETAILS} {JOIN_INVOICE_INFO} where id=$1"
+                    "select {USER_COLS}, {PROFILE_COLS}, {PROFILE_VISIBILITY_COLS}, \
+                     {DETAILS_COLS}, {INVOICE_INFO_COLS} from users u {JOIN_PROFILE} \
+                     {JOIN_DETAILS} {JOIN_INVOICE_INFO} where id=$1"
                 ),
                 &[&*user_id],
             )
@@ -124,9 +249,9 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
         txn.txn()
             .query_opt(
                 &format!(
-                    "select {USER_COLS}, {PROFILE_COLS}, {DETAILS_COLS}, {INVOICE_INFO_COLS} from \
-                     users u {JOIN_PROFILE} {JOIN_DETAILS} {JOIN_INVOICE_INFO} where \
-                     lower(name)=lower($1)"
+                    "select {USER_COLS}, {PROFILE_COLS}, {PROFILE_VISIBILITY_COLS}, \
+                     {DETAILS_COLS}, {INVOICE_INFO_COLS} from users u {JOIN_PROFILE} \
+                     {JOIN_DETAILS} {JOIN_INVOICE_INFO} where lower(name)=lower($1)"
                 ),
                 &[&name.as_str()],
             )
@@ -147,9 +272,9 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
         txn.txn()
             .query_opt(
                 &format!(
-                    "select {USER_COLS}, {PROFILE_COLS}, {DETAILS_COLS}, {INVOICE_INFO_COLS} from \
-                     users u {JOIN_PROFILE} {JOIN_DETAILS} {JOIN_INVOICE_INFO} where \
-                     lower(email)=lower($1)"
+                    "select {USER_COLS}, {PROFILE_COLS}, {PROFILE_VISIBILITY_COLS}, \
+                     {DETAILS_COLS}, {INVOICE_INFO_COLS} from users u {JOIN_PROFILE} \
+                     {JOIN_DETAILS} {JOIN_INVOICE_INFO} where lower(email)=lower($1)"
                 ),
                 &[&email.as_str()],
             )
@@ -171,10 +296,10 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
         txn.txn()
             .query_opt(
                 &format!(
-                    "select {USER_COLS}, {PROFILE_COLS}, {DETAILS_COLS}, {INVOICE_INFO_COLS} from \
-                     users u {JOIN_PROFILE} {JOIN_DETAILS} {JOIN_INVOICE_INFO} inner join \
-                     oauth2_links ol on u.id=ol.user_id where ol.provider_id=$1 and \
-                     ol.remote_user_id=$2"
+                    "select {USER_COLS}, {PROFILE_COLS}, {PROFILE_VISIBILITY_COLS}, \
+                     {DETAILS_COLS}, {INVOICE_INFO_COLS} from users u {JOIN_PROFILE} \
+                     {JOIN_DETAILS} {JOIN_INVOICE_INFO} inner join oauth2_links ol on \
+                     u.id=ol.user_id where ol.provider_id=$1 and ol.remote_user_id=$2"
                 ),
                 &[&**provider_id, &**remote_user_id],
             )
@@ -197,7 +322,7 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
         txn.txn()
             .execute(
                 &format!(
-                    "insert into users ({USER_COL_NAMES}) values ({})",
+                    "insert into users ({USER_COL_NAMES}, email_ever_verified) values ({}, $4)",
                     arg_indices(1..=USER_CNT)
                 ),
                 &[
@@ -209,8 +334,9 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
                     &user.last_login,
                     &user.last_name_change,
                     &user.enabled,
-                    &user.admin,
+                    &encode_roles(&user.roles),
                     &user.newsletter,
+                    &user.deleted_at,
                 ],
             )
             .await
@@ -227,6 +353,26 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
                     &profile.display_name.as_str(),
                     &profile.bio.as_str(),
                     &profile.tags.iter().map(|x| x.as_str()).collect::<Vec<_>>(),
+                    &profile.avatar.as_ref().map(|x| *x.id),
+                    &profile.avatar.as_ref().map(|x| x.url.as_str()),
+                ],
+            )
+            .await
+            .map_err(|err| UserRepoError::Other(err.into()))?;
+
+        txn.txn()
+            .execute(
+                &format!(
+                    "insert into user_profile_visibility ({PROFILE_VISIBILITY_COL_NAMES}) values \
+                     ({})",
+                    arg_indices(1..=PROFILE_VISIBILITY_CNT)
+                ),
+                &[
+                    &*user.id,
+                    &profile.visibility.bio,
+                    &profile.visibility.tags,
+                    &profile.visibility.created_at,
+                    &profile.visibility.last_login,
                 ],
             )
             .await
@@ -268,14 +414,16 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
             last_login,
             last_name_change,
             enabled,
-            admin,
+            roles,
             newsletter,
+            deleted_at,
         }: UserPatchRef<'a>,
     ) -> Result<bool, UserRepoError> {
         let mut query = "update users set id=id".to_owned();
         let mut params: Vec<&(dyn ToSql + Sync)> = vec![&*user_id];
 
         let email = email.map(|x| x.as_ref().map(|x| x.as_str()));
+        let roles = roles.map(encode_roles);
 
         if let PatchValue::Update(name) = name {
             params.push(&**name);
@@ -287,7 +435,12 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
         }
         if let PatchValue::Update(email_verified) = email_verified {
             params.push(email_verified);
-            write!(&mut query, ", email_verified=${}", params.len()).unwrap();
+            write!(
+                &mut query,
+                ", email_verified=${0}, email_ever_verified=email_ever_verified or ${0}",
+                params.len()
+            )
+            .unwrap();
         }
         if let PatchValue::Update(last_login) = last_login {
             params.push(last_login);
@@ -301,14 +454,18 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
             params.push(enabled);
             write!(&mut query, ", enabled=${}", params.len()).unwrap();
         }
-        if let PatchValue::Update(admin) = admin {
-            params.push(admin);
-            write!(&mut query, ", admin=${}", params.len()).unwrap();
+        if let PatchValue::Update(roles) = &roles {
+            params.push(roles);
+            write!(&mut query, ", roles=${}", params.len()).unwrap();
         }
         if let PatchValue::Update(newsletter) = newsletter {
             params.push(newsletter);
             write!(&mut query, ", newsletter=${}", params.len()).unwrap();
         }
+        if let PatchValue::Update(deleted_at) = deleted_at {
+            params.push(deleted_at);
+            write!(&mut query, ", deleted_at=${}", params.len()).unwrap();
+        }
 
         query.push_str(" where id=$1");
 
@@ -328,8 +485,25 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
             display_name,
             bio,
             tags,
+            visibility,
         }: UserProfilePatchRef<'a>,
     ) -> anyhow::Result<bool> {
+        if let PatchValue::Update(visibility) = visibility {
+            txn.txn()
+                .execute(
+                    "update user_profile_visibility set bio=$2, tags=$3, created_at=$4, \
+                     last_login=$5 where user_id=$1",
+                    &[
+                        &*user_id,
+                        &visibility.bio,
+                        &visibility.tags,
+                        &visibility.created_at,
+                        &visibility.last_login,
+                    ],
+                )
+                .await?;
+        }
+
         let mut query = "update user_profiles set user_id=user_id".to_owned();
         let mut params: Vec<&(dyn ToSql + Sync)> = vec![&*user_id];
 
@@ -357,6 +531,27 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
             .map_err(Into::into)
     }
 
+    #[trace_instrument(skip(self, txn))]
+    async fn update_avatar(
+        &self,
+        txn: &mut PostgresTransaction,
+        user_id: UserId,
+        avatar: &Option<UserAvatar>,
+    ) -> anyhow::Result<bool> {
+        txn.txn()
+            .execute(
+                "update user_profiles set avatar_id=$2, avatar_url=$3 where user_id=$1",
+                &[
+                    &*user_id,
+                    &avatar.as_ref().map(|x| *x.id),
+                    &avatar.as_ref().map(|x| x.url.as_str()),
+                ],
+            )
+            .await
+            .map(|x| x != 0)
+            .map_err(Into::into)
+    }
+
     #[trace_instrument(skip(self, txn))]
     async fn update_invoice_info<'a>(
         &self,
@@ -435,6 +630,83 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
             .map_err(Into::into)
     }
 
+    #[trace_instrument(skip(self, txn))]
+    async fn list_unverified_composites(
+        &self,
+        txn: &mut PostgresTransaction,
+        created_at: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<UserComposite>> {
+        txn.txn()
+            .query(
+                &format!(
+                    "select {USER_COLS}, {PROFILE_COLS}, {PROFILE_VISIBILITY_COLS}, \
+                     {DETAILS_COLS}, {INVOICE_INFO_COLS} from users u {JOIN_PROFILE} \
+                     {JOIN_DETAILS} {JOIN_INVOICE_INFO} where {UNVERIFIED} order by u.created_at \
+                     asc"
+                ),
+                &[&created_at],
+            )
+            .await
+            .map_err(Into::into)
+            .and_then(|rows| {
+                rows.into_iter()
+                    .map(|row| decode_composite(&row, &mut Default::default()))
+                    .collect()
+            })
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn delete_unverified(
+        &self,
+        txn: &mut PostgresTransaction,
+        created_at: DateTime<Utc>,
+    ) -> anyhow::Result<u64> {
+        txn.txn()
+            .execute(
+                &format!("delete from users u where {UNVERIFIED}"),
+                &[&created_at],
+            )
+            .await
+            .map_err(Into::into)
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn list_deleted_composites(
+        &self,
+        txn: &mut PostgresTransaction,
+        deleted_at: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<UserComposite>> {
+        txn.txn()
+            .query(
+                &format!(
+                    "select {USER_COLS}, {PROFILE_COLS}, {PROFILE_VISIBILITY_COLS}, \
+                     {DETAILS_COLS}, {INVOICE_INFO_COLS} from users u {JOIN_PROFILE} \
+                     {JOIN_DETAILS} {JOIN_INVOICE_INFO} where u.deleted_at<=$1 order by \
+                     u.deleted_at asc"
+                ),
+                &[&deleted_at],
+            )
+            .await
+            .map_err(Into::into)
+            .and_then(|rows| {
+                rows.into_iter()
+                    .map(|row| decode_composite(&row, &mut Default::default()))
+                    .collect()
+            })
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn delete_by_deleted_at(
+        &self,
+        txn: &mut PostgresTransaction,
+        deleted_at: DateTime<Utc>,
+    ) -> anyhow::Result<u64> {
+        txn.txn()
+            .execute("delete from users where deleted_at<=$1", &[&deleted_at])
+            .await
+            .map_err(Into::into)
+    }
+
     #[trace_instrument(skip(self, txn))]
     async fn save_password_hash(
         &self,
@@ -480,6 +752,61 @@ impl UserRepository<PostgresTransaction> for PostgresUserRepository {
             .map(|n| n != 0)
             .map_err(Into::into)
     }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn count_password_hashes_by_parameters(
+        &self,
+        txn: &mut PostgresTransaction,
+    ) -> anyhow::Result<Vec<(String, u64)>> {
+        txn.txn()
+            .query(
+                "select regexp_replace(password_hash, '\\$[^$]*\\$[^$]*$', '') as parameters, \
+                 count(*) from user_passwords group by parameters order by count(*) desc, \
+                 parameters",
+                &[],
+            )
+            .await
+            .map(|rows| {
+                rows.into_iter()
+                    .map(|row| (row.get(0), row.get::<_, i64>(1) as _))
+                    .collect()
+            })
+            .map_err(Into::into)
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn create_export_request(
+        &self,
+        txn: &mut PostgresTransaction,
+        user_id: UserId,
+        requested_at: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        txn.txn()
+            .execute(
+                "insert into user_export_requests (user_id, requested_at) values ($1, $2) on \
+                 conflict (user_id) do nothing",
+                &[&*user_id, &requested_at],
+            )
+            .await?;
+        Ok(())
+    }
+
+    #[trace_instrument(skip(self, txn))]
+    async fn take_export_request(
+        &self,
+        txn: &mut PostgresTransaction,
+    ) -> anyhow::Result<Option<UserId>> {
+        txn.txn()
+            .query_opt(
+                "delete from user_export_requests where user_id=(select user_id from \
+                 user_export_requests order by requested_at limit 1 for update skip locked) \
+                 returning user_id",
+                &[],
+            )
+            .await
+            .map(|row| row.map(|row| row.get::<_, Uuid>(0).into()))
+            .map_err(Into::into)
+    }
 }
 
 fn make_filter<'a>(
@@ -502,9 +829,9 @@ fn make_filter<'a>(
         params.push(enabled);
         query.push_str(&format!(" and enabled=${}", params.len()));
     }
-    if let Some(admin) = &filter.admin {
-        params.push(admin);
-        query.push_str(&format!(" and admin=${}", params.len()));
+    if let Some(role) = filter.role {
+        params.push(encode_role(role));
+        query.push_str(&format!(" and ${}=any(roles)", params.len()));
     }
     if let Some(mfa_enabled) = &filter.mfa_enabled {
         params.push(mfa_enabled);
@@ -518,6 +845,62 @@ fn make_filter<'a>(
         params.push(newsletter);
         query.push_str(&format!(" and newsletter=${}", params.len()));
     }
+    if let Some(password_login) = &filter.password_login {
+        params.push(password_login);
+        query.push_str(&format!(" and d.password_login=${}", params.len()));
+    }
+    if let Some(oauth2_login) = &filter.oauth2_login {
+        params.push(oauth2_login);
+        query.push_str(&format!(" and d.oauth2_login=${}", params.len()));
+    }
+    if let Some(oauth2_provider) = &filter.oauth2_provider {
+        params.push(&**oauth2_provider);
+        query.push_str(&format!(
+            " and exists (select * from oauth2_links ol where ol.user_id=u.id and \
+             ol.provider_id=${})",
+            params.len()
+        ));
+    }
+    if let Some(can_receive_coins) = &filter.can_receive_coins {
+        params.push(can_receive_coins);
+        query.push_str(&format!(" and ({CAN_RECEIVE_COINS})=${}", params.len()));
+    }
+    if let Some(created_after) = &filter.created_after {
+        params.push(created_after);
+        query.push_str(&format!(" and u.created_at>=${}", params.len()));
+    }
+    if let Some(created_before) = &filter.created_before {
+        params.push(created_before);
+        query.push_str(&format!(" and u.created_at<${}", params.len()));
+    }
+    if let Some(last_login_after) = &filter.last_login_after {
+        params.push(last_login_after);
+        query.push_str(&format!(" and u.last_login>=${}", params.len()));
+    }
+    if let Some(last_login_before) = &filter.last_login_before {
+        params.push(last_login_before);
+        query.push_str(&format!(" and u.last_login<${}", params.len()));
+    }
+}
+
+fn encode_roles(roles: &BTreeSet<Role>) -> Vec<&'static str> {
+    roles.iter().map(|&role| *encode_role(role)).collect()
+}
+
+fn encode_role(role: Role) -> &'static &'static str {
+    ROLES
+        .iter()
+        .find(|(r, _)| *r == role)
+        .map(|(_, s)| s)
+        .unwrap()
+}
+
+fn decode_role(role: &str) -> anyhow::Result<Role> {
+    ROLES
+        .iter()
+        .find(|(_, s)| *s == role)
+        .map(|(r, _)| *r)
+        .ok_or_else(|| anyhow!("Invalid role: {role}"))
 }
 
 fn decode_user(row: &Row, cnt: &mut ColumnCounter) -> anyhow::Result<User> {
@@ -534,8 +917,13 @@ fn decode_user(row: &Row, cnt: &mut ColumnCounter) -> anyhow::Result<User> {
         last_login: row.get(cnt.idx()),
         last_name_change: row.get(cnt.idx()),
         enabled: row.get(cnt.idx()),
-        admin: row.get(cnt.idx()),
+        roles: row
+            .get::<_, Vec<String>>(cnt.idx())
+            .iter()
+            .map(|role| decode_role(role))
+            .collect::<anyhow::Result<_>>()?,
         newsletter: row.get(cnt.idx()),
+        deleted_at: row.get(cnt.idx()),
     })
 }
 
@@ -550,6 +938,34 @@ fn decode_profile(row: &Row, cnt: &mut ColumnCounter) -> anyhow::Result<UserProf
             .map(TryInto::try_into)
             .collect::<Result<Vec<_>, _>>()?
             .try_into()?,
+        avatar: decode_avatar(row, cnt)?,
+        visibility: decode_profile_visibility(row, cnt)?,
+    })
+}
+
+fn decode_avatar(row: &Row, cnt: &mut ColumnCounter) -> anyhow::Result<Option<UserAvatar>> {
+    let id = row.get::<_, Option<Uuid>>(cnt.idx());
+    let url = row.get::<_, Option<String>>(cnt.idx());
+    id.zip(url)
+        .map(|(id, url)| {
+            Ok(UserAvatar {
+                id: id.into(),
+                url: url.parse()?,
+            })
+        })
+        .transpose()
+}
+
+fn decode_profile_visibility(
+    row: &Row,
+    cnt: &mut ColumnCounter,
+) -> anyhow::Result<UserProfileVisibility> {
+    cnt.idx(); // user_id
+    Ok(UserProfileVisibility {
+        bio: row.get(cnt.idx()),
+        tags: row.get(cnt.idx()),
+        created_at: row.get(cnt.idx()),
+        last_login: row.get(cnt.idx()),
     })
 }
 
@@ -1,4 +1,7 @@
-use academy_persistence_postgres::MIGRATIONS;
+use academy_demo::user::{BAR, FOO};
+use academy_persistence_contracts::{user::UserRepository, Database};
+use academy_persistence_postgres::{user::PostgresUserRepository, MIGRATIONS};
+use chrono::Utc;
 use common::{setup, setup_clean};
 
 mod common;
@@ -41,3 +44,40 @@ async fn migrations_with_data() {
         assert_eq!(applied, names[MIGRATIONS.len() - i..]);
     }
 }
+
+#[tokio::test]
+async fn migrations_with_data_email_ever_verified() {
+    let db = setup().await;
+
+    let i = MIGRATIONS
+        .iter()
+        .position(|m| m.name.ends_with("_add_email_ever_verified_to_users"))
+        .unwrap();
+    db.revert_migrations(Some(MIGRATIONS.len() - i))
+        .await
+        .unwrap();
+
+    // FOO has changed their email address but not verified the new one yet,
+    // BAR has never verified their email address and never logged in
+    db.execute(&format!(
+        "update users set email='new.foo@example.com', email_verified=false where id='{}'; \
+         update users set email='bar@example.com' where id='{}'",
+        FOO.user.id.hyphenated(),
+        BAR.user.id.hyphenated()
+    ))
+    .await
+    .unwrap();
+
+    db.run_migrations(None).await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let created_at = Utc::now();
+    let result = PostgresUserRepository
+        .list_unverified_composites(&mut txn, created_at)
+        .await
+        .unwrap();
+    assert_eq!(
+        result.iter().map(|u| u.user.id).collect::<Vec<_>>(),
+        [BAR.user.id]
+    );
+}
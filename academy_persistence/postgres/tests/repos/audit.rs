@@ -0,0 +1,149 @@
+use academy_demo::{
+    audit::{ALL_AUDIT_EVENTS, FOO_AUDIT_EVENT_1, FOO_AUDIT_EVENT_2},
+    user::{ADMIN, BAR, FOO},
+    UUID1,
+};
+use academy_models::audit::{AuditEvent, AuditEventFilter, AuditEventKind, ClientInfo};
+use academy_persistence_contracts::{
+    audit::AuditRepository, user::UserRepository, Database, Transaction,
+};
+use academy_persistence_postgres::{audit::PostgresAuditRepository, user::PostgresUserRepository};
+use academy_utils::Apply;
+
+use crate::{
+    common::setup,
+    repos::{make_slice, sliced},
+};
+
+const REPO: PostgresAuditRepository = PostgresAuditRepository;
+
+#[tokio::test]
+async fn count() {
+    let db = setup().await;
+    let mut txn = db.begin_transaction().await.unwrap();
+
+    for (filter, expected) in [
+        (AuditEventFilter::default(), ALL_AUDIT_EVENTS.len()),
+        (
+            AuditEventFilter {
+                user_id: Some(FOO.user.id),
+                ..Default::default()
+            },
+            2,
+        ),
+        (
+            AuditEventFilter {
+                actor_id: Some(ADMIN.user.id),
+                ..Default::default()
+            },
+            1,
+        ),
+        (
+            AuditEventFilter {
+                kind: Some(AuditEventKind::UserUpdated),
+                ..Default::default()
+            },
+            1,
+        ),
+        (
+            AuditEventFilter {
+                user_id: Some(BAR.user.id),
+                ..Default::default()
+            },
+            0,
+        ),
+    ] {
+        let result = REPO.count(&mut txn, &filter).await.unwrap();
+        assert_eq!(result, expected as u64);
+    }
+}
+
+#[tokio::test]
+async fn list() {
+    let db = setup().await;
+    let mut txn = db.begin_transaction().await.unwrap();
+
+    let expected = [FOO_AUDIT_EVENT_2.clone(), FOO_AUDIT_EVENT_1.clone()];
+
+    for limit in 0..=3 {
+        for offset in 0..=3 {
+            let slice = make_slice(limit, offset);
+            let result = REPO
+                .list(&mut txn, &AuditEventFilter::default(), slice)
+                .await
+                .unwrap();
+            assert_eq!(result, sliced(&expected, slice));
+        }
+    }
+
+    let result = REPO
+        .list(
+            &mut txn,
+            &AuditEventFilter {
+                actor_id: Some(FOO.user.id),
+                kind: Some(AuditEventKind::UserUpdated),
+                ..Default::default()
+            },
+            make_slice(10, 0),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result, std::slice::from_ref(&*FOO_AUDIT_EVENT_1));
+}
+
+#[tokio::test]
+async fn create() {
+    let event = AuditEvent {
+        id: UUID1.into(),
+        actor_id: None,
+        user_id: BAR.user.id,
+        kind: AuditEventKind::PasswordReset,
+        client: ClientInfo::default(),
+        created_at: BAR.user.created_at,
+    };
+
+    let db = setup().await;
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    REPO.create(&mut txn, &event).await.unwrap();
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO
+        .list(
+            &mut txn,
+            &AuditEventFilter {
+                user_id: Some(BAR.user.id),
+                ..Default::default()
+            },
+            make_slice(10, 0),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result, [event]);
+}
+
+#[tokio::test]
+async fn delete_actor_keeps_event() {
+    let db = setup().await;
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    PostgresUserRepository
+        .delete(&mut txn, ADMIN.user.id)
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO
+        .list(&mut txn, &AuditEventFilter::default(), make_slice(10, 0))
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        [
+            FOO_AUDIT_EVENT_2.clone().with(|x| x.actor_id = None),
+            FOO_AUDIT_EVENT_1.clone()
+        ]
+    );
+}
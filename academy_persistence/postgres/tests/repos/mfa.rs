@@ -1,9 +1,14 @@
+use std::time::Duration;
+
 use academy_demo::{
-    mfa::{ADMIN2_TOTP_1, FOO_TOTP_1},
+    mfa::{ADMIN2_TOTP_1, ADMIN2_WEBAUTHN_1, FOO_TOTP_1, WEBAUTHN_CREDENTIAL_KEYS},
     user::{ADMIN2, BAR, FOO},
     SHA256HASH1, UUID1,
 };
-use academy_models::mfa::{MfaRecoveryCodeHash, TotpDevice, TotpDevicePatchRef, TotpSecret};
+use academy_models::mfa::{
+    MfaRecoveryCodeHash, TotpDevice, TotpDevicePatchRef, TotpSecret, WebauthnCredential,
+    WebauthnCredentialKey, WebauthnCredentialPatchRef,
+};
 use academy_persistence_contracts::{mfa::MfaRepository, Database, Transaction};
 use academy_persistence_postgres::mfa::PostgresMfaRepository;
 use academy_utils::Apply;
@@ -21,13 +26,13 @@ async fn list_totp_devices_by_user() {
         .list_totp_devices_by_user(&mut txn, ADMIN2.user.id)
         .await
         .unwrap();
-    assert_eq!(result, [ADMIN2_TOTP_1.clone()]);
+    assert_eq!(result, std::slice::from_ref(&*ADMIN2_TOTP_1));
 
     let result = REPO
         .list_totp_devices_by_user(&mut txn, FOO.user.id)
         .await
         .unwrap();
-    assert_eq!(result, [FOO_TOTP_1.clone()]);
+    assert_eq!(result, std::slice::from_ref(&*FOO_TOTP_1));
 
     let result = REPO
         .list_totp_devices_by_user(&mut txn, BAR.user.id)
@@ -36,13 +41,27 @@ async fn list_totp_devices_by_user() {
     assert_eq!(result, []);
 }
 
+#[tokio::test]
+async fn get_totp_device() {
+    let db = setup().await;
+    let mut txn = db.begin_transaction().await.unwrap();
+
+    let result = REPO.get_totp_device(&mut txn, FOO_TOTP_1.id).await.unwrap();
+    assert_eq!(result.unwrap(), *FOO_TOTP_1);
+
+    let result = REPO.get_totp_device(&mut txn, UUID1.into()).await.unwrap();
+    assert_eq!(result, None);
+}
+
 #[tokio::test]
 async fn create_totp_device() {
     let expected = TotpDevice {
         id: UUID1.into(),
         user_id: BAR.user.id,
+        name: "Phone".try_into().unwrap(),
         enabled: true,
         created_at: BAR.user.created_at,
+        last_used_at: None,
     };
     let secret = TotpSecret::try_new("IZ6GJPVVwQWfRhQTuxwrdBfn".to_owned().into_bytes()).unwrap();
 
@@ -68,9 +87,42 @@ async fn create_totp_device() {
     assert_eq!(result, secret);
 }
 
+#[tokio::test]
+async fn create_second_totp_device() {
+    let expected = TotpDevice {
+        id: UUID1.into(),
+        user_id: ADMIN2.user.id,
+        name: "Backup".try_into().unwrap(),
+        enabled: false,
+        created_at: ADMIN2_TOTP_1.created_at + Duration::from_secs(60),
+        last_used_at: None,
+    };
+    let secret = TotpSecret::try_new("IZ6GJPVVwQWfRhQTuxwrdBfn".to_owned().into_bytes()).unwrap();
+
+    let db = setup().await;
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    REPO.create_totp_device(&mut txn, &expected, &secret)
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let devices = REPO
+        .list_totp_devices_by_user(&mut txn, ADMIN2.user.id)
+        .await
+        .unwrap();
+    assert_eq!(devices, [ADMIN2_TOTP_1.clone(), expected]);
+}
+
 #[tokio::test]
 async fn update_totp_device() {
-    let expected = FOO_TOTP_1.clone().with(|x| x.enabled = true);
+    let now = FOO_TOTP_1.created_at + Duration::from_secs(3600);
+    let expected = FOO_TOTP_1.clone().with(|x| {
+        x.name = "My Phone".try_into().unwrap();
+        x.enabled = true;
+        x.last_used_at = Some(now);
+    });
 
     let db = setup().await;
 
@@ -79,7 +131,10 @@ async fn update_totp_device() {
         .update_totp_device(
             &mut txn,
             expected.id,
-            TotpDevicePatchRef::new().update_enabled(&true),
+            TotpDevicePatchRef::new()
+                .update_name(&expected.name)
+                .update_enabled(&true)
+                .update_last_used_at(&Some(now)),
         )
         .await
         .unwrap();
@@ -94,6 +149,31 @@ async fn update_totp_device() {
     assert_eq!(result, [expected]);
 }
 
+#[tokio::test]
+async fn delete_totp_device() {
+    let db = setup().await;
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO
+        .delete_totp_device(&mut txn, FOO_TOTP_1.id)
+        .await
+        .unwrap();
+    assert!(result);
+    let result = REPO
+        .delete_totp_device(&mut txn, FOO_TOTP_1.id)
+        .await
+        .unwrap();
+    assert!(!result);
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO
+        .list_totp_devices_by_user(&mut txn, FOO.user.id)
+        .await
+        .unwrap();
+    assert_eq!(result, []);
+}
+
 #[tokio::test]
 async fn delete_totp_devices() {
     let db = setup().await;
@@ -113,6 +193,42 @@ async fn delete_totp_devices() {
     assert_eq!(result, []);
 }
 
+#[tokio::test]
+async fn delete_disabled_totp_devices_by_created_at() {
+    let db = setup().await;
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO
+        .delete_disabled_totp_devices_by_created_at(&mut txn, FOO_TOTP_1.created_at)
+        .await
+        .unwrap();
+    assert_eq!(result, 0);
+
+    let result = REPO
+        .delete_disabled_totp_devices_by_created_at(
+            &mut txn,
+            FOO_TOTP_1.created_at + Duration::from_secs(1),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result, 1);
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+
+    let result = REPO
+        .list_totp_devices_by_user(&mut txn, FOO.user.id)
+        .await
+        .unwrap();
+    assert_eq!(result, []);
+
+    let result = REPO
+        .list_totp_devices_by_user(&mut txn, ADMIN2.user.id)
+        .await
+        .unwrap();
+    assert_eq!(result, std::slice::from_ref(&*ADMIN2_TOTP_1));
+}
+
 #[tokio::test]
 async fn save_and_get_totp_device_secret() {
     let secret = TotpSecret::try_new("IZ6GJPVVwQWfRhQTuxwrdBfn".to_owned().into_bytes()).unwrap();
@@ -147,7 +263,7 @@ async fn list_enabled_totp_device_secrets() {
         .list_enabled_totp_device_secrets_by_user(&mut txn, ADMIN2_TOTP_1.user_id)
         .await
         .unwrap();
-    assert_eq!(result, [admin_secret]);
+    assert_eq!(result, [(ADMIN2_TOTP_1.id, admin_secret)]);
 
     let result = REPO
         .list_enabled_totp_device_secrets_by_user(&mut txn, FOO_TOTP_1.user_id)
@@ -193,3 +309,155 @@ async fn save_get_and_delete_mfa_recovery_code_hash() {
         .unwrap();
     assert_eq!(result, None);
 }
+
+#[tokio::test]
+async fn list_webauthn_credentials_by_user() {
+    let db = setup().await;
+    let mut txn = db.begin_transaction().await.unwrap();
+
+    let result = REPO
+        .list_webauthn_credentials_by_user(&mut txn, ADMIN2.user.id)
+        .await
+        .unwrap();
+    assert_eq!(result, std::slice::from_ref(&*ADMIN2_WEBAUTHN_1));
+
+    let result = REPO
+        .list_webauthn_credentials_by_user(&mut txn, FOO.user.id)
+        .await
+        .unwrap();
+    assert_eq!(result, []);
+}
+
+#[tokio::test]
+async fn get_webauthn_credential_by_raw_id() {
+    let db = setup().await;
+    let mut txn = db.begin_transaction().await.unwrap();
+
+    let result = REPO
+        .get_webauthn_credential_by_raw_id(&mut txn, &ADMIN2_WEBAUTHN_1.raw_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        Some((
+            ADMIN2_WEBAUTHN_1.clone(),
+            WEBAUTHN_CREDENTIAL_KEYS[&ADMIN2_WEBAUTHN_1.id].clone()
+        ))
+    );
+
+    let result = REPO
+        .get_webauthn_credential_by_raw_id(&mut txn, &b"unknown".to_vec().try_into().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(result, None);
+}
+
+#[tokio::test]
+async fn create_webauthn_credential() {
+    let expected = WebauthnCredential {
+        id: UUID1.into(),
+        user_id: FOO.user.id,
+        raw_id: b"the credential id".to_vec().try_into().unwrap(),
+        name: "Phone".try_into().unwrap(),
+        created_at: FOO.user.created_at,
+        last_used_at: None,
+    };
+    let key = WebauthnCredentialKey {
+        public_key: b"the public key".to_vec().into(),
+        sign_count: 42,
+    };
+
+    let db = setup().await;
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    REPO.create_webauthn_credential(&mut txn, &expected, &key)
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO
+        .get_webauthn_credential_by_raw_id(&mut txn, &expected.raw_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Some((expected, key)));
+}
+
+#[tokio::test]
+async fn update_webauthn_credential() {
+    let expected = ADMIN2_WEBAUTHN_1.clone().with(|x| {
+        x.name = "Security Key".try_into().unwrap();
+        x.last_used_at = Some(FOO.user.created_at);
+    });
+
+    let db = setup().await;
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO
+        .update_webauthn_credential(
+            &mut txn,
+            expected.id,
+            WebauthnCredentialPatchRef::new()
+                .update_name(&expected.name)
+                .update_last_used_at(&expected.last_used_at),
+        )
+        .await
+        .unwrap();
+    assert!(result);
+    REPO.save_webauthn_credential_sign_count(&mut txn, expected.id, 1337)
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let (credential, key) = REPO
+        .get_webauthn_credential_by_raw_id(&mut txn, &expected.raw_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(credential, expected);
+    assert_eq!(key.sign_count, 1337);
+}
+
+#[tokio::test]
+async fn delete_webauthn_credential() {
+    let db = setup().await;
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO
+        .delete_webauthn_credential(&mut txn, ADMIN2_WEBAUTHN_1.id)
+        .await
+        .unwrap();
+    assert!(result);
+    let result = REPO
+        .delete_webauthn_credential(&mut txn, ADMIN2_WEBAUTHN_1.id)
+        .await
+        .unwrap();
+    assert!(!result);
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO
+        .list_webauthn_credentials_by_user(&mut txn, ADMIN2.user.id)
+        .await
+        .unwrap();
+    assert_eq!(result, []);
+}
+
+#[tokio::test]
+async fn delete_webauthn_credentials_by_user() {
+    let db = setup().await;
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    REPO.delete_webauthn_credentials_by_user(&mut txn, ADMIN2.user.id)
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO
+        .list_webauthn_credentials_by_user(&mut txn, ADMIN2.user.id)
+        .await
+        .unwrap();
+    assert_eq!(result, []);
+}
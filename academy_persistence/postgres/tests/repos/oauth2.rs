@@ -3,7 +3,7 @@ use academy_demo::{
     user::{BAR, FOO},
     UUID1, UUID2,
 };
-use academy_models::oauth2::{OAuth2Link, OAuth2UserInfo};
+use academy_models::oauth2::{OAuth2EncryptedTokens, OAuth2Link, OAuth2UserInfo};
 use academy_persistence_contracts::{
     oauth2::{OAuth2RepoError, OAuth2Repository},
     Database, Transaction,
@@ -24,7 +24,7 @@ async fn list_links_by_user() {
         .list_links_by_user(&mut txn, FOO.user.id)
         .await
         .unwrap();
-    assert_eq!(result, [FOO_OAUTH2_LINK_1.clone()]);
+    assert_eq!(result, std::slice::from_ref(&*FOO_OAUTH2_LINK_1));
 }
 
 #[tokio::test]
@@ -39,6 +39,28 @@ async fn get_link() {
     assert_eq!(result, None);
 }
 
+#[tokio::test]
+async fn get_link_by_remote_user() {
+    let db = setup().await;
+    let mut txn = db.begin_transaction().await.unwrap();
+
+    let result = REPO
+        .get_link_by_remote_user(
+            &mut txn,
+            &FOO_OAUTH2_LINK_1.provider_id,
+            &FOO_OAUTH2_LINK_1.remote_user.id,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.unwrap(), *FOO_OAUTH2_LINK_1);
+
+    let result = REPO
+        .get_link_by_remote_user(&mut txn, &"other".into(), &FOO_OAUTH2_LINK_1.remote_user.id)
+        .await
+        .unwrap();
+    assert_eq!(result, None);
+}
+
 #[tokio::test]
 async fn create_link() {
     let link = OAuth2Link {
@@ -99,3 +121,54 @@ async fn delete_link() {
         .unwrap();
     assert!(!result);
 }
+
+#[tokio::test]
+async fn link_tokens() {
+    let tokens = OAuth2EncryptedTokens {
+        access_token: b"access token".to_vec(),
+        refresh_token: Some(b"refresh token".to_vec()),
+        expires_at: Some(FOO_OAUTH2_LINK_1.created_at),
+    };
+
+    let db = setup().await;
+    let mut txn = db.begin_transaction().await.unwrap();
+
+    let result = REPO
+        .get_link_tokens(&mut txn, FOO_OAUTH2_LINK_1.id)
+        .await
+        .unwrap();
+    assert_eq!(result, None);
+
+    REPO.save_link_tokens(&mut txn, FOO_OAUTH2_LINK_1.id, &tokens)
+        .await
+        .unwrap();
+    let result = REPO
+        .get_link_tokens(&mut txn, FOO_OAUTH2_LINK_1.id)
+        .await
+        .unwrap();
+    assert_eq!(result.unwrap(), tokens);
+
+    let tokens = OAuth2EncryptedTokens {
+        access_token: b"new access token".to_vec(),
+        refresh_token: None,
+        expires_at: None,
+    };
+    REPO.save_link_tokens(&mut txn, FOO_OAUTH2_LINK_1.id, &tokens)
+        .await
+        .unwrap();
+    let result = REPO
+        .get_link_tokens(&mut txn, FOO_OAUTH2_LINK_1.id)
+        .await
+        .unwrap();
+    assert_eq!(result.unwrap(), tokens);
+
+    // tokens are deleted together with the link
+    REPO.delete_link(&mut txn, FOO_OAUTH2_LINK_1.id)
+        .await
+        .unwrap();
+    let result = REPO
+        .get_link_tokens(&mut txn, FOO_OAUTH2_LINK_1.id)
+        .await
+        .unwrap();
+    assert_eq!(result, None);
+}
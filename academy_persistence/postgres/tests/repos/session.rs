@@ -5,13 +5,19 @@ use academy_demo::{
     user::{ADMIN, ALL_USERS, FOO},
     SHA256HASH1, SHA256HASH2, UUID1,
 };
-use academy_models::session::{Session, SessionRefreshTokenHash};
+use academy_models::{
+    pagination::Pagination,
+    session::{Session, SessionRefreshTokenHash},
+};
 use academy_persistence_contracts::{session::SessionRepository, Database, Transaction};
 use academy_persistence_postgres::session::PostgresSessionRepository;
 use academy_utils::patch::Patch;
 use pretty_assertions::assert_eq;
 
-use crate::common::setup;
+use crate::{
+    common::setup,
+    repos::{make_slice, sliced},
+};
 
 const REPO: PostgresSessionRepository = PostgresSessionRepository;
 
@@ -74,6 +80,49 @@ async fn list_by_user() {
     }
 }
 
+#[tokio::test]
+async fn list_by_user_paginated() {
+    let db = setup().await;
+    let mut txn = db.begin_transaction().await.unwrap();
+
+    for &user_composite in &*ALL_USERS {
+        let mut expected = ALL_SESSIONS
+            .iter()
+            .filter(|s| s.user_id == user_composite.user.id)
+            .copied()
+            .cloned()
+            .collect::<Vec<_>>();
+        expected.sort_by_key(|s| (s.created_at, *s.id));
+
+        for slice in [make_slice(100, 0), make_slice(1, 0), make_slice(100, 1)] {
+            let result = REPO
+                .list_by_user_paginated(&mut txn, user_composite.user.id, slice.into())
+                .await
+                .unwrap();
+            assert_eq!(result, sliced(&expected, slice));
+        }
+
+        let mut result = Vec::new();
+        let mut after = None;
+        loop {
+            let pagination = Pagination::Cursor {
+                limit: 1.try_into().unwrap(),
+                after,
+            };
+            let page = REPO
+                .list_by_user_paginated(&mut txn, user_composite.user.id, pagination)
+                .await
+                .unwrap();
+            after = pagination.next_cursor(&page);
+            result.extend(page);
+            if after.is_none() {
+                break;
+            }
+        }
+        assert_eq!(result, expected);
+    }
+}
+
 #[tokio::test]
 async fn create() {
     let db = setup().await;
@@ -82,6 +131,7 @@ async fn create() {
         id: UUID1.into(),
         user_id: ADMIN.user.id,
         device_name: Some("some device name".try_into().unwrap()),
+        impersonator_id: None,
         created_at: ADMIN.user.created_at + Duration::from_secs(10 * 3600),
         updated_at: ADMIN.user.created_at + Duration::from_secs(7 * 24 * 3600),
     };
@@ -234,3 +284,57 @@ async fn refresh_token_hash() {
         .unwrap();
     assert_eq!(result, None);
 }
+
+#[tokio::test]
+async fn retired_refresh_token_hash() {
+    let db = setup().await;
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    REPO.retire_refresh_token_hash(
+        &mut txn,
+        FOO_1.id,
+        (*SHA256HASH1).into(),
+        FOO_1.updated_at,
+        2,
+    )
+    .await
+    .unwrap();
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO
+        .get_by_retired_refresh_token_hash(&mut txn, (*SHA256HASH1).into())
+        .await
+        .unwrap();
+    assert_eq!(result.unwrap(), *FOO_1);
+
+    let result = REPO
+        .get_by_retired_refresh_token_hash(&mut txn, (*SHA256HASH2).into())
+        .await
+        .unwrap();
+    assert_eq!(result, None);
+
+    REPO.retire_refresh_token_hash(
+        &mut txn,
+        FOO_1.id,
+        (*SHA256HASH2).into(),
+        FOO_1.updated_at + Duration::from_secs(60),
+        1,
+    )
+    .await
+    .unwrap();
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO
+        .get_by_retired_refresh_token_hash(&mut txn, (*SHA256HASH1).into())
+        .await
+        .unwrap();
+    assert_eq!(result, None);
+
+    let result = REPO
+        .get_by_retired_refresh_token_hash(&mut txn, (*SHA256HASH2).into())
+        .await
+        .unwrap();
+    assert_eq!(result.unwrap(), *FOO_1);
+}
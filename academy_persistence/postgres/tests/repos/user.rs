@@ -1,17 +1,24 @@
-use std::sync::LazyLock;
+use std::{collections::BTreeSet, sync::LazyLock, time::Duration};
 
 use academy_demo::{
-    oauth2::FOO_OAUTH2_LINK_1,
-    user::{ADMIN, ADMIN2, ALL_USERS, BAR, FOO},
+    oauth2::{FOO_OAUTH2_LINK_1, TEST_OAUTH2_PROVIDER_ID},
+    user::{ADMIN, ADMIN2, ALL_USERS, BAR, FOO, FOO_AVATAR},
     UUID1,
 };
-use academy_models::user::{User, UserComposite, UserDetails, UserFilter};
+use academy_models::{
+    pagination::{Pagination, SortDirection},
+    role::Role,
+    user::{
+        User, UserComposite, UserDetails, UserFilter, UserProfile, UserProfileVisibility, UserSort,
+        UserSortBy,
+    },
+};
 use academy_persistence_contracts::{
     user::{UserRepoError, UserRepository},
     Database, Transaction,
 };
 use academy_persistence_postgres::user::PostgresUserRepository;
-use academy_utils::{assert_matches, patch::Patch};
+use academy_utils::{assert_matches, patch::Patch, Apply};
 
 use crate::{
     common::setup,
@@ -42,16 +49,53 @@ static FILTER_TESTS: LazyLock<Vec<(UserFilter, Vec<&UserComposite>)>> = LazyLock
         (filter!(email: "admin"), vec![&ADMIN, &ADMIN2]),
         (filter!(email_verified: true), vec![&ADMIN, &ADMIN2, &FOO]),
         (filter!(email_verified: false), vec![&BAR]),
-        (filter!(admin: true), vec![&ADMIN, &ADMIN2]),
-        (filter!(admin: false), vec![&FOO, &BAR]),
+        (filter!(role: Role::Superuser), vec![&ADMIN, &ADMIN2]),
+        (filter!(role: Role::Support), vec![]),
         (filter!(enabled: true), vec![&ADMIN, &ADMIN2, &FOO]),
         (filter!(enabled: false), vec![&BAR]),
         (filter!(mfa_enabled: true), vec![&ADMIN2]),
         (filter!(mfa_enabled: false), vec![&ADMIN, &FOO, &BAR]),
         (filter!(newsletter: true), vec![&ADMIN2, &FOO]),
         (filter!(newsletter: false), vec![&ADMIN, &BAR]),
-        (filter!(admin: false, enabled: true), vec![&FOO]),
-        (filter!(name: "2", admin: true), vec![&ADMIN2]),
+        (
+            filter!(role: Role::Superuser, enabled: true),
+            vec![&ADMIN, &ADMIN2],
+        ),
+        (filter!(name: "2", role: Role::Superuser), vec![&ADMIN2]),
+        (filter!(password_login: true), ALL_USERS.clone()),
+        (filter!(password_login: false), vec![]),
+        (filter!(oauth2_login: true), vec![&FOO]),
+        (filter!(oauth2_login: false), vec![&ADMIN, &ADMIN2, &BAR]),
+        (
+            filter!(oauth2_provider: TEST_OAUTH2_PROVIDER_ID.clone()),
+            vec![&FOO],
+        ),
+        (filter!(oauth2_provider: "does-not-exist"), vec![]),
+        (filter!(can_receive_coins: true), vec![&FOO]),
+        (
+            filter!(can_receive_coins: false),
+            vec![&ADMIN, &ADMIN2, &BAR],
+        ),
+        (
+            filter!(created_after: FOO.user.created_at),
+            vec![&FOO, &BAR],
+        ),
+        (
+            filter!(created_before: FOO.user.created_at),
+            vec![&ADMIN, &ADMIN2],
+        ),
+        (
+            filter!(created_after: FOO.user.created_at, created_before: BAR.user.created_at),
+            vec![&FOO],
+        ),
+        (
+            filter!(last_login_after: FOO.user.last_login.unwrap() + Duration::from_secs(1)),
+            vec![&ADMIN, &ADMIN2],
+        ),
+        (
+            filter!(last_login_before: ADMIN.user.last_login.unwrap()),
+            vec![&FOO],
+        ),
     ]
 });
 
@@ -72,17 +116,262 @@ async fn list_composites() {
     let mut txn = db.begin_transaction().await.unwrap();
 
     for (filter, expected) in &*FILTER_TESTS {
-        let slice = make_slice(100, 0);
-        let result = REPO.list_composites(&mut txn, filter, slice).await.unwrap();
-        assert_eq!(&result.iter().collect::<Vec<_>>(), sliced(expected, slice));
+        let mut expected = expected.clone();
+        expected.sort_by_key(|u| (u.user.created_at, *u.user.id));
+
+        for slice in [make_slice(100, 0), make_slice(2, 0), make_slice(100, 1)] {
+            let result = REPO
+                .list_composites(&mut txn, filter, UserSort::default(), slice.into())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(&result.iter().collect::<Vec<_>>(), sliced(&expected, slice));
+        }
+    }
+}
+
+#[tokio::test]
+async fn list_composites_cursor() {
+    let db = setup().await;
+    let mut txn = db.begin_transaction().await.unwrap();
+
+    for (filter, expected) in &*FILTER_TESTS {
+        let mut expected = expected.clone();
+        expected.sort_by_key(|u| (u.user.created_at, *u.user.id));
+
+        for limit in [1, 2, 100] {
+            let mut result = Vec::new();
+            let mut pagination = Pagination::Cursor {
+                limit: limit.try_into().unwrap(),
+                after: None,
+            };
+            loop {
+                let page = REPO
+                    .list_composites(&mut txn, filter, UserSort::default(), pagination)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                assert!(page.len() as u64 <= limit);
+                let next_cursor = pagination.next_cursor(&page);
+                result.extend(page);
+                let Some(after) = next_cursor else { break };
+                pagination = Pagination::Cursor {
+                    limit: limit.try_into().unwrap(),
+                    after: Some(after),
+                };
+            }
+            assert_eq!(&result.iter().collect::<Vec<_>>(), &expected);
+        }
+    }
+}
+
+#[tokio::test]
+async fn list_composites_sorted() {
+    let db = setup().await;
+    let mut txn = db.begin_transaction().await.unwrap();
+
+    for by in [
+        UserSortBy::CreatedAt,
+        UserSortBy::Name,
+        UserSortBy::LastLogin,
+    ] {
+        let mut expected = ALL_USERS.clone();
+        match by {
+            UserSortBy::CreatedAt => expected.sort_by_key(|u| (u.user.created_at, *u.user.id)),
+            UserSortBy::Name => expected.sort_by_key(|u| (u.user.name.to_lowercase(), *u.user.id)),
+            UserSortBy::LastLogin => expected.sort_by_key(|u| (u.user.last_login, *u.user.id)),
+        }
+
+        for direction in [SortDirection::Asc, SortDirection::Desc] {
+            if direction == SortDirection::Desc {
+                expected.reverse();
+            }
+            let sort = UserSort { by, direction };
+
+            let result = REPO
+                .list_composites(&mut txn, &filter!(), sort, make_slice(100, 0).into())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(result.iter().collect::<Vec<_>>(), expected);
+
+            let mut result = Vec::new();
+            let mut after = None;
+            loop {
+                let pagination = Pagination::Cursor {
+                    limit: 1.try_into().unwrap(),
+                    after,
+                };
+                let page = REPO
+                    .list_composites(&mut txn, &filter!(), sort, pagination)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                after = pagination.next_cursor(&page);
+                result.extend(page);
+                if after.is_none() {
+                    break;
+                }
+            }
+            assert_eq!(result.iter().collect::<Vec<_>>(), expected);
+        }
+    }
+}
+
+#[tokio::test]
+async fn list_composites_cursor_deleted() {
+    let db = setup().await;
+    let mut txn = db.begin_transaction().await.unwrap();
+
+    REPO.delete(&mut txn, FOO.user.id).await.unwrap();
+
+    for by in [
+        UserSortBy::CreatedAt,
+        UserSortBy::Name,
+        UserSortBy::LastLogin,
+    ] {
+        let sort = UserSort {
+            by,
+            direction: SortDirection::Asc,
+        };
+        let pagination = Pagination::Cursor {
+            limit: 100.try_into().unwrap(),
+            after: Some((&*FOO).into()),
+        };
+
+        let result = REPO
+            .list_composites(&mut txn, &filter!(), sort, pagination)
+            .await
+            .unwrap();
+
+        // the cursor of users sorted by creation time does not depend on the
+        // deleted user
+        assert_eq!(result.is_some(), by == UserSortBy::CreatedAt);
+    }
+}
+
+#[tokio::test]
+async fn search_profiles() {
+    let db = setup().await;
+    let mut txn = db.begin_transaction().await.unwrap();
+
+    for (query, expected) in [
+        ("foo", vec![&*FOO]),
+        ("FOO", vec![&FOO]),
+        ("administrator", vec![&ADMIN]),
+        ("administrator or administrator2", vec![&ADMIN2, &ADMIN]),
+        ("42", vec![&FOO]),
+        ("blubb baz", vec![&FOO]),
+        ("blubb -baz", vec![]),
+        ("interesting", vec![]),
+        ("", vec![]),
+    ] {
+        let query = query.try_into().unwrap();
+
+        let count = REPO.count_search_profiles(&mut txn, &query).await.unwrap();
+        assert_eq!(count, expected.len() as u64);
+
+        let mut result = REPO
+            .search_profiles(&mut txn, &query, make_slice(100, 0))
+            .await
+            .unwrap();
+        result.sort_by_key(|u| (u.user.created_at, *u.user.id));
+        assert_eq!(result.iter().collect::<Vec<_>>(), expected);
+    }
+}
+
+#[tokio::test]
+async fn search_profiles_ranking() {
+    let db = setup().await;
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    REPO.update_profile(
+        &mut txn,
+        ADMIN.user.id,
+        UserProfile {
+            bio: "foo".try_into().unwrap(),
+            ..ADMIN.profile.clone()
+        }
+        .as_patch_ref(),
+    )
+    .await
+    .unwrap();
+    REPO.update_profile(
+        &mut txn,
+        ADMIN2.user.id,
+        UserProfile {
+            tags: vec!["foo".try_into().unwrap()].try_into().unwrap(),
+            ..ADMIN2.profile.clone()
+        }
+        .as_patch_ref(),
+    )
+    .await
+    .unwrap();
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let query = "foo".try_into().unwrap();
+    let result = REPO
+        .search_profiles(&mut txn, &query, make_slice(100, 0))
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|u| u.user.id)
+        .collect::<Vec<_>>();
+    assert_eq!(result, [FOO.user.id, ADMIN2.user.id, ADMIN.user.id]);
+
+    let result = REPO
+        .search_profiles(&mut txn, &query, make_slice(1, 1))
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|u| u.user.id)
+        .collect::<Vec<_>>();
+    assert_eq!(result, [ADMIN2.user.id]);
+}
+
+#[tokio::test]
+async fn search_profiles_hidden_fields() {
+    let db = setup().await;
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    REPO.update_profile(
+        &mut txn,
+        FOO.user.id,
+        UserProfile {
+            visibility: UserProfileVisibility {
+                tags: false,
+                ..Default::default()
+            },
+            ..FOO.profile.clone()
+        }
+        .as_patch_ref(),
+    )
+    .await
+    .unwrap();
+    txn.commit().await.unwrap();
 
-        let slice = make_slice(2, 0);
-        let result = REPO.list_composites(&mut txn, filter, slice).await.unwrap();
-        assert_eq!(&result.iter().collect::<Vec<_>>(), sliced(expected, slice));
+    let mut txn = db.begin_transaction().await.unwrap();
+    for (query, expected) in [
+        ("foo", vec![FOO.user.id]),
+        ("42", vec![FOO.user.id]),
+        ("baz", vec![]),
+        ("blubb baz", vec![]),
+        ("blubb -baz", vec![FOO.user.id]),
+    ] {
+        let query = query.try_into().unwrap();
+
+        let count = REPO.count_search_profiles(&mut txn, &query).await.unwrap();
+        assert_eq!(count, expected.len() as u64);
 
-        let slice = make_slice(100, 1);
-        let result = REPO.list_composites(&mut txn, filter, slice).await.unwrap();
-        assert_eq!(&result.iter().collect::<Vec<_>>(), sliced(expected, slice));
+        let result = REPO
+            .search_profiles(&mut txn, &query, make_slice(100, 0))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|u| u.user.id)
+            .collect::<Vec<_>>();
+        assert_eq!(result, expected);
     }
 }
 
@@ -292,6 +581,7 @@ async fn update_user() {
             name: "othername".try_into().unwrap(),
             email: Some("other@email".parse().unwrap()),
             created_at: BAR.user.created_at,
+            roles: BTreeSet::from([Role::Support, Role::Billing]),
             ..FOO.user.clone()
         },
         ..BAR.clone()
@@ -359,7 +649,16 @@ async fn update_profile() {
     let db = setup().await;
 
     let expected = UserComposite {
-        profile: FOO.profile.clone(),
+        profile: UserProfile {
+            visibility: UserProfileVisibility {
+                bio: false,
+                tags: true,
+                created_at: false,
+                last_login: true,
+            },
+            avatar: None,
+            ..FOO.profile.clone()
+        },
         ..BAR.clone()
     };
 
@@ -380,6 +679,41 @@ async fn update_profile() {
     assert_eq!(result, expected);
 }
 
+#[tokio::test]
+async fn update_avatar() {
+    let db = setup().await;
+
+    let expected = BAR
+        .clone()
+        .with(|x| x.profile.avatar = Some(FOO_AVATAR.clone()));
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO
+        .update_avatar(&mut txn, BAR.user.id, &Some(FOO_AVATAR.clone()))
+        .await
+        .unwrap();
+    assert!(result);
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO.get_composite(&mut txn, BAR.user.id).await.unwrap();
+    assert_eq!(result.unwrap(), expected);
+
+    let result = REPO
+        .update_avatar(&mut txn, BAR.user.id, &None)
+        .await
+        .unwrap();
+    assert!(result);
+    let result = REPO.get_composite(&mut txn, BAR.user.id).await.unwrap();
+    assert_eq!(result.unwrap(), *BAR);
+
+    let result = REPO
+        .update_avatar(&mut txn, UUID1.into(), &None)
+        .await
+        .unwrap();
+    assert!(!result);
+}
+
 #[tokio::test]
 async fn update_invoice_info() {
     let db = setup().await;
@@ -423,6 +757,194 @@ async fn delete() {
     assert!(!result);
 }
 
+#[tokio::test]
+async fn unverified() {
+    let db = setup().await;
+
+    // the registration itself logs the user in
+    let expected = BAR.clone().with(|x| {
+        x.user.email = Some("bar@example.com".parse().unwrap());
+        x.user.last_login = Some(BAR.user.created_at);
+    });
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    REPO.update(&mut txn, BAR.user.id, expected.user.as_patch_ref())
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO
+        .list_unverified_composites(&mut txn, BAR.user.created_at)
+        .await
+        .unwrap();
+    assert_eq!(result, []);
+
+    let created_at = BAR.user.created_at + Duration::from_secs(1);
+    let result = REPO
+        .list_unverified_composites(&mut txn, created_at)
+        .await
+        .unwrap();
+    assert_eq!(result, [expected]);
+
+    let result = REPO.delete_unverified(&mut txn, created_at).await.unwrap();
+    assert_eq!(result, 1);
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO.get_composite(&mut txn, BAR.user.id).await.unwrap();
+    assert_eq!(result, None);
+    let result = REPO.get_composite(&mut txn, FOO.user.id).await.unwrap();
+    assert_eq!(result.unwrap(), *FOO);
+}
+
+#[tokio::test]
+async fn unverified_changed_email() {
+    let db = setup().await;
+
+    let expected = FOO.clone().with(|x| {
+        x.user.email = Some("new.foo@example.com".parse().unwrap());
+        x.user.email_verified = false;
+    });
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    REPO.update(&mut txn, FOO.user.id, expected.user.as_patch_ref())
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let created_at = FOO.user.created_at + Duration::from_secs(1);
+    let result = REPO
+        .list_unverified_composites(&mut txn, created_at)
+        .await
+        .unwrap();
+    assert_eq!(result, []);
+
+    let result = REPO.delete_unverified(&mut txn, created_at).await.unwrap();
+    assert_eq!(result, 0);
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO.get_composite(&mut txn, FOO.user.id).await.unwrap();
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn unverified_logged_in() {
+    let db = setup().await;
+
+    let expected = BAR.clone().with(|x| {
+        x.user.email = Some("bar@example.com".parse().unwrap());
+        x.user.last_login = Some(BAR.user.created_at + Duration::from_secs(3600));
+    });
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    REPO.update(&mut txn, BAR.user.id, expected.user.as_patch_ref())
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let created_at = BAR.user.created_at + Duration::from_secs(1);
+    let result = REPO
+        .list_unverified_composites(&mut txn, created_at)
+        .await
+        .unwrap();
+    assert_eq!(result, []);
+
+    let result = REPO.delete_unverified(&mut txn, created_at).await.unwrap();
+    assert_eq!(result, 0);
+}
+
+#[tokio::test]
+async fn delete_by_deleted_at() {
+    let db = setup().await;
+
+    let deleted_at = FOO.user.created_at + Duration::from_secs(3600);
+    let expected = FOO.clone().with(|x| x.user.deleted_at = Some(deleted_at));
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    REPO.update(&mut txn, FOO.user.id, expected.user.as_patch_ref())
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO.get_composite(&mut txn, FOO.user.id).await.unwrap();
+    assert_eq!(result.unwrap(), expected);
+
+    let result = REPO
+        .list_deleted_composites(&mut txn, deleted_at - Duration::from_secs(1))
+        .await
+        .unwrap();
+    assert_eq!(result, []);
+
+    let result = REPO
+        .list_deleted_composites(&mut txn, deleted_at)
+        .await
+        .unwrap();
+    assert_eq!(result, [expected]);
+
+    let result = REPO
+        .delete_by_deleted_at(&mut txn, deleted_at - Duration::from_secs(1))
+        .await
+        .unwrap();
+    assert_eq!(result, 0);
+
+    let result = REPO
+        .delete_by_deleted_at(&mut txn, deleted_at)
+        .await
+        .unwrap();
+    assert_eq!(result, 1);
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO.get_composite(&mut txn, FOO.user.id).await.unwrap();
+    assert_eq!(result, None);
+    let result = REPO.get_composite(&mut txn, BAR.user.id).await.unwrap();
+    assert_eq!(result.unwrap(), *BAR);
+}
+
+#[tokio::test]
+async fn export_requests() {
+    let db = setup().await;
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO.take_export_request(&mut txn).await.unwrap();
+    assert_eq!(result, None);
+
+    let requested_at = FOO.user.created_at;
+    REPO.create_export_request(&mut txn, BAR.user.id, requested_at + Duration::from_secs(1))
+        .await
+        .unwrap();
+    REPO.create_export_request(&mut txn, FOO.user.id, requested_at)
+        .await
+        .unwrap();
+    REPO.create_export_request(&mut txn, FOO.user.id, requested_at + Duration::from_secs(2))
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO.take_export_request(&mut txn).await.unwrap();
+    assert_eq!(result, Some(FOO.user.id));
+
+    let mut txn2 = db.begin_transaction().await.unwrap();
+    let result = REPO.take_export_request(&mut txn2).await.unwrap();
+    assert_eq!(result, Some(BAR.user.id));
+    let result = REPO.take_export_request(&mut txn2).await.unwrap();
+    assert_eq!(result, None);
+    txn2.rollback().await.unwrap();
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO.take_export_request(&mut txn).await.unwrap();
+    assert_eq!(result, Some(BAR.user.id));
+    let result = REPO.take_export_request(&mut txn).await.unwrap();
+    assert_eq!(result, None);
+}
+
 #[tokio::test]
 async fn password() {
     let db = setup().await;
@@ -465,3 +987,39 @@ async fn password() {
     let result = REPO.get_password_hash(&mut txn, FOO.user.id).await.unwrap();
     assert_eq!(result, None);
 }
+
+#[tokio::test]
+async fn count_password_hashes_by_parameters() {
+    let db = setup().await;
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    REPO.save_password_hash(
+        &mut txn,
+        FOO.user.id,
+        "$argon2id$v=19$m=65536,t=3,p=4$c2FsdHNhbHQ$aGFzaGhhc2g".into(),
+    )
+    .await
+    .unwrap();
+    REPO.save_password_hash(
+        &mut txn,
+        BAR.user.id,
+        "$argon2i$v=16$m=4096,t=3,p=1$c2FsdHNhbHQ$aGFzaGhhc2g".into(),
+    )
+    .await
+    .unwrap();
+    txn.commit().await.unwrap();
+
+    let mut txn = db.begin_transaction().await.unwrap();
+    let result = REPO
+        .count_password_hashes_by_parameters(&mut txn)
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        [
+            ("$argon2id$v=19$m=19456,t=2,p=1".into(), 2),
+            ("$argon2i$v=16$m=4096,t=3,p=1".into(), 1),
+            ("$argon2id$v=19$m=65536,t=3,p=4".into(), 1),
+        ]
+    );
+}
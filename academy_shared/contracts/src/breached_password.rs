@@ -0,0 +1,23 @@
+use std::future::Future;
+
+use academy_models::Sensitive;
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait BreachedPasswordService: Send + Sync + 'static {
+    /// Check whether a password has appeared in a known data breach.
+    fn is_breached(
+        &self,
+        password: Sensitive<String>,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+}
+
+#[cfg(feature = "mock")]
+impl MockBreachedPasswordService {
+    pub fn with_is_breached(mut self, password: String, result: bool) -> Self {
+        self.expect_is_breached()
+            .once()
+            .with(mockall::predicate::eq(Sensitive(password)))
+            .return_once(move |_| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+}
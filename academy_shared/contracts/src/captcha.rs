@@ -12,6 +12,10 @@ pub trait CaptchaService: Send + Sync + 'static {
     fn get_recaptcha_sitekey<'a>(&'a self) -> Option<&'a str>;
 
     /// Verify the given reCAPTCHA response.
+    #[allow(
+        clippy::needless_lifetimes,
+        reason = "explicit lifetime needed for automock"
+    )]
     fn check<'a>(
         &self,
         response: Option<&'a str>,
@@ -0,0 +1,50 @@
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait EncryptionService: Send + Sync + 'static {
+    /// Encrypt the given data using the current encryption key.
+    ///
+    /// The `associated_data` is authenticated but not encrypted and must be
+    /// passed to [`EncryptionService::decrypt`] again. It should identify the
+    /// context of the data (e.g. the record it belongs to), so that encrypted
+    /// data cannot be moved to a different context.
+    fn encrypt(&self, plaintext: &[u8], associated_data: &[u8]) -> anyhow::Result<Vec<u8>>;
+
+    /// Decrypt data that has been encrypted by [`EncryptionService::encrypt`]
+    /// with the same `associated_data` using any of the configured encryption
+    /// keys.
+    fn decrypt(&self, ciphertext: &[u8], associated_data: &[u8]) -> anyhow::Result<Vec<u8>>;
+}
+
+#[cfg(feature = "mock")]
+impl MockEncryptionService {
+    pub fn with_encrypt(
+        mut self,
+        plaintext: Vec<u8>,
+        associated_data: Vec<u8>,
+        result: Vec<u8>,
+    ) -> Self {
+        self.expect_encrypt()
+            .once()
+            .with(
+                mockall::predicate::eq(plaintext),
+                mockall::predicate::eq(associated_data),
+            )
+            .return_once(|_, _| Ok(result));
+        self
+    }
+
+    pub fn with_decrypt(
+        mut self,
+        ciphertext: Vec<u8>,
+        associated_data: Vec<u8>,
+        result: Vec<u8>,
+    ) -> Self {
+        self.expect_decrypt()
+            .once()
+            .with(
+                mockall::predicate::eq(ciphertext),
+                mockall::predicate::eq(associated_data),
+            )
+            .return_once(|_, _| Ok(result));
+        self
+    }
+}
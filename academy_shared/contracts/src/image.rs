@@ -0,0 +1,51 @@
+use std::{future::Future, ops::RangeInclusive};
+
+use thiserror::Error;
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait ImageService: Send + Sync + 'static {
+    /// Decode a PNG, JPEG or WebP image, crop it to a centered square and
+    /// encode it as WebP in each of the given `sizes` (in pixels).
+    ///
+    /// Images whose width or height is not contained in `dimensions` are
+    /// rejected.
+    fn crop_square(
+        &self,
+        data: Vec<u8>,
+        dimensions: RangeInclusive<u32>,
+        sizes: Vec<u32>,
+    ) -> impl Future<Output = Result<Vec<Vec<u8>>, ImageCropSquareError>> + Send;
+}
+
+#[derive(Debug, Error)]
+pub enum ImageCropSquareError {
+    #[error("The image format is not supported.")]
+    UnsupportedFormat,
+    #[error("The image is invalid.")]
+    InvalidImage,
+    #[error("The image is too small or too large.")]
+    InvalidDimensions,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[cfg(feature = "mock")]
+impl MockImageService {
+    pub fn with_crop_square(
+        mut self,
+        data: Vec<u8>,
+        dimensions: RangeInclusive<u32>,
+        sizes: Vec<u32>,
+        result: Result<Vec<Vec<u8>>, ImageCropSquareError>,
+    ) -> Self {
+        self.expect_crop_square()
+            .once()
+            .with(
+                mockall::predicate::eq(data),
+                mockall::predicate::eq(dimensions),
+                mockall::predicate::eq(sizes),
+            )
+            .return_once(|_, _, _| Box::pin(std::future::ready(result)));
+        self
+    }
+}
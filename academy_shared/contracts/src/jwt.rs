@@ -1,5 +1,6 @@
 use std::{fmt::Debug, time::Duration};
 
+use academy_models::auth::JwtPublicKey;
 use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
 
@@ -21,6 +22,9 @@ pub trait JwtService: Send + Sync + 'static {
         &self,
         jwt: &S,
     ) -> Result<T, VerifyJwtError<T>>;
+
+    /// Return the public keys of all keys that are accepted for verification.
+    fn public_keys(&self) -> Vec<JwtPublicKey>;
 }
 
 #[derive(Debug, Error)]
@@ -63,4 +67,9 @@ impl MockJwtService {
             .return_once(|_| result);
         self
     }
+
+    pub fn with_public_keys(mut self, result: Vec<JwtPublicKey>) -> Self {
+        self.expect_public_keys().once().return_once(|| result);
+        self
+    }
 }
@@ -17,6 +17,14 @@ pub trait PasswordService: Send + Sync + 'static {
         password: Sensitive<String>,
         hash: String,
     ) -> impl Future<Output = Result<(), PasswordVerifyError>> + Send;
+
+    /// Check whether the given hash has been created using an outdated
+    /// algorithm or outdated parameters and should be replaced by a new hash
+    /// of the same password.
+    ///
+    /// The salt and hash parts of the PHC string are optional, so this can
+    /// also be used for hash parameters only (e.g. `$argon2id$v=19$m=19456,t=2,p=1`).
+    fn needs_rehash(&self, hash: &str) -> bool;
 }
 
 #[derive(Debug, Error)]
@@ -48,4 +56,12 @@ impl MockPasswordService {
             .return_once(|_, _| Box::pin(std::future::ready(result)));
         self
     }
+
+    pub fn with_needs_rehash(mut self, hash: String, result: bool) -> Self {
+        self.expect_needs_rehash()
+            .once()
+            .with(mockall::predicate::eq(hash))
+            .return_const(result);
+        self
+    }
 }
@@ -0,0 +1,23 @@
+use academy_models::Sensitive;
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait PasswordStrengthService: Send + Sync + 'static {
+    /// Estimate the strength of a password on a scale from 0 (too guessable)
+    /// to 4 (very unguessable). Words in `user_inputs` (e.g. the user's name
+    /// or email address) are treated as easily guessable.
+    fn estimate(&self, password: Sensitive<String>, user_inputs: Vec<String>) -> u8;
+}
+
+#[cfg(feature = "mock")]
+impl MockPasswordStrengthService {
+    pub fn with_estimate(mut self, password: String, user_inputs: Vec<String>, score: u8) -> Self {
+        self.expect_estimate()
+            .once()
+            .with(
+                mockall::predicate::eq(Sensitive(password)),
+                mockall::predicate::eq(user_inputs),
+            )
+            .return_once(move |_, _| score);
+        self
+    }
+}
@@ -0,0 +1,49 @@
+use std::{future::Future, time::Duration};
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait RateLimitService: Send + Sync + 'static {
+    /// Try to take a single token from the bucket identified by the given key.
+    ///
+    /// Buckets are created lazily and start out full.
+    fn acquire(
+        &self,
+        key: &str,
+        policy: RateLimitPolicy,
+    ) -> impl Future<Output = anyhow::Result<RateLimitStatus>> + Send;
+}
+
+/// Parameters of a token bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitPolicy {
+    /// Maximum number of tokens in the bucket
+    pub capacity: u64,
+    /// Time it takes to refill an empty bucket
+    pub period: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// Whether a token could be taken from the bucket
+    pub allowed: bool,
+    /// Number of tokens left in the bucket
+    pub remaining: u64,
+    /// Time until the bucket is full again (if the request has been allowed)
+    /// or until the next token is available (if the request has been denied)
+    pub reset: Duration,
+}
+
+#[cfg(feature = "mock")]
+impl MockRateLimitService {
+    pub fn with_acquire(
+        mut self,
+        key: String,
+        policy: RateLimitPolicy,
+        result: RateLimitStatus,
+    ) -> Self {
+        self.expect_acquire()
+            .once()
+            .with(mockall::predicate::eq(key), mockall::predicate::eq(policy))
+            .return_once(move |_, _| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+}
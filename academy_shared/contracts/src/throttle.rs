@@ -0,0 +1,76 @@
+use std::{future::Future, net::IpAddr};
+
+use chrono::{DateTime, Utc};
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait ThrottleService: Send + Sync + 'static {
+    /// Return the time until which any of the given keys is locked out, if
+    /// any.
+    fn check(
+        &self,
+        keys: &[ThrottleKey],
+    ) -> impl Future<Output = anyhow::Result<Option<DateTime<Utc>>>> + Send;
+
+    /// Record an attempt for each of the given keys.
+    ///
+    /// Returns the time until which any of the keys is locked out if the
+    /// attempt limit has been exceeded (or had already been exceeded before).
+    /// Consecutive lockouts of the same key grow exponentially.
+    fn record(
+        &self,
+        keys: &[ThrottleKey],
+    ) -> impl Future<Output = anyhow::Result<Option<DateTime<Utc>>>> + Send;
+
+    /// Reset the recorded attempts and lockouts of the given key.
+    fn reset(&self, key: &ThrottleKey) -> impl Future<Output = anyhow::Result<()>> + Send;
+}
+
+/// Identifies a counter of attempts to perform a specific action.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ThrottleKey {
+    /// Attempts concerning a single account, identified by name or email
+    /// address
+    Account { action: &'static str, id: String },
+    /// Attempts originating from a single client IP address
+    Ip { action: &'static str, ip: IpAddr },
+}
+
+impl ThrottleKey {
+    pub fn account(action: &'static str, id: &str) -> Self {
+        Self::Account {
+            action,
+            id: id.to_lowercase(),
+        }
+    }
+
+    pub fn ip(action: &'static str, ip: IpAddr) -> Self {
+        Self::Ip { action, ip }
+    }
+}
+
+#[cfg(feature = "mock")]
+impl MockThrottleService {
+    pub fn with_check(mut self, keys: Vec<ThrottleKey>, result: Option<DateTime<Utc>>) -> Self {
+        self.expect_check()
+            .once()
+            .with(mockall::predicate::eq(keys))
+            .return_once(move |_| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_record(mut self, keys: Vec<ThrottleKey>, result: Option<DateTime<Utc>>) -> Self {
+        self.expect_record()
+            .once()
+            .with(mockall::predicate::eq(keys))
+            .return_once(move |_| Box::pin(std::future::ready(Ok(result))));
+        self
+    }
+
+    pub fn with_reset(mut self, key: ThrottleKey) -> Self {
+        self.expect_reset()
+            .once()
+            .with(mockall::predicate::eq(key))
+            .return_once(|_| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
+}
@@ -0,0 +1,137 @@
+use academy_models::{
+    mfa::{
+        WebauthnAssertion, WebauthnAuthenticationOptions, WebauthnChallenge, WebauthnCredentialKey,
+        WebauthnCredentialRawId, WebauthnRegistrationOptions, WebauthnRegistrationResponse,
+    },
+    user::{UserDisplayName, UserId, UserName},
+};
+use thiserror::Error;
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait WebauthnService: Send + Sync + 'static {
+    /// Generate the options for registering a new WebAuthn credential.
+    fn start_registration(
+        &self,
+        user_id: UserId,
+        user_name: UserName,
+        user_display_name: UserDisplayName,
+        exclude_credentials: Vec<WebauthnCredentialRawId>,
+    ) -> WebauthnRegistrationOptions;
+
+    /// Verify the response of the authenticator to a registration request and
+    /// return the id and public key of the new credential.
+    fn finish_registration(
+        &self,
+        challenge: &WebauthnChallenge,
+        response: &WebauthnRegistrationResponse,
+    ) -> Result<(WebauthnCredentialRawId, WebauthnCredentialKey), WebauthnVerifyError>;
+
+    /// Generate the options for authenticating using a WebAuthn credential.
+    fn start_authentication(&self) -> WebauthnAuthenticationOptions;
+
+    /// Return the challenge the given assertion claims to respond to.
+    ///
+    /// The returned challenge has not been verified yet and must only be used
+    /// to look up the expected challenge.
+    fn assertion_challenge(&self, assertion: &WebauthnAssertion) -> Option<WebauthnChallenge>;
+
+    /// Verify the given assertion using the public key of the credential and
+    /// return the new signature counter.
+    fn finish_authentication(
+        &self,
+        challenge: &WebauthnChallenge,
+        assertion: &WebauthnAssertion,
+        key: &WebauthnCredentialKey,
+    ) -> Result<u32, WebauthnVerifyError>;
+}
+
+#[derive(Debug, Error)]
+pub enum WebauthnVerifyError {
+    #[error("The client data is invalid: {0}")]
+    InvalidClientData(&'static str),
+    #[error("The authenticator data is invalid: {0}")]
+    InvalidAuthenticatorData(&'static str),
+    #[error("The public key is invalid or not supported.")]
+    InvalidPublicKey,
+    #[error("The signature is invalid.")]
+    InvalidSignature,
+    #[error("The signature counter did not increase.")]
+    CounterNotIncreased,
+}
+
+#[cfg(feature = "mock")]
+impl MockWebauthnService {
+    pub fn with_start_registration(
+        mut self,
+        user_id: UserId,
+        user_name: UserName,
+        user_display_name: UserDisplayName,
+        exclude_credentials: Vec<WebauthnCredentialRawId>,
+        result: WebauthnRegistrationOptions,
+    ) -> Self {
+        self.expect_start_registration()
+            .once()
+            .with(
+                mockall::predicate::eq(user_id),
+                mockall::predicate::eq(user_name),
+                mockall::predicate::eq(user_display_name),
+                mockall::predicate::eq(exclude_credentials),
+            )
+            .return_once(|_, _, _, _| result);
+        self
+    }
+
+    pub fn with_finish_registration(
+        mut self,
+        challenge: WebauthnChallenge,
+        response: WebauthnRegistrationResponse,
+        result: Result<(WebauthnCredentialRawId, WebauthnCredentialKey), WebauthnVerifyError>,
+    ) -> Self {
+        self.expect_finish_registration()
+            .once()
+            .with(
+                mockall::predicate::eq(challenge),
+                mockall::predicate::eq(response),
+            )
+            .return_once(|_, _| result);
+        self
+    }
+
+    pub fn with_start_authentication(mut self, result: WebauthnAuthenticationOptions) -> Self {
+        self.expect_start_authentication()
+            .once()
+            .with()
+            .return_once(|| result);
+        self
+    }
+
+    pub fn with_assertion_challenge(
+        mut self,
+        assertion: WebauthnAssertion,
+        result: Option<WebauthnChallenge>,
+    ) -> Self {
+        self.expect_assertion_challenge()
+            .once()
+            .with(mockall::predicate::eq(assertion))
+            .return_once(|_| result);
+        self
+    }
+
+    pub fn with_finish_authentication(
+        mut self,
+        challenge: WebauthnChallenge,
+        assertion: WebauthnAssertion,
+        key: WebauthnCredentialKey,
+        result: Result<u32, WebauthnVerifyError>,
+    ) -> Self {
+        self.expect_finish_authentication()
+            .once()
+            .with(
+                mockall::predicate::eq(challenge),
+                mockall::predicate::eq(assertion),
+                mockall::predicate::eq(key),
+            )
+            .return_once(|_, _, _| result);
+        self
+    }
+}
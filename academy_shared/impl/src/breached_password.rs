@@ -0,0 +1,128 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, LazyLock},
+};
+
+use academy_di::Build;
+use academy_models::Sensitive;
+use academy_shared_contracts::breached_password::BreachedPasswordService;
+use academy_utils::trace_instrument;
+use anyhow::Context;
+use sha1::{Digest, Sha1};
+
+/// Length of the hash prefixes used to look up breached password hashes
+const PREFIX_LENGTH: usize = 5;
+
+/// Hash suffixes of the bundled breached passwords, grouped by their prefix
+static BUNDLED: LazyLock<HashMap<&str, Vec<&str>>> = LazyLock::new(|| {
+    let mut out = HashMap::<_, Vec<_>>::new();
+    for (prefix, suffix) in academy_assets::passwords::BREACHED_TXT
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+    {
+        out.entry(prefix).or_default().push(suffix);
+    }
+    out
+});
+
+#[derive(Debug, Clone, Build)]
+pub struct BreachedPasswordServiceImpl {
+    config: BreachedPasswordServiceConfig,
+}
+
+#[derive(Debug, Clone)]
+pub enum BreachedPasswordServiceConfig {
+    /// Use the small list of common passwords bundled with the application.
+    Bundled,
+    /// Use range files named `{PREFIX}.txt` in the given directory, each
+    /// containing lines of the form `{SUFFIX}:{COUNT}` (the format used by the
+    /// "Have I Been Pwned" Pwned Passwords API).
+    Directory(Arc<PathBuf>),
+}
+
+impl BreachedPasswordService for BreachedPasswordServiceImpl {
+    #[trace_instrument(skip(self))]
+    async fn is_breached(&self, password: Sensitive<String>) -> anyhow::Result<bool> {
+        let hash = hex::encode_upper(Sha1::digest(password.0.as_bytes()));
+        let (prefix, suffix) = hash.split_at(PREFIX_LENGTH);
+
+        match &self.config {
+            BreachedPasswordServiceConfig::Bundled => Ok(BUNDLED
+                .get(prefix)
+                .is_some_and(|suffixes| suffixes.contains(&suffix))),
+            BreachedPasswordServiceConfig::Directory(path) => {
+                let path = path.join(format!("{prefix}.txt"));
+                let range = match tokio::fs::read_to_string(&path).await {
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+                    result => result.with_context(|| {
+                        format!("Failed to read breached password range {}", path.display())
+                    })?,
+                };
+                Ok(range_contains(&range, suffix))
+            }
+        }
+    }
+}
+
+fn range_contains(range: &str, suffix: &str) -> bool {
+    range
+        .lines()
+        .map(|line| line.split_once(':').map_or(line, |(s, _)| s).trim())
+        .any(|s| s.eq_ignore_ascii_case(suffix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn bundled() {
+        // Arrange
+        let sut = BreachedPasswordServiceImpl {
+            config: BreachedPasswordServiceConfig::Bundled,
+        };
+
+        // Act
+        let breached = sut.is_breached(Sensitive("password".into())).await;
+        let not_breached = sut
+            .is_breached(Sensitive("correct horse battery staple".into()))
+            .await;
+
+        // Assert
+        assert!(breached.unwrap());
+        assert!(!not_breached.unwrap());
+    }
+
+    #[tokio::test]
+    async fn directory() {
+        // Arrange
+        let dir = std::env::temp_dir().join(format!(
+            "academy-breached-passwords-{}",
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        // SHA-1("hunter2") = F3BBBD66A63D4BF1747940578EC3D0103530E21D
+        std::fs::write(
+            dir.join("F3BBB.txt"),
+            "0018A45C4D1DEF81644B54AB7F969B88D65:1\r\nD66A63D4BF1747940578EC3D0103530E21D:17043\r\n",
+        )
+        .unwrap();
+
+        let sut = BreachedPasswordServiceImpl {
+            config: BreachedPasswordServiceConfig::Directory(dir.clone().into()),
+        };
+
+        // Act
+        let breached = sut.is_breached(Sensitive("hunter2".into())).await;
+        let not_breached = sut
+            .is_breached(Sensitive("correct horse battery staple".into()))
+            .await;
+
+        // Assert
+        assert!(breached.unwrap());
+        assert!(!not_breached.unwrap());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
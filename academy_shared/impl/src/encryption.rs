@@ -0,0 +1,245 @@
+use std::sync::Arc;
+
+use academy_di::Build;
+use academy_shared_contracts::encryption::EncryptionService;
+use academy_utils::trace_instrument;
+use anyhow::{anyhow, ensure, Context};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    rand::{SecureRandom, SystemRandom},
+};
+use sha2::{Digest, Sha256};
+
+/// Length of the key id prefix of encrypted data
+const KEY_ID_LEN: usize = 8;
+
+#[derive(Debug, Clone, Build)]
+pub struct EncryptionServiceImpl {
+    config: EncryptionServiceConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct EncryptionServiceConfig {
+    /// All keys that can be used for decryption. The first key is used to
+    /// encrypt new data.
+    keys: Arc<[EncryptionKey]>,
+}
+
+impl EncryptionServiceConfig {
+    /// Load the given AES-256-GCM keys (base64 encoded).
+    ///
+    /// The first key is used to encrypt new data, all keys are accepted for
+    /// decryption.
+    pub fn new(keys: &[impl AsRef<str>]) -> anyhow::Result<Self> {
+        let keys = keys
+            .iter()
+            .map(|key| EncryptionKey::from_base64(key.as_ref()))
+            .collect::<anyhow::Result<Arc<[_]>>>()?;
+        ensure!(!keys.is_empty(), "No encryption key has been configured");
+        Ok(Self { keys })
+    }
+}
+
+/// Generate a new AES-256-GCM key (base64 encoded).
+pub fn generate_encryption_key() -> anyhow::Result<String> {
+    let mut key = [0; 32];
+    SystemRandom::new()
+        .fill(&mut key)
+        .map_err(|_| anyhow!("Failed to generate encryption key"))?;
+    Ok(BASE64_STANDARD.encode(key))
+}
+
+impl EncryptionService for EncryptionServiceImpl {
+    #[trace_instrument(skip(self, plaintext, associated_data))]
+    fn encrypt(&self, plaintext: &[u8], associated_data: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let key = &self.config.keys[0];
+
+        let mut nonce = [0; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| anyhow!("Failed to generate nonce"))?;
+
+        // key id || nonce || ciphertext || tag
+        let mut data =
+            Vec::with_capacity(KEY_ID_LEN + NONCE_LEN + plaintext.len() + AES_256_GCM.tag_len());
+        data.extend_from_slice(&key.id);
+        data.extend_from_slice(&nonce);
+        let mut in_out = plaintext.to_vec();
+        key.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                key.aad(associated_data),
+                &mut in_out,
+            )
+            .map_err(|_| anyhow!("Failed to encrypt data"))?;
+        data.extend_from_slice(&in_out);
+
+        Ok(data)
+    }
+
+    #[trace_instrument(skip(self, ciphertext, associated_data))]
+    fn decrypt(&self, ciphertext: &[u8], associated_data: &[u8]) -> anyhow::Result<Vec<u8>> {
+        ensure!(
+            ciphertext.len() >= KEY_ID_LEN + NONCE_LEN + AES_256_GCM.tag_len(),
+            "Encrypted data is too short"
+        );
+        let (key_id, rest) = ciphertext.split_at(KEY_ID_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+        let key = self
+            .config
+            .keys
+            .iter()
+            .find(|key| key.id == key_id)
+            .context("Data has been encrypted with an unknown key")?;
+
+        let mut in_out = ciphertext.to_vec();
+        let plaintext_len = key
+            .key
+            .open_in_place(
+                Nonce::try_assume_unique_for_key(nonce).unwrap(),
+                key.aad(associated_data),
+                &mut in_out,
+            )
+            .map_err(|_| anyhow!("Failed to decrypt data"))?
+            .len();
+        in_out.truncate(plaintext_len);
+
+        Ok(in_out)
+    }
+}
+
+#[derive(Debug)]
+struct EncryptionKey {
+    id: [u8; KEY_ID_LEN],
+    key: LessSafeKey,
+}
+
+impl EncryptionKey {
+    fn from_base64(key: &str) -> anyhow::Result<Self> {
+        let key = BASE64_STANDARD
+            .decode(key)
+            .context("Failed to decode encryption key")?;
+        let id = Sha256::digest(&key)[..KEY_ID_LEN].try_into()?;
+        let key = UnboundKey::new(&AES_256_GCM, &key)
+            .map_err(|_| anyhow!("Encryption keys must be exactly 32 bytes long"))?;
+        Ok(Self {
+            id,
+            key: LessSafeKey::new(key),
+        })
+    }
+
+    /// Authenticate the key id together with the given associated data.
+    fn aad(&self, associated_data: &[u8]) -> Aad<Vec<u8>> {
+        Aad::from([&self.id[..], associated_data].concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_decrypt() {
+        // Arrange
+        let sut = make_sut(&[generate_encryption_key().unwrap()]);
+
+        // Act
+        let encrypted = sut.encrypt(b"hello world", b"context").unwrap();
+        let decrypted = sut.decrypt(&encrypted, b"context");
+
+        // Assert
+        assert_ne!(&encrypted[KEY_ID_LEN + NONCE_LEN..], b"hello world");
+        assert_eq!(decrypted.unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn encrypt_uses_random_nonce() {
+        // Arrange
+        let sut = make_sut(&[generate_encryption_key().unwrap()]);
+
+        // Act
+        let a = sut.encrypt(b"hello world", b"context").unwrap();
+        let b = sut.encrypt(b"hello world", b"context").unwrap();
+
+        // Assert
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn decrypt_rotated() {
+        // Arrange
+        let old_key = generate_encryption_key().unwrap();
+        let new_key = generate_encryption_key().unwrap();
+        let old = make_sut(&[&old_key]);
+        let new = make_sut(&[&new_key, &old_key]);
+
+        // Act
+        let encrypted = old.encrypt(b"hello world", b"context").unwrap();
+        let decrypted = new.decrypt(&encrypted, b"context");
+
+        // Assert
+        assert_eq!(decrypted.unwrap(), b"hello world");
+        assert_ne!(
+            new.encrypt(b"hello world", b"context").unwrap()[..KEY_ID_LEN],
+            encrypted[..KEY_ID_LEN]
+        );
+    }
+
+    #[test]
+    fn decrypt_unknown_key() {
+        // Arrange
+        let a = make_sut(&[generate_encryption_key().unwrap()]);
+        let b = make_sut(&[generate_encryption_key().unwrap()]);
+
+        // Act
+        let encrypted = a.encrypt(b"hello world", b"context").unwrap();
+        let decrypted = b.decrypt(&encrypted, b"context");
+
+        // Assert
+        decrypted.unwrap_err();
+    }
+
+    #[test]
+    fn decrypt_tampered() {
+        // Arrange
+        let sut = make_sut(&[generate_encryption_key().unwrap()]);
+        let mut encrypted = sut.encrypt(b"hello world", b"context").unwrap();
+        *encrypted.last_mut().unwrap() ^= 1;
+
+        // Act
+        let decrypted = sut.decrypt(&encrypted, b"context");
+
+        // Assert
+        decrypted.unwrap_err();
+    }
+
+    #[test]
+    fn decrypt_wrong_associated_data() {
+        // Arrange
+        let sut = make_sut(&[generate_encryption_key().unwrap()]);
+        let encrypted = sut.encrypt(b"hello world", b"context").unwrap();
+
+        // Act
+        let decrypted = sut.decrypt(&encrypted, b"other context");
+
+        // Assert
+        decrypted.unwrap_err();
+    }
+
+    #[test]
+    fn invalid_key() {
+        // Act
+        let result = EncryptionServiceConfig::new(&[BASE64_STANDARD.encode([0; 16])]);
+
+        // Assert
+        result.unwrap_err();
+    }
+
+    fn make_sut(keys: &[impl AsRef<str>]) -> EncryptionServiceImpl {
+        EncryptionServiceImpl {
+            config: EncryptionServiceConfig::new(keys).unwrap(),
+        }
+    }
+}
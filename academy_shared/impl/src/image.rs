@@ -0,0 +1,203 @@
+use std::{io::Cursor, ops::RangeInclusive};
+
+use academy_di::Build;
+use academy_shared_contracts::image::{ImageCropSquareError, ImageService};
+use academy_utils::trace_instrument;
+use anyhow::{anyhow, Context};
+use image::{
+    codecs::webp::WebPEncoder, error::LimitErrorKind, imageops::FilterType, ImageError,
+    ImageFormat, ImageReader, Limits,
+};
+
+#[derive(Debug, Clone, Default, Build)]
+pub struct ImageServiceImpl;
+
+impl ImageService for ImageServiceImpl {
+    #[trace_instrument(skip(self, data))]
+    async fn crop_square(
+        &self,
+        data: Vec<u8>,
+        dimensions: RangeInclusive<u32>,
+        sizes: Vec<u32>,
+    ) -> Result<Vec<Vec<u8>>, ImageCropSquareError> {
+        tokio::task::spawn_blocking(move || crop_square(&data, dimensions, &sizes))
+            .await
+            .map_err(|err| anyhow!(err).context("Failed to process image"))?
+    }
+}
+
+fn crop_square(
+    data: &[u8],
+    dimensions: RangeInclusive<u32>,
+    sizes: &[u32],
+) -> Result<Vec<Vec<u8>>, ImageCropSquareError> {
+    let mut reader = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .context("Failed to guess image format")?;
+
+    if !matches!(
+        reader.format(),
+        Some(ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::WebP)
+    ) {
+        return Err(ImageCropSquareError::UnsupportedFormat);
+    }
+
+    // reject huge images before decoding them
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(*dimensions.end());
+    limits.max_image_height = Some(*dimensions.end());
+    reader.limits(limits);
+
+    let image = reader.decode().map_err(|err| match err {
+        ImageError::Limits(err) if matches!(err.kind(), LimitErrorKind::DimensionError) => {
+            ImageCropSquareError::InvalidDimensions
+        }
+        ImageError::Limits(err) => anyhow!(err).context("Failed to decode image").into(),
+        _ => ImageCropSquareError::InvalidImage,
+    })?;
+
+    let (width, height) = (image.width(), image.height());
+    if !dimensions.contains(&width) || !dimensions.contains(&height) {
+        return Err(ImageCropSquareError::InvalidDimensions);
+    }
+
+    let side = width.min(height);
+    let image = image.crop_imm((width - side) / 2, (height - side) / 2, side, side);
+
+    sizes
+        .iter()
+        .map(|&size| {
+            let resized = image
+                .resize_exact(size, size, FilterType::Lanczos3)
+                .into_rgba8();
+            let mut buf = Vec::new();
+            resized
+                .write_with_encoder(WebPEncoder::new_lossless(&mut buf))
+                .context("Failed to encode image")?;
+            Ok(buf)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use academy_utils::assert_matches;
+    use image::{DynamicImage, Rgba, RgbaImage};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn ok() {
+        // Arrange
+        let image = make_image(300, 200, ImageFormat::Png);
+
+        let sut = ImageServiceImpl;
+
+        // Act
+        let result = sut.crop_square(image, 100..=1000, vec![64, 256]).await;
+
+        // Assert
+        let result = result.unwrap();
+        assert_eq!(result.len(), 2);
+        for (data, size) in result.into_iter().zip([64, 256]) {
+            let image = image::load_from_memory_with_format(&data, ImageFormat::WebP).unwrap();
+            assert_eq!((image.width(), image.height()), (size, size));
+            // the red borders on the left and right have been cropped
+            assert_eq!(image.to_rgba8().get_pixel(0, 0), &Rgba([0, 0, 255, 255]));
+        }
+    }
+
+    #[tokio::test]
+    async fn ok_formats() {
+        for format in [ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::WebP] {
+            // Arrange
+            let image = make_image(200, 300, format);
+
+            let sut = ImageServiceImpl;
+
+            // Act
+            let result = sut.crop_square(image, 100..=1000, vec![128]).await;
+
+            // Assert
+            assert_eq!(result.unwrap().len(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn unsupported_format() {
+        // Arrange
+        let image = b"GIF89a\x01\x00\x01\x00\x00\x00\x00;".to_vec();
+
+        let sut = ImageServiceImpl;
+
+        // Act
+        let result = sut.crop_square(image, 100..=1000, vec![128]).await;
+
+        // Assert
+        assert_matches!(result, Err(ImageCropSquareError::UnsupportedFormat));
+    }
+
+    #[tokio::test]
+    async fn invalid_image() {
+        // Arrange
+        let mut image = make_image(200, 200, ImageFormat::Png);
+        image.truncate(image.len() / 2);
+
+        let sut = ImageServiceImpl;
+
+        // Act
+        let result = sut.crop_square(image, 100..=1000, vec![128]).await;
+
+        // Assert
+        assert_matches!(result, Err(ImageCropSquareError::InvalidImage));
+    }
+
+    #[tokio::test]
+    async fn too_small() {
+        // Arrange
+        let image = make_image(200, 99, ImageFormat::Png);
+
+        let sut = ImageServiceImpl;
+
+        // Act
+        let result = sut.crop_square(image, 100..=1000, vec![128]).await;
+
+        // Assert
+        assert_matches!(result, Err(ImageCropSquareError::InvalidDimensions));
+    }
+
+    #[tokio::test]
+    async fn too_large() {
+        // Arrange
+        let image = make_image(1001, 200, ImageFormat::Png);
+
+        let sut = ImageServiceImpl;
+
+        // Act
+        let result = sut.crop_square(image, 100..=1000, vec![128]).await;
+
+        // Assert
+        assert_matches!(result, Err(ImageCropSquareError::InvalidDimensions));
+    }
+
+    /// Create a blue image with red borders on the longer sides.
+    fn make_image(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+        let side = width.min(height);
+        let (left, top) = ((width - side) / 2, (height - side) / 2);
+        let image = RgbaImage::from_fn(width, height, |x, y| {
+            if (left..left + side).contains(&x) && (top..top + side).contains(&y) {
+                Rgba([0, 0, 255, 255])
+            } else {
+                Rgba([255, 0, 0, 255])
+            }
+        });
+        let image = match format {
+            ImageFormat::Jpeg => DynamicImage::ImageRgb8(DynamicImage::from(image).into_rgb8()),
+            _ => image.into(),
+        };
+
+        let mut buf = Cursor::new(Vec::new());
+        image.write_to(&mut buf, format).unwrap();
+        buf.into_inner()
+    }
+}
@@ -1,16 +1,30 @@
-use std::{fmt::Debug, sync::Arc, time::Duration};
+use std::{collections::BTreeMap, fmt::Debug, sync::Arc, time::Duration};
 
 use academy_di::Build;
+use academy_models::auth::JwtPublicKey;
 use academy_shared_contracts::{
     jwt::{JwtService, VerifyJwtError},
     time::TimeService,
 };
 use academy_utils::trace_instrument;
-use anyhow::Context;
-use hmac::{digest::KeyInit, Hmac};
-use jwt::{SignWithKey, VerifyWithKey};
+use anyhow::{anyhow, Context};
+use base64::{
+    prelude::{BASE64_STANDARD, BASE64_URL_SAFE_NO_PAD},
+    Engine,
+};
+use jwt::{
+    algorithm::{AlgorithmType, SigningAlgorithm, VerifyingAlgorithm},
+    SignWithStore, VerifyWithStore,
+};
+use ring::{
+    rand::SystemRandom,
+    signature::{
+        EcdsaKeyPair, KeyPair, UnparsedPublicKey, ECDSA_P256_SHA256_FIXED,
+        ECDSA_P256_SHA256_FIXED_SIGNING,
+    },
+};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use sha2::Sha256;
+use sha2::{Digest, Sha256};
 
 #[derive(Debug, Clone, Build)]
 pub struct JwtServiceImpl<Time> {
@@ -20,19 +34,55 @@ pub struct JwtServiceImpl<Time> {
 
 #[derive(Debug, Clone)]
 pub struct JwtServiceConfig {
-    jwt_secret: Arc<Hmac<Sha256>>,
+    /// Key id of the key used to sign new JWTs
+    signing_key_id: Arc<str>,
+    /// All keys that are accepted for verification, indexed by their key id
+    keys: Arc<BTreeMap<String, JwtKey>>,
 }
 
 impl JwtServiceConfig {
-    pub fn new(jwt_secret: &str) -> anyhow::Result<Self> {
+    /// Load the given ES256 keys (base64 encoded PKCS#8 documents).
+    ///
+    /// The first key is used to sign new JWTs, all keys are accepted for
+    /// verification.
+    pub fn new(keys: &[impl AsRef<str>]) -> anyhow::Result<Self> {
+        let keys = keys
+            .iter()
+            .map(|key| JwtKey::from_base64(key.as_ref()))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let signing_key_id = keys
+            .first()
+            .ok_or_else(|| anyhow!("No JWT signing key has been configured"))?
+            .public_key
+            .kid
+            .as_str()
+            .into();
+
         Ok(Self {
-            jwt_secret: Hmac::new_from_slice(jwt_secret.as_bytes())
-                .context("Failed to load JWT secret")?
-                .into(),
+            signing_key_id,
+            keys: Arc::new(
+                keys.into_iter()
+                    .map(|key| (key.public_key.kid.clone(), key))
+                    .collect(),
+            ),
         })
     }
 }
 
+/// Generate a new ES256 key (base64 encoded PKCS#8 document).
+pub fn generate_jwt_key() -> anyhow::Result<String> {
+    let pkcs8 =
+        EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &SystemRandom::new())
+            .map_err(|_| anyhow!("Failed to generate JWT key"))?;
+    Ok(BASE64_STANDARD.encode(pkcs8.as_ref()))
+}
+
+/// Return the key id of the given ES256 key (base64 encoded PKCS#8 document).
+pub fn jwt_key_id(key: &str) -> anyhow::Result<String> {
+    JwtKey::from_base64(key).map(|key| key.public_key.kid)
+}
+
 impl<Time> JwtService for JwtServiceImpl<Time>
 where
     Time: TimeService,
@@ -46,8 +96,8 @@ where
         let now = self.time.now().timestamp() as u64;
         let exp = now + ttl.as_secs();
 
-        JwtData { exp, data }
-            .sign_with_key(&*self.config.jwt_secret)
+        (&*self.config.signing_key_id, JwtData { exp, data })
+            .sign_with_store(&*self.config.keys)
             .context("Failed to sign JWT")
             .map(Into::into)
     }
@@ -59,7 +109,7 @@ where
     ) -> Result<T, VerifyJwtError<T>> {
         let JwtData { exp, data } = jwt
             .as_ref()
-            .verify_with_key(&*self.config.jwt_secret)
+            .verify_with_store(&*self.config.keys)
             .map_err(|_| VerifyJwtError::Invalid)?;
 
         let now = self.time.now().timestamp() as u64;
@@ -69,6 +119,15 @@ where
             Err(VerifyJwtError::Expired(data))
         }
     }
+
+    #[trace_instrument(skip(self))]
+    fn public_keys(&self) -> Vec<JwtPublicKey> {
+        self.config
+            .keys
+            .values()
+            .map(|key| key.public_key.clone())
+            .collect()
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -78,6 +137,79 @@ struct JwtData<T> {
     data: T,
 }
 
+#[derive(Debug)]
+struct JwtKey {
+    key_pair: EcdsaKeyPair,
+    public_key: JwtPublicKey,
+}
+
+impl JwtKey {
+    fn from_base64(key: &str) -> anyhow::Result<Self> {
+        let pkcs8 = BASE64_STANDARD
+            .decode(key)
+            .context("Failed to decode JWT key")?;
+        let key_pair = EcdsaKeyPair::from_pkcs8(
+            &ECDSA_P256_SHA256_FIXED_SIGNING,
+            &pkcs8,
+            &SystemRandom::new(),
+        )
+        .map_err(|err| anyhow!("Failed to load JWT key: {err}"))?;
+
+        // uncompressed point: 0x04 || x || y
+        let point = key_pair.public_key().as_ref();
+        let x: [u8; 32] = point[1..33].try_into()?;
+        let y: [u8; 32] = point[33..65].try_into()?;
+
+        // JWK thumbprint as specified in RFC 7638
+        let thumbprint = Sha256::digest(format!(
+            r#"{{"crv":"P-256","kty":"EC","x":"{}","y":"{}"}}"#,
+            BASE64_URL_SAFE_NO_PAD.encode(x),
+            BASE64_URL_SAFE_NO_PAD.encode(y),
+        ));
+        let kid = BASE64_URL_SAFE_NO_PAD.encode(thumbprint);
+
+        Ok(Self {
+            key_pair,
+            public_key: JwtPublicKey { kid, x, y },
+        })
+    }
+}
+
+impl SigningAlgorithm for JwtKey {
+    fn algorithm_type(&self) -> AlgorithmType {
+        AlgorithmType::Es256
+    }
+
+    fn sign(&self, header: &str, claims: &str) -> Result<String, jwt::Error> {
+        self.key_pair
+            .sign(
+                &SystemRandom::new(),
+                format!("{header}.{claims}").as_bytes(),
+            )
+            .map(|signature| BASE64_URL_SAFE_NO_PAD.encode(signature))
+            .map_err(|_| jwt::Error::InvalidSignature)
+    }
+}
+
+impl VerifyingAlgorithm for JwtKey {
+    fn algorithm_type(&self) -> AlgorithmType {
+        AlgorithmType::Es256
+    }
+
+    fn verify_bytes(
+        &self,
+        header: &str,
+        claims: &str,
+        signature: &[u8],
+    ) -> Result<bool, jwt::Error> {
+        Ok(
+            UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, self.key_pair.public_key())
+                .verify(format!("{header}.{claims}").as_bytes(), signature)
+                .is_ok(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use academy_shared_contracts::time::MockTimeService;
@@ -94,7 +226,7 @@ mod tests {
             bar: "hello world".into(),
         };
 
-        let config = JwtServiceConfig::new("the jwt secret").unwrap();
+        let config = JwtServiceConfig::new(&[generate_jwt_key().unwrap()]).unwrap();
 
         let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
         let then = now + Duration::from_secs(10);
@@ -118,7 +250,7 @@ mod tests {
             bar: "hello world".into(),
         };
 
-        let config = JwtServiceConfig::new("the jwt secret").unwrap();
+        let config = JwtServiceConfig::new(&[generate_jwt_key().unwrap()]).unwrap();
 
         let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
         let then = now + Duration::from_secs(20);
@@ -142,8 +274,8 @@ mod tests {
             bar: "hello world".into(),
         };
 
-        let config = JwtServiceConfig::new("the jwt secret").unwrap();
-        let config2 = JwtServiceConfig::new("other jwt secret").unwrap();
+        let config = JwtServiceConfig::new(&[generate_jwt_key().unwrap()]).unwrap();
+        let config2 = JwtServiceConfig::new(&[generate_jwt_key().unwrap()]).unwrap();
 
         let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
         let time = MockTimeService::new().with_now(now);
@@ -162,6 +294,76 @@ mod tests {
         assert_matches!(verified, Err(VerifyJwtError::Invalid));
     }
 
+    #[test]
+    fn sign_verify_rotated() {
+        // Arrange
+        let data = Data {
+            foo: 42,
+            bar: "hello world".into(),
+        };
+
+        let old_key = generate_jwt_key().unwrap();
+        let new_key = generate_jwt_key().unwrap();
+        let config = JwtServiceConfig::new(&[&old_key]).unwrap();
+        let config2 = JwtServiceConfig::new(&[&new_key, &old_key]).unwrap();
+
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let time = MockTimeService::new().with_now(now);
+
+        let sut = JwtServiceImpl { time, config };
+        let sut2 = JwtServiceImpl {
+            time: MockTimeService::new().with_now(now),
+            config: config2,
+        };
+
+        // Act
+        let jwt = sut.sign(data.clone(), Duration::from_secs(10)).unwrap();
+        let verified = sut2.verify::<String, Data>(&jwt);
+
+        // Assert
+        assert_eq!(verified.unwrap(), data);
+    }
+
+    #[test]
+    fn public_keys() {
+        // Arrange
+        let key = generate_jwt_key().unwrap();
+        let config = JwtServiceConfig::new(&[&key]).unwrap();
+
+        let sut = JwtServiceImpl {
+            time: MockTimeService::new(),
+            config,
+        };
+
+        // Act
+        let result = sut.public_keys();
+
+        // Assert
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].kid, jwt_key_id(&key).unwrap());
+    }
+
+    #[test]
+    fn key_id() {
+        // Arrange
+        let key = "MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgzWJf/QUaXQpOHiIG/2/h9tU/aGp0HT0mVrl+6saHikuhRANCAAQh3zIul+3lnHkuF/XglaM0FerQDoVJTrZIvbYfxj+xNRxFVBPW+aUiOowfkKiD4SOQseWNJp1ZhKD5JAsPIwh3";
+
+        // Act
+        let result = jwt_key_id(key).unwrap();
+
+        // Assert
+        assert_eq!(result, "-hviXyRvJQ8gLX7t3r_ukefFq3ZE1Vx_Er-iiz_T0sU");
+    }
+
+    #[test]
+    fn no_keys() {
+        // Act
+        let result = JwtServiceConfig::new(&[] as &[&str]);
+
+        // Assert
+        result.unwrap_err();
+    }
+
     #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
     struct Data {
         foo: i32,
@@ -1,8 +1,15 @@
+pub mod breached_password;
 pub mod captcha;
+pub mod encryption;
 pub mod hash;
 pub mod id;
+pub mod image;
 pub mod jwt;
 pub mod password;
+pub mod password_strength;
+pub mod rate_limit;
 pub mod secret;
+pub mod throttle;
 pub mod time;
 pub mod totp;
+pub mod webauthn;
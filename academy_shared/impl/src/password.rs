@@ -7,19 +7,35 @@ use academy_utils::trace_instrument;
 use anyhow::{anyhow, Context};
 use argon2::{
     password_hash::{self, rand_core::OsRng, SaltString},
-    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version,
 };
 
 #[derive(Debug, Clone, Default, Build)]
 pub struct PasswordServiceImpl {
-    #[di(default)]
+    config: PasswordServiceConfig,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PasswordServiceConfig {
     argon2: Arc<Argon2<'static>>,
 }
 
+impl PasswordServiceConfig {
+    /// Configure Argon2id with the given memory size (in KiB), number of
+    /// iterations and degree of parallelism.
+    pub fn new(memory_cost: u32, time_cost: u32, parallelism: u32) -> anyhow::Result<Self> {
+        let params = Params::new(memory_cost, time_cost, parallelism, None)
+            .map_err(|err| anyhow!(err).context("Invalid Argon2 parameters"))?;
+        Ok(Self {
+            argon2: Argon2::new(Algorithm::Argon2id, Version::V0x13, params).into(),
+        })
+    }
+}
+
 impl PasswordService for PasswordServiceImpl {
     #[trace_instrument(skip(self))]
     async fn hash(&self, password: Sensitive<String>) -> anyhow::Result<String> {
-        let argon2 = Arc::clone(&self.argon2);
+        let argon2 = Arc::clone(&self.config.argon2);
         let salt = SaltString::generate(&mut OsRng);
         tokio::task::spawn_blocking(move || {
             argon2
@@ -36,7 +52,7 @@ impl PasswordService for PasswordServiceImpl {
         password: Sensitive<String>,
         hash: String,
     ) -> Result<(), PasswordVerifyError> {
-        let argon2 = Arc::clone(&self.argon2);
+        let argon2 = Arc::clone(&self.config.argon2);
         tokio::task::spawn_blocking(move || {
             let hash =
                 PasswordHash::new(&hash).map_err(|err| PasswordVerifyError::Other(err.into()))?;
@@ -50,6 +66,23 @@ impl PasswordService for PasswordServiceImpl {
         .await
         .map_err(|err| anyhow!(err).context("Failed to verify password"))?
     }
+
+    #[trace_instrument(skip(self))]
+    fn needs_rehash(&self, hash: &str) -> bool {
+        let Ok(hash) = PasswordHash::new(hash) else {
+            return true;
+        };
+        let Ok(params) = Params::try_from(&hash) else {
+            return true;
+        };
+        let current = self.config.argon2.params();
+
+        hash.algorithm != Algorithm::Argon2id.ident()
+            || hash.version != Some(Version::V0x13.into())
+            || params.m_cost() != current.m_cost()
+            || params.t_cost() != current.t_cost()
+            || params.p_cost() != current.p_cost()
+    }
 }
 
 #[cfg(test)]
@@ -88,4 +121,66 @@ mod tests {
         // Assert
         assert_matches!(result, Err(PasswordVerifyError::InvalidPassword));
     }
+
+    #[tokio::test]
+    async fn needs_rehash_current() {
+        // Arrange
+        let sut = PasswordServiceImpl {
+            config: PasswordServiceConfig::new(8192, 3, 2).unwrap(),
+        };
+        let hash = sut
+            .hash("some user password".to_owned().into())
+            .await
+            .unwrap();
+
+        // Act
+        let result = sut.needs_rehash(&hash);
+
+        // Assert
+        assert!(!result);
+    }
+
+    #[tokio::test]
+    async fn needs_rehash_outdated() {
+        // Arrange
+        let old = PasswordServiceImpl::default();
+        let hash = old
+            .hash("some user password".to_owned().into())
+            .await
+            .unwrap();
+
+        let sut = PasswordServiceImpl {
+            config: PasswordServiceConfig::new(8192, 3, 2).unwrap(),
+        };
+
+        // Act
+        let result = sut.needs_rehash(&hash);
+
+        // Assert
+        assert!(result);
+    }
+
+    #[test]
+    fn needs_rehash_params() {
+        for (hash, expected) in [
+            ("$argon2id$v=19$m=19456,t=2,p=1", false),
+            ("$argon2id$v=19$m=65536,t=3,p=4", true),
+            ("$argon2i$v=19$m=19456,t=2,p=1", true),
+            ("$argon2id$v=16$m=19456,t=2,p=1", true),
+            (
+                "$2b$12$R9h/cIPz0gi.URNNX3kh2OPST9/PgBkqquzi.Ss7KIUgO2t0jWMUW",
+                true,
+            ),
+            ("not a hash", true),
+        ] {
+            // Arrange
+            let sut = PasswordServiceImpl::default();
+
+            // Act
+            let result = sut.needs_rehash(hash);
+
+            // Assert
+            assert_eq!(result, expected, "{hash}");
+        }
+    }
 }
@@ -0,0 +1,447 @@
+//! Password strength estimation inspired by
+//! [zxcvbn](https://github.com/dropbox/zxcvbn).
+//!
+//! The password is matched against a number of patterns (common passwords,
+//! user inputs, keyboard walks, sequences, repeats and years), each of which
+//! is assigned an estimated number of guesses an attacker would need. The
+//! final estimate is the number of guesses of the cheapest sequence of
+//! non-overlapping matches covering the password, where all remaining
+//! characters are treated as random. All guess counts are handled as
+//! logarithms (base 10) to avoid overflows.
+
+use std::{collections::HashMap, sync::LazyLock};
+
+use academy_di::Build;
+use academy_models::Sensitive;
+use academy_shared_contracts::password_strength::PasswordStrengthService;
+use academy_utils::trace_instrument;
+
+/// Maximum number of characters that are analyzed. All further characters are
+/// treated as random characters.
+const MAX_ANALYZED_LENGTH: usize = 100;
+/// Maximum length of dictionary words
+const MAX_WORD_LENGTH: usize = 32;
+/// Number of guesses needed for a random character
+const BRUTEFORCE_CARDINALITY: f64 = 10.0;
+/// Minimum number of guesses for a match covering a single character of a
+/// longer password
+const MIN_SUBMATCH_GUESSES_SINGLE_CHAR: f64 = 10.0;
+/// Minimum number of guesses for a match covering multiple characters of a
+/// longer password
+const MIN_SUBMATCH_GUESSES_MULTI_CHAR: f64 = 50.0;
+/// Additional guesses per match, which penalizes long sequences of matches
+const MIN_GUESSES_BEFORE_GROWING_SEQUENCE: f64 = 10000.0;
+/// Year which is assumed to be the most likely one to appear in passwords
+const REFERENCE_YEAR: i32 = 2025;
+/// Minimum number of guesses for a year
+const MIN_YEAR_SPACE: f64 = 20.0;
+/// Number of keys on a keyboard (including shifted keys)
+const KEYBOARD_STARTING_POSITIONS: f64 = 94.0;
+/// Average number of neighbors of a key on a keyboard
+const KEYBOARD_AVERAGE_DEGREE: f64 = 4.6;
+/// Minimum number of guesses (log10) needed to reach the scores 1 to 4
+const SCORE_THRESHOLDS: [f64; 4] = [3.0, 6.0, 8.0, 10.0];
+
+/// Rows of common keyboard layouts (QWERTY, QWERTZ and AZERTY)
+const KEYBOARD_ROWS: &[&str] = &[
+    "`1234567890-=",
+    "qwertyuiop[]\\",
+    "asdfghjkl;'",
+    "zxcvbnm,./",
+    "qwertzuiopü+",
+    "asdfghjklöä#",
+    "yxcvbnm,.-",
+    "azertyuiop",
+    "qsdfghjklm",
+    "wxcvbn,;:!",
+];
+
+/// Ranks of the bundled common passwords (starting at 1)
+static COMMON_PASSWORDS: LazyLock<HashMap<String, usize>> = LazyLock::new(|| {
+    ranked(
+        academy_assets::passwords::COMMON_TXT
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_lowercase),
+    )
+});
+
+#[derive(Debug, Clone, Copy, Default, Build)]
+pub struct PasswordStrengthServiceImpl;
+
+impl PasswordStrengthService for PasswordStrengthServiceImpl {
+    #[trace_instrument(skip(self))]
+    fn estimate(&self, password: Sensitive<String>, user_inputs: Vec<String>) -> u8 {
+        let chars = password.0.chars().collect::<Vec<_>>();
+        let (analyzed, rest) = chars.split_at(chars.len().min(MAX_ANALYZED_LENGTH));
+
+        let user_inputs = ranked(
+            user_inputs
+                .iter()
+                .map(|input| input.to_lowercase())
+                .flat_map(|input| {
+                    let words = input
+                        .split(|c: char| !c.is_alphanumeric())
+                        .filter(|word| word.chars().count() >= 3)
+                        .map(Into::into)
+                        .collect::<Vec<_>>();
+                    std::iter::once(input).chain(words)
+                })
+                .filter(|input| !input.is_empty()),
+        );
+
+        let guesses = Estimator {
+            user_inputs: &user_inputs,
+            repeat_guesses: Default::default(),
+        }
+        .guesses(analyzed)
+            + rest.len() as f64 * BRUTEFORCE_CARDINALITY.log10();
+
+        SCORE_THRESHOLDS
+            .into_iter()
+            .filter(|&threshold| guesses >= threshold)
+            .count() as _
+    }
+}
+
+fn ranked(words: impl IntoIterator<Item = String>) -> HashMap<String, usize> {
+    let mut out = HashMap::new();
+    for word in words {
+        let rank = out.len() + 1;
+        out.entry(word).or_insert(rank);
+    }
+    out
+}
+
+struct Estimator<'a> {
+    user_inputs: &'a HashMap<String, usize>,
+    /// Cache for the guesses of the base tokens of repeat matches
+    repeat_guesses: std::cell::RefCell<HashMap<Vec<char>, f64>>,
+}
+
+/// A pattern found in the password
+#[derive(Debug, Clone, Copy)]
+struct Match {
+    /// Index of the first character
+    start: usize,
+    /// Index after the last character
+    end: usize,
+    /// log10 of the estimated number of guesses
+    guesses: f64,
+}
+
+impl Estimator<'_> {
+    /// Return log10 of the minimum number of guesses needed to guess the
+    /// password.
+    fn guesses(&self, password: &[char]) -> f64 {
+        let n = password.len();
+        if n == 0 {
+            return 0.0;
+        }
+
+        let mut matches = self.matches(password);
+        for start in 0..n {
+            for end in start + 1..=n {
+                matches.push(Match {
+                    start,
+                    end,
+                    guesses: (end - start) as f64 * BRUTEFORCE_CARDINALITY.log10(),
+                });
+            }
+        }
+        for m in &mut matches {
+            if m.end - m.start < n {
+                let min_guesses = match m.end - m.start {
+                    1 => MIN_SUBMATCH_GUESSES_SINGLE_CHAR,
+                    _ => MIN_SUBMATCH_GUESSES_MULTI_CHAR,
+                };
+                m.guesses = m.guesses.max(min_guesses.log10());
+            }
+        }
+
+        // best[k][l] = minimum product of guesses (log10) of a sequence of `l`
+        // matches covering the first `k` characters
+        let mut best = vec![vec![f64::INFINITY; n + 1]; n + 1];
+        best[0][0] = 0.0;
+        matches.sort_by_key(|m| m.end);
+        for m in matches {
+            for l in 0..=m.start {
+                let guesses = best[m.start][l] + m.guesses;
+                if guesses < best[m.end][l + 1] {
+                    best[m.end][l + 1] = guesses;
+                }
+            }
+        }
+
+        best[n]
+            .iter()
+            .enumerate()
+            .filter(|(_, guesses)| guesses.is_finite())
+            .map(|(l, &guesses)| {
+                log10_sum(
+                    log10_factorial(l) + guesses,
+                    MIN_GUESSES_BEFORE_GROWING_SEQUENCE.log10() * (l as f64 - 1.0),
+                )
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    fn matches(&self, password: &[char]) -> Vec<Match> {
+        let mut matches = Vec::new();
+        self.dictionary_matches(password, &mut matches);
+        spatial_matches(password, &mut matches);
+        sequence_matches(password, &mut matches);
+        self.repeat_matches(password, &mut matches);
+        year_matches(password, &mut matches);
+        matches
+    }
+
+    fn dictionary_matches(&self, password: &[char], matches: &mut Vec<Match>) {
+        for start in 0..password.len() {
+            for end in start + 1..=password.len().min(start + MAX_WORD_LENGTH) {
+                let token = &password[start..end];
+                let lower = token
+                    .iter()
+                    .flat_map(|c| c.to_lowercase())
+                    .collect::<String>();
+                let uppercase = uppercase_variations(token);
+
+                let mut add = |word: &str, extra_guesses: f64| {
+                    if let Some(rank) = self.rank(word) {
+                        matches.push(Match {
+                            start,
+                            end,
+                            guesses: (rank as f64).log10() + uppercase + extra_guesses,
+                        });
+                    }
+                };
+
+                add(&lower, 0.0);
+                add(&lower.chars().rev().collect::<String>(), 2.0f64.log10());
+                for ambiguous in [false, true] {
+                    let (unleeted, substitutions) = unleet(&lower, ambiguous);
+                    if substitutions > 0 {
+                        add(&unleeted, substitutions as f64 * 2.0f64.log10());
+                    }
+                }
+            }
+        }
+    }
+
+    fn rank(&self, word: &str) -> Option<usize> {
+        let common = COMMON_PASSWORDS.get(word).copied();
+        let user_input = self.user_inputs.get(word).copied();
+        common.into_iter().chain(user_input).min()
+    }
+
+    fn repeat_matches(&self, password: &[char], matches: &mut Vec<Match>) {
+        for start in 0..password.len() {
+            for unit in 1..=(password.len() - start) / 2 {
+                let base = &password[start..start + unit];
+                let count = password[start..]
+                    .chunks_exact(unit)
+                    .take_while(|&chunk| chunk == base)
+                    .count();
+                if count < 2 {
+                    continue;
+                }
+
+                let cached = self.repeat_guesses.borrow().get(base).copied();
+                let base_guesses = cached.unwrap_or_else(|| {
+                    let guesses = self.guesses(base);
+                    self.repeat_guesses
+                        .borrow_mut()
+                        .insert(base.into(), guesses);
+                    guesses
+                });
+                matches.push(Match {
+                    start,
+                    end: start + unit * count,
+                    guesses: base_guesses + (count as f64).log10(),
+                });
+            }
+        }
+    }
+}
+
+fn spatial_matches(password: &[char], matches: &mut Vec<Match>) {
+    let lower = password
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect::<Vec<_>>();
+
+    for start in 0..password.len() {
+        for end in start + 3..=password.len() {
+            let token = lower[start..end].iter().collect::<String>();
+            let reversed = token.chars().rev().collect::<String>();
+            if !KEYBOARD_ROWS
+                .iter()
+                .any(|row| row.contains(&token) || row.contains(&reversed))
+            {
+                break;
+            }
+
+            let length = (end - start) as f64;
+            matches.push(Match {
+                start,
+                end,
+                guesses: (KEYBOARD_STARTING_POSITIONS * KEYBOARD_AVERAGE_DEGREE * (length - 1.0))
+                    .log10()
+                    + uppercase_variations(&password[start..end]),
+            });
+        }
+    }
+}
+
+fn sequence_matches(password: &[char], matches: &mut Vec<Match>) {
+    let mut start = 0;
+    while start + 2 < password.len() {
+        let delta = password[start + 1] as i64 - password[start] as i64;
+        let mut end = start + 2;
+        while end < password.len() && password[end] as i64 - password[end - 1] as i64 == delta {
+            end += 1;
+        }
+
+        if (1..=5).contains(&delta.abs()) && end - start >= 3 {
+            let first = password[start];
+            let base = if "aAzZ019".contains(first) {
+                4.0
+            } else if first.is_ascii_digit() {
+                10.0
+            } else {
+                26.0
+            };
+            let direction = if delta > 0 { 1.0 } else { 2.0 };
+            matches.push(Match {
+                start,
+                end,
+                guesses: (base * direction * (end - start) as f64).log10(),
+            });
+            start = end - 1;
+        } else {
+            start += 1;
+        }
+    }
+}
+
+fn year_matches(password: &[char], matches: &mut Vec<Match>) {
+    for (start, window) in password.windows(4).enumerate() {
+        let Some(year) = window
+            .iter()
+            .try_fold(0, |acc, c| Some(acc * 10 + c.to_digit(10)? as i32))
+            .filter(|year| (1900..=2099).contains(year))
+        else {
+            continue;
+        };
+
+        matches.push(Match {
+            start,
+            end: start + 4,
+            guesses: ((year - REFERENCE_YEAR).abs() as f64)
+                .max(MIN_YEAR_SPACE)
+                .log10(),
+        });
+    }
+}
+
+/// Return log10 of the number of ways the letters of the given token could be
+/// capitalized.
+fn uppercase_variations(token: &[char]) -> f64 {
+    let upper = token.iter().filter(|c| c.is_uppercase()).count();
+    let lower = token.iter().filter(|c| c.is_lowercase()).count();
+    if upper == 0 {
+        return 0.0;
+    }
+
+    let first_upper = token.first().is_some_and(|c| c.is_uppercase()) && upper == 1;
+    let last_upper = token.last().is_some_and(|c| c.is_uppercase()) && upper == 1;
+    if first_upper || last_upper || lower == 0 {
+        return 2.0f64.log10();
+    }
+
+    let variations = (1..=upper.min(lower))
+        .map(|i| binomial(upper + lower, i))
+        .sum::<f64>();
+    variations.log10()
+}
+
+/// Replace common l33t substitutions in the given token and return the number
+/// of substituted characters.
+fn unleet(token: &str, ambiguous: bool) -> (String, usize) {
+    let mut substitutions = 0;
+    let unleeted = token
+        .chars()
+        .map(|c| {
+            let replacement = match c {
+                '4' | '@' => 'a',
+                '8' => 'b',
+                '(' | '{' | '[' | '<' => 'c',
+                '3' => 'e',
+                '6' | '9' => 'g',
+                '1' | '!' | '|' if !ambiguous => 'i',
+                '1' | '|' | '7' if ambiguous => 'l',
+                '0' => 'o',
+                '$' | '5' => 's',
+                '+' | '7' => 't',
+                '%' => 'x',
+                '2' => 'z',
+                c => c,
+            };
+            if replacement != c {
+                substitutions += 1;
+            }
+            replacement
+        })
+        .collect();
+    (unleeted, substitutions)
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+fn log10_factorial(n: usize) -> f64 {
+    (2..=n).map(|i| (i as f64).log10()).sum()
+}
+
+/// Return `log10(10^a + 10^b)`.
+fn log10_sum(a: f64, b: f64) -> f64 {
+    let (max, min) = if a > b { (a, b) } else { (b, a) };
+    max + (1.0 + 10.0f64.powf(min - max)).log10()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimate() {
+        for (password, user_inputs, expected) in [
+            ("", &[][..], 0),
+            ("a", &[], 0),
+            ("password", &[], 0),
+            ("P@ssw0rd", &[], 0),
+            ("drowssap", &[], 0),
+            ("qwertyuiop", &[], 0),
+            ("abcdefghijk", &[], 0),
+            ("aaaaaaaaaaaaaaaa", &[], 0),
+            ("monkey1990", &[], 1),
+            ("johnsmith1990", &["John Smith", "johnsmith@example.com"], 1),
+            ("xK9#mQ2$vL7!pW", &[], 4),
+            ("correct horse battery staple", &[], 4),
+        ] {
+            // Arrange
+            let sut = PasswordStrengthServiceImpl;
+
+            // Act
+            let result = sut.estimate(
+                Sensitive(password.into()),
+                user_inputs.iter().copied().map(Into::into).collect(),
+            );
+
+            // Assert
+            assert_eq!(result, expected, "{password:?}");
+        }
+    }
+}
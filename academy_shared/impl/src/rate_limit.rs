@@ -0,0 +1,305 @@
+use std::time::Duration;
+
+use academy_cache_contracts::CacheService;
+use academy_di::Build;
+use academy_shared_contracts::{
+    rate_limit::{RateLimitPolicy, RateLimitService, RateLimitStatus},
+    time::TimeService,
+};
+use academy_utils::trace_instrument;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+
+/// Token bucket rate limiter, implemented using the generic cell rate
+/// algorithm.
+///
+/// Instead of the number of tokens, only the time at which the bucket will be
+/// full again is stored in the cache.
+#[derive(Debug, Clone, Build)]
+pub struct RateLimitServiceImpl<Time, Cache> {
+    time: Time,
+    cache: Cache,
+}
+
+impl<Time, Cache> RateLimitService for RateLimitServiceImpl<Time, Cache>
+where
+    Time: TimeService,
+    Cache: CacheService,
+{
+    #[trace_instrument(skip(self))]
+    async fn acquire(&self, key: &str, policy: RateLimitPolicy) -> anyhow::Result<RateLimitStatus> {
+        let now = self.time.now();
+        let cache_key = format!("rate_limit:{key}");
+
+        // time it takes to refill a single token
+        let interval = Duration::from_nanos(
+            (policy.period.as_nanos() / u128::from(policy.capacity.max(1))) as u64,
+        );
+
+        // retry until the bucket has not been modified concurrently
+        loop {
+            let old = self
+                .cache
+                .get::<DateTime<Utc>>(&cache_key)
+                .await
+                .context("Failed to get rate limit bucket from cache")?;
+            let full_at = old.map_or(now, |full_at| full_at.max(now));
+            let used = (full_at - now).to_std().unwrap_or_default() + interval;
+
+            if used > policy.period {
+                return Ok(RateLimitStatus {
+                    allowed: false,
+                    remaining: 0,
+                    reset: used - policy.period,
+                });
+            }
+
+            if self
+                .cache
+                .compare_and_swap(&cache_key, &old, &(now + used), Some(used))
+                .await
+                .context("Failed to save rate limit bucket in cache")?
+            {
+                return Ok(RateLimitStatus {
+                    allowed: true,
+                    remaining: ((policy.period - used).as_nanos() / interval.as_nanos().max(1))
+                        as u64,
+                    reset: used,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use academy_cache_contracts::MockCacheService;
+    use academy_shared_contracts::time::MockTimeService;
+    use serde::{de::DeserializeOwned, Serialize};
+
+    use super::*;
+
+    const KEY: &str = "user:ip:192.0.2.42";
+    const POLICY: RateLimitPolicy = RateLimitPolicy {
+        capacity: 10,
+        period: Duration::from_secs(60),
+    };
+
+    fn now() -> DateTime<Utc> {
+        DateTime::from_timestamp(1724949831, 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn new_bucket() {
+        // Arrange
+        let time = MockTimeService::new().with_now(now());
+
+        let cache = MockCacheService::new()
+            .with_get(format!("rate_limit:{KEY}"), None::<DateTime<Utc>>)
+            .with_compare_and_swap(
+                format!("rate_limit:{KEY}"),
+                None,
+                now() + Duration::from_secs(6),
+                Some(Duration::from_secs(6)),
+                true,
+            );
+
+        let sut = RateLimitServiceImpl { time, cache };
+
+        // Act
+        let result = sut.acquire(KEY, POLICY).await;
+
+        // Assert
+        assert_eq!(
+            result.unwrap(),
+            RateLimitStatus {
+                allowed: true,
+                remaining: 9,
+                reset: Duration::from_secs(6),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn refilled_bucket() {
+        // Arrange
+        let time = MockTimeService::new().with_now(now());
+
+        let cache = MockCacheService::new()
+            .with_get(
+                format!("rate_limit:{KEY}"),
+                Some(now() - Duration::from_secs(100)),
+            )
+            .with_compare_and_swap(
+                format!("rate_limit:{KEY}"),
+                Some(now() - Duration::from_secs(100)),
+                now() + Duration::from_secs(6),
+                Some(Duration::from_secs(6)),
+                true,
+            );
+
+        let sut = RateLimitServiceImpl { time, cache };
+
+        // Act
+        let result = sut.acquire(KEY, POLICY).await;
+
+        // Assert
+        assert_eq!(
+            result.unwrap(),
+            RateLimitStatus {
+                allowed: true,
+                remaining: 9,
+                reset: Duration::from_secs(6),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn partially_used_bucket() {
+        // Arrange
+        let time = MockTimeService::new().with_now(now());
+
+        let cache = MockCacheService::new()
+            .with_get(
+                format!("rate_limit:{KEY}"),
+                Some(now() + Duration::from_secs(30)),
+            )
+            .with_compare_and_swap(
+                format!("rate_limit:{KEY}"),
+                Some(now() + Duration::from_secs(30)),
+                now() + Duration::from_secs(36),
+                Some(Duration::from_secs(36)),
+                true,
+            );
+
+        let sut = RateLimitServiceImpl { time, cache };
+
+        // Act
+        let result = sut.acquire(KEY, POLICY).await;
+
+        // Assert
+        assert_eq!(
+            result.unwrap(),
+            RateLimitStatus {
+                allowed: true,
+                remaining: 4,
+                reset: Duration::from_secs(36),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn empty_bucket() {
+        // Arrange
+        let time = MockTimeService::new().with_now(now());
+
+        let cache = MockCacheService::new().with_get(
+            format!("rate_limit:{KEY}"),
+            Some(now() + Duration::from_secs(57)),
+        );
+
+        let sut = RateLimitServiceImpl { time, cache };
+
+        // Act
+        let result = sut.acquire(KEY, POLICY).await;
+
+        // Assert
+        assert_eq!(
+            result.unwrap(),
+            RateLimitStatus {
+                allowed: false,
+                remaining: 0,
+                reset: Duration::from_secs(3),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn concurrent_acquire() {
+        // Arrange
+        let mut time = MockTimeService::new();
+        time.expect_now().return_const(now());
+
+        let sut = RateLimitServiceImpl {
+            time,
+            cache: MemoryCache::default(),
+        };
+
+        // Act
+        let results = futures::future::join_all((0..20).map(|_| sut.acquire(KEY, POLICY))).await;
+
+        // Assert
+        let allowed = results
+            .into_iter()
+            .filter(|result| result.as_ref().unwrap().allowed)
+            .count();
+        assert_eq!(allowed, 10);
+    }
+
+    /// In-memory cache which yields before each operation so that concurrent
+    /// requests are interleaved.
+    #[derive(Default)]
+    struct MemoryCache(std::sync::Mutex<std::collections::HashMap<String, serde_json::Value>>);
+
+    impl CacheService for MemoryCache {
+        async fn get<T: DeserializeOwned + Debug + 'static>(
+            &self,
+            key: &str,
+        ) -> anyhow::Result<Option<T>> {
+            tokio::task::yield_now().await;
+            let value = self.0.lock().unwrap().get(key).cloned();
+            Ok(value.map(serde_json::from_value).transpose()?)
+        }
+
+        async fn take<T: DeserializeOwned + Debug + 'static>(
+            &self,
+            key: &str,
+        ) -> anyhow::Result<Option<T>> {
+            tokio::task::yield_now().await;
+            let value = self.0.lock().unwrap().remove(key);
+            Ok(value.map(serde_json::from_value).transpose()?)
+        }
+
+        async fn set<T: Serialize + Debug + Sync + 'static>(
+            &self,
+            key: &str,
+            value: &T,
+            _ttl: Option<Duration>,
+        ) -> anyhow::Result<()> {
+            tokio::task::yield_now().await;
+            let value = serde_json::to_value(value)?;
+            self.0.lock().unwrap().insert(key.into(), value);
+            Ok(())
+        }
+
+        async fn compare_and_swap<T: Serialize + Debug + Sync + 'static>(
+            &self,
+            key: &str,
+            old: &Option<T>,
+            new: &T,
+            _ttl: Option<Duration>,
+        ) -> anyhow::Result<bool> {
+            tokio::task::yield_now().await;
+            let old = old.as_ref().map(serde_json::to_value).transpose()?;
+            let new = serde_json::to_value(new)?;
+            let mut items = self.0.lock().unwrap();
+            if items.get(key) != old.as_ref() {
+                return Ok(false);
+            }
+            items.insert(key.into(), new);
+            Ok(true)
+        }
+
+        async fn remove(&self, key: &str) -> anyhow::Result<()> {
+            tokio::task::yield_now().await;
+            self.0.lock().unwrap().remove(key);
+            Ok(())
+        }
+
+        async fn ping(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,443 @@
+use std::time::Duration;
+
+use academy_cache_contracts::CacheService;
+use academy_di::Build;
+use academy_shared_contracts::{
+    hash::HashService,
+    throttle::{ThrottleKey, ThrottleService},
+    time::TimeService,
+};
+use academy_utils::trace_instrument;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Build)]
+#[cfg_attr(test, derive(Default))]
+pub struct ThrottleServiceImpl<Time, Hash, Cache> {
+    time: Time,
+    hash: Hash,
+    cache: Cache,
+    config: ThrottleServiceConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct ThrottleServiceConfig {
+    /// Length of the sliding window in which attempts are counted
+    pub window: Duration,
+    /// Maximum number of attempts per account within the window
+    pub account_limit: u64,
+    /// Maximum number of attempts per client IP address within the window
+    pub ip_limit: u64,
+    /// Duration of the first lockout
+    pub lockout: Duration,
+    /// Upper bound for the duration of consecutive lockouts
+    pub lockout_max: Duration,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct ThrottleState {
+    /// Timestamps of the attempts within the current window
+    attempts: Vec<DateTime<Utc>>,
+    /// Number of consecutive lockouts
+    lockouts: u32,
+    locked_until: Option<DateTime<Utc>>,
+}
+
+impl<Time, Hash, Cache> ThrottleService for ThrottleServiceImpl<Time, Hash, Cache>
+where
+    Time: TimeService,
+    Hash: HashService,
+    Cache: CacheService,
+{
+    #[trace_instrument(skip(self))]
+    async fn check(&self, keys: &[ThrottleKey]) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let now = self.time.now();
+
+        let mut result = None;
+        for key in keys {
+            let state = self.get_state(&self.cache_key(key)).await?;
+            if let Some(locked_until) = state.locked_until.filter(|&x| x > now) {
+                result = result.max(Some(locked_until));
+            }
+        }
+
+        Ok(result)
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn record(&self, keys: &[ThrottleKey]) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let now = self.time.now();
+
+        let mut result = None;
+        for key in keys {
+            let cache_key = self.cache_key(key);
+
+            // retry until the state has not been modified concurrently
+            loop {
+                let old = self
+                    .cache
+                    .get::<ThrottleState>(&cache_key)
+                    .await
+                    .context("Failed to get throttle state from cache")?;
+                let mut state = old.clone().unwrap_or_default();
+
+                if let Some(locked_until) = state.locked_until.filter(|&x| x > now) {
+                    result = result.max(Some(locked_until));
+                    break;
+                }
+
+                state.attempts.retain(|&x| x > now - self.config.window);
+                state.attempts.push(now);
+
+                let mut lockout_until = None;
+                if state.attempts.len() as u64 > self.limit(key) {
+                    // double the lockout duration for each consecutive lockout
+                    let lockout = self
+                        .config
+                        .lockout
+                        .checked_mul(2u32.saturating_pow(state.lockouts))
+                        .unwrap_or(Duration::MAX)
+                        .min(self.config.lockout_max);
+                    let locked_until = now + lockout;
+
+                    state.attempts.clear();
+                    state.lockouts = state.lockouts.saturating_add(1);
+                    state.locked_until = Some(locked_until);
+                    lockout_until = Some(locked_until);
+                }
+
+                if self
+                    .cache
+                    .compare_and_swap(
+                        &cache_key,
+                        &old,
+                        &state,
+                        Some(self.config.window + self.config.lockout_max),
+                    )
+                    .await
+                    .context("Failed to save throttle state in cache")?
+                {
+                    result = result.max(lockout_until);
+                    break;
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn reset(&self, key: &ThrottleKey) -> anyhow::Result<()> {
+        self.cache
+            .remove(&self.cache_key(key))
+            .await
+            .context("Failed to remove throttle state from cache")
+    }
+}
+
+impl<Time, Hash, Cache> ThrottleServiceImpl<Time, Hash, Cache>
+where
+    Hash: HashService,
+    Cache: CacheService,
+{
+    async fn get_state(&self, cache_key: &str) -> anyhow::Result<ThrottleState> {
+        self.cache
+            .get(cache_key)
+            .await
+            .map(Option::unwrap_or_default)
+            .context("Failed to get throttle state from cache")
+    }
+
+    fn limit(&self, key: &ThrottleKey) -> u64 {
+        match key {
+            ThrottleKey::Account { .. } => self.config.account_limit,
+            ThrottleKey::Ip { .. } => self.config.ip_limit,
+        }
+    }
+
+    fn cache_key(&self, key: &ThrottleKey) -> String {
+        match key {
+            ThrottleKey::Account { action, id } => {
+                let hash = self.hash.sha256(id);
+                format!("throttle:{action}:account:{}", hex::encode(hash.0))
+            }
+            ThrottleKey::Ip { action, ip } => format!("throttle:{action}:ip:{ip}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use academy_cache_contracts::MockCacheService;
+    use academy_demo::{user::FOO, SHA256HASH1, SHA256HASH1_HEX};
+    use academy_shared_contracts::{hash::MockHashService, time::MockTimeService};
+
+    use super::*;
+
+    type Sut = ThrottleServiceImpl<MockTimeService, MockHashService, MockCacheService>;
+
+    const IP: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 42));
+
+    impl Default for ThrottleServiceConfig {
+        fn default() -> Self {
+            Self {
+                window: Duration::from_secs(900),
+                account_limit: 3,
+                ip_limit: 10,
+                lockout: Duration::from_secs(60),
+                lockout_max: Duration::from_secs(3600),
+            }
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::from_timestamp(1724949831, 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn check_not_locked() {
+        // Arrange
+        let time = MockTimeService::new().with_now(now());
+
+        let cache = MockCacheService::new().with_get(
+            "throttle:login:ip:192.0.2.42".into(),
+            Some(ThrottleState {
+                attempts: vec![now()],
+                lockouts: 1,
+                locked_until: Some(now() - Duration::from_secs(1)),
+            }),
+        );
+
+        let sut = ThrottleServiceImpl {
+            time,
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.check(&[ThrottleKey::ip("login", IP)]).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn check_locked() {
+        // Arrange
+        let locked_until = now() + Duration::from_secs(42);
+
+        let time = MockTimeService::new().with_now(now());
+
+        let hash = MockHashService::new().with_sha256(
+            FOO.user.name.clone().into_inner().to_lowercase(),
+            *SHA256HASH1,
+        );
+
+        let cache = MockCacheService::new()
+            .with_get(
+                format!("throttle:login:account:{SHA256HASH1_HEX}"),
+                Some(ThrottleState {
+                    attempts: vec![],
+                    lockouts: 1,
+                    locked_until: Some(locked_until),
+                }),
+            )
+            .with_get("throttle:login:ip:192.0.2.42".into(), None::<ThrottleState>);
+
+        let sut = ThrottleServiceImpl {
+            time,
+            hash,
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut
+            .check(&[
+                ThrottleKey::account("login", &FOO.user.name),
+                ThrottleKey::ip("login", IP),
+            ])
+            .await;
+
+        // Assert
+        assert_eq!(result.unwrap(), Some(locked_until));
+    }
+
+    #[tokio::test]
+    async fn record_below_limit() {
+        // Arrange
+        let old_attempt = now() - Duration::from_secs(1000);
+        let recent_attempt = now() - Duration::from_secs(100);
+
+        let time = MockTimeService::new().with_now(now());
+
+        let cache_key = "throttle:contact:ip:192.0.2.42".to_owned();
+        let state = ThrottleState {
+            attempts: vec![old_attempt, recent_attempt],
+            lockouts: 0,
+            locked_until: None,
+        };
+        let cache = MockCacheService::new()
+            .with_get(cache_key.clone(), Some(state.clone()))
+            .with_compare_and_swap(
+                cache_key,
+                Some(state),
+                ThrottleState {
+                    attempts: vec![recent_attempt, now()],
+                    lockouts: 0,
+                    locked_until: None,
+                },
+                Some(Duration::from_secs(4500)),
+                true,
+            );
+
+        let sut = ThrottleServiceImpl {
+            time,
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.record(&[ThrottleKey::ip("contact", IP)]).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn record_exceed_limit() {
+        // Arrange
+        let expected = now() + Duration::from_secs(240);
+
+        let time = MockTimeService::new().with_now(now());
+
+        let hash = MockHashService::new().with_sha256(
+            FOO.user.name.clone().into_inner().to_lowercase(),
+            *SHA256HASH1,
+        );
+
+        let cache_key = format!("throttle:login:account:{SHA256HASH1_HEX}");
+        let state = ThrottleState {
+            attempts: vec![now(); 3],
+            lockouts: 2,
+            locked_until: Some(now() - Duration::from_secs(1)),
+        };
+        let cache = MockCacheService::new()
+            .with_get(cache_key.clone(), Some(state.clone()))
+            .with_compare_and_swap(
+                cache_key,
+                Some(state),
+                ThrottleState {
+                    attempts: vec![],
+                    lockouts: 3,
+                    locked_until: Some(expected),
+                },
+                Some(Duration::from_secs(4500)),
+                true,
+            );
+
+        let sut = ThrottleServiceImpl {
+            time,
+            hash,
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut
+            .record(&[ThrottleKey::account("login", &FOO.user.name)])
+            .await;
+
+        // Assert
+        assert_eq!(result.unwrap(), Some(expected));
+    }
+
+    #[tokio::test]
+    async fn record_lockout_max() {
+        // Arrange
+        let expected = now() + Duration::from_secs(3600);
+
+        let time = MockTimeService::new().with_now(now());
+
+        let cache_key = "throttle:login:ip:192.0.2.42".to_owned();
+        let state = ThrottleState {
+            attempts: vec![now(); 10],
+            lockouts: 40,
+            locked_until: None,
+        };
+        let cache = MockCacheService::new()
+            .with_get(cache_key.clone(), Some(state.clone()))
+            .with_compare_and_swap(
+                cache_key,
+                Some(state),
+                ThrottleState {
+                    attempts: vec![],
+                    lockouts: 41,
+                    locked_until: Some(expected),
+                },
+                Some(Duration::from_secs(4500)),
+                true,
+            );
+
+        let sut = ThrottleServiceImpl {
+            time,
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.record(&[ThrottleKey::ip("login", IP)]).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), Some(expected));
+    }
+
+    #[tokio::test]
+    async fn record_locked() {
+        // Arrange
+        let locked_until = now() + Duration::from_secs(42);
+
+        let time = MockTimeService::new().with_now(now());
+
+        let cache = MockCacheService::new().with_get(
+            "throttle:login:ip:192.0.2.42".into(),
+            Some(ThrottleState {
+                attempts: vec![],
+                lockouts: 1,
+                locked_until: Some(locked_until),
+            }),
+        );
+
+        let sut = ThrottleServiceImpl {
+            time,
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.record(&[ThrottleKey::ip("login", IP)]).await;
+
+        // Assert
+        assert_eq!(result.unwrap(), Some(locked_until));
+    }
+
+    #[tokio::test]
+    async fn reset() {
+        // Arrange
+        let cache = MockCacheService::new().with_remove("throttle:login:ip:192.0.2.42".into());
+
+        let sut = ThrottleServiceImpl {
+            cache,
+            ..Sut::default()
+        };
+
+        // Act
+        let result = sut.reset(&ThrottleKey::ip("login", IP)).await;
+
+        // Assert
+        result.unwrap();
+    }
+}
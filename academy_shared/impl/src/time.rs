@@ -3,7 +3,7 @@ use academy_shared_contracts::time::TimeService;
 use academy_utils::trace_instrument;
 use chrono::{DateTime, Utc};
 
-#[derive(Debug, Clone, Copy, Build)]
+#[derive(Debug, Clone, Copy, Default, Build)]
 pub struct TimeServiceImpl;
 
 impl TimeService for TimeServiceImpl {
@@ -0,0 +1,652 @@
+use std::{sync::Arc, time::Duration};
+
+use academy_di::Build;
+use academy_models::{
+    mfa::{
+        WebauthnAssertion, WebauthnAuthenticationOptions, WebauthnChallenge, WebauthnCredentialKey,
+        WebauthnCredentialRawId, WebauthnRegistrationOptions, WebauthnRegistrationResponse,
+    },
+    user::{UserDisplayName, UserId, UserName},
+};
+use academy_shared_contracts::{
+    secret::SecretService,
+    webauthn::{WebauthnService, WebauthnVerifyError},
+};
+use academy_utils::trace_instrument;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ciborium::Value;
+use ring::signature::{UnparsedPublicKey, ECDSA_P256_SHA256_ASN1, ED25519};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+const CHALLENGE_LENGTH: usize = 32;
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_ATTESTED_CREDENTIAL_DATA: u8 = 0x40;
+
+#[derive(Debug, Clone, Build)]
+#[cfg_attr(test, derive(Default))]
+pub struct WebauthnServiceImpl<Secret> {
+    secret: Secret,
+    config: WebauthnServiceConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct WebauthnServiceConfig {
+    /// The relying party id (usually the domain of the frontend)
+    pub rp_id: Arc<str>,
+    /// The human-palatable name of the relying party
+    pub rp_name: Arc<str>,
+    /// The origins from which WebAuthn requests are accepted
+    pub origins: Arc<[String]>,
+    /// The time the client has to respond to a challenge
+    pub timeout: Duration,
+}
+
+impl<Secret> WebauthnService for WebauthnServiceImpl<Secret>
+where
+    Secret: SecretService,
+{
+    #[trace_instrument(skip(self))]
+    fn start_registration(
+        &self,
+        user_id: UserId,
+        user_name: UserName,
+        user_display_name: UserDisplayName,
+        exclude_credentials: Vec<WebauthnCredentialRawId>,
+    ) -> WebauthnRegistrationOptions {
+        WebauthnRegistrationOptions {
+            challenge: self.generate_challenge(),
+            rp_id: self.config.rp_id.to_string(),
+            rp_name: self.config.rp_name.to_string(),
+            user_id,
+            user_name,
+            user_display_name,
+            exclude_credentials,
+            timeout: self.config.timeout,
+        }
+    }
+
+    #[trace_instrument(skip(self))]
+    fn finish_registration(
+        &self,
+        challenge: &WebauthnChallenge,
+        response: &WebauthnRegistrationResponse,
+    ) -> Result<(WebauthnCredentialRawId, WebauthnCredentialKey), WebauthnVerifyError> {
+        self.check_client_data(&response.client_data_json, "webauthn.create", challenge)?;
+
+        let attestation_object =
+            ciborium::from_reader::<Value, _>(response.attestation_object.as_slice())
+                .ok()
+                .and_then(|value| value.into_map().ok())
+                .ok_or(WebauthnVerifyError::InvalidAuthenticatorData(
+                    "invalid attestation object",
+                ))?;
+        let auth_data = attestation_object
+            .into_iter()
+            .find(|(k, _)| k.as_text() == Some("authData"))
+            .and_then(|(_, v)| v.into_bytes().ok())
+            .ok_or(WebauthnVerifyError::InvalidAuthenticatorData(
+                "missing authenticator data",
+            ))?;
+
+        let auth_data = self.parse_authenticator_data(&auth_data)?;
+        if auth_data.flags & FLAG_ATTESTED_CREDENTIAL_DATA == 0 {
+            return Err(WebauthnVerifyError::InvalidAuthenticatorData(
+                "missing attested credential data",
+            ));
+        }
+
+        let (raw_id, public_key) = parse_attested_credential_data(auth_data.rest)?;
+
+        // make sure the public key can actually be used to verify signatures
+        CoseKey::parse(&public_key)?;
+
+        Ok((
+            raw_id,
+            WebauthnCredentialKey {
+                public_key: public_key.into(),
+                sign_count: auth_data.sign_count,
+            },
+        ))
+    }
+
+    #[trace_instrument(skip(self))]
+    fn start_authentication(&self) -> WebauthnAuthenticationOptions {
+        WebauthnAuthenticationOptions {
+            challenge: self.generate_challenge(),
+            rp_id: self.config.rp_id.to_string(),
+            timeout: self.config.timeout,
+        }
+    }
+
+    #[trace_instrument(skip(self))]
+    fn assertion_challenge(&self, assertion: &WebauthnAssertion) -> Option<WebauthnChallenge> {
+        serde_json::from_slice::<ClientData>(&assertion.client_data_json)
+            .ok()
+            .map(|client_data| client_data.challenge.trim_end_matches('=').into())
+    }
+
+    #[trace_instrument(skip(self))]
+    fn finish_authentication(
+        &self,
+        challenge: &WebauthnChallenge,
+        assertion: &WebauthnAssertion,
+        key: &WebauthnCredentialKey,
+    ) -> Result<u32, WebauthnVerifyError> {
+        self.check_client_data(&assertion.client_data_json, "webauthn.get", challenge)?;
+
+        let auth_data = self.parse_authenticator_data(&assertion.authenticator_data)?;
+
+        let signed_data = [
+            assertion.authenticator_data.as_slice(),
+            &Sha256::digest(&assertion.client_data_json),
+        ]
+        .concat();
+        CoseKey::parse(&key.public_key)?.verify(&signed_data, &assertion.signature)?;
+
+        // Authenticators that do not implement a signature counter always return zero.
+        // Otherwise the counter must increase with each assertion, a counter that did
+        // not increase indicates that the credential might have been cloned.
+        if (auth_data.sign_count != 0 || key.sign_count != 0)
+            && auth_data.sign_count <= key.sign_count
+        {
+            return Err(WebauthnVerifyError::CounterNotIncreased);
+        }
+
+        Ok(auth_data.sign_count)
+    }
+}
+
+impl<Secret: SecretService> WebauthnServiceImpl<Secret> {
+    fn generate_challenge(&self) -> WebauthnChallenge {
+        URL_SAFE_NO_PAD
+            .encode(self.secret.generate_bytes(CHALLENGE_LENGTH).0)
+            .into()
+    }
+
+    fn check_client_data(
+        &self,
+        client_data_json: &[u8],
+        expected_type: &str,
+        expected_challenge: &WebauthnChallenge,
+    ) -> Result<(), WebauthnVerifyError> {
+        let client_data = serde_json::from_slice::<ClientData>(client_data_json)
+            .map_err(|_| WebauthnVerifyError::InvalidClientData("invalid json"))?;
+
+        if client_data.r#type != expected_type {
+            return Err(WebauthnVerifyError::InvalidClientData("unexpected type"));
+        }
+
+        if client_data.challenge.trim_end_matches('=') != **expected_challenge {
+            return Err(WebauthnVerifyError::InvalidClientData(
+                "unexpected challenge",
+            ));
+        }
+
+        if !self.config.origins.contains(&client_data.origin) {
+            return Err(WebauthnVerifyError::InvalidClientData("unexpected origin"));
+        }
+
+        Ok(())
+    }
+
+    fn parse_authenticator_data<'a>(
+        &self,
+        data: &'a [u8],
+    ) -> Result<AuthenticatorData<'a>, WebauthnVerifyError> {
+        if data.len() < 37 {
+            return Err(WebauthnVerifyError::InvalidAuthenticatorData("too short"));
+        }
+
+        let (rp_id_hash, data) = data.split_at(32);
+        if rp_id_hash != Sha256::digest(self.config.rp_id.as_bytes()).as_slice() {
+            return Err(WebauthnVerifyError::InvalidAuthenticatorData(
+                "unexpected rp id hash",
+            ));
+        }
+
+        let flags = data[0];
+        if flags & FLAG_USER_PRESENT == 0 {
+            return Err(WebauthnVerifyError::InvalidAuthenticatorData(
+                "user not present",
+            ));
+        }
+
+        let sign_count = u32::from_be_bytes(data[1..5].try_into().unwrap());
+
+        Ok(AuthenticatorData {
+            flags,
+            sign_count,
+            rest: &data[5..],
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ClientData {
+    r#type: String,
+    challenge: String,
+    origin: String,
+}
+
+struct AuthenticatorData<'a> {
+    flags: u8,
+    sign_count: u32,
+    rest: &'a [u8],
+}
+
+/// Parse the attested credential data and return the credential id and the
+/// COSE encoded public key.
+fn parse_attested_credential_data(
+    data: &[u8],
+) -> Result<(WebauthnCredentialRawId, Vec<u8>), WebauthnVerifyError> {
+    let invalid = || WebauthnVerifyError::InvalidAuthenticatorData("invalid credential data");
+
+    // skip the aaguid
+    let data = data.get(16..).ok_or_else(invalid)?;
+
+    let len = u16::from_be_bytes(data.get(..2).ok_or_else(invalid)?.try_into().unwrap()) as usize;
+    let raw_id = data.get(2..2 + len).ok_or_else(invalid)?;
+    let raw_id = WebauthnCredentialRawId::try_new(raw_id.to_vec()).map_err(|_| invalid())?;
+
+    let data = &data[2 + len..];
+    let mut rest = data;
+    ciborium::from_reader::<Value, _>(&mut rest).map_err(|_| invalid())?;
+    let public_key = data[..data.len() - rest.len()].to_vec();
+
+    Ok((raw_id, public_key))
+}
+
+enum CoseKey {
+    Es256(Vec<u8>),
+    Ed25519(Vec<u8>),
+}
+
+impl CoseKey {
+    const KTY_OKP: i128 = 1;
+    const KTY_EC2: i128 = 2;
+    const ALG_ES256: i128 = -7;
+    const ALG_EDDSA: i128 = -8;
+    const CRV_P256: i128 = 1;
+    const CRV_ED25519: i128 = 6;
+
+    fn parse(data: &[u8]) -> Result<Self, WebauthnVerifyError> {
+        let map = ciborium::from_reader::<Value, _>(data)
+            .ok()
+            .and_then(|value| value.into_map().ok())
+            .ok_or(WebauthnVerifyError::InvalidPublicKey)?;
+
+        let get = |label: i128| {
+            map.iter()
+                .find(|(k, _)| k.as_integer().map(i128::from) == Some(label))
+                .map(|(_, v)| v)
+        };
+        let get_int = |label: i128| get(label).and_then(|v| v.as_integer()).map(i128::from);
+        let get_bytes = |label: i128| get(label).and_then(|v| v.as_bytes());
+
+        match (get_int(1), get_int(3), get_int(-1)) {
+            (Some(Self::KTY_EC2), Some(Self::ALG_ES256), Some(Self::CRV_P256)) => {
+                let (x, y) = get_bytes(-2)
+                    .zip(get_bytes(-3))
+                    .filter(|(x, y)| x.len() == 32 && y.len() == 32)
+                    .ok_or(WebauthnVerifyError::InvalidPublicKey)?;
+                Ok(Self::Es256([&[0x04], x.as_slice(), y.as_slice()].concat()))
+            }
+            (Some(Self::KTY_OKP), Some(Self::ALG_EDDSA), Some(Self::CRV_ED25519)) => {
+                let x = get_bytes(-2)
+                    .filter(|x| x.len() == 32)
+                    .ok_or(WebauthnVerifyError::InvalidPublicKey)?;
+                Ok(Self::Ed25519(x.clone()))
+            }
+            _ => Err(WebauthnVerifyError::InvalidPublicKey),
+        }
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> Result<(), WebauthnVerifyError> {
+        match self {
+            Self::Es256(key) => UnparsedPublicKey::new(&ECDSA_P256_SHA256_ASN1, key),
+            Self::Ed25519(key) => UnparsedPublicKey::new(&ED25519, key),
+        }
+        .verify(message, signature)
+        .map_err(|_| WebauthnVerifyError::InvalidSignature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use academy_demo::user::FOO;
+    use academy_shared_contracts::secret::MockSecretService;
+    use academy_utils::assert_matches;
+    use ring::{
+        rand::SystemRandom,
+        signature::{EcdsaKeyPair, Ed25519KeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING},
+    };
+
+    use super::*;
+
+    const RP_ID: &str = "bootstrap.academy";
+    const ORIGIN: &str = "https://bootstrap.academy";
+
+    impl Default for WebauthnServiceConfig {
+        fn default() -> Self {
+            Self {
+                rp_id: RP_ID.into(),
+                rp_name: "Bootstrap Academy".into(),
+                origins: [ORIGIN.into()].into(),
+                timeout: Duration::from_secs(300),
+            }
+        }
+    }
+
+    /// Minimal software implementation of a WebAuthn authenticator
+    struct SoftwareAuthenticator {
+        raw_id: Vec<u8>,
+        key: SoftwareKey,
+        sign_count: u32,
+        rp_id: &'static str,
+        origin: &'static str,
+    }
+
+    enum SoftwareKey {
+        Es256(EcdsaKeyPair),
+        Ed25519(Ed25519KeyPair),
+    }
+
+    impl SoftwareAuthenticator {
+        fn es256() -> Self {
+            let rng = SystemRandom::new();
+            let pkcs8 =
+                EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+            let key =
+                EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng)
+                    .unwrap();
+            Self::new(SoftwareKey::Es256(key))
+        }
+
+        fn ed25519() -> Self {
+            let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+            let key = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+            Self::new(SoftwareKey::Ed25519(key))
+        }
+
+        fn new(key: SoftwareKey) -> Self {
+            Self {
+                raw_id: b"the credential id".to_vec(),
+                key,
+                sign_count: 0,
+                rp_id: RP_ID,
+                origin: ORIGIN,
+            }
+        }
+
+        fn cose_key(&self) -> Vec<u8> {
+            let map = match &self.key {
+                SoftwareKey::Es256(key) => {
+                    let point = key.public_key().as_ref();
+                    vec![
+                        (1.into(), 2.into()),
+                        (3.into(), (-7).into()),
+                        ((-1).into(), 1.into()),
+                        ((-2).into(), Value::Bytes(point[1..33].to_vec())),
+                        ((-3).into(), Value::Bytes(point[33..].to_vec())),
+                    ]
+                }
+                SoftwareKey::Ed25519(key) => vec![
+                    (1.into(), 1.into()),
+                    (3.into(), (-8).into()),
+                    ((-1).into(), 6.into()),
+                    (
+                        (-2).into(),
+                        Value::Bytes(key.public_key().as_ref().to_vec()),
+                    ),
+                ],
+            };
+            let mut out = Vec::new();
+            ciborium::into_writer(&Value::Map(map), &mut out).unwrap();
+            out
+        }
+
+        fn client_data(&self, ty: &str, challenge: &WebauthnChallenge) -> Vec<u8> {
+            serde_json::json!({"type": ty, "challenge": **challenge, "origin": self.origin})
+                .to_string()
+                .into_bytes()
+        }
+
+        fn authenticator_data(&self, flags: u8) -> Vec<u8> {
+            [
+                Sha256::digest(self.rp_id.as_bytes()).as_slice(),
+                &[flags],
+                &self.sign_count.to_be_bytes(),
+            ]
+            .concat()
+        }
+
+        fn register(&self, challenge: &WebauthnChallenge) -> WebauthnRegistrationResponse {
+            let auth_data = [
+                self.authenticator_data(FLAG_USER_PRESENT | FLAG_ATTESTED_CREDENTIAL_DATA),
+                vec![0; 16],
+                (self.raw_id.len() as u16).to_be_bytes().to_vec(),
+                self.raw_id.clone(),
+                self.cose_key(),
+            ]
+            .concat();
+
+            let attestation_object = Value::Map(vec![
+                ("fmt".into(), "none".into()),
+                ("attStmt".into(), Value::Map(vec![])),
+                ("authData".into(), Value::Bytes(auth_data)),
+            ]);
+            let mut attestation_object_bytes = Vec::new();
+            ciborium::into_writer(&attestation_object, &mut attestation_object_bytes).unwrap();
+
+            WebauthnRegistrationResponse {
+                client_data_json: self.client_data("webauthn.create", challenge),
+                attestation_object: attestation_object_bytes,
+            }
+        }
+
+        fn assert(&mut self, challenge: &WebauthnChallenge) -> WebauthnAssertion {
+            self.sign_count += 1;
+            let client_data_json = self.client_data("webauthn.get", challenge);
+            let authenticator_data = self.authenticator_data(FLAG_USER_PRESENT);
+            let message = [
+                authenticator_data.as_slice(),
+                &Sha256::digest(&client_data_json),
+            ]
+            .concat();
+            let signature = match &self.key {
+                SoftwareKey::Es256(key) => key
+                    .sign(&SystemRandom::new(), &message)
+                    .unwrap()
+                    .as_ref()
+                    .to_vec(),
+                SoftwareKey::Ed25519(key) => key.sign(&message).as_ref().to_vec(),
+            };
+
+            WebauthnAssertion {
+                raw_id: self.raw_id.clone().try_into().unwrap(),
+                client_data_json,
+                authenticator_data,
+                signature,
+            }
+        }
+    }
+
+    fn challenge() -> WebauthnChallenge {
+        URL_SAFE_NO_PAD.encode([42; CHALLENGE_LENGTH]).into()
+    }
+
+    #[test]
+    fn start_registration() {
+        // Arrange
+        let secret = MockSecretService::new().with_generate_bytes(CHALLENGE_LENGTH, vec![42; 32]);
+
+        let sut = WebauthnServiceImpl {
+            secret,
+            ..Default::default()
+        };
+
+        // Act
+        let result = sut.start_registration(
+            FOO.user.id,
+            FOO.user.name.clone(),
+            FOO.profile.display_name.clone(),
+            vec![],
+        );
+
+        // Assert
+        assert_eq!(
+            result,
+            WebauthnRegistrationOptions {
+                challenge: challenge(),
+                rp_id: RP_ID.into(),
+                rp_name: "Bootstrap Academy".into(),
+                user_id: FOO.user.id,
+                user_name: FOO.user.name.clone(),
+                user_display_name: FOO.profile.display_name.clone(),
+                exclude_credentials: vec![],
+                timeout: Duration::from_secs(300),
+            }
+        );
+    }
+
+    #[test]
+    fn register_and_authenticate_es256() {
+        register_and_authenticate(SoftwareAuthenticator::es256());
+    }
+
+    #[test]
+    fn register_and_authenticate_ed25519() {
+        register_and_authenticate(SoftwareAuthenticator::ed25519());
+    }
+
+    fn register_and_authenticate(mut authenticator: SoftwareAuthenticator) {
+        // Arrange
+        let sut = WebauthnServiceImpl::<MockSecretService>::default();
+        let response = authenticator.register(&challenge());
+
+        // Act
+        let (raw_id, key) = sut.finish_registration(&challenge(), &response).unwrap();
+        let assertion = authenticator.assert(&challenge());
+        let assertion_challenge = sut.assertion_challenge(&assertion);
+        let sign_count = sut.finish_authentication(&challenge(), &assertion, &key);
+
+        // Assert
+        assert_eq!(*raw_id, authenticator.raw_id);
+        assert_eq!(key.sign_count, 0);
+        assert_eq!(assertion_challenge, Some(challenge()));
+        assert_eq!(sign_count.unwrap(), 1);
+    }
+
+    #[test]
+    fn register_invalid_challenge() {
+        // Arrange
+        let sut = WebauthnServiceImpl::<MockSecretService>::default();
+        let response = SoftwareAuthenticator::es256().register(&"other challenge".into());
+
+        // Act
+        let result = sut.finish_registration(&challenge(), &response);
+
+        // Assert
+        assert_matches!(
+            result,
+            Err(WebauthnVerifyError::InvalidClientData(
+                "unexpected challenge"
+            ))
+        );
+    }
+
+    #[test]
+    fn register_invalid_origin() {
+        // Arrange
+        let sut = WebauthnServiceImpl::<MockSecretService>::default();
+        let mut authenticator = SoftwareAuthenticator::es256();
+        authenticator.origin = "https://evil.example";
+        let response = authenticator.register(&challenge());
+
+        // Act
+        let result = sut.finish_registration(&challenge(), &response);
+
+        // Assert
+        assert_matches!(
+            result,
+            Err(WebauthnVerifyError::InvalidClientData("unexpected origin"))
+        );
+    }
+
+    #[test]
+    fn register_invalid_rp_id() {
+        // Arrange
+        let sut = WebauthnServiceImpl::<MockSecretService>::default();
+        let mut authenticator = SoftwareAuthenticator::es256();
+        authenticator.rp_id = "evil.example";
+        let response = authenticator.register(&challenge());
+
+        // Act
+        let result = sut.finish_registration(&challenge(), &response);
+
+        // Assert
+        assert_matches!(
+            result,
+            Err(WebauthnVerifyError::InvalidAuthenticatorData(
+                "unexpected rp id hash"
+            ))
+        );
+    }
+
+    #[test]
+    fn authenticate_invalid_signature() {
+        // Arrange
+        let sut = WebauthnServiceImpl::<MockSecretService>::default();
+        let mut authenticator = SoftwareAuthenticator::es256();
+        let (_, key) = sut
+            .finish_registration(&challenge(), &authenticator.register(&challenge()))
+            .unwrap();
+        let mut assertion = authenticator.assert(&challenge());
+        assertion.authenticator_data[32] |= 0x04;
+
+        // Act
+        let result = sut.finish_authentication(&challenge(), &assertion, &key);
+
+        // Assert
+        assert_matches!(result, Err(WebauthnVerifyError::InvalidSignature));
+    }
+
+    #[test]
+    fn authenticate_wrong_key() {
+        // Arrange
+        let sut = WebauthnServiceImpl::<MockSecretService>::default();
+        let (_, key) = sut
+            .finish_registration(
+                &challenge(),
+                &SoftwareAuthenticator::es256().register(&challenge()),
+            )
+            .unwrap();
+        let assertion = SoftwareAuthenticator::es256().assert(&challenge());
+
+        // Act
+        let result = sut.finish_authentication(&challenge(), &assertion, &key);
+
+        // Assert
+        assert_matches!(result, Err(WebauthnVerifyError::InvalidSignature));
+    }
+
+    #[test]
+    fn authenticate_counter_not_increased() {
+        // Arrange
+        let sut = WebauthnServiceImpl::<MockSecretService>::default();
+        let mut authenticator = SoftwareAuthenticator::ed25519();
+        let (_, mut key) = sut
+            .finish_registration(&challenge(), &authenticator.register(&challenge()))
+            .unwrap();
+        key.sign_count = 7;
+        let assertion = authenticator.assert(&challenge());
+
+        // Act
+        let result = sut.finish_authentication(&challenge(), &assertion, &key);
+
+        // Assert
+        assert_matches!(result, Err(WebauthnVerifyError::CounterNotIncreased));
+    }
+}
@@ -0,0 +1,46 @@
+use std::future::Future;
+
+use academy_models::url::Url;
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait StorageService: Send + Sync + 'static {
+    /// Return the public URL of the object with the given key.
+    ///
+    /// The object does not need to exist.
+    fn url(&self, key: &str) -> Url;
+
+    /// Create a new or replace an existing object.
+    fn put(&self, key: &str, data: Vec<u8>) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Remove an existing object.
+    ///
+    /// Does nothing if the object does not exist.
+    fn remove(&self, key: &str) -> impl Future<Output = anyhow::Result<()>> + Send;
+}
+
+#[cfg(feature = "mock")]
+impl MockStorageService {
+    pub fn with_url(mut self, key: String, url: Url) -> Self {
+        self.expect_url()
+            .once()
+            .with(mockall::predicate::eq(key))
+            .return_once(|_| url);
+        self
+    }
+
+    pub fn with_put(mut self, key: String, data: Vec<u8>) -> Self {
+        self.expect_put()
+            .once()
+            .with(mockall::predicate::eq(key), mockall::predicate::eq(data))
+            .return_once(|_, _| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
+
+    pub fn with_remove(mut self, key: String) -> Self {
+        self.expect_remove()
+            .once()
+            .with(mockall::predicate::eq(key))
+            .return_once(|_| Box::pin(std::future::ready(Ok(()))));
+        self
+    }
+}
@@ -0,0 +1,97 @@
+use std::{
+    io::ErrorKind,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use academy_di::Build;
+use academy_models::url::Url;
+use academy_storage_contracts::StorageService;
+use academy_utils::trace_instrument;
+use anyhow::{anyhow, Context};
+
+/// Stores objects as files in a local directory.
+///
+/// The directory is expected to be served at the configured public URL (e.g.
+/// by the reverse proxy in front of the REST API).
+#[derive(Debug, Clone, Build)]
+pub struct LocalStorage {
+    config: LocalStorageConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct LocalStorageConfig {
+    pub path: Arc<PathBuf>,
+    pub public_url: Arc<Url>,
+}
+
+impl LocalStorage {
+    pub fn new(config: LocalStorageConfig) -> Self {
+        Self { config }
+    }
+
+    fn path(&self, key: &str) -> anyhow::Result<PathBuf> {
+        let key = Path::new(key);
+        if key.as_os_str().is_empty()
+            || !key
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(anyhow!("Invalid storage key: {}", key.display()));
+        }
+
+        Ok(self.config.path.join(key))
+    }
+}
+
+impl StorageService for LocalStorage {
+    fn url(&self, key: &str) -> Url {
+        self.config
+            .public_url
+            .join(key)
+            .expect("storage keys are valid relative URLs")
+            .into()
+    }
+
+    #[trace_instrument(skip(self, data))]
+    async fn put(&self, key: &str, data: Vec<u8>) -> anyhow::Result<()> {
+        let path = self.path(key)?;
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+
+        // write to a temporary file first to never serve partially written objects
+        let tmp = path.with_extension("tmp");
+        tokio::fs::write(&tmp, data)
+            .await
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        tokio::fs::rename(&tmp, &path)
+            .await
+            .with_context(|| format!("Failed to move {} to {}", tmp.display(), path.display()))
+    }
+
+    #[trace_instrument(skip(self))]
+    async fn remove(&self, key: &str) -> anyhow::Result<()> {
+        let path = self.path(key)?;
+
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to remove {}", path.display()))
+            }
+        }
+
+        // clean up directories that have become empty
+        for dir in path.ancestors().skip(1) {
+            if dir == self.config.path.as_path() || tokio::fs::remove_dir(dir).await.is_err() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
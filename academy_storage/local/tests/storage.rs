@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use academy_models::url::Url;
+use academy_storage_contracts::StorageService;
+use academy_storage_local::{LocalStorage, LocalStorageConfig};
+
+#[tokio::test]
+async fn url() {
+    let (storage, _) = setup("url").await;
+
+    let result = storage.url("avatars/foo/256.webp");
+
+    assert_eq!(
+        result.as_str(),
+        "https://example.com/storage/avatars/foo/256.webp"
+    );
+}
+
+#[tokio::test]
+async fn put() {
+    let (storage, path) = setup("put").await;
+
+    storage
+        .put("avatars/foo/256.webp", b"hello".into())
+        .await
+        .unwrap();
+    assert_eq!(
+        std::fs::read(path.join("avatars/foo/256.webp")).unwrap(),
+        b"hello"
+    );
+
+    storage
+        .put("avatars/foo/256.webp", b"world".into())
+        .await
+        .unwrap();
+    assert_eq!(
+        std::fs::read(path.join("avatars/foo/256.webp")).unwrap(),
+        b"world"
+    );
+    assert_eq!(
+        std::fs::read_dir(path.join("avatars/foo")).unwrap().count(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn remove() {
+    let (storage, path) = setup("remove").await;
+
+    storage
+        .put("avatars/foo/64.webp", b"64".into())
+        .await
+        .unwrap();
+    storage
+        .put("avatars/foo/256.webp", b"256".into())
+        .await
+        .unwrap();
+
+    storage.remove("avatars/foo/64.webp").await.unwrap();
+    assert!(!path.join("avatars/foo/64.webp").exists());
+    assert!(path.join("avatars/foo/256.webp").exists());
+
+    storage.remove("avatars/foo/256.webp").await.unwrap();
+    assert!(!path.join("avatars").exists());
+    assert!(path.exists());
+
+    storage.remove("avatars/foo/256.webp").await.unwrap();
+}
+
+#[tokio::test]
+async fn invalid_key() {
+    let (storage, _) = setup("invalid_key").await;
+
+    for key in ["../foo", "/foo", "foo/../../bar", ""] {
+        storage.put(key, b"hello".into()).await.unwrap_err();
+        storage.remove(key).await.unwrap_err();
+    }
+}
+
+async fn setup(name: &str) -> (LocalStorage, PathBuf) {
+    let path = std::env::temp_dir().join(format!(
+        "academy_storage_local_test_{}_{name}",
+        std::process::id()
+    ));
+    let _ = tokio::fs::remove_dir_all(&path).await;
+    tokio::fs::create_dir_all(&path).await.unwrap();
+
+    let storage = LocalStorage::new(LocalStorageConfig {
+        path: path.clone().into(),
+        public_url: "https://example.com/storage/"
+            .parse::<Url>()
+            .unwrap()
+            .into(),
+    });
+
+    (storage, path)
+}
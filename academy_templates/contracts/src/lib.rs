@@ -48,6 +48,15 @@ templates! {
     ResetPasswordTemplate(templates::RESET_PASSWORD_HTML),
     VerifyEmailTemplate(templates::VERIFY_EMAIL_HTML),
     SubscribeNewsletterTemplate(templates::SUBSCRIBE_NEWSLETTER_HTML),
+    LoginLinkTemplate(templates::LOGIN_LINK_HTML),
+    NewSessionTemplate(templates::NEW_SESSION_HTML),
+    PasswordChangedTemplate(templates::PASSWORD_CHANGED_HTML),
+    MfaDisabledTemplate(templates::MFA_DISABLED_HTML),
+    EmailChangedTemplate(templates::EMAIL_CHANGED_HTML),
+    OAuth2LinkCreatedTemplate(templates::OAUTH2_LINK_CREATED_HTML),
+    UserExportTemplate(templates::USER_EXPORT_HTML),
+    UserDeletedTemplate(templates::USER_DELETED_HTML),
+    SessionRevokedTemplate(templates::SESSION_REVOKED_HTML),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
@@ -67,3 +76,47 @@ pub struct SubscribeNewsletterTemplate {
     pub code: String,
     pub url: String,
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LoginLinkTemplate {
+    pub code: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NewSessionTemplate {
+    pub device_name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PasswordChangedTemplate {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MfaDisabledTemplate {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EmailChangedTemplate {
+    pub new_email: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OAuth2LinkCreatedTemplate {
+    pub provider: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UserExportTemplate {
+    pub code: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UserDeletedTemplate {
+    pub code: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionRevokedTemplate {
+    pub device_name: Option<String>,
+}
@@ -46,7 +46,10 @@ impl TemplateService for TemplateServiceImpl {
 #[cfg(test)]
 mod tests {
     use academy_templates_contracts::{
-        ResetPasswordTemplate, SubscribeNewsletterTemplate, VerifyEmailTemplate,
+        EmailChangedTemplate, LoginLinkTemplate, MfaDisabledTemplate, NewSessionTemplate,
+        OAuth2LinkCreatedTemplate, PasswordChangedTemplate, ResetPasswordTemplate,
+        SessionRevokedTemplate, SubscribeNewsletterTemplate, UserDeletedTemplate,
+        UserExportTemplate, VerifyEmailTemplate,
     };
 
     use super::*;
@@ -75,6 +78,71 @@ mod tests {
         });
     }
 
+    #[test]
+    fn login_link() {
+        test_template(LoginLinkTemplate {
+            code: "code".into(),
+            url: "https://bootstrap.academy/".into(),
+        });
+    }
+
+    #[test]
+    fn new_session() {
+        test_template(NewSessionTemplate {
+            device_name: Some("Firefox on Linux".into()),
+        });
+        test_template(NewSessionTemplate { device_name: None });
+    }
+
+    #[test]
+    fn password_changed() {
+        test_template(PasswordChangedTemplate {});
+    }
+
+    #[test]
+    fn mfa_disabled() {
+        test_template(MfaDisabledTemplate {});
+    }
+
+    #[test]
+    fn email_changed() {
+        test_template(EmailChangedTemplate {
+            new_email: Some("foo@example.com".into()),
+        });
+        test_template(EmailChangedTemplate { new_email: None });
+    }
+
+    #[test]
+    fn oauth2_link_created() {
+        test_template(OAuth2LinkCreatedTemplate {
+            provider: "GitHub".into(),
+        });
+    }
+
+    #[test]
+    fn user_export() {
+        test_template(UserExportTemplate {
+            code: "code".into(),
+            url: "https://bootstrap.academy/".into(),
+        });
+    }
+
+    #[test]
+    fn user_deleted() {
+        test_template(UserDeletedTemplate {
+            code: "code".into(),
+            url: "https://bootstrap.academy/".into(),
+        });
+    }
+
+    #[test]
+    fn session_revoked() {
+        test_template(SessionRevokedTemplate {
+            device_name: Some("Firefox on Linux".into()),
+        });
+        test_template(SessionRevokedTemplate { device_name: None });
+    }
+
     fn test_template<T: Template + 'static>(template: T) {
         // Arrange
         let sut = TemplateServiceImpl {
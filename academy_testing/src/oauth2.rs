@@ -1,6 +1,11 @@
-use std::{collections::HashMap, net::IpAddr, sync::Arc};
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use axum::{
     extract::Query,
     http::StatusCode,
@@ -14,12 +19,18 @@ use axum_extra::{
     },
     TypedHeader,
 };
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
 use oauth2::{PkceCodeChallenge, PkceCodeVerifier};
 use rand::{
     distributions::{Alphanumeric, DistString},
     thread_rng,
 };
+use ring::{
+    rand::SystemRandom,
+    signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING},
+};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use tokio::{
     net::TcpListener,
     sync::{Mutex, RwLock},
@@ -34,25 +45,45 @@ pub async fn start_server(
     client_secret: String,
     redirect_url: Url,
 ) -> anyhow::Result<()> {
-    info!("Starting recaptcha testing server on {host}:{port}");
-    info!("Authorization endpoint: http://{host}:{port}/oauth2/authorize");
-    info!("Token endpoint: http://{host}:{port}/oauth2/token");
-    info!("User info endpoint: http://{host}:{port}/user");
+    let issuer = format!("http://{host}:{port}");
+
+    info!("Starting oauth2 testing server on {host}:{port}");
+    info!("Authorization endpoint: {issuer}/oauth2/authorize");
+    info!("Token endpoint: {issuer}/oauth2/token");
+    info!("User info endpoint: {issuer}/user");
+    info!("Revocation endpoint: {issuer}/oauth2/revoke");
+    info!("OpenID Connect issuer: {issuer}");
     info!("Client ID: {client_id:?}");
     info!("Client secret: {client_secret:?}");
     info!("Redirect url: {redirect_url}");
-    info!("Example authorization URL: http://{host}:{port}/oauth2/authorize?response_type=code&client_id={client_id}&state=test-state&redirect_uri={redirect_url}");
+    info!("Example authorization URL: {issuer}/oauth2/authorize?response_type=code&client_id={client_id}&state=test-state&redirect_uri={redirect_url}");
+
+    let rng = SystemRandom::new();
+    let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng)
+        .map_err(|err| anyhow!("Failed to generate signing key: {err}"))?;
+    let signing_key =
+        EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref(), &rng)
+            .map_err(|err| anyhow!("Failed to load signing key: {err}"))?;
 
     let router = Router::new()
+        .route(
+            "/.well-known/openid-configuration",
+            routing::get(openid_configuration),
+        )
         .route("/oauth2/authorize", routing::get(authorize).post(login))
         .route("/oauth2/token", routing::post(token))
+        .route("/oauth2/revoke", routing::post(revoke))
+        .route("/oauth2/jwks", routing::get(jwks))
         .route("/user", routing::get(user))
         .with_state(Arc::new(StateInner {
+            issuer,
             client_id,
             client_secret,
             redirect_url,
+            signing_key,
             codes: Default::default(),
             logins: Default::default(),
+            refresh_tokens: Default::default(),
         }));
 
     let listener = TcpListener::bind((host, port))
@@ -65,16 +96,21 @@ pub async fn start_server(
 
 type State = axum::extract::State<Arc<StateInner>>;
 struct StateInner {
+    issuer: String,
     client_id: String,
     client_secret: String,
     redirect_url: Url,
+    signing_key: EcdsaKeyPair,
     codes: Mutex<HashMap<String, CodeState>>,
     logins: RwLock<HashMap<String, Login>>,
+    refresh_tokens: Mutex<HashMap<String, Login>>,
 }
 
 struct CodeState {
     login: Login,
     code_challenge: Option<String>,
+    openid: bool,
+    nonce: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -85,6 +121,44 @@ struct AuthorizeQuery {
     code_challenge: Option<String>,
     code_challenge_method: Option<String>,
     redirect_uri: Url,
+    scope: Option<String>,
+    nonce: Option<String>,
+}
+
+const SIGNING_KEY_ID: &str = "academy-testing";
+
+async fn openid_configuration(state: State) -> Response {
+    let issuer = &state.issuer;
+    Json(json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{issuer}/oauth2/authorize"),
+        "token_endpoint": format!("{issuer}/oauth2/token"),
+        "userinfo_endpoint": format!("{issuer}/user"),
+        "jwks_uri": format!("{issuer}/oauth2/jwks"),
+        "revocation_endpoint": format!("{issuer}/oauth2/revoke"),
+        "response_types_supported": ["code"],
+        "subject_types_supported": ["public"],
+        "id_token_signing_alg_values_supported": ["ES256"],
+        "code_challenge_methods_supported": ["S256"],
+    }))
+    .into_response()
+}
+
+async fn jwks(state: State) -> Response {
+    // uncompressed point: 0x04 || x || y
+    let point = state.signing_key.public_key().as_ref();
+    Json(json!({
+        "keys": [{
+            "kty": "EC",
+            "use": "sig",
+            "alg": "ES256",
+            "kid": SIGNING_KEY_ID,
+            "crv": "P-256",
+            "x": BASE64_URL_SAFE_NO_PAD.encode(&point[1..33]),
+            "y": BASE64_URL_SAFE_NO_PAD.encode(&point[33..65]),
+        }]
+    }))
+    .into_response()
 }
 
 async fn authorize(state: State, Query(query): Query<AuthorizeQuery>) -> Response {
@@ -106,16 +180,28 @@ async fn authorize(state: State, Query(query): Query<AuthorizeQuery>) -> Respons
         "<form method=post>
         ID: <input name=id autofocus><br>
         Name: <input name=name><br>
+        Email: <input name=email><br>
+        Email verified: <input type=checkbox name=email_verified value=true><br>
+        Display name: <input name=display_name><br>
+        Avatar URL: <input name=avatar_url><br>
         <button type=submit>Login</button>
         </form>",
     )
     .into_response()
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 struct Login {
     id: String,
     name: String,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    avatar_url: Option<String>,
 }
 
 async fn login(
@@ -150,6 +236,10 @@ async fn login(
         CodeState {
             login,
             code_challenge: query.code_challenge,
+            openid: query
+                .scope
+                .is_some_and(|scope| scope.split(' ').any(|x| x == "openid")),
+            nonce: query.nonce,
         },
     );
 
@@ -157,39 +247,65 @@ async fn login(
 }
 
 #[derive(Debug, Deserialize)]
-struct TokenForm {
-    code: String,
-    code_verifier: Option<String>,
-    grant_type: String,
-    redirect_uri: Url,
+#[serde(tag = "grant_type", rename_all = "snake_case")]
+enum TokenForm {
+    AuthorizationCode {
+        code: String,
+        code_verifier: Option<String>,
+        redirect_uri: Url,
+    },
+    RefreshToken {
+        refresh_token: String,
+    },
 }
 
 #[derive(Serialize)]
 struct TokenResponse {
     access_token: String,
     token_type: &'static str,
+    expires_in: u64,
+    refresh_token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id_token: Option<String>,
 }
 
+/// Lifetime of issued access tokens in seconds
+const ACCESS_TOKEN_TTL: u64 = 3600;
+
 async fn token(
     state: State,
     TypedHeader(auth): TypedHeader<Authorization<Basic>>,
-    Form(TokenForm {
-        code,
-        code_verifier,
-        grant_type,
-        redirect_uri,
-    }): Form<TokenForm>,
+    Form(form): Form<TokenForm>,
 ) -> Response {
-    if grant_type != "authorization_code" {
-        return (StatusCode::BAD_REQUEST, "invalid grant_type").into_response();
+    if let Some(response) = check_client(&state, &auth) {
+        return response;
     }
 
-    if auth.username() != state.client_id {
-        return (StatusCode::UNAUTHORIZED, "invalid client_id").into_response();
-    }
-    if auth.password() != state.client_secret {
-        return (StatusCode::UNAUTHORIZED, "invalid client_secret").into_response();
+    match form {
+        TokenForm::AuthorizationCode {
+            code,
+            code_verifier,
+            redirect_uri,
+        } => exchange_code(&state, code, code_verifier, redirect_uri).await,
+        TokenForm::RefreshToken { refresh_token } => {
+            let Some(login) = state.refresh_tokens.lock().await.remove(&refresh_token) else {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({"error": "invalid_grant"})),
+                )
+                    .into_response();
+            };
+            Json(issue_tokens(&state, login, None).await).into_response()
+        }
     }
+}
+
+async fn exchange_code(
+    state: &StateInner,
+    code: String,
+    code_verifier: Option<String>,
+    redirect_uri: Url,
+) -> Response {
     if redirect_uri != state.redirect_url {
         return (StatusCode::FORBIDDEN, "invalid redirect_uri").into_response();
     }
@@ -217,19 +333,102 @@ async fn token(
         }
     }
 
+    let id_token = code_state
+        .openid
+        .then(|| issue_id_token(state, &code_state.login, code_state.nonce.as_deref()));
+
+    Json(issue_tokens(state, code_state.login, id_token).await).into_response()
+}
+
+async fn issue_tokens(state: &StateInner, login: Login, id_token: Option<String>) -> TokenResponse {
     let access_token = generate_code();
+    let refresh_token = generate_code();
 
     state
         .logins
         .write()
         .await
-        .insert(access_token.clone(), code_state.login);
+        .insert(access_token.clone(), login.clone());
+    state
+        .refresh_tokens
+        .lock()
+        .await
+        .insert(refresh_token.clone(), login);
 
-    Json(TokenResponse {
+    TokenResponse {
         access_token,
         token_type: "bearer",
-    })
-    .into_response()
+        expires_in: ACCESS_TOKEN_TTL,
+        refresh_token,
+        id_token,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RevokeForm {
+    token: String,
+}
+
+async fn revoke(
+    state: State,
+    TypedHeader(auth): TypedHeader<Authorization<Basic>>,
+    Form(RevokeForm { token }): Form<RevokeForm>,
+) -> Response {
+    if let Some(response) = check_client(&state, &auth) {
+        return response;
+    }
+
+    // invalid tokens do not cause an error response (RFC 7009, section 2.2)
+    state.logins.write().await.remove(&token);
+    state.refresh_tokens.lock().await.remove(&token);
+
+    StatusCode::OK.into_response()
+}
+
+/// Return an error response if the client credentials are invalid.
+fn check_client(state: &StateInner, auth: &Authorization<Basic>) -> Option<Response> {
+    if auth.username() != state.client_id {
+        return Some((StatusCode::UNAUTHORIZED, "invalid client_id").into_response());
+    }
+    if auth.password() != state.client_secret {
+        return Some((StatusCode::UNAUTHORIZED, "invalid client_secret").into_response());
+    }
+    None
+}
+
+fn issue_id_token(state: &StateInner, login: &Login, nonce: Option<&str>) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let exp = now + Duration::from_secs(600);
+
+    let header = json!({"alg": "ES256", "typ": "JWT", "kid": SIGNING_KEY_ID});
+    let claims = json!({
+        "iss": state.issuer,
+        "sub": login.id,
+        "aud": state.client_id,
+        "iat": now.as_secs(),
+        "exp": exp.as_secs(),
+        "nonce": nonce,
+        "name": login.name,
+        "email": login.email,
+        "email_verified": login.email_verified,
+    });
+
+    let signing_input = format!(
+        "{}.{}",
+        BASE64_URL_SAFE_NO_PAD.encode(header.to_string()),
+        BASE64_URL_SAFE_NO_PAD.encode(claims.to_string())
+    );
+    let signature = state
+        .signing_key
+        .sign(&SystemRandom::new(), signing_input.as_bytes())
+        .unwrap();
+
+    format!(
+        "{signing_input}.{}",
+        BASE64_URL_SAFE_NO_PAD.encode(signature)
+    )
 }
 
 async fn user(state: State, TypedHeader(auth): TypedHeader<Authorization<Bearer>>) -> Response {
@@ -238,7 +437,17 @@ async fn user(state: State, TypedHeader(auth): TypedHeader<Authorization<Bearer>
         return (StatusCode::UNAUTHORIZED, "invalid access token").into_response();
     };
 
-    Json(login).into_response()
+    Json(json!({
+        "sub": login.id,
+        "id": login.id,
+        "name": login.name,
+        "email": login.email,
+        "email_verified": login.email_verified,
+        "display_name": login.display_name,
+        "avatar_url": login.avatar_url,
+        "picture": login.avatar_url,
+    }))
+    .into_response()
 }
 
 fn generate_code() -> String {
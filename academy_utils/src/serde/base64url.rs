@@ -0,0 +1,47 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub fn serialize<S, T>(data: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: AsRef<[u8]>,
+{
+    let encoded = URL_SAFE_NO_PAD.encode(data);
+    encoded.serialize(serializer)
+}
+
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<Vec<u8>>,
+{
+    let input = String::deserialize(deserializer)?;
+    URL_SAFE_NO_PAD
+        .decode(input.trim_end_matches('='))
+        .map_err(serde::de::Error::custom)?
+        .try_into()
+        .map_err(|_| serde::de::Error::custom("Failed to deserialize base64url data"))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[test]
+    fn test() {
+        let encoded = serde_json::Value::String("3q2-7w".into());
+        let data = serde_json::from_value::<Data>(encoded.clone()).unwrap();
+        assert_eq!(data.0, [0xde, 0xad, 0xbe, 0xef]);
+        let serialized = serde_json::to_value(data).unwrap();
+        assert_eq!(serialized, encoded);
+    }
+
+    #[test]
+    fn padding() {
+        let data = serde_json::from_value::<Data>("3q2-7w==".into()).unwrap();
+        assert_eq!(data.0, [0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Data(#[serde(with = "super")] Vec<u8>);
+}
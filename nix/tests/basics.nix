@@ -53,7 +53,7 @@ testers.runNixOSTest {
     assert openapi["info"]["version"] == "0.0.0-dev"
     assert "https://github.com/Bootstrap-Academy" in openapi["info"]["description"]
 
-    machine.succeed("academy admin user create --admin --verified admin admin@example.com supersecureadminpassword")
+    machine.succeed("academy admin user create --role superuser --verified admin admin@example.com supersecureadminpassword")
     login = json.loads(machine.succeed("curl -s http://127.0.0.1:8000/auth/sessions -X POST -H 'Content-Type: application/json' -d '{\"name_or_email\": \"admin\", \"password\": \"supersecureadminpassword\"}'"))
     assert login["user"]["admin"]
     assert login["user"]["email_verified"]
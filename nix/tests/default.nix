@@ -54,6 +54,11 @@
           from = "test@bootstrap.academy";
         };
         internal.shop_url = "http://127.0.0.1:8004/shop/";
+        user = {
+          password_min_length = 1;
+          password_min_strength = 0;
+          password_breach_check = false;
+        };
         health = {
           database_cache_ttl = "2s";
           cache_cache_ttl = "2s";
@@ -87,8 +92,25 @@
               auth_url = "http://127.0.0.1:8002/oauth2/authorize";
               token_url = "http://127.0.0.1:8002/oauth2/token";
               userinfo_url = "http://127.0.0.1:8002/user";
+              revocation_url = "http://127.0.0.1:8002/oauth2/revoke";
               userinfo_id_key = "id";
               userinfo_name_key = "name";
+              userinfo_email_key = "email";
+              userinfo_display_name_key = "display_name";
+              userinfo_avatar_url_key = "avatar_url";
+              scopes = [];
+            };
+            test-oidc = {
+              name = "Test OIDC Provider";
+              client_id = "client-id";
+              client_secret = "client-secret";
+              issuer_url = "http://127.0.0.1:8002";
+              userinfo_id_key = "sub";
+              userinfo_name_key = "name";
+              userinfo_email_key = "email";
+              userinfo_email_verified_key = "email_verified";
+              userinfo_display_name_key = "display_name";
+              userinfo_avatar_url_key = "picture";
               scopes = [];
             };
           };
@@ -139,7 +161,8 @@
       group = "academy";
       mode = "0400";
       argument = ''
-        jwt.secret = "changeme"
+        jwt.keys = ["MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgFVQ1IEHILMEiHZ0PnBhegyzphTOLOxXadLvRERbY4kuhRANCAASMTZ2BqjmrwYgVTacecvpN9N3CBdzMoNqKdjZb2CPKdlBkhPOK/m8+Ye9bmh//qwjxP+lw+6zPiDynzYfUivGc"]
+        encryption.keys = ["T5z/mDW9wHTuiS714U8DFT/r9lCHNFenvH193CpCKw0="]
       '';
     };
   };
@@ -20,6 +20,8 @@ FOO = {
     "last_name_change": 1710424200,
     "enabled": True,
     "admin": False,
+    "roles": [],
+    "permissions": [],
     "password": True,
     "mfa_enabled": False,
     "description": "blubb",
@@ -2,32 +2,35 @@ from urllib.parse import parse_qs, urlparse
 
 from utils import c, create_account, discard_auth, get_self, save_auth
 
+REDIRECT_URI = "http://localhost/oauth2/callback"
 
-def authenticate(id, name):
-    resp = c.post(
-        "http://127.0.0.1:8002/oauth2/authorize?response_type=code&client_id=client-id&state=test123&redirect_uri=http://localhost/oauth2/callback",
-        data={"id": str(id), "name": name},
-        follow_redirects=False,
-    )
+
+def authenticate(id, name, provider_id="test", **profile):
+    resp = c.post(f"/auth/oauth/providers/{provider_id}/authorize", json={"redirect_uri": REDIRECT_URI})
+    assert resp.status_code == 200
+    authorize_url = resp.json()["authorize_url"]
+    state = parse_qs(urlparse(authorize_url).query)["state"][0]
+
+    resp = c.post(authorize_url, data={"id": str(id), "name": name, **profile}, follow_redirects=False)
     assert resp.is_redirect
     url = urlparse(resp.headers["location"])
     query = parse_qs(url.query)
     code = query["code"][0]
-    state = query["state"][0]
-    assert state == "test123"
-    return {"provider_id": "test", "code": code, "redirect_uri": "http://localhost/oauth2/callback"}
+    assert query["state"][0] == state
+    return {"provider_id": provider_id, "code": code, "redirect_uri": REDIRECT_URI, "state": state}
 
 
 resp = c.get("/auth/oauth/providers")
 assert resp.status_code == 200
-assert resp.json() == [
-    {
-        "id": "test",
-        "name": "Test OAuth2 Provider",
-        "authorize_url": "http://127.0.0.1:8002/oauth2/authorize?response_type=code&client_id=client-id",
-    }
+assert sorted(resp.json(), key=lambda p: p["id"]) == [
+    {"id": "test", "name": "Test OAuth2 Provider"},
+    {"id": "test-oidc", "name": "Test OIDC Provider"},
 ]
 
+resp = c.post("/auth/oauth/providers/unknown/authorize", json={"redirect_uri": REDIRECT_URI})
+assert resp.status_code == 404
+assert resp.json() == {"detail": "Provider not found"}
+
 # create link
 login = create_account("a", "a@a", "a")
 user = login["user"]
@@ -78,9 +81,19 @@ assert resp.json() == []
 
 # register
 discard_auth()
-resp = c.post("/auth/sessions/oauth", json=authenticate(43, "bar"))
+resp = c.post(
+    "/auth/sessions/oauth",
+    json=authenticate(43, "bar", email="b@b", email_verified="true", display_name="B", avatar_url="https://b/b.png"),
+)
 assert resp.status_code == 200
 register_token = resp.json()["register_token"]
+# the test provider is not trusted to verify email addresses
+assert resp.json()["profile"] == {
+    "email": "b@b",
+    "email_verified": False,
+    "display_name": "B",
+    "avatar_url": "https://b/b.png",
+}
 
 resp = c.post(
     "/auth/users",
@@ -98,6 +111,7 @@ user = login["user"]
 save_auth(login)
 assert get_self() == user
 assert user["password"] is False
+assert user["email_verified"] is False
 
 resp = c.get("/auth/oauth/links/me")
 assert resp.status_code == 200
@@ -111,3 +125,53 @@ login = resp.json()["login"]
 user["last_login"] = login["user"]["last_login"]
 assert login["user"] == user
 save_auth(login)
+
+# state can only be used once
+login = authenticate(43, "bar")
+discard_auth()
+resp = c.post("/auth/sessions/oauth", json=login)
+assert resp.status_code == 200
+save_auth(resp.json()["login"])
+resp = c.post("/auth/sessions/oauth", json=login)
+assert resp.status_code == 401
+assert resp.json() == {"detail": "Invalid state"}
+
+# openid connect
+resp = c.post("/auth/oauth/links/me", json=authenticate(44, "baz", "test-oidc"))
+assert resp.status_code == 200
+link = resp.json()
+assert link == {"id": link["id"], "provider_id": "test-oidc", "display_name": "baz"}
+
+discard_auth()
+resp = c.post("/auth/sessions/oauth", json=authenticate(44, "baz", "test-oidc"))
+assert resp.status_code == 200
+login = resp.json()["login"]
+user["last_login"] = login["user"]["last_login"]
+assert login["user"] == user
+save_auth(login)
+
+# register with verified email address
+discard_auth()
+resp = c.post(
+    "/auth/sessions/oauth",
+    json=authenticate(45, "qux", "test-oidc", email="q@q", email_verified="true", display_name="Q"),
+)
+assert resp.status_code == 200
+register_token = resp.json()["register_token"]
+profile = resp.json()["profile"]
+assert profile == {"email": "q@q", "email_verified": True, "display_name": "Q", "avatar_url": None}
+
+resp = c.post(
+    "/auth/users",
+    json={
+        "name": "q",
+        "display_name": profile["display_name"],
+        "email": profile["email"],
+        "oauth_register_token": register_token,
+        "recaptcha_response": "success-1.0",
+    },
+)
+assert resp.status_code == 200
+user = resp.json()["user"]
+assert user["email"] == "q@q"
+assert user["email_verified"] is True
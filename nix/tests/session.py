@@ -93,13 +93,15 @@ assert resp.status_code == 200
 assert resp.json() == sessions
 
 # impersonate
-os.system("academy admin user create --admin admin admin@admin admin")
+os.system("academy admin user create --role superuser admin admin@admin admin")
 resp = c.post("/auth/sessions", json={"name_or_email": "admin", "password": "admin"})
 assert resp.status_code == 200
+admin = resp.json()["user"]
 save_auth(resp.json())
 
 resp = c.post(f"/auth/sessions/{login['user']['id']}")
 assert resp.status_code == 200
+assert resp.json()["session"]["impersonator_id"] == admin["id"]
 
 # refresh
 resp = c.post("/auth/sessions", json={"name_or_email": "a", "password": "a"})
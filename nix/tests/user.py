@@ -42,6 +42,8 @@ assert login == {
         "last_name_change": None,
         "enabled": True,
         "admin": False,
+        "roles": [],
+        "permissions": [],
         "password": True,
         "mfa_enabled": False,
         "description": "",
@@ -63,6 +65,7 @@ assert login == {
         "id": login["session"]["id"],
         "user_id": login["user"]["id"],
         "device_name": c.headers["User-Agent"],
+        "impersonator_id": None,
         "last_update": login["session"]["last_update"],
     },
     "access_token": login["access_token"],
@@ -293,8 +296,8 @@ resp = c.patch("/auth/users/me", json={"enabled": False})
 assert resp.status_code == 403
 assert resp.json() == {"detail": "Permission denied"}
 
-## admin
-resp = c.patch("/auth/users/me", json={"admin": True})
+## roles
+resp = c.patch("/auth/users/me", json={"roles": ["superuser"]})
 assert resp.status_code == 403
 assert resp.json() == {"detail": "Permission denied"}
 
@@ -358,7 +361,7 @@ assert resp.json() == {"detail": "Invalid credentials"}
 
 # admin: create via cli
 status, _ = subprocess.getstatusoutput(
-    "academy admin user create --admin --verified admin admin@example.com supersecureadminpassword"
+    "academy admin user create --role superuser --verified admin admin@example.com supersecureadminpassword"
 )
 assert status == 0
 
@@ -392,13 +395,15 @@ assert resp == a
 # admin: update other
 resp = c.patch(
     f"/auth/users/{a['id']}",
-    json={"name": "foo", "display_name": "foo", "email_verified": True, "admin": True, "enabled": False},
+    json={"name": "foo", "display_name": "foo", "email_verified": True, "roles": ["superuser"], "enabled": False},
 )
 assert resp.status_code == 200
 a["name"] = "foo"
 a["display_name"] = "foo"
 a["email_verified"] = True
 a["admin"] = True
+a["roles"] = ["superuser"]
+a["permissions"] = ["user_read", "user_write", "user_profile_write", "user_invoice_info_write", "user_verification_email_send", "user_impersonate", "audit_log_read", "role_assign"]
 a["enabled"] = False
 assert resp.json() == a
 assert c.get(f"/auth/users/{a['id']}").json() == a